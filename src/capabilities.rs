@@ -0,0 +1,359 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::models::{PluginManifest, PluginRequirements};
+
+/// Version of the ExecutionContext layout passed to plugins; bumped on breaking changes
+pub const CONTEXT_SCHEMA_VERSION: u32 = 1;
+
+/// Version of the mis-plugin-api.ts helpers this build ships; bumped on breaking changes
+pub const PLUGIN_API_VERSION: u32 = 1;
+
+/// Runtime features this build of mis provides to plugins
+pub const FEATURES: &[&str] = &[
+    // Structured results written to the file passed via --result-file
+    "result-file",
+    // Events published to later workflow steps (ctx.events)
+    "events",
+    // Planned file changes shown as diffs on dry runs
+    "planned-changes",
+    // Files in ctx.artifacts_dir uploaded with --upload-artifacts
+    "artifacts",
+    // Profiles selected with --env (ctx.environment)
+    "environments",
+    // Branch, commit, tag and dirty state for plugins with [context] git = true (ctx.git)
+    "git",
+    // `::mis::log::` lines on stderr shown with levels and kept in the run log (mis.log)
+    "log-lines",
+    // `::mis::progress::` lines drawn as progress bars and spinners (mis.progress)
+    "progress",
+    // Plans returned from `mis run --plan` (ctx.planning) and applied with --apply-plan (ctx.plan)
+    "plans",
+    // Context piped on stdin for plugins with context_transport = "stdin"
+    "context-stdin",
+    // A directory of the plugin's own that persists between runs (ctx.state_dir)
+    "state-dir",
+];
+
+/// What the CLI supports, passed to every plugin as `ctx.capabilities`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct Capabilities {
+    pub mis_version: String,
+    pub context_schema: u32,
+    /// Lets mis-plugin-api.ts refuse to run against a CLI expecting another version
+    pub api_version: u32,
+    pub features: Vec<String>,
+}
+
+impl Capabilities {
+    pub fn current() -> Self {
+        Self {
+            mis_version: env!("CARGO_PKG_VERSION").to_string(),
+            context_schema: CONTEXT_SCHEMA_VERSION,
+            api_version: PLUGIN_API_VERSION,
+            features: FEATURES.iter().map(|f| f.to_string()).collect(),
+        }
+    }
+
+    /// Everything a manifest asks of the CLI: `mis_version_requirement` and `[requires]`
+    pub fn check_manifest(&self, manifest: &PluginManifest) -> Result<()> {
+        let plugin_name = &manifest.plugin.name;
+        if let Some(requirement) = &manifest.mis_version_requirement {
+            self.check_mis_version(plugin_name, requirement)?;
+        }
+        if let Some(requires) = &manifest.requires {
+            self.check(plugin_name, requires)?;
+        }
+        Ok(())
+    }
+
+    /// Fail with upgrade advice when this mis is outside the versions a plugin supports
+    pub fn check_mis_version(&self, plugin_name: &str, requirement: &str) -> Result<()> {
+        match self.version_mismatch(plugin_name, requirement)? {
+            None => Ok(()),
+            Some(VersionMismatch::TooOld) => anyhow::bail!(
+                "🛑 Plugin '{}' needs mis {}, but this is mis {}.\n\
+                 → Upgrade mis, or install a version of the plugin built for this one.",
+                plugin_name,
+                format_requirement(requirement),
+                self.mis_version
+            ),
+            Some(VersionMismatch::TooNew) => anyhow::bail!(
+                "🛑 Plugin '{}' supports mis {}, but this is mis {}, which is newer.\n\
+                 → Run `mis update {}` to get a version of the plugin built for this mis.\n\
+                 → Or install a mis version the plugin supports.",
+                plugin_name,
+                format_requirement(requirement),
+                self.mis_version,
+                plugin_name
+            ),
+        }
+    }
+
+    fn version_mismatch(
+        &self,
+        plugin_name: &str,
+        requirement: &str,
+    ) -> Result<Option<VersionMismatch>> {
+        let comparators = parse_requirement(requirement).ok_or_else(|| {
+            anyhow::anyhow!(
+                "🛑 Plugin '{}' has an invalid mis version requirement '{}'.\n\
+                 → Use a minimum version like \"0.4.0\", or a range like \">=0.5, <0.7\".",
+                plugin_name,
+                requirement
+            )
+        })?;
+        let Some(current) = parse_version(&self.mis_version) else {
+            return Ok(None);
+        };
+        Ok(comparators
+            .iter()
+            .find(|comparator| !comparator.matches(current))
+            .map(|comparator| {
+                if current < comparator.version {
+                    VersionMismatch::TooOld
+                } else {
+                    VersionMismatch::TooNew
+                }
+            }))
+    }
+
+    /// Fail before the plugin starts if it needs a newer mis or a feature this one lacks
+    pub fn check(&self, plugin_name: &str, requires: &PluginRequirements) -> Result<()> {
+        let missing: Vec<&str> = requires
+            .features
+            .iter()
+            .map(String::as_str)
+            .filter(|feature| !self.features.iter().any(|f| f == feature))
+            .collect();
+
+        let too_old = match &requires.mis {
+            Some(requirement) => match self.version_mismatch(plugin_name, requirement)? {
+                Some(VersionMismatch::TooNew) => {
+                    return self.check_mis_version(plugin_name, requirement);
+                }
+                mismatch => mismatch.is_some(),
+            },
+            None => false,
+        };
+
+        if !too_old && missing.is_empty() {
+            return Ok(());
+        }
+
+        let mut need = String::from("mis");
+        if let Some(requirement) = requires.mis.as_deref().filter(|_| too_old) {
+            need.push_str(&format!(" {}", format_requirement(requirement)));
+        }
+        if !missing.is_empty() {
+            need.push_str(&format!(
+                " with feature{} {}",
+                if missing.len() == 1 { "" } else { "s" },
+                missing.join(", ")
+            ));
+        }
+
+        anyhow::bail!(
+            "🛑 Plugin '{}' needs {}.\n\
+             → This is mis {} (supports: {}).\n\
+             → Upgrade mis, or install a version of the plugin built for this one.",
+            plugin_name,
+            need,
+            self.mis_version,
+            self.features.join(", ")
+        )
+    }
+}
+
+/// Which side of a plugin's supported range this mis is on
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VersionMismatch {
+    TooOld,
+    TooNew,
+}
+
+/// One comparison in a version requirement, e.g. ">=0.5" or "<0.7"
+#[derive(Debug, Clone, PartialEq)]
+struct Comparator {
+    op: &'static str,
+    version: (u64, u64, u64),
+}
+
+impl Comparator {
+    fn matches(&self, version: (u64, u64, u64)) -> bool {
+        match self.op {
+            ">" => version > self.version,
+            "<" => version < self.version,
+            "<=" => version <= self.version,
+            "=" => version == self.version,
+            _ => version >= self.version,
+        }
+    }
+}
+
+/// Parse a requirement like ">=0.5, <0.7" into its comparisons; a bare version is a minimum
+fn parse_requirement(requirement: &str) -> Option<Vec<Comparator>> {
+    let comparators = requirement
+        .split(',')
+        .map(|part| {
+            let (op, version) = split_op(part);
+            Some(Comparator {
+                op,
+                version: parse_version(version)?,
+            })
+        })
+        .collect::<Option<Vec<_>>>()?;
+    (!comparators.is_empty()).then_some(comparators)
+}
+
+/// Split ">= 0.5" into its operator and version; no operator means ">="
+fn split_op(part: &str) -> (&'static str, &str) {
+    let part = part.trim();
+    [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|op| part.strip_prefix(op).map(|version| (op, version.trim())))
+        .unwrap_or((">=", part))
+}
+
+/// ">=0.5,<0.7" as ">= 0.5, < 0.7", and a bare "0.5" as ">= 0.5", for messages
+fn format_requirement(requirement: &str) -> String {
+    requirement
+        .split(',')
+        .map(|part| {
+            let (op, version) = split_op(part);
+            format!("{} {}", op, version)
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Parse "1.2.3" (or ">=1.2.3", "1.2") into comparable parts
+pub fn parse_version(version: &str) -> Option<(u64, u64, u64)> {
+    let version = version.trim().trim_start_matches(">=").trim();
+    let mut parts = version.split('.').map(|part| part.parse::<u64>().ok());
+    let major = parts.next()??;
+    let minor = parts.next().unwrap_or(Some(0))?;
+    let patch = parts.next().unwrap_or(Some(0))?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn capabilities(version: &str) -> Capabilities {
+        Capabilities {
+            mis_version: version.to_string(),
+            ..Capabilities::current()
+        }
+    }
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(parse_version("1.2.3"), Some((1, 2, 3)));
+        assert_eq!(parse_version(">= 0.4"), Some((0, 4, 0)));
+        assert_eq!(parse_version("1.x"), None);
+        assert_eq!(parse_version("1.2.3.4"), None);
+    }
+
+    #[test]
+    fn test_parse_requirement() {
+        let comparators = parse_requirement(">=0.5, <0.7").unwrap();
+        assert_eq!(comparators.len(), 2);
+        assert!(comparators.iter().all(|c| c.matches((0, 6, 3))));
+        assert!(!comparators[1].matches((0, 7, 0)));
+        assert_eq!(parse_requirement("0.5").unwrap()[0].op, ">=");
+        assert!(parse_requirement("=1.0").unwrap()[0].matches((1, 0, 0)));
+        assert_eq!(parse_requirement(">=0.5, latest"), None);
+        assert_eq!(format_requirement(">=0.5,<0.7"), ">= 0.5, < 0.7");
+        assert_eq!(format_requirement("0.5"), ">= 0.5");
+    }
+
+    #[test]
+    fn test_check_mis_version_says_which_side_to_move() {
+        assert!(
+            capabilities("0.6.1")
+                .check_mis_version("deploy", ">=0.5, <0.7")
+                .is_ok()
+        );
+
+        let err = capabilities("0.4.2")
+            .check_mis_version("deploy", ">=0.5, <0.7")
+            .unwrap_err()
+            .to_string();
+        assert!(
+            err.contains("Plugin 'deploy' needs mis >= 0.5, < 0.7, but this is mis 0.4.2."),
+            "{}",
+            err
+        );
+        assert!(err.contains("Upgrade mis"));
+
+        let err = capabilities("0.7.0")
+            .check_mis_version("deploy", ">=0.5, <0.7")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("which is newer"), "{}", err);
+        assert!(err.contains("mis update deploy"));
+
+        // A range under [requires] gets the same advice when mis is too new
+        let requires = PluginRequirements {
+            mis: Some("<0.7".to_string()),
+            features: vec![],
+        };
+        let err = capabilities("0.8.0")
+            .check("deploy", &requires)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("mis update deploy"), "{}", err);
+
+        assert!(
+            capabilities("0.6.0")
+                .check_mis_version("deploy", "newest")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_check_accepts_satisfied_requirements() {
+        let requires = PluginRequirements {
+            mis: Some(">=0.3.0".to_string()),
+            features: vec!["result-file".to_string(), "events".to_string()],
+        };
+        assert!(capabilities("0.3.7").check("deploy", &requires).is_ok());
+        assert!(
+            capabilities("0.3.7")
+                .check("deploy", &PluginRequirements::default())
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_check_names_the_version_and_missing_features() {
+        let requires = PluginRequirements {
+            mis: Some("0.5.0".to_string()),
+            features: vec!["ipc".to_string(), "result-file".to_string()],
+        };
+        let err = capabilities("0.3.7")
+            .check("deploy", &requires)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Plugin 'deploy' needs mis >= 0.5.0 with feature ipc."));
+        assert!(err.contains("This is mis 0.3.7"));
+
+        // New enough, but the feature still isn't there
+        let err = capabilities("0.6.0")
+            .check("deploy", &requires)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("needs mis with feature ipc."));
+
+        let requires = PluginRequirements {
+            mis: Some("latest".to_string()),
+            features: vec![],
+        };
+        assert!(capabilities("0.3.7").check("deploy", &requires).is_err());
+    }
+}
@@ -1,7 +1,11 @@
-use clap::{Parser, Subcommand};
+use clap::{Parser, Subcommand, ValueEnum};
+use crate::config::variables::split_command;
+use crate::models::{ColorChoice, Theme};
+use indexmap::IndexMap;
 use std::{
     collections::HashMap,
     io::{self, Write},
+    path::PathBuf,
 };
 
 /// Your CLI entrypoint definition
@@ -15,30 +19,208 @@ use std::{
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+
+    /// When messages and plugin output use color (overrides MIS_COLOR and the config files)
+    #[arg(long, global = true, value_enum)]
+    pub color: Option<ColorChoice>,
+
+    /// Never use color; same as `--color never`
+    #[arg(long, global = true, conflicts_with = "color")]
+    pub no_color: bool,
+
+    /// How messages are decorated (overrides MIS_THEME and the config files)
+    #[arg(long, global = true, value_enum)]
+    pub theme: Option<Theme>,
+
+    /// Print results as text, or as JSON for automation (messages then go to stderr)
+    #[arg(long, global = true, value_enum, default_value_t = OutputFormat::Text)]
+    pub output: OutputFormat,
+
+    /// Only print errors, warnings and prompts
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Explain what mis is doing (-v), or also show the exact commands it runs (-vv)
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Use this project instead of the one containing the current directory
+    /// (relative to here or to the workspace root, e.g. services/api)
+    #[arg(long, global = true, value_name = "DIR")]
+    pub project: Option<PathBuf>,
+
+    /// Fail on plugin manifest keys mis doesn't recognize instead of ignoring them
+    #[arg(long, global = true)]
+    pub strict: bool,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Initialize this directory as a new .makeitso project
-    Init { name: Option<String> },
+    Init {
+        name: Option<String>,
+
+        /// Ask for the project name, registries, environments and an example plugin
+        #[arg(short, long)]
+        interactive: bool,
+
+        /// Start from a template project (a git URL or directory) and copy its .makeitso/
+        #[arg(long, value_name = "REPO", conflicts_with = "interactive")]
+        from: Option<String>,
+    },
     /// Execute a plugin command
     Run {
-        /// The name of the plugin to run (e.g. api, worker)
-        plugin: String,
+        /// The plugin command (e.g. api:deploy) or workflow name to run
+        /// (defaults to default_command in mis.toml)
+        plugin: Option<String>,
 
         /// Run without actually making changes
         #[arg(long)]
         dry_run: bool,
 
+        /// Environment profile from [env.<name>] in mis.toml (e.g. staging)
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Report every permission the plugin actually uses (needs Deno 2.4+)
+        #[arg(long)]
+        trace_perms: bool,
+
+        /// Print the plugin's merged permissions and the full `deno run` command, without running it
+        #[arg(long, conflicts_with_all = ["plan", "apply_plan"])]
+        show_permissions: bool,
+
+        /// Grant private network addresses plugins request, with a warning, instead of blocking them
+        #[arg(long)]
+        allow_insecure_net: bool,
+
+        /// Fail if the plugin requests any permission security validation blocks
+        #[arg(long)]
+        strict_security: bool,
+
+        /// How to show the file changes a plugin plans on a dry run
+        #[arg(long, value_enum, default_value_t = DiffFormat::Pretty)]
+        diff_format: DiffFormat,
+
+        /// Upload the run's artifacts to the sink configured under [artifacts] in mis.toml
+        #[arg(long)]
+        upload_artifacts: bool,
+
+        /// Run plugins in a container (see [container] in manifest.toml) instead of on the host
+        #[arg(long)]
+        container: bool,
+
+        /// Don't take the project lock, even for commands marked `exclusive = true`
+        #[arg(long)]
+        no_lock: bool,
+
+        /// Wait up to SECS for another run to release the project lock instead of failing
+        #[arg(long, value_name = "SECS")]
+        lock_timeout: Option<u64>,
+
+        /// Have the plugin plan its changes and save the plan for review instead of acting
+        #[arg(long, conflicts_with = "apply_plan")]
+        plan: bool,
+
+        /// Where to save the plan (default: .makeitso/plans/<plugin>-<command>-<time>.json)
+        #[arg(long, value_name = "FILE", requires = "plan")]
+        plan_out: Option<PathBuf>,
+
+        /// Answer yes to commands that ask for confirmation (`confirm` in manifest.toml)
+        #[arg(long)]
+        yes: bool,
+
+        /// Fail instead of warning when running a command marked `deprecated`
+        #[arg(long)]
+        strict_deprecations: bool,
+
+        /// Name of the protected environment being run against, instead of typing it at a prompt
+        #[arg(long, value_name = "ENV")]
+        confirm_env: Option<String>,
+
+        /// Approval token for environments with `require_approval = true`, kept in the run log
+        #[arg(long, value_name = "TOKEN")]
+        approval: Option<String>,
+
+        /// Carry out a plan saved by --plan, with the arguments it was made with
+        #[arg(long, value_name = "FILE", conflicts_with = "dry_run")]
+        apply_plan: Option<PathBuf>,
+
+        /// Resume a failed workflow run, skipping steps that already succeeded
+        #[arg(long, value_name = "RUN_ID")]
+        resume: Option<String>,
+
         /// Any extra args passed to the plugin command
         // #[arg(long, value_parser, num_args=1.., allow_hyphen_values=true)]
-        #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+        #[arg(num_args = 0.., allow_hyphen_values = true)]
+        args: Vec<String>,
+
+        /// Args after `--`, passed to the plugin verbatim as ctx.raw_args
+        #[arg(last = true, value_name = "RAW_ARGS")]
+        raw_args: Vec<String>,
+    },
+    /// Run a one-off script in the plugin sandbox, with the project's context
+    Exec {
+        /// The TypeScript or JavaScript file to run
+        script: PathBuf,
+
+        /// Let the script read these paths, on top of the safe defaults
+        #[arg(long, value_name = "PATH", value_delimiter = ',')]
+        allow_read: Vec<String>,
+
+        /// Let the script write these paths
+        #[arg(long, value_name = "PATH", value_delimiter = ',')]
+        allow_write: Vec<String>,
+
+        /// Let the script reach these hosts (e.g. api.example.com)
+        #[arg(long, value_name = "HOST", value_delimiter = ',')]
+        allow_net: Vec<String>,
+
+        /// Let the script run these commands
+        #[arg(long, value_name = "COMMAND", value_delimiter = ',')]
+        allow_run: Vec<String>,
+
+        /// Let the script see these environment variables (also passed as ctx.env)
+        #[arg(long, value_name = "NAME", value_delimiter = ',')]
+        allow_env: Vec<String>,
+
+        /// Pass dry_run to the script
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Environment profile from [env.<name>] in mis.toml
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Report every permission the script actually uses (needs Deno 2.4+)
+        #[arg(long)]
+        trace_perms: bool,
+
+        /// Name of the protected environment being run against, instead of typing it at a prompt
+        #[arg(long, value_name = "ENV")]
+        confirm_env: Option<String>,
+
+        /// Approval token for environments with `require_approval = true`, kept in the run log
+        #[arg(long, value_name = "TOKEN")]
+        approval: Option<String>,
+
+        /// Arguments for the script, after `--` (e.g. -- --foo bar)
+        #[arg(last = true)]
         args: Vec<String>,
     },
     /// Create a new plugin from template
     Create {
         #[arg(value_name = "plugin_name")]
         name: String,
+
+        /// Template to start from: example, minimal, deploy, docker, terraform,
+        /// or one of yours in ~/.config/makeitso/templates
+        #[arg(long)]
+        template: Option<String>,
+
+        /// Which APIs the generated scripts are written against
+        #[arg(long, value_enum, default_value_t = Runtime::Deno)]
+        runtime: Runtime,
     },
     /// Install plugins from registries
     Add {
@@ -52,6 +234,14 @@ pub enum Commands {
 
         #[arg(long)]
         force: bool,
+
+        /// Fetch the registries even if the cached copies are still fresh
+        #[arg(long)]
+        refresh: bool,
+
+        /// Values for `{{ ask "..." }}` placeholders in plugin config (repeatable)
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
     },
     /// Update a specific plugin or all plugins to the latest versions
     Update {
@@ -59,16 +249,336 @@ pub enum Commands {
 
         #[arg(long)]
         dry_run: bool,
+
+        /// Report plugins with newer versions available without updating; fails if any do
+        #[arg(long, conflicts_with = "dry_run")]
+        check: bool,
+
+        /// Show the files the update changes and ask before overwriting the plugin
+        #[arg(long, conflicts_with = "check")]
+        diff: bool,
+    },
+    /// List the project's plugins and commands, or the projects in its workspace
+    List {
+        /// List the member projects of the workspace instead
+        #[arg(long)]
+        workspace: bool,
     },
     /// Show detailed help for a plugin command
     Info {
         /// Plugin and command to show information for (e.g. my-plugin:deploy)
         plugin_command: Option<String>,
+
+        /// Show resolved project variables and where each one was defined
+        #[arg(long)]
+        vars: bool,
+
+        /// Look the plugin up in the configured registries instead of this project
+        #[arg(long)]
+        remote: bool,
+
+        /// Only show the permissions a plugin's commands (or one command) declare
+        #[arg(long, requires = "plugin_command", conflicts_with_all = ["vars", "remote"])]
+        permissions: bool,
+
+        /// Document every plugin as one page, e.g. `mis info --all > PLUGINS.md`
+        #[arg(long, conflicts_with_all = ["plugin_command", "vars", "remote"])]
+        all: bool,
+
+        /// Format of the `--all` page
+        #[arg(long, value_enum, requires = "all")]
+        format: Option<DocFormat>,
+    },
+    /// Show recent plugin runs recorded in .makeitso/logs/runs.jsonl
+    History {
+        /// Only runs of this plugin (or plugin:command)
+        plugin: Option<String>,
+
+        /// Only runs that failed
+        #[arg(long)]
+        failed: bool,
+
+        /// How many runs to show
+        #[arg(short = 'n', long, default_value_t = 20)]
+        limit: usize,
+    },
+    /// Search the configured registries for plugins
+    Search {
+        /// Text to look for in plugin names and descriptions
+        query: String,
+    },
+    /// Create an executable wrapper in .makeitso/bin for a plugin command
+    Shim {
+        /// Name of the wrapper to create (e.g. deploy-staging)
+        alias: String,
+
+        /// Plugin and command the wrapper runs (e.g. deploy:run)
+        plugin_command: String,
+
+        /// Overwrite an existing wrapper with the same name
+        #[arg(long)]
+        force: bool,
+
+        /// Extra args baked into the wrapper (e.g. -- --env staging)
+        #[arg(last = true)]
+        args: Vec<String>,
+    },
+    /// Show the permissions every plugin command would be granted
+    #[command(args_conflicts_with_subcommands = true)]
+    Audit {
+        /// Only audit this plugin
+        plugin: Option<String>,
+
+        #[command(subcommand)]
+        action: Option<AuditAction>,
+    },
+    /// Check installed plugins against the checksums recorded at install
+    Verify {
+        /// Only verify this plugin
+        plugin: Option<String>,
+
+        /// Also compare against the plugin's current version in its registry
+        #[arg(long)]
+        remote: bool,
+    },
+    /// Print a software bill of materials for installed plugins and their Deno dependencies
+    Sbom {
+        /// Document format
+        #[arg(long, value_enum, default_value_t = SbomFormat::Cyclonedx)]
+        format: SbomFormat,
+    },
+    /// Check every plugin's manifest.toml and config.toml for mistakes
+    Validate {
+        /// Only validate this plugin
+        plugin: Option<String>,
+    },
+    /// Hide an installed plugin from `mis run` and `mis info` without removing it
+    Disable {
+        /// Name of the plugin to disable
+        plugin: String,
+    },
+    /// Make a disabled plugin available again
+    Enable {
+        /// Name of the plugin to enable
+        plugin: String,
+    },
+    /// Upgrade an older project and its plugins to the current layout
+    Migrate {
+        /// Only list the changes that would be made
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Inspect how plugin permissions are resolved
+    Permissions {
+        #[command(subcommand)]
+        action: PermissionsAction,
+    },
+    /// Store or remove credentials for private registries
+    Auth {
+        #[command(subcommand)]
+        action: AuthAction,
+    },
+    /// Generate documentation for mis and the project's plugins
+    Docs {
+        #[command(subcommand)]
+        action: DocsAction,
+    },
+    /// Remove stale caches, artifacts and saved runs, and report the space reclaimed
+    Gc {
+        /// Keep artifacts, saved runs and registry caches used within this many days
+        #[arg(long, default_value_t = 7)]
+        keep_days: u64,
+
+        /// Show what would be removed without deleting anything
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Manage cached data in .makeitso/cache
+    Cache {
+        #[command(subcommand)]
+        action: CacheAction,
+    },
+    /// Run a plugin's test cases in the sandbox and check their results
+    Test {
+        /// Plugin to test, or plugin:command to test only that command
+        target: String,
+    },
+    /// View and edit values in mis.toml, or a plugin's config.toml with --plugin
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    /// Print the context JSON a plugin command would receive, without running it
+    Context {
+        /// The plugin command (e.g. api:deploy)
+        plugin_command: String,
+
+        /// A command argument, as NAME=VALUE (repeatable)
+        #[arg(long = "arg", value_name = "NAME=VALUE")]
+        args: Vec<String>,
+
+        /// Build the context for a dry run
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Environment profile from [env.<name>] in mis.toml (e.g. staging)
+        #[arg(long)]
+        env: Option<String>,
+
+        /// Write the context to this file instead of printing it
+        #[arg(long, value_name = "FILE")]
+        out_file: Option<PathBuf>,
+    },
+    /// Download plugins' Deno dependencies into .makeitso/vendor/ for offline runs
+    Vendor,
+    /// Manage the TypeScript plugin API files in .makeitso/
+    Api {
+        #[command(subcommand)]
+        action: ApiAction,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum AuthAction {
+    /// Save a token for a registry host in the OS keychain
+    Login {
+        /// Registry URL the token is for, e.g. https://github.com/acme/plugins
+        registry: String,
+    },
+    /// Remove the stored token for a registry host
+    Logout {
+        /// Registry URL whose host to forget
+        registry: String,
     },
 }
 
+#[derive(Subcommand, Debug)]
+pub enum AuditAction {
+    /// Check plugins' Deno dependencies against known vulnerabilities (exits non-zero if any)
+    Deps {
+        /// Only check this plugin
+        plugin: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum PermissionsAction {
+    /// Show how a manifest's permissions would resolve, without installing the plugin
+    Simulate {
+        /// Path to a manifest.toml (or the plugin directory containing it)
+        manifest: PathBuf,
+
+        /// Only simulate this command
+        command: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum DocsAction {
+    /// Install man pages for mis, its subcommands and the project's plugins
+    InstallMan {
+        /// Where to write the pages (default: ~/.local/share/man/man1)
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ConfigAction {
+    /// Print a value, e.g. `mis config get project_variables.region`
+    Get {
+        /// Dotted key
+        key: String,
+
+        /// Read the plugin's config.toml instead of mis.toml
+        #[arg(long)]
+        plugin: Option<String>,
+    },
+    /// Change a value, keeping the file's comments and formatting
+    Set {
+        /// Dotted key
+        key: String,
+
+        /// New value, parsed as the key's type (e.g. 3, true, '["a", "b"]')
+        value: String,
+
+        /// Write the plugin's config.toml instead of mis.toml
+        #[arg(long)]
+        plugin: Option<String>,
+    },
+    /// Print every value as a dotted key
+    List {
+        /// List the plugin's config.toml, with its schema defaults, instead of mis.toml
+        #[arg(long)]
+        plugin: Option<String>,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum CacheAction {
+    /// Remove cached data
+    Clean {
+        /// Only clear cached project variable values
+        #[arg(long)]
+        vars: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ApiAction {
+    /// Replace mis-plugin-api.ts and mis-types.d.ts with the versions built into mis
+    Update,
+}
+
+/// Output format for commands that report machine-readable results
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// APIs a scaffolded plugin's scripts are written against
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum Runtime {
+    /// Deno APIs and the mis-plugin-api.ts helpers
+    #[default]
+    Deno,
+    /// Node APIs (`node:` modules), run on Deno's Node compatibility layer
+    Node,
+}
+
+/// Formats `mis info --all` writes the plugin docs page in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DocFormat {
+    #[default]
+    Markdown,
+    Html,
+}
+
+/// How planned file changes are shown on dry runs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum DiffFormat {
+    /// Colored unified diffs with a summary
+    #[default]
+    Pretty,
+    /// Plain unified diffs that `git apply` accepts
+    Patch,
+    Json,
+}
+
+/// Software bill of materials formats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum SbomFormat {
+    /// CycloneDX 1.5 JSON
+    #[default]
+    Cyclonedx,
+    /// SPDX 2.3 JSON
+    Spdx,
+}
+
 pub fn prompt_user(message: &str) -> anyhow::Result<bool> {
-    print!("{} [y/N]: ", message);
+    out!("{} [y/N]: ", message);
     io::stdout().flush()?; // Make sure the prompt shows before user types
 
     let mut input = String::new();
@@ -78,14 +588,43 @@ pub fn prompt_user(message: &str) -> anyhow::Result<bool> {
     Ok(matches!(input.as_str(), "y" | "yes"))
 }
 
+pub fn prompt_input(message: &str) -> anyhow::Result<String> {
+    out!("{}: ", message);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+/// Leading `-q`/`-v` flags, which may come before an implicit plugin:command
+fn leading_verbosity_flags(args: &[String]) -> usize {
+    args.iter()
+        .skip(1)
+        .take_while(|arg| {
+            matches!(arg.as_str(), "-q" | "--quiet" | "--verbose")
+                || (arg.len() > 1 && arg.starts_with('-') && arg[1..].chars().all(|c| c == 'v'))
+        })
+        .count()
+}
+
+/// Built-in subcommands, which neither implicit runs nor aliases can take over
+pub const BUILTIN_SUBCOMMANDS: &[&str] = &[
+    "init", "run", "create", "add", "update", "info", "shim", "cache", "audit", "verify", "sbom", "validate",
+    "migrate", "enable", "disable", "docs", "search", "gc", "permissions", "auth", "history", "api",
+    "test", "context", "config", "vendor", "list", "exec", "help",
+];
+
 /// Check if we should inject "run" command for implicit plugin execution
 /// Returns true if the first argument looks like a plugin:command and isn't already "run"
 pub fn should_inject_run_command(args: &[String]) -> bool {
-    if args.len() < 2 {
+    let first = 1 + leading_verbosity_flags(args);
+    if args.len() <= first {
         return false;
     }
 
-    let first_arg = &args[1];
+    let first_arg = &args[first];
 
     // Don't inject if it's a flag
     if first_arg.starts_with("--") || first_arg.starts_with('-') {
@@ -93,8 +632,7 @@ pub fn should_inject_run_command(args: &[String]) -> bool {
     }
 
     // Don't inject if it's already an explicit subcommand
-    let known_subcommands = ["init", "run", "create", "add", "update", "info"];
-    if known_subcommands.contains(&first_arg.as_str()) {
+    if BUILTIN_SUBCOMMANDS.contains(&first_arg.as_str()) {
         return false;
     }
 
@@ -106,42 +644,143 @@ pub fn should_inject_run_command(args: &[String]) -> bool {
 /// Example: ["mis", "claude:init", "--flag"] → ["mis", "run", "claude:init", "--flag"]
 pub fn transform_args_for_implicit_run(args: &[String]) -> Vec<String> {
     if should_inject_run_command(args) {
-        let mut new_args = vec![args[0].clone(), "run".to_string()];
-        new_args.extend_from_slice(&args[1..]);
+        let first = 1 + leading_verbosity_flags(args);
+        let mut new_args = args[..first].to_vec();
+        new_args.push("run".to_string());
+        new_args.extend_from_slice(&args[first..]);
         new_args
     } else {
         args.to_vec()
     }
 }
 
-pub fn parse_cli_args(args: &[String]) -> HashMap<String, String> {
-    let mut parsed_args = HashMap::new();
+/// Why an alias from `[aliases]` in mis.toml can't be used, if it can't
+pub fn alias_conflict(name: &str) -> Option<String> {
+    if BUILTIN_SUBCOMMANDS.contains(&name) {
+        Some(format!("'{}' is a built-in command", name))
+    } else if name.contains(':') {
+        Some(format!("'{}' looks like a plugin:command", name))
+    } else if name.starts_with('-') || name.contains(char::is_whitespace) {
+        Some(format!("'{}' isn't a single word", name))
+    } else {
+        None
+    }
+}
+
+/// Expand an alias from `[aliases]` in mis.toml. With `deploy = "k8s:apply --environment production"`,
+/// `mis deploy --dry-run` becomes `mis run k8s:apply --environment production --dry-run`.
+/// Aliases aren't expanded again inside other aliases.
+pub fn expand_alias(
+    args: &[String],
+    aliases: &IndexMap<String, String>,
+) -> anyhow::Result<Vec<String>> {
+    let first = 1 + leading_verbosity_flags(args);
+    let Some((name, expansion)) = args
+        .get(first)
+        .and_then(|name| aliases.get_key_value(name))
+        .filter(|(name, _)| alias_conflict(name).is_none())
+    else {
+        return Ok(args.to_vec());
+    };
+
+    let words = split_command(expansion).map_err(|e| {
+        anyhow::anyhow!(
+            "🛑 Alias '{}' in mis.toml has an {}: {}",
+            name,
+            e,
+            expansion
+        )
+    })?;
+    let Some(command) = words.first() else {
+        anyhow::bail!(
+            "🛑 Alias '{}' in mis.toml is empty.\n→ Set it to a command line, e.g. {} = \"plugin:command --flag value\".",
+            name,
+            name
+        );
+    };
+
+    let mut expanded = args[..first].to_vec();
+    // Plugin commands and workflows need `run`; aliases for built-in commands already name one
+    if !BUILTIN_SUBCOMMANDS.contains(&command.as_str()) {
+        expanded.push("run".to_string());
+    }
+    expanded.extend(words);
+    expanded.extend_from_slice(&args[first + 1..]);
+    Ok(expanded)
+}
+
+/// With a `default_command` in mis.toml, a bare `mis` means `mis run`
+pub fn transform_args_for_default_command(args: &[String]) -> Vec<String> {
+    let first = 1 + leading_verbosity_flags(args);
+    if args.len() == first {
+        let mut new_args = args.to_vec();
+        new_args.push("run".to_string());
+        new_args
+    } else {
+        transform_args_for_implicit_run(args)
+    }
+}
+
+/// `--name` or a short `-n`; `-5` is a negative number, not a flag
+fn is_flag(arg: &str) -> bool {
+    arg.starts_with("--") || (arg.starts_with('-') && arg[1..].starts_with(|c: char| c.is_alphabetic()))
+}
+
+/// Parse plugin arguments: `--key value`, `--key=value`, short `-k value` / `-k=value`,
+/// and bare flags as "true". Keys are returned without dashes.
+/// Split plugin arguments at the first `--`: those before it are parsed as flags, those
+/// after it are passed to the plugin verbatim as `ctx.raw_args`
+pub fn split_raw_args(mut args: Vec<String>) -> (Vec<String>, Vec<String>) {
+    match args.iter().position(|arg| arg == "--") {
+        Some(separator) => {
+            let raw_args = args.split_off(separator + 1);
+            args.pop();
+            (args, raw_args)
+        }
+        None => (args, Vec::new()),
+    }
+}
+
+/// Values for plugin args given in TOML (workflow steps, test cases); arrays give several
+pub fn toml_arg_values(value: &toml::Value) -> Vec<String> {
+    match value {
+        toml::Value::String(s) => vec![s.clone()],
+        toml::Value::Array(items) => items.iter().flat_map(toml_arg_values).collect(),
+        other => vec![other.to_string()],
+    }
+}
+
+/// Parse `--name value`, `--name=value` and bare `--flag`s (as "true") into the values
+/// given for each flag, in order, so a repeated flag keeps all of them
+pub fn parse_cli_args(args: &[String]) -> HashMap<String, Vec<String>> {
+    let mut parsed_args: HashMap<String, Vec<String>> = HashMap::new();
     let mut iter = args.iter().peekable();
 
     while let Some(arg) = iter.next() {
-        if arg.starts_with("--") {
+        if is_flag(arg) {
+            let name = arg.strip_prefix("--").unwrap_or(&arg[1..]);
             // Handle --key=value format
-            if let Some(eq_pos) = arg.find('=') {
-                let key = arg[2..eq_pos].to_string();
-                let value = arg[eq_pos + 1..].to_string();
-                parsed_args.insert(key, value);
+            if let Some(eq_pos) = name.find('=') {
+                let key = name[..eq_pos].to_string();
+                let value = name[eq_pos + 1..].to_string();
+                parsed_args.entry(key).or_default().push(value);
             } else {
                 // Handle --key value format or boolean flags
-                let key = arg[2..].to_string();
+                let key = name.to_string();
 
                 // Check if next argument exists and is not a flag
                 if let Some(next_arg) = iter.peek() {
-                    if !next_arg.starts_with("--") {
+                    if !is_flag(next_arg) {
                         // Next argument is a value
                         let value = iter.next().unwrap().to_string();
-                        parsed_args.insert(key, value);
+                        parsed_args.entry(key).or_default().push(value);
                     } else {
                         // Next argument is another flag, treat current as boolean
-                        parsed_args.insert(key, "true".to_string());
+                        parsed_args.entry(key).or_default().push("true".to_string());
                     }
                 } else {
                     // No more arguments, treat as boolean flag
-                    parsed_args.insert(key, "true".to_string());
+                    parsed_args.entry(key).or_default().push("true".to_string());
                 }
             }
         }
@@ -165,8 +804,8 @@ mod tests {
         ];
         let result = parse_cli_args(&args);
 
-        assert_eq!(result.get("name"), Some(&"test".to_string()));
-        assert_eq!(result.get("count"), Some(&"5".to_string()));
+        assert_eq!(result.get("name"), Some(&vec!["test".to_string()]));
+        assert_eq!(result.get("count"), Some(&vec!["5".to_string()]));
     }
 
     #[test]
@@ -180,9 +819,9 @@ mod tests {
         let result = parse_cli_args(&args);
 
         // Now handles boolean flags properly
-        assert_eq!(result.get("verbose"), Some(&"true".to_string()));
-        assert_eq!(result.get("force"), Some(&"true".to_string()));
-        assert_eq!(result.get("name"), Some(&"test".to_string()));
+        assert_eq!(result.get("verbose"), Some(&vec!["true".to_string()]));
+        assert_eq!(result.get("force"), Some(&vec!["true".to_string()]));
+        assert_eq!(result.get("name"), Some(&vec!["test".to_string()]));
     }
 
     #[test]
@@ -195,10 +834,10 @@ mod tests {
         ];
         let result = parse_cli_args(&args);
 
-        assert_eq!(result.get("message"), Some(&"hello world".to_string()));
+        assert_eq!(result.get("message"), Some(&vec!["hello world".to_string()]));
         assert_eq!(
             result.get("path"),
-            Some(&"/path/with spaces/file.txt".to_string())
+            Some(&vec!["/path/with spaces/file.txt".to_string()])
         );
     }
 
@@ -209,8 +848,8 @@ mod tests {
         let result = parse_cli_args(&args);
 
         // Now handles --key=value format
-        assert_eq!(result.get("name"), Some(&"test".to_string()));
-        assert_eq!(result.get("count"), Some(&"5".to_string()));
+        assert_eq!(result.get("name"), Some(&vec!["test".to_string()]));
+        assert_eq!(result.get("count"), Some(&vec!["5".to_string()]));
     }
 
     #[test]
@@ -224,11 +863,11 @@ mod tests {
         ];
         let result = parse_cli_args(&args);
 
-        assert_eq!(result.get("name"), Some(&"test".to_string()));
+        assert_eq!(result.get("name"), Some(&vec!["test".to_string()]));
         // Now handles all formats correctly:
-        assert_eq!(result.get("verbose"), Some(&"true".to_string()));
-        assert_eq!(result.get("count"), Some(&"5".to_string()));
-        assert_eq!(result.get("force"), Some(&"true".to_string()));
+        assert_eq!(result.get("verbose"), Some(&vec!["true".to_string()]));
+        assert_eq!(result.get("count"), Some(&vec!["5".to_string()]));
+        assert_eq!(result.get("force"), Some(&vec!["true".to_string()]));
     }
 
     #[test]
@@ -241,8 +880,8 @@ mod tests {
         ];
         let result = parse_cli_args(&args);
 
-        assert_eq!(result.get("name"), Some(&"".to_string()));
-        assert_eq!(result.get("count"), Some(&"5".to_string()));
+        assert_eq!(result.get("name"), Some(&vec!["".to_string()]));
+        assert_eq!(result.get("count"), Some(&vec!["5".to_string()]));
     }
 
     #[test]
@@ -257,9 +896,9 @@ mod tests {
 
         assert_eq!(
             result.get("url"),
-            Some(&"https://example.com/path?param=value&other=123".to_string())
+            Some(&vec!["https://example.com/path?param=value&other=123".to_string()])
         );
-        assert_eq!(result.get("regex"), Some(&"^[a-zA-Z0-9]+$".to_string()));
+        assert_eq!(result.get("regex"), Some(&vec!["^[a-zA-Z0-9]+$".to_string()]));
     }
 
     #[test]
@@ -274,10 +913,10 @@ mod tests {
         ];
         let result = parse_cli_args(&args);
 
-        assert_eq!(result.get("name"), Some(&"test".to_string()));
+        assert_eq!(result.get("name"), Some(&vec!["test".to_string()]));
         // Now correctly treats --orphaned as a boolean flag
-        assert_eq!(result.get("orphaned"), Some(&"true".to_string()));
-        assert_eq!(result.get("count"), Some(&"5".to_string())); // No longer consumed
+        assert_eq!(result.get("orphaned"), Some(&vec!["true".to_string()]));
+        assert_eq!(result.get("count"), Some(&vec!["5".to_string()])); // No longer consumed
     }
 
     #[test]
@@ -291,7 +930,7 @@ mod tests {
         let result = parse_cli_args(&args);
 
         // Current implementation ignores non-flag arguments
-        assert_eq!(result.get("name"), Some(&"test".to_string()));
+        assert_eq!(result.get("name"), Some(&vec!["test".to_string()]));
         assert_eq!(result.len(), 1);
     }
 
@@ -307,9 +946,71 @@ mod tests {
         ];
         let result = parse_cli_args(&args);
 
-        assert_eq!(result.get("count"), Some(&"42".to_string()));
-        assert_eq!(result.get("price"), Some(&"19.99".to_string()));
-        assert_eq!(result.get("negative"), Some(&"-5".to_string()));
+        assert_eq!(result.get("count"), Some(&vec!["42".to_string()]));
+        assert_eq!(result.get("price"), Some(&vec!["19.99".to_string()]));
+        assert_eq!(result.get("negative"), Some(&vec!["-5".to_string()]));
+    }
+
+    #[test]
+    fn test_parse_cli_args_repeated_flags_keep_every_value() {
+        let args: Vec<String> = ["--tag", "a", "--force", "--tag=b", "-t", "x", "--tag", "c"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result = parse_cli_args(&args);
+
+        assert_eq!(result["tag"], vec!["a", "b", "c"]);
+        assert_eq!(result["t"], vec!["x"]);
+        assert_eq!(result["force"], vec!["true"]);
+    }
+
+    #[test]
+    fn test_split_raw_args_at_first_separator() {
+        let args: Vec<String> = ["--name", "web", "--", "-v", "--", "x"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let (args, raw_args) = split_raw_args(args);
+        assert_eq!(args, vec!["--name", "web"]);
+        assert_eq!(raw_args, vec!["-v", "--", "x"]);
+
+        let (args, raw_args) = split_raw_args(vec!["--force".to_string()]);
+        assert_eq!(args, vec!["--force"]);
+        assert!(raw_args.is_empty());
+    }
+
+    #[test]
+    fn test_run_passes_args_after_separator() {
+        for argv in [
+            vec!["mis", "run", "p:c", "--", "b", "--a"],
+            vec!["mis", "run", "p:c", "--name", "x", "--", "b", "--a"],
+        ] {
+            let Commands::Run { args, raw_args, .. } = Cli::try_parse_from(argv).unwrap().command
+            else {
+                panic!("expected mis run");
+            };
+            let raw_args = if raw_args.is_empty() {
+                split_raw_args(args).1
+            } else {
+                raw_args
+            };
+            assert_eq!(raw_args, vec!["b", "--a"]);
+        }
+    }
+
+    #[test]
+    fn test_parse_cli_args_short_flags() {
+        let args: Vec<String> = ["-e", "prod", "-f", "--name", "app", "-r=3", "--offset", "-5"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let result = parse_cli_args(&args);
+
+        assert_eq!(result.get("e"), Some(&vec!["prod".to_string()]));
+        assert_eq!(result.get("f"), Some(&vec!["true".to_string()]));
+        assert_eq!(result.get("name"), Some(&vec!["app".to_string()]));
+        assert_eq!(result.get("r"), Some(&vec!["3".to_string()]));
+        assert_eq!(result.get("offset"), Some(&vec!["-5".to_string()]));
     }
 
     // Tests for implicit run command feature
@@ -329,6 +1030,25 @@ mod tests {
         assert!(!should_inject_run_command(&["mis".to_string(), "run".to_string(), "plugin:cmd".to_string()]));
         assert!(!should_inject_run_command(&["mis".to_string(), "info".to_string()]));
         assert!(!should_inject_run_command(&["mis".to_string(), "update".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "shim".to_string(), "deploy:run".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "cache".to_string(), "clean".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "audit".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "verify".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "validate".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "migrate".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "disable".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "docs".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "search".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "gc".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "permissions".to_string(), "simulate".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "auth".to_string(), "login".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "history".to_string(), "deploy:prod".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "api".to_string(), "update".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "test".to_string(), "deploy:prod".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "context".to_string(), "deploy:prod".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "vendor".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "list".to_string()]));
+        assert!(!should_inject_run_command(&["mis".to_string(), "exec".to_string(), "a:b.ts".to_string()]));
     }
 
     #[test]
@@ -351,6 +1071,23 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn test_transform_args_injects_run_after_verbosity_flags() {
+        let args = vec!["mis".to_string(), "-vv".to_string(), "claude:init".to_string(), "-q".to_string()];
+        let result = transform_args_for_implicit_run(&args);
+
+        assert_eq!(result, vec![
+            "mis".to_string(),
+            "-vv".to_string(),
+            "run".to_string(),
+            "claude:init".to_string(),
+            "-q".to_string()
+        ]);
+
+        // Other leading flags still disable injection
+        assert!(!should_inject_run_command(&["mis".to_string(), "-q".to_string(), "--version".to_string()]));
+    }
+
     #[test]
     fn test_transform_args_preserves_explicit_run() {
         let args = vec!["mis".to_string(), "run".to_string(), "claude:init".to_string()];
@@ -368,4 +1105,61 @@ mod tests {
         // Should be unchanged
         assert_eq!(result, args);
     }
+
+    fn words(line: &str) -> Vec<String> {
+        line.split(' ').map(str::to_string).collect()
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let aliases = IndexMap::from([
+            ("deploy".to_string(), "k8s:apply --environment production".to_string()),
+            ("ship".to_string(), "release --env 'prod west'".to_string()),
+            ("recent".to_string(), "history --failed".to_string()),
+            ("run".to_string(), "k8s:apply".to_string()),
+            ("broken".to_string(), "k8s:apply 'oops".to_string()),
+            ("empty".to_string(), " ".to_string()),
+        ]);
+
+        assert_eq!(
+            expand_alias(&words("mis -v deploy --dry-run"), &aliases).unwrap(),
+            words("mis -v run k8s:apply --environment production --dry-run")
+        );
+        // Workflows get `run` too; the quoted value stays one argument
+        assert_eq!(
+            expand_alias(&words("mis ship"), &aliases).unwrap(),
+            vec!["mis", "run", "release", "--env", "prod west"]
+        );
+        assert_eq!(
+            expand_alias(&words("mis recent -n 5"), &aliases).unwrap(),
+            words("mis history --failed -n 5")
+        );
+
+        // Built-in commands always win, and anything else is left alone
+        assert_eq!(expand_alias(&words("mis run x:y"), &aliases).unwrap(), words("mis run x:y"));
+        assert_eq!(expand_alias(&words("mis other"), &aliases).unwrap(), words("mis other"));
+        assert_eq!(expand_alias(&words("mis"), &aliases).unwrap(), words("mis"));
+
+        assert!(expand_alias(&words("mis broken"), &aliases).is_err());
+        assert!(expand_alias(&words("mis empty"), &aliases).is_err());
+    }
+
+    #[test]
+    fn test_transform_args_for_default_command() {
+        assert_eq!(transform_args_for_default_command(&words("mis")), words("mis run"));
+        assert_eq!(transform_args_for_default_command(&words("mis -v")), words("mis -v run"));
+        assert_eq!(
+            transform_args_for_default_command(&words("mis deploy:prod")),
+            words("mis run deploy:prod")
+        );
+        assert_eq!(transform_args_for_default_command(&words("mis info")), words("mis info"));
+    }
+
+    #[test]
+    fn test_alias_conflict() {
+        assert_eq!(alias_conflict("deploy"), None);
+        assert!(alias_conflict("run").unwrap().contains("built-in"));
+        assert!(alias_conflict("k8s:apply").is_some());
+        assert!(alias_conflict("--force").is_some());
+    }
 }
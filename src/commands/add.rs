@@ -1,10 +1,26 @@
 use crate::constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE};
 use crate::{
-    config::load_mis_config, git_utils::shallow_clone_repo, models::MakeItSoConfig,
-    plugin_utils::plugin_exists_in_project, security::validate_registry_url,
+    cli::prompt_input,
+    config::{
+        load_mis_config,
+        plugins::{load_plugin_manifest, set_manifest_plugin_fields},
+        settings,
+        placeholders::{find_placeholders, parse_template_vars, render_placeholders},
+    },
+    git_utils::head_commit,
+    integrations::{registry_http::fetch_registry_for, registry_index::fetch_registry_index},
+    models::MakeItSoConfig,
+    plugin_lock::record_plugin_checksums,
+    plugin_utils::plugin_exists_in_project,
+    report::{OutcomeStatus, PluginOutcome, PluginsReport, json_output, print_json},
+    security::{validate_plugin_dependencies, validate_registry_source},
 };
 use anyhow::{Result, anyhow};
-use std::{collections::HashMap, fs, path::Path};
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
 use tempfile::TempDir;
 
 pub fn add_plugin(
@@ -12,9 +28,14 @@ pub fn add_plugin(
     dry_run: bool,
     registry: Option<String>,
     force: bool,
+    refresh: bool,
+    vars: Vec<String>,
 ) -> anyhow::Result<()> {
-    let (config, _, _) = load_mis_config().unwrap();
-    add_plugin_with_config(plugins, dry_run, registry, force, config)
+    let template_vars = parse_template_vars(&vars)?;
+    let (mut config, _, _) = load_mis_config().unwrap();
+    // Registries may also come from the global config or MIS_REGISTRY
+    config.registry = settings().registry.clone();
+    add_plugin_with_config(plugins, dry_run, registry, force, refresh, config, template_vars)
 }
 
 // Testable version that accepts config as parameter (dependency injection)
@@ -23,10 +44,12 @@ pub fn add_plugin_with_config(
     dry_run: bool,
     registry: Option<String>,
     force: bool,
+    refresh: bool,
     config: MakeItSoConfig,
+    template_vars: HashMap<String, String>,
 ) -> anyhow::Result<()> {
     if let Some(reg) = &registry {
-        println!("Custom Registry Provided: {}", reg);
+        debugln!("Using registry from --registry: {}", reg);
     }
 
     // Input validation (Priority 2 issue #8)
@@ -59,17 +82,27 @@ pub fn add_plugin_with_config(
 
     // Validate all registry URLs for security
     for source in &sources {
-        if let Err(security_error) = validate_registry_url(source) {
+        if let Err(security_error) = validate_registry_source(source) {
             return Err(anyhow!(
                 "🛑 Security validation failed for registry '{}': {}\n\
-                 → Registry URLs must be secure HTTPS git repositories from trusted sources.",
+                 → Registry sources must be secure HTTPS git repositories from trusted sources, or local directories (`path:<dir>`).",
                 source,
                 security_error
             ));
         }
     }
 
-    let cloned_repos = temp_clone_repositories(&sources)?;
+    // A registry whose index doesn't list any of the plugins doesn't need to be cloned
+    let sources: Vec<String> = sources
+        .into_iter()
+        .filter(|source| match fetch_registry_index(source) {
+            Some(index) => plugins.iter().any(|plugin| index.get(plugin).is_some()),
+            None => true,
+        })
+        .collect();
+
+    let cloned_repos = cached_repositories(&sources, &plugins, refresh)?;
+    let mut outcomes = Vec::new();
 
     // Loop through the plugin args and handle them
     for plugin in &plugins {
@@ -87,16 +120,17 @@ pub fn add_plugin_with_config(
         }
 
         if !plugin_exists_in_registries(plugin_name, &cloned_repos) {
-            println!("❌ Plugin {} not found in any registry.", plugin_name);
+            outln!("❌ Plugin {} not found in any registry.", plugin_name);
+            outcomes.push(PluginOutcome::new(plugin_name, OutcomeStatus::NotFound));
             continue;
         }
 
         // FIXED: Install from first matching registry only (Priority 1 issue #2)
         let mut installed = false;
-        for (url, temp_dir) in &cloned_repos {
+        for (url, clone_dir) in &cloned_repos {
             // Check both root level and plugins subdirectory
-            let root_plugin_path = temp_dir.path().join(plugin_name);
-            let plugins_subdir_path = temp_dir.path().join("plugins").join(plugin_name);
+            let root_plugin_path = clone_dir.join(plugin_name);
+            let plugins_subdir_path = clone_dir.join("plugins").join(plugin_name);
 
             let source_path = if plugins_subdir_path.exists() && plugins_subdir_path.is_dir() {
                 // Plugin is in plugins/ subdirectory
@@ -110,30 +144,67 @@ pub fn add_plugin_with_config(
             };
 
             if dry_run {
-                println!("📝 Would install plugin '{}' from {}", plugin_name, url);
+                outln!("📝 Would install plugin '{}' from {}", plugin_name, url);
+                let pending = pending_config_placeholders(&source_path, &template_vars)?;
+                if !pending.is_empty() {
+                    outln!("   → Would prompt for config values: {}", pending.join(", "));
+                }
+                outcomes.push(PluginOutcome {
+                    version: manifest_version(&source_path),
+                    registry: Some(url.clone()),
+                    ..PluginOutcome::new(plugin_name, OutcomeStatus::WouldInstall)
+                });
             } else {
-                install_plugin_from_path(plugin_name, &source_path, url, force)?;
+                let dest_path = Path::new(".makeitso/plugins").join(plugin_name);
+                install_plugin_from_path(
+                    plugin_name,
+                    &source_path,
+                    url,
+                    head_commit(clone_dir).as_deref(),
+                    force,
+                )?;
+                fill_config_placeholders(&dest_path, &template_vars)?;
+                outcomes.push(PluginOutcome {
+                    version: manifest_version(&dest_path),
+                    registry: Some(url.clone()),
+                    ..PluginOutcome::new(plugin_name, OutcomeStatus::Installed)
+                });
             }
             installed = true;
             break; // Only install from first matching registry
         }
 
         if !installed && !dry_run {
-            println!(
+            outln!(
                 "❌ Failed to install plugin {} from any registry.",
                 plugin_name
             );
+            outcomes.push(PluginOutcome {
+                error: Some("not installed from any registry".to_string()),
+                ..PluginOutcome::new(plugin_name, OutcomeStatus::Failed)
+            });
         }
     }
 
+    if json_output() {
+        print_json(&PluginsReport::new(dry_run, outcomes))?;
+    }
+
     Ok(())
 }
 
-fn plugin_exists_in_registries(plugin_name: &str, cloned: &HashMap<String, TempDir>) -> bool {
-    for (_registry_url, temp_dir) in cloned {
+/// Version in a plugin directory's manifest, for reports
+pub fn manifest_version(plugin_dir: &Path) -> Option<String> {
+    load_plugin_manifest(&plugin_dir.join(PLUGIN_MANIFEST_FILE))
+        .ok()
+        .map(|manifest| manifest.plugin.version)
+}
+
+fn plugin_exists_in_registries(plugin_name: &str, cloned: &HashMap<String, PathBuf>) -> bool {
+    for clone_dir in cloned.values() {
         // Check both root level and inside 'plugins' subdirectory
-        let root_plugin_path = temp_dir.path().join(plugin_name);
-        let plugins_subdir_path = temp_dir.path().join("plugins").join(plugin_name);
+        let root_plugin_path = clone_dir.join(plugin_name);
+        let plugins_subdir_path = clone_dir.join("plugins").join(plugin_name);
 
         // Check if plugin exists in plugins subdirectory first (more common)
         if plugins_subdir_path.exists() && plugins_subdir_path.is_dir() {
@@ -149,18 +220,82 @@ fn plugin_exists_in_registries(plugin_name: &str, cloned: &HashMap<String, TempD
     false
 }
 
-fn temp_clone_repositories(registries: &[String]) -> Result<HashMap<String, TempDir>> {
-    let mut registry_map = HashMap::new();
+/// Local copies of the registries (with at least `plugins` in them) from the cache;
+/// `refresh` fetches them even when fresh
+fn cached_repositories(
+    registries: &[String],
+    plugins: &[String],
+    refresh: bool,
+) -> Result<HashMap<String, PathBuf>> {
+    fetch_concurrently(registries, |registry_url| {
+        fetch_registry_for(registry_url, plugins, refresh)
+    })
+}
 
+/// Fetch every registry on its own thread, reporting each as it finishes.
+///
+/// Every registry is attempted even if one fails, and all failures are reported together.
+fn fetch_concurrently<F>(registries: &[String], fetch: F) -> Result<HashMap<String, PathBuf>>
+where
+    F: Fn(&str) -> Result<PathBuf> + Sync,
+{
+    let mut unique: Vec<&String> = Vec::new();
     for registry_url in registries {
-        let tmp_dir = TempDir::new()?;
-        let tmp_path = tmp_dir.path().to_string_lossy().to_string();
+        if !unique.contains(&registry_url) {
+            unique.push(registry_url);
+        }
+    }
+    if unique.len() > 1 {
+        outln!("🔄 Fetching {} registries...", unique.len());
+    }
 
-        if let Err(e) = shallow_clone_repo(registry_url.clone(), tmp_path) {
-            return Err(anyhow!("❌ Failed to clone {}: {}", registry_url, e));
+    let results: Vec<(String, Result<PathBuf>)> = std::thread::scope(|scope| {
+        let handles: Vec<_> = unique
+            .iter()
+            .map(|registry_url| {
+                let fetch = &fetch;
+                scope.spawn(move || {
+                    let result = fetch(registry_url);
+                    match &result {
+                        Ok(_) => outln!("   ✅ {}", registry_url),
+                        Err(_) => outln!("   ❌ {}", registry_url),
+                    }
+                    result
+                })
+            })
+            .collect();
+
+        unique
+            .iter()
+            .zip(handles)
+            .map(|(registry_url, handle)| {
+                let result = handle
+                    .join()
+                    .unwrap_or_else(|_| Err(anyhow!("fetch panicked")));
+                (registry_url.to_string(), result)
+            })
+            .collect()
+    });
+
+    let mut registry_map = HashMap::new();
+    let mut failures = Vec::new();
+    for (registry_url, result) in results {
+        match result {
+            Ok(clone_dir) => {
+                registry_map.insert(registry_url, clone_dir);
+            }
+            Err(e) => failures.push(format!("   • {}: {:#}", registry_url, e)),
         }
+    }
 
-        registry_map.insert(registry_url.clone(), tmp_dir); // keep ownership of TempDir
+    if !failures.is_empty() {
+        return Err(anyhow!(
+            "🛑 Failed to fetch {} of {} registries:\n{}\n\
+             → Check the registry URLs and your network connection.",
+            failures.len(),
+            unique.len(),
+            failures.join("\n")
+        ));
     }
 
     Ok(registry_map)
@@ -170,6 +305,7 @@ pub fn install_plugin_from_path(
     plugin_name: &str,
     source_path: &Path,
     registry_url: &str,
+    commit: Option<&str>,
     force: bool,
 ) -> Result<()> {
     if !source_path.exists() || !source_path.is_dir() {
@@ -180,6 +316,12 @@ pub fn install_plugin_from_path(
         ));
     }
 
+    // Refuse plugins whose dependencies the project's security policy doesn't allow
+    let source_manifest = source_path.join(PLUGIN_MANIFEST_FILE);
+    if source_manifest.exists() {
+        validate_plugin_dependencies(&load_plugin_manifest(&source_manifest)?)?;
+    }
+
     let dest_root = Path::new(".makeitso/plugins");
     let dest_path = dest_root.join(plugin_name);
 
@@ -230,7 +372,19 @@ pub fn install_plugin_from_path(
         ));
     }
 
-    println!(
+    // Record checksums so `mis verify` can detect later changes, and the shipped config.toml
+    // so `mis update` can merge the user's edits onto the next version's
+    let default_config = fs::read_to_string(source_path.join(PLUGIN_CONFIG_FILE)).ok();
+    record_plugin_checksums(
+        Path::new("."),
+        plugin_name,
+        Some(registry_url),
+        commit,
+        default_config.as_deref(),
+        &dest_path,
+    )?;
+
+    outln!(
         "✅ Installed plugin '{}' from {} → {}",
         plugin_name,
         registry_url,
@@ -240,26 +394,54 @@ pub fn install_plugin_from_path(
     Ok(())
 }
 
-/// Updates the manifest.toml file to include the registry field
-fn update_manifest_with_registry(manifest_path: &Path, registry_url: &str) -> Result<()> {
-    use crate::constants::PLUGIN_MANIFEST_FILE;
+/// Placeholders in a plugin's config.toml that weren't supplied via --var
+fn pending_config_placeholders(
+    plugin_dir: &Path,
+    template_vars: &HashMap<String, String>,
+) -> Result<Vec<String>> {
+    let config_path = plugin_dir.join(PLUGIN_CONFIG_FILE);
+    if !config_path.exists() {
+        return Ok(vec![]);
+    }
 
-    // Load the existing manifest
-    let manifest_content = fs::read_to_string(manifest_path)?;
-    let mut manifest: crate::models::PluginManifest = toml::from_str(&manifest_content)?;
+    let content = fs::read_to_string(&config_path)?;
+    Ok(find_placeholders(&content)
+        .into_iter()
+        .filter(|name| !template_vars.contains_key(name))
+        .collect())
+}
 
-    // Update the registry field
-    manifest.plugin.registry = Some(registry_url.to_string());
+/// Fill `{{ ask "..." }}` placeholders in an installed plugin's config.toml,
+/// using --var values first and prompting for the rest
+fn fill_config_placeholders(
+    plugin_dir: &Path,
+    template_vars: &HashMap<String, String>,
+) -> Result<()> {
+    let config_path = plugin_dir.join(PLUGIN_CONFIG_FILE);
+    if !config_path.exists() {
+        return Ok(());
+    }
 
-    // Serialize back to TOML
-    let updated_content = toml::to_string_pretty(&manifest)?;
+    let content = fs::read_to_string(&config_path)?;
+    if find_placeholders(&content).is_empty() {
+        return Ok(());
+    }
 
-    // Write back to file
-    fs::write(manifest_path, updated_content)?;
+    outln!("🧩 This plugin needs a few config values:");
+    let rendered = render_placeholders(&content, template_vars, |name| {
+        prompt_input(&format!("   {}", name))
+    })?;
+    fs::write(&config_path, rendered)?;
+    outln!("📋 Wrote {}", config_path.display());
 
     Ok(())
 }
 
+/// Updates the manifest.toml file to include the registry field
+pub fn update_manifest_with_registry(manifest_path: &Path, registry_url: &str) -> Result<()> {
+    set_manifest_plugin_fields(manifest_path, &[("registry", registry_url)])
+}
+
 /// Installs a plugin from a cloned repository (TempDir) without force.
 ///
 /// This is a convenience wrapper around `install_plugin_from_path` that handles
@@ -292,7 +474,7 @@ pub fn install_plugin_from_clone(
         ));
     };
 
-    install_plugin_from_path(plugin_name, &source_path, registry_url, false)
+    install_plugin_from_path(plugin_name, &source_path, registry_url, None, false)
 }
 
 /// Installs a plugin from a cloned repository (TempDir) with optional force.
@@ -329,7 +511,7 @@ pub fn install_plugin_from_clone_with_force(
         ));
     };
 
-    install_plugin_from_path(plugin_name, &source_path, registry_url, force)
+    install_plugin_from_path(plugin_name, &source_path, registry_url, None, force)
 }
 
 pub fn copy_dir_recursive(src: &Path, dst: &Path) -> Result<()> {
@@ -365,6 +547,7 @@ mod tests {
             name: Some("test-project".to_string()),
             project_variables: HashMap::new(),
             registry: registry_sources.map(|sources| RegistryConfig { sources }),
+            ..Default::default()
         }
     }
 
@@ -475,7 +658,7 @@ script = "./test.ts"
     fn test_plugin_exists_in_registries_finds_plugin() {
         let registry = create_mock_registry_with_plugins(vec!["test-plugin", "another-plugin"]);
         let mut cloned = HashMap::new();
-        cloned.insert("test-registry".to_string(), registry);
+        cloned.insert("test-registry".to_string(), registry.path().to_path_buf());
 
         let result = plugin_exists_in_registries("test-plugin", &cloned);
         assert!(result);
@@ -484,6 +667,31 @@ script = "./test.ts"
         assert!(!result);
     }
 
+    #[test]
+    fn test_fetch_concurrently_aggregates_errors() {
+        let registries = vec![
+            "https://example.com/a.git".to_string(),
+            "https://example.com/broken.git".to_string(),
+            "https://example.com/a.git".to_string(),
+            "https://example.com/gone.git".to_string(),
+        ];
+        let fetch = |url: &str| {
+            if url.contains("a.git") {
+                Ok(PathBuf::from("/cache/a"))
+            } else {
+                Err(anyhow!("repository not found"))
+            }
+        };
+
+        let err = fetch_concurrently(&registries, fetch).unwrap_err().to_string();
+        assert!(err.contains("Failed to fetch 2 of 3 registries"));
+        assert!(err.contains("https://example.com/broken.git: repository not found"));
+        assert!(err.contains("https://example.com/gone.git: repository not found"));
+
+        let fetched = fetch_concurrently(&registries[..1], fetch).unwrap();
+        assert_eq!(fetched["https://example.com/a.git"], PathBuf::from("/cache/a"));
+    }
+
     #[test]
     fn test_install_plugin_from_clone_success() {
         let temp_dir = tempdir().unwrap();
@@ -509,6 +717,10 @@ script = "./test.ts"
         assert!(dest_path.join(PLUGIN_MANIFEST_FILE).exists());
         assert!(dest_path.join("main.ts").exists());
 
+        // Checksums are recorded for `mis verify`
+        let lock = crate::plugin_lock::PluginLock::load(temp_dir.path()).unwrap();
+        assert!(lock.plugins["test-plugin"].files.contains_key("main.ts"));
+
         std::env::set_current_dir(original_dir).unwrap();
     }
 
@@ -850,8 +1062,15 @@ script = "./test.ts"
     fn test_add_plugin_validates_empty_plugin_names() {
         let config = create_test_config(Some(vec!["https://example.com/registry".to_string()]));
 
-        let result =
-            add_plugin_with_config(vec!["".to_string()], false, None, false, config.clone());
+        let result = add_plugin_with_config(
+            vec!["".to_string()],
+            false,
+            None,
+            false,
+            false,
+            config.clone(),
+            HashMap::new(),
+        );
         assert!(result.is_err());
         assert!(
             result
@@ -860,7 +1079,15 @@ script = "./test.ts"
                 .contains("Plugin name cannot be empty")
         );
 
-        let result = add_plugin_with_config(vec!["   ".to_string()], false, None, false, config);
+        let result = add_plugin_with_config(
+            vec!["   ".to_string()],
+            false,
+            None,
+            false,
+            false,
+            config,
+            HashMap::new(),
+        );
         assert!(result.is_err());
         assert!(
             result
@@ -892,7 +1119,9 @@ script = "./test.ts"
                 false,
                 None,
                 false,
+                false,
                 config.clone(),
+                HashMap::new(),
             );
             assert!(
                 result.is_err(),
@@ -914,8 +1143,15 @@ script = "./test.ts"
     fn test_add_plugin_should_not_have_duplicate_empty_sources_check() {
         let config = create_test_config(None); // No registry sources
 
-        let result =
-            add_plugin_with_config(vec!["test-plugin".to_string()], false, None, false, config);
+        let result = add_plugin_with_config(
+            vec!["test-plugin".to_string()],
+            false,
+            None,
+            false,
+            false,
+            config,
+            HashMap::new(),
+        );
         assert!(result.is_err());
         let error_msg = result.unwrap_err().to_string();
         assert!(error_msg.contains("No registry sources found"));
@@ -940,7 +1176,9 @@ script = "./test.ts"
                 false,
                 Some(url.to_string()),
                 false,
+                false,
                 config.clone(),
+                HashMap::new(),
             );
 
             assert!(result.is_err(), "Should block localhost URL: {}", url);
@@ -1056,7 +1294,7 @@ max_connections = 10
 
             // Install the plugin directly using the install function
             let result =
-                install_plugin_from_path("config-plugin", &plugin_dir, registry_url, false);
+                install_plugin_from_path("config-plugin", &plugin_dir, registry_url, None, false);
 
             assert!(
                 result.is_ok(),
@@ -1168,4 +1406,39 @@ environment = "production"
             );
         });
     }
+
+    #[test]
+    fn test_fill_config_placeholders_uses_provided_vars() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join(PLUGIN_CONFIG_FILE),
+            "database_url = \"{{ ask \"database_url\" }}\"\n",
+        )
+        .unwrap();
+
+        let mut vars = HashMap::new();
+        vars.insert("database_url".to_string(), "postgres://db".to_string());
+
+        assert!(pending_config_placeholders(temp_dir.path(), &vars).unwrap().is_empty());
+        fill_config_placeholders(temp_dir.path(), &vars).unwrap();
+
+        let content = fs::read_to_string(temp_dir.path().join(PLUGIN_CONFIG_FILE)).unwrap();
+        assert_eq!(content, "database_url = \"postgres://db\"\n");
+    }
+
+    #[test]
+    fn test_pending_config_placeholders_lists_missing_values() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join(PLUGIN_CONFIG_FILE),
+            "a = \"{{ ask \"a\" }}\"\nb = \"{{ ask \"b\" }}\"\n",
+        )
+        .unwrap();
+
+        let mut vars = HashMap::new();
+        vars.insert("a".to_string(), "1".to_string());
+
+        let pending = pending_config_placeholders(temp_dir.path(), &vars).unwrap();
+        assert_eq!(pending, vec!["b"]);
+    }
 }
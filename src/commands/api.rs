@@ -0,0 +1,158 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::{capabilities::PLUGIN_API_VERSION, utils::require_project_root};
+
+/// TypeScript files mis installs into `.makeitso/` for plugins to import
+pub const PLUGIN_API_FILES: &[(&str, &str)] = &[
+    (
+        "mis-types.d.ts",
+        include_str!("../../templates/mis-types.d.ts"),
+    ),
+    (
+        "mis-plugin-api.ts",
+        include_str!("../../templates/mis-plugin-api.ts"),
+    ),
+];
+
+const PLUGIN_API_FILE: &str = "mis-plugin-api.ts";
+
+/// How mis-plugin-api.ts declares its version
+const VERSION_MARKER: &str = "export const MIS_API_VERSION =";
+
+/// Version declared by a copy of mis-plugin-api.ts; None if it predates versioning
+pub fn parse_api_version(source: &str) -> Option<u32> {
+    source.lines().find_map(|line| {
+        line.trim()
+            .strip_prefix(VERSION_MARKER)?
+            .trim()
+            .trim_end_matches(';')
+            .parse()
+            .ok()
+    })
+}
+
+/// Warn when the project's mis-plugin-api.ts doesn't match the version this mis ships.
+/// Nothing is said if the file is missing, since plugins don't have to use it.
+pub fn api_version_warning(makeitso_dir: &Path) -> Option<String> {
+    let source = fs::read_to_string(makeitso_dir.join(PLUGIN_API_FILE)).ok()?;
+
+    match parse_api_version(&source) {
+        Some(version) if version == PLUGIN_API_VERSION => None,
+        Some(version) if version > PLUGIN_API_VERSION => Some(format!(
+            "⚠️  .makeitso/{} is API v{}, newer than this mis supports (v{}).\n\
+             → Upgrade mis, or run `mis api update` to install v{}.",
+            PLUGIN_API_FILE, version, PLUGIN_API_VERSION, PLUGIN_API_VERSION
+        )),
+        version => Some(format!(
+            "⚠️  .makeitso/{} is API v{}, but this mis ships v{}.\n\
+             → Run `mis api update` to refresh it.",
+            PLUGIN_API_FILE,
+            version.unwrap_or(0),
+            PLUGIN_API_VERSION
+        )),
+    }
+}
+
+/// `mis api update`: rewrite the plugin API files with the versions built into mis
+pub fn run_api_update() -> Result<()> {
+    let makeitso_dir = require_project_root()?.join(".makeitso");
+    let previous = fs::read_to_string(makeitso_dir.join(PLUGIN_API_FILE))
+        .ok()
+        .map(|source| parse_api_version(&source).unwrap_or(0));
+
+    let changed = update_api_files(&makeitso_dir)?;
+    if changed.is_empty() {
+        outln!(
+            "✅ Plugin API files are already up to date (v{}).",
+            PLUGIN_API_VERSION
+        );
+        return Ok(());
+    }
+
+    for file in &changed {
+        outln!("📝 Wrote .makeitso/{}", file);
+    }
+    match previous {
+        Some(version) if version != PLUGIN_API_VERSION => outln!(
+            "✅ Plugin API updated from v{} to v{}.",
+            version,
+            PLUGIN_API_VERSION
+        ),
+        _ => outln!("✅ Plugin API is v{}.", PLUGIN_API_VERSION),
+    }
+
+    Ok(())
+}
+
+/// Write any API file that differs from the embedded copy; returns the files written
+pub fn update_api_files(makeitso_dir: &Path) -> Result<Vec<&'static str>> {
+    let mut changed = Vec::new();
+
+    for (file, content) in PLUGIN_API_FILES {
+        let path = makeitso_dir.join(file);
+        if fs::read_to_string(&path).is_ok_and(|existing| existing == *content) {
+            continue;
+        }
+        fs::write(&path, content).with_context(|| format!("Failed to write {}", path.display()))?;
+        changed.push(*file);
+    }
+
+    Ok(changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_embedded_api_declares_current_version() {
+        let (_, source) = PLUGIN_API_FILES
+            .iter()
+            .find(|(file, _)| *file == PLUGIN_API_FILE)
+            .unwrap();
+        assert_eq!(parse_api_version(source), Some(PLUGIN_API_VERSION));
+        assert_eq!(parse_api_version("// no version here"), None);
+    }
+
+    #[test]
+    fn test_update_api_files_and_version_warning() {
+        let temp_dir = tempdir().unwrap();
+        let makeitso_dir = temp_dir.path();
+
+        // No API file: plugins may not use it, so nothing to warn about
+        assert_eq!(api_version_warning(makeitso_dir), None);
+
+        // A copy from before versioning
+        fs::write(
+            makeitso_dir.join(PLUGIN_API_FILE),
+            "export const mis = {};\n",
+        )
+        .unwrap();
+        let warning = api_version_warning(makeitso_dir).unwrap();
+        assert!(warning.contains("is API v0"), "{}", warning);
+        assert!(warning.contains("mis api update"));
+
+        assert_eq!(
+            update_api_files(makeitso_dir).unwrap(),
+            vec!["mis-types.d.ts", "mis-plugin-api.ts"]
+        );
+        assert_eq!(api_version_warning(makeitso_dir), None);
+        assert!(update_api_files(makeitso_dir).unwrap().is_empty());
+
+        // A copy from a newer mis
+        fs::write(
+            makeitso_dir.join(PLUGIN_API_FILE),
+            format!(
+                "export const MIS_API_VERSION = {};\n",
+                PLUGIN_API_VERSION + 1
+            ),
+        )
+        .unwrap();
+        let warning = api_version_warning(makeitso_dir).unwrap();
+        assert!(warning.contains("Upgrade mis"), "{}", warning);
+    }
+}
@@ -0,0 +1,474 @@
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    cli::OutputFormat,
+    commands::sbom::{read_deno_lock, resolved_url},
+    config::plugins::load_plugin_manifest,
+    constants::PLUGIN_MANIFEST_FILE,
+    integrations::{
+        advisories::{Advisory, AdvisorySource, Package, package_for_url},
+        deno::deno_lock_path,
+    },
+    models::PluginManifest,
+    plugin_utils::{get_all_plugin_names, get_plugin_path},
+    report::print_json,
+    security::{PermissionViolation, PluginPermissions, build_plugin_permissions},
+    trust_store::TrustStore,
+    utils::require_project_root,
+};
+
+/// The Deno permissions one plugin command would be granted, and anything worth reviewing
+#[derive(Debug, Serialize)]
+pub struct CommandAudit {
+    pub plugin: String,
+    pub command: String,
+    pub file_read: Vec<String>,
+    pub file_write: Vec<String>,
+    pub env_access: bool,
+    pub env_vars: Vec<String>,
+    pub network: Vec<String>,
+    pub run_commands: Vec<String>,
+
+    /// Requested permissions that security validation blocks
+    pub blocked: Vec<BlockedPermission>,
+
+    /// Grants that deserve a closer look (network access, extra commands)
+    pub flags: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BlockedPermission {
+    pub permission: String,
+    pub reason: String,
+}
+
+/// Print the effective permissions of every installed plugin command (or just one plugin's)
+pub fn run_audit(plugin: Option<&str>, output: OutputFormat) -> Result<()> {
+    let project_root = require_project_root()?;
+    let trust_store = TrustStore::load(&project_root)?;
+
+    let plugin_names = match plugin {
+        Some(name) => {
+            // Validates that the plugin exists
+            get_plugin_path(name)?;
+            vec![name.to_string()]
+        }
+        None => get_all_plugin_names()?,
+    };
+
+    let mut audits = Vec::new();
+    for name in &plugin_names {
+        let manifest_path = get_plugin_path(name)?.join(PLUGIN_MANIFEST_FILE);
+        let manifest = match load_plugin_manifest(&manifest_path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                errln!("⚠️  Warning: Skipping plugin '{}': {}", name, e);
+                continue;
+            }
+        };
+        audits.extend(audit_plugin(&project_root, &manifest, &trust_store)?);
+    }
+
+    match output {
+        OutputFormat::Json => print_json(&audits)?,
+        OutputFormat::Text => print_audits(&audits, &project_root),
+    }
+
+    Ok(())
+}
+
+/// Audit every command of a plugin, sorted by command name
+pub fn audit_plugin(
+    project_root: &Path,
+    manifest: &PluginManifest,
+    trust_store: &TrustStore,
+) -> Result<Vec<CommandAudit>> {
+    let plugin_name = &manifest.plugin.name;
+    let mut command_names: Vec<&String> = manifest.commands.keys().collect();
+    command_names.sort();
+
+    let mut audits = Vec::with_capacity(command_names.len());
+    for command_name in command_names {
+        let mut permissions = build_plugin_permissions(project_root, manifest, command_name)?;
+        trust_store.apply(plugin_name, &mut permissions);
+        audits.push(audit_permissions(plugin_name, command_name, permissions));
+    }
+
+    Ok(audits)
+}
+
+/// Summarize resolved permissions, flagging network access and extra commands
+pub fn audit_permissions(
+    plugin_name: &str,
+    command_name: &str,
+    permissions: PluginPermissions,
+) -> CommandAudit {
+    let extra_commands: Vec<&str> = permissions
+        .run_commands
+        .iter()
+        .map(String::as_str)
+        .filter(|command| *command != "mis")
+        .collect();
+
+    let mut flags = Vec::new();
+    if !permissions.network.is_empty() {
+        flags.push(format!(
+            "network access: {}",
+            permissions.network.join(", ")
+        ));
+    }
+    if !extra_commands.is_empty() {
+        flags.push(format!("runs commands: {}", extra_commands.join(", ")));
+    }
+
+    CommandAudit {
+        plugin: plugin_name.to_string(),
+        command: command_name.to_string(),
+        blocked: blocked_permissions(&permissions.violations),
+        file_read: permissions.file_read,
+        file_write: permissions.file_write,
+        env_access: permissions.env_access,
+        env_vars: permissions.env_vars,
+        network: permissions.network,
+        run_commands: permissions.run_commands,
+        flags,
+    }
+}
+
+pub fn blocked_permissions(violations: &[PermissionViolation]) -> Vec<BlockedPermission> {
+    violations
+        .iter()
+        .map(|v| BlockedPermission {
+            permission: v.kind.describe(&v.value),
+            reason: v.reason.clone(),
+        })
+        .collect()
+}
+
+fn print_audits(audits: &[CommandAudit], project_root: &Path) {
+    if audits.is_empty() {
+        outln!("📋 No plugin commands to audit.");
+        return;
+    }
+
+    outln!("🔒 Effective plugin permissions\n");
+    for audit in audits {
+        outln!("{}:{}", audit.plugin, audit.command);
+        print_command_audit(audit, project_root);
+        outln!();
+    }
+
+    let flagged = audits
+        .iter()
+        .filter(|a| !a.flags.is_empty() || !a.blocked.is_empty())
+        .count();
+    outln!(
+        "{} command(s) audited, {} need a closer look.",
+        audits.len(),
+        flagged
+    );
+}
+
+/// Print one command's permissions, showing the project root as "."
+pub fn print_command_audit(audit: &CommandAudit, project_root: &Path) {
+    outln!("   read:  {}", show_values(&audit.file_read, project_root));
+    outln!("   write: {}", show_values(&audit.file_write, project_root));
+    outln!("   env:   {}", show_env(audit));
+    outln!("   net:   {}", show_values(&audit.network, project_root));
+    outln!(
+        "   run:   {}",
+        show_values(&audit.run_commands, project_root)
+    );
+    for flag in &audit.flags {
+        outln!("   ⚠️  {}", flag);
+    }
+    for blocked in &audit.blocked {
+        outln!("   🛑 Blocked {}: {}", blocked.permission, blocked.reason);
+    }
+}
+
+fn show_env(audit: &CommandAudit) -> String {
+    match (audit.env_vars.is_empty(), audit.env_access) {
+        (false, _) => format!("only {}", audit.env_vars.join(", ")),
+        (true, true) => "yes".to_string(),
+        (true, false) => "no".to_string(),
+    }
+}
+
+pub fn show_values(values: &[String], project_root: &Path) -> String {
+    if values.is_empty() {
+        return "none".to_string();
+    }
+    let root = project_root.to_string_lossy();
+    values
+        .iter()
+        .map(|value| if *value == root { "." } else { value.as_str() })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Known vulnerabilities in one of a plugin's Deno dependencies
+#[derive(Debug, Serialize)]
+pub struct DependencyAudit {
+    pub plugin: String,
+    pub dependency: String,
+
+    /// The URL Deno fetched, after the plugin's lockfile redirects
+    pub url: String,
+
+    /// Package and version the URL names; none if it can't be told from the URL
+    pub package: Option<Package>,
+
+    /// Whether the advisory source has data for the package
+    pub checked: bool,
+
+    pub advisories: Vec<Advisory>,
+}
+
+/// Check every plugin's (or one plugin's) Deno dependencies against the advisory source,
+/// failing if any has a known vulnerability so CI can gate on it
+pub fn run_audit_deps(plugin: Option<&str>, output: OutputFormat) -> Result<()> {
+    let project_root = require_project_root()?;
+    let source = AdvisorySource::configured(&project_root)?;
+
+    let plugin_names = match plugin {
+        Some(name) => {
+            // Validates that the plugin exists
+            get_plugin_path(name)?;
+            vec![name.to_string()]
+        }
+        None => get_all_plugin_names()?,
+    };
+
+    let mut audits = Vec::new();
+    for name in &plugin_names {
+        let manifest_path = get_plugin_path(name)?.join(PLUGIN_MANIFEST_FILE);
+        let manifest = match load_plugin_manifest(&manifest_path) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                errln!("⚠️  Warning: Skipping plugin '{}': {}", name, e);
+                continue;
+            }
+        };
+        let deno_lock = read_deno_lock(&deno_lock_path(&project_root, name)?)?;
+        audits.extend(audit_dependencies(&manifest, &deno_lock, &source)?);
+    }
+
+    match output {
+        OutputFormat::Json => print_json(&audits)?,
+        OutputFormat::Text => print_dependency_audits(&audits, &source),
+    }
+
+    let vulnerable: Vec<&DependencyAudit> =
+        audits.iter().filter(|a| !a.advisories.is_empty()).collect();
+    if !vulnerable.is_empty() {
+        let found: usize = vulnerable.iter().map(|a| a.advisories.len()).sum();
+        anyhow::bail!(
+            "🛑 Found {} known vulnerabilit{} in {} dependenc{}\n\
+             → Upgrade them to a fixed version in the plugin's [deno_dependencies], or update the plugin.",
+            found,
+            if found == 1 { "y" } else { "ies" },
+            vulnerable.len(),
+            if vulnerable.len() == 1 { "y" } else { "ies" }
+        );
+    }
+
+    Ok(())
+}
+
+/// Look up each of a plugin's dependencies, at the version its lockfile resolved to
+pub fn audit_dependencies(
+    manifest: &PluginManifest,
+    deno_lock: &serde_json::Value,
+    source: &AdvisorySource,
+) -> Result<Vec<DependencyAudit>> {
+    let mut audits = Vec::new();
+    for (name, url) in &manifest.deno_dependencies {
+        let resolved = resolved_url(url, deno_lock);
+        let package = package_for_url(resolved);
+        let checked = package.as_ref().is_some_and(|p| source.covers(p));
+        let advisories = match &package {
+            Some(package) if checked => source.advisories(package)?,
+            _ => Vec::new(),
+        };
+        audits.push(DependencyAudit {
+            plugin: manifest.plugin.name.clone(),
+            dependency: name.clone(),
+            url: resolved.to_string(),
+            package,
+            checked,
+            advisories,
+        });
+    }
+    Ok(audits)
+}
+
+fn print_dependency_audits(audits: &[DependencyAudit], source: &AdvisorySource) {
+    if audits.is_empty() {
+        outln!("📋 No plugin dependencies to check.");
+        return;
+    }
+
+    outln!("🔎 Plugin dependencies checked against {}\n", source.describe());
+    let mut current_plugin = None;
+    for audit in audits {
+        if current_plugin != Some(&audit.plugin) {
+            if current_plugin.is_some() {
+                outln!();
+            }
+            outln!("{}", audit.plugin);
+            current_plugin = Some(&audit.plugin);
+        }
+        match &audit.package {
+            None => outln!(
+                "   ⚪ {}: not checked (no package version in {})",
+                audit.dependency,
+                audit.url
+            ),
+            Some(package) if !audit.checked => outln!(
+                "   ⚪ {} ({} {}): not checked ({} has no {} advisories)",
+                audit.dependency,
+                package.name,
+                package.version,
+                source.describe(),
+                package.ecosystem
+            ),
+            Some(package) if audit.advisories.is_empty() => outln!(
+                "   ✅ {} ({} {})",
+                audit.dependency,
+                package.name,
+                package.version
+            ),
+            Some(package) => {
+                outln!(
+                    "   🛑 {} ({} {})",
+                    audit.dependency,
+                    package.name,
+                    package.version
+                );
+                for advisory in &audit.advisories {
+                    let fixed = match advisory.fixed.as_slice() {
+                        [] => "no fix yet".to_string(),
+                        fixed => format!("fixed in {}", fixed.join(", ")),
+                    };
+                    outln!(
+                        "      {} [{}] {} ({})",
+                        advisory.id,
+                        advisory.severity,
+                        advisory.summary.as_deref().unwrap_or("no summary"),
+                        fixed
+                    );
+                }
+            }
+        }
+    }
+
+    let vulnerable = audits.iter().filter(|a| !a.advisories.is_empty()).count();
+    let unchecked = audits.iter().filter(|a| !a.checked).count();
+    outln!(
+        "\n{} dependenc(ies) checked, {} vulnerable, {} not checked.",
+        audits.len() - unchecked,
+        vulnerable,
+        unchecked
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_audit_plugin_merges_permissions_and_flags() {
+        let temp_dir = tempdir().unwrap();
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[commands.status]
+script = "./status.ts"
+
+[commands.apply]
+script = "./apply.ts"
+
+[commands.apply.permissions]
+network = ["api.example.com", "10.0.0.5"]
+run_commands = ["kubectl"]
+
+[permissions]
+env_access = false
+"#,
+        )
+        .unwrap();
+
+        let audits = audit_plugin(temp_dir.path(), &manifest, &TrustStore::default()).unwrap();
+        assert_eq!(audits.len(), 2);
+
+        let apply = &audits[0];
+        assert_eq!(apply.command, "apply");
+        assert!(!apply.env_access);
+        assert_eq!(apply.network, vec!["api.example.com"]);
+        assert_eq!(apply.run_commands, vec!["mis", "kubectl"]);
+        assert_eq!(apply.flags.len(), 2);
+        assert!(apply.flags[1].contains("kubectl"));
+        assert_eq!(apply.blocked.len(), 1);
+        assert!(apply.blocked[0].permission.contains("10.0.0.5"));
+
+        let status = &audits[1];
+        assert!(status.flags.is_empty());
+        assert!(status.blocked.is_empty());
+
+        let json = serde_json::to_value(&audits).unwrap();
+        assert_eq!(json[0]["plugin"], "deploy");
+        assert_eq!(json[0]["blocked"][0]["reason"], apply.blocked[0].reason);
+    }
+
+    #[test]
+    fn test_audit_dependencies_checks_resolved_versions() {
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[deno_dependencies]
+lodash = "https://esm.sh/lodash"
+oak = "https://deno.land/x/oak@v12.6.1/mod.ts"
+local = "https://example.com/lib.ts"
+"#,
+        )
+        .unwrap();
+        let deno_lock = serde_json::json!({
+            "redirects": { "https://esm.sh/lodash": "https://esm.sh/lodash@4.17.15" }
+        });
+        let source = AdvisorySource::Feed {
+            location: "advisories.json".to_string(),
+            records: vec![serde_json::json!({
+                "id": "GHSA-p6mc-m468-83gw",
+                "database_specific": { "severity": "HIGH" },
+                "affected": [{
+                    "package": { "ecosystem": "npm", "name": "lodash" },
+                    "ranges": [{ "events": [{ "introduced": "0" }, { "fixed": "4.17.19" }] }]
+                }]
+            })],
+        };
+
+        let audits = audit_dependencies(&manifest, &deno_lock, &source).unwrap();
+        assert_eq!(audits.len(), 3);
+
+        assert_eq!(audits[0].url, "https://esm.sh/lodash@4.17.15");
+        assert_eq!(audits[0].advisories.len(), 1);
+        assert_eq!(audits[0].advisories[0].severity, "HIGH");
+
+        assert!(audits[1].checked);
+        assert!(audits[1].advisories.is_empty());
+
+        assert!(audits[2].package.is_none());
+        assert!(!audits[2].checked);
+    }
+}
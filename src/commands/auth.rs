@@ -0,0 +1,82 @@
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    integrations::registry_auth::{delete_token, host_token_var, registry_host, store_token},
+    security::validate_registry_source,
+};
+
+/// Ask for a registry token and save it in the OS keychain
+pub fn run_login(registry: &str) -> Result<()> {
+    let host = auth_host(registry)?;
+
+    let token = read_secret(&format!("🔑 Token for {}", host))?;
+    let token = token.trim();
+    if token.is_empty() {
+        anyhow::bail!("🛑 No token entered; nothing was saved.");
+    }
+
+    store_token(&host, token)?;
+    outln!("✅ Saved token for {} in the OS keychain.", host);
+    outln!(
+        "→ {} or MIS_REGISTRY_TOKEN take precedence when set.",
+        host_token_var(&host)
+    );
+    Ok(())
+}
+
+/// Remove a registry token from the OS keychain
+pub fn run_logout(registry: &str) -> Result<()> {
+    let host = auth_host(registry)?;
+    if delete_token(&host)? {
+        outln!("✅ Removed token for {}.", host);
+    } else {
+        outln!("ℹ️  No stored token for {}.", host);
+    }
+    Ok(())
+}
+
+fn auth_host(registry: &str) -> Result<String> {
+    if let Err(security_error) = validate_registry_source(registry) {
+        anyhow::bail!(
+            "🛑 Security validation failed for registry '{}': {}",
+            registry,
+            security_error
+        );
+    }
+    registry_host(registry).ok_or_else(|| {
+        anyhow!(
+            "🛑 '{}' has no host to authenticate against.\n\
+             → Pass the registry's HTTPS URL, e.g. https://github.com/acme/plugins",
+            registry
+        )
+    })
+}
+
+/// Read a line without echoing it when stdin is a terminal
+fn read_secret(prompt: &str) -> Result<String> {
+    out!("{}: ", prompt);
+    io::stdout().flush()?;
+
+    let hide = io::stdin().is_terminal() && set_echo(false);
+    let mut input = String::new();
+    let read = io::stdin().read_line(&mut input);
+    if hide {
+        set_echo(true);
+        outln!();
+    }
+    read?;
+
+    Ok(input.trim_end_matches(['\r', '\n']).to_string())
+}
+
+fn set_echo(on: bool) -> bool {
+    cfg!(unix)
+        && Command::new("stty")
+            .arg(if on { "echo" } else { "-echo" })
+            .stdin(Stdio::inherit())
+            .status()
+            .is_ok_and(|status| status.success())
+}
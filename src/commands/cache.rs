@@ -0,0 +1,76 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    config::variables::variable_cache_path, temp_files::remove_abandoned_runs,
+    utils::require_project_root,
+};
+
+/// Runs that have left their temp files this long are assumed to be gone
+const ABANDONED_AFTER: Duration = Duration::from_secs(60 * 60);
+
+/// Remove cached data under .makeitso/cache (or just the variable cache with `vars_only`),
+/// plus temp files left behind by plugin runs that crashed
+pub fn clean_cache(vars_only: bool) -> Result<()> {
+    let project_root = require_project_root()?;
+
+    let mut removed: Vec<PathBuf> = remove_cache(&project_root, vars_only)?.into_iter().collect();
+    if !vars_only {
+        removed.extend(remove_abandoned_runs(ABANDONED_AFTER));
+    }
+
+    if removed.is_empty() {
+        outln!("✨ Nothing to clean.");
+    }
+    for path in removed {
+        outln!("🧹 Removed {}", path.display());
+    }
+
+    Ok(())
+}
+
+/// Delete the cache and return what was removed, or None if it didn't exist
+pub fn remove_cache(project_root: &Path, vars_only: bool) -> Result<Option<PathBuf>> {
+    if vars_only {
+        let path = variable_cache_path(project_root);
+        if !path.exists() {
+            return Ok(None);
+        }
+        fs::remove_file(&path).with_context(|| format!("Failed to remove {}", path.display()))?;
+        return Ok(Some(path));
+    }
+
+    let dir = project_root.join(".makeitso").join("cache");
+    if !dir.exists() {
+        return Ok(None);
+    }
+    fs::remove_dir_all(&dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    Ok(Some(dir))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_remove_cache_vars_only_keeps_other_entries() {
+        let temp_dir = tempdir().unwrap();
+        let cache_dir = temp_dir.path().join(".makeitso").join("cache");
+        fs::create_dir_all(cache_dir.join("permissions")).unwrap();
+        fs::write(cache_dir.join("vars.json"), "{}").unwrap();
+
+        let removed = remove_cache(temp_dir.path(), true).unwrap();
+        assert_eq!(removed, Some(cache_dir.join("vars.json")));
+        assert!(cache_dir.join("permissions").exists());
+        assert_eq!(remove_cache(temp_dir.path(), true).unwrap(), None);
+
+        let removed = remove_cache(temp_dir.path(), false).unwrap();
+        assert_eq!(removed, Some(cache_dir.clone()));
+        assert!(!cache_dir.exists());
+        assert_eq!(remove_cache(temp_dir.path(), false).unwrap(), None);
+    }
+}
@@ -0,0 +1,389 @@
+use std::fs;
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use toml_edit::{DocumentMut, Item, Table, Value};
+
+use crate::{
+    config::{plugins::load_plugin_manifest, toml_errors::parse_toml},
+    constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE},
+    models::{ConfigValueType, MakeItSoConfig, PluginManifest},
+    plugin_utils::get_plugin_path,
+    report::{json_output, print_json},
+    utils::require_project_root,
+};
+
+/// The file `mis config` reads and writes: mis.toml, or a plugin's config.toml
+struct ConfigFile {
+    path: PathBuf,
+
+    /// The plugin's manifest, for its `[config_schema]`
+    manifest: Option<PluginManifest>,
+}
+
+impl ConfigFile {
+    fn open(plugin: Option<&str>) -> Result<Self> {
+        let Some(plugin) = plugin else {
+            return Ok(Self {
+                path: require_project_root()?.join(".makeitso").join("mis.toml"),
+                manifest: None,
+            });
+        };
+        let plugin_path = get_plugin_path(plugin)?;
+        Ok(Self {
+            path: plugin_path.join(PLUGIN_CONFIG_FILE),
+            manifest: Some(load_plugin_manifest(
+                &plugin_path.join(PLUGIN_MANIFEST_FILE),
+            )?),
+        })
+    }
+
+    /// config.toml is optional, so a missing one reads as empty
+    fn read(&self) -> Result<DocumentMut> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound && self.manifest.is_some() => {
+                String::new()
+            }
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("🛑 Failed to read {}", self.path.display()));
+            }
+        };
+        contents
+            .parse()
+            .with_context(|| format!("🛑 Failed to parse {}", self.path.display()))
+    }
+
+    fn schema_type(&self, key: &str) -> Option<ConfigValueType> {
+        self.manifest.as_ref()?.config_schema.get(key)?.value_type
+    }
+
+    fn schema_default(&self, key: &str) -> Option<&toml::Value> {
+        self.manifest
+            .as_ref()?
+            .config_schema
+            .get(key)?
+            .default
+            .as_ref()
+    }
+
+    fn display_path(&self) -> String {
+        let root = require_project_root().unwrap_or_default();
+        self.path
+            .strip_prefix(&root)
+            .unwrap_or(&self.path)
+            .display()
+            .to_string()
+    }
+}
+
+/// `mis config get <key>`: print one value; strings come out bare so scripts can use them
+pub fn run_config_get(key: &str, plugin: Option<&str>) -> Result<()> {
+    let file = ConfigFile::open(plugin)?;
+    let doc = file.read()?;
+    let value = match lookup(doc.as_item(), key) {
+        Some(item) => item_to_toml(item)?,
+        None => file.schema_default(key).cloned().ok_or_else(|| {
+            anyhow::anyhow!(
+                "🛑 `{}` isn't set in {}.\n\
+                 → Run `mis config list{}` to see what is.",
+                key,
+                file.display_path(),
+                plugin
+                    .map(|p| format!(" --plugin {}", p))
+                    .unwrap_or_default()
+            )
+        })?,
+    };
+
+    if json_output() {
+        return print_json(&serde_json::json!({ "key": key, "value": value }));
+    }
+    match &value {
+        toml::Value::String(s) => outln!("{}", s),
+        other => outln!("{}", other),
+    }
+    Ok(())
+}
+
+/// `mis config set <key> <value>`: change one value, keeping the file's comments and layout
+pub fn run_config_set(key: &str, raw: &str, plugin: Option<&str>) -> Result<()> {
+    let file = ConfigFile::open(plugin)?;
+    let mut doc = file.read()?;
+
+    let expected = file
+        .schema_type(key)
+        .or_else(|| lookup(doc.as_item(), key).and_then(item_type));
+    let new_value = parse_value(key, raw, expected)?;
+    set_path(&mut doc, key, new_value.clone())?;
+
+    let contents = doc.to_string();
+    if file.manifest.is_none() {
+        // Don't leave behind a mis.toml every other command refuses to load
+        parse_toml::<MakeItSoConfig>(&contents, &file.path).with_context(|| {
+            format!("🛑 Setting `{}` to {} would break mis.toml", key, new_value)
+        })?;
+    }
+    fs::write(&file.path, contents)
+        .with_context(|| format!("🛑 Failed to write {}", file.path.display()))?;
+
+    if json_output() {
+        return print_json(&serde_json::json!({
+            "key": key,
+            "value": item_to_toml(&Item::Value(new_value))?,
+            "file": file.display_path(),
+        }));
+    }
+    outln!(
+        "✅ Set {} = {} in {}",
+        key,
+        new_value.to_string().trim(),
+        file.display_path()
+    );
+    Ok(())
+}
+
+/// `mis config list`: every value as a dotted key, plus schema defaults for unset plugin keys
+pub fn run_config_list(plugin: Option<&str>) -> Result<()> {
+    let file = ConfigFile::open(plugin)?;
+    let doc = file.read()?;
+    let table: toml::Table = toml::from_str(&doc.to_string())
+        .with_context(|| format!("🛑 Failed to parse {}", file.path.display()))?;
+
+    let mut entries = Vec::new();
+    flatten("", &toml::Value::Table(table), &mut entries);
+    let defaults: Vec<(String, toml::Value)> = file
+        .manifest
+        .iter()
+        .flat_map(|manifest| &manifest.config_schema)
+        .filter(|(key, _)| lookup(doc.as_item(), key).is_none())
+        .filter_map(|(key, def)| Some((key.clone(), def.default.clone()?)))
+        .collect();
+
+    if json_output() {
+        let values: serde_json::Map<String, serde_json::Value> = entries
+            .iter()
+            .chain(&defaults)
+            .map(|(key, value)| Ok((key.clone(), serde_json::to_value(value)?)))
+            .collect::<Result<_>>()?;
+        return print_json(&serde_json::json!({
+            "file": file.display_path(),
+            "values": values,
+        }));
+    }
+
+    outln!("⚙️  {}\n", file.display_path());
+    if entries.is_empty() && defaults.is_empty() {
+        outln!("   (nothing set)");
+    }
+    for (key, value) in &entries {
+        outln!("   {} = {}", key, value);
+    }
+    for (key, value) in &defaults {
+        outln!("   {} = {}   (default)", key, value);
+    }
+    Ok(())
+}
+
+/// The item at a dotted key, e.g. `project_variables.region`
+pub fn lookup<'a>(item: &'a Item, key: &str) -> Option<&'a Item> {
+    key.split('.')
+        .try_fold(item, |item, part| item.as_table_like()?.get(part))
+}
+
+fn item_type(item: &Item) -> Option<ConfigValueType> {
+    match item {
+        Item::Value(Value::String(_)) => Some(ConfigValueType::String),
+        Item::Value(Value::Boolean(_)) => Some(ConfigValueType::Boolean),
+        Item::Value(Value::Integer(_)) => Some(ConfigValueType::Integer),
+        Item::Value(Value::Float(_)) => Some(ConfigValueType::Float),
+        Item::Value(Value::Array(_)) | Item::ArrayOfTables(_) => Some(ConfigValueType::Array),
+        Item::Value(Value::InlineTable(_)) | Item::Table(_) => Some(ConfigValueType::Table),
+        _ => None,
+    }
+}
+
+/// Parse a command-line value as the type the key expects; with none known, as a TOML value
+/// (`3`, `true`, `["a", "b"]`) and otherwise as a string
+pub fn parse_value(key: &str, raw: &str, expected: Option<ConfigValueType>) -> Result<Value> {
+    let Some(expected) = expected else {
+        return Ok(raw.parse::<Value>().unwrap_or_else(|_| Value::from(raw)));
+    };
+    let parsed = match expected {
+        ConfigValueType::String => Some(Value::from(raw)),
+        ConfigValueType::Boolean => raw.parse::<bool>().ok().map(Value::from),
+        ConfigValueType::Integer => raw.parse::<i64>().ok().map(Value::from),
+        ConfigValueType::Float => raw.parse::<f64>().ok().map(Value::from),
+        ConfigValueType::Array => raw.parse::<Value>().ok().filter(Value::is_array),
+        ConfigValueType::Table => raw.parse::<Value>().ok().filter(Value::is_inline_table),
+    };
+    parsed.ok_or_else(|| {
+        let example = match expected {
+            ConfigValueType::Boolean => "true or false",
+            ConfigValueType::Array => "e.g. '[\"a\", \"b\"]'",
+            ConfigValueType::Table => "e.g. '{ host = \"db\", port = 5432 }'",
+            _ => "",
+        };
+        anyhow::anyhow!(
+            "🛑 `{}` must be {}, but '{}' isn't one.{}",
+            key,
+            expected.with_article(),
+            raw,
+            if example.is_empty() {
+                String::new()
+            } else {
+                format!("\n→ Pass {}", example)
+            }
+        )
+    })
+}
+
+/// Set a dotted key, creating the tables on the way and keeping an existing value's comments
+pub fn set_path(doc: &mut DocumentMut, key: &str, mut new_value: Value) -> Result<()> {
+    let parts: Vec<&str> = key.split('.').collect();
+    let (last, parents) = parts.split_last().expect("split always yields a part");
+
+    let mut table = doc.as_table_mut() as &mut dyn toml_edit::TableLike;
+    for (i, part) in parents.iter().enumerate() {
+        let entry = table.entry(part).or_insert_with(|| {
+            let mut new_table = Table::new();
+            new_table.set_implicit(true);
+            Item::Table(new_table)
+        });
+        table = entry.as_table_like_mut().ok_or_else(|| {
+            anyhow::anyhow!(
+                "🛑 Can't set `{}`: `{}` isn't a table.",
+                key,
+                parts[..=i].join(".")
+            )
+        })?;
+    }
+
+    match table.get_mut(last) {
+        Some(Item::Value(existing)) => {
+            *new_value.decor_mut() = existing.decor().clone();
+            *existing = new_value;
+        }
+        Some(Item::None) | None => {
+            table.insert(last, Item::Value(new_value));
+        }
+        Some(_) => anyhow::bail!(
+            "🛑 `{}` is a section, not a single value.\n\
+             → Set the keys inside it instead, e.g. `{}.<name>`.",
+            key,
+            key
+        ),
+    }
+    Ok(())
+}
+
+fn item_to_toml(item: &Item) -> Result<toml::Value> {
+    let mut doc = DocumentMut::new();
+    doc.insert("value", item.clone());
+    let mut table: toml::Table = toml::from_str(&doc.to_string())?;
+    table
+        .remove("value")
+        .context("🛑 Failed to read the value back")
+}
+
+/// Leaf values of nested tables as dotted keys, sorted
+pub fn flatten(prefix: &str, value: &toml::Value, entries: &mut Vec<(String, toml::Value)>) {
+    match value {
+        toml::Value::Table(table) if !table.is_empty() || prefix.is_empty() => {
+            for (key, value) in table {
+                let key = if prefix.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{}.{}", prefix, key)
+                };
+                flatten(&key, value, entries);
+            }
+        }
+        _ => entries.push((prefix.to_string(), value.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_value_uses_the_expected_type() {
+        let value = parse_value("replicas", "3", Some(ConfigValueType::Integer)).unwrap();
+        assert_eq!(value.as_integer(), Some(3));
+        let value = parse_value("tag", "3", Some(ConfigValueType::String)).unwrap();
+        assert_eq!(value.as_str(), Some("3"));
+        let value = parse_value("ratio", "2", Some(ConfigValueType::Float)).unwrap();
+        assert_eq!(value.as_float(), Some(2.0));
+
+        let err = parse_value("max_retries", "three", Some(ConfigValueType::Integer))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`max_retries` must be an integer, but 'three' isn't one"));
+        assert!(parse_value("debug", "yes", Some(ConfigValueType::Boolean)).is_err());
+
+        // Without a known type, TOML syntax decides and anything else is a string
+        assert!(parse_value("k", "true", None).unwrap().is_bool());
+        assert!(parse_value("k", r#"["a", "b"]"#, None).unwrap().is_array());
+        assert_eq!(
+            parse_value("k", "us-east-1", None).unwrap().as_str(),
+            Some("us-east-1")
+        );
+    }
+
+    #[test]
+    fn test_set_path_keeps_formatting_and_creates_tables() {
+        let mut doc: DocumentMut = r#"name = "api"
+
+[project_variables]
+# Where we deploy
+region = "us-east-1"   # primary
+replicas = 1
+"#
+        .parse()
+        .unwrap();
+
+        set_path(
+            &mut doc,
+            "project_variables.region",
+            Value::from("eu-west-1"),
+        )
+        .unwrap();
+        set_path(
+            &mut doc,
+            "commands.k8s:deploy.variables.namespace",
+            Value::from("payments"),
+        )
+        .unwrap();
+        assert_eq!(
+            doc.to_string(),
+            r#"name = "api"
+
+[project_variables]
+# Where we deploy
+region = "eu-west-1"   # primary
+replicas = 1
+
+[commands."k8s:deploy".variables]
+namespace = "payments"
+"#
+        );
+
+        let err = set_path(&mut doc, "name.first", Value::from("x"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("`name` isn't a table"));
+        assert!(set_path(&mut doc, "project_variables", Value::from("x")).is_err());
+    }
+
+    #[test]
+    fn test_flatten_uses_dotted_keys() {
+        let table: toml::Table =
+            toml::from_str("a = 1\n[b]\nc = \"x\"\n[b.d]\ne = [1, 2]\n[empty]\n").unwrap();
+        let mut entries = Vec::new();
+        flatten("", &toml::Value::Table(table), &mut entries);
+        let keys: Vec<&str> = entries.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["a", "b.c", "b.d.e", "empty"]);
+    }
+}
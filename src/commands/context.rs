@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result};
+
+use crate::{
+    commands::run::{
+        RunOptions, build_execution_context, unknown_command_message, validate_plugin_exists,
+        validated_plugin_args,
+    },
+    config::{
+        apply_command_variables, apply_env_file_variables, apply_env_profile, load_mis_config,
+        plugins::{load_plugin_manifest, load_plugin_user_config},
+    },
+    constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE},
+    utils::require_project_root,
+};
+
+/// `mis context <plugin>:<command>`: build the context the command would receive and
+/// print it, or write it to `out_file`, without running the plugin
+pub fn run_context(
+    plugin_command: &str,
+    args: &[String],
+    dry_run: bool,
+    environment: Option<&str>,
+    out_file: Option<&Path>,
+) -> Result<()> {
+    let (plugin_name, command_name) = plugin_command.split_once(':').ok_or_else(|| {
+        anyhow::anyhow!(
+            "🛑 Invalid target '{}'.\n\
+             → Name a plugin command as <plugin>:<command>, e.g. mis context {}:deploy",
+            plugin_command,
+            plugin_command
+        )
+    })?;
+
+    let plugin_path = validate_plugin_exists(plugin_name)?;
+    let manifest = load_plugin_manifest(&plugin_path.join(PLUGIN_MANIFEST_FILE))?;
+    let user_config = load_plugin_user_config(&plugin_path.join(PLUGIN_CONFIG_FILE), &manifest)?;
+
+    let command = manifest
+        .commands
+        .get(command_name)
+        .with_context(|| unknown_command_message(&manifest, plugin_name, command_name))?;
+
+    let mut plugin_args =
+        validated_plugin_args(parse_arg_pairs(args)?, command, plugin_name, command_name)?;
+    if dry_run {
+        plugin_args.insert("dry_run".to_string(), serde_json::Value::Bool(true));
+    }
+
+    let (mut mis_config, _, _) = load_mis_config()?;
+    apply_env_profile(&mut mis_config, environment)?;
+    apply_command_variables(&mut mis_config, &user_config, plugin_name, command_name);
+    apply_env_file_variables(&mut mis_config, &require_project_root()?, environment)?;
+
+    let options = RunOptions {
+        environment,
+        ..RunOptions::default()
+    };
+    let ctx = build_execution_context(
+        &manifest,
+        user_config,
+        mis_config.project_variables,
+        command_name,
+        plugin_args,
+        dry_run,
+        &options,
+    )?;
+    let json = serde_json::to_string_pretty(&ctx)?;
+
+    match out_file {
+        Some(path) => {
+            fs::write(path, format!("{}\n", json))
+                .with_context(|| format!("🛑 Failed to write {}", path.display()))?;
+            outln!(
+                "📝 Wrote context for {} to {}",
+                plugin_command,
+                path.display()
+            );
+        }
+        // The context is the command's result, so -q doesn't hide it
+        None => println!("{}", json),
+    }
+
+    Ok(())
+}
+
+/// Turn `--arg name=value` pairs into the raw arguments `mis run` would have parsed
+fn parse_arg_pairs(args: &[String]) -> Result<HashMap<String, Vec<String>>> {
+    let mut parsed: HashMap<String, Vec<String>> = HashMap::new();
+    for pair in args {
+        let (name, value) = pair.split_once('=').ok_or_else(|| {
+            anyhow::anyhow!(
+                "🛑 Invalid --arg '{}'.\n\
+                 → Pass arguments as NAME=VALUE, e.g. --arg environment=staging",
+                pair
+            )
+        })?;
+        parsed
+            .entry(name.trim_start_matches("--").to_string())
+            .or_default()
+            .push(value.to_string());
+    }
+    Ok(parsed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_arg_pairs() {
+        let args = vec![
+            "environment=staging".to_string(),
+            "--tag=v1=rc".to_string(),
+            "empty=".to_string(),
+        ];
+        let parsed = parse_arg_pairs(&args).unwrap();
+        assert_eq!(parsed["environment"], vec!["staging"]);
+        assert_eq!(parsed["tag"], vec!["v1=rc"]);
+        assert_eq!(parsed["empty"], vec![""]);
+
+        let err = parse_arg_pairs(&["environment".to_string()]).unwrap_err();
+        assert!(err.to_string().contains("NAME=VALUE"), "{}", err);
+    }
+}
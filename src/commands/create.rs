@@ -1,15 +1,174 @@
 use std::fs;
+use std::path::{Path, PathBuf};
 
-use crate::utils::find_project_root;
+use anyhow::{Context, Result};
 
-// Template files that will be used for scaffolding plugins
-const PLUGIN_TEMPLATE: &str = include_str!("../../templates/mis-plugin-bootstrap.ts");
-const MANIFEST_TEMPLATE: &str = include_str!("../../templates/plugin-manifest.toml");
-const CONFIG_TEMPLATE: &str = include_str!("../../templates/config.toml");
+use crate::{
+    cli::Runtime, config::global_config_path, constants::PLUGIN_MANIFEST_FILE,
+    models::PluginManifest, utils::require_project_root, validation::did_you_mean,
+};
 
-pub fn create_plugin(name: &str) -> anyhow::Result<()> {
-    let root_dir =
-        find_project_root().ok_or_else(|| anyhow::anyhow!("Failed to find project root"))?;
+/// A template file: path relative to the plugin directory, and its contents.
+/// `{{name}}` in either is replaced with the plugin name.
+type TemplateFile = (String, String);
+
+/// A plugin template compiled into the binary
+struct BuiltinTemplate {
+    name: &'static str,
+    description: &'static str,
+    /// Files written whatever the runtime
+    files: &'static [(&'static str, &'static str)],
+    deno: &'static [(&'static str, &'static str)],
+    node: &'static [(&'static str, &'static str)],
+}
+
+const BUILTIN_TEMPLATES: &[BuiltinTemplate] = &[
+    BuiltinTemplate {
+        name: "example",
+        description: "A talking cow that shows off the plugin API",
+        files: &[
+            (
+                "manifest.toml",
+                include_str!("../../templates/plugin-manifest.toml"),
+            ),
+            ("config.toml", include_str!("../../templates/config.toml")),
+        ],
+        deno: &[(
+            "{{name}}.ts",
+            include_str!("../../templates/mis-plugin-bootstrap.ts"),
+        )],
+        node: &[],
+    },
+    BuiltinTemplate {
+        name: "minimal",
+        description: "One command, one argument, no permissions",
+        files: &[(
+            "manifest.toml",
+            include_str!("../../templates/plugins/minimal/manifest.toml"),
+        )],
+        deno: &[(
+            "{{name}}.ts",
+            include_str!("../../templates/plugins/minimal/deno/{{name}}.ts"),
+        )],
+        node: &[(
+            "{{name}}.ts",
+            include_str!("../../templates/plugins/minimal/node/{{name}}.ts"),
+        )],
+    },
+    BuiltinTemplate {
+        name: "deploy",
+        description: "Deploy the current commit to a configured environment",
+        files: &[
+            (
+                "manifest.toml",
+                include_str!("../../templates/plugins/deploy/manifest.toml"),
+            ),
+            (
+                "config.toml",
+                include_str!("../../templates/plugins/deploy/config.toml"),
+            ),
+        ],
+        deno: &[(
+            "{{name}}.ts",
+            include_str!("../../templates/plugins/deploy/deno/{{name}}.ts"),
+        )],
+        node: &[(
+            "{{name}}.ts",
+            include_str!("../../templates/plugins/deploy/node/{{name}}.ts"),
+        )],
+    },
+    BuiltinTemplate {
+        name: "docker",
+        description: "Build and push a Docker image",
+        files: &[
+            (
+                "manifest.toml",
+                include_str!("../../templates/plugins/docker/manifest.toml"),
+            ),
+            (
+                "config.toml",
+                include_str!("../../templates/plugins/docker/config.toml"),
+            ),
+        ],
+        deno: &[
+            (
+                "build.ts",
+                include_str!("../../templates/plugins/docker/deno/build.ts"),
+            ),
+            (
+                "push.ts",
+                include_str!("../../templates/plugins/docker/deno/push.ts"),
+            ),
+        ],
+        node: &[
+            (
+                "build.ts",
+                include_str!("../../templates/plugins/docker/node/build.ts"),
+            ),
+            (
+                "push.ts",
+                include_str!("../../templates/plugins/docker/node/push.ts"),
+            ),
+        ],
+    },
+    BuiltinTemplate {
+        name: "terraform",
+        description: "Plan and apply a Terraform configuration",
+        files: &[
+            (
+                "manifest.toml",
+                include_str!("../../templates/plugins/terraform/manifest.toml"),
+            ),
+            (
+                "config.toml",
+                include_str!("../../templates/plugins/terraform/config.toml"),
+            ),
+        ],
+        deno: &[
+            (
+                "plan.ts",
+                include_str!("../../templates/plugins/terraform/deno/plan.ts"),
+            ),
+            (
+                "apply.ts",
+                include_str!("../../templates/plugins/terraform/deno/apply.ts"),
+            ),
+        ],
+        node: &[
+            (
+                "plan.ts",
+                include_str!("../../templates/plugins/terraform/node/plan.ts"),
+            ),
+            (
+                "apply.ts",
+                include_str!("../../templates/plugins/terraform/node/apply.ts"),
+            ),
+        ],
+    },
+];
+
+/// Template used when `--template` isn't given
+fn default_template(runtime: Runtime) -> &'static str {
+    match runtime {
+        Runtime::Deno => "example",
+        Runtime::Node => "minimal",
+    }
+}
+
+fn runtime_name(runtime: Runtime) -> &'static str {
+    match runtime {
+        Runtime::Deno => "deno",
+        Runtime::Node => "node",
+    }
+}
+
+/// Where user-defined templates live: `~/.config/makeitso/templates/<name>/`
+pub fn user_templates_dir() -> Option<PathBuf> {
+    global_config_path().and_then(|path| path.parent().map(|dir| dir.join("templates")))
+}
+
+pub fn create_plugin(name: &str, template: Option<&str>, runtime: Runtime) -> Result<()> {
+    let root_dir = require_project_root()?;
 
     let makeitso_dir = root_dir.join(".makeitso");
 
@@ -25,35 +184,303 @@ pub fn create_plugin(name: &str) -> anyhow::Result<()> {
         anyhow::bail!("Plugin '{}' already exists", name);
     }
 
-    fs::create_dir_all(&plugin_dir)?;
+    let template = template.unwrap_or(default_template(runtime));
+    let files = render_template(
+        load_template(template, runtime, user_templates_dir().as_deref())?,
+        name,
+    );
 
-    // Write scaffold files using new split config structure
-    fs::write(plugin_dir.join(format!("{}.ts", name)), scaffold_ts(name))?;
-    fs::write(plugin_dir.join("manifest.toml"), scaffold_manifest(name))?;
-    fs::write(plugin_dir.join("config.toml"), scaffold_config())?;
+    for (path, content) in &files {
+        let target = plugin_dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&target, content)
+            .with_context(|| format!("Failed to write {}", target.display()))?;
+    }
 
-    println!(
-        "✅ Created plugin '{}' with new split config structure",
-        name
+    outln!(
+        "✅ Created plugin '{}' from the '{}' template ({})",
+        name,
+        template,
+        runtime_name(runtime)
     );
-    println!("   → manifest.toml: Plugin metadata and commands");
-    println!("   → config.toml: User-editable configuration");
-    println!("   → {}.ts: Plugin script", name);
+    for (path, _) in &files {
+        outln!("   → {}", path);
+    }
+    if runtime == Runtime::Node {
+        outln!("   Scripts use Node APIs and run on Deno's Node compatibility layer,");
+        outln!("   with the permissions declared in manifest.toml.");
+    }
+    match first_command(&files) {
+        Some(command) => outln!(
+            "→ Run `mis info {}:{}` to see how to use it.",
+            name,
+            command
+        ),
+        None => outln!("→ Add commands to manifest.toml, then run `mis info` to see them."),
+    }
 
     Ok(())
 }
 
-fn scaffold_ts(name: &str) -> String {
-    // Use the template file and replace "examples" placeholder with actual plugin name
-    PLUGIN_TEMPLATE.replace("examples", name)
+/// Find a template by name, preferring a user template over a built-in one,
+/// and return its files for `runtime` (not yet rendered)
+fn load_template(
+    template: &str,
+    runtime: Runtime,
+    user_dir: Option<&Path>,
+) -> Result<Vec<TemplateFile>> {
+    if let Some(dir) = user_dir
+        .map(|dir| dir.join(template))
+        .filter(|dir| dir.is_dir())
+    {
+        return read_user_template(template, &dir, runtime);
+    }
+
+    let Some(builtin) = BUILTIN_TEMPLATES.iter().find(|t| t.name == template) else {
+        let user_templates = user_template_names(user_dir);
+        let candidates = BUILTIN_TEMPLATES
+            .iter()
+            .map(|t| t.name)
+            .chain(user_templates.iter().map(String::as_str));
+
+        let mut message = format!(
+            "🛑 Unknown template '{}'{}\n→ Available templates:",
+            template,
+            did_you_mean(template, candidates)
+        );
+        for builtin in BUILTIN_TEMPLATES {
+            message.push_str(&format!(
+                "\n   • {} - {}",
+                builtin.name, builtin.description
+            ));
+        }
+        for name in &user_templates {
+            message.push_str(&format!("\n   • {} (yours)", name));
+        }
+        anyhow::bail!(message);
+    };
+
+    let scripts = match runtime {
+        Runtime::Deno => builtin.deno,
+        Runtime::Node => builtin.node,
+    };
+    if scripts.is_empty() {
+        anyhow::bail!(
+            "🛑 The '{}' template has no {} version.\n→ Use `--runtime deno`, or pick another template such as `--template minimal`.",
+            template,
+            runtime_name(runtime)
+        );
+    }
+
+    Ok(builtin
+        .files
+        .iter()
+        .chain(scripts)
+        .map(|(path, content)| (path.to_string(), content.to_string()))
+        .collect())
 }
 
-fn scaffold_manifest(name: &str) -> String {
-    // Use the template file and replace "examples" placeholder with actual plugin name
-    MANIFEST_TEMPLATE.replace("examples", name)
+/// Read a user template: top-level files (manifest.toml, config.toml, ...) plus
+/// everything under the `deno/` or `node/` directory for the chosen runtime
+fn read_user_template(template: &str, dir: &Path, runtime: Runtime) -> Result<Vec<TemplateFile>> {
+    if !dir.join(PLUGIN_MANIFEST_FILE).is_file() {
+        anyhow::bail!(
+            "🛑 Template '{}' has no {}\n→ Add one at {}",
+            template,
+            PLUGIN_MANIFEST_FILE,
+            dir.join(PLUGIN_MANIFEST_FILE).display()
+        );
+    }
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Failed to read template directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file() {
+            files.push((
+                path.file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .to_string(),
+                fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?,
+            ));
+        }
+    }
+
+    let scripts_dir = dir.join(runtime_name(runtime));
+    let scripts_start = files.len();
+    if scripts_dir.is_dir() {
+        collect_files(&scripts_dir, &scripts_dir, &mut files)?;
+    }
+    if files.len() == scripts_start {
+        anyhow::bail!(
+            "🛑 Template '{}' has no {} scripts\n→ Add them under {}, or pick another `--runtime`.",
+            template,
+            runtime_name(runtime),
+            scripts_dir.display()
+        );
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<TemplateFile>) -> Result<()> {
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Failed to read template directory {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            let relative = path.strip_prefix(root).unwrap_or(&path);
+            files.push((
+                relative.to_string_lossy().replace('\\', "/"),
+                fs::read_to_string(&path)
+                    .with_context(|| format!("Failed to read {}", path.display()))?,
+            ));
+        }
+    }
+    Ok(())
 }
 
-fn scaffold_config() -> String {
-    // Use the config template as-is (it's already generic)
-    CONFIG_TEMPLATE.to_string()
+fn render_template(files: Vec<TemplateFile>, name: &str) -> Vec<TemplateFile> {
+    files
+        .into_iter()
+        .map(|(path, content)| {
+            (
+                path.replace("{{name}}", name),
+                content.replace("{{name}}", name),
+            )
+        })
+        .collect()
+}
+
+/// Alphabetically first command in the generated manifest, for the "try it" hint
+fn first_command(files: &[TemplateFile]) -> Option<String> {
+    let (_, manifest) = files
+        .iter()
+        .find(|(path, _)| path == PLUGIN_MANIFEST_FILE)?;
+    let manifest: PluginManifest = toml::from_str(manifest).ok()?;
+    manifest.commands.into_keys().min()
+}
+
+/// Templates the user has added under `~/.config/makeitso/templates`, sorted
+fn user_template_names(user_dir: Option<&Path>) -> Vec<String> {
+    let mut names: Vec<String> = user_dir
+        .and_then(|dir| fs::read_dir(dir).ok())
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().is_dir())
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_builtin_templates_render_runnable_plugins() {
+        for template in BUILTIN_TEMPLATES {
+            for runtime in [Runtime::Deno, Runtime::Node] {
+                let Ok(files) = load_template(template.name, runtime, None) else {
+                    continue;
+                };
+                let files = render_template(files, "acme");
+
+                let manifest = &files
+                    .iter()
+                    .find(|(path, _)| path == "manifest.toml")
+                    .unwrap()
+                    .1;
+                let manifest: PluginManifest = toml::from_str(manifest)
+                    .unwrap_or_else(|e| panic!("{} manifest: {}", template.name, e));
+                assert_eq!(manifest.plugin.name, "acme");
+
+                for (command, def) in &manifest.commands {
+                    let script = def.script.trim_start_matches("./");
+                    assert!(
+                        files.iter().any(|(path, _)| path == script),
+                        "{} ({:?}): {} runs missing {}",
+                        template.name,
+                        runtime,
+                        command,
+                        script
+                    );
+                }
+                assert!(files.iter().all(|(path, content)| {
+                    !path.contains("{{") && !content.contains("{{name}}")
+                }));
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_builtin_template_but_example_supports_node() {
+        for template in BUILTIN_TEMPLATES {
+            assert!(!template.deno.is_empty(), "{}", template.name);
+            assert_eq!(template.node.is_empty(), template.name == "example");
+        }
+
+        let err = load_template("example", Runtime::Node, None).unwrap_err();
+        assert!(err.to_string().contains("has no node version"));
+    }
+
+    #[test]
+    fn test_unknown_template_suggests_close_match() {
+        let err = load_template("dokcer", Runtime::Deno, None)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Did you mean 'docker'?"), "{}", err);
+        assert!(err.contains("• terraform - Plan and apply"), "{}", err);
+    }
+
+    #[test]
+    fn test_user_templates() {
+        let temp_dir = tempdir().unwrap();
+        let user_dir = temp_dir.path();
+
+        // A user template with the same name as a built-in one takes precedence
+        let docker = user_dir.join("docker");
+        fs::create_dir_all(docker.join("deno").join("lib")).unwrap();
+        fs::write(
+            docker.join("manifest.toml"),
+            "[plugin]\nname = \"{{name}}\"\n",
+        )
+        .unwrap();
+        fs::write(docker.join("README.md"), "# {{name}}\n").unwrap();
+        fs::write(docker.join("deno").join("{{name}}.ts"), "// {{name}}\n").unwrap();
+        fs::write(docker.join("deno").join("lib").join("util.ts"), "").unwrap();
+
+        let files = render_template(
+            load_template("docker", Runtime::Deno, Some(user_dir)).unwrap(),
+            "ship",
+        );
+        let paths: Vec<&str> = files.iter().map(|(path, _)| path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["README.md", "lib/util.ts", "manifest.toml", "ship.ts"]
+        );
+        assert_eq!(files[0].1, "# ship\n");
+
+        let err = load_template("docker", Runtime::Node, Some(user_dir)).unwrap_err();
+        assert!(err.to_string().contains("has no node scripts"));
+
+        fs::create_dir_all(user_dir.join("empty")).unwrap();
+        let err = load_template("empty", Runtime::Deno, Some(user_dir)).unwrap_err();
+        assert!(err.to_string().contains("has no manifest.toml"));
+
+        // Built-in templates are still available alongside user ones
+        assert!(load_template("minimal", Runtime::Node, Some(user_dir)).is_ok());
+        assert_eq!(user_template_names(Some(user_dir)), vec!["docker", "empty"]);
+    }
 }
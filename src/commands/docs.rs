@@ -0,0 +1,638 @@
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use clap::{Arg, ArgAction, CommandFactory, builder::PossibleValuesParser};
+use clap_mangen::Man;
+use indexmap::IndexMap;
+
+use crate::{
+    cli::{BUILTIN_SUBCOMMANDS, Cli, DocFormat},
+    commands::help::{format_arg_type, format_config_type, installed_plugins},
+    config::load_mis_config_from,
+    models::{ArgDefinition, PluginCommand, PluginManifest, SecurityPermissions, flag_name},
+    utils::{find_project_root, require_project_root},
+};
+
+/// One piece of a docs page, rendered as Markdown or HTML. Text may use `backticks` for code.
+#[derive(Debug, PartialEq)]
+enum Block {
+    Heading(u8, String),
+    Paragraph(String),
+    /// A callout, e.g. a deprecation notice
+    Note(String),
+    Code(String),
+    List(Vec<String>),
+    Table(&'static [&'static str], Vec<Vec<String>>),
+}
+
+/// `mis info --all`: document every enabled plugin, its commands, arguments, permissions,
+/// configuration and dependencies as one page to commit alongside the project
+pub fn run_docs_export(format: DocFormat) -> Result<()> {
+    let root = require_project_root()?;
+    let plugins = installed_plugins(&root)?;
+    let project = load_mis_config_from(&root)
+        .ok()
+        .and_then(|(config, _, _)| config.name);
+
+    let title = match &project {
+        Some(name) => format!("{} plugins", name),
+        None => "Plugins".to_string(),
+    };
+    let blocks = plugin_docs(&title, &plugins);
+    // The page is data, like JSON output: printed as is, without theming or --quiet
+    match format {
+        DocFormat::Markdown => print!("{}", render_markdown(&blocks)),
+        DocFormat::Html => print!("{}", render_html(&title, &blocks)),
+    }
+    Ok(())
+}
+
+/// `mis docs install-man`: write man pages for mis and each of its subcommands
+/// (`man mis-run`), plus, inside a project, each enabled plugin (`man mis-deploy`) and
+/// plugin command (`man mis-deploy-prod`)
+pub fn run_install_man(dir: Option<&Path>) -> Result<()> {
+    let dir = match dir {
+        Some(dir) => dir.to_path_buf(),
+        None => default_man_dir()?,
+    };
+    fs::create_dir_all(&dir).with_context(|| format!("🛑 Failed to create {}", dir.display()))?;
+
+    let written = write_man_pages(&dir, find_project_root().as_deref())?;
+    outln!(
+        "📖 Installed {} man page(s) in {}",
+        written.len(),
+        dir.display()
+    );
+    outln!("→ Try `man mis-run`.");
+    if let Some(man_dir) = dir.parent() {
+        outln!(
+            "→ If man can't find the pages, add {} to MANPATH.",
+            man_dir.display()
+        );
+    }
+    Ok(())
+}
+
+/// `$XDG_DATA_HOME/man/man1`, or `~/.local/share/man/man1`
+fn default_man_dir() -> Result<PathBuf> {
+    let data_dir = std::env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("share"))
+        })
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "🛑 Couldn't find your home directory.\n\
+                 → Pass --dir to choose where the man pages go."
+            )
+        })?;
+    Ok(data_dir.join("man").join("man1"))
+}
+
+/// Render every page into `dir`, returning the files written
+pub fn write_man_pages(dir: &Path, project_root: Option<&Path>) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    write_man_page_tree(
+        Cli::command().name("mis").disable_help_subcommand(true),
+        dir,
+        &mut written,
+    )?;
+    if let Some(root) = project_root {
+        for (name, manifest) in installed_plugins(root)? {
+            write_man_page_tree(plugin_man_command(&name, &manifest), dir, &mut written)?;
+        }
+    }
+    Ok(written)
+}
+
+/// A page for `cmd` and one for each of its subcommands, named like `mis-permissions-simulate`
+fn write_man_page_tree(
+    mut cmd: clap::Command,
+    dir: &Path,
+    written: &mut Vec<PathBuf>,
+) -> Result<()> {
+    cmd.build();
+    fn write(cmd: &clap::Command, dir: &Path, written: &mut Vec<PathBuf>) -> Result<()> {
+        for subcommand in cmd.get_subcommands().filter(|s| !s.is_hide_set()) {
+            write(subcommand, dir, written)?;
+        }
+        let page = Man::new(cmd.clone());
+        let path = page.generate_to(dir).with_context(|| {
+            format!(
+                "🛑 Failed to write {}",
+                dir.join(page.get_filename()).display()
+            )
+        })?;
+        written.push(path);
+        Ok(())
+    }
+    write(&cmd, dir, written)
+}
+
+/// A plugin as a clap command, so clap_mangen can render it like a built-in: its commands
+/// become subcommands and their arguments options
+fn plugin_man_command(name: &str, manifest: &PluginManifest) -> clap::Command {
+    // A plugin named like a built-in would overwrite that built-in's page
+    let page_name = if BUILTIN_SUBCOMMANDS.contains(&name) {
+        format!("mis-plugin-{}", name)
+    } else {
+        format!("mis-{}", name)
+    };
+    let about = manifest
+        .plugin
+        .description
+        .clone()
+        .unwrap_or_else(|| format!("The {} plugin", name));
+
+    // The synopsis shows the bin name, so it reads as the `mis run` line that runs it
+    let mut cmd = clap::Command::new(page_name)
+        .bin_name(format!("mis run {}:<command>", name))
+        .about(about)
+        .version(manifest.plugin.version.clone())
+        .disable_help_flag(true)
+        .disable_version_flag(true)
+        .disable_help_subcommand(true);
+
+    for (command_name, command) in &manifest.commands {
+        cmd = cmd.subcommand(
+            command_man_command(name, command_name, command)
+                .version(manifest.plugin.version.clone()),
+        );
+    }
+    cmd
+}
+
+fn command_man_command(plugin: &str, name: &str, command: &PluginCommand) -> clap::Command {
+    let mut long_about = Vec::new();
+    if let Some(message) = &command.deprecated {
+        long_about.push(format!("Deprecated: {}", message));
+    }
+    long_about.extend(command.description.clone());
+    long_about.extend(command.instructions.clone());
+
+    let mut cmd = clap::Command::new(name.to_string())
+        .bin_name(format!("mis run {}:{}", plugin, name))
+        .about(
+            command
+                .description
+                .clone()
+                .unwrap_or_else(|| format!("Run {}:{}", plugin, name)),
+        )
+        .long_about(long_about.join("\n\n"))
+        .disable_help_flag(true)
+        .disable_version_flag(true);
+
+    if let Some(args) = &command.args {
+        let required = args.required.iter().map(|arg| (arg, true));
+        let optional = args.optional.iter().map(|arg| (arg, false));
+
+        // clap refuses duplicate flags, which `mis validate` reports; document the first
+        let mut taken = HashSet::new();
+        for ((arg, def), is_required) in required.chain(optional) {
+            if !taken.insert(arg.clone()) {
+                continue;
+            }
+            cmd = cmd.arg(man_arg(arg, def, is_required, &mut taken));
+        }
+    }
+    cmd
+}
+
+fn man_arg(name: &str, def: &ArgDefinition, required: bool, taken: &mut HashSet<String>) -> Arg {
+    let mut arg = Arg::new(name.to_string())
+        .long(name.to_string())
+        .value_name(format_arg_type(&def.arg_type).to_uppercase())
+        .help(def.description.clone())
+        .required(required);
+    for alias in &def.aliases {
+        if !taken.insert(alias.clone()) {
+            continue;
+        }
+        let mut chars = alias.chars();
+        arg = match (chars.next(), chars.next()) {
+            (Some(short), None) => arg.visible_short_alias(short),
+            _ => arg.visible_alias(alias.clone()),
+        };
+    }
+    if !required && let Some(default) = &def.default_value {
+        arg = arg.default_value(default.clone());
+    }
+    if !def.allowed_values.is_empty() {
+        arg = arg.value_parser(PossibleValuesParser::new(def.allowed_values.clone()));
+    }
+    if def.multiple {
+        arg = arg.action(ArgAction::Append);
+    }
+    arg
+}
+
+fn plugin_docs(title: &str, plugins: &[(String, PluginManifest)]) -> Vec<Block> {
+    let mut blocks = vec![
+        Block::Heading(1, title.to_string()),
+        Block::Paragraph(
+            "Generated by `mis info --all` from each plugin's manifest.toml; regenerate it after adding or updating plugins.".to_string(),
+        ),
+    ];
+    if plugins.is_empty() {
+        blocks.push(Block::Paragraph("No plugins are installed.".to_string()));
+    }
+
+    for (name, manifest) in plugins {
+        blocks.push(Block::Heading(2, name.clone()));
+        if let Some(description) = &manifest.plugin.description {
+            blocks.push(Block::Paragraph(description.clone()));
+        }
+        let mut about = vec![format!("Version: `{}`", manifest.plugin.version)];
+        if let Some(registry) = &manifest.plugin.registry {
+            about.push(format!("Registry: `{}`", registry));
+        }
+        if let Some(requirement) = &manifest.mis_version_requirement {
+            about.push(format!("Needs mis `{}`", requirement));
+        }
+        blocks.push(Block::List(about));
+
+        blocks.push(Block::Heading(3, "Commands".to_string()));
+        if manifest.commands.is_empty() {
+            blocks.push(Block::Paragraph("No commands defined.".to_string()));
+        }
+        for (command_name, command) in &manifest.commands {
+            command_docs(&mut blocks, name, command_name, command);
+        }
+
+        blocks.push(Block::Heading(3, "Permissions".to_string()));
+        match manifest.permissions.as_ref().map(permission_items) {
+            Some(items) if !items.is_empty() => {
+                blocks.push(Block::Paragraph(format!(
+                    "On top of mis's defaults (`mis audit {}` shows the full set):",
+                    name
+                )));
+                blocks.push(Block::List(items));
+            }
+            _ => blocks.push(Block::Paragraph(format!(
+                "Only mis's defaults; `mis audit {}` shows them.",
+                name
+            ))),
+        }
+
+        if !manifest.config_schema.is_empty() {
+            blocks.push(Block::Heading(3, "Configuration (config.toml)".to_string()));
+            let rows = manifest
+                .config_schema
+                .iter()
+                .map(|(key, def)| {
+                    vec![
+                        format!("`{}`", key),
+                        def.value_type
+                            .as_ref()
+                            .map(|t| format_config_type(t).to_string())
+                            .unwrap_or_default(),
+                        yes_no(def.required),
+                        def.default
+                            .as_ref()
+                            .map(|d| format!("`{}`", d))
+                            .unwrap_or_default(),
+                        def.description.clone().unwrap_or_default(),
+                    ]
+                })
+                .collect();
+            blocks.push(Block::Table(
+                &["Key", "Type", "Required", "Default", "Description"],
+                rows,
+            ));
+        }
+
+        if !manifest.deno_dependencies.is_empty() {
+            blocks.push(Block::Heading(3, "Dependencies".to_string()));
+            blocks.push(Block::Table(
+                &["Name", "Source"],
+                code_rows(&manifest.deno_dependencies),
+            ));
+        }
+    }
+    blocks
+}
+
+fn command_docs(blocks: &mut Vec<Block>, plugin: &str, name: &str, command: &PluginCommand) {
+    blocks.push(Block::Heading(4, format!("`{}:{}`", plugin, name)));
+    if let Some(message) = &command.deprecated {
+        blocks.push(Block::Note(format!("Deprecated: {}", message)));
+    }
+    if let Some(description) = &command.description {
+        blocks.push(Block::Paragraph(description.clone()));
+    }
+    if let Some(instructions) = &command.instructions {
+        blocks.push(Block::Paragraph(instructions.clone()));
+    }
+
+    let mut usage = format!("mis run {}:{}", plugin, name);
+    let mut rows = Vec::new();
+    if let Some(args) = &command.args {
+        for (arg, def) in &args.required {
+            usage.push_str(&format!(" --{} {}", arg, def.value_placeholder()));
+            rows.push(arg_row(arg, def, true));
+        }
+        for (arg, def) in &args.optional {
+            usage.push_str(&format!(" [--{} {}]", arg, def.value_placeholder()));
+            rows.push(arg_row(arg, def, false));
+        }
+    }
+    blocks.push(Block::Code(usage));
+    if !rows.is_empty() {
+        blocks.push(Block::Table(
+            &["Argument", "Type", "Required", "Default", "Description"],
+            rows,
+        ));
+    }
+
+    if let Some(items) = command.permissions.as_ref().map(permission_items)
+        && !items.is_empty()
+    {
+        blocks.push(Block::Paragraph(
+            "Extra permissions for this command:".to_string(),
+        ));
+        blocks.push(Block::List(items));
+    }
+    if command.confirm.is_some() {
+        blocks.push(Block::Paragraph(
+            "Asks for confirmation before running; pass `--yes` to skip the question.".to_string(),
+        ));
+    }
+}
+
+fn arg_row(name: &str, def: &ArgDefinition, required: bool) -> Vec<String> {
+    let flags: Vec<String> = std::iter::once(format!("--{}", name))
+        .chain(def.aliases.iter().map(|alias| flag_name(alias)))
+        .map(|flag| format!("`{}`", flag))
+        .collect();
+    let mut description = def.description.clone();
+    if !def.allowed_values.is_empty() {
+        let values: Vec<String> = def
+            .allowed_values
+            .iter()
+            .map(|value| format!("`{}`", value))
+            .collect();
+        description.push_str(&format!(" One of: {}.", values.join(", ")));
+    }
+    vec![
+        flags.join(", "),
+        format_arg_type(&def.arg_type).to_string(),
+        yes_no(required),
+        def.default_value
+            .as_ref()
+            .map(|d| format!("`{}`", d))
+            .unwrap_or_default(),
+        description.trim().to_string(),
+    ]
+}
+
+fn permission_items(permissions: &SecurityPermissions) -> Vec<String> {
+    let code = |values: &[String]| {
+        values
+            .iter()
+            .map(|value| format!("`{}`", value))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    let mut items = Vec::new();
+    for (label, values) in [
+        ("Read", &permissions.file_read),
+        ("Write", &permissions.file_write),
+        ("Network", &permissions.network),
+        ("Run", &permissions.run_commands),
+        ("Environment variables", &permissions.env_vars),
+    ] {
+        if !values.is_empty() {
+            items.push(format!("{}: {}", label, code(values)));
+        }
+    }
+    match permissions.env_access {
+        Some(true) if permissions.env_vars.is_empty() => {
+            items.push("Environment variables: all".to_string())
+        }
+        Some(false) => items.push("Environment variables: none".to_string()),
+        _ => {}
+    }
+    items
+}
+
+fn code_rows(map: &IndexMap<String, String>) -> Vec<Vec<String>> {
+    map.iter()
+        .map(|(key, value)| vec![format!("`{}`", key), format!("`{}`", value)])
+        .collect()
+}
+
+fn yes_no(value: bool) -> String {
+    if value { "yes" } else { "no" }.to_string()
+}
+
+fn render_markdown(blocks: &[Block]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|").replace('\n', "<br>");
+    let mut out = String::new();
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("{} {}\n", "#".repeat(*level as usize), text))
+            }
+            Block::Paragraph(text) => out.push_str(&format!("{}\n", text)),
+            Block::Note(text) => out.push_str(&format!("> **{}**\n", text)),
+            Block::Code(text) => out.push_str(&format!("```sh\n{}\n```\n", text)),
+            Block::List(items) => {
+                for item in items {
+                    out.push_str(&format!("- {}\n", item));
+                }
+            }
+            Block::Table(headers, rows) => {
+                out.push_str(&format!("| {} |\n", headers.join(" | ")));
+                out.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+                for row in rows {
+                    let cells: Vec<String> = row.iter().map(|c| cell(c)).collect();
+                    out.push_str(&format!("| {} |\n", cells.join(" | ")));
+                }
+            }
+        }
+        out.push('\n');
+    }
+    out.truncate(out.trim_end().len());
+    out.push('\n');
+    out
+}
+
+fn render_html(title: &str, blocks: &[Block]) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n",
+        escape_html(title)
+    );
+    for block in blocks {
+        match block {
+            Block::Heading(level, text) => {
+                out.push_str(&format!("<h{0}>{1}</h{0}>\n", level, inline_html(text)))
+            }
+            Block::Paragraph(text) => out.push_str(&format!("<p>{}</p>\n", inline_html(text))),
+            Block::Note(text) => out.push_str(&format!(
+                "<blockquote><strong>{}</strong></blockquote>\n",
+                inline_html(text)
+            )),
+            Block::Code(text) => {
+                out.push_str(&format!("<pre><code>{}</code></pre>\n", escape_html(text)))
+            }
+            Block::List(items) => {
+                out.push_str("<ul>\n");
+                for item in items {
+                    out.push_str(&format!("<li>{}</li>\n", inline_html(item)));
+                }
+                out.push_str("</ul>\n");
+            }
+            Block::Table(headers, rows) => {
+                out.push_str("<table>\n<tr>");
+                for header in *headers {
+                    out.push_str(&format!("<th>{}</th>", escape_html(header)));
+                }
+                out.push_str("</tr>\n");
+                for row in rows {
+                    out.push_str("<tr>");
+                    for cell in row {
+                        out.push_str(&format!("<td>{}</td>", inline_html(cell)));
+                    }
+                    out.push_str("</tr>\n");
+                }
+                out.push_str("</table>\n");
+            }
+        }
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// Escape text for HTML, turning `backticked` spans into <code>
+fn inline_html(text: &str) -> String {
+    text.split('`')
+        .enumerate()
+        .map(|(i, part)| {
+            if i % 2 == 1 {
+                format!("<code>{}</code>", escape_html(part))
+            } else {
+                escape_html(part)
+            }
+        })
+        .collect()
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest() -> PluginManifest {
+        toml::from_str(
+            r#"
+[plugin]
+name = "deploy"
+description = "Ships the app"
+version = "1.2.0"
+
+[commands.prod]
+script = "prod.ts"
+description = "Deploy to production"
+deprecated = "Use deploy:v2 instead"
+
+[commands.prod.args.required]
+env = { description = "Target | environment", arg_type = "enum", allowed_values = ["staging", "prod"], aliases = ["e"] }
+
+[commands.prod.args.optional]
+verbose = { description = "Log more", arg_type = "boolean", default_value = "false" }
+
+[permissions]
+network = ["api.example.com"]
+
+[deno_dependencies]
+zod = "npm:zod@3"
+"#,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_render_markdown() {
+        let docs = render_markdown(&plugin_docs(
+            "shop plugins",
+            &[("deploy".to_string(), manifest())],
+        ));
+        assert!(docs.starts_with("# shop plugins\n"), "{}", docs);
+        for expected in [
+            "## deploy\n\nShips the app\n\n- Version: `1.2.0`\n",
+            "#### `deploy:prod`\n\n> **Deprecated: Use deploy:v2 instead**\n",
+            "```sh\nmis run deploy:prod --env <staging|prod> [--verbose <value>]\n```\n",
+            "| `--env`, `-e` | enum | yes |  | Target \\| environment One of: `staging`, `prod`. |\n",
+            "| `--verbose` | boolean | no | `false` | Log more |\n",
+            "- Network: `api.example.com`\n",
+            "| `zod` | `npm:zod@3` |\n",
+        ] {
+            assert!(
+                docs.contains(expected),
+                "missing {:?} in:\n{}",
+                expected,
+                docs
+            );
+        }
+        assert!(docs.ends_with("|\n"));
+    }
+
+    #[test]
+    fn test_write_man_pages() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let plugin_dir = temp_dir.path().join(".makeitso/plugins/deploy");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(
+            plugin_dir.join("manifest.toml"),
+            toml::to_string(&manifest()).unwrap(),
+        )
+        .unwrap();
+        let man_dir = temp_dir.path().join("man1");
+        fs::create_dir_all(&man_dir).unwrap();
+
+        let written = write_man_pages(&man_dir, Some(temp_dir.path())).unwrap();
+        for page in [
+            "mis.1",
+            "mis-run.1",
+            "mis-permissions-simulate.1",
+            "mis-deploy.1",
+        ] {
+            assert!(
+                written.contains(&man_dir.join(page)),
+                "{} in {:?}",
+                page,
+                written
+            );
+        }
+
+        let page = fs::read_to_string(man_dir.join("mis-deploy-prod.1")).unwrap();
+        assert!(
+            page.contains("Deprecated: Use deploy:v2 instead"),
+            "{}",
+            page
+        );
+        assert!(
+            page.contains("mis run deploy:prod\\fR <\\fB\\-\\-env"),
+            "{}",
+            page
+        );
+        assert!(page.contains("staging"), "{}", page);
+    }
+
+    #[test]
+    fn test_render_html_escapes_text() {
+        let html = render_html("a <b> page", &plugin_docs("a <b> page", &[]));
+        assert!(html.contains("<title>a &lt;b&gt; page</title>"), "{}", html);
+        assert!(html.contains("<p>Generated by <code>mis info --all</code> from"));
+        assert!(html.ends_with("</body>\n</html>\n"));
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use anyhow::Result;
+use toml_edit::{Array, Item, value};
+
+use crate::{
+    config::{edit_toml_file, load_mis_config_from},
+    plugin_utils::get_all_plugin_names,
+    utils::require_project_root,
+    validation::did_you_mean,
+};
+
+/// `mis disable <plugin>`: hide an installed plugin from `mis run` and `mis info`, keeping
+/// its files and config
+pub fn run_disable(plugin_name: &str) -> Result<()> {
+    let project_root = require_project_root()?;
+    let plugin_path = project_root
+        .join(".makeitso")
+        .join("plugins")
+        .join(plugin_name);
+    if !plugin_path.is_dir() {
+        let installed = get_all_plugin_names().unwrap_or_default();
+        anyhow::bail!(
+            "🛑 Plugin '{}' not found in .makeitso/plugins.{}\n\
+             → Run `mis list` to see the installed plugins.",
+            plugin_name,
+            did_you_mean(plugin_name, installed.iter().map(String::as_str))
+        );
+    }
+
+    if set_plugin_disabled(&project_root, plugin_name, true)? {
+        outln!(
+            "💤 Disabled plugin '{}'. Its files and config are kept.",
+            plugin_name
+        );
+        outln!("→ Run `mis enable {}` to use it again.", plugin_name);
+    } else {
+        outln!("ℹ️  Plugin '{}' is already disabled.", plugin_name);
+    }
+    Ok(())
+}
+
+/// `mis enable <plugin>`: undo `mis disable`
+pub fn run_enable(plugin_name: &str) -> Result<()> {
+    let project_root = require_project_root()?;
+    if set_plugin_disabled(&project_root, plugin_name, false)? {
+        outln!("✅ Enabled plugin '{}'.", plugin_name);
+    } else {
+        outln!("ℹ️  Plugin '{}' isn't disabled.", plugin_name);
+    }
+    Ok(())
+}
+
+/// Add the plugin to, or remove it from, `disabled_plugins` in mis.toml; false if it was
+/// already in the requested state
+pub fn set_plugin_disabled(project_root: &Path, plugin_name: &str, disabled: bool) -> Result<bool> {
+    let (config, config_path, _) = load_mis_config_from(project_root)?;
+    if config.disabled_plugins.iter().any(|p| p == plugin_name) == disabled {
+        return Ok(false);
+    }
+
+    edit_toml_file(&config_path, |doc| {
+        if disabled {
+            if let Some(list) = doc
+                .entry("disabled_plugins")
+                .or_insert(value(Array::new()))
+                .as_array_mut()
+            {
+                list.push(plugin_name);
+            }
+            return;
+        }
+
+        let Some(list) = doc.get_mut("disabled_plugins").and_then(Item::as_array_mut) else {
+            return;
+        };
+        list.retain(|p| p.as_str() != Some(plugin_name));
+        if list.is_empty() {
+            doc.remove("disabled_plugins");
+        }
+    })?;
+    Ok(true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_set_plugin_disabled_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(".makeitso")).unwrap();
+        let config_path = root.join(".makeitso/mis.toml");
+        let original = "name = \"shop\"   # the storefront\n\n[project_variables]\nfoo = \"bar\"\n";
+        fs::write(&config_path, original).unwrap();
+
+        assert!(set_plugin_disabled(root, "k8s", true).unwrap());
+        assert!(!set_plugin_disabled(root, "k8s", true).unwrap());
+        assert!(set_plugin_disabled(root, "lint", true).unwrap());
+        let (config, _, _) = load_mis_config_from(root).unwrap();
+        assert_eq!(config.disabled_plugins, vec!["k8s", "lint"]);
+        assert!(
+            fs::read_to_string(&config_path)
+                .unwrap()
+                .starts_with("name = \"shop\"   # the storefront\n")
+        );
+
+        assert!(set_plugin_disabled(root, "k8s", false).unwrap());
+        assert!(!set_plugin_disabled(root, "k8s", false).unwrap());
+        assert!(set_plugin_disabled(root, "lint", false).unwrap());
+        assert_eq!(fs::read_to_string(&config_path).unwrap(), original);
+    }
+}
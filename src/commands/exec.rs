@@ -0,0 +1,215 @@
+use std::path::{Path, PathBuf};
+use std::time::Instant;
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+
+use crate::{
+    cli::parse_cli_args,
+    commands::run::{
+        RunOptions, build_execution_context, ensure_deno_installed, execute_plugin,
+        report_run_result,
+    },
+    config::{apply_env_file_variables, apply_env_profile, load_mis_config},
+    integrations::artifacts::prepare_artifacts_dir,
+    models::{PluginCommand, PluginManifest, PluginMeta, PluginUserConfig, SecurityPermissions},
+    run_log::{RunLogEntry, append_run_log},
+    utils::require_project_root,
+};
+
+/// The plugin name ad-hoc scripts run under, in `ctx.meta` and the run log
+const EXEC_PLUGIN_NAME: &str = "exec";
+
+/// `mis exec <script>`: run a one-off script the way a plugin command runs, with the safe
+/// default permissions plus `grants`, and the project's context
+pub fn run_exec(
+    script: &Path,
+    grants: SecurityPermissions,
+    args: &[String],
+    dry_run: bool,
+    options: RunOptions,
+) -> Result<()> {
+    ensure_deno_installed()?;
+    let project_root = require_project_root()?;
+
+    let script = script.canonicalize().with_context(|| {
+        format!(
+            "🛑 Script not found: {}\n→ Pass the path to a TypeScript or JavaScript file.",
+            script.display()
+        )
+    })?;
+    let (dir, file_name, command_name) = split_script_path(&script)?;
+    let manifest = exec_manifest(&file_name, &command_name, grants);
+
+    // Nothing declares the script's arguments, so they're passed through as strings, and
+    // repeated ones as arrays of strings
+    let mut plugin_args: serde_json::Map<String, serde_json::Value> = parse_cli_args(args)
+        .into_iter()
+        .map(|(name, mut values)| {
+            let value = if values.len() == 1 {
+                serde_json::Value::String(values.remove(0))
+            } else {
+                serde_json::Value::from(values)
+            };
+            (name, value)
+        })
+        .collect();
+
+    let mut run_log = RunLogEntry::start(
+        EXEC_PLUGIN_NAME,
+        &command_name,
+        &plugin_args,
+        dry_run,
+        options.environment,
+    );
+    run_log.approval = options.approval.map(str::to_string);
+
+    if dry_run {
+        plugin_args.insert("dry_run".to_string(), serde_json::Value::Bool(true));
+    }
+
+    let (mut mis_config, _, _) = load_mis_config()?;
+    apply_env_profile(&mut mis_config, options.environment)?;
+    apply_env_file_variables(&mut mis_config, &project_root, options.environment)?;
+
+    let ctx = build_execution_context(
+        &manifest,
+        PluginUserConfig::default(),
+        mis_config.project_variables,
+        &command_name,
+        plugin_args,
+        dry_run,
+        &options,
+    )?;
+    prepare_artifacts_dir(&project_root, Path::new(&ctx.artifacts_dir))?;
+
+    let started = Instant::now();
+    let run_result = execute_plugin(
+        &dir,
+        &file_name,
+        &ctx,
+        &manifest,
+        &command_name,
+        &options,
+        &mut run_log,
+    );
+    run_log.finish(started.elapsed(), &run_result);
+    if let Err(e) = append_run_log(&project_root, &run_log) {
+        errln!("⚠️  Warning: Failed to write run log: {:#}", e);
+    }
+    let run_result = run_result?;
+
+    report_run_result(
+        EXEC_PLUGIN_NAME,
+        &command_name,
+        run_result.as_ref(),
+        &[],
+        &[],
+        &run_log.violations,
+        &options,
+    )
+}
+
+/// The script's directory, file name, and the command name it runs as (its file stem)
+fn split_script_path(script: &Path) -> Result<(PathBuf, String, String)> {
+    let (Some(dir), Some(file_name), Some(stem)) = (
+        script.parent(),
+        script.file_name().and_then(|name| name.to_str()),
+        script.file_stem().and_then(|stem| stem.to_str()),
+    ) else {
+        anyhow::bail!("🛑 {} isn't a script file.", script.display());
+    };
+    Ok((dir.to_path_buf(), file_name.to_string(), stem.to_string()))
+}
+
+/// A stand-in manifest with a single command that runs the script, so it goes through
+/// the same permission and context machinery as a plugin
+fn exec_manifest(
+    file_name: &str,
+    command_name: &str,
+    grants: SecurityPermissions,
+) -> PluginManifest {
+    let command = PluginCommand {
+        script: file_name.to_string(),
+        description: None,
+        instructions: None,
+        args: None,
+        permissions: None,
+        hooks: None,
+        test: None,
+        exclusive: false,
+        confirm: None,
+        deprecated: None,
+    };
+
+    PluginManifest {
+        plugin: PluginMeta {
+            name: EXEC_PLUGIN_NAME.to_string(),
+            description: Some(format!("Ad-hoc script {}", file_name)),
+            version: "0.0.0".to_string(),
+            registry: None,
+            pin: None,
+            execution: None,
+        },
+        commands: IndexMap::from([(command_name.to_string(), command)]),
+        deno_dependencies: IndexMap::new(),
+        permissions: Some(grants),
+        config_schema: IndexMap::new(),
+        mis_version_requirement: None,
+        requires: None,
+        container: None,
+        context: None,
+        context_transport: None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::security::build_plugin_permissions;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_split_script_path() {
+        let (dir, file_name, command) =
+            split_script_path(Path::new("/work/scripts/migrate.ts")).unwrap();
+        assert_eq!(dir, Path::new("/work/scripts"));
+        assert_eq!(file_name, "migrate.ts");
+        assert_eq!(command, "migrate");
+    }
+
+    #[test]
+    fn test_exec_manifest_adds_grants_to_safe_defaults() {
+        let temp_dir = tempdir().unwrap();
+        let grants = SecurityPermissions {
+            network: vec!["api.example.com".to_string()],
+            ..SecurityPermissions::default()
+        };
+        let manifest = exec_manifest("migrate.ts", "migrate", grants);
+        assert_eq!(manifest.commands["migrate"].script, "migrate.ts");
+
+        let granted = build_plugin_permissions(temp_dir.path(), &manifest, "migrate")
+            .unwrap()
+            .to_deno_args();
+        assert!(
+            granted
+                .iter()
+                .any(|arg| arg.starts_with("--allow-net") && arg.contains("api.example.com")),
+            "{:?}",
+            granted
+        );
+
+        let defaults = build_plugin_permissions(
+            temp_dir.path(),
+            &exec_manifest("migrate.ts", "migrate", SecurityPermissions::default()),
+            "migrate",
+        )
+        .unwrap()
+        .to_deno_args();
+        assert!(
+            !defaults.iter().any(|arg| arg.starts_with("--allow-net")),
+            "{:?}",
+            defaults
+        );
+    }
+}
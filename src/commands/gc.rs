@@ -0,0 +1,290 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result};
+
+use crate::{
+    integrations::{
+        registry_cache::{registry_cache_root, stale_cache_entries},
+        registry_download::partial_downloads_dir,
+    },
+    config::plugins::load_plugin_manifest,
+    constants::PLUGIN_MANIFEST_FILE,
+    plugin_lock::PluginLock,
+    utils::require_project_root,
+};
+
+/// Something `mis gc` can delete, and why
+#[derive(Debug, PartialEq)]
+pub struct Garbage {
+    pub path: PathBuf,
+    pub reason: String,
+    pub bytes: u64,
+}
+
+/// Remove data under .makeitso (and the shared registry cache) that nothing needs anymore
+pub fn run_gc(keep_days: u64, dry_run: bool) -> Result<()> {
+    let project_root = require_project_root()?;
+    let max_age = Duration::from_secs(keep_days * 24 * 60 * 60);
+
+    let mut garbage = find_project_garbage(&project_root, max_age)?;
+    garbage.extend(
+        stale_cache_entries(&registry_cache_root(), max_age)
+            .into_iter()
+            .map(|path| garbage_entry(path, "registry cache not used recently")),
+    );
+    garbage.extend(
+        older_than(&partial_downloads_dir(), max_age)
+            .into_iter()
+            .map(|path| garbage_entry(path, "abandoned registry download")),
+    );
+
+    if garbage.is_empty() {
+        outln!("✨ Nothing to clean.");
+        return Ok(());
+    }
+
+    for item in &garbage {
+        if dry_run {
+            outln!(
+                "📝 Would remove {} ({}, {})",
+                item.path.display(),
+                format_bytes(item.bytes),
+                item.reason
+            );
+        } else {
+            remove_path(&item.path)?;
+            outln!(
+                "🧹 Removed {} ({}, {})",
+                item.path.display(),
+                format_bytes(item.bytes),
+                item.reason
+            );
+        }
+    }
+
+    let total = garbage.iter().map(|item| item.bytes).sum();
+    if dry_run {
+        outln!("\n→ {} would be reclaimed.", format_bytes(total));
+    } else {
+        outln!("\n✅ Reclaimed {}.", format_bytes(total));
+    }
+
+    Ok(())
+}
+
+/// Garbage inside the project's .makeitso directory.
+///
+/// Artifacts, state and permission reports of plugins that are neither installed nor in
+/// plugins.lock always go; artifacts and saved workflow runs are otherwise kept for
+/// `max_age` after they were last written.
+pub fn find_project_garbage(project_root: &Path, max_age: Duration) -> Result<Vec<Garbage>> {
+    let makeitso_dir = project_root.join(".makeitso");
+    let referenced = referenced_plugins(project_root)?;
+    let mut garbage = Vec::new();
+
+    for plugin_dir in child_paths(&makeitso_dir.join("artifacts")) {
+        if !is_referenced(&plugin_dir, &referenced) {
+            garbage.push(garbage_entry(plugin_dir, "plugin no longer installed"));
+            continue;
+        }
+        garbage.extend(
+            older_than(&plugin_dir, max_age)
+                .into_iter()
+                .map(|path| garbage_entry(path, "old artifacts")),
+        );
+    }
+
+    let state_dirs = child_paths(&makeitso_dir.join("state"));
+    let permission_dirs = child_paths(&makeitso_dir.join("cache").join("permissions"));
+    for plugin_dir in state_dirs.into_iter().chain(permission_dirs) {
+        if !is_referenced(&plugin_dir, &referenced) {
+            garbage.push(garbage_entry(plugin_dir, "plugin no longer installed"));
+        }
+    }
+
+    garbage.extend(
+        older_than(&makeitso_dir.join("runs"), max_age)
+            .into_iter()
+            .map(|path| garbage_entry(path, "old saved run")),
+    );
+
+    Ok(garbage)
+}
+
+/// Plugins installed in .makeitso/plugins or tracked in plugins.lock. Installed plugins count
+/// under their manifest name too, which keys their artifacts and state.
+fn referenced_plugins(project_root: &Path) -> Result<BTreeSet<String>> {
+    let mut plugins: BTreeSet<String> = PluginLock::load(project_root)?
+        .plugins
+        .into_keys()
+        .collect();
+    for plugin_dir in child_paths(&project_root.join(".makeitso").join("plugins")) {
+        if !plugin_dir.is_dir() {
+            continue;
+        }
+        if let Some(name) = plugin_dir.file_name() {
+            plugins.insert(name.to_string_lossy().to_string());
+        }
+        if let Ok(manifest) = load_plugin_manifest(&plugin_dir.join(PLUGIN_MANIFEST_FILE)) {
+            plugins.insert(manifest.plugin.name);
+        }
+    }
+    Ok(plugins)
+}
+
+fn is_referenced(path: &Path, referenced: &BTreeSet<String>) -> bool {
+    path.file_name()
+        .is_some_and(|name| referenced.contains(name.to_string_lossy().as_ref()))
+}
+
+/// Entries of `dir` last modified more than `max_age` ago
+fn older_than(dir: &Path, max_age: Duration) -> Vec<PathBuf> {
+    child_paths(dir)
+        .into_iter()
+        .filter(|path| {
+            fs::symlink_metadata(path)
+                .and_then(|meta| meta.modified())
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .is_some_and(|age| age >= max_age)
+        })
+        .collect()
+}
+
+fn child_paths(dir: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = entries.filter_map(|e| e.ok()).map(|e| e.path()).collect();
+    paths.sort();
+    paths
+}
+
+fn garbage_entry(path: PathBuf, reason: &str) -> Garbage {
+    Garbage {
+        bytes: disk_usage(&path),
+        path,
+        reason: reason.to_string(),
+    }
+}
+
+/// Total size of a file or directory tree, without following symlinks
+fn disk_usage(path: &Path) -> u64 {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return 0;
+    };
+    if !meta.is_dir() {
+        return meta.len();
+    }
+    child_paths(path)
+        .iter()
+        .map(|child| disk_usage(child))
+        .sum()
+}
+
+fn remove_path(path: &Path) -> Result<()> {
+    let result = if path.is_dir() && !path.is_symlink() {
+        fs::remove_dir_all(path)
+    } else {
+        fs::remove_file(path)
+    };
+    result.with_context(|| format!("Failed to remove {}", path.display()))
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(3 * 1024 * 1024 * 1024), "3.0 GB");
+    }
+
+    #[test]
+    fn test_find_project_garbage_keeps_referenced_and_recent_data() {
+        let temp_dir = tempdir().unwrap();
+        let makeitso = temp_dir.path().join(".makeitso");
+        fs::create_dir_all(makeitso.join("plugins/deploy")).unwrap();
+        fs::create_dir_all(makeitso.join("artifacts/deploy/apply")).unwrap();
+        fs::write(makeitso.join("artifacts/deploy/apply/plan.txt"), "plan").unwrap();
+        fs::create_dir_all(makeitso.join("artifacts/removed/build")).unwrap();
+        fs::write(makeitso.join("artifacts/removed/build/out.bin"), "12345").unwrap();
+        fs::create_dir_all(makeitso.join("cache/permissions/removed")).unwrap();
+        fs::create_dir_all(makeitso.join("cache/permissions/deploy")).unwrap();
+        fs::create_dir_all(makeitso.join("state/removed")).unwrap();
+        fs::create_dir_all(makeitso.join("state/deploy")).unwrap();
+        fs::create_dir_all(makeitso.join("runs")).unwrap();
+        fs::write(makeitso.join("runs/release-1.json"), "{}").unwrap();
+
+        let garbage = find_project_garbage(temp_dir.path(), Duration::from_secs(3600)).unwrap();
+        let paths: Vec<&Path> = garbage.iter().map(|g| g.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            vec![
+                makeitso.join("artifacts/removed").as_path(),
+                makeitso.join("state/removed").as_path(),
+                makeitso.join("cache/permissions/removed").as_path(),
+            ]
+        );
+        assert_eq!(garbage[0].bytes, 5);
+
+        // With no retention window, old artifacts and saved runs go too
+        let garbage = find_project_garbage(temp_dir.path(), Duration::ZERO).unwrap();
+        let paths: Vec<&Path> = garbage.iter().map(|g| g.path.as_path()).collect();
+        assert!(paths.contains(&makeitso.join("artifacts/deploy/apply").as_path()));
+        assert!(paths.contains(&makeitso.join("runs/release-1.json").as_path()));
+        assert!(!paths.contains(&makeitso.join("cache/permissions/deploy").as_path()));
+        assert!(!paths.contains(&makeitso.join("state/deploy").as_path()));
+    }
+
+    #[test]
+    fn test_referenced_plugins_include_the_lock_file() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".makeitso/plugins/deploy")).unwrap();
+        fs::write(
+            temp_dir.path().join(".makeitso/plugins.lock"),
+            "[plugins.lint.files]\n",
+        )
+        .unwrap();
+
+        let referenced = referenced_plugins(temp_dir.path()).unwrap();
+        assert_eq!(
+            referenced.into_iter().collect::<Vec<_>>(),
+            vec!["deploy", "lint"]
+        );
+
+        // A plugin installed under another directory name keeps its state
+        let renamed = temp_dir.path().join(".makeitso/plugins/deploy-v2");
+        fs::create_dir_all(&renamed).unwrap();
+        fs::write(
+            renamed.join(PLUGIN_MANIFEST_FILE),
+            "[plugin]\nname = \"release\"\nversion = \"2.0.0\"\n",
+        )
+        .unwrap();
+        fs::create_dir_all(temp_dir.path().join(".makeitso/state/release")).unwrap();
+        let referenced = referenced_plugins(temp_dir.path()).unwrap();
+        assert!(referenced.contains("deploy-v2") && referenced.contains("release"));
+        let garbage = find_project_garbage(temp_dir.path(), Duration::from_secs(3600)).unwrap();
+        assert!(garbage.is_empty(), "{:?}", garbage);
+    }
+}
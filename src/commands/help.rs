@@ -1,9 +1,23 @@
 use crate::{
-    config::plugins::load_plugin_manifest, constants::PLUGIN_MANIFEST_FILE, models::ArgType,
-    utils::find_project_root,
+    cli::alias_conflict,
+    config::{
+        load_mis_config_from,
+        plugins::{load_plugin_manifest, read_plugin_user_config},
+    },
+    commands::run::unknown_command_message,
+    constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE},
+    integrations::registry_index::{configured_registries, list_registry_plugins},
+    models::{
+        ArgDefinition, ArgType, ConfigValueType, PluginManifest, PluginUserConfig,
+        SecurityPermissions,
+    },
+    plugin_utils::{disabled_plugins, ensure_plugin_enabled, get_all_plugin_names},
+    report::{json_output, print_json},
+    utils::require_project_root,
+    validation::did_you_mean,
 };
 use anyhow::{Context, Result, anyhow};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 pub fn show_help(plugin_command: &str) -> Result<()> {
     // Parse plugin:command format
@@ -27,164 +41,296 @@ pub fn show_help(plugin_command: &str) -> Result<()> {
     let command = plugin_manifest
         .commands
         .get(command_name)
-        .with_context(|| {
-            let available_commands: Vec<String> =
-                plugin_manifest.commands.keys().map(|k| k.clone()).collect();
-            format!(
-                "Command '{}' not found in plugin '{}'.\n\
-                 Available commands: {}",
-                command_name,
-                plugin_name,
-                available_commands.join(", ")
-            )
-        })?;
+        .with_context(|| unknown_command_message(&plugin_manifest, plugin_name, command_name))?;
+
+    if json_output() {
+        return print_json(&serde_json::json!({
+            "plugin": plugin_manifest.plugin.name,
+            "version": plugin_manifest.plugin.version,
+            "description": plugin_manifest.plugin.description,
+            "command": command_name,
+            "details": command,
+            "plugin_permissions": plugin_manifest.permissions,
+        }));
+    }
 
     // Display help information
-    println!("📖 Help for {}:{}\n", plugin_name, command_name);
+    outln!("📖 Help for {}:{}\n", plugin_name, command_name);
 
     // Plugin information
-    println!(
+    outln!(
         "🔌 Plugin: {} (v{})",
         plugin_manifest.plugin.name, plugin_manifest.plugin.version
     );
     if let Some(desc) = &plugin_manifest.plugin.description {
-        println!("   {}", desc);
+        outln!("   {}", desc);
     }
-    println!();
+    outln!();
 
     // Command information
     if let Some(desc) = &command.description {
-        println!("📝 Command: {}", desc);
+        outln!("📝 Command: {}", desc);
     } else {
-        println!("📝 Command: {}", command_name);
+        outln!("📝 Command: {}", command_name);
+    }
+    outln!("   Script: {}", command.script);
+    if let Some(message) = &command.deprecated {
+        outln!("   ⚠️  Deprecated: {}", message);
     }
-    println!("   Script: {}", command.script);
-    println!();
+    outln!();
 
     // Usage line
-    print!("⚡ Usage: mis run {}:{}", plugin_name, command_name);
+    out!("⚡ Usage: mis run {}:{}", plugin_name, command_name);
 
     if let Some(args) = &command.args {
         // Add required args to usage
-        for arg_name in args.required.keys() {
-            print!(" --{} <value>", arg_name);
+        for (arg_name, def) in &args.required {
+            out!(" --{} {}", arg_name, def.value_placeholder());
         }
 
         // Add optional args to usage
-        for arg_name in args.optional.keys() {
-            print!(" [--{} <value>]", arg_name);
+        for (arg_name, def) in &args.optional {
+            out!(" [--{} {}]", arg_name, def.value_placeholder());
         }
     } else {
-        print!(" [arguments...]");
+        out!(" [arguments...]");
     }
-    println!("\n");
+    outln!("\n");
 
     // Arguments section
     if let Some(args) = &command.args {
         if !args.required.is_empty() || !args.optional.is_empty() {
-            println!("📋 Arguments:");
+            outln!("📋 Arguments:");
 
             // Required arguments
             if !args.required.is_empty() {
-                println!("\n  🔴 Required:");
+                outln!("\n  🔴 Required:");
                 for (name, def) in &args.required {
-                    println!(
-                        "    --{:15} {} ({})",
-                        name,
+                    outln!(
+                        "    --{:15} {} ({}){}",
+                        format!("{}{}", name, def.alias_flags()),
                         def.description,
-                        format_arg_type(&def.arg_type)
+                        format_arg_type(&def.arg_type),
+                        format_allowed_values(def)
                     );
                 }
             }
 
             // Optional arguments
             if !args.optional.is_empty() {
-                println!("\n  🟡 Optional:");
+                outln!("\n  🟡 Optional:");
                 for (name, def) in &args.optional {
                     let default_info = def
                         .default_value
                         .as_ref()
                         .map(|d| format!(" [default: {}]", d))
                         .unwrap_or_default();
-                    println!(
-                        "    --{:15} {} ({}){}",
-                        name,
+                    outln!(
+                        "    --{:15} {} ({}){}{}",
+                        format!("{}{}", name, def.alias_flags()),
                         def.description,
                         format_arg_type(&def.arg_type),
+                        format_allowed_values(def),
                         default_info
                     );
                 }
             }
-            println!();
+            outln!();
         }
     } else {
-        println!("ℹ️  This command accepts any arguments (no validation defined).\n");
+        outln!("ℹ️  This command accepts any arguments (no validation defined).\n");
     }
 
     // Examples section
-    println!("💡 Examples:");
+    outln!("💡 Examples:");
     if let Some(args) = &command.args {
         if !args.required.is_empty() {
             // Generate example with required args
-            print!("   mis run {}:{}", plugin_name, command_name);
+            out!("   mis run {}:{}", plugin_name, command_name);
             for (name, def) in &args.required {
-                let example_value = generate_example_value(&def.arg_type);
-                print!(" --{} {}", name, example_value);
+                let example_value = generate_example_value(def);
+                out!(" --{} {}", name, example_value);
             }
-            println!();
+            outln!();
         }
 
         if !args.optional.is_empty() {
             // Generate example with optional args
-            print!("   mis run {}:{}", plugin_name, command_name);
+            out!("   mis run {}:{}", plugin_name, command_name);
             for (name, def) in &args.required {
-                let example_value = generate_example_value(&def.arg_type);
-                print!(" --{} {}", name, example_value);
+                let example_value = generate_example_value(def);
+                out!(" --{} {}", name, example_value);
             }
             // Add one optional arg as example
             if let Some((name, def)) = args.optional.iter().next() {
-                let example_value = generate_example_value(&def.arg_type);
-                print!(" --{} {}", name, example_value);
+                let example_value = generate_example_value(def);
+                out!(" --{} {}", name, example_value);
             }
-            println!();
+            outln!();
         }
     }
 
     // Show dry run example
-    println!(
+    outln!(
         "   mis run {}:{} --dry-run  # Preview without executing",
         plugin_name, command_name
     );
-    println!();
+    outln!();
 
-    // Plugin configuration hint - Note: config is now in config.toml
-    println!("Plugin configuration can be customized in config.toml");
-    println!();
+    // Plugin configuration - documented keys from the manifest's config_schema
+    let user_config = read_plugin_user_config(&plugin_path.join(PLUGIN_CONFIG_FILE))?;
+    print_config_documentation(&plugin_manifest, &user_config);
+
+    print_declared_permissions(&plugin_manifest, command_name);
 
     // Dependencies information
     if !plugin_manifest.deno_dependencies.is_empty() {
-        println!("📦 External Dependencies:");
+        outln!("📦 External Dependencies:");
         for (name, url) in &plugin_manifest.deno_dependencies {
-            println!("   {} → {}", name, url);
+            outln!("   {} → {}", name, url);
         }
-        println!();
+        outln!();
     }
 
     // Custom instructions
     if let Some(instructions) = &command.instructions {
-        println!("📋 Instructions:");
+        outln!("📋 Instructions:");
         // Split by lines and indent each line
         for line in instructions.lines() {
-            println!("   {}", line);
+            outln!("   {}", line);
+        }
+        outln!();
+    }
+
+    Ok(())
+}
+
+/// `mis info --permissions`: what a plugin's commands (or one command) declare they need
+pub fn show_plugin_permissions(target: &str) -> Result<()> {
+    let (plugin_name, command_name) = match target.split_once(':') {
+        Some((plugin, command)) => (plugin, Some(command)),
+        None => (target, None),
+    };
+    let plugin_path = validate_plugin_exists(plugin_name)?;
+    let manifest = load_plugin_manifest(&plugin_path.join(PLUGIN_MANIFEST_FILE))?;
+
+    let command_names: Vec<&str> = match command_name {
+        Some(command_name) => {
+            manifest
+                .commands
+                .get(command_name)
+                .with_context(|| unknown_command_message(&manifest, plugin_name, command_name))?;
+            vec![command_name]
         }
-        println!();
+        None => manifest.commands.keys().map(String::as_str).collect(),
+    };
+
+    if json_output() {
+        let commands: Vec<_> = command_names
+            .iter()
+            .map(|name| {
+                serde_json::json!({
+                    "command": name,
+                    "permissions": manifest.commands[*name].permissions,
+                    "declared": declared_permissions(&manifest, name),
+                })
+            })
+            .collect();
+        return print_json(&serde_json::json!({
+            "plugin": manifest.plugin.name,
+            "version": manifest.plugin.version,
+            "permissions": manifest.permissions,
+            "commands": commands,
+        }));
     }
 
+    outln!(
+        "🔌 Plugin: {} (v{})\n",
+        manifest.plugin.name, manifest.plugin.version
+    );
+    for name in command_names {
+        outln!("📝 {}:{}", plugin_name, name);
+        print_declared_permissions(&manifest, name);
+    }
+    outln!(
+        "→ Run `mis audit {}` to see what they resolve to under your security policy.",
+        plugin_name
+    );
     Ok(())
 }
 
+/// A command's permission block on top of its plugin's, the way `mis run` combines them
+fn declared_permissions(manifest: &PluginManifest, command_name: &str) -> SecurityPermissions {
+    let mut declared = manifest.permissions.clone().unwrap_or_default();
+    let Some(command) = manifest
+        .commands
+        .get(command_name)
+        .and_then(|command| command.permissions.as_ref())
+    else {
+        return declared;
+    };
+    for (all, extra) in [
+        (&mut declared.file_read, &command.file_read),
+        (&mut declared.file_write, &command.file_write),
+        (&mut declared.env_vars, &command.env_vars),
+        (&mut declared.network, &command.network),
+        (&mut declared.run_commands, &command.run_commands),
+    ] {
+        for value in extra {
+            if !all.contains(value) {
+                all.push(value.clone());
+            }
+        }
+    }
+    declared.env_access = command.env_access.or(declared.env_access);
+    declared
+}
+
+/// Labelled one-line summary, e.g. "can run: docker, kubectl; network: docker.io"
+fn describe_permissions(permissions: &SecurityPermissions) -> Option<String> {
+    let mut parts = Vec::new();
+    for (label, values) in [
+        ("can run", &permissions.run_commands),
+        ("network", &permissions.network),
+        ("reads", &permissions.file_read),
+        ("writes", &permissions.file_write),
+    ] {
+        if !values.is_empty() {
+            parts.push(format!("{}: {}", label, values.join(", ")));
+        }
+    }
+    match (permissions.env_vars.is_empty(), permissions.env_access) {
+        (false, _) => parts.push(format!("env: {}", permissions.env_vars.join(", "))),
+        (true, Some(true)) => parts.push("env: all variables".to_string()),
+        (true, Some(false)) => parts.push("env: none".to_string()),
+        (true, None) => {}
+    }
+    (!parts.is_empty()).then(|| parts.join("; "))
+}
+
+/// The command's declared permissions, then which block of the manifest each part came from
+fn print_declared_permissions(manifest: &PluginManifest, command_name: &str) {
+    let Some(summary) = describe_permissions(&declared_permissions(manifest, command_name)) else {
+        outln!("🔐 Permissions: none declared, so only mis's defaults apply\n");
+        return;
+    };
+    outln!("🔐 Permissions declared in manifest.toml:");
+    outln!("   This command {}", summary);
+    let plugin_wide = manifest.permissions.as_ref().and_then(describe_permissions);
+    let command_only = manifest
+        .commands
+        .get(command_name)
+        .and_then(|command| command.permissions.as_ref())
+        .and_then(describe_permissions);
+    if let (Some(plugin_wide), Some(command_only)) = (plugin_wide, command_only) {
+        outln!("   ├─ [permissions] (every command): {}", plugin_wide);
+        outln!("   └─ [commands.{}.permissions]: {}", command_name, command_only);
+    }
+    outln!();
+}
+
 pub fn show_all_plugins() -> Result<()> {
-    let root = find_project_root().ok_or_else(|| anyhow::anyhow!("Failed to find project root"))?;
+    let root = require_project_root()?;
 
     if !root.exists() {
         anyhow::bail!(
@@ -197,86 +343,325 @@ pub fn show_all_plugins() -> Result<()> {
     let plugins_dir = root.join(".makeitso/plugins");
 
     if !plugins_dir.exists() {
-        println!("📋 Available Plugins and Commands\n");
-        println!("🛑 No plugins directory found (.makeitso/plugins).");
-        println!("→ Create your first plugin with: mis create <plugin_name>");
+        if json_output() {
+            return print_plugin_list(&root, &[]);
+        }
+        outln!("📋 Available Plugins and Commands\n");
+        outln!("🛑 No plugins directory found (.makeitso/plugins).");
+        outln!("→ Create your first plugin with: mis create <plugin_name>");
         return Ok(());
     }
 
-    let mut plugins = Vec::new();
-    for entry in std::fs::read_dir(&plugins_dir)? {
-        let entry = entry?;
-        if entry.file_type()?.is_dir() {
-            if let Some(name) = entry.file_name().to_str() {
-                let plugin_path = entry.path();
-                let manifest_path = plugin_path.join(PLUGIN_MANIFEST_FILE);
-
-                if manifest_path.exists() {
-                    match load_plugin_manifest(&manifest_path) {
-                        Ok(manifest) => {
-                            plugins.push((name.to_string(), manifest));
-                        }
-                        Err(_) => {
-                            println!("⚠️  Warning: Failed to load manifest for plugin '{}'", name);
-                        }
-                    }
-                } else {
-                    println!("⚠️  Warning: Plugin '{}' missing manifest.toml", name);
-                }
-            }
-        }
+    let disabled = disabled_plugins(&root);
+    let plugins = installed_plugins(&root)?;
+
+    if json_output() {
+        return print_plugin_list(&root, &plugins);
     }
 
     if plugins.is_empty() {
-        println!("📋 Available Plugins and Commands\n");
-        println!("🛑 No valid plugins found in .makeitso/plugins.");
-        println!("→ Create your first plugin with: mis create <plugin_name>");
+        outln!("📋 Available Plugins and Commands\n");
+        if !disabled.is_empty() {
+            outln!("💤 Every plugin is disabled: {}", disabled.join(", "));
+            outln!("→ Run `mis enable <plugin>` to use one again.");
+            return Ok(());
+        }
+        outln!("🛑 No valid plugins found in .makeitso/plugins.");
+        outln!("→ Create your first plugin with: mis create <plugin_name>");
         return Ok(());
     }
 
-    // Sort plugins by name
-    plugins.sort_by(|a, b| a.0.cmp(&b.0));
-
-    println!("📋 Available Plugins and Commands\n");
+    outln!("📋 Available Plugins and Commands\n");
 
     for (plugin_name, manifest) in &plugins {
-        println!("🔌 {}", plugin_name);
+        outln!("🔌 {}", plugin_name);
         if let Some(desc) = &manifest.plugin.description {
-            println!("   {}", desc);
+            outln!("   {}", desc);
         }
-        println!("   Version: {}", manifest.plugin.version);
+        outln!("   Version: {}", manifest.plugin.version);
 
         if manifest.commands.is_empty() {
-            println!("   └─ No commands defined");
+            outln!("   └─ No commands defined");
         } else {
-            let mut commands: Vec<_> = manifest.commands.iter().collect();
-            commands.sort_by_key(|(name, _)| *name);
-
-            for (i, (cmd_name, cmd)) in commands.iter().enumerate() {
-                let is_last = i == commands.len() - 1;
+            for (i, (cmd_name, cmd)) in manifest.commands.iter().enumerate() {
+                let is_last = i == manifest.commands.len() - 1;
                 let prefix = if is_last { "   └─" } else { "   ├─" };
+                let deprecated = if cmd.deprecated.is_some() {
+                    " (⚠️  deprecated)"
+                } else {
+                    ""
+                };
 
                 if let Some(desc) = &cmd.description {
-                    println!("{} {} - {}", prefix, cmd_name, desc);
+                    outln!("{} {}{} - {}", prefix, cmd_name, deprecated, desc);
                 } else {
-                    println!("{} {}", prefix, cmd_name);
+                    outln!("{} {}{}", prefix, cmd_name, deprecated);
+                }
+            }
+        }
+        outln!();
+    }
+
+    print_workflows(&root);
+    print_aliases(&root);
+
+    if !disabled.is_empty() {
+        outln!("💤 Disabled: {}", disabled.join(", "));
+        outln!("   → Run `mis enable <plugin>` to use one again.");
+        outln!();
+    }
+
+    outln!("💡 Usage:");
+    outln!("   mis run <plugin>:<command>     # Run a command");
+    outln!("   mis run <workflow>             # Run a workflow from mis.toml");
+    outln!("   mis <alias>                    # Run an alias from mis.toml");
+    outln!("   mis info <plugin>:<command>    # Get detailed help for a command");
+    outln!("   mis create <plugin>            # Create a new plugin");
+    outln!("   mis shim <alias> <plugin>:<command>  # Create a shell wrapper");
+    outln!();
+
+    Ok(())
+}
+
+/// The enabled plugins with loadable manifests, sorted by name. Broken ones are skipped
+/// with a warning on stderr, so the output can still be redirected to a file.
+pub fn installed_plugins(root: &Path) -> Result<Vec<(String, PluginManifest)>> {
+    let plugins_dir = root.join(".makeitso/plugins");
+    if !plugins_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let disabled = disabled_plugins(root);
+    let mut plugins = Vec::new();
+    for entry in std::fs::read_dir(&plugins_dir)? {
+        let entry = entry?;
+        if entry.file_type()?.is_dir()
+            && let Some(name) = entry.file_name().to_str()
+        {
+            if disabled.iter().any(|d| d == name) {
+                continue;
+            }
+            let plugin_path = entry.path();
+            let manifest_path = plugin_path.join(PLUGIN_MANIFEST_FILE);
+
+            if manifest_path.exists() {
+                match load_plugin_manifest(&manifest_path) {
+                    Ok(manifest) => {
+                        plugins.push((name.to_string(), manifest));
+                    }
+                    Err(_) => {
+                        errln!("⚠️  Warning: Failed to load manifest for plugin '{}'", name);
+                    }
                 }
+            } else {
+                errln!("⚠️  Warning: Plugin '{}' missing manifest.toml", name);
             }
         }
-        println!();
     }
 
-    println!("💡 Usage:");
-    println!("   mis run <plugin>:<command>     # Run a command");
-    println!("   mis info <plugin>:<command>    # Get detailed help for a command");
-    println!("   mis create <plugin>            # Create a new plugin");
-    println!();
+    // Sort plugins by name
+    plugins.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(plugins)
+}
+
+/// `mis info --output json`: installed plugins with their commands, plus workflows
+fn print_plugin_list(root: &Path, plugins: &[(String, PluginManifest)]) -> Result<()> {
+    let plugins: Vec<_> = plugins
+        .iter()
+        .map(|(name, manifest)| {
+            let commands: Vec<_> = manifest
+                .commands
+                .iter()
+                .map(|(name, command)| {
+                    serde_json::json!({
+                        "name": name,
+                        "description": command.description,
+                        "deprecated": command.deprecated,
+                    })
+                })
+                .collect();
+            serde_json::json!({
+                "name": name,
+                "version": manifest.plugin.version,
+                "description": manifest.plugin.description,
+                "commands": commands,
+            })
+        })
+        .collect();
+
+    let config = load_mis_config_from(root)
+        .map(|(config, _, _)| config)
+        .unwrap_or_default();
+    let workflows: Vec<_> = config
+        .workflows
+        .into_iter()
+        .map(|(name, workflow)| {
+            let steps: Vec<String> = workflow.steps.into_iter().map(|s| s.run).collect();
+            serde_json::json!({
+                "name": name,
+                "description": workflow.description,
+                "steps": steps,
+            })
+        })
+        .collect();
+
+    let aliases: Vec<_> = config
+        .aliases
+        .into_iter()
+        .map(|(name, command)| {
+            serde_json::json!({
+                "name": name,
+                "command": command,
+                "conflict": alias_conflict(&name),
+            })
+        })
+        .collect();
+
+    print_json(&serde_json::json!({
+        "plugins": plugins,
+        "disabled_plugins": config.disabled_plugins,
+        "workflows": workflows,
+        "aliases": aliases,
+    }))
+}
+
+pub fn show_project_variables() -> Result<()> {
+    let root = require_project_root()?;
+    let (config, _, _) = load_mis_config_from(&root)?;
+
+    if json_output() {
+        let mut variables: Vec<_> = config.project_variables.iter().collect();
+        variables.sort_by_key(|(name, _)| *name);
+        let variables: Vec<_> = variables
+            .into_iter()
+            .map(|(name, value)| {
+                serde_json::json!({
+                    "name": name,
+                    "value": value,
+                    "origin": config.variable_origins.get(name).map(|path| {
+                        path.strip_prefix(&root).unwrap_or(path).display().to_string()
+                    }),
+                })
+            })
+            .collect();
+        return print_json(&serde_json::json!({ "variables": variables }));
+    }
+
+    outln!("📦 Project Variables\n");
+
+    if config.project_variables.is_empty() {
+        outln!("   No project variables defined.");
+        outln!("→ Add them under [project_variables] in .makeitso/mis.toml");
+        return Ok(());
+    }
+
+    let mut names: Vec<_> = config.project_variables.keys().collect();
+    names.sort();
+
+    for name in names {
+        let value = &config.project_variables[name];
+        let origin = config
+            .variable_origins
+            .get(name)
+            .map(|path| path.strip_prefix(&root).unwrap_or(path).display().to_string())
+            .unwrap_or_else(|| "unknown".to_string());
+        outln!("   {} = {}", name, value);
+        outln!("     └─ from {}", origin);
+    }
+    outln!();
 
     Ok(())
 }
 
+/// Show what the configured registries know about a plugin, without installing it
+pub fn show_remote_plugin(plugin_name: &str) -> Result<()> {
+    for registry in configured_registries()? {
+        let entries = match list_registry_plugins(&registry) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errln!("⚠️  Warning: Skipping registry {}: {:#}", registry, e);
+                continue;
+            }
+        };
+        let Some(entry) = entries.into_iter().find(|entry| entry.name == plugin_name) else {
+            continue;
+        };
+
+        if json_output() {
+            return print_json(&serde_json::json!({
+                "name": entry.name,
+                "version": entry.version,
+                "description": entry.description,
+                "registry": registry,
+            }));
+        }
+
+        match &entry.version {
+            Some(version) => outln!("📦 {} v{}", entry.name, version),
+            None => outln!("📦 {}", entry.name),
+        }
+        if let Some(description) = &entry.description {
+            outln!("   {}", description);
+        }
+        outln!("   Registry: {}", registry);
+        outln!("\n→ Install it with `mis add {}`.", entry.name);
+        return Ok(());
+    }
+
+    anyhow::bail!(
+        "🛑 Plugin '{}' not found in any registry.\n\
+         → Search for plugins with `mis search <term>`.",
+        plugin_name
+    )
+}
+
+fn print_workflows(root: &Path) {
+    // Workflows are optional; an unreadable mis.toml is reported by the commands that need it
+    let Ok((config, _, _)) = load_mis_config_from(root) else {
+        return;
+    };
+    if config.workflows.is_empty() {
+        return;
+    }
+
+    outln!("🔗 Workflows");
+    for (i, (name, workflow)) in config.workflows.iter().enumerate() {
+        let is_last = i == config.workflows.len() - 1;
+        let prefix = if is_last { "   └─" } else { "   ├─" };
+        let steps: Vec<&str> = workflow.steps.iter().map(|s| s.run.as_str()).collect();
+
+        match &workflow.description {
+            Some(desc) => outln!("{} {} - {}", prefix, name, desc),
+            None => outln!("{} {}", prefix, name),
+        }
+        let indent = if is_last { "      " } else { "   │  " };
+        outln!("{}{}", indent, steps.join(" → "));
+    }
+    outln!();
+}
+
+fn print_aliases(root: &Path) {
+    let Ok((config, _, _)) = load_mis_config_from(root) else {
+        return;
+    };
+    if config.aliases.is_empty() {
+        return;
+    }
+
+    outln!("🏷️  Aliases");
+    for (i, (name, command)) in config.aliases.iter().enumerate() {
+        let is_last = i == config.aliases.len() - 1;
+        let prefix = if is_last { "   └─" } else { "   ├─" };
+        match alias_conflict(name) {
+            Some(conflict) => outln!("{} {} → {} (ignored: {})", prefix, name, command, conflict),
+            None => outln!("{} {} → {}", prefix, name, command),
+        }
+    }
+    outln!();
+}
+
 fn validate_plugin_exists(plugin_name: &str) -> Result<PathBuf> {
-    let root = find_project_root().ok_or_else(|| anyhow::anyhow!("Failed to find project root"))?;
+    let root = require_project_root()?;
 
     if !root.exists() {
         anyhow::bail!(
@@ -289,18 +674,21 @@ fn validate_plugin_exists(plugin_name: &str) -> Result<PathBuf> {
     let plugin_path = root.join(".makeitso/plugins").join(plugin_name);
 
     if !plugin_path.exists() {
+        let installed = get_all_plugin_names().unwrap_or_default();
         anyhow::bail!(
-            "🛑 Plugin '{}' not found in .makeitso/plugins.\n\
+            "🛑 Plugin '{}' not found in .makeitso/plugins.{}\n\
              → Available plugins: {}\n\
              → To install a plugin, run `mis add {}`\n\
              → To create a plugin, run `mis create {}`",
             plugin_name,
+            did_you_mean(plugin_name, installed.iter().map(String::as_str)),
             list_available_plugins()?,
             plugin_name,
             plugin_name
         );
     }
 
+    ensure_plugin_enabled(&root, plugin_name)?;
     let manifest_path = plugin_path.join(PLUGIN_MANIFEST_FILE);
     if !manifest_path.exists() {
         anyhow::bail!(
@@ -316,7 +704,7 @@ fn validate_plugin_exists(plugin_name: &str) -> Result<PathBuf> {
 }
 
 fn list_available_plugins() -> Result<String> {
-    let root = find_project_root().ok_or_else(|| anyhow::anyhow!("Failed to find project root"))?;
+    let root = require_project_root()?;
 
     let plugins_dir = root.join(".makeitso/plugins");
 
@@ -342,20 +730,135 @@ fn list_available_plugins() -> Result<String> {
     }
 }
 
-fn format_arg_type(arg_type: &ArgType) -> &'static str {
+fn print_config_documentation(manifest: &PluginManifest, user_config: &PluginUserConfig) {
+    if manifest.config_schema.is_empty() {
+        outln!("Plugin configuration can be customized in config.toml");
+        outln!();
+        return;
+    }
+
+    outln!("⚙️  Configuration (config.toml):");
+
+    for (name, def) in &manifest.config_schema {
+        let mut details = Vec::new();
+        if let Some(value_type) = &def.value_type {
+            details.push(format_config_type(value_type));
+        }
+        if def.required {
+            details.push("required");
+        }
+
+        let details_info = if details.is_empty() {
+            String::new()
+        } else {
+            format!(" ({})", details.join(", "))
+        };
+        let default_info = def
+            .default
+            .as_ref()
+            .map(|d| format!(" [default: {}]", d))
+            .unwrap_or_default();
+
+        outln!(
+            "    {:17} {}{}{}",
+            name,
+            def.description.as_deref().unwrap_or(""),
+            details_info,
+            default_info
+        );
+
+        // Only whether it's set: values may be tokens or passwords
+        match user_config.get_path(name) {
+            Some(_) => outln!("    {:17} ✅ set", ""),
+            None if def.required && def.default.is_none() => {
+                outln!("    {:17} ❗ unset — required", "")
+            }
+            None => outln!("    {:17} ⚪ unset", ""),
+        }
+    }
+    outln!();
+}
+
+pub fn format_config_type(value_type: &ConfigValueType) -> &'static str {
+    match value_type {
+        ConfigValueType::String => "string",
+        ConfigValueType::Boolean => "boolean",
+        ConfigValueType::Integer => "integer",
+        ConfigValueType::Float => "float",
+        ConfigValueType::Array => "array",
+        ConfigValueType::Table => "table",
+    }
+}
+
+pub fn format_arg_type(arg_type: &ArgType) -> &'static str {
     match arg_type {
         ArgType::String => "string",
         ArgType::Boolean => "boolean",
         ArgType::Integer => "integer",
         ArgType::Float => "float",
+        ArgType::Enum => "enum",
     }
 }
 
-fn generate_example_value(arg_type: &ArgType) -> &'static str {
-    match arg_type {
-        ArgType::String => "\"value\"",
+fn format_allowed_values(def: &ArgDefinition) -> String {
+    if def.allowed_values.is_empty() {
+        String::new()
+    } else {
+        format!(" [one of: {}]", def.allowed_values.join(", "))
+    }
+}
+
+fn generate_example_value(def: &ArgDefinition) -> String {
+    if let Some(first) = def.allowed_values.first() {
+        return first.clone();
+    }
+    match def.arg_type {
+        ArgType::String | ArgType::Enum => "\"value\"",
         ArgType::Boolean => "true",
         ArgType::Integer => "5",
         ArgType::Float => "3.14",
     }
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_declared_permissions_combine_plugin_and_command_blocks() {
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[permissions]
+network = ["docker.io"]
+env_access = false
+
+[commands.push]
+script = "./push.ts"
+
+[commands.push.permissions]
+run_commands = ["docker", "kubectl"]
+network = ["docker.io", "ghcr.io"]
+env_vars = ["KUBECONFIG"]
+
+[commands.status]
+script = "./status.ts"
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            describe_permissions(&declared_permissions(&manifest, "push")).unwrap(),
+            "can run: docker, kubectl; network: docker.io, ghcr.io; env: KUBECONFIG"
+        );
+        assert_eq!(
+            describe_permissions(&declared_permissions(&manifest, "status")).unwrap(),
+            "network: docker.io; env: none"
+        );
+        assert_eq!(describe_permissions(&SecurityPermissions::default()), None);
+    }
 }
@@ -0,0 +1,151 @@
+use anyhow::Result;
+
+use crate::{
+    cli::OutputFormat,
+    report::print_json,
+    run_log::{RunLogEntry, RunStatus, read_run_log},
+    utils::require_project_root,
+};
+
+/// Show the most recent plugin runs from `.makeitso/logs/runs.jsonl`, newest first
+pub fn run_history(
+    plugin: Option<&str>,
+    failed: bool,
+    limit: usize,
+    output: OutputFormat,
+) -> Result<()> {
+    let project_root = require_project_root()?;
+    let entries = filter_history(read_run_log(&project_root)?, plugin, failed, limit);
+
+    match output {
+        OutputFormat::Json => print_json(&entries)?,
+        OutputFormat::Text => print_history(&entries),
+    }
+
+    Ok(())
+}
+
+/// Keep runs matching the filters, newest first, at most `limit` of them.
+///
+/// `plugin` matches either a plugin name or a `plugin:command` pair.
+pub fn filter_history(
+    entries: Vec<RunLogEntry>,
+    plugin: Option<&str>,
+    failed: bool,
+    limit: usize,
+) -> Vec<RunLogEntry> {
+    entries
+        .into_iter()
+        .rev()
+        .filter(|entry| match plugin {
+            Some(filter) if filter.contains(':') => {
+                format!("{}:{}", entry.plugin, entry.command) == filter
+            }
+            Some(filter) => entry.plugin == filter,
+            None => true,
+        })
+        .filter(|entry| !failed || entry.status == RunStatus::Failed)
+        .take(limit)
+        .collect()
+}
+
+fn print_history(entries: &[RunLogEntry]) {
+    if entries.is_empty() {
+        outln!("📜 No runs recorded yet.");
+        return;
+    }
+
+    outln!("📜 Recent runs (newest first):\n");
+    for entry in entries {
+        let icon = match entry.status {
+            RunStatus::Success => "✅",
+            RunStatus::Failed => "❌",
+        };
+        let dry_run = if entry.dry_run { " (dry run)" } else { "" };
+        let environment = entry
+            .environment
+            .as_ref()
+            .map(|env| format!(" [env: {}]", env))
+            .unwrap_or_default();
+        outln!(
+            "{} {}  {}:{}{}{}  {}",
+            icon,
+            entry.timestamp,
+            entry.plugin,
+            entry.command,
+            environment,
+            dry_run,
+            format_duration(entry.duration_ms)
+        );
+
+        if !entry.args.is_empty() {
+            let args: Vec<String> = entry
+                .args
+                .iter()
+                .map(|(name, value)| match value {
+                    serde_json::Value::String(s) => format!("--{} {}", name, s),
+                    other => format!("--{} {}", name, other),
+                })
+                .collect();
+            outln!("      args: {}", args.join(" "));
+        }
+        if let Some(code) = entry.exit_code.filter(|code| *code != 0) {
+            outln!("      exit code: {}", code);
+        }
+        if let Some(error) = &entry.error {
+            outln!("      error: {}", error.lines().next().unwrap_or_default());
+        }
+    }
+    outln!("\n→ Full details, including granted permissions: `mis history --output json`.");
+}
+
+fn format_duration(ms: u64) -> String {
+    if ms < 1_000 {
+        format!("{}ms", ms)
+    } else if ms < 60_000 {
+        format!("{:.1}s", ms as f64 / 1_000.0)
+    } else {
+        format!("{}m{:02}s", ms / 60_000, ms % 60_000 / 1_000)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(plugin: &str, command: &str, status: RunStatus) -> RunLogEntry {
+        let mut entry = RunLogEntry::start(plugin, command, &serde_json::Map::new(), false, None);
+        entry.status = status;
+        entry
+    }
+
+    #[test]
+    fn test_filter_history() {
+        let entries = vec![
+            entry("deploy", "staging", RunStatus::Success),
+            entry("deploy", "prod", RunStatus::Failed),
+            entry("lint", "check", RunStatus::Failed),
+        ];
+
+        let all = filter_history(entries.clone(), None, false, 10);
+        assert_eq!(all[0].plugin, "lint");
+        assert_eq!(all.len(), 3);
+
+        let deploys = filter_history(entries.clone(), Some("deploy"), false, 10);
+        assert_eq!(deploys.len(), 2);
+
+        let prod = filter_history(entries.clone(), Some("deploy:prod"), false, 10);
+        assert_eq!(prod.len(), 1);
+
+        let failed = filter_history(entries.clone(), None, true, 1);
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].plugin, "lint");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(250), "250ms");
+        assert_eq!(format_duration(1_500), "1.5s");
+        assert_eq!(format_duration(125_000), "2m05s");
+    }
+}
@@ -1,52 +1,277 @@
 use std::fs;
+use std::path::{Path, PathBuf};
 
-use anyhow::Result;
+use anyhow::{Context, Result};
+use tempfile::TempDir;
 
-use crate::cli::prompt_user;
+use crate::cli::{Runtime, prompt_input, prompt_user};
+use crate::commands::api::PLUGIN_API_FILES;
+use crate::commands::create::create_plugin;
+use crate::constants::PROJECT_SCHEMA_VERSION;
+use crate::git_utils::shallow_clone_repo;
 use crate::integrations::deno::{install_deno, is_deno_installed};
 use crate::utils::find_project_root;
 
 // use crate::strategy::deploy::get_deploy_strategy;
 
-fn generate_mis_toml(_name: Option<&str>) -> String {
-    // If name is None, use the name of the current directory
-    // let current_dir = std::env::current_dir()
-    //     .expect("Failed to get current directory");
-    // let dir_name = current_dir
-    //     .file_name()
-    //     .and_then(|s| s.to_str())
-    //     .unwrap_or(".");
+/// Runtime files mis writes under `.makeitso/`, which don't belong in version control
+const GITIGNORE_ENTRIES: &[&str] = &[
+    ".makeitso/.lock",
+    ".makeitso/artifacts/",
+    ".makeitso/cache/",
+    ".makeitso/logs/",
+    ".makeitso/plans/",
+    ".makeitso/runs/",
+];
 
-    // let proj_name = name.unwrap_or_else(|| dir_name);
+/// Name of the plugin scaffolded when `mis init --interactive` is asked for an example
+const EXAMPLE_PLUGIN: &str = "example";
 
-    format!(
+/// Replaced with the project name in the names and contents of files copied by `mis init --from`
+const PROJECT_NAME_PLACEHOLDER: &str = "{{project_name}}";
+
+/// What goes into a new project
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct InitOptions {
+    pub name: String,
+    /// Registry sources for `mis add`
+    pub registries: Vec<String>,
+    /// Names of the `[env.<name>]` profiles to create
+    pub environments: Vec<String>,
+    pub example_plugin: bool,
+}
+
+fn toml_string(value: &str) -> String {
+    toml::Value::String(value.to_string()).to_string()
+}
+
+fn generate_mis_toml(options: &InitOptions) -> String {
+    let mut toml = format!(
         r#"
-name = "make-it-so-cli"
+name = {}
+schema_version = {}    # ← layout version, upgraded by `mis migrate`
 
 # 👇 Project-scoped settings available to all plugins
 [project_variables]
 # — User-created (optional) env variables accessible by plugins through the ctx object:
 foo = "bar"            # ← EXAMPLE of a project-scoped variable
-"#
+"#,
+        toml_string(&options.name),
+        PROJECT_SCHEMA_VERSION
     )
     .trim_start()
-    .to_string()
+    .to_string();
+
+    toml.push_str("\n# 👇 Where `mis add` looks for plugins\n");
+    if options.registries.is_empty() {
+        toml.push_str("# [registry]\n");
+        toml.push_str("# sources = [\"https://github.com/your-org/mis-plugins.git\"]\n");
+    } else {
+        let sources: Vec<String> = options.registries.iter().map(|s| toml_string(s)).collect();
+        toml.push_str("[registry]\n");
+        toml.push_str(&format!("sources = [{}]\n", sources.join(", ")));
+    }
+
+    toml.push_str("\n# 👇 Environment profiles, picked with `mis run --env <name>`\n");
+    if options.environments.is_empty() {
+        toml.push_str("# [env.production.project_variables]\n");
+        toml.push_str(
+            "# foo = \"baz\"          # ← overrides foo when run with --env production\n",
+        );
+    }
+    for env in &options.environments {
+        toml.push_str(&format!(
+            "[env.{env}]\n\
+             # protected = true        # ← runs must type \"{env}\" back (or pass --confirm-env)\n\
+             # require_approval = true # ← runs also need --approval <TOKEN>\n\
+             \n\
+             [env.{env}.project_variables]\n\
+             # foo = \"baz\"          # ← overrides foo when run with --env {env}\n\
+             \n"
+        ));
+    }
+
+    toml.trim_end().to_string() + "\n"
+}
+
+/// Split a comma-separated answer into its non-empty parts
+fn split_list(answer: &str) -> Vec<String> {
+    answer
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Environment names become `[env.<name>]` tables, so they must be bare TOML keys
+fn valid_env_name(name: &str) -> bool {
+    name.chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Ask for everything `mis init --interactive` sets up. `ask` shows a prompt and
+/// returns the answer; blank answers take the default.
+fn ask_init_options(
+    default_name: &str,
+    mut ask: impl FnMut(&str) -> Result<String>,
+) -> Result<InitOptions> {
+    let name = ask(&format!("Project name [{}]", default_name))?;
+    let name = match name.trim() {
+        "" => default_name.to_string(),
+        name => name.to_string(),
+    };
+
+    let registries = split_list(&ask(
+        "Plugin registries to install from (comma-separated git URLs, blank for none)",
+    )?);
+
+    let environments = loop {
+        let environments = split_list(&ask(
+            "Environment profiles (comma-separated, e.g. staging,production; blank for none)",
+        )?);
+        match environments.iter().find(|env| !valid_env_name(env)) {
+            Some(invalid) => errln!(
+                "⚠️  '{}' isn't a valid environment name; use letters, digits, '-' and '_'.",
+                invalid
+            ),
+            None => break environments,
+        }
+    };
+
+    let example_plugin = !matches!(
+        ask("Scaffold an example plugin? [Y/n]")?
+            .trim()
+            .to_lowercase()
+            .as_str(),
+        "n" | "no"
+    );
+
+    Ok(InitOptions {
+        name,
+        registries,
+        environments,
+        example_plugin,
+    })
+}
+
+/// Add the runtime entries `.gitignore` doesn't already have, returning the ones added
+fn update_gitignore(project_root: &Path) -> Result<Vec<&'static str>> {
+    let path = project_root.join(".gitignore");
+    let existing = fs::read_to_string(&path).unwrap_or_default();
+    let present: Vec<&str> = existing.lines().map(str::trim).collect();
+    let missing: Vec<&'static str> = GITIGNORE_ENTRIES
+        .iter()
+        .copied()
+        .filter(|entry| !present.contains(entry) && !present.contains(&entry.trim_end_matches('/')))
+        .collect();
+    if missing.is_empty() {
+        return Ok(missing);
+    }
+
+    let mut contents = existing;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        contents.push('\n');
+    }
+    if !contents.is_empty() {
+        contents.push('\n');
+    }
+    contents.push_str("# Make It So runtime files\n");
+    for entry in &missing {
+        contents.push_str(entry);
+        contents.push('\n');
+    }
+    fs::write(&path, contents)?;
+    Ok(missing)
+}
+
+/// Find the template project for `mis init --from`: a local directory, or a git repo
+/// cloned into a temporary directory that lives as long as the returned `TempDir`
+fn fetch_template(source: &str) -> Result<(Option<TempDir>, PathBuf)> {
+    let (clone, root) = if Path::new(source).is_dir() {
+        (None, PathBuf::from(source))
+    } else {
+        let clone = TempDir::new()?;
+        let root = clone.path().join("template");
+        shallow_clone_repo(source.to_string(), root.to_string_lossy().to_string()).with_context(
+            || {
+                format!(
+                    "🛑 Couldn't fetch the template project {}.\n\
+                     → Pass a local directory or a git URL you can clone.",
+                    source
+                )
+            },
+        )?;
+        (Some(clone), root)
+    };
+
+    if !root.join(".makeitso").is_dir() {
+        anyhow::bail!(
+            "🛑 {} has no .makeitso/ directory to copy.\n\
+             → A template project is a project set up with `mis init`, with its plugins and config.",
+            source
+        );
+    }
+    Ok((clone, root))
 }
 
-// Template files that will be copied to .makeitso/
-const MIS_TYPES_TEMPLATE: &str = include_str!("../../templates/mis-types.d.ts");
-const MIS_UTILS_TEMPLATE: &str = include_str!("../../templates/mis-plugin-api.ts");
+/// Copy a template project's `.makeitso/` into `makeitso_dir`, leaving out its runtime
+/// files and filling in the project name. Returns the number of files copied.
+fn copy_template_project(template_root: &Path, makeitso_dir: &Path, name: &str) -> Result<usize> {
+    let runtime: Vec<&str> = GITIGNORE_ENTRIES
+        .iter()
+        .filter_map(|entry| entry.strip_prefix(".makeitso/"))
+        .map(|entry| entry.trim_end_matches('/'))
+        .collect();
+    copy_template_dir(
+        &template_root.join(".makeitso"),
+        makeitso_dir,
+        name,
+        &runtime,
+    )
+}
+
+fn copy_template_dir(src: &Path, dst: &Path, name: &str, skip: &[&str]) -> Result<usize> {
+    fs::create_dir_all(dst)?;
+
+    let mut copied = 0;
+    for entry in fs::read_dir(src)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if skip.contains(&file_name.as_str()) {
+            continue;
+        }
+        let source_path = entry.path();
+        let target_path = dst.join(file_name.replace(PROJECT_NAME_PLACEHOLDER, name));
+
+        if source_path.is_dir() {
+            copied += copy_template_dir(&source_path, &target_path, name, &[])?;
+            continue;
+        }
+        let bytes = fs::read(&source_path)
+            .with_context(|| format!("Failed to read {}", source_path.display()))?;
+        let contents = match String::from_utf8(bytes) {
+            Ok(text) => text.replace(PROJECT_NAME_PLACEHOLDER, name).into_bytes(),
+            Err(binary) => binary.into_bytes(),
+        };
+        fs::write(&target_path, contents)
+            .with_context(|| format!("Failed to write {}", target_path.display()))?;
+        copied += 1;
+    }
+
+    Ok(copied)
+}
 
-pub fn run_init(name: Option<&str>) -> Result<()> {
+pub fn run_init(name: Option<&str>, interactive: bool, from: Option<&str>) -> Result<()> {
     if !is_deno_installed() {
         let should_install = prompt_user("Deno is not installed. Would you like to install it?")?;
         if !should_install {
             anyhow::bail!("Deno is required for Make It So. Please install it and try again.");
         }
-        
+
         // Install Deno
         install_deno()?; // or prompt/abort if you want confirmation
-    }    
+    }
 
     if let Some(existing_root) = find_project_root() {
         anyhow::bail!(
@@ -57,43 +282,209 @@ pub fn run_init(name: Option<&str>) -> Result<()> {
     }
 
     let current_dir = std::env::current_dir()?;
+    let dir_name = current_dir
+        .file_name()
+        .and_then(|s| s.to_str())
+        .unwrap_or("my-project");
+    let default_name = name.unwrap_or(dir_name);
+
+    let options = if interactive {
+        ask_init_options(default_name, prompt_input)?
+    } else {
+        InitOptions {
+            name: default_name.to_string(),
+            ..InitOptions::default()
+        }
+    };
+
     let makeitso_dir = current_dir.join(".makeitso");
 
+    if let Some(source) = from {
+        let (_clone, template_root) = fetch_template(source)?;
+        let copied = copy_template_project(&template_root, &makeitso_dir, &options.name)?;
+        outln!(
+            "📦 Copied {} file(s) from template project {}",
+            copied,
+            source
+        );
+    }
+
     if !makeitso_dir.exists() {
         fs::create_dir_all(&makeitso_dir)?;
-        println!("📁 Created .makeitso/");
+        outln!("📁 Created .makeitso/");
     }
 
     let config_path = makeitso_dir.join("mis.toml");
 
     if !config_path.exists() {
-        let toml = generate_mis_toml(name);
+        let toml = generate_mis_toml(&options);
         fs::write(&config_path, toml)?;
-        println!("📝 Created config file: {}", config_path.display());
+        outln!("📝 Created config file: {}", config_path.display());
     } else {
-        println!("⚠️  Config already exists: {}", config_path.display());
+        outln!("⚠️  Config already exists: {}", config_path.display());
     }
 
-    // Copy TypeScript template files to .makeitso/
-    let types_path = makeitso_dir.join("mis-types.d.ts");
-    let utils_path = makeitso_dir.join("mis-plugin-api.ts");
-
-    if !types_path.exists() {
-        fs::write(&types_path, MIS_TYPES_TEMPLATE)?;
-        println!("📝 Created TypeScript types: {}", types_path.display());
-    } else {
-        println!("⚠️  TypeScript types already exist: {}", types_path.display());
+    // Copy the TypeScript plugin API files to .makeitso/
+    for (file, content) in PLUGIN_API_FILES {
+        let path = makeitso_dir.join(file);
+        if !path.exists() {
+            fs::write(&path, content)?;
+            outln!("📝 Created TypeScript API file: {}", path.display());
+        } else {
+            outln!(
+                "⚠️  TypeScript API file already exists: {} (refresh it with `mis api update`)",
+                path.display()
+            );
+        }
     }
 
-    if !utils_path.exists() {
-        fs::write(&utils_path, MIS_UTILS_TEMPLATE)?;
-        println!("📝 Created TypeScript utilities: {}", utils_path.display());
-    } else {
-        println!("⚠️  TypeScript utilities already exist: {}", utils_path.display());
+    if interactive {
+        let added = update_gitignore(&current_dir)?;
+        if !added.is_empty() {
+            outln!("📝 Added {} to .gitignore", added.join(", "));
+        }
     }
 
     // scaffold_plugin_if_needed(&strategy)?;
+    if options.example_plugin {
+        create_plugin(EXAMPLE_PLUGIN, None, Runtime::Deno)?;
+    }
 
-    println!("✅ Make-It-So service initialized.");
+    outln!("✅ Make-It-So service initialized.");
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MakeItSoConfig;
+    use tempfile::tempdir;
+
+    fn scripted(answers: &[&str]) -> impl FnMut(&str) -> Result<String> {
+        let mut answers: Vec<String> = answers.iter().rev().map(|a| a.to_string()).collect();
+        move |_| Ok(answers.pop().expect("asked more questions than scripted"))
+    }
+
+    #[test]
+    fn test_ask_init_options() {
+        let options = ask_init_options(
+            "shop",
+            scripted(&[
+                "",
+                "https://github.com/acme/mis-plugins.git",
+                "staging, prod west",
+                "staging, production",
+                "n",
+            ]),
+        )
+        .unwrap();
+        assert_eq!(
+            options,
+            InitOptions {
+                name: "shop".to_string(),
+                registries: vec!["https://github.com/acme/mis-plugins.git".to_string()],
+                environments: vec!["staging".to_string(), "production".to_string()],
+                example_plugin: false,
+            }
+        );
+
+        let options = ask_init_options("shop", scripted(&["storefront", "", "", ""])).unwrap();
+        assert_eq!(options.name, "storefront");
+        assert!(options.registries.is_empty());
+        assert!(options.environments.is_empty());
+        assert!(options.example_plugin);
+    }
+
+    #[test]
+    fn test_generated_mis_toml_parses() {
+        let options = InitOptions {
+            name: "shop \"main\"".to_string(),
+            registries: vec!["https://github.com/acme/mis-plugins.git".to_string()],
+            environments: vec!["staging".to_string(), "production".to_string()],
+            example_plugin: false,
+        };
+        let toml = generate_mis_toml(&options);
+        let config: MakeItSoConfig = toml::from_str(&toml).unwrap();
+        assert_eq!(config.name.as_deref(), Some("shop \"main\""));
+        assert_eq!(config.schema_version, Some(PROJECT_SCHEMA_VERSION));
+        assert_eq!(
+            config.registry.unwrap().sources,
+            vec!["https://github.com/acme/mis-plugins.git"]
+        );
+        assert_eq!(config.env.len(), 2);
+        assert!(!config.env["production"].protected);
+        assert!(toml.contains("# protected = true"));
+
+        let config: MakeItSoConfig = toml::from_str(&generate_mis_toml(&InitOptions {
+            name: "shop".to_string(),
+            ..InitOptions::default()
+        }))
+        .unwrap();
+        assert!(config.registry.is_none());
+        assert!(config.env.is_empty());
+    }
+
+    #[test]
+    fn test_copy_template_project() {
+        let template = tempdir().unwrap();
+        let source = template.path().join(".makeitso");
+        fs::create_dir_all(source.join("plugins/{{project_name}}-deploy")).unwrap();
+        fs::create_dir_all(source.join("logs")).unwrap();
+        fs::write(source.join("mis.toml"), "name = \"{{project_name}}\"\n").unwrap();
+        fs::write(
+            source.join("plugins/{{project_name}}-deploy/manifest.toml"),
+            "[plugin]\nname = \"{{project_name}}-deploy\"\n",
+        )
+        .unwrap();
+        fs::write(source.join("plugins/logo.png"), [0x89, b'P', 0xff, 0x00]).unwrap();
+        fs::write(source.join("logs/runs.jsonl"), "{}\n").unwrap();
+        fs::write(source.join(".lock"), "{}\n").unwrap();
+
+        let (clone, root) = fetch_template(&template.path().to_string_lossy()).unwrap();
+        assert!(clone.is_none());
+
+        let project = tempdir().unwrap();
+        let makeitso_dir = project.path().join(".makeitso");
+        let copied = copy_template_project(&root, &makeitso_dir, "shop").unwrap();
+        assert_eq!(copied, 3);
+        assert_eq!(
+            fs::read_to_string(makeitso_dir.join("mis.toml")).unwrap(),
+            "name = \"shop\"\n"
+        );
+        assert_eq!(
+            fs::read_to_string(makeitso_dir.join("plugins/shop-deploy/manifest.toml")).unwrap(),
+            "[plugin]\nname = \"shop-deploy\"\n"
+        );
+        assert_eq!(
+            fs::read(makeitso_dir.join("plugins/logo.png")).unwrap(),
+            [0x89, b'P', 0xff, 0x00]
+        );
+        assert!(!makeitso_dir.join("logs").exists());
+        assert!(!makeitso_dir.join(".lock").exists());
+    }
+
+    #[test]
+    fn test_fetch_template_needs_makeitso_dir() {
+        let template = tempdir().unwrap();
+        let err = fetch_template(&template.path().to_string_lossy()).unwrap_err();
+        assert!(err.to_string().contains("no .makeitso/"), "{}", err);
+    }
+
+    #[test]
+    fn test_update_gitignore_adds_only_missing_entries() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join(".gitignore");
+        fs::write(&path, "target/\n.makeitso/logs\n.makeitso/cache/").unwrap();
+
+        let added = update_gitignore(temp_dir.path()).unwrap();
+        assert!(!added.contains(&".makeitso/logs/"));
+        assert!(!added.contains(&".makeitso/cache/"));
+        assert!(added.contains(&".makeitso/.lock"));
+        let contents = fs::read_to_string(&path).unwrap();
+        assert!(contents.starts_with("target/\n.makeitso/logs\n.makeitso/cache/\n\n"));
+        assert!(contents.contains(".makeitso/plans/\n"));
+
+        assert!(update_gitignore(temp_dir.path()).unwrap().is_empty());
+        assert_eq!(fs::read_to_string(&path).unwrap(), contents);
+    }
+}
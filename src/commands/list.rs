@@ -0,0 +1,152 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::commands::help::show_all_plugins;
+use crate::config::{is_workspace_root, load_mis_config_from, workspace_members};
+use crate::report::{json_output, print_json};
+use crate::utils::{ProjectResolution, find_project_root_from, require_project_root};
+
+/// A member project, as shown by `mis list --workspace`
+#[derive(Debug, Serialize, PartialEq)]
+pub struct WorkspaceMember {
+    /// Directory relative to the workspace root, as passed to `--project`
+    pub path: String,
+    /// `name` from the member's mis.toml
+    pub name: Option<String>,
+    pub plugins: Vec<String>,
+    /// False when `members` lists a directory without a .makeitso/
+    pub found: bool,
+}
+
+pub fn run_list(workspace: bool) -> Result<()> {
+    if !workspace {
+        return show_all_plugins();
+    }
+
+    let project_root = require_project_root()?;
+    let workspace_root =
+        find_project_root_from(&project_root, ProjectResolution::Workspace).unwrap_or(project_root);
+    if !is_workspace_root(&workspace_root)? {
+        anyhow::bail!(
+            "🛑 {} isn't part of a workspace.\n\
+             → Add a [workspace] table to the mis.toml at the top of the repository.\n\
+             → List member directories in `members`, or leave it out to include every nested project.",
+            workspace_root.display()
+        );
+    }
+
+    let members = describe_members(&workspace_root)?;
+    if json_output() {
+        return print_json(&serde_json::json!({
+            "root": workspace_root,
+            "projects": members,
+        }));
+    }
+
+    outln!(
+        "📦 Workspace at {} ({} project(s))\n",
+        workspace_root.display(),
+        members.len()
+    );
+    if members.is_empty() {
+        outln!("   No member projects found.");
+        outln!("→ Run `mis init` in a subdirectory to add one.");
+        return Ok(());
+    }
+    for member in &members {
+        match &member.name {
+            Some(name) => outln!("📁 {} ({})", member.path, name),
+            None => outln!("📁 {}", member.path),
+        }
+        if !member.found {
+            outln!("   ⚠️  Listed in members, but has no .makeitso/");
+        } else if member.plugins.is_empty() {
+            outln!("   No plugins");
+        } else {
+            outln!("   Plugins: {}", member.plugins.join(", "));
+        }
+    }
+    outln!();
+    outln!("💡 Run a project's commands from anywhere in the workspace:");
+    outln!("   mis run --project <path> <plugin>:<command>");
+
+    Ok(())
+}
+
+fn describe_members(workspace_root: &Path) -> Result<Vec<WorkspaceMember>> {
+    let members = workspace_members(workspace_root)?
+        .into_iter()
+        .map(|dir| {
+            let path = dir
+                .strip_prefix(workspace_root)
+                .unwrap_or(&dir)
+                .to_string_lossy()
+                .to_string();
+            let name = load_mis_config_from(&dir)
+                .ok()
+                .and_then(|(config, _, _)| config.name);
+            WorkspaceMember {
+                path,
+                name,
+                plugins: plugin_names(&dir),
+                found: dir.join(".makeitso").is_dir(),
+            }
+        })
+        .collect();
+    Ok(members)
+}
+
+fn plugin_names(project_root: &Path) -> Vec<String> {
+    let mut plugins: Vec<String> = fs::read_dir(project_root.join(".makeitso").join("plugins"))
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+        .collect();
+    plugins.sort();
+    plugins
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_describe_members() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join(".makeitso")).unwrap();
+        fs::write(
+            root.join(".makeitso/mis.toml"),
+            "[workspace]\nmembers = [\"services/api\", \"services/gone\"]\n",
+        )
+        .unwrap();
+        let api = root.join("services/api/.makeitso");
+        fs::create_dir_all(api.join("plugins/migrate")).unwrap();
+        fs::create_dir_all(api.join("plugins/deploy")).unwrap();
+        fs::write(api.join("mis.toml"), "name = \"api\"\n").unwrap();
+
+        assert_eq!(
+            describe_members(root).unwrap(),
+            vec![
+                WorkspaceMember {
+                    path: "services/api".to_string(),
+                    name: Some("api".to_string()),
+                    plugins: vec!["deploy".to_string(), "migrate".to_string()],
+                    found: true,
+                },
+                WorkspaceMember {
+                    path: "services/gone".to_string(),
+                    name: None,
+                    plugins: Vec::new(),
+                    found: false,
+                },
+            ]
+        );
+    }
+}
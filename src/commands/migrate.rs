@@ -0,0 +1,315 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+use toml_edit::{DocumentMut, Item, value};
+
+use crate::{
+    cli::OutputFormat,
+    config::{edit_toml_file, toml_errors::parse_toml},
+    constants::{PLUGIN_MANIFEST_FILE, PROJECT_SCHEMA_VERSION},
+    report::print_json,
+    utils::require_project_root,
+};
+
+/// What manifests were called before they became manifest.toml
+pub const LEGACY_MANIFEST_FILE: &str = "plugin.toml";
+
+/// Top-level manifest tables that older plugins nested under [plugin]
+const HOISTED_TABLES: &[&str] = &[
+    "commands",
+    "deno_dependencies",
+    "permissions",
+    "config_schema",
+    "requires",
+    "container",
+    "context",
+];
+
+/// One change `mis migrate` makes to bring a project to the current layout
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Migration {
+    /// plugin.toml → manifest.toml
+    RenameManifest { from: PathBuf, to: PathBuf },
+    /// `[plugin.<table>]` → `[<table>]`
+    HoistTable { manifest: PathBuf, table: String },
+    /// Record the layout version as `schema_version` in mis.toml
+    SetSchemaVersion { config: PathBuf, from: u32, to: u32 },
+}
+
+impl Migration {
+    fn describe(&self, project_root: &Path) -> String {
+        let relative = |path: &Path| {
+            path.strip_prefix(project_root)
+                .unwrap_or(path)
+                .display()
+                .to_string()
+        };
+        match self {
+            Migration::RenameManifest { from, to } => {
+                format!("Rename {} to {}", relative(from), relative(to))
+            }
+            Migration::HoistTable { manifest, table } => format!(
+                "Move [plugin.{}] to [{}] in {}",
+                table,
+                table,
+                relative(manifest)
+            ),
+            Migration::SetSchemaVersion { config, from, to } => format!(
+                "Set schema_version = {} in {} (was {})",
+                to,
+                relative(config),
+                from
+            ),
+        }
+    }
+
+    fn apply(&self) -> Result<()> {
+        match self {
+            Migration::RenameManifest { from, to } => fs::rename(from, to)
+                .with_context(|| format!("🛑 Failed to rename {}", from.display())),
+            Migration::HoistTable { manifest, table } => edit_toml_file(manifest, |doc| {
+                let item = doc
+                    .get_mut("plugin")
+                    .and_then(Item::as_table_like_mut)
+                    .and_then(|plugin| plugin.remove(table));
+                if let Some(item) = item {
+                    doc.insert(table, item);
+                }
+            }),
+            Migration::SetSchemaVersion { config, to, .. } => edit_toml_file(config, |doc| {
+                doc.insert("schema_version", value(i64::from(*to)));
+            }),
+        }
+    }
+}
+
+/// `mis migrate`: upgrade the project and its plugins from the layout version recorded in
+/// mis.toml to the current one, or just list the changes with `--dry-run`
+pub fn run_migrate(dry_run: bool, output: OutputFormat) -> Result<()> {
+    let project_root = require_project_root()?;
+    let config_path = project_root.join(".makeitso").join("mis.toml");
+    let from = project_schema_version(&config_path)?;
+    if from > PROJECT_SCHEMA_VERSION {
+        anyhow::bail!(
+            "🛑 {} uses layout version {}, but this mis only knows up to {}.\n\
+             → Upgrade mis to work with this project.",
+            config_path.display(),
+            from,
+            PROJECT_SCHEMA_VERSION
+        );
+    }
+
+    let migrations = plan_migrations(&project_root, from)?;
+    if !dry_run {
+        apply_migrations(&migrations)?;
+    }
+
+    if output == OutputFormat::Json {
+        return print_json(&serde_json::json!({
+            "from": from,
+            "to": PROJECT_SCHEMA_VERSION,
+            "applied": !dry_run,
+            "migrations": migrations,
+        }));
+    }
+
+    if migrations.is_empty() {
+        outln!(
+            "✅ The project already uses layout version {}.",
+            PROJECT_SCHEMA_VERSION
+        );
+        return Ok(());
+    }
+
+    if dry_run {
+        outln!(
+            "📋 Migrating from layout version {} to {} would make {} change(s):",
+            from,
+            PROJECT_SCHEMA_VERSION,
+            migrations.len()
+        );
+    }
+    for migration in &migrations {
+        let marker = if dry_run { "•" } else { "✅" };
+        outln!("   {} {}", marker, migration.describe(&project_root));
+    }
+    if dry_run {
+        outln!("→ Run `mis migrate` to apply them.");
+    } else {
+        outln!("🎉 Migrated to layout version {}.", PROJECT_SCHEMA_VERSION);
+    }
+    Ok(())
+}
+
+/// `schema_version` from mis.toml; projects from before it existed are version 0
+pub fn project_schema_version(config_path: &Path) -> Result<u32> {
+    if !config_path.exists() {
+        return Ok(0);
+    }
+    let contents = fs::read_to_string(config_path)
+        .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
+    let config: toml::Table = parse_toml(&contents, config_path)?;
+    match config.get("schema_version") {
+        None => Ok(0),
+        Some(version) => version
+            .as_integer()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "🛑 schema_version in {} must be a whole number, like 1.",
+                    config_path.display()
+                )
+            }),
+    }
+}
+
+/// Every change needed to bring the project from layout version `from` to the current one
+pub fn plan_migrations(project_root: &Path, from: u32) -> Result<Vec<Migration>> {
+    let mut migrations = Vec::new();
+
+    // Version 1: manifests are named manifest.toml and keep their tables at the top level
+    if from < 1 {
+        for plugin_dir in plugin_dirs(project_root)? {
+            let manifest = plugin_dir.join(PLUGIN_MANIFEST_FILE);
+            let legacy = plugin_dir.join(LEGACY_MANIFEST_FILE);
+            let source = if !manifest.exists() && legacy.exists() {
+                migrations.push(Migration::RenameManifest {
+                    from: legacy.clone(),
+                    to: manifest.clone(),
+                });
+                legacy
+            } else {
+                manifest.clone()
+            };
+
+            // Manifests that don't parse are left for `mis validate` to explain
+            let Some(doc) = fs::read_to_string(&source)
+                .ok()
+                .and_then(|text| text.parse::<DocumentMut>().ok())
+            else {
+                continue;
+            };
+            let Some(plugin) = doc.get("plugin").and_then(Item::as_table_like) else {
+                continue;
+            };
+            for table in HOISTED_TABLES {
+                // Both places in use can't be merged safely; validate reports the nested one
+                if plugin.contains_key(table) && !doc.contains_key(table) {
+                    migrations.push(Migration::HoistTable {
+                        manifest: manifest.clone(),
+                        table: table.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    if from < PROJECT_SCHEMA_VERSION {
+        migrations.push(Migration::SetSchemaVersion {
+            config: project_root.join(".makeitso").join("mis.toml"),
+            from,
+            to: PROJECT_SCHEMA_VERSION,
+        });
+    }
+    Ok(migrations)
+}
+
+/// Apply migrations in order; each one is a small in-place edit, so a failure leaves the
+/// earlier ones done and `mis migrate` can be run again
+pub fn apply_migrations(migrations: &[Migration]) -> Result<()> {
+    migrations.iter().try_for_each(Migration::apply)
+}
+
+fn plugin_dirs(project_root: &Path) -> Result<Vec<PathBuf>> {
+    let plugins_dir = project_root.join(".makeitso").join("plugins");
+    if !plugins_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut dirs: Vec<PathBuf> = fs::read_dir(&plugins_dir)?
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .collect();
+    dirs.sort();
+    Ok(dirs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::plugins::load_plugin_manifest;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_migrate_legacy_project() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let makeitso = root.join(".makeitso");
+        let legacy = makeitso.join("plugins/deploy");
+        fs::create_dir_all(&legacy).unwrap();
+        fs::write(makeitso.join("mis.toml"), "# My project\nname = \"shop\"\n").unwrap();
+        fs::write(
+            legacy.join(LEGACY_MANIFEST_FILE),
+            "[plugin]\nname = \"deploy\"\nversion = \"1.0.0\"\n\n\
+             [plugin.permissions]\nnetwork = [\"api.example.com\"] # the deploy API\n\n\
+             [commands.prod]\nscript = \"prod.ts\"\n",
+        )
+        .unwrap();
+        let current = makeitso.join("plugins/build");
+        fs::create_dir_all(&current).unwrap();
+        fs::write(
+            current.join(PLUGIN_MANIFEST_FILE),
+            "[plugin]\nname = \"build\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+
+        let from = project_schema_version(&makeitso.join("mis.toml")).unwrap();
+        assert_eq!(from, 0);
+        let migrations = plan_migrations(root, from).unwrap();
+        let described: Vec<String> = migrations.iter().map(|m| m.describe(root)).collect();
+        assert_eq!(
+            described,
+            vec![
+                "Rename .makeitso/plugins/deploy/plugin.toml to .makeitso/plugins/deploy/manifest.toml",
+                "Move [plugin.permissions] to [permissions] in .makeitso/plugins/deploy/manifest.toml",
+                "Set schema_version = 1 in .makeitso/mis.toml (was 0)",
+            ]
+        );
+
+        apply_migrations(&migrations).unwrap();
+        let manifest_path = legacy.join(PLUGIN_MANIFEST_FILE);
+        let manifest = load_plugin_manifest(&manifest_path).unwrap();
+        assert_eq!(
+            manifest.permissions.unwrap().network,
+            vec!["api.example.com"]
+        );
+        assert!(
+            fs::read_to_string(&manifest_path)
+                .unwrap()
+                .contains("[permissions]\nnetwork = [\"api.example.com\"] # the deploy API")
+        );
+        assert!(!legacy.join(LEGACY_MANIFEST_FILE).exists());
+        assert_eq!(
+            fs::read_to_string(makeitso.join("mis.toml")).unwrap(),
+            "# My project\nname = \"shop\"\nschema_version = 1\n"
+        );
+
+        let from = project_schema_version(&makeitso.join("mis.toml")).unwrap();
+        assert_eq!(from, PROJECT_SCHEMA_VERSION);
+        assert!(plan_migrations(root, from).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_project_schema_version() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("mis.toml");
+        assert_eq!(project_schema_version(&path).unwrap(), 0);
+        fs::write(&path, "schema_version = 3\n").unwrap();
+        assert_eq!(project_schema_version(&path).unwrap(), 3);
+        fs::write(&path, "schema_version = \"1\"\n").unwrap();
+        assert!(project_schema_version(&path).is_err());
+    }
+}
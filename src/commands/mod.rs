@@ -1,6 +1,28 @@
 pub mod add;
+pub mod api;
+pub mod audit;
+pub mod auth;
+pub mod cache;
+pub mod config;
+pub mod context;
 pub mod create;
+pub mod docs;
+pub mod enable;
+pub mod exec;
+pub mod gc;
 pub mod help;
+pub mod history;
 pub mod init;
+pub mod list;
+pub mod migrate;
+pub mod permissions;
 pub mod run;
+pub mod sbom;
+pub mod search;
+pub mod shim;
+pub mod test;
 pub mod update;
+pub mod validate;
+pub mod vendor;
+pub mod verify;
+pub mod workflow;
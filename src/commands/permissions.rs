@@ -0,0 +1,288 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    cli::OutputFormat,
+    commands::audit::{
+        BlockedPermission, CommandAudit, audit_permissions, blocked_permissions,
+        print_command_audit, show_values,
+    },
+    config::plugins::load_plugin_manifest,
+    constants::PLUGIN_MANIFEST_FILE,
+    models::PluginManifest,
+    report::print_json,
+    security::{PluginPermissions, apply_security_permissions, default_plugin_permissions},
+    utils::find_project_root,
+};
+
+/// What one layer of the permission resolution (defaults, plugin, command) contributed
+#[derive(Debug, Serialize)]
+pub struct PermissionLayer {
+    pub source: String,
+    pub file_read: Vec<String>,
+    pub file_write: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env_access: Option<bool>,
+    pub env_vars: Vec<String>,
+    pub network: Vec<String>,
+    pub run_commands: Vec<String>,
+    pub blocked: Vec<BlockedPermission>,
+}
+
+/// How a command's permissions would be resolved if the plugin were installed
+#[derive(Debug, Serialize)]
+pub struct CommandSimulation {
+    pub layers: Vec<PermissionLayer>,
+    pub effective: CommandAudit,
+}
+
+/// Resolve the permissions a manifest would get, without installing the plugin
+pub fn run_simulate(path: &Path, command: Option<&str>, output: OutputFormat) -> Result<()> {
+    let manifest_path = &manifest_file(path);
+    let manifest = load_plugin_manifest(manifest_path)?;
+    // Outside a project, paths resolve against the current directory
+    let project_root = match find_project_root() {
+        Some(root) => root,
+        None => std::env::current_dir()?,
+    };
+
+    let command_names: Vec<String> = match command {
+        Some(name) => {
+            if !manifest.commands.contains_key(name) {
+                let mut available: Vec<&String> = manifest.commands.keys().collect();
+                available.sort();
+                anyhow::bail!(
+                    "🛑 Command '{}' not found in {}.\n\
+                     → Available commands: {}",
+                    name,
+                    manifest_path.display(),
+                    available
+                        .iter()
+                        .map(|c| c.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                );
+            }
+            vec![name.to_string()]
+        }
+        None => {
+            let mut names: Vec<String> = manifest.commands.keys().cloned().collect();
+            names.sort();
+            names
+        }
+    };
+
+    let simulations = command_names
+        .iter()
+        .map(|name| simulate_command(&project_root, &manifest, name))
+        .collect::<Result<Vec<_>>>()?;
+
+    match output {
+        OutputFormat::Json => print_json(&simulations)?,
+        OutputFormat::Text => print_simulations(&simulations, manifest_path, &project_root),
+    }
+
+    Ok(())
+}
+
+/// Resolve one command's permissions layer by layer, recording what each layer added
+pub fn simulate_command(
+    project_root: &Path,
+    manifest: &PluginManifest,
+    command_name: &str,
+) -> Result<CommandSimulation> {
+    let defaults = default_plugin_permissions(project_root);
+    let mut layers = vec![layer_diff("defaults", None, &defaults)];
+
+    let mut resolved = defaults.clone();
+    if let Some(plugin_perms) = &manifest.permissions {
+        let before = resolved.clone();
+        apply_security_permissions(&mut resolved, plugin_perms, "plugin-level")?;
+        layers.push(layer_diff("plugin", Some(&before), &resolved));
+    }
+
+    if let Some(command_perms) = manifest
+        .commands
+        .get(command_name)
+        .and_then(|command| command.permissions.as_ref())
+    {
+        let before = resolved.clone();
+        let context = format!("command '{}'", command_name);
+        apply_security_permissions(&mut resolved, command_perms, &context)?;
+        layers.push(layer_diff(&context, Some(&before), &resolved));
+    }
+
+    Ok(CommandSimulation {
+        layers,
+        effective: audit_permissions(&manifest.plugin.name, command_name, resolved),
+    })
+}
+
+/// Entries in `after` that weren't in `before` (everything when there's no `before`)
+fn layer_diff(
+    source: &str,
+    before: Option<&PluginPermissions>,
+    after: &PluginPermissions,
+) -> PermissionLayer {
+    let added = |before: Option<&Vec<String>>, after: &Vec<String>| -> Vec<String> {
+        after
+            .iter()
+            .filter(|value| before.is_none_or(|before| !before.contains(value)))
+            .cloned()
+            .collect()
+    };
+
+    PermissionLayer {
+        source: source.to_string(),
+        file_read: added(before.map(|b| &b.file_read), &after.file_read),
+        file_write: added(before.map(|b| &b.file_write), &after.file_write),
+        env_access: match before {
+            Some(before) if before.env_access == after.env_access => None,
+            _ => Some(after.env_access),
+        },
+        env_vars: added(before.map(|b| &b.env_vars), &after.env_vars),
+        network: added(before.map(|b| &b.network), &after.network),
+        run_commands: added(before.map(|b| &b.run_commands), &after.run_commands),
+        blocked: blocked_permissions(&after.violations[before.map_or(0, |b| b.violations.len())..]),
+    }
+}
+
+fn print_simulations(simulations: &[CommandSimulation], manifest_path: &Path, project_root: &Path) {
+    if simulations.is_empty() {
+        outln!("📋 {} declares no commands.", manifest_path.display());
+        return;
+    }
+
+    for simulation in simulations {
+        let effective = &simulation.effective;
+        outln!(
+            "🔒 Permission resolution for {}:{}\n",
+            effective.plugin,
+            effective.command
+        );
+        for layer in &simulation.layers {
+            outln!("{}:", layer.source);
+            print_layer(layer, project_root);
+        }
+        outln!("effective:");
+        print_command_audit(effective, project_root);
+        outln!();
+    }
+
+    let blocked: usize = simulations.iter().map(|s| s.effective.blocked.len()).sum();
+    if blocked > 0 {
+        outln!(
+            "→ {} requested permission(s) would be blocked; adjust the manifest and run again.",
+            blocked
+        );
+    } else {
+        outln!("✅ Every requested permission passes validation.");
+    }
+}
+
+fn print_layer(layer: &PermissionLayer, project_root: &Path) {
+    let mut printed = false;
+    for (label, values) in [
+        ("read: ", &layer.file_read),
+        ("write:", &layer.file_write),
+        ("env:  ", &layer.env_vars),
+        ("net:  ", &layer.network),
+        ("run:  ", &layer.run_commands),
+    ] {
+        if !values.is_empty() {
+            outln!("   + {} {}", label, show_values(values, project_root));
+            printed = true;
+        }
+    }
+    if let Some(env_access) = layer.env_access {
+        outln!("   = env:   {}", if env_access { "yes" } else { "no" });
+        printed = true;
+    }
+    for blocked in &layer.blocked {
+        outln!("   🛑 Blocked {}: {}", blocked.permission, blocked.reason);
+        printed = true;
+    }
+    if !printed {
+        outln!("   (nothing)");
+    }
+}
+
+/// Accept either a manifest file or the plugin directory holding one
+fn manifest_file(path: &Path) -> PathBuf {
+    if path.is_dir() {
+        path.join(PLUGIN_MANIFEST_FILE)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_simulate_command_attributes_each_layer() {
+        let temp_dir = tempdir().unwrap();
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[permissions]
+network = ["api.example.com"]
+
+[commands.apply]
+script = "./apply.ts"
+
+[commands.apply.permissions]
+network = ["10.0.0.5"]
+run_commands = ["kubectl"]
+env_access = true
+"#,
+        )
+        .unwrap();
+
+        let simulation = simulate_command(temp_dir.path(), &manifest, "apply").unwrap();
+        let sources: Vec<&str> = simulation
+            .layers
+            .iter()
+            .map(|l| l.source.as_str())
+            .collect();
+        assert_eq!(sources, vec!["defaults", "plugin", "command 'apply'"]);
+
+        let defaults = &simulation.layers[0];
+        assert!(defaults.env_access.is_some());
+        assert_eq!(defaults.run_commands, vec!["mis"]);
+
+        let plugin = &simulation.layers[1];
+        assert_eq!(plugin.network, vec!["api.example.com"]);
+        assert!(plugin.blocked.is_empty());
+
+        let command = &simulation.layers[2];
+        assert_eq!(command.run_commands, vec!["kubectl"]);
+        assert!(command.network.is_empty());
+        assert_eq!(command.blocked.len(), 1);
+        assert!(command.blocked[0].permission.contains("10.0.0.5"));
+
+        assert!(simulation.effective.env_access);
+        assert_eq!(simulation.effective.network, vec!["api.example.com"]);
+        assert_eq!(simulation.effective.blocked.len(), 1);
+    }
+
+    #[test]
+    fn test_simulate_command_without_manifest_permissions() {
+        let temp_dir = tempdir().unwrap();
+        let manifest: PluginManifest = toml::from_str(
+            "[plugin]\nname = \"lint\"\nversion = \"0.1.0\"\n\n[commands.check]\nscript = \"./check.ts\"\n",
+        )
+        .unwrap();
+
+        let simulation = simulate_command(temp_dir.path(), &manifest, "check").unwrap();
+        assert_eq!(simulation.layers.len(), 1);
+        assert!(simulation.effective.blocked.is_empty());
+    }
+}
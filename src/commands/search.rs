@@ -0,0 +1,102 @@
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::{
+    cli::OutputFormat,
+    integrations::registry_index::{RegistryEntry, configured_registries, list_registry_plugins},    report::print_json,
+};
+
+#[derive(Debug, Serialize)]
+pub struct SearchResult {
+    #[serde(flatten)]
+    pub plugin: RegistryEntry,
+    pub registry: String,
+}
+
+/// Find plugins in the configured registries whose name or description contains `query`
+pub fn run_search(query: &str, output: OutputFormat) -> Result<()> {
+    let mut results = Vec::new();
+    for registry in configured_registries()? {
+        let entries = match list_registry_plugins(&registry) {
+            Ok(entries) => entries,
+            Err(e) => {
+                errln!("⚠️  Warning: Skipping registry {}: {:#}", registry, e);
+                continue;
+            }
+        };
+        results.extend(
+            entries
+                .into_iter()
+                .filter(|entry| matches_query(entry, query))
+                .map(|plugin| SearchResult {
+                    plugin,
+                    registry: registry.clone(),
+                }),
+        );
+    }
+
+    match output {
+        OutputFormat::Json => print_json(&results)?,
+        OutputFormat::Text => print_results(query, &results),
+    }
+
+    Ok(())
+}
+
+/// Case-insensitive match against the plugin's name and description
+pub fn matches_query(entry: &RegistryEntry, query: &str) -> bool {
+    let query = query.to_lowercase();
+    entry.name.to_lowercase().contains(&query)
+        || entry
+            .description
+            .as_ref()
+            .is_some_and(|description| description.to_lowercase().contains(&query))
+}
+
+fn print_results(query: &str, results: &[SearchResult]) {
+    if results.is_empty() {
+        outln!("🔍 No plugins match '{}'.", query);
+        return;
+    }
+
+    outln!("🔍 Plugins matching '{}':\n", query);
+    for result in results {
+        let version = result
+            .plugin
+            .version
+            .as_ref()
+            .map(|v| format!(" v{}", v))
+            .unwrap_or_default();
+        outln!("   {}{}", result.plugin.name, version);
+        if let Some(description) = &result.plugin.description {
+            outln!("      {}", description);
+        }
+        outln!("      from {}", result.registry);
+    }
+    outln!("\n→ Install one with `mis add <plugin>`.");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_matches_query_checks_name_and_description() {
+        let entry = RegistryEntry {
+            name: "k8s-deploy".to_string(),
+            version: Some("1.0.0".to_string()),
+            description: Some("Roll out to Kubernetes".to_string()),
+            url: None,
+            sha256: None,
+        };
+        assert!(matches_query(&entry, "deploy"));
+        assert!(matches_query(&entry, "KUBERNETES"));
+        assert!(!matches_query(&entry, "terraform"));
+
+        let entry = RegistryEntry {
+            description: None,
+            ..entry
+        };
+        assert!(!matches_query(&entry, "kubernetes"));
+    }
+}
@@ -0,0 +1,259 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    config::plugins::load_plugin_manifest, constants::PLUGIN_MANIFEST_FILE,
+    plugin_utils::get_plugin_path, utils::require_project_root,
+};
+
+/// Create an executable wrapper in .makeitso/bin that runs a plugin command
+pub fn create_shim(
+    alias: &str,
+    plugin_command: &str,
+    args: Vec<String>,
+    force: bool,
+) -> Result<()> {
+    validate_alias(alias)?;
+
+    let (plugin_name, command_name) = plugin_command
+        .split_once(':')
+        .ok_or_else(|| anyhow!("Invalid plugin format. Use <plugin_name>:<command_name>"))?;
+
+    // Make sure the target actually exists before handing out a wrapper for it
+    let plugin_path = get_plugin_path(plugin_name)?;
+    let manifest = load_plugin_manifest(&plugin_path.join(PLUGIN_MANIFEST_FILE))?;
+    if !manifest.commands.contains_key(command_name) {
+        anyhow::bail!(
+            "🛑 Command '{}' not found in plugin '{}'.\n\
+             → Run `mis info` to see available commands.",
+            command_name,
+            plugin_name
+        );
+    }
+
+    let bin_dir = require_project_root()?.join(".makeitso").join("bin");
+    let shim_path = write_shim(&bin_dir, alias, plugin_command, &args, force)?;
+
+    outln!("✅ Created shim '{}' → mis run {}", alias, plugin_command);
+    outln!("   → {}", shim_path.display());
+    outln!();
+    outln!("💡 Add the shims directory to your PATH to use it:");
+    if cfg!(windows) {
+        outln!("   $env:PATH = \"{};$env:PATH\"", bin_dir.display());
+    } else {
+        outln!("   export PATH=\"{}:$PATH\"", bin_dir.display());
+    }
+
+    Ok(())
+}
+
+/// Write the wrapper script for the current platform and return its path
+pub fn write_shim(
+    bin_dir: &Path,
+    alias: &str,
+    plugin_command: &str,
+    args: &[String],
+    force: bool,
+) -> Result<PathBuf> {
+    fs::create_dir_all(bin_dir)?;
+
+    let shim_path = if cfg!(windows) {
+        bin_dir.join(format!("{}.cmd", alias))
+    } else {
+        bin_dir.join(alias)
+    };
+
+    if shim_path.exists() && !force {
+        anyhow::bail!(
+            "🛑 Shim '{}' already exists at {}.\n\
+             → Use --force to overwrite it.",
+            alias,
+            shim_path.display()
+        );
+    }
+
+    let contents = if cfg!(windows) {
+        render_cmd_shim(plugin_command, args)?
+    } else {
+        render_sh_shim(plugin_command, args)
+    };
+    fs::write(&shim_path, contents)?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&shim_path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(shim_path)
+}
+
+fn validate_alias(alias: &str) -> Result<()> {
+    if alias.trim().is_empty() {
+        return Err(anyhow!("Shim alias cannot be empty"));
+    }
+    if !alias
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(anyhow!(
+            "Shim alias '{}' contains invalid characters (use letters, digits, '-' or '_')",
+            alias
+        ));
+    }
+    Ok(())
+}
+
+fn render_sh_shim(plugin_command: &str, args: &[String]) -> String {
+    let mut line = format!("exec mis run {}", sh_quote(plugin_command));
+    for arg in args {
+        line.push(' ');
+        line.push_str(&sh_quote(arg));
+    }
+    format!(
+        "#!/bin/sh\n# Generated by `mis shim` — re-run it to regenerate.\n{} \"$@\"\n",
+        line
+    )
+}
+
+fn render_cmd_shim(plugin_command: &str, args: &[String]) -> Result<String> {
+    let mut line = format!("mis run {}", cmd_quote(plugin_command));
+    for arg in args {
+        if arg.contains(['\r', '\n']) {
+            return Err(anyhow!(
+                "Shim argument {:?} contains a line break, which a .cmd file can't pass on",
+                arg
+            ));
+        }
+        line.push(' ');
+        line.push_str(&cmd_quote(arg));
+    }
+    Ok(format!(
+        "@echo off\r\nREM Generated by `mis shim` — re-run it to regenerate.\r\n{} %*\r\n",
+        line
+    ))
+}
+
+/// Quote a value for a line of a .cmd file. It's quoted the way Windows programs split
+/// their command line, then every cmd metacharacter (the quotes too, so cmd never sees a
+/// quoted section) gets a `^` and `%` is doubled, so cmd passes it on untouched.
+fn cmd_quote(value: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "-_:=.,/@+".contains(c);
+    if !value.is_empty() && value.chars().all(plain) {
+        return value.to_string();
+    }
+
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in value.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        // Backslashes only escape when a quote follows them
+        let count = if c == '"' { backslashes * 2 + 1 } else { backslashes };
+        quoted.push_str(&"\\".repeat(count));
+        quoted.push(c);
+        backslashes = 0;
+    }
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+
+    let mut escaped = String::new();
+    for c in quoted.chars() {
+        match c {
+            '%' => escaped.push_str("%%"),
+            '^' | '&' | '|' | '<' | '>' | '(' | ')' | '!' | '"' => {
+                escaped.push('^');
+                escaped.push(c);
+            }
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Single-quote a value for POSIX sh
+fn sh_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "'\\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_validate_alias_rejects_invalid_characters() {
+        assert!(validate_alias("deploy-staging").is_ok());
+        assert!(validate_alias("build_all2").is_ok());
+        assert!(validate_alias("").is_err());
+        assert!(validate_alias("deploy;rm").is_err());
+        assert!(validate_alias("../escape").is_err());
+    }
+
+    #[test]
+    fn test_render_sh_shim_bakes_in_args() {
+        let script = render_sh_shim(
+            "deploy:run",
+            &["--env".to_string(), "it's staging".to_string()],
+        );
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains("exec mis run 'deploy:run' '--env' 'it'\\''s staging' \"$@\""));
+    }
+
+    #[test]
+    fn test_render_cmd_shim_forwards_args() {
+        let script =
+            render_cmd_shim("deploy:run", &["--env".to_string(), "staging".to_string()]).unwrap();
+        assert!(script.contains("mis run deploy:run --env staging %*"));
+    }
+
+    #[test]
+    fn test_render_cmd_shim_escapes_metacharacters() {
+        let script = render_cmd_shim(
+            "deploy:run",
+            &[
+                "--msg".to_string(),
+                "a & b at 100%".to_string(),
+                "say \"hi\" | more".to_string(),
+                "C:\\dir with space\\".to_string(),
+            ],
+        )
+        .unwrap();
+        assert!(script.contains(
+            "mis run deploy:run --msg ^\"a ^& b at 100%%^\" ^\"say \\^\"hi\\^\" ^| more^\" \
+             ^\"C:\\dir with space\\\\^\" %*"
+        ));
+
+        assert!(render_cmd_shim("deploy:run", &["a\nb".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_write_shim_refuses_to_overwrite_without_force() {
+        let temp_dir = tempdir().unwrap();
+        let bin_dir = temp_dir.path().join("bin");
+
+        let path = write_shim(&bin_dir, "deploy-staging", "deploy:run", &[], false).unwrap();
+        assert!(path.exists());
+
+        let result = write_shim(&bin_dir, "deploy-staging", "deploy:run", &[], false);
+        assert!(result.is_err());
+        assert!(result.unwrap_err().to_string().contains("--force"));
+
+        assert!(write_shim(&bin_dir, "deploy-staging", "deploy:run", &[], true).is_ok());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_shim_is_executable() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let path = write_shim(temp_dir.path(), "lint", "ci:lint", &[], false).unwrap();
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o111, 0o111);
+    }
+}
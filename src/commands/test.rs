@@ -0,0 +1,447 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{Context, Result};
+use serde::Serialize;
+
+use crate::{
+    cli::{OutputFormat, toml_arg_values},
+    commands::run::{
+        RunOptions, ensure_plugin_runtime, execute_plugin, json_to_toml, unknown_command_message,
+        validate_plugin_exists, validated_plugin_args,
+    },
+    config::plugins::{apply_config_schema, load_plugin_manifest, read_plugin_user_config},
+    constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE},
+    models::{
+        ExecutionContext, PluginManifest, PluginMeta, PluginRunResult, PluginTestCase,
+        PluginUserConfig, TestExpectation,
+    },
+    report::print_json,
+    run_log::RunLogEntry,
+    utils::require_project_root,
+};
+
+/// How long a case may run when it doesn't set `timeout`
+const DEFAULT_TEST_TIMEOUT_SECS: u64 = 60;
+
+/// Outcome of one test case
+#[derive(Debug, Serialize)]
+pub struct TestReport {
+    pub name: String,
+    pub command: String,
+    pub passed: bool,
+    pub failures: Vec<String>,
+    pub duration_ms: u64,
+}
+
+/// `mis test <plugin>[:<command>]`: run the plugin's test cases and check their results
+pub fn run_plugin_tests(target: &str, output: OutputFormat) -> Result<()> {
+    let (plugin_name, command_filter) = match target.split_once(':') {
+        Some((plugin, command)) => (plugin, Some(command)),
+        None => (target, None),
+    };
+
+    let project_root = require_project_root()?;
+    let plugin_path = validate_plugin_exists(plugin_name)?;
+    let manifest = load_plugin_manifest(&plugin_path.join(PLUGIN_MANIFEST_FILE))?;
+    // Checked against the schema per case, since a case's config can fill in missing keys
+    let user_config = read_plugin_user_config(&plugin_path.join(PLUGIN_CONFIG_FILE))?;
+
+    let mut cases = collect_test_cases(&manifest, &plugin_path)?;
+    if let Some(command) = command_filter {
+        if !manifest.commands.contains_key(command) {
+            anyhow::bail!(unknown_command_message(&manifest, plugin_name, command));
+        }
+        cases.retain(|case| case.command.as_deref() == Some(command));
+    }
+    if cases.is_empty() {
+        anyhow::bail!(
+            "🛑 No tests found for '{}'.\n\
+             → Add a [commands.<name>.test] table to its manifest.toml, or .toml files under {}.",
+            target,
+            plugin_path.join("tests").display()
+        );
+    }
+
+    ensure_plugin_runtime(&manifest, false)?;
+
+    outln!(
+        "🧪 Running {} test{} for '{}'\n",
+        cases.len(),
+        if cases.len() == 1 { "" } else { "s" },
+        target
+    );
+
+    let mut reports = Vec::new();
+    for case in &cases {
+        let started = Instant::now();
+        let outcome = run_test_case(&project_root, &plugin_path, &manifest, &user_config, case);
+        let failures = check_expectations(&case.expect, &outcome);
+        let report = TestReport {
+            name: case.name.clone().unwrap_or_default(),
+            command: case.command.clone().unwrap_or_default(),
+            passed: failures.is_empty(),
+            failures,
+            duration_ms: started.elapsed().as_millis() as u64,
+        };
+
+        if report.passed {
+            outln!("✅ {} ({}ms)", report.name, report.duration_ms);
+        } else {
+            outln!("❌ {} ({}ms)", report.name, report.duration_ms);
+            for failure in &report.failures {
+                outln!("      → {}", failure);
+            }
+        }
+        reports.push(report);
+    }
+
+    if output == OutputFormat::Json {
+        print_json(&reports)?;
+    }
+
+    let failed = reports.iter().filter(|report| !report.passed).count();
+    if failed > 0 {
+        anyhow::bail!("🛑 {} of {} tests failed.", failed, reports.len());
+    }
+    outln!("\n✅ All {} tests passed.", reports.len());
+
+    Ok(())
+}
+
+/// Cases from `[commands.<name>.test]` (commands in name order), then `tests/*.toml` (file order)
+pub fn collect_test_cases(
+    manifest: &PluginManifest,
+    plugin_path: &Path,
+) -> Result<Vec<PluginTestCase>> {
+    let mut cases = Vec::new();
+
+    let mut command_names: Vec<&String> = manifest.commands.keys().collect();
+    command_names.sort();
+    for command_name in command_names {
+        let Some(tests) = manifest.commands[command_name].test.clone() else {
+            continue;
+        };
+        let command_cases = tests.into_cases();
+        let numbered = command_cases.len() > 1;
+        for (i, mut case) in command_cases.into_iter().enumerate() {
+            case.command = Some(command_name.clone());
+            if case.name.is_none() {
+                case.name = Some(if numbered {
+                    format!("{} #{}", command_name, i + 1)
+                } else {
+                    command_name.clone()
+                });
+            }
+            cases.push(case);
+        }
+    }
+
+    let tests_dir = plugin_path.join("tests");
+    if tests_dir.is_dir() {
+        let mut files: Vec<_> = fs::read_dir(&tests_dir)
+            .with_context(|| format!("Failed to read {}", tests_dir.display()))?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "toml"))
+            .collect();
+        files.sort();
+
+        for file in files {
+            let content = fs::read_to_string(&file)
+                .with_context(|| format!("Failed to read {}", file.display()))?;
+            let mut case: PluginTestCase = toml::from_str(&content)
+                .with_context(|| format!("🛑 Invalid test case in {}", file.display()))?;
+
+            let Some(command) = case.command.as_deref() else {
+                anyhow::bail!(
+                    "🛑 {} doesn't say which command to run.\n→ Add `command = \"<name>\"` to it.",
+                    file.display()
+                );
+            };
+            if !manifest.commands.contains_key(command) {
+                anyhow::bail!(
+                    "{}\n→ Referenced by {}",
+                    unknown_command_message(manifest, &manifest.plugin.name, command),
+                    file.display()
+                );
+            }
+            if case.name.is_none() {
+                case.name = file
+                    .file_stem()
+                    .map(|stem| stem.to_string_lossy().to_string());
+            }
+            cases.push(case);
+        }
+    }
+
+    Ok(cases)
+}
+
+/// Run one case's command in the sandbox with a context built from the case alone
+fn run_test_case(
+    project_root: &Path,
+    plugin_path: &Path,
+    manifest: &PluginManifest,
+    user_config: &PluginUserConfig,
+    case: &PluginTestCase,
+) -> Result<Option<PluginRunResult>> {
+    let command_name = case.command.as_deref().unwrap_or_default();
+    let command = &manifest.commands[command_name];
+
+    let raw_args: HashMap<String, Vec<String>> = case
+        .args
+        .iter()
+        .map(|(name, value)| (name.clone(), toml_arg_values(value)))
+        .collect();
+    let mut plugin_args =
+        validated_plugin_args(raw_args, command, &manifest.plugin.name, command_name)?;
+    if case.dry_run {
+        plugin_args.insert("dry_run".to_string(), serde_json::Value::Bool(true));
+    }
+
+    let mut config = user_config.clone();
+    config.config.extend(case.config.clone());
+    apply_config_schema(&mut config, manifest, &plugin_path.join(PLUGIN_CONFIG_FILE))?;
+
+    let meta = PluginMeta {
+        name: manifest.plugin.name.clone(),
+        description: manifest.plugin.description.clone(),
+        version: manifest.plugin.version.clone(),
+        registry: None,
+        pin: None,
+        execution: None,
+    };
+    let mut ctx = ExecutionContext::from_parts(
+        plugin_args
+            .into_iter()
+            .map(|(k, v)| (k, json_to_toml(v)))
+            .collect(),
+        manifest,
+        &config,
+        case.project_variables.clone().into_iter().collect(),
+        project_root.to_string_lossy().to_string(),
+        meta,
+        case.dry_run,
+    )?;
+    ctx.environment = case.environment.clone();
+    ctx.events = serde_json::to_value(&case.events)?
+        .as_object()
+        .cloned()
+        .unwrap_or_default();
+
+    // Keep the real artifacts and state of earlier runs out of reach
+    let artifacts_dir = tempfile::tempdir()?;
+    ctx.artifacts_dir = artifacts_dir.path().to_string_lossy().to_string();
+    let state_dir = tempfile::tempdir()?;
+    ctx.state_dir = state_dir.path().to_string_lossy().to_string();
+
+    let label = case.name.clone().unwrap_or_default();
+    let options = RunOptions {
+        // Tagging output with the case name also keeps prompts off: tests must run unattended
+        output_prefix: Some(&label),
+        timeout: Some(Duration::from_secs(
+            case.timeout.unwrap_or(DEFAULT_TEST_TIMEOUT_SECS),
+        )),
+        ..RunOptions::default()
+    };
+    let mut run_log = RunLogEntry::start(
+        &manifest.plugin.name,
+        command_name,
+        &serde_json::Map::new(),
+        case.dry_run,
+        case.environment.as_deref(),
+    );
+
+    execute_plugin(
+        &plugin_path.to_path_buf(),
+        &command.script,
+        &ctx,
+        manifest,
+        command_name,
+        &options,
+        &mut run_log,
+    )
+}
+
+/// Everything about the run that doesn't match the case's expectations
+pub fn check_expectations(
+    expect: &TestExpectation,
+    outcome: &Result<Option<PluginRunResult>>,
+) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    let result = match outcome {
+        Ok(result) => {
+            if !expect.success {
+                failures.push("expected the run to fail, but it succeeded".to_string());
+            }
+            result.clone().unwrap_or_default()
+        }
+        Err(e) => {
+            let error = format!("{:#}", e);
+            if expect.success {
+                failures.push(format!(
+                    "run failed: {}",
+                    error.lines().next().unwrap_or_default()
+                ));
+            } else if let Some(expected) = &expect.message
+                && !error.contains(expected.as_str())
+            {
+                failures.push(format!(
+                    "error was '{}', expected it to contain '{}'",
+                    error.lines().next().unwrap_or_default(),
+                    expected
+                ));
+            }
+            return failures;
+        }
+    };
+
+    if let Some(expected) = &expect.message {
+        match &result.message {
+            Some(message) if message.contains(expected.as_str()) => {}
+            Some(message) => failures.push(format!(
+                "message was '{}', expected it to contain '{}'",
+                message, expected
+            )),
+            None => failures.push(format!(
+                "no message in the result, expected one containing '{}'",
+                expected
+            )),
+        }
+    }
+
+    for (key, expected) in &expect.outputs {
+        let expected = serde_json::to_value(expected).unwrap_or_default();
+        match result.outputs.get(key) {
+            Some(actual) if *actual == expected => {}
+            Some(actual) => failures.push(format!(
+                "output '{}' was {}, expected {}",
+                key, actual, expected
+            )),
+            None => failures.push(format!("missing output '{}'", key)),
+        }
+    }
+
+    for event in &expect.events {
+        if !result
+            .events
+            .iter()
+            .any(|published| &published.name == event)
+        {
+            failures.push(format!("event '{}' was not published", event));
+        }
+    }
+
+    for path in &expect.changes {
+        if !result.changes.iter().any(|change| &change.path == path) {
+            failures.push(format!("no change planned for '{}'", path));
+        }
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    const MANIFEST: &str = r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[commands.prod]
+script = "./prod.ts"
+
+[[commands.prod.test]]
+name = "dry run"
+dry_run = true
+args = { region = "eu" }
+
+[[commands.prod.test]]
+expect = { success = false, message = "denied" }
+
+[commands.staging]
+script = "./staging.ts"
+
+[commands.staging.test]
+config = { url = "http://localhost" }
+expect = { outputs = { url = "http://localhost" } }
+"#;
+
+    #[test]
+    fn test_collect_test_cases_from_manifest_and_tests_dir() {
+        let temp_dir = tempdir().unwrap();
+        let manifest: PluginManifest = toml::from_str(MANIFEST).unwrap();
+
+        let tests_dir = temp_dir.path().join("tests");
+        fs::create_dir_all(&tests_dir).unwrap();
+        fs::write(
+            tests_dir.join("smoke.toml"),
+            "command = \"staging\"\n[expect]\nevents = [\"deployed\"]\n",
+        )
+        .unwrap();
+        fs::write(tests_dir.join("notes.md"), "not a test").unwrap();
+
+        let cases = collect_test_cases(&manifest, temp_dir.path()).unwrap();
+        let names: Vec<&str> = cases.iter().map(|c| c.name.as_deref().unwrap()).collect();
+        assert_eq!(names, vec!["dry run", "prod #2", "staging", "smoke"]);
+        assert_eq!(cases[0].command.as_deref(), Some("prod"));
+        assert!(cases[0].dry_run);
+        assert!(!cases[1].expect.success);
+        assert!(cases[2].expect.success);
+        assert_eq!(cases[3].expect.events, vec!["deployed"]);
+
+        // A test file for a command that doesn't exist is an error, not a skipped case
+        fs::write(tests_dir.join("typo.toml"), "command = \"stagign\"\n").unwrap();
+        let err = collect_test_cases(&manifest, temp_dir.path())
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Did you mean 'staging'?"), "{}", err);
+    }
+
+    #[test]
+    fn test_check_expectations() {
+        let result: PluginRunResult = serde_json::from_value(serde_json::json!({
+            "message": "Deployed v1 to staging",
+            "outputs": { "version": "v1", "replicas": 3 },
+            "events": [{ "name": "deployed" }],
+        }))
+        .unwrap();
+        let expect: TestExpectation = toml::from_str(
+            "message = \"Deployed\"\nevents = [\"deployed\"]\n[outputs]\nversion = \"v1\"\nreplicas = 3\n",
+        )
+        .unwrap();
+        assert!(check_expectations(&expect, &Ok(Some(result.clone()))).is_empty());
+
+        let expect: TestExpectation = toml::from_str(
+            "message = \"prod\"\nevents = [\"rolled_back\"]\nchanges = [\"VERSION\"]\n[outputs]\nversion = \"v2\"\nregion = \"eu\"\n",
+        )
+        .unwrap();
+        let failures = check_expectations(&expect, &Ok(Some(result)));
+        assert_eq!(failures.len(), 5, "{:?}", failures);
+        assert!(failures.contains(&"output 'version' was \"v1\", expected \"v2\"".to_string()));
+        assert!(failures.contains(&"missing output 'region'".to_string()));
+
+        // Expected failures match against the error instead of the result
+        let expect: TestExpectation =
+            toml::from_str("success = false\nmessage = \"denied\"\n").unwrap();
+        let denied = Err(anyhow::anyhow!("🛑 Plugin reported failure: access denied"));
+        assert!(check_expectations(&expect, &denied).is_empty());
+        assert_eq!(
+            check_expectations(&expect, &Ok(None)),
+            vec![
+                "expected the run to fail, but it succeeded",
+                "no message in the result, expected one containing 'denied'"
+            ]
+        );
+        assert_eq!(
+            check_expectations(&TestExpectation::default(), &denied),
+            vec!["run failed: 🛑 Plugin reported failure: access denied"]
+        );
+    }
+}
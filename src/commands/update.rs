@@ -1,27 +1,79 @@
-use crate::commands::add::{copy_dir_recursive, install_plugin_from_path};
-use crate::config::plugins::load_plugin_manifest;
-use crate::git_utils::shallow_clone_repo;
+use crate::capabilities::parse_version;
+use crate::cli::prompt_user;
+use crate::commands::add::{copy_dir_recursive, install_plugin_from_path, manifest_version};
+use crate::commands::config::{flatten, lookup, set_path};
+use crate::config::placeholders::find_placeholders;
+use crate::config::plugins::{load_plugin_manifest, set_manifest_plugin_fields};
+use crate::constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE};
+use crate::git_utils::head_commit;
+use crate::integrations::registry_http::fetch_registry_for;
+use crate::integrations::registry_index::fetch_registry_index;
+use crate::models::PluginManifest;
+use crate::plugin_lock::{
+    ChecksumDrift, PluginLock, compare_checksums, hash_plugin_dir, record_plugin_checksums,
+};
 use crate::plugin_utils::{get_all_plugin_names, get_plugin_path};
-use crate::security::validate_registry_url;
-use anyhow::Result;
+use crate::report::{OutcomeStatus, PluginOutcome, PluginsReport, json_output, print_json};
+use crate::security::validate_registry_source;
+use crate::utils::require_project_root;
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fs;
-use tempfile::TempDir;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, TableLike};
+
+/// How an installed plugin compares to the latest version in its registry
+#[derive(Debug, PartialEq)]
+pub enum UpdateStatus {
+    UpToDate,
+    Available,
+    /// A newer version exists, but the plugin is pinned to this one
+    Pinned(String),
+}
+
+/// What updating a plugin would change, ignoring its config.toml
+#[derive(Debug, PartialEq)]
+pub struct UpdateDiff {
+    /// `missing` holds files the update removes
+    pub files: ChecksumDrift,
+    pub from_version: String,
+    pub to_version: String,
+}
+
+impl UpdateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.files.is_clean() && self.from_version == self.to_version
+    }
+}
+
+/// Update a specific plugin or all plugins to the latest versions.
+///
+/// With `check`, only report which plugins have newer versions available. With `diff`,
+/// show what each update changes and ask before overwriting the plugin.
+pub fn update_plugin(plugin: Option<String>, dry_run: bool, check: bool, diff: bool) -> Result<()> {
+    if check {
+        return check_for_updates(plugin.as_deref());
+    }
 
-/// Update a specific plugin or all plugins to the latest versions
-pub fn update_plugin(plugin: Option<String>, dry_run: bool) -> Result<()> {
     match plugin {
         Some(plugin_name) => {
-            update_single_plugin(&plugin_name, dry_run)?;
+            let outcome = update_single_plugin(&plugin_name, dry_run, diff)?;
+            if json_output() {
+                print_json(&PluginsReport::new(dry_run, vec![outcome]))?;
+            }
         }
         None => {
-            update_all_plugins(dry_run)?;
+            update_all_plugins(dry_run, diff)?;
         }
     }
 
     Ok(())
 }
 
-fn update_single_plugin(plugin_name: &str, dry_run: bool) -> Result<()> {
+/// Returns whether the plugin was (or would be) updated; pinned plugins already at
+/// their pinned version, unchanged plugins and declined diffs are skipped
+fn update_single_plugin(plugin_name: &str, dry_run: bool, diff: bool) -> Result<PluginOutcome> {
     // This will validate that the plugin exists and return its path
     let plugin_path = get_plugin_path(plugin_name)?;
 
@@ -30,7 +82,7 @@ fn update_single_plugin(plugin_name: &str, dry_run: bool) -> Result<()> {
     let manifest = load_plugin_manifest(&manifest_path)?;
 
     // Check if registry field exists
-    let registry_url = manifest.plugin.registry.ok_or_else(|| {
+    let registry_url = manifest.plugin.registry.clone().ok_or_else(|| {
         anyhow::anyhow!(
             "🛑 Plugin '{}' has no registry field in manifest.toml.\n\
              → This plugin cannot be updated automatically.\n\
@@ -40,60 +92,112 @@ fn update_single_plugin(plugin_name: &str, dry_run: bool) -> Result<()> {
     })?;
 
     // Validate registry URL for security
-    if let Err(security_error) = validate_registry_url(&registry_url) {
+    if let Err(security_error) = validate_registry_source(&registry_url) {
         return Err(anyhow::anyhow!(
             "🛑 Security validation failed for registry '{}': {}\n\
-             → Registry URLs must be secure HTTPS git repositories from trusted sources.",
+             → Registry sources must be secure HTTPS git repositories from trusted sources, or local directories (`path:<dir>`).",
             registry_url,
             security_error
         ));
     }
 
-    if dry_run {
-        println!(
-            "📝 Would update plugin '{}' from {}",
-            plugin_name, registry_url
+    let outcome = |status, version: Option<String>| PluginOutcome {
+        version,
+        registry: Some(registry_url.clone()),
+        ..PluginOutcome::new(plugin_name, status)
+    };
+
+    let project_root = require_project_root()?;
+    let pin = plugin_pin(&project_root, plugin_name, &manifest)?;
+    if let Some(pin) = &pin
+        && versions_match(&manifest.plugin.version, pin)
+    {
+        outln!(
+            "📌 Plugin '{}' is pinned to {}; skipping.",
+            plugin_name, pin
         );
-        return Ok(());
+        return Ok(outcome(OutcomeStatus::Skipped, Some(manifest.plugin.version.clone())));
     }
 
-    println!("🔄 Updating plugin '{}'...", plugin_name);
-
-    // Clone the registry to a temporary directory
-    let temp_dir = TempDir::new()?;
-    let temp_path = temp_dir.path().to_string_lossy().to_string();
+    if dry_run && !diff {
+        match &pin {
+            Some(pin) => outln!(
+                "📝 Would update plugin '{}' to pinned version {} from {}",
+                plugin_name, pin, registry_url
+            ),
+            None => outln!(
+                "📝 Would update plugin '{}' from {}",
+                plugin_name, registry_url
+            ),
+        }
+        return Ok(outcome(OutcomeStatus::WouldUpdate, pin));
+    }
 
-    if let Err(e) = shallow_clone_repo(registry_url.clone(), temp_path) {
-        return Err(anyhow::anyhow!(
-            "❌ Failed to clone {}: {}",
-            registry_url,
-            e
-        ));
+    if !dry_run {
+        outln!("🔄 Updating plugin '{}'...", plugin_name);
     }
 
-    // Find the plugin in the cloned repository
-    let root_plugin_path = temp_dir.path().join(plugin_name);
-    let plugins_subdir_path = temp_dir.path().join("plugins").join(plugin_name);
+    // Updates always want the registry's latest state, so skip the cache freshness window
+    let clone_dir = fetch_registry_for(&registry_url, &[plugin_name.to_string()], true)?;
 
-    let source_path = if plugins_subdir_path.exists() && plugins_subdir_path.is_dir() {
-        plugins_subdir_path
-    } else if root_plugin_path.exists() && root_plugin_path.is_dir() {
-        root_plugin_path
-    } else {
-        return Err(anyhow::anyhow!(
+    // Find the plugin in the cloned repository
+    let source_path = find_plugin_source(&clone_dir, plugin_name).ok_or_else(|| {
+        anyhow::anyhow!(
             "❌ Plugin '{}' not found in registry {}",
             plugin_name,
             registry_url
-        ));
-    };
+        )
+    })?;
+
+    // Registries only serve their latest version, so a pin can only be honored when it matches
+    if let Some(pin) = &pin {
+        let available = load_plugin_manifest(&source_path.join(PLUGIN_MANIFEST_FILE))?
+            .plugin
+            .version;
+        if !versions_match(&available, pin) {
+            return Err(anyhow::anyhow!(
+                "🛑 Plugin '{}' is pinned to {}, but {} has {}.\n\
+                 → Change or remove the pin in plugins.lock or manifest.toml to update it.",
+                plugin_name,
+                pin,
+                registry_url,
+                available
+            ));
+        }
+    }
+
+    if diff {
+        let changes = diff_plugin_update(&plugin_path, &source_path)?;
+        if changes.is_empty() {
+            outln!(
+                "✅ Plugin '{}' already matches {}",
+                plugin_name, registry_url
+            );
+            return Ok(outcome(OutcomeStatus::Skipped, Some(changes.from_version)));
+        }
+        print_update_diff(plugin_name, &changes);
+        if dry_run {
+            outln!("📝 Would apply these changes to '{}'", plugin_name);
+            return Ok(outcome(OutcomeStatus::WouldUpdate, Some(changes.to_version)));
+        }
+        if !prompt_user(&format!("Update '{}'?", plugin_name))? {
+            outln!("❌ Update of '{}' cancelled.", plugin_name);
+            return Ok(outcome(OutcomeStatus::Skipped, Some(changes.from_version)));
+        }
+    }
 
-    // Preserve existing config.toml
-    let config_path = plugin_path.join("config.toml");
+    // Keep the user's config.toml, to merge with the new version's defaults
+    let config_path = plugin_path.join(PLUGIN_CONFIG_FILE);
     let existing_config = if config_path.exists() {
         Some(fs::read_to_string(&config_path)?)
     } else {
         None
     };
+    let old_default_config = PluginLock::load(&project_root)?
+        .plugins
+        .get(plugin_name)
+        .and_then(|locked| locked.default_config.clone());
+    let new_default_config = fs::read_to_string(source_path.join(PLUGIN_CONFIG_FILE)).ok();
 
     // Remove existing plugin directory
     if plugin_path.exists() {
@@ -106,62 +210,128 @@ fn update_single_plugin(plugin_name: &str, dry_run: bool) -> Result<()> {
     // Update manifest.toml to include registry field (in case it wasn't there)
     let new_manifest_path = plugin_path.join("manifest.toml");
     if new_manifest_path.exists() {
-        update_manifest_with_registry(&new_manifest_path, &registry_url)?;
+        update_manifest_with_registry(
+            &new_manifest_path,
+            &registry_url,
+            manifest.plugin.pin.as_deref(),
+        )?;
     }
 
-    // Restore preserved config.toml if it existed
-    if let Some(config_content) = existing_config {
-        fs::write(&config_path, config_content)?;
-        println!("📋 Preserved existing config.toml");
+    match (existing_config, &new_default_config) {
+        (Some(user_config), Some(new_default)) => {
+            let merge =
+                merge_plugin_config(old_default_config.as_deref(), &user_config, new_default)?;
+            fs::write(&config_path, &merge.contents)?;
+            print_config_merge(plugin_name, &merge);
+        }
+        (Some(user_config), None) => {
+            fs::write(&config_path, user_config)?;
+            outln!("📋 Preserved existing config.toml");
+        }
+        (None, _) => {}
     }
 
-    println!(
+    // Record checksums so `mis verify` can detect later changes
+    record_plugin_checksums(
+        &project_root,
+        plugin_name,
+        Some(&registry_url),
+        head_commit(&clone_dir).as_deref(),
+        new_default_config.as_deref(),
+        &plugin_path,
+    )?;
+
+    outln!(
         "✅ Plugin '{}' updated successfully from {}",
         plugin_name, registry_url
     );
-    Ok(())
+    Ok(outcome(OutcomeStatus::Updated, manifest_version(&plugin_path)))
 }
 
-fn update_all_plugins(dry_run: bool) -> Result<()> {
+fn update_all_plugins(dry_run: bool, diff: bool) -> Result<()> {
     let plugins = get_all_plugin_names()?;
 
     if plugins.is_empty() {
-        println!("📋 No plugins found to update.");
+        outln!("📋 No plugins found to update.");
+        if json_output() {
+            print_json(&PluginsReport::new(dry_run, Vec::new()))?;
+        }
         return Ok(());
     }
 
-    if dry_run {
-        println!("📝 Would update {} plugin(s):", plugins.len());
+    if dry_run && !diff {
+        outln!("📝 Would update {} plugin(s):", plugins.len());
+        let mut outcomes = Vec::new();
         for plugin in &plugins {
             match get_plugin_registry(plugin) {
-                Ok(registry) => println!("  - {} (from {})", plugin, registry),
-                Err(_) => println!("  - {} (no registry - cannot update)", plugin),
+                Ok(registry) => {
+                    outln!("  - {} (from {})", plugin, registry);
+                    outcomes.push(PluginOutcome {
+                        registry: Some(registry),
+                        ..PluginOutcome::new(plugin, OutcomeStatus::WouldUpdate)
+                    });
+                }
+                Err(e) => {
+                    outln!("  - {} (no registry - cannot update)", plugin);
+                    outcomes.push(PluginOutcome {
+                        error: Some(e.to_string()),
+                        ..PluginOutcome::new(plugin, OutcomeStatus::Failed)
+                    });
+                }
             }
         }
+        if json_output() {
+            print_json(&PluginsReport::new(dry_run, outcomes))?;
+        }
         return Ok(());
     }
 
-    println!("🔄 Updating {} plugin(s)...", plugins.len());
+    outln!("🔄 Updating {} plugin(s)...", plugins.len());
     let mut updated_count = 0;
+    let mut skipped_count = 0;
     let mut failed_count = 0;
+    let mut outcomes = Vec::new();
 
     for plugin in &plugins {
-        println!("  - Updating '{}'...", plugin);
-        match update_single_plugin(plugin, false) {
-            Ok(()) => {
-                updated_count += 1;
+        outln!("  - Updating '{}'...", plugin);
+        match update_single_plugin(plugin, dry_run, diff) {
+            Ok(outcome) => {
+                if outcome.status == OutcomeStatus::Skipped {
+                    skipped_count += 1;
+                } else {
+                    updated_count += 1;
+                }
+                outcomes.push(outcome);
             }
             Err(e) => {
-                println!("    ❌ Failed to update '{}': {}", plugin, e);
+                outln!("    ❌ Failed to update '{}': {}", plugin, e);
                 failed_count += 1;
+                outcomes.push(PluginOutcome {
+                    error: Some(format!("{:#}", e)),
+                    ..PluginOutcome::new(plugin, OutcomeStatus::Failed)
+                });
             }
         }
     }
 
-    if failed_count == 0 {
-        println!("✅ All {} plugins updated successfully", updated_count);
+    if json_output() {
+        print_json(&PluginsReport::new(dry_run, outcomes))?;
+    }
+
+    if dry_run {
+        outln!(
+            "📝 Would update {} plugins, {} skipped, {} failed",
+            updated_count, skipped_count, failed_count
+        );
+    } else if failed_count == 0 && skipped_count == 0 {
+        outln!("✅ All {} plugins updated successfully", updated_count);
+    } else if failed_count == 0 {
+        outln!(
+            "✅ Updated {} plugins, {} skipped",
+            updated_count, skipped_count
+        );
     } else {
-        println!(
+        outln!(
             "⚠️  Updated {} plugins, {} failed",
             updated_count, failed_count
         );
@@ -170,6 +340,427 @@ fn update_all_plugins(dry_run: bool) -> Result<()> {
     Ok(())
 }
 
+/// Compare an installed plugin with the registry's copy.
+///
+/// manifest.toml is compared after carrying over the fields `mis update` sets itself
+/// (registry and pin), so only real changes show up.
+pub fn diff_plugin_update(installed_dir: &Path, new_dir: &Path) -> Result<UpdateDiff> {
+    let mut installed_files = hash_plugin_dir(installed_dir)?;
+    let mut new_files = hash_plugin_dir(new_dir)?;
+    installed_files.remove(PLUGIN_MANIFEST_FILE);
+    new_files.remove(PLUGIN_MANIFEST_FILE);
+    let mut files = compare_checksums(&installed_files, &new_files);
+
+    let installed = load_plugin_manifest(&installed_dir.join(PLUGIN_MANIFEST_FILE))?;
+    let mut new = load_plugin_manifest(&new_dir.join(PLUGIN_MANIFEST_FILE))?;
+    new.plugin.registry = installed.plugin.registry.clone();
+    new.plugin.pin = new.plugin.pin.or_else(|| installed.plugin.pin.clone());
+    if toml::to_string(&installed)? != toml::to_string(&new)? {
+        files.modified.push(PLUGIN_MANIFEST_FILE.to_string());
+        files.modified.sort();
+    }
+
+    Ok(UpdateDiff {
+        files,
+        from_version: installed.plugin.version,
+        to_version: new.plugin.version,
+    })
+}
+
+fn print_update_diff(plugin_name: &str, changes: &UpdateDiff) {
+    if changes.from_version == changes.to_version {
+        outln!(
+            "📋 Changes to plugin '{}' ({}, version unchanged):",
+            plugin_name, changes.from_version
+        );
+    } else {
+        outln!(
+            "📋 Changes to plugin '{}' ({} → {}):",
+            plugin_name, changes.from_version, changes.to_version
+        );
+    }
+    changes.files.print("   ");
+    outln!(
+        "   {} modified, {} removed, {} added (your config.toml is merged, not replaced)",
+        changes.files.modified.len(),
+        changes.files.missing.len(),
+        changes.files.added.len()
+    );
+}
+
+/// The result of merging a user's config.toml with a new plugin version's defaults
+#[derive(Debug, Default, PartialEq)]
+pub struct ConfigMerge {
+    pub contents: String,
+
+    /// Keys the new version introduced, set to their defaults
+    pub added: Vec<String>,
+
+    /// Keys the user never changed whose default changed, set to the new default
+    pub updated: Vec<String>,
+
+    /// Keys the new version dropped that still had their old default, removed
+    pub removed: Vec<String>,
+
+    /// Keys the new version doesn't have that hold the user's own value, kept
+    pub obsolete: Vec<String>,
+
+    /// Dropped and introduced keys with the same default, which look like a rename
+    pub renamed: Vec<(String, String)>,
+
+    /// New keys whose default asks for a value (`{{ ask "..." }}`), left for the user to add
+    pub needs_value: Vec<String>,
+}
+
+/// Three-way merge of config.toml: `base` is the default the user's file started from (none
+/// for plugins installed before mis recorded it), `user` the file as edited and `new_default`
+/// the one the new version ships. The user's values and formatting win; new keys get their
+/// defaults, and defaults the user never touched follow the plugin.
+pub fn merge_plugin_config(
+    base: Option<&str>,
+    user: &str,
+    new_default: &str,
+) -> Result<ConfigMerge> {
+    let leaves = |contents: &str, which: &str| -> Result<BTreeMap<String, toml::Value>> {
+        let table: toml::Table = toml::from_str(contents)
+            .with_context(|| format!("🛑 Failed to parse the {} config.toml", which))?;
+        let mut entries = Vec::new();
+        flatten("", &toml::Value::Table(table), &mut entries);
+        Ok(entries.into_iter().collect())
+    };
+    let base = base.map(|base| leaves(base, "previously installed")).transpose()?;
+    let user_values = leaves(user, "project's")?;
+    let new_values = leaves(new_default, "new")?;
+
+    let mut doc: DocumentMut = user
+        .parse()
+        .context("🛑 Failed to parse the project's config.toml")?;
+    let new_doc: DocumentMut = new_default.parse()?;
+    let mut merge = ConfigMerge::default();
+
+    for (key, new_value) in &new_values {
+        let base_value = base.as_ref().and_then(|base| base.get(key));
+        match user_values.get(key) {
+            // Deleted by the user, or a section the user turned into a value
+            None if base_value.is_some() || lookup(doc.as_item(), key).is_some() => {}
+            None if !find_placeholders(&new_value.to_string()).is_empty() => {
+                merge.needs_value.push(key.clone());
+            }
+            None => {
+                let Some(Ok(mut value)) = lookup(new_doc.as_item(), key)
+                    .cloned()
+                    .map(toml_edit::Item::into_value)
+                else {
+                    continue;
+                };
+                value.decor_mut().clear();
+                if set_path(&mut doc, key, value).is_ok() {
+                    merge.added.push(key.clone());
+                }
+            }
+            Some(user_value) => {
+                if base_value == Some(user_value)
+                    && user_value != new_value
+                    && let Some(Ok(value)) = lookup(new_doc.as_item(), key)
+                        .cloned()
+                        .map(toml_edit::Item::into_value)
+                    && set_path(&mut doc, key, value).is_ok()
+                {
+                    merge.updated.push(key.clone());
+                }
+            }
+        }
+    }
+
+    for (key, user_value) in &user_values {
+        if new_values.contains_key(key) {
+            continue;
+        }
+        match base.as_ref().map(|base| base.get(key)) {
+            Some(Some(base_value)) if base_value == user_value => {
+                let parts: Vec<&str> = key.split('.').collect();
+                remove_path(doc.as_table_mut(), &parts);
+                merge.removed.push(key.clone());
+            }
+            // Added by the user
+            Some(None) => {}
+            _ => merge.obsolete.push(key.clone()),
+        }
+    }
+
+    if let Some(base) = &base {
+        let dropped: Vec<(&String, &toml::Value)> =
+            base.iter().filter(|(key, _)| !new_values.contains_key(*key)).collect();
+        let introduced: Vec<(&String, &toml::Value)> =
+            new_values.iter().filter(|(key, _)| !base.contains_key(*key)).collect();
+        for (old, old_value) in &dropped {
+            let matches: Vec<&String> = introduced
+                .iter()
+                .filter(|(_, value)| value == old_value)
+                .map(|(key, _)| *key)
+                .collect();
+            let unique = dropped.iter().filter(|(_, value)| value == old_value).count() == 1;
+            if let [new] = matches.as_slice()
+                && unique
+            {
+                merge.renamed.push((old.to_string(), new.to_string()));
+            }
+        }
+    }
+
+    merge.contents = doc.to_string();
+    Ok(merge)
+}
+
+/// Remove a dotted key, and any table it leaves empty
+fn remove_path(table: &mut dyn TableLike, parts: &[&str]) -> bool {
+    let [first, rest @ ..] = parts else {
+        return false;
+    };
+    if rest.is_empty() {
+        return table.remove(first).is_some();
+    }
+    let Some(child) = table.get_mut(first).and_then(|item| item.as_table_like_mut()) else {
+        return false;
+    };
+    let removed = remove_path(child, rest);
+    if removed && child.is_empty() {
+        table.remove(first);
+    }
+    removed
+}
+
+fn print_config_merge(plugin_name: &str, merge: &ConfigMerge) {
+    let changed = !(merge.added.is_empty()
+        && merge.updated.is_empty()
+        && merge.removed.is_empty()
+        && merge.obsolete.is_empty()
+        && merge.needs_value.is_empty());
+    if !changed {
+        outln!("📋 Kept your config.toml (no config changes in this version)");
+        return;
+    }
+
+    outln!("📋 Merged config.toml with the new version's defaults (your values are kept):");
+    for key in &merge.added {
+        outln!("   + {} (new, set to its default)", key);
+    }
+    for key in &merge.updated {
+        outln!("   ~ {} (you hadn't changed it, so it follows the new default)", key);
+    }
+    for key in &merge.removed {
+        outln!("   - {} (no longer used)", key);
+    }
+    for key in &merge.obsolete {
+        match merge.renamed.iter().find(|(old, _)| old == key) {
+            Some((_, new)) => {
+                outln!("   ! {} looks renamed to {}; move your value there", key, new)
+            }
+            None => outln!("   ! {} isn't in the new defaults; kept your value", key),
+        }
+    }
+    for (old, new) in &merge.renamed {
+        if merge.removed.contains(old) {
+            outln!("   ℹ️  {} was renamed to {}", old, new);
+        }
+    }
+    for key in &merge.needs_value {
+        outln!(
+            "   ❗ {} is new and needs a value: run `mis config set {} <value> --plugin {}`",
+            key,
+            key,
+            plugin_name
+        );
+    }
+}
+
+/// Report plugins whose registries have a newer version, without changing anything.
+///
+/// Fails when updates are available (or a plugin couldn't be checked) so CI can catch it.
+fn check_for_updates(plugin: Option<&str>) -> Result<()> {
+    let project_root = require_project_root()?;
+    let plugins = match plugin {
+        Some(name) => {
+            get_plugin_path(name)?;
+            vec![name.to_string()]
+        }
+        None => get_all_plugin_names()?,
+    };
+
+    if plugins.is_empty() {
+        outln!("📋 No plugins found to check.");
+        if json_output() {
+            print_json(&serde_json::json!({ "status": "ok", "plugins": [] }))?;
+        }
+        return Ok(());
+    }
+
+    outln!("🔍 Checking {} plugin(s) for updates...", plugins.len());
+    let mut available_count = 0;
+    let mut failed_count = 0;
+    let mut checks = Vec::new();
+
+    for plugin in &plugins {
+        let mut check = UpdateCheck {
+            name: plugin.clone(),
+            status: "failed",
+            installed: None,
+            latest: None,
+            pin: None,
+            error: None,
+        };
+        match check_plugin(&project_root, plugin) {
+            Ok((installed, latest, status)) => {
+                match &status {
+                    UpdateStatus::UpToDate => {
+                        check.status = "up_to_date";
+                        outln!("  ✅ {} {} is up to date", plugin, installed);
+                    }
+                    UpdateStatus::Available => {
+                        available_count += 1;
+                        check.status = "update_available";
+                        outln!("  ⬆️  {} {} → {}", plugin, installed, latest);
+                    }
+                    UpdateStatus::Pinned(pin) => {
+                        check.status = "pinned";
+                        check.pin = Some(pin.clone());
+                        outln!(
+                            "  📌 {} {} is pinned to {} ({} available)",
+                            plugin, installed, pin, latest
+                        );
+                    }
+                }
+                check.installed = Some(installed);
+                check.latest = Some(latest);
+            }
+            Err(e) => {
+                failed_count += 1;
+                outln!("  ❌ {}: {}", plugin, e);
+                check.error = Some(format!("{:#}", e));
+            }
+        }
+        checks.push(check);
+    }
+
+    if json_output() {
+        let status = if available_count + failed_count > 0 {
+            "error"
+        } else {
+            "ok"
+        };
+        print_json(&serde_json::json!({ "status": status, "plugins": checks }))?;
+    }
+
+    if available_count > 0 {
+        anyhow::bail!(
+            "🛑 {} plugin(s) have updates available.\n\
+             → Run `mis update` to install them, or pin them to stay on the current version.",
+            available_count
+        );
+    }
+    if failed_count > 0 {
+        anyhow::bail!("🛑 Could not check {} plugin(s) for updates.", failed_count);
+    }
+
+    outln!("✅ All plugins are up to date.");
+    Ok(())
+}
+
+/// One plugin's entry in the `mis update --check --output json` report
+#[derive(Debug, Serialize)]
+struct UpdateCheck {
+    name: String,
+    /// up_to_date, update_available, pinned or failed
+    status: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    installed: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latest: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pin: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Installed version, latest registry version and how they compare
+fn check_plugin(project_root: &Path, plugin_name: &str) -> Result<(String, String, UpdateStatus)> {
+    let manifest = load_plugin_manifest(&get_plugin_path(plugin_name)?.join(PLUGIN_MANIFEST_FILE))?;
+    let registry = manifest.plugin.registry.clone().ok_or_else(|| {
+        anyhow::anyhow!("no registry field in manifest.toml, so it can't be checked")
+    })?;
+    if let Err(security_error) = validate_registry_source(&registry) {
+        anyhow::bail!(
+            "Security validation failed for registry '{}': {}",
+            registry,
+            security_error
+        );
+    }
+
+    let latest = latest_version(&registry, plugin_name)?;
+    let pin = plugin_pin(project_root, plugin_name, &manifest)?;
+    let status = update_status(&manifest.plugin.version, &latest, pin.as_deref());
+    Ok((manifest.plugin.version, latest, status))
+}
+
+/// The registry's version of a plugin: from its index when it has one, otherwise from the
+/// plugin's manifest in a fresh copy of the registry
+fn latest_version(registry: &str, plugin_name: &str) -> Result<String> {
+    if let Some(version) = fetch_registry_index(registry)
+        .and_then(|index| index.get(plugin_name).and_then(|entry| entry.version.clone()))
+    {
+        return Ok(version);
+    }
+
+    let registry_dir = fetch_registry_for(registry, &[plugin_name.to_string()], true)?;
+    let source = find_plugin_source(&registry_dir, plugin_name)
+        .ok_or_else(|| anyhow::anyhow!("not found in registry {}", registry))?;
+    Ok(load_plugin_manifest(&source.join(PLUGIN_MANIFEST_FILE))?
+        .plugin
+        .version)
+}
+
+pub fn update_status(installed: &str, latest: &str, pin: Option<&str>) -> UpdateStatus {
+    let newer = match (parse_version(latest), parse_version(installed)) {
+        (Some(latest), Some(installed)) => latest > installed,
+        _ => latest.trim() != installed.trim(),
+    };
+    match pin {
+        _ if !newer => UpdateStatus::UpToDate,
+        Some(pin) if !versions_match(latest, pin) => UpdateStatus::Pinned(pin.to_string()),
+        _ => UpdateStatus::Available,
+    }
+}
+
+/// The pin from plugins.lock, falling back to `pin` in the plugin's manifest.toml
+fn plugin_pin(
+    project_root: &Path,
+    plugin_name: &str,
+    manifest: &PluginManifest,
+) -> Result<Option<String>> {
+    let locked = PluginLock::load(project_root)?
+        .plugins
+        .remove(plugin_name)
+        .and_then(|locked| locked.pin);
+    Ok(locked.or_else(|| manifest.plugin.pin.clone()))
+}
+
+fn versions_match(version: &str, pin: &str) -> bool {
+    match (parse_version(version), parse_version(pin)) {
+        (Some(version), Some(pin)) => version == pin,
+        _ => version.trim() == pin.trim(),
+    }
+}
+
+/// A plugin inside a fetched registry: under plugins/, or at the top level
+fn find_plugin_source(registry_dir: &Path, plugin_name: &str) -> Option<PathBuf> {
+    [
+        registry_dir.join("plugins").join(plugin_name),
+        registry_dir.join(plugin_name),
+    ]
+    .into_iter()
+    .find(|path| path.is_dir())
+}
+
 /// Helper function to get registry URL from a plugin's manifest
 fn get_plugin_registry(plugin_name: &str) -> Result<String> {
     let plugin_path = get_plugin_path(plugin_name)?;
@@ -182,25 +773,18 @@ fn get_plugin_registry(plugin_name: &str) -> Result<String> {
         .ok_or_else(|| anyhow::anyhow!("Plugin '{}' has no registry field", plugin_name))
 }
 
-/// Updates the manifest.toml file to include the registry field
+/// Updates the manifest.toml file to include the registry field, carrying over the
+/// pin of the manifest it replaced
 fn update_manifest_with_registry(
     manifest_path: &std::path::Path,
     registry_url: &str,
+    pin: Option<&str>,
 ) -> Result<()> {
-    // Load the existing manifest
-    let manifest_content = fs::read_to_string(manifest_path)?;
-    let mut manifest: crate::models::PluginManifest = toml::from_str(&manifest_content)?;
-
-    // Update the registry field
-    manifest.plugin.registry = Some(registry_url.to_string());
-
-    // Serialize back to TOML
-    let updated_content = toml::to_string_pretty(&manifest)?;
-
-    // Write back to file
-    fs::write(manifest_path, updated_content)?;
-
-    Ok(())
+    let mut fields = vec![("registry", registry_url)];
+    if let Some(pin) = pin {
+        fields.push(("pin", pin));
+    }
+    set_manifest_plugin_fields(manifest_path, &fields)
 }
 
 #[cfg(test)]
@@ -217,7 +801,7 @@ mod tests {
         let original_dir = std::env::current_dir().unwrap();
         std::env::set_current_dir(temp_dir.path()).unwrap();
 
-        let result = update_plugin(Some("test-plugin".to_string()), false);
+        let result = update_plugin(Some("test-plugin".to_string()), false, false, false);
         assert!(result.is_err());
         assert!(
             result
@@ -239,7 +823,7 @@ mod tests {
         let makeitso_dir = temp_dir.path().join(".makeitso");
         fs::create_dir_all(&makeitso_dir).unwrap();
 
-        let result = update_plugin(Some("test-plugin".to_string()), false);
+        let result = update_plugin(Some("test-plugin".to_string()), false, false, false);
         assert!(result.is_err());
         assert!(
             result
@@ -261,7 +845,7 @@ mod tests {
         let plugins_dir = temp_dir.path().join(".makeitso/plugins");
         fs::create_dir_all(&plugins_dir).unwrap();
 
-        let result = update_plugin(Some("nonexistent-plugin".to_string()), false);
+        let result = update_plugin(Some("nonexistent-plugin".to_string()), false, false, false);
         assert!(result.is_err());
         assert!(
             result
@@ -296,7 +880,7 @@ script = "./test.ts"
 "#;
         fs::write(plugin_dir.join("manifest.toml"), manifest_content).unwrap();
 
-        let result = update_plugin(Some("test-plugin".to_string()), true); // Use dry-run to avoid actual network calls
+        let result = update_plugin(Some("test-plugin".to_string()), true, false, false); // Use dry-run to avoid actual network calls
         assert!(
             result.is_ok(),
             "Update should succeed in dry-run mode. Error: {:?}",
@@ -316,7 +900,7 @@ script = "./test.ts"
         let plugins_dir = temp_dir.path().join(".makeitso/plugins");
         fs::create_dir_all(&plugins_dir).unwrap();
 
-        let result = update_plugin(None, false);
+        let result = update_plugin(None, false, false, false);
         assert!(result.is_ok());
 
         std::env::set_current_dir(original_dir).unwrap();
@@ -334,7 +918,7 @@ script = "./test.ts"
         fs::create_dir_all(&plugins_dir.join("plugin2")).unwrap();
         fs::create_dir_all(&plugins_dir.join("plugin3")).unwrap();
 
-        let result = update_plugin(None, false);
+        let result = update_plugin(None, false, false, false);
         assert!(result.is_ok());
 
         std::env::set_current_dir(original_dir).unwrap();
@@ -425,7 +1009,7 @@ script = "./test.ts"
 
         // The update should be able to read the registry field
         // For now, just test that it doesn't fail (actual update logic comes next)
-        let result = update_plugin(Some("test-plugin".to_string()), true); // dry-run
+        let result = update_plugin(Some("test-plugin".to_string()), true, false, false); // dry-run
         assert!(
             result.is_ok(),
             "Update should succeed in dry-run mode. Error: {:?}",
@@ -466,7 +1050,7 @@ debug = false
         fs::write(plugin_dir.join("config.toml"), user_config).unwrap();
 
         // Update should preserve the config file
-        let result = update_plugin(Some("config-plugin".to_string()), true); // dry-run
+        let result = update_plugin(Some("config-plugin".to_string()), true, false, false); // dry-run
         assert!(result.is_ok(), "Update should succeed");
 
         // Verify config.toml is still there with user values
@@ -504,7 +1088,7 @@ script = "./test.ts"
         fs::write(plugin_dir.join("manifest.toml"), manifest_content).unwrap();
 
         // Update should fail gracefully when no registry is specified
-        let result = update_plugin(Some("legacy-plugin".to_string()), false);
+        let result = update_plugin(Some("legacy-plugin".to_string()), false, false, false);
 
         // For now, this might succeed since we haven't implemented the logic yet
         // But when we do implement it, it should fail with a helpful error
@@ -573,7 +1157,7 @@ script = "./old.ts"
         .unwrap();
 
         // Update all should handle the mixed scenarios
-        let result = update_plugin(None, true); // dry-run
+        let result = update_plugin(None, true, false, false); // dry-run
         assert!(
             result.is_ok(),
             "Update all should handle mixed registry sources"
@@ -604,11 +1188,164 @@ script = "./test.ts"
         fs::write(plugin_dir.join("manifest.toml"), manifest_content).unwrap();
 
         // Update should fail when registry URL is dangerous
-        let result = update_plugin(Some("dangerous-plugin".to_string()), false);
+        let result = update_plugin(Some("dangerous-plugin".to_string()), false, false, false);
 
         // When we implement the actual update logic, this should fail with security error
         // For now, this documents the expected behavior
 
         std::env::set_current_dir(original_dir).unwrap();
     }
+
+    #[test]
+    fn test_update_status_respects_pins() {
+        assert_eq!(update_status("1.0.0", "1.0.0", None), UpdateStatus::UpToDate);
+        assert_eq!(update_status("1.2.0", "1.1.0", None), UpdateStatus::UpToDate);
+        assert_eq!(update_status("1.0.0", "1.2.0", None), UpdateStatus::Available);
+        assert_eq!(
+            update_status("1.0.0", "1.2.0", Some("1.0.0")),
+            UpdateStatus::Pinned("1.0.0".to_string())
+        );
+        // Pinned to exactly what the registry now has
+        assert_eq!(
+            update_status("1.0.0", "1.2.0", Some("1.2")),
+            UpdateStatus::Available
+        );
+        assert_eq!(update_status("nightly-1", "nightly-2", None), UpdateStatus::Available);
+    }
+
+    #[test]
+    fn test_update_skips_plugin_at_its_pinned_version() {
+        let temp_dir = tempdir().unwrap();
+        let original_dir = std::env::current_dir().unwrap();
+        std::env::set_current_dir(temp_dir.path()).unwrap();
+
+        let plugin_dir = temp_dir.path().join(".makeitso/plugins/pinned-plugin");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(
+            plugin_dir.join(PLUGIN_MANIFEST_FILE),
+            r#"
+[plugin]
+name = "pinned-plugin"
+version = "1.0.0"
+registry = "https://github.com/example/plugins.git"
+"#,
+        )
+        .unwrap();
+        fs::write(
+            temp_dir.path().join(".makeitso/plugins.lock"),
+            "[plugins.pinned-plugin]\npin = \"1.0.0\"\n\n[plugins.pinned-plugin.files]\n",
+        )
+        .unwrap();
+
+        // No network access needed: the plugin is skipped before the registry is fetched
+        let updated = update_single_plugin("pinned-plugin", false, false);
+        std::env::set_current_dir(original_dir).unwrap();
+        let outcome = updated.unwrap();
+        assert_eq!(outcome.status, OutcomeStatus::Skipped);
+        assert_eq!(outcome.version.as_deref(), Some("1.0.0"));
+    }
+
+    #[test]
+    fn test_diff_plugin_update_lists_file_and_version_changes() {
+        let temp_dir = tempdir().unwrap();
+        let installed = temp_dir.path().join("installed");
+        let new = temp_dir.path().join("new");
+        fs::create_dir_all(&installed).unwrap();
+        fs::create_dir_all(&new).unwrap();
+
+        fs::write(
+            installed.join(PLUGIN_MANIFEST_FILE),
+            "[plugin]\nname = \"deploy\"\nversion = \"1.0.0\"\nregistry = \"https://github.com/example/plugins.git\"\n",
+        )
+        .unwrap();
+        fs::write(
+            new.join(PLUGIN_MANIFEST_FILE),
+            "[plugin]\nname = \"deploy\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        for dir in [&installed, &new] {
+            fs::write(dir.join("main.ts"), "console.log('v1');").unwrap();
+        }
+        fs::write(installed.join("config.toml"), "token = \"abc\"").unwrap();
+
+        // Only the registry field differs, which `mis update` sets itself
+        let changes = diff_plugin_update(&installed, &new).unwrap();
+        assert!(changes.is_empty(), "{:?}", changes);
+
+        fs::write(
+            new.join(PLUGIN_MANIFEST_FILE),
+            "[plugin]\nname = \"deploy\"\nversion = \"1.1.0\"\n",
+        )
+        .unwrap();
+        fs::write(new.join("main.ts"), "console.log('v2');").unwrap();
+        fs::write(installed.join("old.ts"), "").unwrap();
+        fs::write(new.join("new.ts"), "").unwrap();
+
+        let changes = diff_plugin_update(&installed, &new).unwrap();
+        assert_eq!(changes.from_version, "1.0.0");
+        assert_eq!(changes.to_version, "1.1.0");
+        assert_eq!(changes.files.modified, vec!["main.ts", "manifest.toml"]);
+        assert_eq!(changes.files.missing, vec!["old.ts"]);
+        assert_eq!(changes.files.added, vec!["new.ts"]);
+    }
+
+    #[test]
+    fn test_merge_plugin_config_keeps_user_values_and_follows_untouched_defaults() {
+        let base = "region = \"us-east-1\"\nretries = 3\nlegacy = true\nold_name = \"x\"\n";
+        let user = "# my settings\nregion = \"eu-west-1\" # closest to us\nretries = 3\n\
+                    legacy = true\nold_name = \"x\"\nmine = 1\n";
+        let new_default = "region = \"us-east-1\"\nretries = 5\nnew_name = \"x\"\n\
+                           token = \"{{ ask \\\"API token\\\" }}\"\n\n[pool]\nsize = 4\n";
+
+        let merge = merge_plugin_config(Some(base), user, new_default).unwrap();
+        let merged: toml::Table = toml::from_str(&merge.contents).unwrap();
+
+        assert_eq!(merged["region"].as_str(), Some("eu-west-1"));
+        assert_eq!(merged["retries"].as_integer(), Some(5));
+        assert_eq!(merged["pool"]["size"].as_integer(), Some(4));
+        assert_eq!(merged["new_name"].as_str(), Some("x"));
+        assert_eq!(merged["mine"].as_integer(), Some(1));
+        assert!(!merged.contains_key("legacy"));
+        assert!(!merged.contains_key("token"));
+        assert!(merge.contents.starts_with("# my settings\n"));
+        assert!(merge.contents.contains("# closest to us"));
+
+        assert_eq!(merge.added, vec!["new_name", "pool.size"]);
+        assert_eq!(merge.updated, vec!["retries"]);
+        assert_eq!(merge.removed, vec!["legacy", "old_name"]);
+        assert!(merge.obsolete.is_empty());
+        assert_eq!(merge.renamed, vec![("old_name".to_string(), "new_name".to_string())]);
+        assert_eq!(merge.needs_value, vec!["token"]);
+    }
+
+    #[test]
+    fn test_merge_plugin_config_without_base_only_adds_keys() {
+        let user = "region = \"us-east-1\"\nretired = \"keep me\"\n\n[pool]\nsize = 2\n";
+        let new_default = "region = \"us-west-2\"\n\n[pool]\nsize = 4\ntimeout = 30\n";
+
+        let merge = merge_plugin_config(None, user, new_default).unwrap();
+        let merged: toml::Table = toml::from_str(&merge.contents).unwrap();
+
+        // Without the old default there's no telling which values the user chose
+        assert_eq!(merged["region"].as_str(), Some("us-east-1"));
+        assert_eq!(merged["pool"]["size"].as_integer(), Some(2));
+        assert_eq!(merged["pool"]["timeout"].as_integer(), Some(30));
+        assert_eq!(merged["retired"].as_str(), Some("keep me"));
+        assert_eq!(merge.added, vec!["pool.timeout"]);
+        assert!(merge.updated.is_empty());
+        assert_eq!(merge.obsolete, vec!["retired"]);
+    }
+
+    #[test]
+    fn test_merge_plugin_config_respects_deleted_keys_and_drops_empty_tables() {
+        let base = "verbose = false\n\n[cache]\ndir = \".cache\"\n";
+        let user = "[cache]\ndir = \".cache\"\n";
+        let new_default = "verbose = false\n";
+
+        let merge = merge_plugin_config(Some(base), user, new_default).unwrap();
+
+        assert!(merge.added.is_empty(), "{:?}", merge.added);
+        assert_eq!(merge.removed, vec!["cache.dir"]);
+        assert_eq!(merge.contents.trim(), "");
+    }
 }
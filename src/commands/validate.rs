@@ -0,0 +1,627 @@
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::Result;
+use serde::Serialize;
+use toml_edit::{ImDocument, Item, TableLike};
+
+use crate::{
+    cli::OutputFormat,
+    constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE},
+    manifest_schema::{line_at, unknown_keys},
+    models::{ArgType, ConfigValueType, PluginManifest, flag_name},
+    plugin_utils::{get_all_plugin_names, get_plugin_path},
+    report::print_json,
+    utils::require_project_root,
+    validation::{suggest_similar, validate_arg_value},
+};
+
+/// Something `mis validate` found wrong in a manifest.toml or config.toml
+#[derive(Debug, Serialize, PartialEq)]
+pub struct Problem {
+    /// Relative to the project root
+    pub file: PathBuf,
+    /// 1-based line of the offending key, when there is one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hint: Option<String>,
+}
+
+/// Every problem in one plugin's files
+#[derive(Debug, Serialize)]
+pub struct PluginValidation {
+    pub plugin: String,
+    pub problems: Vec<Problem>,
+}
+
+pub fn run_validate(plugin: Option<&str>, output: OutputFormat) -> Result<()> {
+    let project_root = require_project_root()?;
+    let plugin_names = match plugin {
+        Some(name) => {
+            // Validates that the plugin exists
+            get_plugin_path(name)?;
+            vec![name.to_string()]
+        }
+        None => {
+            let mut names = get_all_plugin_names()?;
+            names.sort();
+            names
+        }
+    };
+
+    let mut results = Vec::with_capacity(plugin_names.len());
+    for name in plugin_names {
+        let problems = validate_plugin(&project_root, &get_plugin_path(&name)?)?;
+        results.push(PluginValidation {
+            plugin: name,
+            problems,
+        });
+    }
+
+    match output {
+        OutputFormat::Json => print_json(&results)?,
+        OutputFormat::Text => print_validations(&results),
+    }
+
+    let failed = results.iter().filter(|r| !r.problems.is_empty()).count();
+    if failed > 0 {
+        anyhow::bail!(
+            "🛑 Found {} problem(s) in {} plugin(s).\n\
+             → Fix them and run `mis validate` again.",
+            results.iter().map(|r| r.problems.len()).sum::<usize>(),
+            failed
+        );
+    }
+    Ok(())
+}
+
+fn print_validations(results: &[PluginValidation]) {
+    if results.is_empty() {
+        outln!("📋 No plugins installed.");
+        return;
+    }
+
+    for result in results {
+        if result.problems.is_empty() {
+            outln!("✅ {}", result.plugin);
+            continue;
+        }
+        outln!("❌ {}", result.plugin);
+        for problem in &result.problems {
+            match problem.line {
+                Some(line) => outln!(
+                    "   {}:{}: {}",
+                    problem.file.display(),
+                    line,
+                    problem.message
+                ),
+                None => outln!("   {}: {}", problem.file.display(), problem.message),
+            }
+            if let Some(hint) = &problem.hint {
+                outln!("      💡 {}", hint);
+            }
+        }
+    }
+}
+
+/// Check a plugin's manifest.toml and config.toml, collecting every problem instead of
+/// stopping at the first
+pub fn validate_plugin(project_root: &Path, plugin_dir: &Path) -> Result<Vec<Problem>> {
+    let relative = |file: &str| {
+        let path = plugin_dir.join(file);
+        path.strip_prefix(project_root)
+            .map(Path::to_path_buf)
+            .unwrap_or(path)
+    };
+
+    let manifest_text = fs::read_to_string(plugin_dir.join(PLUGIN_MANIFEST_FILE))?;
+    let mut manifest_check = FileCheck::new(relative(PLUGIN_MANIFEST_FILE), &manifest_text);
+    let manifest = manifest_check.check_manifest(plugin_dir);
+    let mut problems = manifest_check.finish();
+
+    let config_path = plugin_dir.join(PLUGIN_CONFIG_FILE);
+    if config_path.exists() {
+        let config_text = fs::read_to_string(&config_path)?;
+        let mut config_check = FileCheck::new(relative(PLUGIN_CONFIG_FILE), &config_text);
+        config_check.check_config(manifest.as_ref());
+        problems.extend(config_check.finish());
+    }
+
+    Ok(problems)
+}
+
+/// Problems found in one file, located by the spans toml_edit keeps while parsing
+struct FileCheck<'a> {
+    file: PathBuf,
+    text: &'a str,
+    doc: Option<ImDocument<&'a str>>,
+    problems: Vec<Problem>,
+}
+
+impl<'a> FileCheck<'a> {
+    fn new(file: PathBuf, text: &'a str) -> Self {
+        let mut check = Self {
+            file,
+            text,
+            doc: None,
+            problems: Vec::new(),
+        };
+        match ImDocument::parse(text) {
+            Ok(doc) => check.doc = Some(doc),
+            Err(e) => {
+                let hint = e.message().contains("duplicate key").then(|| {
+                    "Each command, argument and key can only be defined once.".to_string()
+                });
+                check.report(e.span(), e.message().trim(), hint);
+            }
+        }
+        check
+    }
+
+    fn finish(mut self) -> Vec<Problem> {
+        self.problems.sort_by_key(|problem| problem.line);
+        self.problems
+    }
+
+    fn report(&mut self, span: Option<Range<usize>>, message: &str, hint: Option<String>) {
+        let line = span.map(|span| line_at(self.text, span.start));
+        self.problems.push(Problem {
+            file: self.file.clone(),
+            line,
+            message: message.to_string(),
+            hint,
+        });
+    }
+
+    /// Where the key at `path` is written, e.g. ["commands", "deploy", "script"]
+    fn span_of(&self, path: &[&str]) -> Option<Range<usize>> {
+        let (last, parents) = path.split_last()?;
+        let mut table: &dyn TableLike = self.doc.as_ref()?.as_table();
+        for key in parents {
+            table = table.get(key)?.as_table_like()?;
+        }
+        let (key, item) = table.get_key_value(last)?;
+        key.span().or_else(|| item.span())
+    }
+
+    fn check_manifest(&mut self, plugin_dir: &Path) -> Option<PluginManifest> {
+        for unknown in unknown_keys(self.doc.as_ref()?.as_table()) {
+            self.report(unknown.span.clone(), &unknown.message(), Some(unknown.hint));
+        }
+
+        let manifest = match toml::from_str::<PluginManifest>(self.text) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                self.report(e.span(), e.message().trim(), None);
+                return None;
+            }
+        };
+        self.check_commands(&manifest, plugin_dir);
+        Some(manifest)
+    }
+
+    fn check_commands(&mut self, manifest: &PluginManifest, plugin_dir: &Path) {
+        let mut names: Vec<&String> = manifest.commands.keys().collect();
+        names.sort();
+
+        let mut seen: HashMap<String, &str> = HashMap::new();
+        for name in names {
+            let command = &manifest.commands[name];
+            if let Some(other) = seen.insert(name.to_lowercase(), name) {
+                self.report(
+                    self.span_of(&["commands", name]),
+                    &format!("Command `{}` duplicates `{}` apart from case", name, other),
+                    Some(
+                        "Rename one of them; `mis run` can't tell them apart reliably.".to_string(),
+                    ),
+                );
+            }
+
+            if !plugin_dir.join(&command.script).is_file() {
+                self.report(
+                    self.span_of(&["commands", name, "script"]),
+                    &format!(
+                        "Script `{}` for command `{}` doesn't exist",
+                        command.script, name
+                    ),
+                    Some(format!(
+                        "Create {} or point `script` at an existing file.",
+                        command.script
+                    )),
+                );
+            }
+
+            if let Some(args) = &command.args {
+                // Names and aliases as typed on the command line, and the argument each belongs to
+                let mut flags: HashMap<&str, (&str, &String)> = HashMap::new();
+                for (section, defs) in [("required", &args.required), ("optional", &args.optional)]
+                {
+                    let mut arg_names: Vec<&String> = defs.keys().collect();
+                    arg_names.sort();
+                    for arg_name in arg_names {
+                        let def = &defs[arg_name];
+                        let path = ["commands", name, "args", section, arg_name];
+                        let span = self.span_of(&path);
+                        let table = format!("[commands.{}.args.{}.{}]", name, section, arg_name);
+
+                        for flag in std::iter::once(arg_name).chain(&def.aliases) {
+                            let flag = flag.trim_start_matches('-');
+                            let Some((owner_section, owner)) =
+                                flags.insert(flag, (section, arg_name))
+                            else {
+                                continue;
+                            };
+                            if owner_section == section && owner == arg_name {
+                                continue;
+                            }
+                            let message = if owner == arg_name {
+                                format!(
+                                    "Argument `{}` of command `{}` is both required and optional",
+                                    arg_name, name
+                                )
+                            } else {
+                                format!(
+                                    "`{}` is used by both `{}` and `{}` in command `{}`",
+                                    flag_name(flag),
+                                    owner,
+                                    arg_name,
+                                    name
+                                )
+                            };
+                            self.report(
+                                span.clone(),
+                                &message,
+                                Some("Give each argument its own name and aliases.".to_string()),
+                            );
+                        }
+                        if let Some(alias) = def.aliases.iter().find(|alias| alias.starts_with('-'))
+                        {
+                            self.report(
+                                span.clone(),
+                                &format!("Alias `{}` in {} starts with a dash", alias, table),
+                                Some(format!(
+                                    "Write it as \"{}\"; mis adds the dashes.",
+                                    alias.trim_start_matches('-')
+                                )),
+                            );
+                        }
+
+                        if section == "required" && def.default_value.is_some() {
+                            self.report(
+                                span.clone(),
+                                &format!("Required argument `{}` has a default_value", arg_name),
+                                Some(
+                                    "Move it to [args.optional], or drop the default.".to_string(),
+                                ),
+                            );
+                        }
+                        let checked = match &def.default_value {
+                            Some(default) => validate_arg_value(default, def).err().map(|e| {
+                                format!(
+                                    "Default `{}` for `{}` is invalid: {}",
+                                    default, arg_name, e
+                                )
+                            }),
+                            None => (matches!(def.arg_type, ArgType::Enum)
+                                && def.allowed_values.is_empty())
+                            .then(|| {
+                                format!(
+                                    "Argument `{}` is an enum but lists no allowed_values",
+                                    arg_name
+                                )
+                            }),
+                        };
+                        if let Some(message) = checked {
+                            self.report(span, &message, None);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn check_config(&mut self, manifest: Option<&PluginManifest>) {
+        let Some(manifest) = manifest.filter(|m| !m.config_schema.is_empty()) else {
+            return;
+        };
+        let Some(doc) = self.doc.take() else {
+            return;
+        };
+        self.check_config_table(doc.as_table(), manifest, "");
+
+        let mut required: Vec<&String> = manifest
+            .config_schema
+            .iter()
+            .filter(|(_, def)| def.required && def.default.is_none())
+            .map(|(key, _)| key)
+            .collect();
+        required.sort();
+        for key in required {
+            let path: Vec<&str> = key.split('.').collect();
+            let mut table: Option<&dyn TableLike> = Some(doc.as_table());
+            for part in &path[..path.len() - 1] {
+                table = table
+                    .and_then(|t| t.get(part))
+                    .and_then(Item::as_table_like);
+            }
+            if table.and_then(|t| t.get(path[path.len() - 1])).is_none() {
+                let hint = manifest.config_schema[key].description.clone();
+                self.report(None, &format!("Missing required key `{}`", key), hint);
+            }
+        }
+        self.doc = Some(doc);
+    }
+
+    fn check_config_table(
+        &mut self,
+        table: &dyn TableLike,
+        manifest: &PluginManifest,
+        prefix: &str,
+    ) {
+        for (key, value) in table.iter() {
+            // Per-command settings, e.g. [commands.deploy.variables]
+            if prefix.is_empty() && key == "commands" {
+                continue;
+            }
+            let path = format!("{}{}", prefix, key);
+            let span = table
+                .key(key)
+                .and_then(|k| k.span())
+                .or_else(|| value.span());
+
+            if let Some(def) = manifest.config_schema.get(&path) {
+                if let Some(expected) = def.value_type
+                    && !matches_type(expected, value)
+                {
+                    self.report(
+                        span,
+                        &format!(
+                            "`{}` should be {}, not {}",
+                            path,
+                            expected.with_article(),
+                            value.type_name()
+                        ),
+                        def.description.clone(),
+                    );
+                }
+                continue;
+            }
+
+            let nested = format!("{}.", path);
+            if let Some(inner) = value.as_table_like()
+                && manifest
+                    .config_schema
+                    .keys()
+                    .any(|k| k.starts_with(&nested))
+            {
+                self.check_config_table(inner, manifest, &nested);
+                continue;
+            }
+
+            let hint = suggest_similar(&path, manifest.config_schema.keys().map(String::as_str))
+                .map(|suggestion| format!("Did you mean `{}`?", suggestion))
+                .or_else(|| {
+                    Some(
+                        "The plugin's [config_schema] doesn't document it; check the spelling."
+                            .to_string(),
+                    )
+                });
+            self.report(span, &format!("Unknown config key `{}`", path), hint);
+        }
+    }
+}
+
+fn matches_type(expected: ConfigValueType, value: &Item) -> bool {
+    match expected {
+        ConfigValueType::String => value.is_str(),
+        ConfigValueType::Boolean => value.is_bool(),
+        ConfigValueType::Integer => value.is_integer(),
+        ConfigValueType::Float => value.is_float() || value.is_integer(),
+        ConfigValueType::Array => value.is_array() || value.is_array_of_tables(),
+        ConfigValueType::Table => value.is_table_like(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn validate(manifest: &str, config: Option<&str>, scripts: &[&str]) -> Vec<Problem> {
+        let temp_dir = tempdir().unwrap();
+        let plugin_dir = temp_dir.path().join(".makeitso/plugins/deploy");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join(PLUGIN_MANIFEST_FILE), manifest).unwrap();
+        if let Some(config) = config {
+            fs::write(plugin_dir.join(PLUGIN_CONFIG_FILE), config).unwrap();
+        }
+        for script in scripts {
+            fs::write(plugin_dir.join(script), "").unwrap();
+        }
+        validate_plugin(temp_dir.path(), &plugin_dir).unwrap()
+    }
+
+    #[test]
+    fn test_validate_plugin_accepts_a_valid_plugin() {
+        let manifest = r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[commands.prod]
+script = "prod.ts"
+
+[commands.prod.args.optional.region]
+description = "Region"
+arg_type = "enum"
+allowed_values = ["eu", "us"]
+default_value = "eu"
+aliases = ["r"]
+
+[permissions]
+network = ["api.example.com"]
+
+[config_schema."pool.size"]
+type = "integer"
+"#;
+        let problems = validate(manifest, Some("[pool]\nsize = 5\n"), &["prod.ts"]);
+        assert_eq!(problems, Vec::new());
+    }
+
+    #[test]
+    fn test_validate_plugin_reports_every_problem_with_lines() {
+        let manifest = r#"[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[plugin.permissions]
+network = ["api.example.com"]
+
+[commands.prod]
+script = "prod.ts"
+descripton = "Deploy"
+
+[commands.Prod]
+script = "missing.ts"
+
+[commands.prod.args.required.region]
+description = "Region"
+arg_type = "enum"
+default_value = "eu"
+aliases = ["-r"]
+
+[commands.prod.args.optional.reason]
+description = "Why"
+aliases = ["r"]
+"#;
+        let problems = validate(manifest, None, &["prod.ts"]);
+        let messages: Vec<(Option<usize>, &str)> = problems
+            .iter()
+            .map(|p| (p.line, p.message.as_str()))
+            .collect();
+
+        assert!(
+            messages.contains(&(Some(5), "Unknown key `permissions` in [plugin]")),
+            "{:?}",
+            messages
+        );
+        let nesting = problems.iter().find(|p| p.line == Some(5)).unwrap();
+        assert!(
+            nesting
+                .hint
+                .as_deref()
+                .unwrap()
+                .contains("write [permissions]")
+        );
+
+        let typo = problems.iter().find(|p| p.line == Some(10)).unwrap();
+        assert_eq!(typo.message, "Unknown key `descripton` in [commands.prod]");
+        assert_eq!(typo.hint.as_deref(), Some("Did you mean `description`?"));
+
+        assert!(
+            messages.contains(&(Some(8), "Command `prod` duplicates `Prod` apart from case")),
+            "{:?}",
+            messages
+        );
+        assert!(
+            messages.contains(&(
+                Some(13),
+                "Script `missing.ts` for command `Prod` doesn't exist"
+            )),
+            "{:?}",
+            messages
+        );
+        assert!(
+            messages
+                .iter()
+                .any(|(_, m)| m.contains("Required argument `region` has a default_value"))
+        );
+        assert!(messages.iter().any(|(_, m)| m.contains("Alias `-r`")));
+        assert!(
+            messages
+                .iter()
+                .any(|(_, m)| m.contains("no allowed_values")),
+            "{:?}",
+            messages
+        );
+        assert!(
+            messages
+                .iter()
+                .any(|(_, m)| m.contains("`-r` is used by both `region` and `reason`")),
+            "{:?}",
+            messages
+        );
+        assert_eq!(
+            problems[0].file,
+            Path::new(".makeitso/plugins/deploy/manifest.toml")
+        );
+    }
+
+    #[test]
+    fn test_validate_plugin_reports_syntax_and_type_errors() {
+        let problems = validate("[plugin\nname = \"deploy\"\n", None, &[]);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(1));
+
+        let problems = validate("[plugin]\nname = \"deploy\"\nversion = 1\n", None, &[]);
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(3));
+
+        let duplicate = "[plugin]\nname = \"deploy\"\nversion = \"1.0.0\"\n\n[commands.prod]\nscript = \"a.ts\"\n\n[commands.prod]\nscript = \"b.ts\"\n";
+        let problems = validate(duplicate, None, &[]);
+        assert_eq!(problems[0].line, Some(8));
+        assert!(
+            problems[0]
+                .hint
+                .as_deref()
+                .unwrap()
+                .contains("only be defined once")
+        );
+    }
+
+    #[test]
+    fn test_validate_plugin_checks_config_against_schema() {
+        let manifest = r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[config_schema.database_url]
+type = "string"
+required = true
+description = "Postgres connection string"
+
+[config_schema."pool.size"]
+type = "integer"
+"#;
+        let config = "databse_url = \"postgres://\"\n\n[pool]\nsize = \"five\"\nszie = 3\n";
+        let problems = validate(manifest, Some(config), &[]);
+        let config_problems: Vec<&Problem> = problems
+            .iter()
+            .filter(|p| p.file.ends_with(PLUGIN_CONFIG_FILE))
+            .collect();
+        assert_eq!(config_problems.len(), 4, "{:?}", config_problems);
+        assert_eq!(
+            config_problems[0].message,
+            "Missing required key `database_url`"
+        );
+        assert_eq!(
+            config_problems[1].message,
+            "Unknown config key `databse_url`"
+        );
+        assert_eq!(
+            config_problems[1].hint.as_deref(),
+            Some("Did you mean `database_url`?")
+        );
+        assert_eq!(
+            config_problems[2].message,
+            "`pool.size` should be an integer, not string"
+        );
+        assert_eq!(config_problems[2].line, Some(4));
+        assert_eq!(config_problems[3].message, "Unknown config key `pool.szie`");
+    }
+}
@@ -0,0 +1,193 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use indexmap::IndexMap;
+
+use crate::{
+    commands::run::ensure_deno_installed,
+    config::plugins::load_plugin_manifest,
+    constants::PLUGIN_MANIFEST_FILE,
+    integrations::deno::vendor_deno_dependencies,
+    plugin_utils::{get_all_plugin_names, get_plugin_path},
+    security::validate_deno_dependency_url,
+    utils::require_project_root,
+};
+
+const IMPORT_MAP_FILE: &str = "import_map.json";
+
+/// Where `mis vendor` keeps the project's Deno dependencies
+pub fn vendor_dir(project_root: &Path) -> PathBuf {
+    project_root.join(".makeitso").join("vendor")
+}
+
+/// The import map written by `mis vendor`, if this project vendors its dependencies
+pub fn vendored_import_map(project_root: &Path) -> Option<PathBuf> {
+    let path = vendor_dir(project_root).join(IMPORT_MAP_FILE);
+    path.is_file().then_some(path)
+}
+
+/// `mis vendor`: download the [deno_dependencies] of every installed plugin into
+/// `.makeitso/vendor/` so plugins run without network access
+pub fn run_vendor() -> Result<()> {
+    let project_root = require_project_root()?;
+
+    let mut urls = BTreeSet::new();
+    for name in get_all_plugin_names()? {
+        let manifest = load_plugin_manifest(&get_plugin_path(&name)?.join(PLUGIN_MANIFEST_FILE))?;
+        for (dep_name, dep_url) in &manifest.deno_dependencies {
+            validate_deno_dependency_url(dep_url).map_err(|security_error| {
+                anyhow::anyhow!(
+                    "🛑 Security validation failed for dependency '{}' of '{}' ({}): {}\n\
+                     → Deno dependencies must use secure HTTPS URLs from trusted sources.",
+                    dep_name,
+                    name,
+                    dep_url,
+                    security_error
+                )
+            })?;
+            urls.insert(dep_url.clone());
+        }
+    }
+
+    if urls.is_empty() {
+        outln!("📦 No installed plugin declares [deno_dependencies]; nothing to vendor.");
+        return Ok(());
+    }
+
+    ensure_deno_installed()?;
+
+    let output_dir = vendor_dir(&project_root);
+    outln!("📦 Vendoring {} Deno dependencies...", urls.len());
+    for url in &urls {
+        outln!("• {}", url);
+    }
+    let urls: Vec<&str> = urls.iter().map(String::as_str).collect();
+    vendor_deno_dependencies(&urls, &output_dir)?;
+
+    outln!("✅ Dependencies vendored into .makeitso/vendor/.");
+    outln!("→ Plugins now run against these copies with remote imports disabled.");
+    outln!("→ Run `mis vendor` again after adding or updating plugins.");
+    Ok(())
+}
+
+/// Make sure every dependency a plugin declares is covered by the vendored import map,
+/// since the plugin runs with remote imports disabled
+pub fn check_vendored(
+    import_map_path: &Path,
+    plugin_name: &str,
+    deps: &IndexMap<String, String>,
+) -> Result<()> {
+    let import_map = fs::read_to_string(import_map_path)
+        .with_context(|| format!("🛑 Failed to read {}", import_map_path.display()))?;
+    let missing = unvendored_dependencies(&import_map, deps).with_context(|| {
+        format!(
+            "🛑 {} is not a valid import map.\n→ Run `mis vendor` to regenerate it.",
+            import_map_path.display()
+        )
+    })?;
+
+    if !missing.is_empty() {
+        anyhow::bail!(
+            "🛑 '{}' depends on modules that aren't vendored: {}\n\
+             → Run `mis vendor` to download them, or delete .makeitso/vendor/ to fetch them at run time.",
+            plugin_name,
+            missing.join(", ")
+        );
+    }
+    Ok(())
+}
+
+/// Dependency URLs the import map has no entry for. Keys ending in `/` cover every URL under them.
+fn unvendored_dependencies<'a>(
+    import_map: &str,
+    deps: &'a IndexMap<String, String>,
+) -> Result<Vec<&'a str>> {
+    let import_map: serde_json::Value = serde_json::from_str(import_map)?;
+    let imports = import_map
+        .get("imports")
+        .and_then(serde_json::Value::as_object)
+        .cloned()
+        .unwrap_or_default();
+
+    let mut missing: Vec<&str> = deps
+        .values()
+        .map(String::as_str)
+        .filter(|url| {
+            !imports
+                .keys()
+                .any(|key| key == url || (key.ends_with('/') && url.starts_with(key.as_str())))
+        })
+        .collect();
+    missing.sort();
+    Ok(missing)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_unvendored_dependencies() {
+        let import_map = r#"{
+            "imports": {
+                "https://deno.land/": "./deno.land/",
+                "https://esm.sh/lodash@4.17.21": "./esm.sh/lodash@4.17.21.js"
+            }
+        }"#;
+        let deps = IndexMap::from([
+            (
+                "std".to_string(),
+                "https://deno.land/std@0.224.0/path/mod.ts".to_string(),
+            ),
+            (
+                "lodash".to_string(),
+                "https://esm.sh/lodash@4.17.21".to_string(),
+            ),
+            ("zod".to_string(), "https://esm.sh/zod@3.23.8".to_string()),
+        ]);
+
+        assert_eq!(
+            unvendored_dependencies(import_map, &deps).unwrap(),
+            vec!["https://esm.sh/zod@3.23.8"]
+        );
+        assert_eq!(
+            unvendored_dependencies("{}", &deps).unwrap().len(),
+            3,
+            "an empty import map covers nothing"
+        );
+        assert!(unvendored_dependencies("not json", &deps).is_err());
+    }
+
+    #[test]
+    fn test_check_vendored() {
+        let temp_dir = tempdir().unwrap();
+        assert_eq!(vendored_import_map(temp_dir.path()), None);
+
+        let dir = vendor_dir(temp_dir.path());
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join(IMPORT_MAP_FILE),
+            r#"{ "imports": { "https://deno.land/": "./deno.land/" } }"#,
+        )
+        .unwrap();
+        let import_map = vendored_import_map(temp_dir.path()).unwrap();
+
+        let vendored = IndexMap::from([(
+            "std".to_string(),
+            "https://deno.land/std@0.224.0/fs/mod.ts".to_string(),
+        )]);
+        assert!(check_vendored(&import_map, "deploy", &vendored).is_ok());
+
+        let missing = IndexMap::from([("zod".to_string(), "https://esm.sh/zod@3.23.8".to_string())]);
+        let err = check_vendored(&import_map, "deploy", &missing).unwrap_err();
+        assert!(
+            err.to_string().contains("https://esm.sh/zod@3.23.8"),
+            "{}",
+            err
+        );
+        assert!(err.to_string().contains("mis vendor"));
+    }
+}
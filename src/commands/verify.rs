@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, anyhow};
+use serde::Serialize;
+use tempfile::TempDir;
+
+use crate::{
+    cli::OutputFormat,
+    commands::add::{copy_dir_recursive, update_manifest_with_registry},
+    constants::PLUGIN_MANIFEST_FILE,
+    integrations::registry_http::fetch_registry_for,
+    plugin_lock::{ChecksumDrift, PluginLock, compare_checksums, hash_plugin_dir},
+    plugin_utils::{get_all_plugin_names, get_plugin_path},
+    report::print_json,
+    security::validate_registry_source,
+    utils::require_project_root,
+};
+
+/// How an installed plugin compares to the checksums recorded when it was installed
+#[derive(Debug, Serialize)]
+pub struct PluginVerification {
+    pub plugin: String,
+
+    /// False when the plugin was never installed with `mis add` or `mis update`
+    pub tracked: bool,
+
+    /// Local changes since install
+    pub local: Option<ChecksumDrift>,
+
+    /// Differences between the recorded files and the registry's current version (--remote)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<ChecksumDrift>,
+}
+
+impl PluginVerification {
+    fn tampered(&self) -> bool {
+        self.local.as_ref().is_some_and(|drift| !drift.is_clean())
+    }
+}
+
+/// Check installed plugins against their recorded checksums, optionally comparing with the registry
+pub fn run_verify(plugin: Option<&str>, remote: bool, output: OutputFormat) -> Result<()> {
+    let project_root = require_project_root()?;
+    let lock = PluginLock::load(&project_root)?;
+
+    let plugin_names = match plugin {
+        Some(name) => {
+            // Validates that the plugin exists
+            get_plugin_path(name)?;
+            vec![name.to_string()]
+        }
+        None => get_all_plugin_names()?,
+    };
+
+    let mut clones: BTreeMap<String, PathBuf> = BTreeMap::new();
+    let mut results = Vec::with_capacity(plugin_names.len());
+    for name in &plugin_names {
+        let mut result = verify_plugin(&lock, name, &get_plugin_path(name)?)?;
+
+        if remote && let Some(locked) = lock.plugins.get(name) {
+            match &locked.registry {
+                Some(registry) => {
+                    let registry_files =
+                        registry_checksums(&mut clones, registry, name, &plugin_names)?;
+                    result.registry = Some(compare_checksums(&locked.files, &registry_files));
+                }
+                None => errln!(
+                    "⚠️  Warning: '{}' has no registry recorded; skipping the registry check.",
+                    name
+                ),
+            }
+        }
+        results.push(result);
+    }
+
+    match output {
+        OutputFormat::Json => print_json(&results)?,
+        OutputFormat::Text => print_verifications(&results),
+    }
+
+    let tampered: Vec<&str> = results
+        .iter()
+        .filter(|r| r.tampered())
+        .map(|r| r.plugin.as_str())
+        .collect();
+    if !tampered.is_empty() {
+        anyhow::bail!(
+            "🛑 {} plugin(s) changed since they were installed: {}\n\
+             → Review the changes, then reinstall with `mis add <plugin> --force` or `mis update <plugin>`.",
+            tampered.len(),
+            tampered.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Compare one plugin directory against the lock file
+pub fn verify_plugin(
+    lock: &PluginLock,
+    plugin_name: &str,
+    plugin_dir: &Path,
+) -> Result<PluginVerification> {
+    let Some(locked) = lock.plugins.get(plugin_name) else {
+        return Ok(PluginVerification {
+            plugin: plugin_name.to_string(),
+            tracked: false,
+            local: None,
+            registry: None,
+        });
+    };
+
+    Ok(PluginVerification {
+        plugin: plugin_name.to_string(),
+        tracked: true,
+        local: Some(compare_checksums(
+            &locked.files,
+            &hash_plugin_dir(plugin_dir)?,
+        )),
+        registry: None,
+    })
+}
+
+/// Checksums the plugin would have if it were installed from the registry right now
+fn registry_checksums(
+    clones: &mut BTreeMap<String, PathBuf>,
+    registry: &str,
+    plugin_name: &str,
+    plugin_names: &[String],
+) -> Result<BTreeMap<String, String>> {
+    if let Err(security_error) = validate_registry_source(registry) {
+        return Err(anyhow!(
+            "🛑 Security validation failed for registry '{}': {}\n\
+             → Registry sources must be secure HTTPS git repositories from trusted sources, or local directories (`path:<dir>`).",
+            registry,
+            security_error
+        ));
+    }
+
+    if !clones.contains_key(registry) {
+        // Compare against what the registry has now, not a stale cached copy
+        clones.insert(
+            registry.to_string(),
+            fetch_registry_for(registry, plugin_names, true)?,
+        );
+    }
+
+    let source_path = find_plugin_in_clone(&clones[registry], plugin_name).ok_or_else(|| {
+        anyhow!(
+            "❌ Plugin '{}' not found in registry {}",
+            plugin_name,
+            registry
+        )
+    })?;
+
+    // Install into a scratch directory so manifest.toml gets the same registry field
+    let scratch = TempDir::new()?;
+    let installed = scratch.path().join(plugin_name);
+    copy_dir_recursive(&source_path, &installed)?;
+    let manifest_path = installed.join(PLUGIN_MANIFEST_FILE);
+    if manifest_path.exists() {
+        update_manifest_with_registry(&manifest_path, registry)?;
+    }
+
+    hash_plugin_dir(&installed)
+}
+
+/// Registries keep plugins either under plugins/ or at the repository root
+fn find_plugin_in_clone(clone_root: &Path, plugin_name: &str) -> Option<PathBuf> {
+    [
+        clone_root.join("plugins").join(plugin_name),
+        clone_root.join(plugin_name),
+    ]
+    .into_iter()
+    .find(|path| path.is_dir())
+}
+
+fn print_verifications(results: &[PluginVerification]) {
+    if results.is_empty() {
+        outln!("📋 No plugins installed.");
+        return;
+    }
+
+    for result in results {
+        match &result.local {
+            None => {
+                outln!("❔ {}: no recorded checksums", result.plugin);
+                outln!(
+                    "   → Run `mis update {}` or `mis add {} --force` to record them.",
+                    result.plugin,
+                    result.plugin
+                );
+            }
+            Some(drift) if drift.is_clean() => outln!("✅ {}: unchanged", result.plugin),
+            Some(drift) => {
+                outln!("❌ {}: changed since install", result.plugin);
+                drift.print("   ");
+            }
+        }
+
+        if let Some(drift) = &result.registry {
+            if drift.is_clean() {
+                outln!("   ✅ matches the registry");
+            } else {
+                outln!("   🔄 differs from the registry:");
+                drift.print("      ");
+                outln!(
+                    "   → Run `mis update {}` to get the latest version.",
+                    result.plugin
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::plugin_lock::record_plugin_checksums;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_verify_plugin_detects_tampering() {
+        let temp_dir = tempdir().unwrap();
+        let plugin_dir = temp_dir.path().join(".makeitso/plugins/deploy");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join(PLUGIN_MANIFEST_FILE), "[plugin]").unwrap();
+        fs::write(plugin_dir.join("main.ts"), "deploy();").unwrap();
+        record_plugin_checksums(temp_dir.path(), "deploy", None, None, None, &plugin_dir).unwrap();
+
+        let lock = PluginLock::load(temp_dir.path()).unwrap();
+        let result = verify_plugin(&lock, "deploy", &plugin_dir).unwrap();
+        assert!(result.tracked);
+        assert!(!result.tampered());
+
+        fs::write(plugin_dir.join("main.ts"), "exfiltrate();").unwrap();
+        let result = verify_plugin(&lock, "deploy", &plugin_dir).unwrap();
+        assert!(result.tampered());
+        assert_eq!(result.local.unwrap().modified, vec!["main.ts"]);
+    }
+
+    #[test]
+    fn test_verify_plugin_without_checksums_is_untracked() {
+        let temp_dir = tempdir().unwrap();
+        let result = verify_plugin(&PluginLock::default(), "local", temp_dir.path()).unwrap();
+        assert!(!result.tracked);
+        assert!(!result.tampered());
+    }
+
+    #[test]
+    fn test_find_plugin_in_clone_prefers_plugins_dir() {
+        let temp_dir = tempdir().unwrap();
+        assert!(find_plugin_in_clone(temp_dir.path(), "deploy").is_none());
+
+        fs::create_dir_all(temp_dir.path().join("deploy")).unwrap();
+        assert_eq!(
+            find_plugin_in_clone(temp_dir.path(), "deploy").unwrap(),
+            temp_dir.path().join("deploy")
+        );
+
+        fs::create_dir_all(temp_dir.path().join("plugins/deploy")).unwrap();
+        assert_eq!(
+            find_plugin_in_clone(temp_dir.path(), "deploy").unwrap(),
+            temp_dir.path().join("plugins/deploy")
+        );
+    }
+}
@@ -0,0 +1,742 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use anyhow::{Result, anyhow};
+use serde_json::{Map, Value as JsonValue};
+
+use crate::{
+    cli::{OutputFormat, toml_arg_values},
+    commands::run::{RunOptions, ensure_deno_installed, run_cmd},
+    config::{load_mis_config, variables::parse_duration},
+    models::{PluginEvent, PluginRunResult, WorkflowDefinition, WorkflowStep},
+    report::{json_output, print_json},
+    run_state::{StepStatus, WorkflowRunState},
+    scheduler::{TaskGraph, run_graph},
+    signals,
+    utils::require_project_root,
+    validation::did_you_mean,
+};
+
+/// How many steps may run at once when a workflow doesn't set `max_parallel`
+const DEFAULT_MAX_PARALLEL: usize = 4;
+
+/// Run every step of a workflow defined under [workflows] in mis.toml, stopping on failure.
+///
+/// Progress is saved under a run id; passing that id as `resume` skips the steps that
+/// already succeeded in that run.
+pub fn run_workflow(
+    workflow_name: &str,
+    dry_run: bool,
+    cli_args: HashMap<String, Vec<String>>,
+    options: RunOptions,
+    resume: Option<&str>,
+) -> Result<()> {
+    let (mis_config, _, _) = load_mis_config()?;
+
+    let workflow = mis_config.workflows.get(workflow_name).ok_or_else(|| {
+        let mut available: Vec<_> = mis_config.workflows.keys().cloned().collect();
+        available.sort();
+        anyhow!(
+            "🛑 '{}' is not a plugin command or a workflow.{}\n\
+             → Use <plugin_name>:<command_name> to run a plugin command.\n\
+             → Available workflows: {}",
+            workflow_name,
+            did_you_mean(workflow_name, available.iter().map(String::as_str)),
+            if available.is_empty() {
+                "none".to_string()
+            } else {
+                available.join(", ")
+            }
+        )
+    })?;
+
+    validate_workflow(workflow_name, workflow)?;
+
+    let project_root = require_project_root()?;
+    let mut state = match resume {
+        Some(run_id) => {
+            let state = WorkflowRunState::load(&project_root, run_id, workflow_name)?;
+            outln!("🔁 Resuming run {}", state.run_id);
+            state
+        }
+        None => WorkflowRunState::new(workflow_name),
+    };
+
+    // Steps report as text (on stderr); the workflow as a whole gets one JSON report
+    let options = RunOptions {
+        output: OutputFormat::Text,
+        ..options
+    };
+
+    let result = if workflow.steps.iter().any(|step| !step.needs.is_empty()) {
+        run_workflow_graph(
+            workflow_name,
+            workflow,
+            dry_run,
+            &cli_args,
+            options,
+            &mut state,
+            &project_root,
+        )
+    } else {
+        run_workflow_steps(
+            workflow_name,
+            workflow,
+            dry_run,
+            &cli_args,
+            options,
+            &mut state,
+            &project_root,
+        )
+    };
+
+    if json_output() {
+        print_json(&serde_json::json!({
+            "status": if result.is_ok() { "ok" } else { "error" },
+            "workflow": workflow_name,
+            "run_id": state.run_id,
+            "steps": state.steps,
+            "events": state.events,
+            "error": result.as_ref().err().map(|e| format!("{:#}", e)),
+        }))?;
+    }
+
+    match result {
+        Ok(()) => {
+            // Nothing left to resume
+            if let Err(e) = state.remove(&project_root) {
+                errln!("⚠️  Warning: {:#}", e);
+            }
+            Ok(())
+        }
+        Err(e) => {
+            checkpoint(&state, &project_root);
+            Err(anyhow!(
+                "{}\n\n→ Fix the problem, then resume with `mis run {} --resume {}`",
+                e,
+                workflow_name,
+                state.run_id
+            ))
+        }
+    }
+}
+
+/// Run a workflow's steps one after another
+fn run_workflow_steps(
+    workflow_name: &str,
+    workflow: &WorkflowDefinition,
+    dry_run: bool,
+    cli_args: &HashMap<String, Vec<String>>,
+    options: RunOptions,
+    state: &mut WorkflowRunState,
+    project_root: &Path,
+) -> Result<()> {
+    let total = workflow.steps.len();
+    outln!("🔗 Running workflow '{}' ({} steps)", workflow_name, total);
+
+    let mut first_error = None;
+    let mut allowed_failures = Vec::new();
+
+    for (i, step) in workflow.steps.iter().enumerate() {
+        let (plugin_name, command_name) = split_step_target(&step.run)?;
+        let step_dry_run = dry_run || step.dry_run;
+        let step_args = build_step_args(step, cli_args);
+        let id = step_id(step);
+
+        if first_error.is_some() && !step.always_run {
+            outln!(
+                "\n⏭️  Step {}/{}: {} not run (an earlier step failed)",
+                i + 1,
+                total,
+                step.run
+            );
+            continue;
+        }
+
+        // Cleanup steps run again on resume, since the steps before them will
+        if state.succeeded(id) && !step.always_run {
+            outln!(
+                "\n⏩ Step {}/{}: {} already succeeded in this run",
+                i + 1,
+                total,
+                step.run
+            );
+            continue;
+        }
+
+        if let Some(trigger) = untriggered(step, &state.events) {
+            outln!(
+                "\n⏭️  Step {}/{}: {} skipped (event '{}' was not published)",
+                i + 1,
+                total,
+                step.run,
+                trigger
+            );
+            state.record(id, StepStatus::Skipped);
+            continue;
+        }
+
+        outln!(
+            "\n▶️  Step {}/{}: {}{}",
+            i + 1,
+            total,
+            step.run,
+            if step_dry_run { " (dry run)" } else { "" }
+        );
+
+        let result = run_cmd(
+            plugin_name.to_string(),
+            command_name,
+            step_dry_run,
+            step_args,
+            RunOptions {
+                events: Some(&state.events),
+                timeout: step_timeout(step),
+                ..options
+            },
+        );
+
+        match result {
+            Ok(result) => {
+                publish_events(&mut state.events, result.as_ref());
+                state.record(id, StepStatus::Succeeded);
+                checkpoint(state, project_root);
+            }
+            Err(e) => {
+                state.record(id, StepStatus::Failed);
+                // An interrupted run stops, even at steps that may fail
+                if step.continue_on_error && signals::received().is_none() {
+                    errln!(
+                        "⚠️  Step {}/{} ({}) failed, continuing:\n{}",
+                        i + 1,
+                        total,
+                        step.run,
+                        e
+                    );
+                    allowed_failures.push(id.to_string());
+                } else if first_error.is_some() {
+                    errln!(
+                        "🛑 Step {}/{} ({}) also failed:\n{}",
+                        i + 1,
+                        total,
+                        step.run,
+                        e
+                    );
+                } else {
+                    first_error = Some(anyhow!(
+                        "🛑 Workflow '{}' failed at step {}/{} ({}):\n{}",
+                        workflow_name,
+                        i + 1,
+                        total,
+                        step.run,
+                        e
+                    ));
+                }
+            }
+        }
+    }
+
+    if let Some(e) = first_error {
+        return Err(e);
+    }
+
+    report_completion(workflow_name, total, &allowed_failures);
+    Ok(())
+}
+
+/// Run a workflow whose steps declare `needs`, starting independent steps concurrently
+fn run_workflow_graph(
+    workflow_name: &str,
+    workflow: &WorkflowDefinition,
+    dry_run: bool,
+    cli_args: &HashMap<String, Vec<String>>,
+    options: RunOptions,
+    state: &mut WorkflowRunState,
+    project_root: &Path,
+) -> Result<()> {
+    let mut graph = step_graph(workflow)?;
+    for (i, step) in workflow.steps.iter().enumerate() {
+        if step.always_run {
+            graph.set_always_run(i);
+        }
+    }
+    let max_parallel = workflow.max_parallel.unwrap_or(DEFAULT_MAX_PARALLEL).max(1);
+
+    // Prompt once up front; concurrent steps can't share the terminal
+    ensure_deno_installed()?;
+
+    outln!(
+        "🔗 Running workflow '{}' ({} steps, up to {} at a time)",
+        workflow_name,
+        graph.len(),
+        max_parallel
+    );
+
+    let state = Mutex::new(state);
+    let allowed_failures = Mutex::new(Vec::new());
+
+    let outcome = run_graph(&graph, max_parallel, |i| {
+        let step = &workflow.steps[i];
+        let step_id = graph.id(i);
+        let (plugin_name, command_name) = split_step_target(&step.run)?;
+        let step_dry_run = dry_run || step.dry_run;
+
+        // Snapshot what's been published so far; a step's `needs` decide what that includes
+        let published = {
+            let mut state = state.lock().expect("run state lock poisoned");
+            if state.succeeded(step_id) && !step.always_run {
+                outln!("[{}] ⏩ Already succeeded in this run", step_id);
+                return Ok(());
+            }
+            if let Some(trigger) = untriggered(step, &state.events) {
+                outln!(
+                    "[{}] ⏭️  Skipped (event '{}' was not published)",
+                    step_id,
+                    trigger
+                );
+                state.record(step_id, StepStatus::Skipped);
+                return Ok(());
+            }
+            state.events.clone()
+        };
+
+        outln!(
+            "[{}] ▶️  Starting {}{}",
+            step_id,
+            step.run,
+            if step_dry_run { " (dry run)" } else { "" }
+        );
+
+        let result = run_cmd(
+            plugin_name.to_string(),
+            command_name,
+            step_dry_run,
+            build_step_args(step, cli_args),
+            RunOptions {
+                output_prefix: Some(step_id),
+                events: Some(&published),
+                timeout: step_timeout(step),
+                ..options
+            },
+        );
+
+        let mut state = state.lock().expect("run state lock poisoned");
+        match result {
+            Ok(result) => {
+                publish_events(&mut state.events, result.as_ref());
+                state.record(step_id, StepStatus::Succeeded);
+                checkpoint(&state, project_root);
+            }
+            Err(e) if step.continue_on_error && signals::received().is_none() => {
+                state.record(step_id, StepStatus::Failed);
+                errln!("[{}] ⚠️  Failed, continuing:\n{}", step_id, e);
+                allowed_failures
+                    .lock()
+                    .expect("failure list lock poisoned")
+                    .push(step_id.to_string());
+                return Ok(());
+            }
+            Err(e) => {
+                state.record(step_id, StepStatus::Failed);
+                return Err(e);
+            }
+        }
+        outln!("[{}] ✅ Finished", step_id);
+        Ok(())
+    });
+
+    if !outcome.is_success() {
+        let mut message = format!("🛑 Workflow '{}' failed:", workflow_name);
+        for (i, e) in &outcome.failed {
+            message.push_str(&format!("\n\n[{}] {}", graph.id(*i), e));
+        }
+        if !outcome.skipped.is_empty() {
+            let skipped: Vec<&str> = outcome.skipped.iter().map(|&i| graph.id(i)).collect();
+            message.push_str(&format!("\n\n→ Not started: {}", skipped.join(", ")));
+        }
+        return Err(anyhow!(message));
+    }
+
+    report_completion(
+        workflow_name,
+        graph.len(),
+        &allowed_failures
+            .into_inner()
+            .expect("failure list lock poisoned"),
+    );
+    Ok(())
+}
+
+/// Print the final status, calling out failures the workflow was told to tolerate
+fn report_completion(workflow_name: &str, total: usize, allowed_failures: &[String]) {
+    if allowed_failures.is_empty() {
+        outln!(
+            "\n✅ Workflow '{}' completed ({} steps)",
+            workflow_name,
+            total
+        );
+        return;
+    }
+
+    outln!(
+        "\n⚠️  Workflow '{}' completed ({} steps), but {} failed: {}\n\
+         → These steps set `continue_on_error`, so the workflow kept going.",
+        workflow_name,
+        total,
+        if allowed_failures.len() == 1 {
+            "1 step"
+        } else {
+            "these steps"
+        },
+        allowed_failures.join(", ")
+    );
+}
+
+/// Save run progress; a failed save only costs the ability to resume, so it doesn't stop the run
+fn checkpoint(state: &WorkflowRunState, project_root: &Path) {
+    if let Err(e) = state.save(project_root) {
+        errln!("⚠️  Warning: Failed to save workflow progress: {:#}", e);
+    }
+}
+
+/// Check a workflow's structure before running any of its steps
+pub fn validate_workflow(workflow_name: &str, workflow: &WorkflowDefinition) -> Result<()> {
+    if workflow.steps.is_empty() {
+        anyhow::bail!("🛑 Workflow '{}' has no steps defined.", workflow_name);
+    }
+
+    for (i, step) in workflow.steps.iter().enumerate() {
+        split_step_target(&step.run)
+            .map_err(|e| anyhow!("Workflow '{}' step {}: {}", workflow_name, i + 1, e))?;
+    }
+
+    step_graph(workflow).map_err(|e| anyhow!("Workflow '{}': {}", workflow_name, e))?;
+
+    for step in &workflow.steps {
+        if let Some(timeout) = &step.timeout {
+            parse_duration(timeout).map_err(|e| {
+                anyhow!(
+                    "🛑 Workflow '{}': invalid timeout for step '{}': {}",
+                    workflow_name,
+                    step_id(step),
+                    e
+                )
+            })?;
+        }
+    }
+
+    // Parallel steps only see events from the steps they need, so a trigger without
+    // `needs` would be checked before anything could have published it
+    let uses_needs = workflow.steps.iter().any(|step| !step.needs.is_empty());
+    for step in &workflow.steps {
+        if let Some(trigger) = &step.on
+            && uses_needs
+            && step.needs.is_empty()
+        {
+            anyhow::bail!(
+                "🛑 Workflow '{}': step '{}' waits for event '{}' but has no `needs`.\n\
+                 → Add the step that publishes '{}' to its `needs`.",
+                workflow_name,
+                step_id(step),
+                trigger,
+                trigger
+            );
+        }
+    }
+
+    Ok(())
+}
+
+/// The event a step is waiting for, if it hasn't been published yet
+fn untriggered<'a>(step: &'a WorkflowStep, events: &Map<String, JsonValue>) -> Option<&'a str> {
+    step.on
+        .as_deref()
+        .filter(|trigger| !events.contains_key(*trigger))
+}
+
+/// Add a step's published events to the run's event bus, keyed by name
+fn publish_events(events: &mut Map<String, JsonValue>, result: Option<&PluginRunResult>) {
+    let published: &[PluginEvent] = result.map(|r| r.events.as_slice()).unwrap_or_default();
+    for event in published {
+        let payloads = events
+            .entry(event.name.clone())
+            .or_insert_with(|| JsonValue::Array(Vec::new()));
+        if let JsonValue::Array(payloads) = payloads {
+            payloads.push(event.payload.clone());
+        }
+    }
+}
+
+/// Build the dependency graph of a workflow's steps, keyed by each step's id
+fn step_graph(workflow: &WorkflowDefinition) -> Result<TaskGraph> {
+    TaskGraph::new(
+        workflow
+            .steps
+            .iter()
+            .map(|step| (step_id(step).to_string(), step.needs.clone()))
+            .collect(),
+    )
+}
+
+/// A step's timeout; validate_workflow has already rejected malformed values
+fn step_timeout(step: &WorkflowStep) -> Option<Duration> {
+    step.timeout
+        .as_ref()
+        .and_then(|timeout| parse_duration(timeout).ok())
+}
+
+fn step_id(step: &WorkflowStep) -> &str {
+    step.id.as_deref().unwrap_or(&step.run)
+}
+
+fn split_step_target(target: &str) -> Result<(&str, &str)> {
+    match target.split_once(':') {
+        Some((plugin, command)) if !plugin.is_empty() && !command.is_empty() => {
+            Ok((plugin, command))
+        }
+        _ => Err(anyhow!(
+            "Invalid step '{}'. Use <plugin_name>:<command_name>",
+            target
+        )),
+    }
+}
+
+/// Combine a step's declared args with any forwarded workflow-level CLI args
+fn build_step_args(
+    step: &WorkflowStep,
+    cli_args: &HashMap<String, Vec<String>>,
+) -> HashMap<String, Vec<String>> {
+    let mut args: HashMap<String, Vec<String>> = step
+        .args
+        .iter()
+        .map(|(k, v)| (k.clone(), toml_arg_values(v)))
+        .collect();
+
+    for name in &step.forward_args {
+        if let Some(value) = cli_args.get(name) {
+            args.insert(name.clone(), value.clone());
+        }
+    }
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MakeItSoConfig;
+
+    fn parse_config(toml_str: &str) -> MakeItSoConfig {
+        toml::from_str(toml_str).unwrap()
+    }
+
+    #[test]
+    fn test_workflow_parsing() {
+        let config = parse_config(
+            r#"
+[workflows.deploy-all]
+description = "Build and ship"
+steps = [
+  { run = "build:compile" },
+  { run = "docker:push", args = { tag = "latest", retries = 3 } },
+  { run = "k8s:apply", dry_run = true, forward_args = ["env"] },
+]
+"#,
+        );
+
+        let workflow = &config.workflows["deploy-all"];
+        assert_eq!(workflow.description.as_deref(), Some("Build and ship"));
+        assert_eq!(workflow.steps.len(), 3);
+        assert_eq!(workflow.steps[0].run, "build:compile");
+        assert!(workflow.steps[2].dry_run);
+        assert!(validate_workflow("deploy-all", workflow).is_ok());
+    }
+
+    #[test]
+    fn test_validate_workflow_rejects_bad_steps() {
+        let empty = WorkflowDefinition::default();
+        assert!(validate_workflow("empty", &empty).is_err());
+
+        let bad = WorkflowDefinition {
+            steps: vec![WorkflowStep {
+                run: "no-colon".to_string(),
+                ..Default::default()
+            }],
+            ..Default::default()
+        };
+        let err = validate_workflow("bad", &bad).unwrap_err().to_string();
+        assert!(err.contains("step 1"));
+    }
+
+    #[test]
+    fn test_workflow_needs_build_step_graph() {
+        let config = parse_config(
+            r#"
+[workflows.release]
+max_parallel = 2
+steps = [
+  { run = "build:compile" },
+  { id = "lint", run = "check:lint" },
+  { id = "test", run = "check:test", needs = ["build:compile"] },
+  { run = "deploy:ship", needs = ["test", "lint"] },
+]
+"#,
+        );
+
+        let workflow = &config.workflows["release"];
+        assert_eq!(workflow.max_parallel, Some(2));
+        assert!(validate_workflow("release", workflow).is_ok());
+
+        let graph = step_graph(workflow).unwrap();
+        assert_eq!(graph.id(0), "build:compile");
+        assert_eq!(graph.id(1), "lint");
+        assert_eq!(graph.id(3), "deploy:ship");
+    }
+
+    #[test]
+    fn test_validate_workflow_rejects_bad_needs() {
+        let config = parse_config(
+            r#"
+[workflows.broken]
+steps = [
+  { id = "a", run = "x:a", needs = ["b"] },
+  { id = "b", run = "x:b", needs = ["a"] },
+]
+"#,
+        );
+        let err = validate_workflow("broken", &config.workflows["broken"])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Workflow 'broken'"));
+        assert!(err.contains("cycle"));
+    }
+
+    #[test]
+    fn test_events_trigger_steps_and_collect_payloads() {
+        let config = parse_config(
+            r#"
+[workflows.ship]
+steps = [
+  { run = "docker:publish" },
+  { run = "k8s:deploy", on = "image_published" },
+]
+"#,
+        );
+        let workflow = &config.workflows["ship"];
+        assert!(validate_workflow("ship", workflow).is_ok());
+
+        let deploy = &workflow.steps[1];
+        let mut events = Map::new();
+        assert_eq!(untriggered(deploy, &events), Some("image_published"));
+        assert_eq!(untriggered(&workflow.steps[0], &events), None);
+
+        let result: PluginRunResult = serde_json::from_str(
+            r#"{"events": [
+                {"name": "image_published", "payload": {"tag": "v1"}},
+                {"name": "image_published", "payload": {"tag": "latest"}},
+                {"name": "cache_warmed"}
+            ]}"#,
+        )
+        .unwrap();
+        publish_events(&mut events, Some(&result));
+        publish_events(&mut events, None);
+
+        assert_eq!(untriggered(deploy, &events), None);
+        assert_eq!(
+            events["image_published"],
+            serde_json::json!([{"tag": "v1"}, {"tag": "latest"}])
+        );
+        assert_eq!(events["cache_warmed"], serde_json::json!([null]));
+    }
+
+    #[test]
+    fn test_validate_workflow_requires_needs_for_parallel_triggers() {
+        let config = parse_config(
+            r#"
+[workflows.ship]
+steps = [
+  { id = "publish", run = "docker:publish" },
+  { id = "scan", run = "sec:scan", needs = ["publish"] },
+  { id = "deploy", run = "k8s:deploy", on = "image_published" },
+]
+"#,
+        );
+        let err = validate_workflow("ship", &config.workflows["ship"])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("step 'deploy' waits for event 'image_published'"));
+    }
+
+    #[test]
+    fn test_step_timeout_and_failure_policies() {
+        let config = parse_config(
+            r#"
+[workflows.deploy]
+steps = [
+  { run = "build:compile", timeout = "10m" },
+  { run = "check:lint", timeout = 30, continue_on_error = true },
+  { run = "env:teardown", always_run = true },
+]
+"#,
+        );
+        let workflow = &config.workflows["deploy"];
+        assert!(validate_workflow("deploy", workflow).is_ok());
+
+        assert_eq!(
+            step_timeout(&workflow.steps[0]),
+            Some(Duration::from_secs(600))
+        );
+        assert_eq!(
+            step_timeout(&workflow.steps[1]),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(step_timeout(&workflow.steps[2]), None);
+        assert!(workflow.steps[1].continue_on_error);
+        assert!(workflow.steps[2].always_run);
+
+        let bad = parse_config(
+            r#"
+[workflows.bad]
+steps = [{ id = "slow", run = "x:y", timeout = "forever" }]
+"#,
+        );
+        let err = validate_workflow("bad", &bad.workflows["bad"])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("invalid timeout for step 'slow'"));
+    }
+
+    #[test]
+    fn test_build_step_args_converts_values_and_forwards() {
+        let mut step_args = HashMap::new();
+        step_args.insert("tag".to_string(), toml::Value::String("latest".to_string()));
+        step_args.insert("retries".to_string(), toml::Value::Integer(3));
+        step_args.insert("force".to_string(), toml::Value::Boolean(true));
+        step_args.insert(
+            "label".to_string(),
+            toml::Value::Array(vec!["a".into(), "b".into()]),
+        );
+
+        let step = WorkflowStep {
+            run: "docker:push".to_string(),
+            args: step_args,
+            forward_args: vec!["env".to_string(), "missing".to_string()],
+            ..Default::default()
+        };
+
+        let mut cli_args = HashMap::new();
+        cli_args.insert("env".to_string(), vec!["staging".to_string()]);
+        cli_args.insert("unrelated".to_string(), vec!["x".to_string()]);
+
+        let args = build_step_args(&step, &cli_args);
+        assert_eq!(args["tag"], vec!["latest"]);
+        assert_eq!(args["retries"], vec!["3"]);
+        assert_eq!(args["force"], vec!["true"]);
+        assert_eq!(args["env"], vec!["staging"]);
+        assert_eq!(args["label"], vec!["a", "b"]);
+        assert!(!args.contains_key("unrelated"));
+        assert!(!args.contains_key("missing"));
+    }
+}
@@ -0,0 +1,202 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::config::load_mis_config_from;
+
+/// The files to read, in order: each entry of `env_files` followed by its `.<profile>` variant
+/// when an environment profile is selected. Later files win over earlier ones.
+pub fn env_file_paths(
+    project_root: &Path,
+    env_files: &[String],
+    environment: Option<&str>,
+) -> Vec<PathBuf> {
+    env_files
+        .iter()
+        .flat_map(|file| {
+            let base = project_root.join(file);
+            let profile = environment.map(|name| project_root.join(format!("{}.{}", file, name)));
+            std::iter::once(base).chain(profile)
+        })
+        .collect()
+}
+
+/// Merge the variables of every env file that exists; missing files are skipped
+pub fn read_env_files(
+    project_root: &Path,
+    env_files: &[String],
+    environment: Option<&str>,
+) -> Result<BTreeMap<String, String>> {
+    let mut variables = BTreeMap::new();
+    for path in env_file_paths(project_root, env_files, environment) {
+        let Ok(contents) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let parsed = parse_env_file(&contents)
+            .with_context(|| format!("🛑 Failed to parse {}", path.display()))?;
+        debugln!(
+            "Loaded {} variable(s) from {}",
+            parsed.len(),
+            path.display()
+        );
+        variables.extend(parsed);
+    }
+    Ok(variables)
+}
+
+/// Load the project's `env_files` into this process's environment, so plugins, hooks and
+/// `${env:NAME}` references see them. Variables already set in the real environment win.
+///
+/// Must run before mis starts any threads, since it modifies the process environment.
+pub fn load_env_files(project_root: Option<&Path>, environment: Option<&str>) -> Result<()> {
+    let Some(project_root) = project_root else {
+        return Ok(());
+    };
+    let (config, _, _) = load_mis_config_from(project_root)?;
+    if config.env_files.is_empty() {
+        return Ok(());
+    }
+
+    for (name, value) in read_env_files(project_root, &config.env_files, environment)? {
+        if std::env::var_os(&name).is_none() {
+            // SAFETY: called from main before any other thread exists
+            unsafe { std::env::set_var(&name, value) };
+        }
+    }
+    Ok(())
+}
+
+/// Parse `NAME=value` lines. Supports `export NAME=value`, `#` comments, blank lines,
+/// 'single-quoted' literal values and "double-quoted" values with \n, \t, \" and \\ escapes.
+pub fn parse_env_file(contents: &str) -> Result<Vec<(String, String)>> {
+    let mut variables = Vec::new();
+
+    for (index, line) in contents.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+
+        let (name, value) = line
+            .split_once('=')
+            .ok_or_else(|| anyhow!("line {}: expected NAME=value, found '{}'", index + 1, line))?;
+        let name = name.trim();
+        let valid_name = name
+            .chars()
+            .next()
+            .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid_name {
+            anyhow::bail!("line {}: invalid variable name '{}'", index + 1, name);
+        }
+
+        let value = parse_value(value.trim())
+            .ok_or_else(|| anyhow!("line {}: unterminated quote in {}", index + 1, name))?;
+        variables.push((name.to_string(), value));
+    }
+
+    Ok(variables)
+}
+
+fn parse_value(raw: &str) -> Option<String> {
+    if let Some(rest) = raw.strip_prefix('\'') {
+        let end = rest.find('\'')?;
+        return Some(rest[..end].to_string());
+    }
+
+    if let Some(rest) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = rest.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => return Some(value),
+                '\\' => match chars.next()? {
+                    'n' => value.push('\n'),
+                    't' => value.push('\t'),
+                    other => value.push(other),
+                },
+                c => value.push(c),
+            }
+        }
+        return None;
+    }
+
+    // Unquoted: a ` #` starts a comment
+    let value = match raw.find(" #") {
+        Some(index) => &raw[..index],
+        None => raw,
+    };
+    Some(value.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_parse_env_file() {
+        let parsed = parse_env_file(
+            r#"
+# Local settings
+export AWS_PROFILE=dev
+REGION = eu-west-1   # inline comment
+GREETING="hello \"world\"\nbye"
+LITERAL='no $expansion \n here'
+EMPTY=
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            parsed,
+            vec![
+                ("AWS_PROFILE".to_string(), "dev".to_string()),
+                ("REGION".to_string(), "eu-west-1".to_string()),
+                ("GREETING".to_string(), "hello \"world\"\nbye".to_string()),
+                ("LITERAL".to_string(), "no $expansion \\n here".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+
+        let err = parse_env_file("OK=1\nnot a variable\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{}", err);
+        assert!(parse_env_file("1BAD=x").is_err());
+        assert!(parse_env_file("OPEN=\"never closed").is_err());
+    }
+
+    #[test]
+    fn test_read_env_files_order_and_profiles() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        fs::write(root.join(".env"), "REGION=us-east-1\nREPLICAS=1\n").unwrap();
+        fs::write(root.join(".env.production"), "REPLICAS=3\n").unwrap();
+        fs::write(root.join(".env.local"), "REGION=eu-west-1\n").unwrap();
+        let files = vec![".env".to_string(), ".env.local".to_string()];
+
+        assert_eq!(
+            env_file_paths(root, &files, Some("production")),
+            vec![
+                root.join(".env"),
+                root.join(".env.production"),
+                root.join(".env.local"),
+                root.join(".env.local.production"),
+            ]
+        );
+
+        let vars = read_env_files(root, &files, None).unwrap();
+        assert_eq!(vars["REGION"], "eu-west-1");
+        assert_eq!(vars["REPLICAS"], "1");
+
+        let vars = read_env_files(root, &files, Some("production")).unwrap();
+        assert_eq!(vars["REPLICAS"], "3");
+        assert_eq!(vars["REGION"], "eu-west-1");
+
+        // Missing files are skipped
+        let vars = read_env_files(root, &[".env.missing".to_string()], None).unwrap();
+        assert!(vars.is_empty());
+    }
+}
@@ -1,32 +1,1147 @@
+pub mod env_files;
+pub mod placeholders;
 pub mod plugins;
+pub mod toml_errors;
+pub mod variables;
 
+use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
-use anyhow::{Context, Result};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use anyhow::{Context, Result, anyhow};
 use toml::Value;
-use crate::{models::MakeItSoConfig, utils::find_project_root};
+use toml_edit::DocumentMut;
+use toml_errors::parse_toml;
+use crate::{
+    models::{ColorChoice, MakeItSoConfig, PluginUserConfig, RegistryConfig, Theme, UserSettings},
+    utils::require_project_root,
+};
+
+static SETTINGS: OnceLock<UserSettings> = OnceLock::new();
+static DEFAULT_SETTINGS: UserSettings = UserSettings {
+    registry: None,
+    deno_path: None,
+    container_runtime: None,
+    color: None,
+    theme: None,
+    download_limit: None,
+    credential_helper: None,
+    grace_period: None,
+    permissions: None,
+    security: None,
+};
 
 pub fn load_mis_config() -> Result<(MakeItSoConfig, PathBuf, Value)> {
-    let project_root = find_project_root()
-        .context("Could not determine project root")?;
+    let project_root = require_project_root()?;
+    let (service_config, config_path, raw_config_value) = load_mis_config_from(&project_root)?;
+
+    debugln!(
+        "Loaded {} (service: {})",
+        config_path.display(),
+        service_config.name.as_deref().unwrap_or("unknown")
+    );
+
+    Ok((service_config, config_path, raw_config_value))
+}
 
+/// Load mis.toml for the project at `project_root`, merging in any enclosing workspace variables
+pub fn load_mis_config_from(project_root: &Path) -> Result<(MakeItSoConfig, PathBuf, Value)> {
     let config_path = project_root
         .join(".makeitso")
         .join("mis.toml");
 
-    let contents = fs::read_to_string(&config_path)
+    let (mut service_config, raw_config_value) = read_mis_toml(&config_path)?;
+
+    service_config.variable_origins = service_config
+        .project_variables
+        .keys()
+        .map(|k| (k.clone(), config_path.clone()))
+        .collect();
+
+    if let Some(workspace_root) = find_workspace_root(project_root)? {
+        let workspace_config_path = workspace_root.join(".makeitso").join("mis.toml");
+        let (workspace_config, _) = read_mis_toml(&workspace_config_path)?;
+        inherit_workspace_variables(&mut service_config, &workspace_config, &workspace_config_path);
+    }
+
+    Ok((service_config, config_path, raw_config_value))
+}
+
+fn read_mis_toml(config_path: &Path) -> Result<(MakeItSoConfig, Value)> {
+    let contents = fs::read_to_string(config_path)
         .with_context(|| format!("Failed to read config file: {}", config_path.display()))?;
 
-    let service_config: MakeItSoConfig = toml::from_str(&contents)
-        .with_context(|| format!("Failed to parse TOML from: {}", config_path.display()))?;
+    let service_config: MakeItSoConfig = parse_toml(&contents, config_path)?;
+    let raw_config_value: Value = parse_toml(&contents, config_path)?;
+
+    Ok((service_config, raw_config_value))
+}
 
-    let raw_config_value: Value = contents
+/// Edit a TOML file in place, keeping its comments and formatting; a missing file starts empty
+pub fn edit_toml_file<T>(path: &Path, edit: impl FnOnce(&mut DocumentMut) -> T) -> Result<T> {
+    let contents = fs::read_to_string(path).unwrap_or_default();
+    let mut doc: DocumentMut = contents
         .parse()
-        .with_context(|| format!("Failed to parse TOML from: {}", config_path.display()))?;
+        .with_context(|| format!("🛑 Failed to parse {}", path.display()))?;
+    let result = edit(&mut doc);
+    fs::write(path, doc.to_string())
+        .with_context(|| format!("🛑 Failed to write {}", path.display()))?;
+    Ok(result)
+}
 
-    // This is kind of dummy code because I don't want to get rid of a name property that is currently unused... Bad programming.
-    let n = &service_config.name;
-    println!("Loaded config for service: {}", n.as_deref().unwrap_or("unknown"));
+/// Overlay the selected `[env.<name>]` profile's variables onto `project_variables`
+pub fn apply_env_profile(config: &mut MakeItSoConfig, environment: Option<&str>) -> Result<()> {
+    let Some(name) = environment else {
+        return Ok(());
+    };
 
-    Ok((service_config, config_path, raw_config_value))
-}
\ No newline at end of file
+    let profile = config.env.get(name).ok_or_else(|| {
+        let mut available: Vec<_> = config.env.keys().cloned().collect();
+        available.sort();
+        anyhow!(
+            "🛑 Unknown environment '{}'.\n\
+             → Define it under [env.{}] in .makeitso/mis.toml\n\
+             → Available environments: {}",
+            name,
+            name,
+            if available.is_empty() {
+                "none".to_string()
+            } else {
+                available.join(", ")
+            }
+        )
+    })?;
+
+    for (key, value) in profile.project_variables.clone() {
+        config.project_variables.insert(key, value);
+    }
+
+    Ok(())
+}
+
+/// Overlay the variables scoped to one command onto `project_variables`: first the plugin
+/// config.toml's `[commands.<command>.variables]`, then mis.toml's
+/// `[commands."<plugin>:<command>".variables]`. They take precedence over an env profile.
+pub fn apply_command_variables(
+    config: &mut MakeItSoConfig,
+    plugin_user_config: &PluginUserConfig,
+    plugin_name: &str,
+    command_name: &str,
+) {
+    if let Some(variables) = plugin_user_config.command_variables(command_name) {
+        for (key, value) in variables {
+            config.project_variables.insert(key.clone(), value.clone());
+        }
+    }
+    let plugin_command = format!("{}:{}", plugin_name, command_name);
+    if let Some(settings) = config.commands.get(&plugin_command) {
+        for (key, value) in settings.variables.clone() {
+            config.project_variables.insert(key, value);
+        }
+    }
+}
+
+/// With `env_file_variables = true`, add the env files' variables to `project_variables`.
+/// Variables defined in mis.toml or the selected profile keep their values.
+pub fn apply_env_file_variables(
+    config: &mut MakeItSoConfig,
+    project_root: &Path,
+    environment: Option<&str>,
+) -> Result<()> {
+    if !config.env_file_variables {
+        return Ok(());
+    }
+
+    for (key, value) in env_files::read_env_files(project_root, &config.env_files, environment)? {
+        config
+            .project_variables
+            .entry(key)
+            .or_insert(Value::String(value));
+    }
+    Ok(())
+}
+
+/// Expand `${name}` and `${env:NAME}` references inside project variable values.
+///
+/// Variables may reference each other in any order; cycles and undefined names are errors.
+/// Write `$${` for a literal `${`.
+pub fn interpolate_project_variables<F>(
+    variables: &mut HashMap<String, Value>,
+    env_var: F,
+) -> Result<()>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut names: Vec<String> = variables.keys().cloned().collect();
+    names.sort();
+
+    let mut resolved = HashMap::new();
+    for name in &names {
+        resolve_project_variable(name, variables, &env_var, &mut resolved, &mut Vec::new())?;
+    }
+
+    *variables = resolved;
+    Ok(())
+}
+
+/// Expand `${name}` (project variables) and `${env:NAME}` references in plugin config values
+pub fn interpolate_config_values<F>(
+    config: &mut HashMap<String, Value>,
+    project_variables: &HashMap<String, Value>,
+    env_var: F,
+) -> Result<()>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    for (key, value) in config.iter_mut() {
+        let context = format!("config key '{}'", key);
+        *value = interpolate_value(value, &mut |reference| {
+            if let Some(name) = reference.strip_prefix("env:") {
+                return lookup_env_var(name, &context, &env_var);
+            }
+            let referenced = project_variables.get(reference).ok_or_else(|| {
+                anyhow!(
+                    "🛑 Undefined variable '${{{}}}' in {}.\n\
+                     → Define '{}' under [project_variables] in .makeitso/mis.toml",
+                    reference,
+                    context,
+                    reference
+                )
+            })?;
+            value_to_interpolated_string(reference, referenced)
+        })?;
+    }
+
+    Ok(())
+}
+
+fn resolve_project_variable<F>(
+    name: &str,
+    raw: &HashMap<String, Value>,
+    env_var: &F,
+    resolved: &mut HashMap<String, Value>,
+    stack: &mut Vec<String>,
+) -> Result<Value>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    if let Some(value) = resolved.get(name) {
+        return Ok(value.clone());
+    }
+
+    if let Some(start) = stack.iter().position(|n| n == name) {
+        let mut cycle = stack[start..].to_vec();
+        cycle.push(name.to_string());
+        return Err(anyhow!(
+            "🛑 Project variables reference each other in a cycle: {}\n\
+             → Replace one of the references with a literal value.",
+            cycle.join(" → ")
+        ));
+    }
+
+    let raw_value = &raw[name];
+    let context = format!("project variable '{}'", name);
+    stack.push(name.to_string());
+
+    let value = interpolate_value(raw_value, &mut |reference| {
+        if let Some(env_name) = reference.strip_prefix("env:") {
+            return lookup_env_var(env_name, &context, env_var);
+        }
+        if !raw.contains_key(reference) {
+            return Err(anyhow!(
+                "🛑 Undefined variable '${{{}}}' in {}.\n\
+                 → Define '{}' under [project_variables] in .makeitso/mis.toml",
+                reference,
+                context,
+                reference
+            ));
+        }
+        let referenced = resolve_project_variable(reference, raw, env_var, resolved, stack)?;
+        value_to_interpolated_string(reference, &referenced)
+    })?;
+
+    stack.pop();
+    resolved.insert(name.to_string(), value.clone());
+    Ok(value)
+}
+
+fn lookup_env_var<F>(name: &str, context: &str, env_var: &F) -> Result<String>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    env_var(name).ok_or_else(|| {
+        anyhow!(
+            "🛑 Environment variable '{}' is not set (referenced by {}).\n\
+             → Export it before running, e.g. `export {}=...`",
+            name,
+            context,
+            name
+        )
+    })
+}
+
+fn value_to_interpolated_string(name: &str, value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Integer(_) | Value::Float(_) | Value::Boolean(_) | Value::Datetime(_) => {
+            Ok(value.to_string())
+        }
+        Value::Array(_) | Value::Table(_) => Err(anyhow!(
+            "🛑 Variable '{}' is a list or table and can't be inserted into a string.",
+            name
+        )),
+    }
+}
+
+/// Interpolate every string inside `value`, recursing into arrays and tables
+fn interpolate_value(
+    value: &Value,
+    resolve: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<Value> {
+    Ok(match value {
+        Value::String(s) => Value::String(interpolate_str(s, resolve)?),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .map(|item| interpolate_value(item, resolve))
+                .collect::<Result<_>>()?,
+        ),
+        Value::Table(table) => {
+            let mut interpolated = toml::map::Map::new();
+            for (key, item) in table {
+                interpolated.insert(key.clone(), interpolate_value(item, resolve)?);
+            }
+            Value::Table(interpolated)
+        }
+        other => other.clone(),
+    })
+}
+
+fn interpolate_str(
+    input: &str,
+    resolve: &mut dyn FnMut(&str) -> Result<String>,
+) -> Result<String> {
+    let mut output = String::with_capacity(input.len());
+    let mut rest = input;
+
+    while let Some(start) = rest.find('$') {
+        output.push_str(&rest[..start]);
+        let after = &rest[start..];
+
+        if let Some(escaped) = after.strip_prefix("$${") {
+            output.push_str("${");
+            rest = escaped;
+        } else if let Some(inner) = after.strip_prefix("${") {
+            let end = inner.find('}').ok_or_else(|| {
+                anyhow!("🛑 Unclosed '${{' in value: {}", input)
+            })?;
+            output.push_str(&resolve(inner[..end].trim())?);
+            rest = &inner[end + 1..];
+        } else {
+            output.push('$');
+            rest = &after[1..];
+        }
+    }
+
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Find the nearest ancestor of `project_root` whose mis.toml declares a `[workspace]`
+/// that includes this project
+pub fn find_workspace_root(project_root: &Path) -> Result<Option<PathBuf>> {
+    let mut current = project_root.to_path_buf();
+
+    while current.pop() {
+        let candidate = current.join(".makeitso").join("mis.toml");
+        if !candidate.is_file() {
+            continue;
+        }
+
+        let (config, _) = read_mis_toml(&candidate)?;
+        let Some(workspace) = config.workspace else {
+            continue;
+        };
+
+        if workspace.members.is_empty() {
+            return Ok(Some(current));
+        }
+
+        let relative = project_root.strip_prefix(&current).unwrap_or(project_root);
+        if workspace
+            .members
+            .iter()
+            .any(|member| Path::new(member.trim_end_matches('/')) == relative)
+        {
+            return Ok(Some(current));
+        }
+    }
+
+    Ok(None)
+}
+
+/// Whether the project at `dir` declares a `[workspace]` in its own mis.toml
+pub fn is_workspace_root(dir: &Path) -> Result<bool> {
+    let config_path = dir.join(".makeitso").join("mis.toml");
+    if !config_path.is_file() {
+        return Ok(false);
+    }
+    let (config, _) = read_mis_toml(&config_path)?;
+    Ok(config.workspace.is_some())
+}
+
+/// Directories never searched for nested projects
+const SKIPPED_DIRS: &[&str] = &["node_modules", "target", "vendor"];
+
+/// The member projects of the workspace at `workspace_root`: the directories listed in
+/// `members`, or else every nested project that belongs to this workspace, sorted
+pub fn workspace_members(workspace_root: &Path) -> Result<Vec<PathBuf>> {
+    let (config, _) = read_mis_toml(&workspace_root.join(".makeitso").join("mis.toml"))?;
+    let members = config.workspace.map(|w| w.members).unwrap_or_default();
+    if !members.is_empty() {
+        return Ok(members
+            .iter()
+            .map(|member| workspace_root.join(member.trim_end_matches('/')))
+            .collect());
+    }
+
+    let mut nested = Vec::new();
+    collect_nested_projects(workspace_root, &mut nested);
+    let mut members = Vec::new();
+    for project in nested {
+        // A nested workspace keeps its own members
+        if find_workspace_root(&project)?.as_deref() == Some(workspace_root) {
+            members.push(project);
+        }
+    }
+    members.sort();
+    Ok(members)
+}
+
+fn collect_nested_projects(dir: &Path, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        let is_dir = entry.file_type().is_ok_and(|kind| kind.is_dir());
+        if !is_dir || name.starts_with('.') || SKIPPED_DIRS.contains(&name.as_str()) {
+            continue;
+        }
+        let path = entry.path();
+        if path.join(".makeitso").is_dir() {
+            found.push(path.clone());
+        }
+        collect_nested_projects(&path, found);
+    }
+}
+
+/// Fill in workspace-level project variables that the project doesn't override
+fn inherit_workspace_variables(
+    project_config: &mut MakeItSoConfig,
+    workspace_config: &MakeItSoConfig,
+    workspace_config_path: &Path,
+) {
+    for (key, value) in &workspace_config.project_variables {
+        if !project_config.project_variables.contains_key(key) {
+            project_config
+                .project_variables
+                .insert(key.clone(), value.clone());
+            project_config
+                .variable_origins
+                .insert(key.clone(), workspace_config_path.to_path_buf());
+        }
+    }
+}
+
+/// Resolve settings once at startup; later calls to `settings()` see the result
+pub fn init_settings(project_root: Option<&Path>, cli_overrides: UserSettings) -> Result<()> {
+    let global_path = global_config_path();
+    let resolved = load_settings(
+        global_path.as_deref(),
+        project_root,
+        |key| std::env::var(key).ok(),
+        cli_overrides,
+    )?;
+    let _ = SETTINGS.set(resolved);
+    Ok(())
+}
+
+/// The resolved settings (plain defaults if `init_settings` hasn't run, e.g. in tests)
+pub fn settings() -> &'static UserSettings {
+    SETTINGS.get().unwrap_or(&DEFAULT_SETTINGS)
+}
+
+/// Where the user-level config lives: `$XDG_CONFIG_HOME/makeitso/config.toml`,
+/// `~/.config/makeitso/config.toml`, or `%APPDATA%\makeitso\config.toml` on Windows
+pub fn global_config_path() -> Option<PathBuf> {
+    let config_dir = if cfg!(windows) {
+        std::env::var_os("APPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    }?;
+
+    Some(config_dir.join("makeitso").join("config.toml"))
+}
+
+/// Layer settings with clear precedence: global config → project mis.toml → env vars → CLI flags
+pub fn load_settings<F>(
+    global_path: Option<&Path>,
+    project_root: Option<&Path>,
+    env_var: F,
+    cli_overrides: UserSettings,
+) -> Result<UserSettings>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut settings = UserSettings::default();
+
+    if let Some(path) = global_path.filter(|p| p.is_file()) {
+        debugln!("Reading global config {}", path.display());
+        settings = merge_settings(settings, read_settings_file(path)?);
+    }
+
+    if let Some(root) = project_root {
+        let path = root.join(".makeitso").join("mis.toml");
+        if path.is_file() {
+            debugln!("Reading project settings {}", path.display());
+            let mut project = read_settings_file(&path)?;
+            drop_untrusted_project_settings(&mut project, &path);
+            settings = merge_settings(settings, project);
+        }
+    }
+
+    settings = merge_settings(settings, settings_from_env(env_var)?);
+    Ok(merge_settings(settings, cli_overrides))
+}
+
+/// Settings that choose programs for `mis` to run only count from the global config, the
+/// environment or the command line: a project's mis.toml comes with whatever was cloned
+fn drop_untrusted_project_settings(settings: &mut UserSettings, path: &Path) {
+    let variable_commands = settings
+        .permissions
+        .as_mut()
+        .and_then(|policy| policy.variable_commands.take());
+    let ignored: Vec<&str> = [
+        ("deno_path", settings.deno_path.take().is_some()),
+        ("container_runtime", settings.container_runtime.take().is_some()),
+        ("credential_helper", settings.credential_helper.take().is_some()),
+        ("[permissions] variable_commands", variable_commands.is_some()),
+    ]
+    .into_iter()
+    .filter_map(|(key, was_set)| was_set.then_some(key))
+    .collect();
+
+    if !ignored.is_empty() {
+        errln!(
+            "⚠️  Warning: Ignoring {} in {}.\n\
+             → Set {} in your global config or the environment instead.",
+            ignored.join(", "),
+            path.display(),
+            if ignored.len() == 1 { "it" } else { "them" }
+        );
+    }
+}
+
+fn read_settings_file(path: &Path) -> Result<UserSettings> {
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read config file: {}", path.display()))?;
+    let mut settings: UserSettings = parse_toml(&contents, path)?;
+    if let Some(security) = &mut settings.security {
+        security.trusted_domain_origins = security
+            .trusted_domains
+            .iter()
+            .map(|domain| (domain.clone(), path.to_path_buf()))
+            .collect();
+    }
+    Ok(settings)
+}
+
+/// Read `MIS_REGISTRY` (comma-separated), `MIS_DENO_PATH`, `MIS_CONTAINER_RUNTIME`, `MIS_COLOR`,
+/// `NO_COLOR`, `MIS_THEME`, `MIS_DOWNLOAD_LIMIT`, `MIS_CREDENTIAL_HELPER` and `MIS_GRACE_PERIOD`
+fn settings_from_env<F>(env_var: F) -> Result<UserSettings>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let registry = env_var("MIS_REGISTRY").map(|sources| RegistryConfig {
+        sources: sources
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect(),
+    });
+
+    let color = match env_var("MIS_COLOR") {
+        Some(value) => Some(match value.to_lowercase().as_str() {
+            "auto" => ColorChoice::Auto,
+            "always" => ColorChoice::Always,
+            "never" => ColorChoice::Never,
+            _ => {
+                return Err(anyhow!(
+                    "🛑 Invalid MIS_COLOR value '{}'.\n→ Use one of: auto, always, never",
+                    value
+                ));
+            }
+        }),
+        // https://no-color.org: any non-empty value disables color
+        None => env_var("NO_COLOR")
+            .filter(|v| !v.is_empty())
+            .map(|_| ColorChoice::Never),
+    };
+
+    let theme = match env_var("MIS_THEME") {
+        Some(value) => Some(match value.to_lowercase().as_str() {
+            "fancy" => Theme::Fancy,
+            "ascii" => Theme::Ascii,
+            "minimal" => Theme::Minimal,
+            _ => {
+                return Err(anyhow!(
+                    "🛑 Invalid MIS_THEME value '{}'.\n→ Use one of: fancy, ascii, minimal",
+                    value
+                ));
+            }
+        }),
+        None => None,
+    };
+
+    let grace_period = match env_var("MIS_GRACE_PERIOD").filter(|g| !g.is_empty()) {
+        Some(value) => Some(value.trim().parse().map_err(|_| {
+            anyhow!(
+                "🛑 Invalid MIS_GRACE_PERIOD value '{}'.\n→ Use a number of seconds, e.g. 10",
+                value
+            )
+        })?),
+        None => None,
+    };
+
+    Ok(UserSettings {
+        registry,
+        deno_path: env_var("MIS_DENO_PATH").filter(|p| !p.is_empty()),
+        container_runtime: env_var("MIS_CONTAINER_RUNTIME").filter(|r| !r.is_empty()),
+        color,
+        theme,
+        download_limit: env_var("MIS_DOWNLOAD_LIMIT").filter(|l| !l.is_empty()),
+        credential_helper: env_var("MIS_CREDENTIAL_HELPER").filter(|c| !c.is_empty()),
+        grace_period,
+        permissions: None,
+        security: None,
+    })
+}
+
+/// Overlay `higher` on top of `lower`; anything `higher` leaves unset falls through
+fn merge_settings(lower: UserSettings, higher: UserSettings) -> UserSettings {
+    UserSettings {
+        registry: higher.registry.or(lower.registry),
+        deno_path: higher.deno_path.or(lower.deno_path),
+        container_runtime: higher.container_runtime.or(lower.container_runtime),
+        color: higher.color.or(lower.color),
+        theme: higher.theme.or(lower.theme),
+        download_limit: higher.download_limit.or(lower.download_limit),
+        credential_helper: higher.credential_helper.or(lower.credential_helper),
+        grace_period: higher.grace_period.or(lower.grace_period),
+        permissions: match (lower.permissions, higher.permissions) {
+            (Some(lower), Some(higher)) => Some(crate::models::PermissionsPolicy {
+                env_access: higher.env_access.or(lower.env_access),
+                variable_commands: higher.variable_commands.or(lower.variable_commands),
+            }),
+            (lower, higher) => higher.or(lower),
+        },
+        security: match (lower.security, higher.security) {
+            // Blocked commands accumulate so a project can only add to the global list
+            (Some(lower), Some(higher)) => Some(crate::models::SecurityPolicy {
+                blocked_commands: union(lower.blocked_commands, higher.blocked_commands),
+                allowed_network: union(lower.allowed_network, higher.allowed_network),
+                allow_private_network: higher
+                    .allow_private_network
+                    .or(lower.allow_private_network),
+                allow_insecure_network: higher
+                    .allow_insecure_network
+                    .or(lower.allow_insecure_network),
+                env_access: higher.env_access.or(lower.env_access),
+                strict: higher.strict.or(lower.strict),
+                trusted_domains: union(lower.trusted_domains, higher.trusted_domains),
+                trusted_domain_origins: lower
+                    .trusted_domain_origins
+                    .into_iter()
+                    .chain(higher.trusted_domain_origins)
+                    .collect(),
+                // A project can narrow the allowed dependency hosts, never widen them
+                dependency_hosts: match (lower.dependency_hosts, higher.dependency_hosts) {
+                    (Some(lower), Some(higher)) => Some(intersect_hosts(&lower, &higher)),
+                    (lower, higher) => higher.or(lower),
+                },
+                advisory_feed: higher.advisory_feed.or(lower.advisory_feed),
+            }),
+            (lower, higher) => higher.or(lower),
+        },
+    }
+}
+
+fn union(mut lower: Vec<String>, higher: Vec<String>) -> Vec<String> {
+    for value in higher {
+        if !lower.contains(&value) {
+            lower.push(value);
+        }
+    }
+    lower
+}
+
+/// Host entries allowed by both lists, keeping the narrower of two overlapping entries
+/// (`api.corp.example` out of `*.corp.example` and `api.corp.example`)
+fn intersect_hosts(lower: &[String], higher: &[String]) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+    for entry in higher {
+        for allowed in lower {
+            let narrower = if crate::security::host_matches(allowed, entry) {
+                entry
+            } else if crate::security::host_matches(entry, allowed) {
+                allowed
+            } else {
+                continue;
+            };
+            if !hosts.contains(narrower) {
+                hosts.push(narrower.clone());
+            }
+        }
+    }
+    hosts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_mis_toml(dir: &Path, contents: &str) {
+        let makeitso_dir = dir.join(".makeitso");
+        fs::create_dir_all(&makeitso_dir).unwrap();
+        fs::write(makeitso_dir.join("mis.toml"), contents).unwrap();
+    }
+
+    #[test]
+    fn test_load_mis_config_without_workspace() {
+        let temp_dir = tempdir().unwrap();
+        write_mis_toml(temp_dir.path(), "[project_variables]\nfoo = \"bar\"\n");
+
+        let (config, config_path, _) = load_mis_config_from(temp_dir.path()).unwrap();
+        assert_eq!(config.project_variables["foo"].as_str(), Some("bar"));
+        assert_eq!(config.variable_origins["foo"], config_path);
+    }
+
+    #[test]
+    fn test_project_inherits_and_overrides_workspace_variables() {
+        let temp_dir = tempdir().unwrap();
+        write_mis_toml(
+            temp_dir.path(),
+            "[workspace]\n\n[project_variables]\nregion = \"us-east-1\"\nenv = \"dev\"\n",
+        );
+        let member = temp_dir.path().join("services/api");
+        write_mis_toml(&member, "[project_variables]\nenv = \"staging\"\n");
+
+        let (config, config_path, _) = load_mis_config_from(&member).unwrap();
+        assert_eq!(config.project_variables["region"].as_str(), Some("us-east-1"));
+        assert_eq!(config.project_variables["env"].as_str(), Some("staging"));
+
+        let workspace_path = temp_dir.path().join(".makeitso/mis.toml");
+        assert_eq!(config.variable_origins["region"], workspace_path);
+        assert_eq!(config.variable_origins["env"], config_path);
+    }
+
+    #[test]
+    fn test_workspace_members_limit_inheritance() {
+        let temp_dir = tempdir().unwrap();
+        write_mis_toml(
+            temp_dir.path(),
+            "[workspace]\nmembers = [\"apps/web\"]\n\n[project_variables]\nshared = true\n",
+        );
+        let web = temp_dir.path().join("apps/web");
+        let other = temp_dir.path().join("apps/other");
+        write_mis_toml(&web, "");
+        write_mis_toml(&other, "");
+
+        let (web_config, _, _) = load_mis_config_from(&web).unwrap();
+        assert!(web_config.project_variables.contains_key("shared"));
+
+        let (other_config, _, _) = load_mis_config_from(&other).unwrap();
+        assert!(!other_config.project_variables.contains_key("shared"));
+    }
+
+    #[test]
+    fn test_parent_project_without_workspace_table_is_ignored() {
+        let temp_dir = tempdir().unwrap();
+        write_mis_toml(temp_dir.path(), "[project_variables]\nparent = 1\n");
+        let nested = temp_dir.path().join("nested");
+        write_mis_toml(&nested, "");
+
+        assert_eq!(find_workspace_root(&nested).unwrap(), None);
+    }
+
+    #[test]
+    fn test_workspace_members() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        write_mis_toml(root, "[workspace]\n");
+        write_mis_toml(&root.join("services/api"), "");
+        write_mis_toml(&root.join("services/web"), "");
+        write_mis_toml(&root.join("node_modules/dep"), "");
+        write_mis_toml(&root.join("tools"), "[workspace]\n");
+        write_mis_toml(&root.join("tools/lint"), "");
+
+        assert!(is_workspace_root(root).unwrap());
+        assert!(!is_workspace_root(&root.join("services/api")).unwrap());
+        assert_eq!(
+            workspace_members(root).unwrap(),
+            vec![
+                root.join("services/api"),
+                root.join("services/web"),
+                root.join("tools"),
+            ]
+        );
+        assert_eq!(
+            workspace_members(&root.join("tools")).unwrap(),
+            vec![root.join("tools/lint")]
+        );
+
+        write_mis_toml(root, "[workspace]\nmembers = [\"services/web/\"]\n");
+        assert_eq!(
+            workspace_members(root).unwrap(),
+            vec![root.join("services/web")]
+        );
+    }
+
+    #[test]
+    fn test_project_cannot_choose_programs_to_run() {
+        let temp_dir = tempdir().unwrap();
+        let global_path = temp_dir.path().join("global.toml");
+        fs::write(&global_path, "deno_path = \"/opt/deno\"\n").unwrap();
+        let project = temp_dir.path().join("project");
+        write_mis_toml(
+            &project,
+            "deno_path = \"./evil\"\ncontainer_runtime = \"./evil\"\n\
+             credential_helper = \"./evil\"\ncolor = \"never\"\n\n\
+             [permissions]\nenv_access = false\nvariable_commands = [\"sh\"]\n",
+        );
+
+        let settings = load_settings(
+            Some(&global_path),
+            Some(&project),
+            |_: &str| None,
+            UserSettings::default(),
+        )
+        .unwrap();
+        assert_eq!(settings.deno_path.as_deref(), Some("/opt/deno"));
+        assert_eq!(settings.container_runtime, None);
+        assert_eq!(settings.credential_helper, None);
+        assert_eq!(settings.color, Some(ColorChoice::Never));
+        let permissions = settings.permissions.unwrap();
+        assert_eq!(permissions.env_access, Some(false));
+        assert_eq!(permissions.variable_commands, None);
+    }
+
+    #[test]
+    fn test_project_cannot_widen_dependency_hosts() {
+        let temp_dir = tempdir().unwrap();
+        let global_path = temp_dir.path().join("global.toml");
+        fs::write(
+            &global_path,
+            "[security]\ndependency_hosts = [\"deno.land\", \"*.cdn.corp.example\"]\n",
+        )
+        .unwrap();
+        let project = temp_dir.path().join("project");
+        write_mis_toml(
+            &project,
+            "[security]\n\
+             dependency_hosts = [\"esm.sh\", \"deno.land\", \"eu.cdn.corp.example\"]\n",
+        );
+
+        let settings = load_settings(
+            Some(&global_path),
+            Some(&project),
+            |_: &str| None,
+            UserSettings::default(),
+        )
+        .unwrap();
+        let hosts = settings.security.unwrap().dependency_hosts.unwrap();
+        assert_eq!(hosts, vec!["deno.land", "eu.cdn.corp.example"]);
+
+        // Without a global list the project's applies as it is
+        let settings =
+            load_settings(None, Some(&project), |_: &str| None, UserSettings::default()).unwrap();
+        assert_eq!(
+            settings.security.unwrap().dependency_hosts.unwrap(),
+            vec!["esm.sh", "deno.land", "eu.cdn.corp.example"]
+        );
+    }
+
+    #[test]
+    fn test_load_settings_precedence() {
+        let temp_dir = tempdir().unwrap();
+        let global_path = temp_dir.path().join("global.toml");
+        fs::write(
+            &global_path,
+            "deno_path = \"/opt/deno\"\ncolor = \"always\"\n\n[registry]\nsources = [\"https://github.com/me/global.git\"]\n\n[permissions]\nenv_access = false\n\n[security]\nblocked_commands = [\"terraform\"]\ntrusted_domains = [\"gitlab.build.internal\"]\n",
+        )
+        .unwrap();
+
+        let project = temp_dir.path().join("project");
+        write_mis_toml(
+            &project,
+            "[registry]\nsources = [\"https://github.com/team/plugins.git\"]\n\n[security]\nblocked_commands = [\"kubectl\"]\nallow_private_network = true\ntrusted_domains = [\"nexus.corp.example\"]\n",
+        );
+
+        let no_env = |_: &str| None;
+        let settings = load_settings(
+            Some(&global_path),
+            Some(&project),
+            no_env,
+            UserSettings::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            settings.registry.unwrap().sources,
+            vec!["https://github.com/team/plugins.git"]
+        );
+        assert_eq!(settings.deno_path.as_deref(), Some("/opt/deno"));
+        assert_eq!(settings.color, Some(ColorChoice::Always));
+        assert_eq!(settings.permissions.unwrap().env_access, Some(false));
+        let security = settings.security.unwrap();
+        assert_eq!(security.blocked_commands, vec!["terraform", "kubectl"]);
+        assert_eq!(security.allow_private_network, Some(true));
+        assert_eq!(
+            security.trusted_domains,
+            vec!["gitlab.build.internal", "nexus.corp.example"]
+        );
+        assert_eq!(
+            security.trusted_domain_origins["gitlab.build.internal"],
+            global_path
+        );
+        assert_eq!(
+            security.trusted_domain_origins["nexus.corp.example"],
+            project.join(".makeitso").join("mis.toml")
+        );
+
+        let env = |key: &str| match key {
+            "MIS_DENO_PATH" => Some("/env/deno".to_string()),
+            "NO_COLOR" => Some("1".to_string()),
+            _ => None,
+        };
+        let cli = UserSettings {
+            color: Some(ColorChoice::Auto),
+            ..Default::default()
+        };
+        let settings = load_settings(Some(&global_path), Some(&project), env, cli).unwrap();
+        assert_eq!(settings.deno_path.as_deref(), Some("/env/deno"));
+        assert_eq!(settings.color, Some(ColorChoice::Auto));
+    }
+
+    #[test]
+    fn test_settings_from_env() {
+        let env = |key: &str| match key {
+            "MIS_REGISTRY" => Some("https://a.example/x.git, https://b.example/y.git,".to_string()),
+            "MIS_COLOR" => Some("NEVER".to_string()),
+            "MIS_THEME" => Some("ascii".to_string()),
+            "MIS_DOWNLOAD_LIMIT" => Some("2M".to_string()),
+            "MIS_CREDENTIAL_HELPER" => Some("gh auth token --hostname".to_string()),
+            "MIS_GRACE_PERIOD" => Some("10".to_string()),
+            _ => None,
+        };
+        let settings = settings_from_env(env).unwrap();
+        assert_eq!(settings.registry.unwrap().sources.len(), 2);
+        assert_eq!(settings.color, Some(ColorChoice::Never));
+        assert_eq!(settings.theme, Some(Theme::Ascii));
+        assert_eq!(settings.download_limit.as_deref(), Some("2M"));
+        assert_eq!(
+            settings.credential_helper.as_deref(),
+            Some("gh auth token --hostname")
+        );
+        assert_eq!(settings.grace_period, Some(10));
+
+        let bad = |key: &str| (key == "MIS_COLOR").then(|| "rainbow".to_string());
+        assert!(settings_from_env(bad).is_err());
+        let bad = |key: &str| (key == "MIS_GRACE_PERIOD").then(|| "soon".to_string());
+        assert!(settings_from_env(bad).is_err());
+        let bad = |key: &str| (key == "MIS_THEME").then(|| "sparkly".to_string());
+        assert!(settings_from_env(bad).is_err());
+    }
+
+    #[test]
+    fn test_load_settings_without_any_files() {
+        let settings = load_settings(None, None, |_| None, UserSettings::default()).unwrap();
+        assert_eq!(settings, UserSettings::default());
+    }
+
+    #[test]
+    fn test_apply_env_profile_overrides_project_variables() {
+        let mut config: MakeItSoConfig = toml::from_str(
+            r#"
+[project_variables]
+region = "us-east-1"
+replicas = 1
+
+[env.production.project_variables]
+replicas = 3
+"#,
+        )
+        .unwrap();
+
+        apply_env_profile(&mut config, None).unwrap();
+        assert_eq!(config.project_variables["replicas"].as_integer(), Some(1));
+
+        let err = apply_env_profile(&mut config, Some("staging"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Unknown environment 'staging'"));
+        assert!(err.contains("Available environments: production"));
+
+        apply_env_profile(&mut config, Some("production")).unwrap();
+        assert_eq!(config.project_variables["replicas"].as_integer(), Some(3));
+        assert_eq!(config.project_variables["region"].as_str(), Some("us-east-1"));
+    }
+
+    #[test]
+    fn test_apply_command_variables_only_for_that_command() {
+        let mis_toml = r#"
+[project_variables]
+namespace = "default"
+cluster = "main"
+region = "us-east-1"
+
+[env.production.project_variables]
+namespace = "prod"
+
+[commands."k8s:deploy".variables]
+cluster = "canary"
+"#;
+        let plugin_config: PluginUserConfig = toml::from_str(
+            r#"
+[commands.deploy.variables]
+namespace = "payments"
+cluster = "edge"
+"#,
+        )
+        .unwrap();
+
+        let mut config: MakeItSoConfig = toml::from_str(mis_toml).unwrap();
+        apply_env_profile(&mut config, Some("production")).unwrap();
+        apply_command_variables(&mut config, &plugin_config, "k8s", "deploy");
+        assert_eq!(config.project_variables["namespace"].as_str(), Some("payments"));
+        // mis.toml wins over the plugin's config.toml
+        assert_eq!(config.project_variables["cluster"].as_str(), Some("canary"));
+        assert_eq!(config.project_variables["region"].as_str(), Some("us-east-1"));
+
+        let mut config: MakeItSoConfig = toml::from_str(mis_toml).unwrap();
+        apply_command_variables(&mut config, &plugin_config, "k8s", "status");
+        assert_eq!(config.project_variables["namespace"].as_str(), Some("default"));
+        assert_eq!(config.project_variables["cluster"].as_str(), Some("main"));
+    }
+
+    #[test]
+    fn test_apply_env_file_variables_keeps_mis_toml_values() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join(".env"), "REGION=eu-west-1\nTOKEN=abc\n").unwrap();
+        let mut config: MakeItSoConfig = toml::from_str(
+            r#"
+env_files = [".env"]
+
+[project_variables]
+REGION = "us-east-1"
+"#,
+        )
+        .unwrap();
+
+        // Off unless env_file_variables is set
+        apply_env_file_variables(&mut config, temp_dir.path(), None).unwrap();
+        assert!(!config.project_variables.contains_key("TOKEN"));
+
+        config.env_file_variables = true;
+        apply_env_file_variables(&mut config, temp_dir.path(), None).unwrap();
+        assert_eq!(config.project_variables["TOKEN"].as_str(), Some("abc"));
+        assert_eq!(config.project_variables["REGION"].as_str(), Some("us-east-1"));
+    }
+
+    #[test]
+    fn test_interpolate_project_variables_resolves_references() {
+        let mut vars: HashMap<String, Value> = toml::from_str(
+            r#"
+image = "registry.example.com/${service_name}:${env:GIT_SHA}"
+service_name = "${team}-api"
+team = "payments"
+replicas = 3
+summary = "${service_name} x${replicas} costs $$${cost}"
+tags = ["${team}", "static"]
+"#,
+        )
+        .unwrap();
+        vars.insert("cost".to_string(), Value::Integer(5));
+
+        let env = |key: &str| (key == "GIT_SHA").then(|| "abc123".to_string());
+        interpolate_project_variables(&mut vars, env).unwrap();
+
+        assert_eq!(
+            vars["image"].as_str(),
+            Some("registry.example.com/payments-api:abc123")
+        );
+        assert_eq!(vars["summary"].as_str(), Some("payments-api x3 costs $${cost}"));
+        assert_eq!(vars["tags"].as_array().unwrap()[0].as_str(), Some("payments"));
+        assert_eq!(vars["replicas"].as_integer(), Some(3));
+    }
+
+    #[test]
+    fn test_interpolate_project_variables_reports_errors() {
+        let no_env = |_: &str| None;
+
+        let mut cyclic: HashMap<String, Value> =
+            toml::from_str("a = \"${b}\"\nb = \"${c}\"\nc = \"${a}\"\n").unwrap();
+        let err = interpolate_project_variables(&mut cyclic, no_env)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("cycle: a → b → c → a"));
+
+        let mut undefined: HashMap<String, Value> =
+            toml::from_str("image = \"${missing}\"\n").unwrap();
+        let err = interpolate_project_variables(&mut undefined, no_env)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Undefined variable '${missing}' in project variable 'image'"));
+
+        let mut unset_env: HashMap<String, Value> =
+            toml::from_str("sha = \"${env:GIT_SHA}\"\n").unwrap();
+        let err = interpolate_project_variables(&mut unset_env, no_env)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Environment variable 'GIT_SHA' is not set"));
+
+        let mut unclosed: HashMap<String, Value> =
+            toml::from_str("x = \"${oops\"\n").unwrap();
+        assert!(interpolate_project_variables(&mut unclosed, no_env).is_err());
+    }
+
+    #[test]
+    fn test_interpolate_config_values_uses_project_variables() {
+        let project_variables: HashMap<String, Value> =
+            toml::from_str("service_name = \"api\"\n").unwrap();
+        let mut config: HashMap<String, Value> = toml::from_str(
+            r#"
+[database]
+url = "postgres://${env:DB_HOST}/${service_name}"
+"#,
+        )
+        .unwrap();
+
+        let env = |key: &str| (key == "DB_HOST").then(|| "db.internal".to_string());
+        interpolate_config_values(&mut config, &project_variables, env).unwrap();
+        assert_eq!(
+            config["database"]["url"].as_str(),
+            Some("postgres://db.internal/api")
+        );
+
+        let mut bad: HashMap<String, Value> = toml::from_str("x = \"${nope}\"\n").unwrap();
+        let err = interpolate_config_values(&mut bad, &project_variables, env)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("in config key 'x'"));
+    }
+}
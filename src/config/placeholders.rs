@@ -0,0 +1,158 @@
+use anyhow::{Result, anyhow};
+use std::collections::HashMap;
+
+/// Find the names of all `{{ ask "name" }}` placeholders, in first-seen order
+pub fn find_placeholders(content: &str) -> Vec<String> {
+    let mut names = Vec::new();
+    for (_, _, name) in scan_placeholders(content) {
+        if !names.contains(&name) {
+            names.push(name);
+        }
+    }
+    names
+}
+
+/// Replace every `{{ ask "name" }}` placeholder with a value.
+///
+/// Values come from `provided` first; anything missing is requested through `ask`.
+/// Values are escaped so they stay valid inside a TOML basic string.
+pub fn render_placeholders<F>(
+    content: &str,
+    provided: &HashMap<String, String>,
+    mut ask: F,
+) -> Result<String>
+where
+    F: FnMut(&str) -> Result<String>,
+{
+    let mut answers: HashMap<String, String> = HashMap::new();
+    for name in find_placeholders(content) {
+        let value = match provided.get(&name) {
+            Some(value) => value.clone(),
+            None => ask(&name)?,
+        };
+        answers.insert(name, value);
+    }
+
+    let mut rendered = String::with_capacity(content.len());
+    let mut last_end = 0;
+    for (start, end, name) in scan_placeholders(content) {
+        rendered.push_str(&content[last_end..start]);
+        rendered.push_str(&escape_toml_string(&answers[&name]));
+        last_end = end;
+    }
+    rendered.push_str(&content[last_end..]);
+
+    Ok(rendered)
+}
+
+/// Parse `--var key=value` pairs passed on the command line
+pub fn parse_template_vars(vars: &[String]) -> Result<HashMap<String, String>> {
+    let mut parsed = HashMap::new();
+    for var in vars {
+        let (key, value) = var
+            .split_once('=')
+            .ok_or_else(|| anyhow!("Invalid --var '{}'. Use --var key=value", var))?;
+        let key = key.trim();
+        if key.is_empty() {
+            return Err(anyhow!("Invalid --var '{}': key cannot be empty", var));
+        }
+        parsed.insert(key.to_string(), value.to_string());
+    }
+    Ok(parsed)
+}
+
+/// Yield (start, end, name) for each well-formed placeholder in `content`
+fn scan_placeholders(content: &str) -> Vec<(usize, usize, String)> {
+    let mut found = Vec::new();
+    let mut search_from = 0;
+
+    while let Some(rel_start) = content[search_from..].find("{{") {
+        let start = search_from + rel_start;
+        let Some(rel_end) = content[start..].find("}}") else {
+            break;
+        };
+        let end = start + rel_end + 2;
+
+        if let Some(name) = parse_ask_expression(&content[start + 2..end - 2]) {
+            found.push((start, end, name));
+        }
+        search_from = end;
+    }
+
+    found
+}
+
+/// Parse the inside of `{{ ... }}`, accepting only `ask "name"`
+fn parse_ask_expression(inner: &str) -> Option<String> {
+    let rest = inner.trim().strip_prefix("ask")?.trim_start();
+    let name = rest.strip_prefix('"')?.strip_suffix('"')?;
+    if name.is_empty() || name.contains('"') {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+fn escape_toml_string(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_placeholders_dedupes_in_order() {
+        let content = r#"
+url = "{{ ask "database_url" }}"
+user = "{{ask "db_user"}}"
+backup_url = "{{ ask "database_url" }}"
+"#;
+        assert_eq!(find_placeholders(content), vec!["database_url", "db_user"]);
+    }
+
+    #[test]
+    fn test_find_placeholders_ignores_other_expressions() {
+        let content = r#"greeting = "{{ name }}" # not an ask"#;
+        assert!(find_placeholders(content).is_empty());
+    }
+
+    #[test]
+    fn test_render_placeholders_prefers_provided_values() {
+        let content = "url = \"{{ ask \"database_url\" }}\"\nport = {{ ask \"port\" }}\n";
+        let mut provided = HashMap::new();
+        provided.insert("database_url".to_string(), "postgres://db".to_string());
+
+        let mut asked = Vec::new();
+        let rendered = render_placeholders(content, &provided, |name| {
+            asked.push(name.to_string());
+            Ok("5432".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(asked, vec!["port"]);
+        assert_eq!(rendered, "url = \"postgres://db\"\nport = 5432\n");
+        assert!(toml::from_str::<toml::Value>(&rendered).is_ok());
+    }
+
+    #[test]
+    fn test_render_placeholders_escapes_quotes() {
+        let content = "motto = \"{{ ask \"motto\" }}\"";
+        let mut provided = HashMap::new();
+        provided.insert("motto".to_string(), r#"say "hi" \o/"#.to_string());
+
+        let rendered = render_placeholders(content, &provided, |_| unreachable!()).unwrap();
+        let parsed: toml::Value = toml::from_str(&rendered).unwrap();
+        assert_eq!(parsed["motto"].as_str(), Some(r#"say "hi" \o/"#));
+    }
+
+    #[test]
+    fn test_parse_template_vars() {
+        let vars = vec!["a=1".to_string(), "url=https://x?y=z".to_string()];
+        let parsed = parse_template_vars(&vars).unwrap();
+        assert_eq!(parsed["a"], "1");
+        assert_eq!(parsed["url"], "https://x?y=z");
+
+        assert!(parse_template_vars(&["novalue".to_string()]).is_err());
+        assert!(parse_template_vars(&["=x".to_string()]).is_err());
+    }
+}
@@ -1,29 +1,133 @@
 use anyhow::{Context, Result};
 use std::fs;
 use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
 
-use crate::models::{PluginManifest, PluginUserConfig};
+use toml_edit::{DocumentMut, ImDocument, Item, value};
+
+use crate::capabilities::Capabilities;
+use crate::config::toml_errors::{parse_toml, toml_error};
+use crate::manifest_schema::{line_at, unknown_keys};
+use crate::models::{FailureHint, PluginErrorHints, PluginManifest, PluginUserConfig};
+
+/// Set from `--strict` or `strict_manifests` in mis.toml
+static STRICT_MANIFESTS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_strict_manifests(strict: bool) {
+    STRICT_MANIFESTS.store(strict, Ordering::Relaxed);
+}
 
 pub fn load_plugin_manifest(path: &Path) -> Result<PluginManifest> {
     let toml_str = fs::read_to_string(path)
         .with_context(|| format!("Failed to read plugin manifest at {}", path.display()))?;
 
-    let manifest: PluginManifest = toml::from_str(&toml_str).with_context(|| {
+    if STRICT_MANIFESTS.load(Ordering::Relaxed) {
+        reject_unknown_keys(&toml_str, path)?;
+    }
+
+    let parsed: Result<PluginManifest, _> = toml::from_str(&toml_str);
+    if parsed.is_err()
+        && let Some(err) = unsupported_mis_version(&toml_str, path)
+    {
+        return Err(err);
+    }
+    let manifest = parsed.map_err(|e| toml_error(&toml_str, path, &e))?;
+
+    Ok(manifest)
+}
+
+/// Set keys under `[plugin]` in manifest.toml, editing the file in place so the author's
+/// comments, formatting and key order survive
+pub fn set_manifest_plugin_fields(manifest_path: &Path, fields: &[(&str, &str)]) -> Result<()> {
+    let contents = fs::read_to_string(manifest_path).with_context(|| {
         format!(
-            "🛑 Corrupted manifest.toml found at {}\n\
-                 → The TOML syntax is invalid. Common issues:\n\
-                 → • Missing closing brackets: [plugin\n\
-                 → • Missing quotes: version = 1.0.0 (should be \"1.0.0\")\n\
-                 → • Invalid characters or formatting\n\
-                 → Fix the syntax errors and try again.",
-            path.display()
+            "Failed to read plugin manifest at {}",
+            manifest_path.display()
         )
     })?;
+    // Refuse to touch a manifest mis couldn't load anyway
+    let _: PluginManifest = parse_toml(&contents, manifest_path)?;
 
-    Ok(manifest)
+    let mut doc: DocumentMut = contents.parse()?;
+    let plugin = doc
+        .get_mut("plugin")
+        .and_then(Item::as_table_like_mut)
+        .ok_or_else(|| anyhow::anyhow!("🛑 {} has no [plugin] table.", manifest_path.display()))?;
+    for (key, new_value) in fields {
+        plugin.insert(key, value(*new_value));
+    }
+
+    fs::write(manifest_path, doc.to_string())
+        .with_context(|| format!("🛑 Failed to write {}", manifest_path.display()))
+}
+
+/// Strict mode: fail on keys serde would silently drop, like `descripton` or `[plugin.permissions]`
+fn reject_unknown_keys(toml_str: &str, path: &Path) -> Result<()> {
+    // Syntax errors are left to the regular parse, which explains them
+    let Ok(doc) = ImDocument::parse(toml_str) else {
+        return Ok(());
+    };
+    let unknown = unknown_keys(doc.as_table());
+    if unknown.is_empty() {
+        return Ok(());
+    }
+
+    let listed: Vec<String> = unknown
+        .iter()
+        .map(|key| match &key.span {
+            Some(span) => format!(
+                "   line {}: {}\n   💡 {}",
+                line_at(toml_str, span.start),
+                key.message(),
+                key.hint
+            ),
+            None => format!("   {}\n   💡 {}", key.message(), key.hint),
+        })
+        .collect();
+    anyhow::bail!(
+        "🛑 {} has keys mis doesn't recognize:\n{}\n\
+         → Fix or remove them; `mis validate` checks every plugin at once.\n\
+         → Strict mode is on because of --strict or `strict_manifests = true` in mis.toml.",
+        path.display(),
+        listed.join("\n")
+    )
 }
 
-pub fn load_plugin_user_config(path: &Path) -> Result<PluginUserConfig> {
+/// A manifest written for another mis may not parse with this one, so check its version
+/// requirement before blaming the syntax
+fn unsupported_mis_version(toml_str: &str, path: &Path) -> Option<anyhow::Error> {
+    let raw: toml::Table = toml::from_str(toml_str).ok()?;
+    let plugin_name = raw
+        .get("plugin")
+        .and_then(|plugin| plugin.get("name"))
+        .and_then(|name| name.as_str())
+        .or_else(|| path.parent()?.file_name()?.to_str())?;
+
+    let capabilities = Capabilities::current();
+    [
+        raw.get("mis_version_requirement"),
+        raw.get("requires").and_then(|requires| requires.get("mis")),
+    ]
+    .into_iter()
+    .flatten()
+    .filter_map(|requirement| requirement.as_str())
+    .find_map(|requirement| {
+        capabilities
+            .check_mis_version(plugin_name, requirement)
+            .err()
+    })
+}
+
+/// Load a plugin's config.toml, checked against the manifest's `[config_schema]` with its
+/// defaults filled in
+pub fn load_plugin_user_config(path: &Path, manifest: &PluginManifest) -> Result<PluginUserConfig> {
+    let mut config = read_plugin_user_config(path)?;
+    apply_config_schema(&mut config, manifest, path)?;
+    Ok(config)
+}
+
+/// config.toml as written, without checking it against the plugin's schema
+pub fn read_plugin_user_config(path: &Path) -> Result<PluginUserConfig> {
     if !path.exists() {
         // config.toml is optional - return empty config if it doesn't exist
         return Ok(PluginUserConfig::default());
@@ -32,13 +136,234 @@ pub fn load_plugin_user_config(path: &Path) -> Result<PluginUserConfig> {
     let toml_str = fs::read_to_string(path)
         .with_context(|| format!("Failed to read plugin config at {}", path.display()))?;
 
-    let config: PluginUserConfig = toml::from_str(&toml_str).with_context(|| {
-        format!(
-            "🛑 Corrupted config.toml found at {}\n\
-                 → The TOML syntax is invalid. Check for syntax errors and try again.",
-            path.display()
-        )
-    })?;
+    let config: PluginUserConfig = parse_toml(&toml_str, path)?;
 
     Ok(config)
 }
+
+/// Fill in `[config_schema]` defaults for keys the user didn't set, and fail with every
+/// mistyped or missing required key at once
+pub fn apply_config_schema(
+    config: &mut PluginUserConfig,
+    manifest: &PluginManifest,
+    path: &Path,
+) -> Result<()> {
+    let mut problems = Vec::new();
+    for (key, def) in &manifest.config_schema {
+        match config.get_path(key) {
+            Some(value) => {
+                if let Some(expected) = def.value_type
+                    && !expected.matches(value)
+                {
+                    problems.push(format!(
+                        "`{}` must be {}, not {}",
+                        key,
+                        expected.with_article(),
+                        value.type_str()
+                    ));
+                }
+            }
+            None => match &def.default {
+                Some(default) => {
+                    if let Err(parent) = set_config_path(config, key, default.clone()) {
+                        problems.push(format!("`{}` must be a table", parent));
+                    }
+                }
+                None if def.required => problems.push(format!("`{}` is required", key)),
+                None => {}
+            },
+        }
+    }
+
+    if problems.is_empty() {
+        return Ok(());
+    }
+    let file = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.display().to_string());
+    anyhow::bail!(
+        "🛑 Invalid config for plugin '{}':\n{}\n\
+         → Fix {}, or run `mis validate {}` to see where each key is.",
+        manifest.plugin.name,
+        problems
+            .iter()
+            .map(|problem| format!("   {}: {}", file, problem))
+            .collect::<Vec<_>>()
+            .join("\n"),
+        path.display(),
+        manifest.plugin.name
+    )
+}
+
+/// Set a dotted key, creating the tables on the way; fails with the first part of the path
+/// that holds something other than a table
+fn set_config_path(
+    config: &mut PluginUserConfig,
+    key: &str,
+    value: toml::Value,
+) -> Result<(), String> {
+    let parts: Vec<&str> = key.split('.').collect();
+    let (last, parents) = parts.split_last().expect("split always yields a part");
+    let Some((first, rest)) = parents.split_first() else {
+        config.config.insert(last.to_string(), value);
+        return Ok(());
+    };
+
+    let mut table = config
+        .config
+        .entry(first.to_string())
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| first.to_string())?;
+    for (i, part) in rest.iter().enumerate() {
+        table = table
+            .entry(part.to_string())
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| parts[..i + 2].join("."))?;
+    }
+    table.insert(last.to_string(), value);
+    Ok(())
+}
+
+pub fn load_plugin_error_hints(path: &Path) -> Result<Vec<FailureHint>> {
+    if !path.exists() {
+        // errors.toml is optional - plugins without it just get no hints
+        return Ok(Vec::new());
+    }
+
+    let toml_str = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read plugin error hints at {}", path.display()))?;
+
+    let hints: PluginErrorHints = parse_toml(&toml_str, path)?;
+
+    Ok(hints.hints)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_plugin_manifest_reports_unsupported_mis_version() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("manifest.toml");
+
+        // A layout this mis can't read, from a plugin that says it needs a newer one
+        fs::write(
+            &path,
+            "mis_version_requirement = \">=99.0\"\ncommands = [\"deploy\"]\n\n[plugin]\nname = \"deploy\"\nversion = \"2.0.0\"\n",
+        )
+        .unwrap();
+        let err = load_plugin_manifest(&path).unwrap_err().to_string();
+        assert!(err.contains("Plugin 'deploy' needs mis >= 99.0"), "{}", err);
+
+        // Without a requirement it's reported where the manifest goes wrong
+        fs::write(
+            &path,
+            "commands = [\"deploy\"]\n\n[plugin]\nname = \"deploy\"\nversion = \"2.0.0\"\n",
+        )
+        .unwrap();
+        let err = load_plugin_manifest(&path).unwrap_err().to_string();
+        assert!(err.contains("Invalid TOML in"), "{}", err);
+        assert!(err.contains("at line 1"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_plugin_user_config_checks_schema_and_fills_defaults() {
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[config_schema.max_retries]
+type = "integer"
+default = 3
+
+[config_schema."pool.size"]
+type = "integer"
+default = 5
+
+[config_schema.database_url]
+type = "string"
+required = true
+
+[config_schema.timeout]
+type = "float"
+"#,
+        )
+        .unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let config_path = temp_dir.path().join("config.toml");
+
+        fs::write(&config_path, "database_url = \"postgres://db\"\ntimeout = 10\n").unwrap();
+        let config = load_plugin_user_config(&config_path, &manifest).unwrap();
+        assert_eq!(config.get_path("max_retries"), Some(&toml::Value::Integer(3)));
+        assert_eq!(config.get_path("pool.size"), Some(&toml::Value::Integer(5)));
+
+        fs::write(&config_path, "max_retries = \"three\"\npool = 4\n").unwrap();
+        let err = load_plugin_user_config(&config_path, &manifest)
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("config.toml: `max_retries` must be an integer, not string"));
+        assert!(err.contains("config.toml: `pool` must be a table"));
+        assert!(err.contains("config.toml: `database_url` is required"));
+    }
+
+    #[test]
+    fn test_set_manifest_plugin_fields_keeps_formatting() {
+        let temp_dir = tempdir().unwrap();
+        let path = temp_dir.path().join("manifest.toml");
+        let manifest = "# Deploys the app\n[plugin]\nname = \"deploy\"   # shown in mis list\nversion = \"1.0.0\"\nregistry = \"https://old.example.com\"\n\n[commands.prod]\nscript = \"prod.ts\"\n";
+        fs::write(&path, manifest).unwrap();
+
+        set_manifest_plugin_fields(
+            &path,
+            &[("registry", "https://example.com/plugins"), ("pin", "1.0")],
+        )
+        .unwrap();
+        let updated = fs::read_to_string(&path).unwrap();
+        assert_eq!(
+            updated,
+            manifest.replace(
+                "registry = \"https://old.example.com\"\n",
+                "registry = \"https://example.com/plugins\"\npin = \"1.0\"\n"
+            )
+        );
+
+        fs::write(
+            &path,
+            "plugin = { name = \"deploy\", version = \"1.0.0\" }\n",
+        )
+        .unwrap();
+        set_manifest_plugin_fields(&path, &[("registry", "https://example.com")]).unwrap();
+        let manifest = load_plugin_manifest(&path).unwrap();
+        assert_eq!(
+            manifest.plugin.registry.as_deref(),
+            Some("https://example.com")
+        );
+
+        fs::write(&path, "[plugin]\nname = \"deploy\"\n").unwrap();
+        assert!(set_manifest_plugin_fields(&path, &[("registry", "x")]).is_err());
+    }
+
+    #[test]
+    fn test_reject_unknown_keys() {
+        let path = Path::new(".makeitso/plugins/deploy/manifest.toml");
+        let manifest = "[plugin]\nname = \"deploy\"\nversion = \"1.0.0\"\n\n[commands.prod]\nscript = \"prod.ts\"\ndescripton = \"Deploy\"\n";
+        let err = reject_unknown_keys(manifest, path).unwrap_err().to_string();
+        assert!(
+            err.contains("line 7: Unknown key `descripton` in [commands.prod]"),
+            "{}",
+            err
+        );
+        assert!(err.contains("Did you mean `description`?"));
+
+        assert!(reject_unknown_keys(&manifest.replace("descripton", "description"), path).is_ok());
+        // Left to the regular parse
+        assert!(reject_unknown_keys("[plugin", path).is_ok());
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::Path;
+
+use anyhow::Result;
+use serde::de::DeserializeOwned;
+
+/// Parse a TOML file's contents, failing with the offending line marked and a hint
+pub fn parse_toml<T: DeserializeOwned>(contents: &str, path: &Path) -> Result<T> {
+    toml::from_str(contents).map_err(|e| toml_error(contents, path, &e))
+}
+
+/// Turn a parse error into a message that shows where the problem is:
+///
+/// ```text
+/// 🛑 Invalid TOML in .makeitso/mis.toml at line 3, column 11:
+///    2 | name = "demo"
+///    3 | version = 1.0.0
+///      |           ^^^^^
+///    expected newline, `#`
+/// → Check the marked spot for missing quotes around strings, brackets or commas.
+/// ```
+pub fn toml_error(contents: &str, path: &Path, err: &toml::de::Error) -> anyhow::Error {
+    let message = err.message().trim().replace('\n', "\n   ");
+    let hint = toml_hint(&message);
+
+    let Some(span) = err.span().filter(|span| span.start < contents.len()) else {
+        return anyhow::anyhow!(
+            "🛑 Invalid TOML in {}:\n   {}\n→ {}",
+            path.display(),
+            message,
+            hint
+        );
+    };
+
+    let line_start = contents[..span.start].rfind('\n').map_or(0, |i| i + 1);
+    let line_end = contents[span.start..]
+        .find('\n')
+        .map_or(contents.len(), |i| span.start + i);
+    let line_number = contents[..span.start].matches('\n').count() + 1;
+    let column = contents[line_start..span.start].chars().count() + 1;
+    let marked = contents[span.start..span.end.min(line_end)]
+        .chars()
+        .count()
+        .max(1);
+
+    let width = line_number.to_string().len();
+    let mut snippet = String::new();
+    if line_number > 1 {
+        let previous = contents[..line_start - 1]
+            .rsplit('\n')
+            .next()
+            .unwrap_or_default();
+        snippet.push_str(&format!(
+            "   {:>width$} | {}\n",
+            line_number - 1,
+            previous.trim_end()
+        ));
+    }
+    snippet.push_str(&format!(
+        "   {} | {}\n   {:width$} | {}{}",
+        line_number,
+        contents[line_start..line_end].trim_end(),
+        "",
+        " ".repeat(column - 1),
+        "^".repeat(marked)
+    ));
+
+    anyhow::anyhow!(
+        "🛑 Invalid TOML in {} at line {}, column {}:\n{}\n   {}\n→ {}",
+        path.display(),
+        line_number,
+        column,
+        snippet,
+        message,
+        hint
+    )
+}
+
+/// What usually fixes the error the parser reported
+fn toml_hint(message: &str) -> String {
+    if let Some(field) = message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.split('`').next())
+    {
+        return format!("Add `{}` to this table.", field);
+    }
+    let hint = if message.contains("duplicate key") {
+        "Each key and table can only be defined once; merge or remove the duplicate."
+    } else if message.contains("unknown variant") {
+        "Use one of the values listed above."
+    } else if message.contains("invalid type") {
+        "Check the value's type; text needs quotes, e.g. version = \"1.0.0\"."
+    } else if message.contains("table header") || message.contains("`]`") {
+        "Close the table header, e.g. [plugin]."
+    } else {
+        "Check the marked spot for missing quotes around strings, brackets or commas."
+    };
+    hint.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PluginMeta;
+    use std::collections::HashMap;
+
+    fn error(contents: &str) -> String {
+        parse_toml::<HashMap<String, toml::Value>>(contents, Path::new("mis.toml"))
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn test_toml_error_marks_the_offending_spot() {
+        let err = error("name = \"demo\"\nversion = 1.0.0\n");
+        assert!(
+            err.starts_with("🛑 Invalid TOML in mis.toml at line 2, column 14:\n"),
+            "{}",
+            err
+        );
+        assert!(
+            err.contains("   1 | name = \"demo\"\n   2 | version = 1.0.0\n     |              ^")
+        );
+        assert!(err.contains("→ Check the marked spot"));
+
+        let err = error("[plugin\nname = \"demo\"\n");
+        assert!(err.contains("at line 1, column 8"), "{}", err);
+        assert!(err.contains("→ Close the table header"), "{}", err);
+        assert!(err.contains("   invalid table header\n   expected"), "{}", err);
+
+        let err = error("a = 1\na = 2\n");
+        assert!(err.contains("line 2"), "{}", err);
+        assert!(err.contains("only be defined once"), "{}", err);
+    }
+
+    #[test]
+    fn test_toml_error_hints_at_type_mistakes() {
+        let err =
+            parse_toml::<PluginMeta>("name = \"demo\"\nversion = 1\n", Path::new("manifest.toml"))
+                .unwrap_err()
+                .to_string();
+        assert!(err.contains("at line 2, column 11"), "{}", err);
+        assert!(err.contains("text needs quotes"), "{}", err);
+
+        let err = parse_toml::<PluginMeta>("name = \"demo\"\n", Path::new("manifest.toml"))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Add `version` to this table."), "{}", err);
+    }
+}
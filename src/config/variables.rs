@@ -0,0 +1,532 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use toml::Value;
+
+use crate::config::settings;
+
+/// Programs `from_command` variables may run without extra configuration
+const DEFAULT_VARIABLE_COMMANDS: &[&str] = &["git", "date", "whoami", "hostname", "uname"];
+
+/// The git subcommands a variable may run: ones that only read the repository
+const GIT_VARIABLE_SUBCOMMANDS: &[&str] = &["rev-parse", "describe", "log", "rev-list"];
+
+/// Options that make a command run another program or write files, refused for every program
+const REJECTED_VARIABLE_OPTIONS: &[&str] = &[
+    "-c",
+    "--config",
+    "--config-env",
+    "--exec",
+    "--exec-path",
+    "--ext-diff",
+    "--textconv",
+    "--output",
+    "--upload-pack",
+    "--receive-pack",
+    "--set",
+];
+
+/// Outputs already computed in this process, keyed by (working directory, command)
+static COMMAND_CACHE: OnceLock<Mutex<HashMap<(PathBuf, String), String>>> = OnceLock::new();
+
+/// A `{ from_command = "...", ttl = "..." }` project variable
+#[derive(Debug, PartialEq)]
+pub struct CommandVariable<'a> {
+    pub command: &'a str,
+    /// How long the output may be reused across runs (None = recompute every run)
+    pub ttl: Option<&'a Value>,
+}
+
+/// Command outputs persisted in `.makeitso/cache/vars.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct VariableCache {
+    #[serde(default)]
+    entries: HashMap<String, CachedValue>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct CachedValue {
+    value: String,
+    /// Seconds since the Unix epoch
+    stored_at: u64,
+}
+
+/// Where TTL-cached variable values are kept
+pub fn variable_cache_path(project_root: &Path) -> PathBuf {
+    project_root
+        .join(".makeitso")
+        .join("cache")
+        .join("vars.json")
+}
+
+/// The built-in allow-list plus any `permissions.variable_commands` from settings (which a
+/// project's mis.toml can't set)
+pub fn allowed_variable_commands() -> Vec<String> {
+    let mut allowed: Vec<String> = DEFAULT_VARIABLE_COMMANDS
+        .iter()
+        .map(|c| c.to_string())
+        .collect();
+
+    if let Some(extra) = settings()
+        .permissions
+        .as_ref()
+        .and_then(|policy| policy.variable_commands.as_ref())
+    {
+        allowed.extend(extra.iter().cloned());
+    }
+
+    allowed
+}
+
+/// Replace every `{ from_command = "..." }` variable with the command's trimmed stdout.
+///
+/// Commands run without a shell from `project_root`, and each distinct command runs at
+/// most once per process so workflows don't repeat them for every step. Variables with a
+/// `ttl` reuse the output stored in `.makeitso/cache/vars.json` until it expires.
+pub fn resolve_command_variables(
+    variables: &mut HashMap<String, Value>,
+    project_root: &Path,
+    allowed: &[String],
+) -> Result<()> {
+    let cache_path = variable_cache_path(project_root);
+    let mut disk_cache: Option<VariableCache> = None;
+    let mut disk_cache_changed = false;
+    let now = unix_now();
+
+    for (name, value) in variables.iter_mut() {
+        let Some(source) = command_source(value) else {
+            continue;
+        };
+
+        let ttl = source
+            .ttl
+            .map(|ttl| {
+                parse_duration(ttl)
+                    .map_err(|e| anyhow!("🛑 Invalid ttl for project variable '{}': {}", name, e))
+            })
+            .transpose()?;
+
+        let Some(ttl) = ttl else {
+            let output = run_variable_command(name, source.command, project_root, allowed)?;
+            *value = Value::String(output);
+            continue;
+        };
+
+        let cache = disk_cache.get_or_insert_with(|| load_variable_cache(&cache_path));
+        if let Some(cached) = cache.entries.get(source.command)
+            && now.saturating_sub(cached.stored_at) < ttl.as_secs()
+        {
+            *value = Value::String(cached.value.clone());
+            continue;
+        }
+
+        let output = run_variable_command(name, source.command, project_root, allowed)?;
+        cache.entries.insert(
+            source.command.to_string(),
+            CachedValue {
+                value: output.clone(),
+                stored_at: now,
+            },
+        );
+        disk_cache_changed = true;
+        *value = Value::String(output);
+    }
+
+    if let (Some(cache), true) = (&disk_cache, disk_cache_changed) {
+        save_variable_cache(&cache_path, cache)?;
+    }
+
+    Ok(())
+}
+
+/// The command behind a `{ from_command = "..." }` table, if that's what `value` is
+pub fn command_source(value: &Value) -> Option<CommandVariable<'_>> {
+    let table = value.as_table()?;
+    if table
+        .keys()
+        .any(|key| key != "from_command" && key != "ttl")
+    {
+        return None;
+    }
+
+    Some(CommandVariable {
+        command: table.get("from_command")?.as_str()?,
+        ttl: table.get("ttl"),
+    })
+}
+
+/// Parse a duration given as seconds (`300`) or a string with a unit (`"30s"`, `"10m"`, `"2h"`, `"1d"`)
+pub fn parse_duration(value: &Value) -> Result<Duration, String> {
+    let seconds = match value {
+        Value::Integer(secs) if *secs >= 0 => *secs as u64,
+        Value::String(text) => {
+            let text = text.trim();
+            let split = text
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(text.len());
+            let (amount, unit) = text.split_at(split);
+            let amount: u64 = amount
+                .parse()
+                .map_err(|_| format!("'{}' doesn't start with a number", text))?;
+            let multiplier = match unit.trim() {
+                "" | "s" => 1,
+                "m" => 60,
+                "h" => 60 * 60,
+                "d" => 24 * 60 * 60,
+                other => return Err(format!("unknown unit '{}' (use s, m, h or d)", other)),
+            };
+            amount * multiplier
+        }
+        other => {
+            return Err(format!(
+                "expected seconds or a string like \"10m\", got {}",
+                other
+            ));
+        }
+    };
+
+    Ok(Duration::from_secs(seconds))
+}
+
+fn load_variable_cache(path: &Path) -> VariableCache {
+    // A missing or corrupted cache just means everything gets recomputed
+    fs::read_to_string(path)
+        .ok()
+        .and_then(|contents| serde_json::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+fn save_variable_cache(path: &Path, cache: &VariableCache) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    fs::write(path, serde_json::to_string_pretty(cache)?)
+        .with_context(|| format!("Failed to write variable cache: {}", path.display()))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+fn run_variable_command(
+    name: &str,
+    command: &str,
+    project_root: &Path,
+    allowed: &[String],
+) -> Result<String> {
+    let cache_key = (project_root.to_path_buf(), command.to_string());
+    let cache = COMMAND_CACHE.get_or_init(|| Mutex::new(HashMap::new()));
+    if let Some(cached) = cache.lock().unwrap().get(&cache_key) {
+        return Ok(cached.clone());
+    }
+
+    let parts = split_command(command).map_err(|e| {
+        anyhow!(
+            "🛑 Invalid from_command for project variable '{}': {}",
+            name,
+            e
+        )
+    })?;
+    let (program, args) = parts
+        .split_first()
+        .ok_or_else(|| anyhow!("🛑 Empty from_command for project variable '{}'.", name))?;
+
+    if program.contains(['/', '\\']) || !allowed.iter().any(|a| a == program) {
+        return Err(anyhow!(
+            "🛑 Project variable '{}' wants to run '{}', which isn't allowed.\n\
+             → Allowed commands: {}\n\
+             → Allow it with `variable_commands` under [permissions] in your global config.",
+            name,
+            program,
+            allowed.join(", ")
+        ));
+    }
+    if let Err(reason) = check_variable_command_args(program, args) {
+        return Err(anyhow!(
+            "🛑 Project variable '{}' can't run `{}`: {}.",
+            name,
+            command,
+            reason
+        ));
+    }
+
+    let output = Command::new(program)
+        .args(args)
+        .current_dir(project_root)
+        .output()
+        .map_err(|e| {
+            anyhow!(
+                "🛑 Failed to run '{}' for project variable '{}': {}",
+                command,
+                name,
+                e
+            )
+        })?;
+
+    if !output.status.success() {
+        return Err(anyhow!(
+            "🛑 Command for project variable '{}' failed: {}\n→ {}",
+            name,
+            command,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+
+    let value = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    cache.lock().unwrap().insert(cache_key, value.clone());
+    Ok(value)
+}
+
+/// Allowing a program allows it to read, not to run other programs: `git` only gets its
+/// read-only subcommands, and options like `-c` (`git -c alias.x='!sh ...'`) or `--output`
+/// are refused for everything
+fn check_variable_command_args(program: &str, args: &[String]) -> Result<(), String> {
+    if program == "git" {
+        match args.first() {
+            Some(subcommand) if GIT_VARIABLE_SUBCOMMANDS.contains(&subcommand.as_str()) => {}
+            _ => {
+                return Err(format!(
+                    "git may only run {}",
+                    GIT_VARIABLE_SUBCOMMANDS.join(", ")
+                ));
+            }
+        }
+    }
+
+    let rejected = args.iter().find(|arg| {
+        REJECTED_VARIABLE_OPTIONS.iter().any(|option| {
+            arg.as_str() == *option
+                || arg
+                    .strip_prefix(option)
+                    .is_some_and(|rest| rest.starts_with('=') || option.len() == 2)
+        })
+    });
+    match rejected {
+        Some(arg) => Err(format!("the option '{}' isn't allowed", arg)),
+        None => Ok(()),
+    }
+}
+
+/// Split a command line into words, honoring single and double quotes (no shell expansion)
+pub fn split_command(command: &str) -> Result<Vec<String>, String> {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for c in command.chars() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => current.push(c),
+            None if c == '"' || c == '\'' => {
+                quote = Some(c);
+                in_word = true;
+            }
+            None if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut current));
+                    in_word = false;
+                }
+            }
+            None => {
+                current.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err("unterminated quote".to_string());
+    }
+    if in_word {
+        words.push(current);
+    }
+
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn allowed(commands: &[&str]) -> Vec<String> {
+        commands.iter().map(|c| c.to_string()).collect()
+    }
+
+    #[test]
+    fn test_command_source_detects_only_from_command_tables() {
+        let vars: HashMap<String, Value> = toml::from_str(
+            r#"
+sha = { from_command = "git rev-parse HEAD" }
+account = { from_command = "git config user.email", ttl = "1h" }
+plain = "value"
+other = { from_command = "git status", extra = 1 }
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            command_source(&vars["sha"]),
+            Some(CommandVariable {
+                command: "git rev-parse HEAD",
+                ttl: None
+            })
+        );
+        let account = command_source(&vars["account"]).unwrap();
+        assert_eq!(account.ttl.and_then(Value::as_str), Some("1h"));
+        assert_eq!(command_source(&vars["plain"]), None);
+        assert_eq!(command_source(&vars["other"]), None);
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(
+            parse_duration(&Value::Integer(90)),
+            Ok(Duration::from_secs(90))
+        );
+        assert_eq!(
+            parse_duration(&Value::String("10m".to_string())),
+            Ok(Duration::from_secs(600))
+        );
+        assert_eq!(
+            parse_duration(&Value::String("2h".to_string())),
+            Ok(Duration::from_secs(7200))
+        );
+        assert_eq!(
+            parse_duration(&Value::String("45".to_string())),
+            Ok(Duration::from_secs(45))
+        );
+        assert!(parse_duration(&Value::String("soon".to_string())).is_err());
+        assert!(parse_duration(&Value::String("5w".to_string())).is_err());
+        assert!(parse_duration(&Value::Integer(-1)).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ttl_variables_use_disk_cache_until_expired() {
+        let temp_dir = tempdir().unwrap();
+        let cache_path = variable_cache_path(temp_dir.path());
+        let vars_toml = r#"
+fresh = { from_command = "echo fresh-run", ttl = "1h" }
+stale = { from_command = "echo stale-run", ttl = 60 }
+"#;
+
+        let mut cache = VariableCache::default();
+        cache.entries.insert(
+            "echo fresh-run".to_string(),
+            CachedValue {
+                value: "from-cache".to_string(),
+                stored_at: unix_now(),
+            },
+        );
+        cache.entries.insert(
+            "echo stale-run".to_string(),
+            CachedValue {
+                value: "expired".to_string(),
+                stored_at: 0,
+            },
+        );
+        save_variable_cache(&cache_path, &cache).unwrap();
+
+        let mut vars: HashMap<String, Value> = toml::from_str(vars_toml).unwrap();
+        resolve_command_variables(&mut vars, temp_dir.path(), &allowed(&["echo"])).unwrap();
+        assert_eq!(vars["fresh"].as_str(), Some("from-cache"));
+        assert_eq!(vars["stale"].as_str(), Some("stale-run"));
+
+        let saved = load_variable_cache(&cache_path);
+        assert_eq!(saved.entries["echo stale-run"].value, "stale-run");
+        assert!(saved.entries["echo stale-run"].stored_at > 0);
+    }
+
+    #[test]
+    fn test_split_command_handles_quotes() {
+        assert_eq!(
+            split_command(r#"git log -1 --format="%h %s""#).unwrap(),
+            vec!["git", "log", "-1", "--format=%h %s"]
+        );
+        assert_eq!(split_command("echo ''").unwrap(), vec!["echo", ""]);
+        assert!(split_command("echo 'oops").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_resolve_command_variables_runs_allowed_commands() {
+        let temp_dir = tempdir().unwrap();
+        let mut vars: HashMap<String, Value> = toml::from_str(
+            r#"
+greeting = { from_command = "echo 'hello world'" }
+plain = "unchanged"
+"#,
+        )
+        .unwrap();
+
+        resolve_command_variables(&mut vars, temp_dir.path(), &allowed(&["echo"])).unwrap();
+        assert_eq!(vars["greeting"].as_str(), Some("hello world"));
+        assert_eq!(vars["plain"].as_str(), Some("unchanged"));
+    }
+
+    #[test]
+    fn test_resolve_command_variables_rejects_unlisted_commands() {
+        let temp_dir = tempdir().unwrap();
+        let mut vars: HashMap<String, Value> =
+            toml::from_str(r#"secret = { from_command = "curl https://example.com" }"#).unwrap();
+
+        let err = resolve_command_variables(&mut vars, temp_dir.path(), &allowed(&["git"]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("wants to run 'curl'"));
+
+        let mut vars: HashMap<String, Value> =
+            toml::from_str(r#"sneaky = { from_command = "/usr/bin/git status" }"#).unwrap();
+        assert!(resolve_command_variables(&mut vars, temp_dir.path(), &allowed(&["git"])).is_err());
+    }
+
+    #[test]
+    fn test_allowed_commands_cannot_run_other_programs() {
+        let args = |command: &str| split_command(command).unwrap()[1..].to_vec();
+
+        assert!(check_variable_command_args("git", &args("git rev-parse --short HEAD")).is_ok());
+        assert!(check_variable_command_args("git", &args("git describe --tags")).is_ok());
+        assert!(check_variable_command_args("date", &args("date +%Y-%m-%d")).is_ok());
+
+        for command in [
+            "git -c 'alias.x=!sh -c id' x",
+            "git x",
+            "git log --output=/tmp/owned",
+            "git log --ext-diff",
+            "git rev-parse -cfoo",
+            "date --set=2020-01-01",
+        ] {
+            let parts = split_command(command).unwrap();
+            assert!(
+                check_variable_command_args(&parts[0], &parts[1..]).is_err(),
+                "{} should be refused",
+                command
+            );
+        }
+
+        let temp_dir = tempdir().unwrap();
+        let mut vars: HashMap<String, Value> = toml::from_str(
+            r#"sha = { from_command = "git -c 'alias.x=!touch owned' x" }"#,
+        )
+        .unwrap();
+        let err = resolve_command_variables(&mut vars, temp_dir.path(), &allowed(&["git"]))
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("git may only run rev-parse"), "{}", err);
+        assert!(!temp_dir.path().join("owned").exists());
+    }
+}
@@ -1,2 +1,6 @@
 pub const PLUGIN_MANIFEST_FILE: &str = "manifest.toml";
 pub const PLUGIN_CONFIG_FILE: &str = "config.toml";
+pub const PLUGIN_ERRORS_FILE: &str = "errors.toml";
+
+/// Layout version of .makeitso/ and its manifests; `mis migrate` upgrades older projects
+pub const PROJECT_SCHEMA_VERSION: u32 = 1;
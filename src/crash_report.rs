@@ -0,0 +1,160 @@
+use std::panic::{self, PanicHookInfo};
+
+use url::Url;
+
+const MIS_REPOSITORY: &str = env!("CARGO_PKG_REPOSITORY");
+
+/// Print a pre-filled GitHub issue link whenever mis itself panics
+pub fn install_panic_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info);
+
+        let title = format!("Crash: {}", truncate(&panic_message(info), 80));
+        let location = info
+            .location()
+            .map(|l| format!("{}:{}", l.file(), l.line()))
+            .unwrap_or_else(|| "unknown".to_string());
+        let body = format!(
+            "**What happened**\n\n<!-- What were you doing when mis crashed? -->\n\n\
+             **Panic**\n\n```\n{}\n```\n\nat `{}`\n\n{}",
+            redact(&panic_message(info)),
+            location,
+            diagnostics()
+        );
+
+        errln!("\n🐞 mis crashed unexpectedly. This is a bug, please report it:");
+        match github_issue_url(MIS_REPOSITORY, &title, &body) {
+            Some(url) => errln!("→ {}", url),
+            None => errln!("→ {}/issues", MIS_REPOSITORY),
+        }
+    }));
+}
+
+/// Point users at the plugin's registry repo when a plugin fails in a way no hint explains
+pub fn print_plugin_issue_link(
+    plugin_name: &str,
+    plugin_version: &str,
+    command_name: &str,
+    registry: Option<&str>,
+) {
+    let Some(registry) = registry else {
+        return;
+    };
+
+    let title = format!("{}:{} failed", plugin_name, command_name);
+    let body = format!(
+        "**What happened**\n\n<!-- Paste the relevant plugin output here. \
+         Check it for secrets first. -->\n\n\
+         **Plugin**: {} v{}\n**Command**: {}\n\n{}",
+        plugin_name,
+        plugin_version,
+        command_name,
+        diagnostics()
+    );
+
+    if let Some(url) = github_issue_url(registry, &title, &body) {
+        errln!(
+            "\n🐞 If this looks like a bug in '{}', report it:",
+            plugin_name
+        );
+        errln!("→ {}", url);
+    }
+}
+
+/// Build a "new issue" link for a GitHub repository URL (other forges aren't supported)
+pub fn github_issue_url(repo_url: &str, title: &str, body: &str) -> Option<String> {
+    let repo = Url::parse(repo_url.trim_end_matches('/')).ok()?;
+    if repo.host_str() != Some("github.com") {
+        return None;
+    }
+
+    let path = repo.path().trim_end_matches(".git");
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    let (owner, name) = (segments.next()?, segments.next()?);
+
+    let issue_url = Url::parse_with_params(
+        &format!("https://github.com/{}/{}/issues/new", owner, name),
+        &[("title", title), ("body", body)],
+    )
+    .ok()?;
+
+    Some(issue_url.to_string())
+}
+
+/// Environment details that are safe to share publicly
+fn diagnostics() -> String {
+    // Only the subcommand is included; arguments may contain secrets
+    let command = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "(none)".to_string());
+
+    format!(
+        "**Environment**\n\n- mis version: {}\n- OS: {} ({})\n- Command: `mis {}`",
+        env!("CARGO_PKG_VERSION"),
+        std::env::consts::OS,
+        std::env::consts::ARCH,
+        redact(&command)
+    )
+}
+
+fn panic_message(info: &PanicHookInfo) -> String {
+    let payload = info.payload();
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+/// Hide the user's home directory so paths don't leak usernames
+fn redact(text: &str) -> String {
+    let home = std::env::var("HOME").or_else(|_| std::env::var("USERPROFILE"));
+    match home {
+        Ok(home) if !home.is_empty() => text.replace(&home, "~"),
+        _ => text.to_string(),
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    let first_line = text.lines().next().unwrap_or_default();
+    if first_line.chars().count() <= max_chars {
+        return first_line.to_string();
+    }
+    let truncated: String = first_line.chars().take(max_chars).collect();
+    format!("{}…", truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_github_issue_url_from_repo_urls() {
+        let url = github_issue_url(
+            "https://github.com/user/registry-plugins.git",
+            "a:b failed",
+            "details & more",
+        )
+        .unwrap();
+        assert!(url.starts_with("https://github.com/user/registry-plugins/issues/new?"));
+        assert!(url.contains("title=a%3Ab+failed"));
+        assert!(url.contains("body=details+%26+more"));
+
+        assert!(github_issue_url("https://github.com/user/repo/", "t", "b").is_some());
+        assert!(github_issue_url(MIS_REPOSITORY, "t", "b").is_some());
+    }
+
+    #[test]
+    fn test_github_issue_url_rejects_other_hosts() {
+        assert!(github_issue_url("https://gitlab.com/user/repo", "t", "b").is_none());
+        assert!(github_issue_url("https://github.com/user", "t", "b").is_none());
+        assert!(github_issue_url("not a url", "t", "b").is_none());
+    }
+
+    #[test]
+    fn test_truncate_keeps_first_line() {
+        assert_eq!(truncate("short\nsecond line", 80), "short");
+        assert_eq!(truncate("abcdef", 3), "abc…");
+    }
+}
@@ -0,0 +1,89 @@
+use regex::Regex;
+
+use crate::models::FailureHint;
+
+/// Find the hints whose pattern matches the captured plugin output.
+///
+/// Hints with an invalid regex are reported and skipped rather than failing the run.
+pub fn match_failure_hints<'a>(hints: &'a [FailureHint], output: &str) -> Vec<&'a FailureHint> {
+    hints
+        .iter()
+        .filter(|hint| match Regex::new(&hint.pattern) {
+            Ok(regex) => regex.is_match(output),
+            Err(e) => {
+                errln!(
+                    "⚠️  Warning: Ignoring invalid pattern '{}' in errors.toml: {}",
+                    hint.pattern,
+                    e
+                );
+                false
+            }
+        })
+        .collect()
+}
+
+pub fn print_failure_hints(hints: &[&FailureHint]) {
+    for hint in hints {
+        errln!("\n💡 {}", hint.explanation);
+        for step in &hint.remediation {
+            errln!("   → {}", step);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::PluginErrorHints;
+
+    fn parse_hints(toml_str: &str) -> Vec<FailureHint> {
+        toml::from_str::<PluginErrorHints>(toml_str).unwrap().hints
+    }
+
+    #[test]
+    fn test_match_failure_hints_finds_matching_patterns() {
+        let hints = parse_hints(
+            r#"
+[[hint]]
+pattern = "ECONNREFUSED .*:5432"
+explanation = "Postgres isn't reachable."
+remediation = ["Start it with `docker compose up db`"]
+
+[[hint]]
+pattern = "(?i)permission denied"
+explanation = "The plugin can't access a file."
+"#,
+        );
+
+        let output = "Connecting...\nError: connect ECONNREFUSED 127.0.0.1:5432\n";
+        let matched = match_failure_hints(&hints, output);
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].explanation, "Postgres isn't reachable.");
+        assert_eq!(matched[0].remediation.len(), 1);
+
+        let matched = match_failure_hints(&hints, "PERMISSION DENIED: read access");
+        assert_eq!(matched.len(), 1);
+        assert!(matched[0].remediation.is_empty());
+
+        assert!(match_failure_hints(&hints, "all good").is_empty());
+    }
+
+    #[test]
+    fn test_match_failure_hints_skips_invalid_patterns() {
+        let hints = parse_hints(
+            r#"
+[[hint]]
+pattern = "unclosed ("
+explanation = "Broken"
+
+[[hint]]
+pattern = "timeout"
+explanation = "The request timed out."
+"#,
+        );
+
+        let matched = match_failure_hints(&hints, "unclosed ( timeout");
+        assert_eq!(matched.len(), 1);
+        assert_eq!(matched[0].explanation, "The request timed out.");
+    }
+}
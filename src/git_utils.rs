@@ -1,8 +1,14 @@
 use anyhow::anyhow;
+use serde::Serialize;
+use std::path::Path;
 use std::process::Command;
 
+use crate::integrations::registry_auth::{git_auth_env, redact, registry_token};
+
 pub fn shallow_clone_repo(repo_uri: String, target_dir: String) -> anyhow::Result<()> {
+    let token = registry_token(&repo_uri);
     let output = Command::new("git")
+        .envs(auth_env(&repo_uri, token.as_deref()))
         .arg("clone")
         .arg("--depth")
         .arg("1")
@@ -11,9 +17,153 @@ pub fn shallow_clone_repo(repo_uri: String, target_dir: String) -> anyhow::Resul
         .output()?;
 
     if !output.status.success() {
-        let error_message = String::from_utf8_lossy(&output.stderr);
+        let error_message = redact(&String::from_utf8_lossy(&output.stderr), token.as_deref());
         return Err(anyhow!("Failed to clone repository: {}", error_message));
     }
 
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Bring a shallow clone up to date with its remote's default branch
+pub fn fetch_latest(repo_dir: &Path, repo_uri: &str) -> anyhow::Result<()> {
+    let token = registry_token(repo_uri);
+    let fetch = Command::new("git")
+        .envs(auth_env(repo_uri, token.as_deref()))
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["fetch", "--depth", "1", "origin"])
+        .output()?;
+    if !fetch.status.success() {
+        let error_message = redact(&String::from_utf8_lossy(&fetch.stderr), token.as_deref());
+        return Err(anyhow!("Failed to fetch repository: {}", error_message));
+    }
+
+    let reset = Command::new("git")
+        .arg("-C")
+        .arg(repo_dir)
+        .args(["reset", "--hard", "FETCH_HEAD"])
+        .output()?;
+    if !reset.status.success() {
+        let error_message = String::from_utf8_lossy(&reset.stderr);
+        return Err(anyhow!("Failed to update repository: {}", error_message));
+    }
+
+    Ok(())
+}
+
+/// The state of the project's checkout, passed to plugins that ask for it as `ctx.git`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct GitInfo {
+    /// None on a detached HEAD, as in most CI checkouts
+    pub branch: Option<String>,
+    /// None before the first commit
+    pub commit: Option<String>,
+    /// Tag pointing at HEAD, if any
+    pub tag: Option<String>,
+    /// Whether there are uncommitted changes, including untracked files
+    pub dirty: bool,
+}
+
+/// Read branch, commit, tag and dirty state; None if `dir` isn't in a git repository
+pub fn git_info(dir: &Path) -> Option<GitInfo> {
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git").arg("-C").arg(dir).args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    git(&["rev-parse", "--is-inside-work-tree"])?;
+
+    Some(GitInfo {
+        branch: git(&["symbolic-ref", "--short", "-q", "HEAD"]).filter(|b| !b.is_empty()),
+        commit: git(&["rev-parse", "--verify", "-q", "HEAD"]).filter(|c| !c.is_empty()),
+        tag: git(&["describe", "--tags", "--exact-match", "HEAD"]).filter(|t| !t.is_empty()),
+        dirty: git(&["status", "--porcelain"]).is_some_and(|status| !status.is_empty()),
+    })
+}
+
+/// The commit checked out in the repository whose top level is `repo`; None if `repo` isn't
+/// the root of a git checkout (e.g. an unpacked archive that sits inside another repository)
+pub fn head_commit(repo: &Path) -> Option<String> {
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git").arg("-C").arg(repo).args(args).output().ok()?;
+        output
+            .status
+            .success()
+            .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+    };
+
+    let top_level = git(&["rev-parse", "--show-toplevel"])?;
+    if Path::new(&top_level).canonicalize().ok()? != repo.canonicalize().ok()? {
+        return None;
+    }
+    git(&["rev-parse", "--verify", "-q", "HEAD"]).filter(|commit| !commit.is_empty())
+}
+
+fn auth_env(repo_uri: &str, token: Option<&str>) -> Vec<(String, String)> {
+    token
+        .map(|token| git_auth_env(repo_uri, token))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn git(dir: &Path, args: &[&str]) {
+        let status = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["-c", "user.name=mis", "-c", "user.email=mis@example.com"])
+            .args(args)
+            .output()
+            .unwrap()
+            .status;
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_git_info() {
+        let temp_dir = tempdir().unwrap();
+        let repo = temp_dir.path();
+        assert_eq!(git_info(repo), None);
+
+        git(repo, &["init", "-q", "-b", "main"]);
+        let info = git_info(repo).unwrap();
+        assert_eq!(info.branch.as_deref(), Some("main"));
+        assert_eq!(info.commit, None);
+        assert!(!info.dirty);
+
+        fs::write(repo.join("README.md"), "hello\n").unwrap();
+        assert!(git_info(repo).unwrap().dirty);
+
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "-q", "-m", "init"]);
+        git(repo, &["tag", "v1.0.0"]);
+        let info = git_info(repo).unwrap();
+        assert_eq!(info.commit.as_ref().map(String::len), Some(40));
+        assert_eq!(info.tag.as_deref(), Some("v1.0.0"));
+        assert!(!info.dirty);
+
+        git(repo, &["checkout", "-q", "--detach"]);
+        assert_eq!(git_info(repo).unwrap().branch, None);
+    }
+
+    #[test]
+    fn test_head_commit_only_at_the_repository_root() {
+        let temp_dir = tempdir().unwrap();
+        let repo = temp_dir.path();
+        git(repo, &["init", "-q", "-b", "main"]);
+        fs::create_dir(repo.join("plugins")).unwrap();
+        fs::write(repo.join("plugins/mod.ts"), "export {};\n").unwrap();
+        git(repo, &["add", "."]);
+        git(repo, &["commit", "-q", "-m", "init"]);
+
+        assert_eq!(head_commit(repo), git_info(repo).unwrap().commit);
+        assert_eq!(head_commit(&repo.join("plugins")), None);
+    }
+}
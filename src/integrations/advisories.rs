@@ -0,0 +1,441 @@
+//! Known vulnerabilities in plugins' Deno dependencies, looked up on OSV.dev or in an
+//! OSV-format feed the organization hosts itself (`[security] advisory_feed`)
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, anyhow};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::config::settings;
+
+pub const OSV_QUERY_URL: &str = "https://api.osv.dev/v1/query";
+
+/// A versioned package a dependency URL points at, named the way advisories name it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Package {
+    /// `npm`, `JSR` or `deno.land`
+    pub ecosystem: String,
+    pub name: String,
+    pub version: String,
+}
+
+/// One known vulnerability affecting a dependency's version
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Advisory {
+    pub id: String,
+    pub summary: Option<String>,
+
+    /// As the advisory rates it, e.g. `HIGH` or `MODERATE` (`UNKNOWN` if it doesn't)
+    pub severity: String,
+
+    /// Versions that fix it
+    pub fixed: Vec<String>,
+}
+
+/// Where advisories come from
+pub enum AdvisorySource {
+    /// The public OSV.dev database (npm packages only)
+    Osv,
+
+    /// OSV records from a configured feed, and where it was read from
+    Feed {
+        location: String,
+        records: Vec<Value>,
+    },
+}
+
+impl AdvisorySource {
+    /// The configured feed, or OSV.dev when there's none
+    pub fn configured(project_root: &Path) -> Result<Self> {
+        let Some(location) = settings()
+            .security
+            .as_ref()
+            .and_then(|security| security.advisory_feed.clone())
+        else {
+            return Ok(Self::Osv);
+        };
+        let records = load_feed(&location, project_root)?;
+        Ok(Self::Feed { location, records })
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            Self::Osv => "OSV.dev".to_string(),
+            Self::Feed { location, .. } => location.clone(),
+        }
+    }
+
+    /// Whether this source has data for the package's ecosystem
+    pub fn covers(&self, package: &Package) -> bool {
+        match self {
+            Self::Osv => package.ecosystem == "npm",
+            Self::Feed { .. } => true,
+        }
+    }
+
+    /// Advisories affecting exactly this package version
+    pub fn advisories(&self, package: &Package) -> Result<Vec<Advisory>> {
+        let records = match self {
+            Self::Osv => query_osv(package)?,
+            Self::Feed { records, .. } => records.clone(),
+        };
+        Ok(records
+            .iter()
+            .filter(|record| affects(record, package))
+            .map(|record| advisory(record, package))
+            .collect())
+    }
+}
+
+/// A feed is a JSON array of OSV records, or an object with them under `vulns`; it can be a
+/// file (relative to the project root) or an http(s) URL
+fn load_feed(location: &str, project_root: &Path) -> Result<Vec<Value>> {
+    let contents = if location.starts_with("https://") || location.starts_with("http://") {
+        let output = Command::new("curl")
+            .args(["--fail", "--silent", "--show-error", "--location"])
+            .args(["--max-time", "30", location])
+            .output()
+            .map_err(|e| {
+                anyhow!(
+                    "🛑 Failed to run curl: {}\n→ Install curl to fetch advisory feeds.",
+                    e
+                )
+            })?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "🛑 Failed to download the advisory feed {}: {}\n\
+                 → Check advisory_feed under [security] in your config.",
+                location,
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+        String::from_utf8_lossy(&output.stdout).to_string()
+    } else {
+        let path = project_root.join(location);
+        fs::read_to_string(&path)
+            .with_context(|| format!("🛑 Failed to read the advisory feed {}", path.display()))?
+    };
+
+    let feed: Value = serde_json::from_str(&contents)
+        .with_context(|| format!("🛑 The advisory feed {} isn't valid JSON", location))?;
+    match feed {
+        Value::Array(records) => Ok(records),
+        Value::Object(mut object) => match object.remove("vulns") {
+            Some(Value::Array(records)) => Ok(records),
+            _ => anyhow::bail!(
+                "🛑 The advisory feed {} has no `vulns` list.\n\
+                 → Use a JSON array of OSV records, or an object with them under `vulns`.",
+                location
+            ),
+        },
+        _ => anyhow::bail!(
+            "🛑 The advisory feed {} isn't a list of OSV records.",
+            location
+        ),
+    }
+}
+
+fn query_osv(package: &Package) -> Result<Vec<Value>> {
+    let query = serde_json::json!({
+        "package": { "ecosystem": package.ecosystem, "name": package.name },
+        "version": package.version,
+    });
+    let output = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--max-time", "30"])
+        .args(["--header", "Content-Type: application/json"])
+        .args(["--data", &query.to_string(), OSV_QUERY_URL])
+        .output()
+        .map_err(|e| {
+            anyhow!(
+                "🛑 Failed to run curl: {}\n→ Install curl to query OSV.dev.",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "🛑 Failed to query OSV.dev for {} {}: {}\n\
+             → Check your network connection, or set advisory_feed under [security] to use your own feed.",
+            package.name,
+            package.version,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    let response: Value =
+        serde_json::from_slice(&output.stdout).context("🛑 OSV.dev returned invalid JSON")?;
+    Ok(response["vulns"].as_array().cloned().unwrap_or_default())
+}
+
+/// The package behind a dependency URL or specifier, for the registries and CDNs whose
+/// URLs name one: esm.sh, unpkg, jsDelivr and Skypack (npm), jsr.io and deno.land
+pub fn package_for_url(url: &str) -> Option<Package> {
+    if let Some(spec) = url.strip_prefix("npm:") {
+        return name_at_version("npm", spec.trim_start_matches('/'));
+    }
+    if let Some(spec) = url.strip_prefix("jsr:") {
+        return name_at_version("JSR", spec.trim_start_matches('/'));
+    }
+
+    let parsed = url::Url::parse(url).ok()?;
+    let segments: Vec<&str> = parsed.path_segments()?.filter(|s| !s.is_empty()).collect();
+    match parsed.host_str()? {
+        "esm.sh" => {
+            // Skip esm.sh's build prefix, e.g. /v135/react@18.2.0
+            let start = segments.first().is_some_and(|s| {
+                s.len() > 1 && s.starts_with('v') && s[1..].chars().all(|c| c.is_ascii_digit())
+            }) as usize;
+            npm_package(&segments[start..])
+        }
+        "unpkg.com" | "cdn.skypack.dev" => npm_package(&segments),
+        "cdn.jsdelivr.net" if segments.first() == Some(&"npm") => npm_package(&segments[1..]),
+        "jsr.io" => match segments.as_slice() {
+            [scope, name, version, ..] if scope.starts_with('@') => Some(Package {
+                ecosystem: "JSR".to_string(),
+                name: format!("{}/{}", scope, name),
+                version: version.to_string(),
+            }),
+            _ => None,
+        },
+        "deno.land" => match segments.as_slice() {
+            ["x", module, ..] => name_at_version("deno.land", module),
+            [module, ..] => name_at_version("deno.land", module),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// `react@18.2.0/...` or `@scope/name@1.0.0/...`
+fn npm_package(segments: &[&str]) -> Option<Package> {
+    match segments {
+        [scope, name, ..] if scope.starts_with('@') => {
+            name_at_version("npm", &format!("{}/{}", scope, name))
+        }
+        [name, ..] => name_at_version("npm", name),
+        [] => None,
+    }
+}
+
+fn name_at_version(ecosystem: &str, spec: &str) -> Option<Package> {
+    let spec = spec.split(['/', '?', '#']).collect::<Vec<_>>();
+    // A scoped name keeps its first segment: @scope/name@1.0.0
+    let spec = match spec.as_slice() {
+        [scope, name, ..] if scope.starts_with('@') => format!("{}/{}", scope, name),
+        [name, ..] => name.to_string(),
+        [] => return None,
+    };
+    let (name, version) = spec.rsplit_once('@').filter(|(name, _)| !name.is_empty())?;
+    let version = version.trim_start_matches('v');
+    version
+        .starts_with(|c: char| c.is_ascii_digit())
+        .then(|| Package {
+            ecosystem: ecosystem.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+        })
+}
+
+/// Whether an OSV record affects `package`: listed in `versions`, or inside one of its ranges
+pub fn affects(record: &Value, package: &Package) -> bool {
+    let Some(affected) = record["affected"].as_array() else {
+        return false;
+    };
+    affected.iter().any(|entry| {
+        let same_package = entry["package"]["name"].as_str() == Some(package.name.as_str())
+            && entry["package"]["ecosystem"]
+                .as_str()
+                .is_some_and(|ecosystem| ecosystem.eq_ignore_ascii_case(&package.ecosystem));
+        if !same_package {
+            return false;
+        }
+        let listed = entry["versions"].as_array().is_some_and(|versions| {
+            versions
+                .iter()
+                .any(|v| v.as_str() == Some(&package.version))
+        });
+        listed
+            || entry["ranges"]
+                .as_array()
+                .is_some_and(|ranges| ranges.iter().any(|range| in_range(range, &package.version)))
+    })
+}
+
+/// OSV range events, in order: `introduced` opens an affected span, `fixed` and
+/// `last_affected` close it
+fn in_range(range: &Value, version: &str) -> bool {
+    let Some(version) = version_key(version) else {
+        return false;
+    };
+    let mut affected = false;
+    for event in range["events"].as_array().into_iter().flatten() {
+        if let Some(introduced) = event["introduced"].as_str() {
+            if introduced == "0" || version_key(introduced).is_some_and(|v| version >= v) {
+                affected = true;
+            }
+        } else if let Some(fixed) = event["fixed"].as_str() {
+            if version_key(fixed).is_some_and(|v| version >= v) {
+                affected = false;
+            }
+        } else if let Some(last) = event["last_affected"].as_str()
+            && version_key(last).is_some_and(|v| version > v)
+        {
+            affected = false;
+        }
+    }
+    affected
+}
+
+/// Comparable form of a semver version; a pre-release sorts before its release
+fn version_key(version: &str) -> Option<(u64, u64, u64, bool)> {
+    let version = version.trim().trim_start_matches('v');
+    let (release, pre) = match version.split_once(['-', '+']) {
+        Some((release, rest)) => (
+            release,
+            !version[release.len()..].starts_with('+') && !rest.is_empty(),
+        ),
+        None => (version, false),
+    };
+    let (major, minor, patch) = crate::capabilities::parse_version(release)?;
+    Some((major, minor, patch, !pre))
+}
+
+fn advisory(record: &Value, package: &Package) -> Advisory {
+    let severity = record["database_specific"]["severity"]
+        .as_str()
+        .or_else(|| {
+            record["affected"]
+                .as_array()?
+                .iter()
+                .find_map(|entry| entry["ecosystem_specific"]["severity"].as_str())
+        })
+        .unwrap_or("UNKNOWN")
+        .to_uppercase();
+    let fixed = record["affected"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter(|entry| entry["package"]["name"].as_str() == Some(package.name.as_str()))
+        .flat_map(|entry| entry["ranges"].as_array().into_iter().flatten())
+        .flat_map(|range| range["events"].as_array().into_iter().flatten())
+        .filter_map(|event| event["fixed"].as_str().map(str::to_string))
+        .collect();
+    Advisory {
+        id: record["id"].as_str().unwrap_or("unknown").to_string(),
+        summary: record["summary"].as_str().map(str::to_string),
+        severity,
+        fixed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn package(ecosystem: &str, name: &str, version: &str) -> Package {
+        Package {
+            ecosystem: ecosystem.to_string(),
+            name: name.to_string(),
+            version: version.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_package_for_url() {
+        assert_eq!(
+            package_for_url("https://esm.sh/react@18.2.0"),
+            Some(package("npm", "react", "18.2.0"))
+        );
+        assert_eq!(
+            package_for_url("https://esm.sh/v135/@types/node@20.1.0/index.d.ts"),
+            Some(package("npm", "@types/node", "20.1.0"))
+        );
+        assert_eq!(
+            package_for_url("https://cdn.jsdelivr.net/npm/lodash@4.17.15/lodash.js"),
+            Some(package("npm", "lodash", "4.17.15"))
+        );
+        assert_eq!(
+            package_for_url("https://deno.land/x/oak@v12.6.1/mod.ts"),
+            Some(package("deno.land", "oak", "12.6.1"))
+        );
+        assert_eq!(
+            package_for_url("https://deno.land/std@0.224.0/path/mod.ts"),
+            Some(package("deno.land", "std", "0.224.0"))
+        );
+        assert_eq!(
+            package_for_url("https://jsr.io/@std/path/1.0.8/mod.ts"),
+            Some(package("JSR", "@std/path", "1.0.8"))
+        );
+        assert_eq!(
+            package_for_url("npm:@scope/pkg@2.0.0"),
+            Some(package("npm", "@scope/pkg", "2.0.0"))
+        );
+        // Unversioned or unknown hosts can't be checked
+        assert_eq!(package_for_url("https://deno.land/x/oak/mod.ts"), None);
+        assert_eq!(package_for_url("https://example.com/lib@1.0.0.ts"), None);
+    }
+
+    #[test]
+    fn test_affects_follows_ranges_and_versions() {
+        let record = json!({
+            "id": "GHSA-p6mc-m468-83gw",
+            "summary": "Prototype Pollution in lodash",
+            "database_specific": { "severity": "HIGH" },
+            "affected": [{
+                "package": { "ecosystem": "npm", "name": "lodash" },
+                "ranges": [{
+                    "type": "SEMVER",
+                    "events": [{ "introduced": "0" }, { "fixed": "4.17.19" }]
+                }],
+                "versions": ["5.0.0-beta"]
+            }]
+        });
+
+        assert!(affects(&record, &package("npm", "lodash", "4.17.15")));
+        assert!(affects(&record, &package("npm", "lodash", "4.17.19-rc.1")));
+        assert!(!affects(&record, &package("npm", "lodash", "4.17.19")));
+        assert!(affects(&record, &package("npm", "lodash", "5.0.0-beta")));
+        assert!(!affects(&record, &package("npm", "underscore", "1.0.0")));
+        assert!(!affects(&record, &package("JSR", "lodash", "4.17.15")));
+
+        let found = advisory(&record, &package("npm", "lodash", "4.17.15"));
+        assert_eq!(found.severity, "HIGH");
+        assert_eq!(found.fixed, vec!["4.17.19"]);
+    }
+
+    #[test]
+    fn test_last_affected_closes_the_range() {
+        let range = json!({
+            "events": [{ "introduced": "1.2.0" }, { "last_affected": "1.4.0" }]
+        });
+        assert!(!in_range(&range, "1.1.9"));
+        assert!(in_range(&range, "1.2.0"));
+        assert!(in_range(&range, "1.4.0"));
+        assert!(!in_range(&range, "1.4.1"));
+    }
+
+    #[test]
+    fn test_load_feed_accepts_a_list_or_vulns_object() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("list.json"), r#"[{"id": "A"}]"#).unwrap();
+        fs::write(
+            temp_dir.path().join("object.json"),
+            r#"{"vulns": [{"id": "B"}]}"#,
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("bad.json"), r#"{"id": "C"}"#).unwrap();
+
+        assert_eq!(
+            load_feed("list.json", temp_dir.path()).unwrap()[0]["id"],
+            "A"
+        );
+        assert_eq!(
+            load_feed("object.json", temp_dir.path()).unwrap()[0]["id"],
+            "B"
+        );
+        assert!(load_feed("bad.json", temp_dir.path()).is_err());
+        assert!(load_feed("missing.json", temp_dir.path()).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::models::{ArtifactSink, ArtifactsConfig};
+use crate::plugin_utils::ensure_path_component;
+
+/// Directory a plugin command writes its artifacts to, emptied before every run
+pub fn artifacts_dir(
+    project_root: &Path,
+    plugin_name: &str,
+    command_name: &str,
+) -> Result<PathBuf> {
+    ensure_path_component("plugin name", plugin_name)?;
+    ensure_path_component("command name", command_name)?;
+    Ok(project_root
+        .join(".makeitso")
+        .join("artifacts")
+        .join(plugin_name)
+        .join(command_name))
+}
+
+/// Start the run with an empty artifacts directory so only this run's files get uploaded
+pub fn prepare_artifacts_dir(project_root: &Path, dir: &Path) -> Result<()> {
+    // Never clear anything outside .makeitso/artifacts, symlinks included
+    let root = project_root.join(".makeitso").join("artifacts");
+    let inside = |dir: &Path, root: &Path| dir.starts_with(root) && dir != root;
+    let resolved_inside = !dir.exists()
+        || matches!(
+            (dir.canonicalize(), root.canonicalize()),
+            (Ok(dir), Ok(root)) if inside(&dir, &root)
+        );
+    if !inside(dir, &root) || !resolved_inside {
+        return Err(anyhow!(
+            "🛑 Refusing to clear {}: it isn't inside {}.",
+            dir.display(),
+            root.display()
+        ));
+    }
+
+    if dir.exists() {
+        fs::remove_dir_all(dir)
+            .with_context(|| format!("Failed to clear artifacts directory: {}", dir.display()))?;
+    }
+    fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create artifacts directory: {}", dir.display()))
+}
+
+/// Upload every file in `dir` with the configured sink and return where each one went.
+///
+/// Uploads shell out to the provider's own CLI (`aws`, `gcloud`, `az`) or `curl`, so
+/// credentials come from however those tools are already logged in.
+pub fn upload_artifacts(
+    config: &ArtifactsConfig,
+    dir: &Path,
+    plugin_name: &str,
+    command_name: &str,
+) -> Result<Vec<String>> {
+    validate_destination(config)?;
+
+    let files = collect_files(dir)?;
+    if files.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let token = match &config.token_env {
+        Some(name) => Some(std::env::var(name).map_err(|_| {
+            anyhow!(
+                "🛑 Artifact upload needs a token in ${}, but it isn't set.",
+                name
+            )
+        })?),
+        None => None,
+    };
+
+    let started = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let run_prefix = format!("{}/{}/{}", plugin_name, command_name, started);
+
+    let mut uploaded = Vec::with_capacity(files.len());
+    for relative in &files {
+        let url = remote_url(config, &run_prefix, relative);
+        let mut command = upload_command(config, &dir.join(relative), &url, token.is_some());
+        run_upload(&mut command, token.as_deref()).with_context(|| {
+            format!(
+                "🛑 Failed to upload artifact {}\n→ Check that the {} CLI is installed and logged in.",
+                relative.display(),
+                sink_tool(config.sink)
+            )
+        })?;
+        uploaded.push(url);
+    }
+
+    Ok(uploaded)
+}
+
+fn validate_destination(config: &ArtifactsConfig) -> Result<()> {
+    let scheme = match config.sink {
+        ArtifactSink::S3 => "s3://",
+        ArtifactSink::Gcs => "gs://",
+        ArtifactSink::Azure | ArtifactSink::Http => "https://",
+    };
+
+    if !config.destination.starts_with(scheme) {
+        anyhow::bail!(
+            "🛑 Invalid [artifacts] destination '{}'.\n\
+             → The {:?} sink expects a destination starting with {}",
+            config.destination,
+            config.sink,
+            scheme
+        );
+    }
+    if config.token_env.is_some() && config.sink != ArtifactSink::Http {
+        anyhow::bail!("🛑 [artifacts] token_env only applies to the http sink.");
+    }
+
+    Ok(())
+}
+
+/// Every file under `dir`, relative to it and sorted
+fn collect_files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current)
+            .with_context(|| format!("Failed to read {}", current.display()))?
+        {
+            let path = entry?.path();
+            if path.is_dir() {
+                pending.push(path);
+            } else if let Ok(relative) = path.strip_prefix(dir) {
+                files.push(relative.to_path_buf());
+            }
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+fn remote_url(config: &ArtifactsConfig, run_prefix: &str, relative: &Path) -> String {
+    let relative: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect();
+    format!(
+        "{}/{}/{}",
+        config.destination.trim_end_matches('/'),
+        run_prefix,
+        relative.join("/")
+    )
+}
+
+fn upload_command(config: &ArtifactsConfig, file: &Path, url: &str, with_token: bool) -> Command {
+    let mut command = Command::new(sink_tool(config.sink));
+    match config.sink {
+        ArtifactSink::S3 => {
+            command
+                .args(["s3", "cp", "--only-show-errors"])
+                .arg(file)
+                .arg(url);
+        }
+        ArtifactSink::Gcs => {
+            command.args(["storage", "cp"]).arg(file).arg(url);
+        }
+        ArtifactSink::Azure => {
+            command
+                .args([
+                    "storage",
+                    "blob",
+                    "upload",
+                    "--overwrite",
+                    "--only-show-errors",
+                ])
+                .args(["--auth-mode", "login", "--blob-url", url, "--file"])
+                .arg(file);
+        }
+        ArtifactSink::Http => {
+            command.args(["--fail", "--silent", "--show-error", "--upload-file"]);
+            command.arg(file);
+            if with_token {
+                // Read the header from stdin so the token never shows up in the process list
+                command.args(["--header", "@-"]);
+            }
+            command.arg(url);
+        }
+    }
+    command
+}
+
+fn run_upload(command: &mut Command, token: Option<&str>) -> Result<()> {
+    command.stdin(if token.is_some() {
+        Stdio::piped()
+    } else {
+        Stdio::null()
+    });
+
+    let mut child = command.spawn()?;
+    if let (Some(token), Some(mut stdin)) = (token, child.stdin.take()) {
+        writeln!(stdin, "Authorization: Bearer {}", token)?;
+    }
+
+    let status = child.wait()?;
+    if !status.success() {
+        anyhow::bail!("upload command exited with {}", status);
+    }
+    Ok(())
+}
+
+fn sink_tool(sink: ArtifactSink) -> &'static str {
+    match sink {
+        ArtifactSink::S3 => "aws",
+        ArtifactSink::Gcs => "gcloud",
+        ArtifactSink::Azure => "az",
+        ArtifactSink::Http => "curl",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn config(sink: ArtifactSink, destination: &str) -> ArtifactsConfig {
+        ArtifactsConfig {
+            sink,
+            destination: destination.to_string(),
+            token_env: None,
+        }
+    }
+
+    fn args(command: &Command) -> Vec<String> {
+        command
+            .get_args()
+            .map(|a| a.to_string_lossy().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_artifacts_dir_rejects_traversal() {
+        let temp_dir = tempdir().unwrap();
+        assert!(artifacts_dir(temp_dir.path(), "../../..", "build").is_err());
+        assert!(artifacts_dir(temp_dir.path(), "deploy", "../../..").is_err());
+        assert!(artifacts_dir(temp_dir.path(), "deploy", "a/b").is_err());
+
+        // Nothing outside .makeitso/artifacts gets cleared, even through a symlink
+        let outside = temp_dir.path().join("keep");
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(outside.join("important.txt"), "data").unwrap();
+        assert!(prepare_artifacts_dir(temp_dir.path(), &outside).is_err());
+        #[cfg(unix)]
+        {
+            let plugin_dir = temp_dir.path().join(".makeitso/artifacts/deploy");
+            fs::create_dir_all(&plugin_dir).unwrap();
+            std::os::unix::fs::symlink(&outside, plugin_dir.join("apply")).unwrap();
+            assert!(prepare_artifacts_dir(temp_dir.path(), &plugin_dir.join("apply")).is_err());
+        }
+        assert!(outside.join("important.txt").exists());
+    }
+
+    #[test]
+    fn test_prepare_and_collect_artifacts() {
+        let temp_dir = tempdir().unwrap();
+        let dir = artifacts_dir(temp_dir.path(), "build", "compile").unwrap();
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("stale.txt"), "old").unwrap();
+
+        prepare_artifacts_dir(temp_dir.path(), &dir).unwrap();
+        assert!(collect_files(&dir).unwrap().is_empty());
+
+        fs::create_dir_all(dir.join("reports")).unwrap();
+        fs::write(dir.join("app.tar.gz"), "bin").unwrap();
+        fs::write(dir.join("reports").join("junit.xml"), "<xml/>").unwrap();
+        assert_eq!(
+            collect_files(&dir).unwrap(),
+            vec![
+                PathBuf::from("app.tar.gz"),
+                PathBuf::from("reports").join("junit.xml")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_remote_url_and_upload_commands() {
+        let s3 = config(ArtifactSink::S3, "s3://ci-artifacts/mis/");
+        let url = remote_url(
+            &s3,
+            "build/compile/1700000000",
+            &PathBuf::from("reports").join("junit.xml"),
+        );
+        assert_eq!(
+            url,
+            "s3://ci-artifacts/mis/build/compile/1700000000/reports/junit.xml"
+        );
+
+        let command = upload_command(&s3, Path::new("/tmp/junit.xml"), &url, false);
+        assert_eq!(command.get_program(), "aws");
+        assert_eq!(
+            args(&command),
+            vec![
+                "s3",
+                "cp",
+                "--only-show-errors",
+                "/tmp/junit.xml",
+                url.as_str()
+            ]
+        );
+
+        let http = config(ArtifactSink::Http, "https://artifacts.example.com/upload");
+        let command = upload_command(
+            &http,
+            Path::new("/tmp/app.tar.gz"),
+            "https://x/app.tar.gz",
+            true,
+        );
+        assert_eq!(command.get_program(), "curl");
+        let http_args = args(&command);
+        assert!(http_args.contains(&"@-".to_string()));
+        assert_eq!(http_args.last().unwrap(), "https://x/app.tar.gz");
+    }
+
+    #[test]
+    fn test_validate_destination() {
+        assert!(validate_destination(&config(ArtifactSink::Gcs, "gs://bucket/path")).is_ok());
+        assert!(validate_destination(&config(ArtifactSink::Gcs, "s3://bucket")).is_err());
+        assert!(
+            validate_destination(&config(ArtifactSink::Http, "http://insecure.example.com"))
+                .is_err()
+        );
+
+        let mut s3 = config(ArtifactSink::S3, "s3://bucket");
+        s3.token_env = Some("TOKEN".to_string());
+        assert!(validate_destination(&s3).is_err());
+    }
+}
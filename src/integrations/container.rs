@@ -0,0 +1,304 @@
+use anyhow::Result;
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{
+    config::settings,
+    models::{Execution, PluginManifest},
+    security::PluginPermissions,
+};
+
+/// Image used when a plugin runs in a container without naming one in `[container]`
+pub const DEFAULT_IMAGE: &str = "denoland/deno:2.1.4";
+
+/// Host variables that describe the host itself and would break the container's environment
+const HOST_ONLY_ENV_VARS: &[&str] = &[
+    "HOME", "HOSTNAME", "OLDPWD", "PATH", "PWD", "SHELL", "SHLVL", "TMPDIR", "USER", "_",
+];
+
+/// The image to run the plugin in, or None to run it directly with Deno.
+/// `forced` comes from `mis run --container`.
+pub fn container_image(manifest: &PluginManifest, forced: bool) -> Option<&str> {
+    let wants_container = forced || manifest.plugin.execution == Some(Execution::Docker);
+    wants_container.then(|| {
+        manifest
+            .container
+            .as_ref()
+            .and_then(|container| container.image.as_deref())
+            .unwrap_or(DEFAULT_IMAGE)
+    })
+}
+
+/// A container engine invocation honoring the configured runtime (docker, podman, ...)
+pub fn container_command() -> Command {
+    Command::new(container_runtime())
+}
+
+fn container_runtime() -> &'static str {
+    settings().container_runtime.as_deref().unwrap_or("docker")
+}
+
+pub fn ensure_container_runtime(plugin_name: &str) -> Result<()> {
+    let available = container_command()
+        .arg("--version")
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false);
+
+    if !available {
+        anyhow::bail!(
+            "🛑 '{}' runs in a container, but `{}` isn't available.\n\
+             → Install Docker, or set container_runtime (or MIS_CONTAINER_RUNTIME) to another engine such as podman.",
+            plugin_name,
+            container_runtime()
+        );
+    }
+    Ok(())
+}
+
+/// A host path made visible inside the container at the same path
+#[derive(Debug, Clone, PartialEq)]
+pub struct Mount {
+    pub path: PathBuf,
+    pub writable: bool,
+}
+
+/// Bind mounts that give the container what the permissions grant and nothing else.
+/// The project is always mounted, read-only unless a write permission covers it.
+/// Paths that don't exist are left out, since the engine would create them as directories.
+pub fn permission_mounts(permissions: &PluginPermissions, project_root: &Path) -> Vec<Mount> {
+    let mut mounts = vec![Mount {
+        path: project_root.to_path_buf(),
+        writable: false,
+    }];
+
+    let requested = permissions
+        .file_write
+        .iter()
+        .map(|path| (path, true))
+        .chain(permissions.file_read.iter().map(|path| (path, false)));
+
+    for (path, writable) in requested {
+        let path = project_root.join(path);
+        if !path.exists() {
+            continue;
+        }
+        let covered = mounts
+            .iter()
+            .any(|mount| path.starts_with(&mount.path) && (mount.writable || !writable));
+        if covered {
+            continue;
+        }
+        // A writable mount replaces any read-only one it covers, including the project itself
+        mounts.retain(|mount| !(writable && mount.path.starts_with(&path)));
+        mounts.push(Mount { path, writable });
+    }
+
+    mounts.sort_by(|a, b| a.path.cmp(&b.path));
+    mounts
+}
+
+/// Arguments for `<engine> run` up to and including the image; the Deno command follows them.
+/// Network is cut off unless the plugin was granted hosts or must fetch remote modules.
+pub fn container_run_args(
+    permissions: &PluginPermissions,
+    project_root: &Path,
+    image: &str,
+    needs_remote_modules: bool,
+    host_env: impl IntoIterator<Item = String>,
+) -> Vec<String> {
+    let mut args: Vec<String> = ["run", "--rm", "-i", "--init"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+
+    if permissions.network.is_empty() && !needs_remote_modules {
+        args.push("--network=none".to_string());
+    }
+
+    for mount in permission_mounts(permissions, project_root) {
+        let path = mount.path.to_string_lossy();
+        let mode = if mount.writable { "rw" } else { "ro" };
+        args.push("-v".to_string());
+        args.push(format!("{}:{}:{}", path, path, mode));
+    }
+    args.push("-w".to_string());
+    args.push(project_root.to_string_lossy().to_string());
+
+    // `-e NAME` has the engine copy the value, so it never appears in the process list
+    let forwarded: Vec<String> = if !permissions.env_vars.is_empty() {
+        permissions.env_vars.clone()
+    } else if permissions.env_access {
+        host_env
+            .into_iter()
+            .filter(|name| !HOST_ONLY_ENV_VARS.contains(&name.as_str()))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    for name in forwarded {
+        args.push("-e".to_string());
+        args.push(name);
+    }
+
+    args.push(image.to_string());
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use crate::models::{ContainerSettings, PluginMeta};
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn manifest(execution: Option<Execution>, image: Option<&str>) -> PluginManifest {
+        PluginManifest {
+            plugin: PluginMeta {
+                name: "build".to_string(),
+                description: None,
+                version: "1.0.0".to_string(),
+                registry: None,
+                pin: None,
+                execution,
+            },
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
+            permissions: None,
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: image.map(|image| ContainerSettings {
+                image: Some(image.to_string()),
+            }),
+            context: None,
+            context_transport: None,
+        }
+    }
+
+    fn permissions(read: &[&Path], write: &[&Path]) -> PluginPermissions {
+        let mut permissions = PluginPermissions::safe_defaults(Path::new("/"));
+        permissions.file_read = read
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        permissions.file_write = write
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        permissions.env_access = false;
+        permissions.network.clear();
+        permissions
+    }
+
+    #[test]
+    fn test_container_image() {
+        assert_eq!(container_image(&manifest(None, None), false), None);
+        assert_eq!(
+            container_image(&manifest(Some(Execution::Deno), None), true),
+            Some(DEFAULT_IMAGE)
+        );
+        assert_eq!(
+            container_image(&manifest(Some(Execution::Docker), Some("ci/deno:2")), false),
+            Some("ci/deno:2")
+        );
+
+        let parsed: PluginManifest = toml::from_str(
+            "[plugin]\nname = \"build\"\nversion = \"1.0.0\"\nexecution = \"docker\"\n\n\
+             [container]\nimage = \"ci/deno:2\"\n",
+        )
+        .unwrap();
+        assert_eq!(container_image(&parsed, false), Some("ci/deno:2"));
+    }
+
+    #[test]
+    fn test_permission_mounts() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path().join("project");
+        let cache = temp_dir.path().join("cache");
+        let result = temp_dir.path().join("result.json");
+        fs::create_dir_all(root.join("dist")).unwrap();
+        fs::create_dir_all(&cache).unwrap();
+        fs::write(&result, "").unwrap();
+
+        // Reads inside the project are already covered; missing paths are skipped
+        let mounts = permission_mounts(
+            &permissions(
+                &[
+                    &root,
+                    &root.join("dist"),
+                    &cache,
+                    &temp_dir.path().join("gone"),
+                ],
+                &[&root.join("dist"), &result],
+            ),
+            &root,
+        );
+        assert_eq!(
+            mounts,
+            vec![
+                Mount {
+                    path: cache.clone(),
+                    writable: false
+                },
+                Mount {
+                    path: root.clone(),
+                    writable: false
+                },
+                Mount {
+                    path: root.join("dist"),
+                    writable: true
+                },
+                Mount {
+                    path: result.clone(),
+                    writable: true
+                },
+            ]
+        );
+
+        // Write access to the whole project replaces the read-only mount
+        let mounts = permission_mounts(&permissions(&[], &[&root]), &root);
+        assert_eq!(
+            mounts,
+            vec![Mount {
+                path: root.clone(),
+                writable: true
+            }]
+        );
+    }
+
+    #[test]
+    fn test_container_run_args() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let root_str = root.to_string_lossy().to_string();
+        let env = || vec!["API_TOKEN".to_string(), "PATH".to_string()];
+
+        let mut perms = permissions(&[], &[]);
+        let args = container_run_args(&perms, root, "denoland/deno:2", false, env());
+        assert!(args.contains(&"--network=none".to_string()));
+        assert!(args.contains(&format!("{}:{}:ro", root_str, root_str)));
+        assert!(!args.contains(&"API_TOKEN".to_string()));
+        assert_eq!(args.last().unwrap(), "denoland/deno:2");
+
+        // Remote modules need the network even if the plugin itself doesn't
+        let args = container_run_args(&perms, root, "denoland/deno:2", true, env());
+        assert!(!args.contains(&"--network=none".to_string()));
+
+        perms.network.push("api.example.com".to_string());
+        perms.env_access = true;
+        let args = container_run_args(&perms, root, "denoland/deno:2", false, env());
+        assert!(!args.contains(&"--network=none".to_string()));
+        assert!(args.contains(&"API_TOKEN".to_string()));
+        assert!(!args.contains(&"PATH".to_string()));
+
+        // Declared variables are the only ones forwarded
+        perms.env_vars = vec!["CI".to_string()];
+        let args = container_run_args(&perms, root, "denoland/deno:2", false, env());
+        assert!(args.contains(&"CI".to_string()));
+        assert!(!args.contains(&"API_TOKEN".to_string()));
+    }
+}
@@ -1,8 +1,15 @@
 use anyhow::{Context, Result};
-use std::{collections::HashMap, process::Command};
+use indexmap::IndexMap;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use crate::{config::settings, models::ColorChoice, plugin_utils::ensure_path_component};
 
 pub fn install_deno() -> Result<()> {
-  println!("⬇️ Installing Deno...");
+  outln!("⬇️ Installing Deno...");
 
   #[cfg(target_os = "macos")]
   let shell_command = "curl -fsSL https://deno.land/install.sh | sh";
@@ -30,40 +37,157 @@ pub fn install_deno() -> Result<()> {
       return Err(anyhow::anyhow!("Deno installation failed"));
   }
 
-  println!("✅ Deno installed. You may need to restart your shell.");
+  outln!("✅ Deno installed. You may need to restart your shell.");
   Ok(())
 }
 
+/// A `deno` invocation honoring the configured Deno path and color preference
+pub fn deno_command() -> Command {
+    let settings = settings();
+    let mut command = Command::new(settings.deno_path.as_deref().unwrap_or("deno"));
+
+    match settings.color {
+        Some(ColorChoice::Never) => {
+            command.env("NO_COLOR", "1");
+        }
+        Some(ColorChoice::Always) => {
+            command.env_remove("NO_COLOR");
+        }
+        Some(ColorChoice::Auto) | None => {}
+    }
+
+    command
+}
+
 pub fn is_deno_installed() -> bool {
-    Command::new("deno")
+    deno_command()
         .arg("--version")
         .output()
         .map(|output| output.status.success())
         .unwrap_or(false)
 }
 
-pub fn cache_deno_dependencies(deps: &HashMap<String, String>) -> Result<()> {
+/// Where the lockfile recording a plugin's dependency checksums lives
+pub fn deno_lock_path(project_root: &Path, plugin_name: &str) -> Result<PathBuf> {
+    ensure_path_component("plugin name", plugin_name)?;
+    Ok(project_root
+        .join(".makeitso")
+        .join("locks")
+        .join(format!("{}.lock", plugin_name)))
+}
+
+/// How `deno run` resolves a plugin's remote modules: the vendored copies through the import
+/// map with remote imports off, or else `frozen_lock` so nothing missing from it or changed
+/// since it was recorded can load
+pub fn module_resolution_args(
+    import_map: Option<&Path>,
+    frozen_lock: Option<&Path>,
+) -> Vec<String> {
+    match (import_map, frozen_lock) {
+        (Some(import_map), _) => vec![
+            "--import-map".to_string(),
+            import_map.to_string_lossy().to_string(),
+            "--no-remote".to_string(),
+        ],
+        (None, Some(lock_file)) => vec![
+            format!("--lock={}", lock_file.display()),
+            "--frozen".to_string(),
+        ],
+        (None, None) => Vec::new(),
+    }
+}
+
+/// Cache `deps` and the remote modules `entry` imports, checking them against `lock_file`.
+/// Modules not in the lockfile yet are added to it; changed content fails the integrity check.
+pub fn cache_deno_dependencies(
+    deps: &IndexMap<String, String>,
+    entry: &Path,
+    lock_file: &Path,
+) -> Result<()> {
     if deps.is_empty() {
-        println!("📦 No Deno dependencies defined — skipping cache.");
+        outln!("📦 No Deno dependencies defined — skipping cache.");
         return Ok(());
     }
 
-    println!("📦 Caching Deno dependencies...");
+    outln!("📦 Caching Deno dependencies...");
     for url in deps.values() {
-        println!("• {}", url);
+        outln!("• {}", url);
     }
 
-    let status = Command::new("deno")
+    if let Some(parent) = lock_file.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let status = deno_command()
         .arg("cache")
-        .arg("--no-lock")
+        .arg(format!("--lock={}", lock_file.display()))
         .args(deps.values())
+        .arg(entry)
         .status()
         .context("Failed to run `deno cache`")?;
 
     if !status.success() {
-        return Err(anyhow::anyhow!("Deno cache failed"));
+        return Err(anyhow::anyhow!(
+            "🛑 Deno cache failed.\n\
+             → If Deno reported an integrity check failure, a dependency changed since it was recorded in {}.\n\
+             → Delete that file only if you trust the new content.",
+            lock_file.display()
+        ));
     }
 
-    println!("✅ Dependencies cached.");
+    outln!("✅ Dependencies cached.");
     Ok(())
-}
\ No newline at end of file
+}
+
+/// Download `urls` and everything they import into `output_dir`, with an import map
+/// (`import_map.json`) that points the remote URLs at the local copies
+pub fn vendor_deno_dependencies(urls: &[&str], output_dir: &Path) -> Result<()> {
+    let status = deno_command()
+        .arg("vendor")
+        .arg("--force")
+        .arg("--output")
+        .arg(output_dir)
+        .args(urls)
+        .status()
+        .context("Failed to run `deno vendor`")?;
+
+    if !status.success() {
+        return Err(anyhow::anyhow!("Deno vendor failed"));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deno_lock_path_stays_in_locks_dir() {
+        let root = Path::new("/project");
+        assert_eq!(
+            deno_lock_path(root, "deploy").unwrap(),
+            PathBuf::from("/project/.makeitso/locks/deploy.lock")
+        );
+        assert!(deno_lock_path(root, "../../../etc/evil").is_err());
+        assert!(deno_lock_path(root, "..").is_err());
+    }
+
+    #[test]
+    fn test_module_resolution_args() {
+        let import_map = Path::new("/project/vendor/import_map.json");
+        let lock_file = Path::new("/project/.makeitso/locks/deploy.lock");
+
+        assert_eq!(
+            module_resolution_args(None, Some(lock_file)),
+            vec!["--lock=/project/.makeitso/locks/deploy.lock", "--frozen"]
+        );
+        // Vendored copies win over the lockfile
+        assert_eq!(
+            module_resolution_args(Some(import_map), Some(lock_file)),
+            vec!["--import-map", "/project/vendor/import_map.json", "--no-remote"]
+        );
+        assert!(module_resolution_args(None, None).is_empty());
+    }
+}
@@ -1 +1,9 @@
-pub mod deno;
\ No newline at end of file
+pub mod advisories;
+pub mod artifacts;
+pub mod container;
+pub mod deno;
+pub mod registry_auth;
+pub mod registry_cache;
+pub mod registry_download;
+pub mod registry_http;
+pub mod registry_index;
\ No newline at end of file
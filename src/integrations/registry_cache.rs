@@ -0,0 +1,238 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use anyhow::{Context, Result, anyhow};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    git_utils::fetch_latest,
+    integrations::registry_download::fetch_registry,
+    security::{local_registry_path, resolve_local_registry},
+};
+
+/// How long a cached registry is used as-is before it's fetched again
+const FRESH_FOR: Duration = Duration::from_secs(15 * 60);
+
+/// Where registry clones are cached: `$XDG_CACHE_HOME/makeitso/registries`,
+/// `~/.cache/makeitso/registries`, or `%LOCALAPPDATA%\makeitso\registries` on Windows
+pub fn registry_cache_root() -> PathBuf {
+    let cache_dir = if cfg!(windows) {
+        std::env::var_os("LOCALAPPDATA").map(PathBuf::from)
+    } else {
+        std::env::var_os("XDG_CACHE_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+    };
+
+    cache_dir
+        .unwrap_or_else(std::env::temp_dir)
+        .join("makeitso")
+        .join("registries")
+}
+
+/// A local copy of the registry, fetched again when it's stale or `refresh` is set.
+///
+/// The returned directory is shared between commands, so callers must only read from it.
+pub fn cached_registry(registry: &str, refresh: bool) -> Result<PathBuf> {
+    // Local registries are read in place; there's nothing to fetch or cache
+    if let Some(path) = local_registry_path(registry) {
+        let dir = resolve_local_registry(path);
+        if !dir.is_dir() {
+            anyhow::bail!(
+                "🛑 Local registry {} not found.\n\
+                 → Check the path in your registry sources.",
+                dir.display()
+            );
+        }
+        return Ok(dir);
+    }
+
+    cached_registry_in(&registry_cache_root(), registry, refresh)
+}
+
+/// The directory a registry is cached in
+pub fn registry_cache_dir(registry: &str) -> PathBuf {
+    registry_cache_root().join(cache_key(registry))
+}
+
+/// Record that a registry's cache was just used, so `mis gc` keeps it
+pub fn mark_registry_fetched(registry: &str) -> Result<()> {
+    touch(&registry_cache_root().join(format!("{}.fetched", cache_key(registry))))
+}
+
+fn cached_registry_in(cache_root: &Path, registry: &str, refresh: bool) -> Result<PathBuf> {
+    let key = cache_key(registry);
+    let dir = cache_root.join(&key);
+    let stamp = cache_root.join(format!("{}.fetched", key));
+
+    if dir.is_dir() && !refresh && is_fresh(&stamp) {
+        debugln!("Using cached registry {} ({})", registry, dir.display());
+        return Ok(dir);
+    }
+
+    // Git registries only need the new commits; a failed fetch falls back to a fresh clone
+    if dir.join(".git").is_dir() && fetch_latest(&dir, registry).is_ok() {
+        debugln!("Fetched latest {} into {}", registry, dir.display());
+        touch(&stamp)?;
+        return Ok(dir);
+    }
+
+    fs::create_dir_all(cache_root)
+        .with_context(|| format!("Failed to create directory: {}", cache_root.display()))?;
+    let staging = cache_root.join(format!("{}.tmp", key));
+    remove_dir_if_exists(&staging)?;
+    if let Err(e) = fetch_registry(registry, &staging) {
+        let _ = remove_dir_if_exists(&staging);
+        return Err(anyhow!("❌ Failed to clone {}: {}", registry, e));
+    }
+
+    remove_dir_if_exists(&dir)?;
+    fs::rename(&staging, &dir)
+        .with_context(|| format!("Failed to move registry into {}", dir.display()))?;
+    touch(&stamp)?;
+
+    Ok(dir)
+}
+
+/// Cached registries not fetched within `max_age`, with their stamps and any leftover
+/// staging directories from interrupted fetches
+pub fn stale_cache_entries(cache_root: &Path, max_age: Duration) -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(cache_root) else {
+        return Vec::new();
+    };
+
+    let mut stale = Vec::new();
+    for entry in entries.filter_map(|e| e.ok()) {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if let Some(key) = name.strip_suffix(".fetched") {
+            // Stamps go with their cache directory, or on their own once it's gone
+            if !cache_root.join(key).exists() || age(&path).is_none_or(|a| a >= max_age) {
+                stale.push(path);
+            }
+        } else if name.ends_with(".tmp")
+            || (path.is_dir()
+                && age(&cache_root.join(format!("{}.fetched", name))).is_none_or(|a| a >= max_age))
+        {
+            stale.push(path);
+        }
+    }
+
+    stale.sort();
+    stale
+}
+
+fn cache_key(registry: &str) -> String {
+    let digest = format!("{:x}", Sha256::digest(registry.trim_end_matches('/')));
+    digest[..16].to_string()
+}
+
+fn is_fresh(stamp: &Path) -> bool {
+    age(stamp).is_some_and(|age| age < FRESH_FOR)
+}
+
+fn age(path: &Path) -> Option<Duration> {
+    fs::metadata(path)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+}
+
+fn touch(stamp: &Path) -> Result<()> {
+    fs::write(stamp, b"").with_context(|| format!("Failed to write {}", stamp.display()))
+}
+
+fn remove_dir_if_exists(dir: &Path) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_cache_key_ignores_trailing_slash() {
+        assert_eq!(
+            cache_key("https://github.com/user/registry"),
+            cache_key("https://github.com/user/registry/")
+        );
+        assert_ne!(
+            cache_key("https://github.com/user/registry"),
+            cache_key("https://github.com/user/other")
+        );
+        assert_eq!(cache_key("https://github.com/user/registry").len(), 16);
+    }
+
+    #[test]
+    fn test_cached_registry_reuses_fresh_copy() {
+        let cache_root = tempdir().unwrap();
+        let registry = "https://github.com/user/registry.git";
+        let key = cache_key(registry);
+        fs::create_dir_all(cache_root.path().join(&key).join("plugins/deploy")).unwrap();
+        touch(&cache_root.path().join(format!("{}.fetched", key))).unwrap();
+
+        // Fresh: returned without touching the network
+        let dir = cached_registry_in(cache_root.path(), registry, false).unwrap();
+        assert!(dir.join("plugins/deploy").is_dir());
+    }
+
+    #[test]
+    fn test_cached_registry_reads_local_paths_in_place() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("plugins/deploy")).unwrap();
+
+        let source = format!("path:{}", temp_dir.path().display());
+        assert_eq!(cached_registry(&source, true).unwrap(), temp_dir.path());
+
+        let missing = format!("path:{}", temp_dir.path().join("missing").display());
+        assert!(cached_registry(&missing, false).is_err());
+    }
+
+    #[test]
+    fn test_stale_cache_entries() {
+        let cache_root = tempdir().unwrap();
+        for dir in ["recent", "old", "unstamped", "abc.tmp"] {
+            fs::create_dir_all(cache_root.path().join(dir)).unwrap();
+        }
+        touch(&cache_root.path().join("recent.fetched")).unwrap();
+        touch(&cache_root.path().join("old.fetched")).unwrap();
+        touch(&cache_root.path().join("orphan.fetched")).unwrap();
+
+        let stale = stale_cache_entries(cache_root.path(), Duration::from_secs(3600));
+        let names: Vec<String> = stale
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, vec!["abc.tmp", "orphan.fetched", "unstamped"]);
+
+        let stale = stale_cache_entries(cache_root.path(), Duration::ZERO);
+        assert!(stale.contains(&cache_root.path().join("old")));
+        assert!(stale.contains(&cache_root.path().join("old.fetched")));
+        assert!(stale.contains(&cache_root.path().join("recent")));
+    }
+
+    #[test]
+    fn test_is_fresh_needs_a_recent_stamp() {
+        let temp_dir = tempdir().unwrap();
+        let stamp = temp_dir.path().join("abc.fetched");
+        assert!(!is_fresh(&stamp));
+
+        touch(&stamp).unwrap();
+        assert!(is_fresh(&stamp));
+
+        let old = SystemTime::now() - FRESH_FOR - Duration::from_secs(1);
+        fs::File::options()
+            .write(true)
+            .open(&stamp)
+            .unwrap()
+            .set_modified(old)
+            .unwrap();
+        assert!(!is_fresh(&stamp));
+    }
+}
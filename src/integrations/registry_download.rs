@@ -0,0 +1,346 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+use anyhow::{Context, Result, anyhow};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    config::settings,
+    git_utils::shallow_clone_repo,
+    integrations::registry_cache::registry_cache_root,
+    temp_files::{ensure_private_dir, owned_by_current_user},
+};
+
+/// Download attempts before giving up on an archive
+const MAX_ATTEMPTS: u32 = 5;
+
+/// Fetch a registry into `target_dir`: tarball registries are downloaded and unpacked,
+/// everything else is shallow-cloned with git
+pub fn fetch_registry(registry: &str, target_dir: &Path) -> Result<()> {
+    if is_archive_url(registry) {
+        download_archive(registry, target_dir, None)
+    } else {
+        shallow_clone_repo(
+            registry.to_string(),
+            target_dir.to_string_lossy().to_string(),
+        )
+    }
+}
+
+/// Whether a registry source points at a .tar.gz bundle rather than a git repository
+pub fn is_archive_url(registry: &str) -> bool {
+    let path = registry.split(['?', '#']).next().unwrap_or_default();
+    path.ends_with(".tar.gz") || path.ends_with(".tgz")
+}
+
+/// Download a tarball and unpack it into `target_dir`, resuming the partial file after each
+/// failed attempt and checking it against `sha256` when one is given.
+///
+/// Partial downloads are kept in a private per-user directory. With a checksum an interrupted
+/// `mis add` picks up where it left off the next time too; without one there'd be no telling
+/// what an old partial holds, so the download starts over.
+pub fn download_archive(url: &str, target_dir: &Path, sha256: Option<&str>) -> Result<()> {
+    let limit = settings().download_limit.as_deref();
+    if let Some(limit) = limit {
+        validate_rate_limit(limit)?;
+    }
+
+    let partial = partial_download_path(url);
+    if let Some(dir) = partial.parent() {
+        ensure_private_dir(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    discard_stale_partial(&partial, sha256.is_some())?;
+
+    let mut attempt = 1;
+    loop {
+        let status = Command::new("curl")
+            .args(download_args(url, &partial, limit))
+            .status()
+            .map_err(|e| {
+                anyhow!(
+                    "🛑 Failed to run curl: {}\n\
+                     → Install curl to use tarball registries.",
+                    e
+                )
+            })?;
+        if status.success() {
+            break;
+        }
+        if attempt == MAX_ATTEMPTS {
+            anyhow::bail!(
+                "❌ Failed to download {} after {} attempts.\n\
+                 → Run the command again to resume the download.",
+                url,
+                MAX_ATTEMPTS
+            );
+        }
+
+        let delay = backoff(attempt);
+        errln!(
+            "⚠️  Download of {} interrupted ({}), retrying in {}s...",
+            url,
+            status,
+            delay.as_secs()
+        );
+        thread::sleep(delay);
+        attempt += 1;
+    }
+
+    if let Some(expected) = sha256 {
+        let actual = format!("{:x}", Sha256::digest(fs::read(&partial)?));
+        if !actual.eq_ignore_ascii_case(expected) {
+            let _ = fs::remove_file(&partial);
+            anyhow::bail!(
+                "🛑 Checksum mismatch for {}.\n\
+                 → Expected sha256 {}, got {}. The file may have been tampered with.",
+                url,
+                expected,
+                actual
+            );
+        }
+    }
+
+    let extracted = extract_archive(&partial, target_dir);
+    // A finished download is never resumed; a corrupt one must start over
+    let _ = fs::remove_file(&partial);
+    extracted
+}
+
+/// Remove a partial download left by an earlier run unless it can be checked after resuming,
+/// and always if it's a symlink or someone else's file
+fn discard_stale_partial(partial: &Path, verifiable: bool) -> Result<()> {
+    if let Ok(meta) = fs::symlink_metadata(partial)
+        && (!verifiable || !meta.is_file() || !owned_by_current_user(&meta))
+    {
+        fs::remove_file(partial).with_context(|| {
+            format!("Failed to discard partial download: {}", partial.display())
+        })?;
+    }
+    Ok(())
+}
+
+fn download_args(url: &str, partial: &Path, limit: Option<&str>) -> Vec<String> {
+    // Only the URL's own scheme (an http one only passes validation for trusted hosts), and
+    // redirects only to https, never to file:// or plain http
+    let proto = if url.starts_with("http://") { "=http" } else { "=https" };
+    let mut args: Vec<String> = [
+        "--fail",
+        "--silent",
+        "--show-error",
+        "--proto",
+        proto,
+        "--proto-redir",
+        "=https",
+        "--location",
+        // Resume from however much of the partial file already exists
+        "--continue-at",
+        "-",
+        // Treat a stalled connection (under 1 byte/s for 30s) as a failure so it's retried
+        "--speed-limit",
+        "1",
+        "--speed-time",
+        "30",
+        "--output",
+    ]
+    .iter()
+    .map(|arg| arg.to_string())
+    .collect();
+    args.push(partial.to_string_lossy().to_string());
+    if let Some(limit) = limit {
+        args.push("--limit-rate".to_string());
+        args.push(limit.to_string());
+    }
+    args.push(url.to_string());
+    args
+}
+
+/// 1s, 2s, 4s, ... between attempts, capped at 30s
+fn backoff(attempt: u32) -> Duration {
+    Duration::from_secs(2u64.saturating_pow(attempt - 1).min(30))
+}
+
+/// Where interrupted tarball downloads are kept between attempts: next to the registry cache,
+/// in a directory only the current user can open
+pub fn partial_downloads_dir() -> PathBuf {
+    registry_cache_root().with_file_name("downloads")
+}
+
+fn partial_download_path(url: &str) -> PathBuf {
+    partial_downloads_dir().join(format!("{:x}.tar.gz.part", Sha256::digest(url)))
+}
+
+/// Bandwidth limits use curl's syntax: bytes per second with an optional K, M or G suffix
+pub fn validate_rate_limit(limit: &str) -> Result<()> {
+    let digits = limit.trim_end_matches(['k', 'K', 'm', 'M', 'g', 'G']);
+    let suffix_len = limit.len() - digits.len();
+    if digits.is_empty() || suffix_len > 1 || !digits.chars().all(|c| c.is_ascii_digit()) {
+        anyhow::bail!(
+            "🛑 Invalid download limit '{}'.\n\
+             → Use bytes per second with an optional K, M or G suffix (e.g. 500K or 2M).",
+            limit
+        );
+    }
+    Ok(())
+}
+
+/// Unpack a tarball, dropping the single top-level directory archives usually wrap things in
+fn extract_archive(archive: &Path, target_dir: &Path) -> Result<()> {
+    fs::create_dir_all(target_dir)
+        .with_context(|| format!("Failed to create directory: {}", target_dir.display()))?;
+
+    let output = Command::new("tar")
+        .arg("-xzf")
+        .arg(archive)
+        .arg("-C")
+        .arg(target_dir)
+        .output()
+        .map_err(|e| anyhow!("🛑 Failed to run tar: {}", e))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "❌ Failed to unpack archive: {}\n\
+             → The download may be corrupt; run the command again to start over.",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    flatten_single_root(target_dir)
+}
+
+fn flatten_single_root(dir: &Path) -> Result<()> {
+    let entries: Vec<_> = fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
+    let [entry] = entries.as_slice() else {
+        return Ok(());
+    };
+    // A lone plugins/ directory is already the registry layout
+    if !entry.file_type()?.is_dir() || entry.file_name() == "plugins" {
+        return Ok(());
+    }
+
+    let root = entry.path();
+    for child in fs::read_dir(&root)? {
+        let child = child?;
+        fs::rename(child.path(), dir.join(child.file_name()))?;
+    }
+    fs::remove_dir(&root)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_is_archive_url() {
+        assert!(is_archive_url("https://example.com/registry.tar.gz"));
+        assert!(is_archive_url("https://example.com/registry.tgz?token=abc"));
+        assert!(!is_archive_url("https://github.com/user/registry.git"));
+        assert!(!is_archive_url("git@github.com:user/registry.git"));
+    }
+
+    #[test]
+    fn test_download_args_resume_and_limit() {
+        let args = download_args(
+            "https://example.com/r.tgz",
+            Path::new("/tmp/r.part"),
+            Some("2M"),
+        );
+        let joined = args.join(" ");
+        assert!(joined.contains("--continue-at -"));
+        assert!(joined.contains("--output /tmp/r.part"));
+        assert!(joined.contains("--limit-rate 2M"));
+        assert!(joined.contains("--proto =https --proto-redir =https --location"));
+        assert_eq!(args.last().unwrap(), "https://example.com/r.tgz");
+
+        let args = download_args("https://example.com/r.tgz", Path::new("/tmp/r.part"), None);
+        assert!(!args.contains(&"--limit-rate".to_string()));
+
+        // A trusted http registry is fetched over http, but never redirected off https
+        let args = download_args("http://git.corp.example/r.tgz", Path::new("/tmp/r.part"), None);
+        assert!(args.join(" ").contains("--proto =http --proto-redir =https"));
+    }
+
+    #[test]
+    fn test_discard_stale_partial() {
+        let temp_dir = tempdir().unwrap();
+        let partial = temp_dir.path().join("r.tar.gz.part");
+
+        fs::write(&partial, b"half a tarball").unwrap();
+        discard_stale_partial(&partial, true).unwrap();
+        assert!(partial.exists(), "a checksummed download should resume");
+
+        discard_stale_partial(&partial, false).unwrap();
+        assert!(!partial.exists(), "nothing could vouch for the earlier bytes");
+
+        #[cfg(unix)]
+        {
+            let victim = temp_dir.path().join("victim");
+            fs::write(&victim, b"keep me").unwrap();
+            std::os::unix::fs::symlink(&victim, &partial).unwrap();
+            discard_stale_partial(&partial, true).unwrap();
+            assert!(fs::symlink_metadata(&partial).is_err());
+            assert_eq!(fs::read(&victim).unwrap(), b"keep me");
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        assert_eq!(backoff(1), Duration::from_secs(1));
+        assert_eq!(backoff(3), Duration::from_secs(4));
+        assert_eq!(backoff(10), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_validate_rate_limit() {
+        assert!(validate_rate_limit("500K").is_ok());
+        assert!(validate_rate_limit("2m").is_ok());
+        assert!(validate_rate_limit("1024").is_ok());
+        assert!(validate_rate_limit("fast").is_err());
+        assert!(validate_rate_limit("2MB").is_err());
+        assert!(validate_rate_limit("M").is_err());
+    }
+
+    #[test]
+    fn test_flatten_single_root_moves_contents_up() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join("registry-main/plugins/deploy")).unwrap();
+        fs::write(temp_dir.path().join("registry-main/index.toml"), "").unwrap();
+
+        flatten_single_root(temp_dir.path()).unwrap();
+        assert!(temp_dir.path().join("plugins/deploy").is_dir());
+        assert!(temp_dir.path().join("index.toml").is_file());
+        assert!(!temp_dir.path().join("registry-main").exists());
+
+        // Already flat: left alone
+        flatten_single_root(temp_dir.path()).unwrap();
+        assert!(temp_dir.path().join("plugins/deploy").is_dir());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_extract_archive_unpacks_github_style_tarball() {
+        let temp_dir = tempdir().unwrap();
+        let source = temp_dir.path().join("src/registry-main/plugins/deploy");
+        fs::create_dir_all(&source).unwrap();
+        fs::write(source.join("manifest.toml"), "[plugin]").unwrap();
+
+        let archive = temp_dir.path().join("registry.tar.gz");
+        let status = Command::new("tar")
+            .arg("-czf")
+            .arg(&archive)
+            .arg("-C")
+            .arg(temp_dir.path().join("src"))
+            .arg("registry-main")
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let target = temp_dir.path().join("out");
+        extract_archive(&archive, &target).unwrap();
+        assert!(target.join("plugins/deploy/manifest.toml").is_file());
+    }
+}
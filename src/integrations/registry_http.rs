@@ -0,0 +1,215 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use url::Url;
+
+use crate::{
+    config::plugins::load_plugin_manifest,
+    constants::PLUGIN_MANIFEST_FILE,
+    integrations::{
+        registry_cache::{cached_registry, mark_registry_fetched, registry_cache_dir},
+        registry_download::{download_archive, is_archive_url},
+        registry_index::{RegistryEntry, fetch_registry_index, looks_like_git_remote},
+    },
+    security::{local_registry_path, validate_registry_url},
+};
+
+/// How a registry source is fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RegistryType {
+    /// A directory on this machine, read in place
+    Local,
+    /// A single .tar.gz holding the whole registry
+    Archive,
+    /// A git repository, shallow-cloned
+    Git,
+    /// An HTTPS server with an index listing one tarball per plugin
+    HttpIndex,
+}
+
+/// Work out what kind of registry a source is.
+///
+/// Anything that isn't obviously local, an archive or a git remote is probed for an
+/// index; plain HTTPS servers that have one are index registries.
+pub fn detect_registry_type(registry: &str) -> RegistryType {
+    if local_registry_path(registry).is_some() {
+        RegistryType::Local
+    } else if is_archive_url(registry) {
+        RegistryType::Archive
+    } else if looks_like_git_remote(registry) || fetch_registry_index(registry).is_none() {
+        RegistryType::Git
+    } else {
+        RegistryType::HttpIndex
+    }
+}
+
+/// A local directory laid out like a registry (plugins under plugins/) with at least
+/// the requested plugins in it
+pub fn fetch_registry_for(registry: &str, plugins: &[String], refresh: bool) -> Result<PathBuf> {
+    match detect_registry_type(registry) {
+        RegistryType::HttpIndex => fetch_index_plugins(registry, plugins, refresh),
+        _ => cached_registry(registry, refresh),
+    }
+}
+
+/// Download the requested plugins from an HTTPS index registry into its cache directory.
+///
+/// Plugins the index doesn't list are skipped, and a cached copy whose version matches
+/// the index is reused unless `refresh` is set.
+fn fetch_index_plugins(registry: &str, plugins: &[String], refresh: bool) -> Result<PathBuf> {
+    let index = fetch_registry_index(registry)
+        .ok_or_else(|| anyhow!("❌ Failed to fetch the index of registry {}", registry))?;
+    let dir = registry_cache_dir(registry);
+
+    for plugin in plugins {
+        let Some(entry) = index.get(plugin) else {
+            continue;
+        };
+        let target = dir.join("plugins").join(plugin);
+        if !refresh && is_cached_version(&target, entry) {
+            continue;
+        }
+
+        let url = plugin_tarball_url(registry, entry)?;
+        if let Err(security_error) = validate_registry_url(&url) {
+            anyhow::bail!(
+                "🛑 Security validation failed for plugin download '{}': {}",
+                url,
+                security_error
+            );
+        }
+
+        let staging = dir.join("plugins").join(format!("{}.tmp", plugin));
+        remove_dir_if_exists(&staging)?;
+        download_archive(&url, &staging, entry.sha256.as_deref())
+            .map_err(|e| anyhow!("❌ Failed to download plugin '{}': {:#}", plugin, e))?;
+        remove_dir_if_exists(&target)?;
+        fs::rename(&staging, &target)
+            .with_context(|| format!("Failed to move plugin into {}", target.display()))?;
+    }
+
+    if dir.exists() {
+        mark_registry_fetched(registry)?;
+    }
+    Ok(dir)
+}
+
+/// Where to download a plugin from: the index entry's `url` (resolved against the registry
+/// URL when relative), or `plugins/<name>-<version>.tar.gz` next to the index
+pub fn plugin_tarball_url(registry: &str, entry: &RegistryEntry) -> Result<String> {
+    let base = Url::parse(&format!("{}/", registry.trim().trim_end_matches('/')))
+        .map_err(|e| anyhow!("🛑 Invalid registry URL '{}': {}", registry, e))?;
+
+    let path = match (&entry.url, &entry.version) {
+        (Some(url), _) => url.clone(),
+        (None, Some(version)) => format!("plugins/{}-{}.tar.gz", entry.name, version),
+        (None, None) => format!("plugins/{}.tar.gz", entry.name),
+    };
+
+    base.join(&path).map(String::from).map_err(|e| {
+        anyhow!(
+            "🛑 Invalid download URL '{}' for plugin '{}': {}",
+            path,
+            entry.name,
+            e
+        )
+    })
+}
+
+fn is_cached_version(plugin_dir: &Path, entry: &RegistryEntry) -> bool {
+    let Some(version) = &entry.version else {
+        return false;
+    };
+    load_plugin_manifest(&plugin_dir.join(PLUGIN_MANIFEST_FILE))
+        .is_ok_and(|manifest| &manifest.plugin.version == version)
+}
+
+fn remove_dir_if_exists(dir: &Path) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir).with_context(|| format!("Failed to remove {}", dir.display()))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn entry(url: Option<&str>, version: Option<&str>) -> RegistryEntry {
+        RegistryEntry {
+            name: "deploy".to_string(),
+            version: version.map(String::from),
+            description: None,
+            url: url.map(String::from),
+            sha256: None,
+        }
+    }
+
+    #[test]
+    fn test_plugin_tarball_url() {
+        let registry = "https://artifacts.example.com/mis";
+        assert_eq!(
+            plugin_tarball_url(registry, &entry(None, Some("1.2.0"))).unwrap(),
+            "https://artifacts.example.com/mis/plugins/deploy-1.2.0.tar.gz"
+        );
+        assert_eq!(
+            plugin_tarball_url(registry, &entry(None, None)).unwrap(),
+            "https://artifacts.example.com/mis/plugins/deploy.tar.gz"
+        );
+        assert_eq!(
+            plugin_tarball_url(registry, &entry(Some("dist/deploy.tgz"), None)).unwrap(),
+            "https://artifacts.example.com/mis/dist/deploy.tgz"
+        );
+        assert_eq!(
+            plugin_tarball_url(
+                registry,
+                &entry(Some("https://cdn.example.com/deploy.tgz"), None)
+            )
+            .unwrap(),
+            "https://cdn.example.com/deploy.tgz"
+        );
+    }
+
+    #[test]
+    fn test_detect_registry_type_by_syntax() {
+        assert_eq!(detect_registry_type("path:../plugins"), RegistryType::Local);
+        assert_eq!(
+            detect_registry_type("https://example.com/registry.tar.gz"),
+            RegistryType::Archive
+        );
+        assert_eq!(
+            detect_registry_type("https://github.com/user/registry"),
+            RegistryType::Git
+        );
+        assert_eq!(
+            detect_registry_type("git@gitlab.example.com:user/registry.git"),
+            RegistryType::Git
+        );
+    }
+
+    #[test]
+    fn test_is_cached_version_compares_manifest() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join(PLUGIN_MANIFEST_FILE),
+            "[plugin]\nname = \"deploy\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+
+        assert!(is_cached_version(
+            temp_dir.path(),
+            &entry(None, Some("1.2.0"))
+        ));
+        assert!(!is_cached_version(
+            temp_dir.path(),
+            &entry(None, Some("1.3.0"))
+        ));
+        assert!(!is_cached_version(temp_dir.path(), &entry(None, None)));
+        assert!(!is_cached_version(
+            &temp_dir.path().join("missing"),
+            &entry(None, Some("1.2.0"))
+        ));
+    }
+}
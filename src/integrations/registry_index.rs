@@ -0,0 +1,340 @@
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    config::{plugins::load_plugin_manifest, settings},
+    constants::PLUGIN_MANIFEST_FILE,
+    integrations::registry_cache::cached_registry,
+    security::{local_registry_path, resolve_local_registry, validate_registry_source},
+};
+
+/// Index files a registry may keep at its root, in the order they're tried
+const INDEX_FILES: [&str; 2] = ["index.toml", "index.json"];
+
+/// A plugin a registry offers
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct RegistryEntry {
+    pub name: String,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+
+    /// Tarball to install the plugin from, for HTTPS index registries (absolute or
+    /// relative to the registry URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+
+    /// Expected SHA-256 of the tarball
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+/// The optional index.toml / index.json at a registry's root
+#[derive(Debug, Default, Deserialize, Serialize, PartialEq)]
+pub struct RegistryIndex {
+    #[serde(default)]
+    pub plugins: Vec<RegistryEntry>,
+}
+
+impl RegistryIndex {
+    pub fn parse(file_name: &str, contents: &str) -> Result<Self> {
+        if file_name.ends_with(".json") {
+            Ok(serde_json::from_str(contents)?)
+        } else {
+            Ok(toml::from_str(contents)?)
+        }
+    }
+
+    pub fn get(&self, plugin_name: &str) -> Option<&RegistryEntry> {
+        self.plugins.iter().find(|entry| entry.name == plugin_name)
+    }
+}
+
+/// Registry sources from mis.toml, the global config and MIS_REGISTRY, validated
+pub fn configured_registries() -> Result<Vec<String>> {
+    let sources = settings()
+        .registry
+        .as_ref()
+        .map(|registry| registry.sources.clone())
+        .unwrap_or_default();
+    if sources.is_empty() {
+        anyhow::bail!(
+            "🛑 No registry sources found.\n\
+             → Add a [registry] section to mis.toml or set MIS_REGISTRY."
+        );
+    }
+
+    for source in &sources {
+        if let Err(security_error) = validate_registry_source(source) {
+            anyhow::bail!(
+                "🛑 Security validation failed for registry '{}': {}\n\
+                 → Registry sources must be secure HTTPS git repositories from trusted sources, or local directories (`path:<dir>`).",
+                source,
+                security_error
+            );
+        }
+    }
+
+    Ok(sources)
+}
+
+/// Where a single file of a registry can be downloaded without cloning it.
+///
+/// Only GitHub and GitLab raw URLs are known; other hosts return None.
+pub fn raw_file_url(registry: &str, file: &str) -> Option<String> {
+    let url = Url::parse(registry.trim_end_matches('/')).ok()?;
+    let path = url.path().trim_end_matches(".git");
+    let mut segments = path.split('/').filter(|s| !s.is_empty());
+    let (owner, repo) = (segments.next()?, segments.next()?);
+
+    match url.host_str()? {
+        "github.com" => Some(format!(
+            "https://raw.githubusercontent.com/{}/{}/HEAD/{}",
+            owner, repo, file
+        )),
+        "gitlab.com" => Some(format!(
+            "https://gitlab.com/{}/{}/-/raw/HEAD/{}",
+            owner, repo, file
+        )),
+        _ => None,
+    }
+}
+
+/// Where an HTTPS index registry serves `file`: directly under the registry URL.
+///
+/// Only plain HTTPS URLs qualify; git remotes (`.git`, SSH, known git hosts) return None.
+pub fn index_file_url(registry: &str, file: &str) -> Option<String> {
+    if looks_like_git_remote(registry) {
+        return None;
+    }
+    let url = Url::parse(registry.trim()).ok()?;
+    if url.scheme() != "https" {
+        return None;
+    }
+    Some(format!("{}/{}", url.as_str().trim_end_matches('/'), file))
+}
+
+/// Registry sources that can only be git repositories
+pub fn looks_like_git_remote(registry: &str) -> bool {
+    let registry = registry.trim().trim_end_matches('/');
+    if registry.starts_with("git@")
+        || registry.starts_with("ssh://")
+        || registry.starts_with("git://")
+        || registry.ends_with(".git")
+    {
+        return true;
+    }
+    Url::parse(registry).is_ok_and(|url| {
+        matches!(
+            url.host_str(),
+            Some("github.com" | "gitlab.com" | "bitbucket.org")
+        )
+    })
+}
+
+/// Download the registry's index, or None if it has none or it can't be fetched
+pub fn fetch_registry_index(registry: &str) -> Option<RegistryIndex> {
+    if let Some(path) = local_registry_path(registry) {
+        let dir = resolve_local_registry(path);
+        let file = INDEX_FILES
+            .into_iter()
+            .find(|file| dir.join(file).is_file())?;
+        let contents = fs::read_to_string(dir.join(file)).ok()?;
+        return parse_index(registry, file, &contents);
+    }
+
+    for file in INDEX_FILES {
+        let url = raw_file_url(registry, file).or_else(|| index_file_url(registry, file))?;
+        let Ok(output) = Command::new("curl")
+            .args(["--fail", "--silent", "--location", "--max-time", "10", &url])
+            .output()
+        else {
+            // No curl: fall back to cloning
+            return None;
+        };
+        if !output.status.success() {
+            continue;
+        }
+
+        let contents = String::from_utf8_lossy(&output.stdout);
+        return parse_index(registry, file, &contents);
+    }
+
+    None
+}
+
+fn parse_index(registry: &str, file: &str, contents: &str) -> Option<RegistryIndex> {
+    match RegistryIndex::parse(file, contents) {
+        Ok(index) => Some(index),
+        Err(e) => {
+            errln!(
+                "⚠️  Warning: Ignoring invalid {} in registry {}: {}",
+                file,
+                registry,
+                e
+            );
+            None
+        }
+    }
+}
+
+/// Every plugin a registry offers, from its index when it has one and from a (cached) clone otherwise
+pub fn list_registry_plugins(registry: &str) -> Result<Vec<RegistryEntry>> {
+    if let Some(index) = fetch_registry_index(registry) {
+        return Ok(index.plugins);
+    }
+
+    Ok(scan_registry_clone(&cached_registry(registry, false)?))
+}
+
+/// List the plugins in a cloned registry (under plugins/ or at the root)
+pub fn scan_registry_clone(clone_root: &Path) -> Vec<RegistryEntry> {
+    let mut entries: Vec<RegistryEntry> = Vec::new();
+
+    for dir in [clone_root.join("plugins"), clone_root.to_path_buf()] {
+        let Ok(read_dir) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut manifests: Vec<_> = read_dir
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path().join(PLUGIN_MANIFEST_FILE))
+            .filter(|manifest| manifest.is_file())
+            .collect();
+        manifests.sort();
+
+        for manifest_path in manifests {
+            let Ok(manifest) = load_plugin_manifest(&manifest_path) else {
+                continue;
+            };
+            if entries.iter().any(|e| e.name == manifest.plugin.name) {
+                continue;
+            }
+            entries.push(RegistryEntry {
+                name: manifest.plugin.name,
+                version: Some(manifest.plugin.version),
+                description: manifest.plugin.description,
+                url: None,
+                sha256: None,
+            });
+        }
+    }
+
+    entries
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_raw_file_url_for_known_hosts() {
+        assert_eq!(
+            raw_file_url("https://github.com/user/registry.git", "index.toml").unwrap(),
+            "https://raw.githubusercontent.com/user/registry/HEAD/index.toml"
+        );
+        assert_eq!(
+            raw_file_url("https://gitlab.com/group/registry/", "index.json").unwrap(),
+            "https://gitlab.com/group/registry/-/raw/HEAD/index.json"
+        );
+        assert!(raw_file_url("https://git.example.com/user/registry", "index.toml").is_none());
+        assert!(raw_file_url("https://github.com/user", "index.toml").is_none());
+    }
+
+    #[test]
+    fn test_index_file_url_only_for_plain_https_registries() {
+        assert_eq!(
+            index_file_url("https://artifacts.example.com/mis/", "index.json").unwrap(),
+            "https://artifacts.example.com/mis/index.json"
+        );
+        assert!(
+            index_file_url("https://git.example.com/user/registry.git", "index.toml").is_none()
+        );
+        assert!(index_file_url("https://github.com/user/registry", "index.toml").is_none());
+        assert!(index_file_url("git@example.com:user/registry", "index.toml").is_none());
+        assert!(index_file_url("http://artifacts.example.com/mis", "index.toml").is_none());
+    }
+
+    #[test]
+    fn test_registry_index_parses_toml_and_json() {
+        let index = RegistryIndex::parse(
+            "index.toml",
+            r#"
+[[plugins]]
+name = "deploy"
+version = "1.2.0"
+description = "Deploy to Kubernetes"
+
+[[plugins]]
+name = "lint"
+"#,
+        )
+        .unwrap();
+        assert_eq!(index.plugins.len(), 2);
+        assert_eq!(
+            index.get("deploy").unwrap().version.as_deref(),
+            Some("1.2.0")
+        );
+        assert!(index.get("lint").unwrap().description.is_none());
+        assert!(index.get("missing").is_none());
+
+        let index = RegistryIndex::parse(
+            "index.json",
+            r#"{"plugins": [{"name": "deploy", "version": "1.2.0"}]}"#,
+        )
+        .unwrap();
+        assert!(index.get("deploy").is_some());
+
+        assert!(RegistryIndex::parse("index.json", "not json").is_err());
+    }
+
+    #[test]
+    fn test_fetch_registry_index_reads_local_registries() {
+        let temp_dir = tempdir().unwrap();
+        let source = format!("path:{}", temp_dir.path().display());
+        assert!(fetch_registry_index(&source).is_none());
+
+        fs::write(
+            temp_dir.path().join("index.json"),
+            r#"{"plugins": [{"name": "deploy"}]}"#,
+        )
+        .unwrap();
+        let index = fetch_registry_index(&source).unwrap();
+        assert!(index.get("deploy").is_some());
+    }
+
+    #[test]
+    fn test_scan_registry_clone_reads_manifests() {
+        let temp_dir = tempdir().unwrap();
+        let manifest = |name: &str| {
+            format!(
+                "[plugin]\nname = \"{}\"\nversion = \"0.1.0\"\ndescription = \"The {} plugin\"\n",
+                name, name
+            )
+        };
+        for (dir, name) in [
+            ("plugins/deploy", "deploy"),
+            ("lint", "lint"),
+            ("deploy", "deploy"),
+        ] {
+            fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+            fs::write(
+                temp_dir.path().join(dir).join(PLUGIN_MANIFEST_FILE),
+                manifest(name),
+            )
+            .unwrap();
+        }
+        fs::create_dir_all(temp_dir.path().join("docs")).unwrap();
+
+        let entries = scan_registry_clone(temp_dir.path());
+        let names: Vec<&str> = entries.iter().map(|e| e.name.as_str()).collect();
+        assert_eq!(names, vec!["deploy", "lint"]);
+        assert_eq!(entries[1].description.as_deref(), Some("The lint plugin"));
+    }
+}
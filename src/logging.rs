@@ -0,0 +1,67 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// How much mis says about what it's doing, set with `-q`, `-v` and `-vv`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    /// Errors, warnings, prompts and JSON reports only
+    Quiet,
+    Normal,
+    /// Also where config, plugins and registries were found
+    Verbose,
+    /// Also the exact commands run and files handed to plugins
+    Trace,
+}
+
+impl Verbosity {
+    pub fn from_flags(quiet: bool, verbose: u8) -> Self {
+        match (quiet, verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Trace,
+        }
+    }
+}
+
+static LEVEL: AtomicU8 = AtomicU8::new(Verbosity::Normal as u8);
+
+pub fn set_verbosity(verbosity: Verbosity) {
+    LEVEL.store(verbosity as u8, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` should be printed
+pub fn enabled(level: Verbosity) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level as u8
+}
+
+/// Print a diagnostic line to stderr with `-v` or `-vv`
+macro_rules! debugln {
+    ($($arg:tt)*) => {
+        if $crate::logging::enabled($crate::logging::Verbosity::Verbose) {
+            eprintln!("debug: {}", $crate::theme::render(&format!($($arg)*)))
+        }
+    };
+}
+
+/// Print a diagnostic line to stderr with `-vv`
+macro_rules! traceln {
+    ($($arg:tt)*) => {
+        if $crate::logging::enabled($crate::logging::Verbosity::Trace) {
+            eprintln!("trace: {}", $crate::theme::render(&format!($($arg)*)))
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verbosity_from_flags() {
+        assert_eq!(Verbosity::from_flags(true, 2), Verbosity::Quiet);
+        assert_eq!(Verbosity::from_flags(false, 0), Verbosity::Normal);
+        assert_eq!(Verbosity::from_flags(false, 1), Verbosity::Verbose);
+        assert_eq!(Verbosity::from_flags(false, 3), Verbosity::Trace);
+        assert!(Verbosity::Trace > Verbosity::Verbose);
+    }
+}
@@ -4,48 +4,308 @@
 //! A silly, hilarious extravagance in personal CLI tooling that is delightfully excessive yet hopefully useful.
 //!
 
+// Declared first so their printing macros are available in every other module
+#[macro_use]
+mod theme;
+#[macro_use]
+mod logging;
+
+mod capabilities;
 mod cli;
 mod commands;
 mod config;
 mod constants;
+mod crash_report;
+mod failure_hints;
 mod git_utils;
 mod integrations;
+mod manifest_schema;
 mod models;
+mod onboarding;
+mod permission_audit;
+mod plan;
+mod plan_diff;
+mod plugin_lock;
+mod plugin_log;
 mod plugin_utils;
+mod progress;
+mod project_lock;
+mod report;
+mod run_log;
+mod run_state;
+mod scheduler;
 mod security;
+mod signals;
+mod temp_files;
+mod trust_store;
 mod utils;
 mod validation;
 
 use anyhow::anyhow;
 use clap::Parser;
-use cli::{Cli, Commands};
+use plan::SavedPlan;
+use cli::{
+    ApiAction, AuditAction, AuthAction, CacheAction, Cli, Commands, ConfigAction, DocsAction,
+    PermissionsAction,
+};
 use commands::{
     add::add_plugin,
+    api::run_api_update,
+    audit::{run_audit, run_audit_deps},
+    auth::{run_login, run_logout},
+    cache::clean_cache,
+    config::{run_config_get, run_config_list, run_config_set},
+    context::run_context,
     create::create_plugin,
-    help::{show_all_plugins, show_help},
+    docs::{run_docs_export, run_install_man},
+    enable::{run_disable, run_enable},
+    exec::run_exec,
+    gc::run_gc,
+    help::{
+        show_all_plugins, show_help, show_plugin_permissions, show_project_variables,
+        show_remote_plugin,
+    },
+    history::run_history,
     init::run_init,
-    run::run_cmd,
+    list::run_list,
+    migrate::run_migrate,
+    permissions::run_simulate,
+    run::{RunOptions, check_protected_environment, default_command, run_cmd},
+    sbom::run_sbom,
+    search::run_search,
+    shim::create_shim,
+    test::run_plugin_tests,
+    workflow::run_workflow,
     update::update_plugin,
+    vendor::run_vendor,
+    validate::run_validate,
+    verify::run_verify,
 };
 
-fn main() -> anyhow::Result<()> {
+fn main() {
+    if let Err(e) = run() {
+        if report::json_output() {
+            report::print_error(&e);
+        } else {
+            errln!("Error: {:?}", e);
+        }
+        // Like a shell, report a run stopped by a signal as 128 + the signal number
+        std::process::exit(signals::received().map_or(1, |signal| 128 + signal));
+    }
+}
+
+fn run() -> anyhow::Result<()> {
+    crash_report::install_panic_hook();
+
     // Transform args to support implicit run (e.g., "mis plugin:cmd" → "mis run plugin:cmd")
     let args: Vec<String> = std::env::args().collect();
-    let transformed_args = cli::transform_args_for_implicit_run(&args);
+    // Aliases from mis.toml expand first, so `mis deploy` can stand for a whole command line
+    let project_config = utils::find_project_root()
+        .and_then(|root| config::load_mis_config_from(&root).ok())
+        .map(|(config, _, _)| config)
+        .unwrap_or_default();
+    let aliases = project_config.aliases;
+    let mut conflicts: Vec<_> = aliases
+        .keys()
+        .filter_map(|name| cli::alias_conflict(name))
+        .collect();
+    conflicts.sort();
+    for conflict in conflicts {
+        errln!(
+            "⚠️  Ignoring an alias in mis.toml: {}.\n→ Rename it in [aliases].",
+            conflict
+        );
+    }
+    let args = cli::expand_alias(&args, &aliases)?;
+    let transformed_args = match project_config.default_command {
+        Some(_) => cli::transform_args_for_default_command(&args),
+        None => cli::transform_args_for_implicit_run(&args),
+    };
 
     let cli = Cli::parse_from(transformed_args);
+    let output = cli.output;
+    report::set_json_output(output == cli::OutputFormat::Json);
+    logging::set_verbosity(logging::Verbosity::from_flags(cli.quiet, cli.verbose));
+
+    // Like `git -C`: everything after this sees the chosen project as the current directory
+    if let Some(project) = &cli.project {
+        let dir = utils::resolve_project_dir(&std::env::current_dir()?, project)?;
+        std::env::set_current_dir(&dir)?;
+        debugln!("Using project {}", dir.display());
+    }
+
+    let project_config = utils::find_project_root()
+        .and_then(|root| config::load_mis_config_from(&root).ok())
+        .map(|(config, _, _)| config);
+    let strict_manifests =
+        cli.strict || project_config.as_ref().is_some_and(|config| config.strict_manifests);
+    config::plugins::set_strict_manifests(strict_manifests);
+    if let Some(version) = project_config.and_then(|config| config.schema_version)
+        && version > constants::PROJECT_SCHEMA_VERSION
+        && !matches!(cli.command, Commands::Migrate { .. })
+    {
+        errln!(
+            "⚠️  This project uses layout version {}, but this mis only knows up to {}. Upgrade mis.",
+            version,
+            constants::PROJECT_SCHEMA_VERSION
+        );
+    }
+
+    // Env files go into the process environment before settings read it and before any thread starts
+    let environment = match &cli.command {
+        Commands::Run { env, .. }
+        | Commands::Context { env, .. }
+        | Commands::Exec { env, .. } => env.as_deref(),
+        _ => None,
+    };
+    if matches!(
+        cli.command,
+        Commands::Run { .. } | Commands::Context { .. } | Commands::Test { .. } | Commands::Exec { .. }
+    ) {
+        config::env_files::load_env_files(utils::find_project_root().as_deref(), environment)?;
+    }
+
+    let (allow_insecure_net, strict_security) = match cli.command {
+        Commands::Run {
+            allow_insecure_net,
+            strict_security,
+            ..
+        } => (allow_insecure_net, strict_security),
+        _ => (false, false),
+    };
+    let cli_settings = models::UserSettings {
+        color: if cli.no_color {
+            Some(models::ColorChoice::Never)
+        } else {
+            cli.color
+        },
+        theme: cli.theme,
+        security: (allow_insecure_net || strict_security).then(|| models::SecurityPolicy {
+            allow_insecure_network: allow_insecure_net.then_some(true),
+            strict: strict_security.then_some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    config::init_settings(utils::find_project_root().as_deref(), cli_settings)?;
+
+    // A run that crashed couldn't remove its temp files; clear out what's been left for a day
+    temp_files::remove_abandoned_runs(temp_files::STALE_AFTER);
 
     match cli.command {
-        Commands::Init { name } => {
+        Commands::Init {
+            name,
+            interactive,
+            from,
+        } => {
             let name_ref = name.as_deref();
-            run_init(name_ref)?;
+            run_init(name_ref, interactive, from.as_deref())?;
         }
 
         Commands::Run {
             plugin,
-            args,
             dry_run,
+            env,
+            trace_perms,
+            show_permissions,
+            resume,
+            allow_insecure_net: _,
+            strict_security: _,
+            upload_artifacts,
+            diff_format,
+            container,
+            no_lock,
+            lock_timeout,
+            plan,
+            plan_out,
+            yes,
+            strict_deprecations,
+            confirm_env,
+            approval,
+            apply_plan,
+            mut args,
+            raw_args,
         } => {
+            // clap keeps a `--` that follows other args in `args`, but consumes a leading one
+            if !raw_args.is_empty() {
+                args.push("--".to_string());
+                args.extend(raw_args);
+            }
+            let saved_plan = apply_plan.as_deref().map(SavedPlan::load).transpose()?;
+            let (plugin, parsed_args, raw_args) = match &saved_plan {
+                Some(saved) => {
+                    saved.check_invocation(plugin.as_deref(), &args, env.as_deref())?;
+                    (
+                        saved.target(),
+                        saved.args.clone().into_iter().collect(),
+                        saved.raw_args.clone(),
+                    )
+                }
+                None => {
+                    let (plugin, args) = match plugin {
+                        Some(plugin) => (plugin, args),
+                        // Arguments given to `mis run` add to those in default_command
+                        None => {
+                            let (target, mut default_args) = default_command()?;
+                            default_args.extend(args);
+                            (target, default_args)
+                        }
+                    };
+                    let (args, raw_args) = cli::split_raw_args(args);
+                    (plugin, cli::parse_cli_args(&args), raw_args)
+                }
+            };
+            let dry_run = dry_run || plan;
+            let options = RunOptions {
+                output,
+                environment: env.as_deref(),
+                trace_permissions: trace_perms,
+                show_permissions,
+                upload_artifacts,
+                diff_format,
+                container,
+                no_lock,
+                lock_timeout: lock_timeout.map(std::time::Duration::from_secs),
+                plan,
+                plan_out: plan_out.as_deref(),
+                apply_plan: saved_plan.as_ref(),
+                yes,
+                strict_deprecations,
+                approval: approval.as_deref(),
+                raw_args: &raw_args,
+                ..Default::default()
+            };
+            check_protected_environment(
+                options.environment,
+                dry_run,
+                confirm_env.as_deref(),
+                options.approval,
+            )?;
+
+            // A bare name (no ':') refers to a workflow defined in mis.toml
+            if !plugin.contains(':') {
+                if plan {
+                    anyhow::bail!("🛑 --plan only applies to plugin commands, not workflows.");
+                }
+                if show_permissions {
+                    anyhow::bail!(
+                        "🛑 --show-permissions only applies to plugin commands, not workflows.\n\
+                         → Run it for each step, e.g. `mis run <plugin>:<command> --show-permissions`."
+                    );
+                }
+                if !raw_args.is_empty() {
+                    anyhow::bail!(
+                        "🛑 Arguments after `--` only apply to plugin commands, not workflows."
+                    );
+                }
+                run_workflow(&plugin, dry_run, parsed_args, options, resume.as_deref())?;
+                return Ok(());
+            }
+
+            if resume.is_some() {
+                anyhow::bail!("🛑 --resume only applies to workflows, not plugin commands.");
+            }
+
             let parts: Vec<&str> = plugin.split(':').collect();
             if parts.len() != 2 {
                 return Err(anyhow!(
@@ -57,14 +317,54 @@ fn main() -> anyhow::Result<()> {
 
             let plugin_name = parts[0].to_string();
 
-            let parsed_args = cli::parse_cli_args(&args);
-
             // Run the command
-            run_cmd(plugin_name, command_name, dry_run, parsed_args)?;
+            run_cmd(plugin_name, command_name, dry_run, parsed_args, options)?;
+        }
+
+        Commands::Exec {
+            script,
+            allow_read,
+            allow_write,
+            allow_net,
+            allow_run,
+            allow_env,
+            dry_run,
+            env,
+            trace_perms,
+            confirm_env,
+            approval,
+            args,
+        } => {
+            let grants = models::SecurityPermissions {
+                file_read: allow_read,
+                file_write: allow_write,
+                network: allow_net,
+                run_commands: allow_run,
+                env_vars: allow_env,
+                ..Default::default()
+            };
+            let options = RunOptions {
+                output,
+                environment: env.as_deref(),
+                trace_permissions: trace_perms,
+                approval: approval.as_deref(),
+                ..Default::default()
+            };
+            check_protected_environment(
+                options.environment,
+                dry_run,
+                confirm_env.as_deref(),
+                options.approval,
+            )?;
+            run_exec(&script, grants, &args, dry_run, options)?;
         }
 
-        Commands::Create { name } => {
-            create_plugin(&name)?;
+        Commands::Create {
+            name,
+            template,
+            runtime,
+        } => {
+            create_plugin(&name, template.as_deref(), runtime)?;
         }
 
         Commands::Add {
@@ -72,18 +372,137 @@ fn main() -> anyhow::Result<()> {
             dry_run,
             registry,
             force,
+            refresh,
+            vars,
         } => {
-            add_plugin(plugins, dry_run, registry, force)?;
+            add_plugin(plugins, dry_run, registry, force, refresh, vars)?;
         }
 
-        Commands::Update { plugin, dry_run } => {
-            update_plugin(plugin, dry_run)?;
+        Commands::Update {
+            plugin,
+            dry_run,
+            check,
+            diff,
+        } => {
+            update_plugin(plugin, dry_run, check, diff)?;
         }
 
-        Commands::Info { plugin_command } => match plugin_command {
+        Commands::List { workspace } => run_list(workspace)?,
+
+        Commands::Info {
+            plugin_command,
+            vars,
+            remote,
+            permissions,
+            all,
+            format,
+        } => match plugin_command {
+            _ if all => run_docs_export(format.unwrap_or_default())?,
+            _ if vars => show_project_variables()?,
+            Some(target) if permissions => show_plugin_permissions(&target)?,
+            Some(plugin_cmd) if remote => {
+                // `plugin:command` is accepted too; registries list plugins
+                let plugin_name = plugin_cmd.split(':').next().unwrap_or(&plugin_cmd);
+                show_remote_plugin(plugin_name)?
+            }
+            None if remote => anyhow::bail!("🛑 `mis info --remote` needs a plugin name."),
             Some(plugin_cmd) => show_help(&plugin_cmd)?,
             None => show_all_plugins()?,
         },
+
+        Commands::Shim {
+            alias,
+            plugin_command,
+            force,
+            args,
+        } => {
+            create_shim(&alias, &plugin_command, args, force)?;
+        }
+
+        Commands::Audit { plugin, action } => match action {
+            Some(AuditAction::Deps { plugin }) => run_audit_deps(plugin.as_deref(), output)?,
+            None => run_audit(plugin.as_deref(), output)?,
+        },
+
+        Commands::Sbom { format } => {
+            run_sbom(format)?;
+        }
+
+        Commands::Verify { plugin, remote } => {
+            run_verify(plugin.as_deref(), remote, output)?;
+        }
+
+        Commands::Validate { plugin } => run_validate(plugin.as_deref(), output)?,
+        Commands::Migrate { dry_run } => run_migrate(dry_run, output)?,
+        Commands::Disable { plugin } => run_disable(&plugin)?,
+        Commands::Enable { plugin } => run_enable(&plugin)?,
+
+        Commands::History {
+            plugin,
+            failed,
+            limit,
+        } => {
+            run_history(plugin.as_deref(), failed, limit, output)?;
+        }
+
+        Commands::Search { query } => {
+            run_search(&query, output)?;
+        }
+
+        Commands::Permissions { action } => match action {
+            PermissionsAction::Simulate { manifest, command } => run_simulate(&manifest, command.as_deref(), output)?,
+        },
+
+        Commands::Docs { action } => match action {
+            DocsAction::InstallMan { dir } => run_install_man(dir.as_deref())?,
+        },
+
+        Commands::Auth { action } => match action {
+            AuthAction::Login { registry } => run_login(&registry)?,
+            AuthAction::Logout { registry } => run_logout(&registry)?,
+        },
+
+        Commands::Gc { keep_days, dry_run } => {
+            run_gc(keep_days, dry_run)?;
+        }
+
+        Commands::Cache { action } => match action {
+            CacheAction::Clean { vars } => clean_cache(vars)?,
+        },
+
+        Commands::Config { action } => match action {
+            ConfigAction::Get { key, plugin } => run_config_get(&key, plugin.as_deref())?,
+            ConfigAction::Set { key, value, plugin } => {
+                run_config_set(&key, &value, plugin.as_deref())?
+            }
+            ConfigAction::List { plugin } => run_config_list(plugin.as_deref())?,
+        },
+
+        Commands::Test { target } => {
+            run_plugin_tests(&target, output)?;
+        }
+
+        Commands::Context {
+            plugin_command,
+            args,
+            dry_run,
+            env,
+            out_file,
+        } => {
+            run_context(
+                &plugin_command,
+                &args,
+                dry_run,
+                env.as_deref(),
+                out_file.as_deref(),
+            )?;
+        }
+
+        Commands::Vendor => run_vendor()?,
+
+        Commands::Api { action } => match action {
+            ApiAction::Update => run_api_update()?,
+        },
     }
 
     Ok(())
@@ -0,0 +1,288 @@
+use std::ops::Range;
+
+use toml_edit::{Item, TableLike};
+
+use crate::validation::suggest_similar;
+
+/// A manifest.toml key that `PluginManifest` has no field for, so serde would drop it
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownKey {
+    /// Dotted path of the table holding the key; empty at the top level
+    pub table: String,
+    pub key: String,
+    /// Where the key is written
+    pub span: Option<Range<usize>>,
+    /// The likely fix: a nesting mistake, a close spelling, or the keys that are allowed
+    pub hint: String,
+}
+
+impl UnknownKey {
+    pub fn message(&self) -> String {
+        if self.table.is_empty() {
+            format!("Unknown key `{}`", self.key)
+        } else {
+            format!("Unknown key `{}` in [{}]", self.key, self.table)
+        }
+    }
+}
+
+/// The keys a manifest.toml table may hold
+enum Shape {
+    /// Any value, including tables with keys of their own
+    Any,
+    /// A table with only these keys
+    Table(&'static [(&'static str, Shape)]),
+    /// A table keyed by names the author picks (commands, arguments), each of this shape
+    Map(&'static Shape),
+    /// One table of this shape, or an array of them
+    Tables(&'static Shape),
+}
+
+impl Shape {
+    fn fields(&self) -> &'static [(&'static str, Shape)] {
+        match self {
+            Shape::Table(fields) => fields,
+            _ => &[],
+        }
+    }
+}
+
+const PERMISSIONS: Shape = Shape::Table(&[
+    ("file_read", Shape::Any),
+    ("file_write", Shape::Any),
+    ("env_access", Shape::Any),
+    ("env_vars", Shape::Any),
+    ("network", Shape::Any),
+    ("run_commands", Shape::Any),
+]);
+
+const ARG: Shape = Shape::Table(&[
+    ("description", Shape::Any),
+    ("arg_type", Shape::Any),
+    ("default_value", Shape::Any),
+    ("allowed_values", Shape::Any),
+    ("aliases", Shape::Any),
+    ("multiple", Shape::Any),
+]);
+
+const TEST_CASE: Shape = Shape::Table(&[
+    ("name", Shape::Any),
+    ("command", Shape::Any),
+    ("args", Shape::Any),
+    ("dry_run", Shape::Any),
+    ("environment", Shape::Any),
+    ("config", Shape::Any),
+    ("project_variables", Shape::Any),
+    ("events", Shape::Any),
+    ("timeout", Shape::Any),
+    (
+        "expect",
+        Shape::Table(&[
+            ("success", Shape::Any),
+            ("message", Shape::Any),
+            ("outputs", Shape::Any),
+            ("events", Shape::Any),
+            ("changes", Shape::Any),
+        ]),
+    ),
+]);
+
+const COMMAND: Shape = Shape::Table(&[
+    ("script", Shape::Any),
+    ("description", Shape::Any),
+    ("instructions", Shape::Any),
+    (
+        "args",
+        Shape::Table(&[
+            ("required", Shape::Map(&ARG)),
+            ("optional", Shape::Map(&ARG)),
+        ]),
+    ),
+    ("permissions", PERMISSIONS),
+    (
+        "hooks",
+        Shape::Table(&[
+            ("pre_run", Shape::Any),
+            ("post_run", Shape::Any),
+            ("on_failure", Shape::Any),
+        ]),
+    ),
+    ("test", Shape::Tables(&TEST_CASE)),
+    ("exclusive", Shape::Any),
+    ("confirm", Shape::Any),
+    ("deprecated", Shape::Any),
+]);
+
+/// Everything manifest.toml may contain, mirroring `PluginManifest`
+const MANIFEST: Shape = Shape::Table(&[
+    ("mis_version_requirement", Shape::Any),
+    ("context_transport", Shape::Any),
+    (
+        "plugin",
+        Shape::Table(&[
+            ("name", Shape::Any),
+            ("description", Shape::Any),
+            ("version", Shape::Any),
+            ("registry", Shape::Any),
+            ("pin", Shape::Any),
+            ("execution", Shape::Any),
+        ]),
+    ),
+    ("commands", Shape::Map(&COMMAND)),
+    ("deno_dependencies", Shape::Any),
+    ("permissions", PERMISSIONS),
+    (
+        "config_schema",
+        Shape::Map(&Shape::Table(&[
+            ("description", Shape::Any),
+            ("type", Shape::Any),
+            ("required", Shape::Any),
+            ("default", Shape::Any),
+        ])),
+    ),
+    (
+        "requires",
+        Shape::Table(&[("mis", Shape::Any), ("features", Shape::Any)]),
+    ),
+    ("container", Shape::Table(&[("image", Shape::Any)])),
+    ("context", Shape::Table(&[("git", Shape::Any)])),
+]);
+
+/// Every key in a parsed manifest.toml that mis doesn't recognize
+pub fn unknown_keys(manifest: &dyn TableLike) -> Vec<UnknownKey> {
+    let mut found = Vec::new();
+    check_table(manifest, MANIFEST.fields(), "", &mut found);
+    found
+}
+
+/// 1-based line of a byte offset in `text`
+pub fn line_at(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+fn check_shape(item: &Item, shape: &Shape, path: &str, found: &mut Vec<UnknownKey>) {
+    match shape {
+        Shape::Any => {}
+        Shape::Table(fields) => {
+            if let Some(table) = item.as_table_like() {
+                check_table(table, fields, path, found);
+            }
+        }
+        Shape::Map(inner) => {
+            if let Some(table) = item.as_table_like() {
+                for (key, value) in table.iter() {
+                    check_shape(value, inner, &format!("{}.{}", path, key), found);
+                }
+            }
+        }
+        Shape::Tables(inner) => {
+            if let Some(tables) = item.as_array_of_tables() {
+                for table in tables.iter() {
+                    check_table(table, inner.fields(), path, found);
+                }
+            } else if let Some(values) = item.as_array() {
+                for table in values.iter().filter_map(|value| value.as_inline_table()) {
+                    check_table(table, inner.fields(), path, found);
+                }
+            } else {
+                check_shape(item, inner, path, found);
+            }
+        }
+    }
+}
+
+fn check_table(
+    table: &dyn TableLike,
+    fields: &[(&str, Shape)],
+    path: &str,
+    found: &mut Vec<UnknownKey>,
+) {
+    for (key, value) in table.iter() {
+        match fields.iter().find(|(name, _)| *name == key) {
+            Some((_, shape)) if path.is_empty() => check_shape(value, shape, key, found),
+            Some((_, shape)) => check_shape(value, shape, &format!("{}.{}", path, key), found),
+            None => found.push(UnknownKey {
+                table: path.to_string(),
+                key: key.to_string(),
+                span: table
+                    .key(key)
+                    .and_then(|k| k.span())
+                    .or_else(|| value.span()),
+                hint: unknown_key_hint(key, fields, path),
+            }),
+        }
+    }
+}
+
+/// Name the likely fix for an unknown manifest key
+fn unknown_key_hint(key: &str, fields: &[(&str, Shape)], path: &str) -> String {
+    // `[plugin.permissions]` and friends: top-level tables nested under [plugin] by mistake
+    if path == "plugin" && MANIFEST.fields().iter().any(|(name, _)| *name == key) {
+        return format!(
+            "[{}] is a top-level table; write [{}] instead of [plugin.{}].",
+            key, key, key
+        );
+    }
+    let known = fields.iter().map(|(name, _)| *name);
+    match suggest_similar(key, known.clone()) {
+        Some(suggestion) => format!("Did you mean `{}`?", suggestion),
+        None => format!("Expected one of: {}", known.collect::<Vec<_>>().join(", ")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use toml_edit::ImDocument;
+
+    fn unknown(manifest: &str) -> Vec<UnknownKey> {
+        unknown_keys(ImDocument::parse(manifest).unwrap().as_table())
+    }
+
+    #[test]
+    fn test_unknown_keys() {
+        let manifest = r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+descripton = "Deploys"
+
+[commands.prod]
+script = "prod.ts"
+test = [{ name = "smoke", expect = { sucess = true } }]
+
+[commands.prod.args.optional.region]
+description = "Region"
+type = "string"
+
+[deno_dependencies]
+anything = "https://deno.land/x/anything/mod.ts"
+"#;
+        let found = unknown(manifest);
+        let messages: Vec<String> = found.iter().map(UnknownKey::message).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Unknown key `descripton` in [plugin]",
+                "Unknown key `sucess` in [commands.prod.test.expect]",
+                "Unknown key `type` in [commands.prod.args.optional.region]",
+            ]
+        );
+        assert_eq!(found[0].hint, "Did you mean `description`?");
+        assert_eq!(line_at(manifest, found[0].span.clone().unwrap().start), 5);
+        assert!(
+            found[2]
+                .hint
+                .starts_with("Expected one of: description, arg_type")
+        );
+
+        let found = unknown(
+            "[plugin]\nname = \"x\"\nversion = \"1\"\n\n[plugin.commands.go]\nscript = \"go.ts\"\n",
+        );
+        assert_eq!(
+            found[0].hint,
+            "[commands] is a top-level table; write [commands] instead of [plugin.commands]."
+        );
+        assert!(unknown("[plugin]\nname = \"x\"\nversion = \"1\"\n").is_empty());
+    }
+}
@@ -1,9 +1,14 @@
+use indexmap::IndexMap;
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::path::PathBuf;
 use toml::Value as TomlValue;
 
-#[derive(Debug, Deserialize, Clone)]
+use crate::capabilities::{CONTEXT_SCHEMA_VERSION, Capabilities};
+use crate::git_utils::GitInfo;
+
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct MakeItSoConfig {
     pub name: Option<String>,
 
@@ -12,13 +17,320 @@ pub struct MakeItSoConfig {
 
     #[serde(default)]
     pub registry: Option<RegistryConfig>,
+
+    /// Marks this mis.toml as a workspace root whose variables are shared with member projects
+    #[serde(default)]
+    pub workspace: Option<WorkspaceConfig>,
+
+    /// The mis.toml each entry in `project_variables` was resolved from (filled in by the loader)
+    #[serde(skip)]
+    pub variable_origins: HashMap<String, PathBuf>,
+
+    /// Named sequences of plugin commands, run with `mis run <workflow>`
+    #[serde(default)]
+    pub workflows: IndexMap<String, WorkflowDefinition>,
+
+    /// Environment profiles selected with `mis run --env <name>`
+    #[serde(default)]
+    pub env: IndexMap<String, EnvProfile>,
+
+    /// Where `mis run --upload-artifacts` sends each run's artifacts
+    #[serde(default)]
+    pub artifacts: Option<ArtifactsConfig>,
+
+    /// Hooks around plugin commands, keyed by "plugin:command"
+    #[serde(default)]
+    pub hooks: IndexMap<String, CommandHooks>,
+
+    /// Settings for one plugin command, keyed by "plugin:command"
+    #[serde(default)]
+    pub commands: IndexMap<String, CommandSettings>,
+
+    /// Dotenv files loaded before plugins run, relative to the project root (e.g. [".env"])
+    #[serde(default)]
+    pub env_files: Vec<String>,
+
+    /// Also expose the env files' variables as project variables
+    #[serde(default)]
+    pub env_file_variables: bool,
+
+    /// Shortcuts for whole command lines, e.g. `deploy = "k8s:apply --environment production"`
+    #[serde(default)]
+    pub aliases: IndexMap<String, String>,
+
+    /// What a bare `mis` or `mis run` runs, like `make` without a target (e.g. "build:all")
+    #[serde(default)]
+    pub default_command: Option<String>,
+
+    /// Refuse to load plugin manifests with keys mis doesn't recognize, like `--strict`
+    #[serde(default)]
+    pub strict_manifests: bool,
+
+    /// Layout version the project was created or last migrated with; missing means 0
+    #[serde(default)]
+    pub schema_version: Option<u32>,
+
+    /// Installed plugins hidden from `mis run` and `mis info` until `mis enable`, e.g. ["k8s"]
+    #[serde(default)]
+    pub disabled_plugins: Vec<String>,
+}
+
+/// What to run before, after, or instead of finishing a plugin command
+#[derive(Debug, Deserialize, Serialize, Clone, Default, PartialEq)]
+pub struct CommandHooks {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pre_run: Vec<HookAction>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub post_run: Vec<HookAction>,
+
+    /// Run when the command (or one of its pre_run hooks) fails
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on_failure: Vec<HookAction>,
+}
+
+/// One hook: a plugin command ("lint:check") or a shell snippet ({ shell = "npm ci" })
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum HookAction {
+    Plugin(String),
+    Shell { shell: String },
 }
 
 #[derive(Debug, Deserialize, Clone)]
+pub struct ArtifactsConfig {
+    pub sink: ArtifactSink,
+
+    /// Bucket, container or base URL that files are uploaded under
+    pub destination: String,
+
+    /// Environment variable holding a bearer token (http sink only)
+    #[serde(default)]
+    pub token_env: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ArtifactSink {
+    S3,
+    Gcs,
+    Azure,
+    Http,
+}
+
+/// `[commands."plugin:command"]` in mis.toml
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct CommandSettings {
+    /// Overrides `project_variables` for this command only
+    #[serde(default)]
+    pub variables: HashMap<String, TomlValue>,
+}
+
+/// Variables that apply only when a profile is selected (e.g. [env.staging])
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct EnvProfile {
+    #[serde(default)]
+    pub project_variables: HashMap<String, TomlValue>,
+
+    /// Runs against this environment need its name typed back (or --confirm-env)
+    #[serde(default)]
+    pub protected: bool,
+
+    /// Runs against this environment need an approval token (--approval), kept in the run log
+    #[serde(default)]
+    pub require_approval: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct WorkflowDefinition {
+    #[serde(default)]
+    pub description: Option<String>,
+
+    #[serde(default)]
+    pub steps: Vec<WorkflowStep>,
+
+    /// Upper bound on steps running at once when steps declare `needs`
+    #[serde(default)]
+    pub max_parallel: Option<usize>,
+}
+
+/// One plugin command invocation inside a workflow
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct WorkflowStep {
+    /// Name other steps use in `needs` (defaults to the `run` target)
+    #[serde(default)]
+    pub id: Option<String>,
+
+    /// The plugin command to run (e.g. "build:compile")
+    pub run: String,
+
+    /// Steps that must succeed before this one starts
+    #[serde(default)]
+    pub needs: Vec<String>,
+
+    /// Only run this step if an earlier step published this event
+    #[serde(default)]
+    pub on: Option<String>,
+
+    /// Arguments passed to the command
+    #[serde(default)]
+    pub args: HashMap<String, TomlValue>,
+
+    /// Names of workflow-level CLI args to forward to this step
+    #[serde(default)]
+    pub forward_args: Vec<String>,
+
+    /// Always run this step in dry-run mode
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Stop the step if it runs longer than this (seconds, or e.g. "90s", "10m")
+    #[serde(default)]
+    pub timeout: Option<TomlValue>,
+
+    /// Keep going if this step fails; the workflow still reports the failure
+    #[serde(default)]
+    pub continue_on_error: bool,
+
+    /// Run this step even after an earlier step failed (e.g. cleanup)
+    #[serde(default)]
+    pub always_run: bool,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+pub struct WorkspaceConfig {
+    /// Member project directories relative to the workspace root (empty = every nested project)
+    #[serde(default)]
+    pub members: Vec<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct RegistryConfig {
     pub sources: Vec<String>,
 }
 
+/// User-level defaults, read from the global config file and the top level of mis.toml
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct UserSettings {
+    #[serde(default)]
+    pub registry: Option<RegistryConfig>,
+
+    /// Deno executable to run plugins with (defaults to `deno` on PATH)
+    #[serde(default)]
+    pub deno_path: Option<String>,
+
+    /// Container engine for plugins that run in a container (defaults to `docker` on PATH)
+    #[serde(default)]
+    pub container_runtime: Option<String>,
+
+    #[serde(default)]
+    pub color: Option<ColorChoice>,
+
+    /// How messages are decorated (emoji, plain ASCII or no decoration)
+    #[serde(default)]
+    pub theme: Option<Theme>,
+
+    /// Bandwidth cap for registry downloads, in curl's syntax (e.g. "500K", "2M")
+    #[serde(default)]
+    pub download_limit: Option<String>,
+
+    /// Command that prints an access token for the registry host passed as its argument
+    #[serde(default)]
+    pub credential_helper: Option<String>,
+
+    /// Seconds a plugin gets to exit after Ctrl-C or SIGTERM is passed on, before it's killed
+    #[serde(default)]
+    pub grace_period: Option<u64>,
+
+    #[serde(default)]
+    pub permissions: Option<PermissionsPolicy>,
+
+    #[serde(default)]
+    pub security: Option<SecurityPolicy>,
+}
+
+/// Defaults applied before a plugin's own declared permissions
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct PermissionsPolicy {
+    /// Whether plugins get environment variable access unless they declare otherwise
+    #[serde(default)]
+    pub env_access: Option<bool>,
+
+    /// Extra programs project variables may run via `from_command` (beyond the built-in list)
+    #[serde(default)]
+    pub variable_commands: Option<Vec<String>>,
+}
+
+/// Tweaks to the built-in permission validation rules ([security] in mis.toml)
+#[derive(Debug, Deserialize, Clone, Default, PartialEq)]
+pub struct SecurityPolicy {
+    /// Commands no plugin may run, on top of the built-in list
+    #[serde(default)]
+    pub blocked_commands: Vec<String>,
+
+    /// Hosts plugins may be granted even where the built-in rules would block them
+    #[serde(default)]
+    pub allowed_network: Vec<String>,
+
+    /// Let plugins be granted private network addresses (10.x, 172.x, 192.168.x)
+    #[serde(default)]
+    pub allow_private_network: Option<bool>,
+
+    /// Grant private network addresses with a warning instead of blocking them
+    /// (also enabled for one run with `mis run --allow-insecure-net`)
+    #[serde(default)]
+    pub allow_insecure_network: Option<bool>,
+
+    /// Whether plugins get environment variable access unless they declare otherwise
+    /// (takes precedence over `[permissions] env_access`)
+    #[serde(default)]
+    pub env_access: Option<bool>,
+
+    /// Fail runs whose manifest requests anything validation blocks, instead of warning
+    /// (also enabled for one run with `mis run --strict-security`)
+    #[serde(default)]
+    pub strict: Option<bool>,
+
+    /// Hosts registries and dependencies may be fetched from over plain HTTP or on a private
+    /// network, e.g. an internal GitLab on a build network (`*.corp.example` for subdomains)
+    #[serde(default)]
+    pub trusted_domains: Vec<String>,
+
+    /// The config file each entry in `trusted_domains` came from (filled in by the loader)
+    #[serde(skip)]
+    pub trusted_domain_origins: HashMap<String, PathBuf>,
+
+    /// The only hosts `deno_dependencies` may come from, when set (`*.corp.example` for
+    /// subdomains); a project's list replaces the global one
+    #[serde(default)]
+    pub dependency_hosts: Option<Vec<String>>,
+
+    /// OSV-format vulnerability feed `mis audit deps` checks instead of OSV.dev: a path
+    /// relative to the project root, or an http(s) URL
+    #[serde(default)]
+    pub advisory_feed: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Theme {
+    /// Emoji and Unicode symbols
+    #[default]
+    Fancy,
+    /// Plain ASCII markers like [error] and ->
+    Ascii,
+    /// No decoration, for log aggregation systems
+    Minimal,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 pub struct EnvConfig {
     pub namespace: Option<String>,
@@ -45,6 +357,10 @@ pub struct SecurityPermissions {
     #[serde(default)]
     pub env_access: Option<bool>,
 
+    /// The only environment variables the plugin can see; also passed as `ctx.env`
+    #[serde(default)]
+    pub env_vars: Vec<String>,
+
     /// Network domains/IPs that can be accessed (including localhost if explicitly declared)
     #[serde(default)]
     pub network: Vec<String>,
@@ -56,27 +372,251 @@ pub struct SecurityPermissions {
 
 #[derive(Serialize)]
 pub struct ExecutionContext {
-    pub plugin_args: HashMap<String, TomlValue>,
+    pub schema_version: u32, // <-- layout of this JSON; bumped on breaking changes
+    pub plugin_args: BTreeMap<String, TomlValue>,
+    pub raw_args: Vec<String>, // <-- arguments after `--`, unparsed and in order
     pub manifest: JsonValue,          // <-- plugin manifest data
     pub config: JsonValue,            // <-- user-editable config
     pub project_variables: JsonValue, // <-- project-scoped variables
     pub project_root: String,
     pub meta: PluginMeta,
     pub dry_run: bool,
+    pub environment: Option<String>, // <-- profile selected with --env
+    pub events: serde_json::Map<String, JsonValue>, // <-- event name → payloads published earlier in the workflow
+    pub artifacts_dir: String, // <-- files written here can be uploaded with --upload-artifacts
+    pub state_dir: String, // <-- the plugin's own directory, kept between runs
+    pub capabilities: Capabilities, // <-- mis version, context schema and features this CLI supports
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<GitInfo>, // <-- only for plugins that opt in with [context] git = true
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>, // <-- values of the declared env_vars that are set
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub planning: bool, // <-- set by `mis run --plan`: return a plan instead of acting
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub plan: Option<PluginPlan>, // <-- the reviewed plan to carry out, with `mis run --apply-plan`
     // #[serde(skip_serializing)]
     // pub log: Option<()>, // ignored during serialization
 }
 
+/// Structured result a plugin can write to the file passed via `--result-file`
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct PluginRunResult {
+    /// Explicit success flag (None = rely on the exit code)
+    #[serde(default)]
+    pub success: Option<bool>,
+
+    /// Human-readable summary of what the plugin did
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// Non-fatal issues the user should know about
+    #[serde(default)]
+    pub warnings: Vec<String>,
+
+    /// Machine-readable outputs for scripts and other tooling
+    #[serde(default)]
+    pub outputs: serde_json::Map<String, JsonValue>,
+
+    /// Named events for later steps of the same workflow run
+    #[serde(default)]
+    pub events: Vec<PluginEvent>,
+
+    /// Files the plugin would write on a dry run, shown as diffs against what's on disk
+    #[serde(default)]
+    pub changes: Vec<PlannedChange>,
+
+    /// What the plugin will do, returned from `mis run --plan` and applied with `--apply-plan`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub plan: Option<PluginPlan>,
+}
+
+/// A reviewable plan: the actions shown to the user and the data the plugin
+/// needs to carry them out exactly, handed back as `ctx.plan` when it's applied
+#[derive(Debug, Deserialize, Serialize, Default, Clone, PartialEq)]
+pub struct PluginPlan {
+    #[serde(default)]
+    pub summary: Option<String>,
+
+    #[serde(default)]
+    pub actions: Vec<PlanAction>,
+
+    #[serde(default)]
+    pub data: JsonValue,
+}
+
+/// One step of a plan, e.g. "Scale web to 3 replicas"
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PlanAction {
+    pub description: String,
+
+    /// "create", "update" or "delete", shown as +, ~ or -
+    #[serde(default)]
+    pub kind: Option<String>,
+}
+
+/// A file change a plugin plans to make (`content: None` means the file would be deleted)
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PlannedChange {
+    pub path: String,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+/// An event a plugin publishes for other workflow steps (e.g. "image_published")
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct PluginEvent {
+    pub name: String,
+    #[serde(default)]
+    pub payload: JsonValue,
+}
+
 /// Plugin manifest (manifest.toml) - defines plugin structure and metadata
 #[derive(Debug, Deserialize, Serialize)]
 pub struct PluginManifest {
     pub plugin: PluginMeta,
     #[serde(default)]
-    pub commands: HashMap<String, PluginCommand>,
+    pub commands: IndexMap<String, PluginCommand>,
     #[serde(default)]
-    pub deno_dependencies: HashMap<String, String>,
+    pub deno_dependencies: IndexMap<String, String>,
     #[serde(default)]
     pub permissions: Option<SecurityPermissions>,
+    /// Documentation for the keys users can set in config.toml
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub config_schema: IndexMap<String, ConfigKeyDefinition>,
+    /// mis versions the plugin works with, e.g. ">=0.5" or ">=0.5, <0.7"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mis_version_requirement: Option<String>,
+    /// Minimum mis version and runtime features the plugin needs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requires: Option<PluginRequirements>,
+    /// Image and options for running the plugin in a container
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub container: Option<ContainerSettings>,
+    /// Optional extras the plugin wants in its ExecutionContext
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<ContextOptions>,
+    /// How the ExecutionContext reaches the plugin (a temp file unless set to `stdin`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context_transport: Option<ContextTransport>,
+}
+
+/// `context_transport` in manifest.toml
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ContextTransport {
+    /// A temp file passed as `--context-file`, leaving stdin to the terminal for prompts
+    #[default]
+    File,
+    /// Piped on the plugin's stdin and never written to disk; the plugin can't prompt
+    Stdin,
+}
+
+/// `[context]` in manifest.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct ContextOptions {
+    /// Add the project's branch, commit, tag and dirty state as `ctx.git`
+    #[serde(default)]
+    pub git: bool,
+}
+
+/// `[container]` in manifest.toml
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct ContainerSettings {
+    /// Image with Deno on its PATH (defaults to the official denoland/deno image)
+    #[serde(default)]
+    pub image: Option<String>,
+}
+
+/// Where a plugin's scripts run
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum Execution {
+    /// Directly on the host, sandboxed by Deno's permissions
+    #[default]
+    Deno,
+    /// Inside a container, with mounts and network derived from the same permissions
+    Docker,
+}
+
+/// What a plugin needs from the CLI running it (`[requires]` in manifest.toml)
+#[derive(Debug, Clone, Default, Deserialize, Serialize, PartialEq)]
+pub struct PluginRequirements {
+    /// Minimum mis version, e.g. "0.4.0", or a range like ">=0.5, <0.7"
+    #[serde(default)]
+    pub mis: Option<String>,
+
+    /// Runtime features the plugin relies on, e.g. ["result-file", "events"]
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+/// Describes one config.toml key (dotted keys like "database.host" address nested tables)
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct ConfigKeyDefinition {
+    #[serde(default)]
+    pub description: Option<String>,
+
+    #[serde(rename = "type", default)]
+    pub value_type: Option<ConfigValueType>,
+
+    #[serde(default)]
+    pub required: bool,
+
+    #[serde(default)]
+    pub default: Option<TomlValue>,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigValueType {
+    String,
+    Boolean,
+    Integer,
+    Float,
+    Array,
+    Table,
+}
+
+impl ConfigValueType {
+    /// Whether a config.toml value has this type (an integer also counts as a float)
+    pub fn matches(self, value: &TomlValue) -> bool {
+        match self {
+            Self::String => value.is_str(),
+            Self::Boolean => value.is_bool(),
+            Self::Integer => value.is_integer(),
+            Self::Float => value.is_float() || value.is_integer(),
+            Self::Array => value.is_array(),
+            Self::Table => value.is_table(),
+        }
+    }
+
+    /// "a string", "an integer", ...
+    pub fn with_article(self) -> &'static str {
+        match self {
+            Self::String => "a string",
+            Self::Boolean => "a boolean",
+            Self::Integer => "an integer",
+            Self::Float => "a float",
+            Self::Array => "an array",
+            Self::Table => "a table",
+        }
+    }
+}
+
+/// Known failure patterns (errors.toml) - maps plugin output to friendly explanations
+#[derive(Debug, Deserialize, Default, Clone)]
+pub struct PluginErrorHints {
+    #[serde(default, rename = "hint")]
+    pub hints: Vec<FailureHint>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+pub struct FailureHint {
+    /// Regex matched against the plugin's stdout and stderr
+    pub pattern: String,
+    pub explanation: String,
+    #[serde(default)]
+    pub remediation: Vec<String>,
 }
 
 /// User configuration (config.toml) - user-editable project-specific config
@@ -86,6 +626,27 @@ pub struct PluginUserConfig {
     pub config: HashMap<String, TomlValue>,
 }
 
+impl PluginUserConfig {
+    /// `[commands.<command>.variables]`: project variables overridden for one command
+    pub fn command_variables(&self, command_name: &str) -> Option<&toml::Table> {
+        self.config
+            .get("commands")?
+            .get(command_name)?
+            .get("variables")?
+            .as_table()
+    }
+
+    /// Look up a value by dotted key path (e.g. "database.host")
+    pub fn get_path(&self, key: &str) -> Option<&TomlValue> {
+        let mut parts = key.split('.');
+        let mut current = self.config.get(parts.next()?)?;
+        for part in parts {
+            current = current.as_table()?.get(part)?;
+        }
+        Some(current)
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct PluginMeta {
     pub name: String,
@@ -93,6 +654,12 @@ pub struct PluginMeta {
     pub version: String,
     #[serde(default)]
     pub registry: Option<String>,
+    /// Version `mis update` keeps the plugin at
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+    /// `execution = "docker"` runs the plugin in a container instead of on the host
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub execution: Option<Execution>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -111,15 +678,133 @@ pub struct PluginCommand {
     /// Command-specific security permissions (extends plugin permissions)
     #[serde(default)]
     pub permissions: Option<SecurityPermissions>,
+
+    /// Plugin commands to run around this one (shell hooks are only allowed in mis.toml)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<CommandHooks>,
+
+    /// Cases `mis test` runs against this command
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub test: Option<CommandTests>,
+
+    /// Hold the project lock (`.makeitso/.lock`) while running, so two runs can't overlap
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub exclusive: bool,
+    /// Question `mis run` asks before running the command, e.g. "This will deploy to PRODUCTION. Continue?"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<String>,
+
+    /// Marks the command as deprecated, with what to use instead, e.g. "Use deploy:v2 instead"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deprecated: Option<String>,
+}
+
+/// `[commands.<name>.test]` holds one case; `[[commands.<name>.test]]` several
+#[derive(Debug, Deserialize, Serialize, Clone)]
+#[serde(untagged)]
+pub enum CommandTests {
+    One(Box<PluginTestCase>),
+    Many(Vec<PluginTestCase>),
+}
+
+impl CommandTests {
+    pub fn into_cases(self) -> Vec<PluginTestCase> {
+        match self {
+            CommandTests::One(case) => vec![*case],
+            CommandTests::Many(cases) => cases,
+        }
+    }
+}
+
+/// A `mis test` case: the synthetic context a command runs with, and what its result must be
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct PluginTestCase {
+    /// Shown in test output; defaults to the command or test file name
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Command to run; required in tests/*.toml, implied under [commands.<name>.test]
+    #[serde(default)]
+    pub command: Option<String>,
+
+    /// Arguments, validated as if given on the command line
+    #[serde(default)]
+    pub args: toml::Table,
+
+    #[serde(default)]
+    pub dry_run: bool,
+
+    /// Passed as ctx.environment
+    #[serde(default)]
+    pub environment: Option<String>,
+
+    /// Keys that replace the same keys from the plugin's config.toml
+    #[serde(default)]
+    pub config: toml::Table,
+
+    /// Stand-ins for mis.toml's [project_variables]
+    #[serde(default)]
+    pub project_variables: toml::Table,
+
+    /// Event payloads from "earlier workflow steps", by event name (ctx.events)
+    #[serde(default)]
+    pub events: toml::Table,
+
+    /// Seconds before the run is stopped and the case fails (default 60)
+    #[serde(default)]
+    pub timeout: Option<u64>,
+
+    #[serde(default)]
+    pub expect: TestExpectation,
+}
+
+/// What a test case's run must produce
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct TestExpectation {
+    /// Whether the run should succeed
+    #[serde(default = "default_expect_success")]
+    pub success: bool,
+
+    /// Text the result message (or the error, for failures) must contain
+    #[serde(default)]
+    pub message: Option<String>,
+
+    /// Outputs that must be present with exactly these values
+    #[serde(default)]
+    pub outputs: toml::Table,
+
+    /// Names of events the run must publish
+    #[serde(default)]
+    pub events: Vec<String>,
+
+    /// Paths the run must plan changes to
+    #[serde(default)]
+    pub changes: Vec<String>,
+}
+
+impl Default for TestExpectation {
+    fn default() -> Self {
+        Self {
+            success: default_expect_success(),
+            message: None,
+            outputs: toml::Table::new(),
+            events: Vec::new(),
+            changes: Vec::new(),
+        }
+    }
+}
+
+fn default_expect_success() -> bool {
+    true
 }
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct CommandArgs {
     #[serde(default)]
-    pub required: HashMap<String, ArgDefinition>,
+    pub required: IndexMap<String, ArgDefinition>,
 
     #[serde(default)]
-    pub optional: HashMap<String, ArgDefinition>,
+    pub optional: IndexMap<String, ArgDefinition>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -131,6 +816,48 @@ pub struct ArgDefinition {
 
     #[serde(default)]
     pub default_value: Option<String>,
+
+    /// The only values accepted (required for `arg_type = "enum"`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allowed_values: Vec<String>,
+
+    /// Other names for the argument; single letters are short flags (`-e prod`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// Accept the argument more than once (`--tag a --tag b`); the plugin gets an array
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub multiple: bool,
+}
+
+impl ArgDefinition {
+    /// How the value appears in usage lines: `<staging|prod>` for choices, otherwise
+    /// `<value>`, followed by `...` if the argument can be repeated
+    pub fn value_placeholder(&self) -> String {
+        let repeat = if self.multiple { "..." } else { "" };
+        if self.allowed_values.is_empty() {
+            format!("<value>{}", repeat)
+        } else {
+            format!("<{}>{}", self.allowed_values.join("|"), repeat)
+        }
+    }
+
+    /// The aliases as they're typed, e.g. ", -e, --env", to follow the argument's name
+    pub fn alias_flags(&self) -> String {
+        self.aliases
+            .iter()
+            .map(|alias| format!(", {}", flag_name(alias)))
+            .collect()
+    }
+}
+
+/// `-e` for a single-letter name, `--env` otherwise
+pub fn flag_name(name: &str) -> String {
+    if name.chars().count() == 1 {
+        format!("-{}", name)
+    } else {
+        format!("--{}", name)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, Default)]
@@ -141,6 +868,8 @@ pub enum ArgType {
     Boolean,
     Integer,
     Float,
+    /// One of the argument's `allowed_values`
+    Enum,
 }
 
 impl ExecutionContext {
@@ -176,13 +905,24 @@ impl ExecutionContext {
         let project_vars_json: JsonValue = toml_to_json(TomlValue::Table(vars_table));
 
         Ok(Self {
-            plugin_args: args,
+            schema_version: CONTEXT_SCHEMA_VERSION,
+            plugin_args: args.into_iter().collect(),
+            raw_args: Vec::new(),
             manifest: manifest_json,
             config: user_config_json,
             project_variables: project_vars_json,
             project_root,
             meta,
             dry_run,
+            environment: None,
+            events: serde_json::Map::new(),
+            artifacts_dir: String::new(),
+            state_dir: String::new(),
+            capabilities: Capabilities::current(),
+            git: None,
+            env: None,
+            planning: false,
+            plan: None,
         })
     }
 }
@@ -192,7 +932,7 @@ impl ExecutionContext {
 struct ManifestData {
     pub plugin: PluginMeta,
     pub commands: Vec<String>, // Just command names, not full definitions
-    pub deno_dependencies: HashMap<String, String>,
+    pub deno_dependencies: IndexMap<String, String>,
     pub registry: Option<String>,
 }
 
@@ -676,4 +1416,145 @@ std = "https://deno.land/std@0.204.0/path/mod.ts"
         assert!(manifest.commands.contains_key("test"));
         assert!(!manifest.deno_dependencies.is_empty());
     }
+
+    #[test]
+    fn test_config_schema_parsing() {
+        let toml_with_schema = r#"
+[plugin]
+name = "db-plugin"
+version = "1.0.0"
+
+[commands.migrate]
+script = "./migrate.ts"
+
+[config_schema.database_url]
+description = "Postgres connection string"
+type = "string"
+required = true
+
+[config_schema."pool.size"]
+description = "Connection pool size"
+type = "integer"
+default = 5
+"#;
+
+        let manifest: PluginManifest = toml::from_str(toml_with_schema).unwrap();
+        assert_eq!(manifest.config_schema.len(), 2);
+
+        let url = &manifest.config_schema["database_url"];
+        assert!(url.required);
+        assert_eq!(url.value_type, Some(ConfigValueType::String));
+
+        let pool = &manifest.config_schema["pool.size"];
+        assert!(!pool.required);
+        assert_eq!(pool.default.as_ref().and_then(|v| v.as_integer()), Some(5));
+    }
+
+    #[test]
+    fn test_user_config_get_path() {
+        let config: PluginUserConfig = toml::from_str(
+            r#"
+message = "hi"
+
+[pool]
+size = 10
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.get_path("message").and_then(|v| v.as_str()), Some("hi"));
+        assert_eq!(config.get_path("pool.size").and_then(|v| v.as_integer()), Some(10));
+        assert!(config.get_path("pool.timeout").is_none());
+        assert!(config.get_path("message.nested").is_none());
+    }
+
+    #[test]
+    fn test_manifest_keeps_declaration_order() {
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+[plugin]
+name = "k8s"
+version = "1.0.0"
+
+[commands.status]
+script = "./status.ts"
+
+[commands.apply]
+script = "./apply.ts"
+
+[commands.apply.args.required]
+namespace = { description = "Namespace" }
+cluster = { description = "Cluster" }
+
+[commands.apply.args.optional]
+wait = { description = "Wait for rollout", arg_type = "boolean" }
+context = { description = "kubectl context" }
+
+[config_schema.timeout]
+description = "Rollout timeout"
+
+[config_schema.kubeconfig]
+description = "Path to kubeconfig"
+"#,
+        )
+        .unwrap();
+
+        let commands: Vec<_> = manifest.commands.keys().collect();
+        assert_eq!(commands, ["status", "apply"]);
+        let args = manifest.commands["apply"].args.as_ref().unwrap();
+        assert_eq!(args.required.keys().collect::<Vec<_>>(), ["namespace", "cluster"]);
+        assert_eq!(args.optional.keys().collect::<Vec<_>>(), ["wait", "context"]);
+        let keys: Vec<_> = manifest.config_schema.keys().collect();
+        assert_eq!(keys, ["timeout", "kubeconfig"]);
+    }
+
+    #[test]
+    fn test_manifest_context_transport() {
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+context_transport = "stdin"
+
+[plugin]
+name = "vault"
+version = "1.0.0"
+"#,
+        )
+        .unwrap();
+        assert_eq!(manifest.context_transport, Some(ContextTransport::Stdin));
+
+        let manifest: PluginManifest =
+            toml::from_str("[plugin]\nname = \"vault\"\nversion = \"1.0.0\"\n").unwrap();
+        assert_eq!(manifest.context_transport, None);
+
+        let invalid: Result<PluginManifest, _> = toml::from_str(
+            "context_transport = \"pipe\"\n[plugin]\nname = \"vault\"\nversion = \"1.0.0\"\n",
+        );
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_execution_context_json_is_stable() {
+        let manifest: PluginManifest =
+            toml::from_str("[plugin]\nname = \"k8s\"\nversion = \"1.0.0\"\n").unwrap();
+        let args: HashMap<String, TomlValue> = ["zone", "app", "mode", "build"]
+            .iter()
+            .map(|name| (name.to_string(), TomlValue::String(name.to_string())))
+            .collect();
+
+        let ctx = ExecutionContext::from_parts(
+            args,
+            &manifest,
+            &PluginUserConfig::default(),
+            HashMap::new(),
+            "/project".to_string(),
+            manifest.plugin.clone(),
+            false,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&ctx.plugin_args).unwrap();
+        assert_eq!(
+            json,
+            r#"{"app":"app","build":"build","mode":"mode","zone":"zone"}"#
+        );
+    }
 }
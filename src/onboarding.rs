@@ -0,0 +1,196 @@
+use std::path::{Path, PathBuf};
+
+/// A tech stack recognised from marker files near the current directory
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectStack {
+    Node,
+    Deno,
+    Rust,
+    Python,
+    Go,
+    Git,
+}
+
+impl ProjectStack {
+    /// All stacks in detection priority order (most specific first)
+    const ALL: [ProjectStack; 6] = [
+        ProjectStack::Node,
+        ProjectStack::Deno,
+        ProjectStack::Rust,
+        ProjectStack::Python,
+        ProjectStack::Go,
+        ProjectStack::Git,
+    ];
+
+    /// Files or directories whose presence indicates this stack
+    fn markers(&self) -> &'static [&'static str] {
+        match self {
+            ProjectStack::Node => &["package.json"],
+            ProjectStack::Deno => &["deno.json", "deno.jsonc"],
+            ProjectStack::Rust => &["Cargo.toml"],
+            ProjectStack::Python => &["pyproject.toml", "requirements.txt", "setup.py"],
+            ProjectStack::Go => &["go.mod"],
+            ProjectStack::Git => &[".git"],
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            ProjectStack::Node => "Node.js",
+            ProjectStack::Deno => "Deno",
+            ProjectStack::Rust => "Rust",
+            ProjectStack::Python => "Python",
+            ProjectStack::Go => "Go",
+            ProjectStack::Git => "git",
+        }
+    }
+
+    /// Suggested name for a starter plugin that fits this stack
+    pub fn starter_plugin(&self) -> &'static str {
+        match self {
+            ProjectStack::Node => "npm-scripts",
+            ProjectStack::Deno => "deno-tasks",
+            ProjectStack::Rust => "cargo-tasks",
+            ProjectStack::Python => "py-tasks",
+            ProjectStack::Go => "go-tasks",
+            ProjectStack::Git => "git-helpers",
+        }
+    }
+}
+
+/// A directory that looks like the root of a project that hasn't run `mis init` yet
+#[derive(Debug)]
+pub struct DetectedProject {
+    pub root: PathBuf,
+    pub stacks: Vec<ProjectStack>,
+}
+
+impl DetectedProject {
+    /// The stack used to pick a starter plugin suggestion
+    pub fn primary_stack(&self) -> ProjectStack {
+        self.stacks[0]
+    }
+}
+
+/// Detect which stacks are present directly inside `dir`
+pub fn detect_stacks_in(dir: &Path) -> Vec<ProjectStack> {
+    ProjectStack::ALL
+        .iter()
+        .copied()
+        .filter(|stack| stack.markers().iter().any(|m| dir.join(m).exists()))
+        .collect()
+}
+
+/// Walk up from `start` and return the nearest directory with any project indicators
+pub fn detect_project(start: &Path) -> Option<DetectedProject> {
+    let mut current = start.to_path_buf();
+
+    loop {
+        let stacks = detect_stacks_in(&current);
+        if !stacks.is_empty() {
+            return Some(DetectedProject {
+                root: current,
+                stacks,
+            });
+        }
+
+        if !current.pop() {
+            return None;
+        }
+    }
+}
+
+/// Build a friendly message explaining how to get started from `cwd`
+pub fn not_in_project_hint(cwd: &Path) -> String {
+    let mut hint = String::from("🛑 You're not inside a Make It So project.");
+
+    match detect_project(cwd) {
+        Some(detected) => {
+            let labels: Vec<&str> = detected.stacks.iter().map(|s| s.label()).collect();
+            hint.push_str(&format!(
+                "\n→ Detected a {} project at {}",
+                labels.join(" + "),
+                detected.root.display()
+            ));
+
+            if detected.root != cwd {
+                hint.push_str(&format!(
+                    "\n→ Get started with: cd {} && mis init",
+                    detected.root.display()
+                ));
+            } else {
+                hint.push_str("\n→ Get started with: mis init");
+            }
+
+            hint.push_str(&format!(
+                "\n→ Then scaffold a starter plugin: mis create {}",
+                detected.primary_stack().starter_plugin()
+            ));
+        }
+        None => {
+            hint.push_str(
+                "\n→ Make sure you're in the project root (where .makeitso/ lives).\n\
+                 → If you haven't set it up yet, run `mis init`.",
+            );
+        }
+    }
+
+    hint
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_detect_stacks_in_empty_directory() {
+        let temp_dir = tempdir().unwrap();
+        assert!(detect_stacks_in(temp_dir.path()).is_empty());
+    }
+
+    #[test]
+    fn test_detect_stacks_in_orders_most_specific_first() {
+        let temp_dir = tempdir().unwrap();
+        fs::create_dir_all(temp_dir.path().join(".git")).unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+
+        let stacks = detect_stacks_in(temp_dir.path());
+        assert_eq!(stacks, vec![ProjectStack::Rust, ProjectStack::Git]);
+    }
+
+    #[test]
+    fn test_detect_project_walks_up_to_nearest_indicator() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+        let nested = temp_dir.path().join("src/components");
+        fs::create_dir_all(&nested).unwrap();
+
+        let detected = detect_project(&nested).unwrap();
+        assert_eq!(detected.root, temp_dir.path());
+        assert_eq!(detected.primary_stack(), ProjectStack::Node);
+    }
+
+    #[test]
+    fn test_not_in_project_hint_suggests_starter_plugin() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("Cargo.toml"), "[package]").unwrap();
+
+        let hint = not_in_project_hint(temp_dir.path());
+        assert!(hint.contains("Detected a Rust project"));
+        assert!(hint.contains("Get started with: mis init"));
+        assert!(hint.contains("mis create cargo-tasks"));
+    }
+
+    #[test]
+    fn test_not_in_project_hint_suggests_cd_for_parent_root() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(temp_dir.path().join("go.mod"), "module x").unwrap();
+        let nested = temp_dir.path().join("cmd");
+        fs::create_dir_all(&nested).unwrap();
+
+        let hint = not_in_project_hint(&nested);
+        assert!(hint.contains(&format!("cd {} && mis init", temp_dir.path().display())));
+    }
+}
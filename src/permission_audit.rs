@@ -0,0 +1,298 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{PluginManifest, SecurityPermissions};
+
+/// Environment variable Deno reads to log every permission access as JSON lines (Deno 2.4+)
+pub const DENO_AUDIT_ENV_VAR: &str = "DENO_AUDIT_PERMISSIONS";
+
+/// Every permission a plugin command actually exercised during one run
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct PermissionReport {
+    pub plugin: String,
+    pub command: String,
+
+    /// Permission kind (read, write, net, env, run, ...) → values accessed
+    pub exercised: BTreeMap<String, BTreeSet<String>>,
+
+    /// Grants declared in manifest.toml that the run never used
+    pub unused: BTreeMap<String, BTreeSet<String>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct AuditEntry {
+    permission: String,
+    #[serde(default)]
+    value: Option<String>,
+}
+
+/// Parse Deno's permission audit log, skipping lines it can't understand
+pub fn parse_audit_log(contents: &str) -> BTreeMap<String, BTreeSet<String>> {
+    let mut exercised: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+
+    for line in contents.lines().filter(|l| !l.trim().is_empty()) {
+        let Ok(entry) = serde_json::from_str::<AuditEntry>(line) else {
+            continue;
+        };
+        exercised
+            .entry(entry.permission)
+            .or_default()
+            .insert(entry.value.unwrap_or_else(|| "*".to_string()));
+    }
+
+    exercised
+}
+
+impl PermissionReport {
+    pub fn new(
+        plugin_manifest: &PluginManifest,
+        command_name: &str,
+        exercised: BTreeMap<String, BTreeSet<String>>,
+    ) -> Self {
+        let declared = declared_permissions(plugin_manifest, command_name);
+        let unused = unused_grants(&declared, &exercised);
+
+        Self {
+            plugin: plugin_manifest.plugin.name.clone(),
+            command: command_name.to_string(),
+            exercised,
+            unused,
+        }
+    }
+
+    pub fn print(&self) {
+        outln!(
+            "\n🔍 Permissions exercised by {}:{}",
+            self.plugin,
+            self.command
+        );
+        if self.exercised.is_empty() {
+            outln!("   None recorded.");
+            outln!("→ Permission tracing needs Deno 2.4 or newer.");
+        }
+        for (kind, values) in &self.exercised {
+            let values: Vec<&str> = values.iter().map(String::as_str).collect();
+            outln!("   {}: {}", kind, values.join(", "));
+        }
+
+        if !self.unused.is_empty() {
+            outln!("\n💡 Declared in manifest.toml but never used:");
+            for (kind, values) in &self.unused {
+                let values: Vec<&str> = values.iter().map(String::as_str).collect();
+                outln!("   {}: {}", kind, values.join(", "));
+            }
+            outln!("→ Consider removing them so the plugin runs with least privilege.");
+        }
+    }
+
+    /// Save the report under `.makeitso/cache/permissions/<plugin>/<command>.json`
+    pub fn save(&self, project_root: &Path) -> Result<PathBuf> {
+        let dir = project_root
+            .join(".makeitso")
+            .join("cache")
+            .join("permissions")
+            .join(&self.plugin);
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+
+        let path = dir.join(format!("{}.json", self.command));
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write permission report: {}", path.display()))?;
+
+        Ok(path)
+    }
+}
+
+/// Merge plugin-level and command-level permissions from the manifest
+fn declared_permissions(
+    plugin_manifest: &PluginManifest,
+    command_name: &str,
+) -> SecurityPermissions {
+    let mut declared = plugin_manifest.permissions.clone().unwrap_or_default();
+
+    if let Some(command_perms) = plugin_manifest
+        .commands
+        .get(command_name)
+        .and_then(|command| command.permissions.as_ref())
+    {
+        declared
+            .file_read
+            .extend(command_perms.file_read.iter().cloned());
+        declared
+            .file_write
+            .extend(command_perms.file_write.iter().cloned());
+        declared
+            .network
+            .extend(command_perms.network.iter().cloned());
+        declared
+            .run_commands
+            .extend(command_perms.run_commands.iter().cloned());
+        declared
+            .env_vars
+            .extend(command_perms.env_vars.iter().cloned());
+        if command_perms.env_access.is_some() {
+            declared.env_access = command_perms.env_access;
+        }
+    }
+
+    declared
+}
+
+/// Compare network, run and env grants against what was exercised.
+///
+/// File grants are directory prefixes resolved by Deno, so they aren't compared here.
+fn unused_grants(
+    declared: &SecurityPermissions,
+    exercised: &BTreeMap<String, BTreeSet<String>>,
+) -> BTreeMap<String, BTreeSet<String>> {
+    let mut unused: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+    let used = |kind: &str| exercised.get(kind).cloned().unwrap_or_default();
+
+    let used_hosts: BTreeSet<String> = used("net")
+        .iter()
+        .map(|value| strip_port(value).to_string())
+        .collect();
+    for domain in &declared.network {
+        if !used_hosts.contains(strip_port(domain)) && !used_hosts.contains("*") {
+            unused
+                .entry("network".to_string())
+                .or_default()
+                .insert(domain.clone());
+        }
+    }
+
+    let used_commands: BTreeSet<String> = used("run")
+        .iter()
+        .map(|value| command_name(value).to_string())
+        .collect();
+    for command in &declared.run_commands {
+        if !used_commands.contains(command_name(command)) {
+            unused
+                .entry("run_commands".to_string())
+                .or_default()
+                .insert(command.clone());
+        }
+    }
+
+    let used_vars = used("env");
+    for name in &declared.env_vars {
+        if !used_vars.contains(name) && !used_vars.contains("*") {
+            unused
+                .entry("env_vars".to_string())
+                .or_default()
+                .insert(name.clone());
+        }
+    }
+
+    if declared.env_access == Some(true) && !exercised.contains_key("env") {
+        unused
+            .entry("env_access".to_string())
+            .or_default()
+            .insert("true".to_string());
+    }
+
+    unused
+}
+
+fn strip_port(host: &str) -> &str {
+    match host.rsplit_once(':') {
+        Some((name, port)) if port.chars().all(|c| c.is_ascii_digit()) => name,
+        _ => host,
+    }
+}
+
+fn command_name(command: &str) -> &str {
+    Path::new(command)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(command)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn manifest_with_permissions(permissions: &str) -> PluginManifest {
+        toml::from_str(&format!(
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[commands.apply]
+script = "./apply.ts"
+
+[permissions]
+{}
+"#,
+            permissions
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_parse_audit_log_groups_by_permission() {
+        let log = r#"{"v":1,"datetime":"2025-06-01T00:00:00Z","permission":"read","value":"./package.json"}
+{"v":1,"datetime":"2025-06-01T00:00:00Z","permission":"net","value":"api.example.com:443"}
+{"v":1,"datetime":"2025-06-01T00:00:00Z","permission":"read","value":"./package.json"}
+not json
+{"v":1,"datetime":"2025-06-01T00:00:00Z","permission":"env","value":null}
+"#;
+        let exercised = parse_audit_log(log);
+        assert_eq!(exercised["read"].len(), 1);
+        assert!(exercised["net"].contains("api.example.com:443"));
+        assert!(exercised["env"].contains("*"));
+    }
+
+    #[test]
+    fn test_report_flags_unused_grants() {
+        let manifest = manifest_with_permissions(
+            r#"network = ["api.example.com", "unused.example.com"]
+run_commands = ["git", "docker"]
+env_access = true"#,
+        );
+        let exercised = parse_audit_log(
+            r#"{"permission":"net","value":"api.example.com:443"}
+{"permission":"run","value":"/usr/bin/git"}"#,
+        );
+
+        let report = PermissionReport::new(&manifest, "apply", exercised);
+        assert_eq!(report.plugin, "deploy");
+        assert!(report.unused["network"].contains("unused.example.com"));
+        assert!(!report.unused["network"].contains("api.example.com"));
+        assert_eq!(report.unused["run_commands"].len(), 1);
+        assert!(report.unused["run_commands"].contains("docker"));
+        assert!(report.unused.contains_key("env_access"));
+    }
+
+    #[test]
+    fn test_report_flags_unused_env_vars() {
+        let manifest = manifest_with_permissions(r#"env_vars = ["AWS_PROFILE", "CI"]"#);
+        let exercised = parse_audit_log(r#"{"permission":"env","value":"CI"}"#);
+
+        let report = PermissionReport::new(&manifest, "apply", exercised);
+        assert_eq!(
+            report.unused["env_vars"],
+            BTreeSet::from(["AWS_PROFILE".to_string()])
+        );
+    }
+
+    #[test]
+    fn test_report_save_writes_json() {
+        let temp_dir = tempdir().unwrap();
+        let manifest = manifest_with_permissions("");
+        let report = PermissionReport::new(&manifest, "apply", BTreeMap::new());
+
+        let path = report.save(temp_dir.path()).unwrap();
+        assert!(path.ends_with(".makeitso/cache/permissions/deploy/apply.json"));
+
+        let saved: PermissionReport =
+            serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+        assert_eq!(saved, report);
+    }
+}
@@ -0,0 +1,332 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::models::{PlannedChange, PluginManifest, PluginPlan};
+use crate::plugin_lock::{compare_checksums, hash_plugin_dir};
+use crate::run_log::format_utc;
+
+/// Bumped when the saved plan layout changes incompatibly
+const PLAN_FORMAT: u32 = 2;
+
+/// A plan from `mis run --plan`, saved for review and applied with `mis run --apply-plan`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SavedPlan {
+    pub format: u32,
+    /// When the plan was made, as UTC `YYYY-MM-DDTHH:MM:SSZ`
+    pub created: String,
+    pub plugin: String,
+    pub command: String,
+    pub plugin_version: String,
+    /// Arguments exactly as given to `mis run --plan`; the apply run gets the same ones
+    pub args: BTreeMap<String, Vec<String>>,
+    /// Arguments given after `--`, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub raw_args: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    /// Checksums of the plugin's files, so a plan is never applied by different code
+    pub plugin_files: BTreeMap<String, String>,
+    pub plan: PluginPlan,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<PlannedChange>,
+}
+
+impl SavedPlan {
+    pub fn new(
+        manifest: &PluginManifest,
+        command: &str,
+        plugin_dir: &Path,
+        args: &HashMap<String, Vec<String>>,
+        environment: Option<&str>,
+        plan: PluginPlan,
+        changes: Vec<PlannedChange>,
+    ) -> Result<Self> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        Ok(Self {
+            format: PLAN_FORMAT,
+            created: format_utc(now),
+            plugin: manifest.plugin.name.clone(),
+            command: command.to_string(),
+            plugin_version: manifest.plugin.version.clone(),
+            args: args.iter().map(|(k, v)| (k.clone(), v.clone())).collect(),
+            raw_args: Vec::new(),
+            environment: environment.map(str::to_string),
+            plugin_files: hash_plugin_dir(plugin_dir)?,
+            plan,
+            changes,
+        })
+    }
+
+    pub fn target(&self) -> String {
+        format!("{}:{}", self.plugin, self.command)
+    }
+
+    /// Where `mis run --plan` saves a plan when no `--plan-out` is given
+    pub fn default_path(&self, project_root: &Path) -> PathBuf {
+        let stamp: String = self.created.chars().filter(char::is_ascii_digit).collect();
+        project_root
+            .join(".makeitso")
+            .join("plans")
+            .join(format!("{}-{}-{}.json", self.plugin, self.command, stamp))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        fs::write(path, format!("{}\n", serde_json::to_string_pretty(self)?))
+            .with_context(|| format!("🛑 Failed to write plan: {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("🛑 Failed to read plan: {}", path.display()))?;
+        let plan: Self = serde_json::from_str(&contents).with_context(|| {
+            format!(
+                "🛑 {} is not a plan saved by `mis run --plan`.",
+                path.display()
+            )
+        })?;
+        if plan.format != PLAN_FORMAT {
+            anyhow::bail!(
+                "🛑 {} was saved in plan format {}, but this mis reads format {}.\n\
+                 → Make the plan again with `mis run {} --plan`.",
+                path.display(),
+                plan.format,
+                PLAN_FORMAT,
+                plan.target()
+            );
+        }
+        Ok(plan)
+    }
+
+    /// Make sure `mis run --apply-plan` wasn't also given a different target, arguments
+    /// or environment, since the plan runs exactly as it was made
+    pub fn check_invocation(
+        &self,
+        target: Option<&str>,
+        args: &[String],
+        environment: Option<&str>,
+    ) -> Result<()> {
+        if let Some(target) = target
+            && target != self.target()
+        {
+            anyhow::bail!("🛑 This plan is for {}, not {}.", self.target(), target);
+        }
+        if !args.is_empty() {
+            anyhow::bail!(
+                "🛑 --apply-plan runs with the arguments the plan was made with.\n\
+                 → Drop the extra arguments, or make a new plan with them."
+            );
+        }
+        if environment != self.environment.as_deref() {
+            match &self.environment {
+                Some(env) => anyhow::bail!(
+                    "🛑 This plan was made for environment '{}'.\n→ Apply it with --env {}.",
+                    env,
+                    env
+                ),
+                None => anyhow::bail!(
+                    "🛑 This plan was made without --env.\n\
+                     → Apply it without --env, or make a new plan for that environment."
+                ),
+            }
+        }
+        Ok(())
+    }
+
+    /// Refuse to apply the plan if the plugin changed since it was made
+    pub fn check_plugin_unchanged(&self, plugin_version: &str, plugin_dir: &Path) -> Result<()> {
+        let drift = compare_checksums(&self.plugin_files, &hash_plugin_dir(plugin_dir)?);
+        if self.plugin_version == plugin_version && drift.is_clean() {
+            return Ok(());
+        }
+
+        let mut changed = Vec::new();
+        if self.plugin_version != plugin_version {
+            changed.push(format!(
+                "version {} → {}",
+                self.plugin_version, plugin_version
+            ));
+        }
+        changed.extend(drift.modified.iter().map(|f| format!("~ {}", f)));
+        changed.extend(drift.missing.iter().map(|f| format!("- {}", f)));
+        changed.extend(drift.added.iter().map(|f| format!("+ {}", f)));
+        anyhow::bail!(
+            "🛑 Plugin '{}' changed since this plan was made ({}).\n\
+             → Make a new plan with `mis run {} --plan` and review it.",
+            self.plugin,
+            changed.join(", "),
+            self.target()
+        );
+    }
+
+    /// Show what the plan will do
+    pub fn print(&self) {
+        outln!("📋 Plan for {}", self.target());
+        if let Some(summary) = &self.plan.summary {
+            outln!("   {}", summary);
+        }
+        for action in &self.plan.actions {
+            let marker = match action.kind.as_deref() {
+                Some("create") => "+",
+                Some("delete") => "-",
+                Some("update") => "~",
+                _ => "•",
+            };
+            outln!("   {} {}", marker, action.description);
+        }
+        if self.plan.actions.is_empty() {
+            outln!("   No changes.");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use indexmap::IndexMap;
+    use crate::models::{PlanAction, PluginMeta};
+    use tempfile::tempdir;
+
+    fn manifest(version: &str) -> PluginManifest {
+        PluginManifest {
+            plugin: PluginMeta {
+                name: "deploy".to_string(),
+                description: None,
+                version: version.to_string(),
+                registry: None,
+                pin: None,
+                execution: None,
+            },
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
+            permissions: None,
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
+        }
+    }
+
+    fn plan() -> PluginPlan {
+        PluginPlan {
+            summary: Some("Roll out web to production".to_string()),
+            actions: vec![PlanAction {
+                description: "Scale web to 3 replicas".to_string(),
+                kind: Some("update".to_string()),
+            }],
+            data: serde_json::json!({ "replicas": 3 }),
+        }
+    }
+
+    #[test]
+    fn test_saved_plan_round_trips() {
+        let temp_dir = tempdir().unwrap();
+        let plugin_dir = temp_dir.path().join("deploy");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(plugin_dir.join("deploy.ts"), "console.log('hi')").unwrap();
+        let args = HashMap::from([("replicas".to_string(), vec!["3".to_string()])]);
+
+        let saved = SavedPlan::new(
+            &manifest("1.0.0"),
+            "prod",
+            &plugin_dir,
+            &args,
+            Some("production"),
+            plan(),
+            Vec::new(),
+        )
+        .unwrap();
+        let path = saved.default_path(temp_dir.path());
+        assert!(path.starts_with(temp_dir.path().join(".makeitso").join("plans")));
+        saved.save(&path).unwrap();
+
+        let loaded = SavedPlan::load(&path).unwrap();
+        assert_eq!(loaded, saved);
+        assert_eq!(loaded.args["replicas"], vec!["3"]);
+        assert_eq!(loaded.plan.data["replicas"], 3);
+
+        fs::write(&path, "{}").unwrap();
+        assert!(SavedPlan::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_check_invocation() {
+        let temp_dir = tempdir().unwrap();
+        let saved = SavedPlan::new(
+            &manifest("1.0.0"),
+            "prod",
+            temp_dir.path(),
+            &HashMap::new(),
+            Some("production"),
+            plan(),
+            Vec::new(),
+        )
+        .unwrap();
+
+        assert!(
+            saved
+                .check_invocation(None, &[], Some("production"))
+                .is_ok()
+        );
+        assert!(
+            saved
+                .check_invocation(Some("deploy:prod"), &[], Some("production"))
+                .is_ok()
+        );
+        assert!(
+            saved
+                .check_invocation(Some("deploy:staging"), &[], Some("production"))
+                .is_err()
+        );
+        assert!(
+            saved
+                .check_invocation(None, &["--replicas=5".to_string()], Some("production"))
+                .is_err()
+        );
+        let err = saved.check_invocation(None, &[], None).unwrap_err();
+        assert!(err.to_string().contains("--env production"), "{}", err);
+    }
+
+    #[test]
+    fn test_check_plugin_unchanged() {
+        let temp_dir = tempdir().unwrap();
+        let plugin_dir = temp_dir.path();
+        fs::write(plugin_dir.join("deploy.ts"), "v1").unwrap();
+        let saved = SavedPlan::new(
+            &manifest("1.0.0"),
+            "prod",
+            plugin_dir,
+            &HashMap::new(),
+            None,
+            plan(),
+            Vec::new(),
+        )
+        .unwrap();
+
+        assert!(saved.check_plugin_unchanged("1.0.0", plugin_dir).is_ok());
+
+        let err = saved
+            .check_plugin_unchanged("1.1.0", plugin_dir)
+            .unwrap_err();
+        assert!(err.to_string().contains("version 1.0.0 → 1.1.0"), "{}", err);
+
+        fs::write(plugin_dir.join("deploy.ts"), "v2").unwrap();
+        let err = saved
+            .check_plugin_unchanged("1.0.0", plugin_dir)
+            .unwrap_err();
+        assert!(err.to_string().contains("~ deploy.ts"), "{}", err);
+    }
+}
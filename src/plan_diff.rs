@@ -0,0 +1,231 @@
+use std::fs;
+use std::path::{Component, Path};
+
+use anyhow::Result;
+use serde::Serialize;
+use similar::TextDiff;
+
+use crate::cli::DiffFormat;
+use crate::models::PlannedChange;
+use crate::theme::{Stream, paint, use_color};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Create,
+    Modify,
+    Delete,
+    Unchanged,
+}
+
+/// A planned change rendered as a unified diff against the file currently on disk
+#[derive(Debug, Serialize, PartialEq)]
+pub struct FileDiff {
+    pub path: String,
+    pub kind: ChangeKind,
+    pub patch: String,
+}
+
+/// Diff each planned change against the project's files.
+///
+/// Paths must stay inside the project; anything else is reported and skipped.
+pub fn diff_planned_changes(project_root: &Path, changes: &[PlannedChange]) -> Vec<FileDiff> {
+    let mut diffs = Vec::with_capacity(changes.len());
+
+    for change in changes {
+        if !is_project_relative(&change.path) {
+            errln!(
+                "⚠️  Warning: Ignoring planned change to '{}': paths must be relative to the project root.",
+                change.path
+            );
+            continue;
+        }
+
+        let current = fs::read_to_string(project_root.join(&change.path)).ok();
+        let kind = match (&current, &change.content) {
+            (None, Some(_)) => ChangeKind::Create,
+            (Some(_), None) => ChangeKind::Delete,
+            (Some(old), Some(new)) if old != new => ChangeKind::Modify,
+            _ => ChangeKind::Unchanged,
+        };
+
+        let patch = if kind == ChangeKind::Unchanged {
+            String::new()
+        } else {
+            let old_header = match current {
+                Some(_) => format!("a/{}", change.path),
+                None => "/dev/null".to_string(),
+            };
+            let new_header = match change.content {
+                Some(_) => format!("b/{}", change.path),
+                None => "/dev/null".to_string(),
+            };
+            TextDiff::from_lines(
+                current.as_deref().unwrap_or_default(),
+                change.content.as_deref().unwrap_or_default(),
+            )
+            .unified_diff()
+            .context_radius(3)
+            .header(&old_header, &new_header)
+            .to_string()
+        };
+
+        diffs.push(FileDiff {
+            path: change.path.clone(),
+            kind,
+            patch,
+        });
+    }
+
+    diffs
+}
+
+/// Print planned changes in the requested format
+pub fn render_diffs(diffs: &[FileDiff], format: DiffFormat) -> Result<()> {
+    match format {
+        DiffFormat::Json => println!("{}", serde_json::to_string_pretty(diffs)?),
+        DiffFormat::Patch => {
+            for diff in diffs {
+                print!("{}", diff.patch);
+            }
+        }
+        DiffFormat::Pretty => {
+            let color = use_color(Stream::Stdout);
+            outln!("📝 Planned changes:");
+            for diff in diffs {
+                let label = match diff.kind {
+                    ChangeKind::Create => "create",
+                    ChangeKind::Modify => "modify",
+                    ChangeKind::Delete => "delete",
+                    ChangeKind::Unchanged => "unchanged",
+                };
+                outln!("\n   {} ({})", diff.path, label);
+                for line in diff.patch.lines() {
+                    outln!("   {}", colorize(line, color));
+                }
+            }
+
+            let count = |kind: ChangeKind| diffs.iter().filter(|d| d.kind == kind).count();
+            outln!(
+                "\nPlan: {} to create, {} to modify, {} to delete.",
+                count(ChangeKind::Create),
+                count(ChangeKind::Modify),
+                count(ChangeKind::Delete)
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn is_project_relative(path: &str) -> bool {
+    !path.is_empty()
+        && Path::new(path)
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+/// Color a unified diff line the way `git diff` does
+fn colorize(line: &str, color: bool) -> String {
+    if !color {
+        return line.to_string();
+    }
+
+    let code = if line.starts_with("+++") || line.starts_with("---") {
+        "1"
+    } else if line.starts_with('+') {
+        "32"
+    } else if line.starts_with('-') {
+        "31"
+    } else if line.starts_with("@@") {
+        "36"
+    } else {
+        return line.to_string();
+    };
+    paint(code, line)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn change(path: &str, content: Option<&str>) -> PlannedChange {
+        PlannedChange {
+            path: path.to_string(),
+            content: content.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_diff_planned_changes_classifies_changes() {
+        let temp_dir = tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join("app.toml"),
+            "port = 80\nhost = \"a\"\n",
+        )
+        .unwrap();
+        fs::write(temp_dir.path().join("old.txt"), "bye\n").unwrap();
+        fs::write(temp_dir.path().join("same.txt"), "same\n").unwrap();
+
+        let diffs = diff_planned_changes(
+            temp_dir.path(),
+            &[
+                change("app.toml", Some("port = 8080\nhost = \"a\"\n")),
+                change("new.txt", Some("hello\n")),
+                change("old.txt", None),
+                change("same.txt", Some("same\n")),
+            ],
+        );
+
+        let kinds: Vec<ChangeKind> = diffs.iter().map(|d| d.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                ChangeKind::Modify,
+                ChangeKind::Create,
+                ChangeKind::Delete,
+                ChangeKind::Unchanged
+            ]
+        );
+
+        assert!(
+            diffs[0]
+                .patch
+                .starts_with("--- a/app.toml\n+++ b/app.toml\n@@")
+        );
+        assert!(
+            diffs[0]
+                .patch
+                .contains("-port = 80\n+port = 8080\n host = \"a\"\n")
+        );
+        assert!(diffs[1].patch.starts_with("--- /dev/null\n+++ b/new.txt\n"));
+        assert!(diffs[2].patch.contains("+++ /dev/null\n"));
+        assert!(diffs[3].patch.is_empty());
+    }
+
+    #[test]
+    fn test_diff_planned_changes_skips_paths_outside_project() {
+        let temp_dir = tempdir().unwrap();
+        let diffs = diff_planned_changes(
+            temp_dir.path(),
+            &[
+                change("../secrets.txt", Some("x")),
+                change("/etc/passwd", Some("x")),
+                change("", Some("x")),
+                change("./src/main.ts", Some("x")),
+            ],
+        );
+        assert_eq!(diffs.len(), 1);
+        assert_eq!(diffs[0].path, "./src/main.ts");
+    }
+
+    #[test]
+    fn test_colorize_matches_git_colors() {
+        assert_eq!(colorize("+added", true), "\x1b[32m+added\x1b[0m");
+        assert_eq!(colorize("-removed", true), "\x1b[31m-removed\x1b[0m");
+        assert_eq!(colorize("--- a/file", true), "\x1b[1m--- a/file\x1b[0m");
+        assert_eq!(colorize(" context", true), " context");
+        assert_eq!(colorize("+added", false), "+added");
+    }
+}
@@ -0,0 +1,280 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::config::plugins::load_plugin_manifest;
+use crate::constants::{PLUGIN_CONFIG_FILE, PLUGIN_MANIFEST_FILE};
+
+/// SHA-256 checksums of every installed plugin's files (.makeitso/plugins.lock)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct PluginLock {
+    #[serde(default)]
+    pub plugins: BTreeMap<String, LockedPlugin>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct LockedPlugin {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+
+    /// Registry commit the plugin was installed from, when the registry is a git checkout
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+
+    /// Version from the plugin's manifest when it was installed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+
+    /// Version `mis update` keeps the plugin at; takes precedence over `pin` in manifest.toml
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pin: Option<String>,
+
+    /// config.toml as the plugin shipped it, the base `mis update` merges the user's edits onto
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_config: Option<String>,
+
+    /// Path relative to the plugin directory → hex SHA-256
+    #[serde(default)]
+    pub files: BTreeMap<String, String>,
+}
+
+/// Files that differ between two sets of checksums
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ChecksumDrift {
+    pub modified: Vec<String>,
+    pub missing: Vec<String>,
+    pub added: Vec<String>,
+}
+
+impl ChecksumDrift {
+    pub fn is_clean(&self) -> bool {
+        self.modified.is_empty() && self.missing.is_empty() && self.added.is_empty()
+    }
+
+    pub fn print(&self, indent: &str) {
+        for file in &self.modified {
+            outln!("{}~ {}", indent, file);
+        }
+        for file in &self.missing {
+            outln!("{}- {}", indent, file);
+        }
+        for file in &self.added {
+            outln!("{}+ {}", indent, file);
+        }
+    }
+}
+
+impl PluginLock {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".makeitso").join("plugins.lock")
+    }
+
+    /// Load the project's lock file; a missing file means no plugin is tracked yet
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&contents).map_err(|e| {
+            anyhow!(
+                "🛑 Failed to parse {}: {}\n\
+                 → Reinstall your plugins with `mis add <plugin> --force` to recreate it.",
+                path.display(),
+                e
+            )
+        })
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        fs::write(&path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+}
+
+/// Record the version, checksums and shipped config.toml of a freshly installed or updated
+/// plugin, keeping any pin already set for it
+pub fn record_plugin_checksums(
+    project_root: &Path,
+    plugin_name: &str,
+    registry: Option<&str>,
+    commit: Option<&str>,
+    default_config: Option<&str>,
+    plugin_dir: &Path,
+) -> Result<()> {
+    let mut lock = PluginLock::load(project_root)?;
+    let pin = lock
+        .plugins
+        .get(plugin_name)
+        .and_then(|locked| locked.pin.clone());
+    let version = load_plugin_manifest(&plugin_dir.join(PLUGIN_MANIFEST_FILE))
+        .ok()
+        .map(|manifest| manifest.plugin.version);
+    lock.plugins.insert(
+        plugin_name.to_string(),
+        LockedPlugin {
+            registry: registry.map(str::to_string),
+            commit: commit.map(str::to_string),
+            version,
+            pin,
+            default_config: default_config.map(str::to_string),
+            files: hash_plugin_dir(plugin_dir)?,
+        },
+    );
+    lock.save(project_root)
+}
+
+/// Hash every file in a plugin directory.
+///
+/// The top-level config.toml is skipped because users are expected to edit it.
+pub fn hash_plugin_dir(plugin_dir: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    hash_dir_into(plugin_dir, "", &mut files)?;
+    files.remove(PLUGIN_CONFIG_FILE);
+    Ok(files)
+}
+
+fn hash_dir_into(dir: &Path, prefix: &str, files: &mut BTreeMap<String, String>) -> Result<()> {
+    let entries = fs::read_dir(dir).with_context(|| format!("Failed to read {}", dir.display()))?;
+
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        let relative = format!("{}{}", prefix, name);
+
+        if entry.file_type()?.is_dir() {
+            hash_dir_into(&entry.path(), &format!("{}/", relative), files)?;
+        } else {
+            let contents = fs::read(entry.path())
+                .with_context(|| format!("Failed to read {}", entry.path().display()))?;
+            files.insert(relative, format!("{:x}", Sha256::digest(&contents)));
+        }
+    }
+
+    Ok(())
+}
+
+/// Compare the recorded checksums against the files found now
+pub fn compare_checksums(
+    expected: &BTreeMap<String, String>,
+    actual: &BTreeMap<String, String>,
+) -> ChecksumDrift {
+    let mut drift = ChecksumDrift::default();
+
+    for (file, hash) in expected {
+        match actual.get(file) {
+            Some(actual_hash) if actual_hash != hash => drift.modified.push(file.clone()),
+            Some(_) => {}
+            None => drift.missing.push(file.clone()),
+        }
+    }
+    drift.added = actual
+        .keys()
+        .filter(|file| !expected.contains_key(*file))
+        .cloned()
+        .collect();
+
+    drift
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn write_plugin(dir: &Path) {
+        fs::create_dir_all(dir.join("lib")).unwrap();
+        fs::write(
+            dir.join("manifest.toml"),
+            "[plugin]\nname = \"deploy\"\nversion = \"1.2.0\"\n",
+        )
+        .unwrap();
+        fs::write(dir.join("main.ts"), "console.log('hi');").unwrap();
+        fs::write(dir.join("lib/util.ts"), "export {};").unwrap();
+        fs::write(dir.join(PLUGIN_CONFIG_FILE), "token = \"abc\"").unwrap();
+    }
+
+    #[test]
+    fn test_hash_plugin_dir_skips_config() {
+        let temp_dir = tempdir().unwrap();
+        write_plugin(temp_dir.path());
+
+        let files = hash_plugin_dir(temp_dir.path()).unwrap();
+        let names: Vec<&str> = files.keys().map(String::as_str).collect();
+        assert_eq!(names, vec!["lib/util.ts", "main.ts", "manifest.toml"]);
+        assert_eq!(
+            files["lib/util.ts"],
+            format!("{:x}", Sha256::digest(b"export {};"))
+        );
+    }
+
+    #[test]
+    fn test_compare_checksums_reports_drift() {
+        let temp_dir = tempdir().unwrap();
+        write_plugin(temp_dir.path());
+        let expected = hash_plugin_dir(temp_dir.path()).unwrap();
+
+        fs::write(temp_dir.path().join(PLUGIN_CONFIG_FILE), "token = \"new\"").unwrap();
+        assert!(
+            compare_checksums(&expected, &hash_plugin_dir(temp_dir.path()).unwrap()).is_clean()
+        );
+
+        fs::write(temp_dir.path().join("main.ts"), "steal();").unwrap();
+        fs::remove_file(temp_dir.path().join("lib/util.ts")).unwrap();
+        fs::write(temp_dir.path().join("extra.ts"), "").unwrap();
+
+        let drift = compare_checksums(&expected, &hash_plugin_dir(temp_dir.path()).unwrap());
+        assert_eq!(drift.modified, vec!["main.ts"]);
+        assert_eq!(drift.missing, vec!["lib/util.ts"]);
+        assert_eq!(drift.added, vec!["extra.ts"]);
+    }
+
+    #[test]
+    fn test_record_plugin_checksums_round_trips() {
+        let temp_dir = tempdir().unwrap();
+        let plugin_dir = temp_dir.path().join(".makeitso/plugins/deploy");
+        write_plugin(&plugin_dir);
+
+        record_plugin_checksums(
+            temp_dir.path(),
+            "deploy",
+            Some("https://github.com/user/registry"),
+            Some("0123456789abcdef0123456789abcdef01234567"),
+            None,
+            &plugin_dir,
+        )
+        .unwrap();
+
+        let lock = PluginLock::load(temp_dir.path()).unwrap();
+        let locked = &lock.plugins["deploy"];
+        assert_eq!(
+            locked.registry.as_deref(),
+            Some("https://github.com/user/registry")
+        );
+        assert_eq!(
+            locked.commit.as_deref(),
+            Some("0123456789abcdef0123456789abcdef01234567")
+        );
+        assert_eq!(locked.version.as_deref(), Some("1.2.0"));
+        assert_eq!(locked.files, hash_plugin_dir(&plugin_dir).unwrap());
+
+        // A pin set by hand survives reinstalling the plugin
+        let mut lock = PluginLock::load(temp_dir.path()).unwrap();
+        lock.plugins.get_mut("deploy").unwrap().pin = Some("1.2.0".to_string());
+        lock.save(temp_dir.path()).unwrap();
+        record_plugin_checksums(temp_dir.path(), "deploy", None, None, None, &plugin_dir).unwrap();
+        let lock = PluginLock::load(temp_dir.path()).unwrap();
+        assert_eq!(lock.plugins["deploy"].pin.as_deref(), Some("1.2.0"));
+    }
+}
@@ -0,0 +1,219 @@
+use serde::{Deserialize, Serialize};
+
+use crate::logging::{Verbosity, enabled};
+use crate::run_log::format_utc;
+use crate::theme::{Stream, paint, use_color};
+
+/// Marks a stderr line as a structured log entry; the JSON object follows it on the same line
+pub const LOG_LINE_PREFIX: &str = "::mis::log::";
+
+/// Marks a stderr line as a progress update, e.g. `::mis::progress::{"progress": 42}`
+pub const PROGRESS_LINE_PREFIX: &str = "::mis::progress::";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Debug,
+    Info,
+    #[serde(alias = "warning")]
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    fn label(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "DEBUG",
+            LogLevel::Info => "INFO",
+            LogLevel::Warn => "WARN",
+            LogLevel::Error => "ERROR",
+        }
+    }
+
+    fn color_code(self) -> &'static str {
+        match self {
+            LogLevel::Debug => "2",
+            LogLevel::Info => "36",
+            LogLevel::Warn => "33",
+            LogLevel::Error => "31",
+        }
+    }
+
+    /// Debug entries need `-v`; `-q` keeps only warnings and errors
+    fn visible(self) -> bool {
+        match self {
+            LogLevel::Debug => enabled(Verbosity::Verbose),
+            LogLevel::Info => enabled(Verbosity::Normal),
+            LogLevel::Warn | LogLevel::Error => true,
+        }
+    }
+}
+
+/// A log entry a plugin wrote as `::mis::log::{"level": "info", "message": "..."}`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginLogLine {
+    /// When mis received the line, as UTC `YYYY-MM-DDTHH:MM:SSZ`
+    #[serde(default)]
+    pub timestamp: String,
+    pub level: LogLevel,
+    pub message: String,
+    /// Any other keys the plugin included, shown as `key=value`
+    #[serde(default, flatten)]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// Parse a structured log line received at `received_at` (seconds since the epoch).
+/// Anything else, including protocol lines with invalid JSON, is ordinary output.
+pub fn parse_log_line(line: &str, received_at: u64) -> Option<PluginLogLine> {
+    let json = line
+        .trim_end_matches(['\r', '\n'])
+        .strip_prefix(LOG_LINE_PREFIX)?;
+    let mut entry: PluginLogLine = serde_json::from_str(json).ok()?;
+    entry.timestamp = format_utc(received_at);
+    Some(entry)
+}
+
+/// A progress update; without `progress` the CLI shows a spinner instead of a bar
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProgressUpdate {
+    /// Percent complete, clamped to 0-100
+    #[serde(default)]
+    pub progress: Option<f64>,
+    #[serde(default)]
+    pub message: Option<String>,
+}
+
+/// Parse a progress line; anything else is ordinary output
+pub fn parse_progress_line(line: &str) -> Option<ProgressUpdate> {
+    let json = line
+        .trim_end_matches(['\r', '\n'])
+        .strip_prefix(PROGRESS_LINE_PREFIX)?;
+    let mut update: ProgressUpdate = serde_json::from_str(json).ok()?;
+    update.progress = update
+        .progress
+        .filter(|percent| percent.is_finite())
+        .map(|percent| percent.clamp(0.0, 100.0));
+    Some(update)
+}
+
+/// Print a log entry to stderr if the current verbosity shows its level
+pub fn print_log_line(entry: &PluginLogLine, prefix: Option<&str>) {
+    if !entry.level.visible() {
+        return;
+    }
+    let line = format_log_line(entry, use_color(Stream::Stderr));
+    match prefix {
+        Some(prefix) => eprintln!("[{}] {}", prefix, line),
+        None => eprintln!("{}", line),
+    }
+}
+
+/// `HH:MM:SS LEVEL message key=value`, with the level colored when `color` is set
+fn format_log_line(entry: &PluginLogLine, color: bool) -> String {
+    let time = entry.timestamp.get(11..19).unwrap_or(&entry.timestamp);
+    let label = format!("{:<5}", entry.level.label());
+    let label = if color {
+        paint(entry.level.color_code(), &label)
+    } else {
+        label
+    };
+
+    let mut line = format!("{} {} {}", time, label, entry.message);
+    for (key, value) in &entry.fields {
+        match value {
+            serde_json::Value::String(text) => line.push_str(&format!(" {}={}", key, text)),
+            other => line.push_str(&format!(" {}={}", key, other)),
+        }
+    }
+    line
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_log_line() {
+        let entry = parse_log_line(
+            "::mis::log::{\"level\":\"warning\",\"message\":\"Cache is cold\",\"region\":\"eu\",\"timestamp\":\"x\"}\n",
+            1_792_152_000,
+        )
+        .unwrap();
+        assert_eq!(entry.level, LogLevel::Warn);
+        assert_eq!(entry.message, "Cache is cold");
+        assert_eq!(entry.timestamp, "2026-10-16T12:00:00Z");
+        assert_eq!(entry.fields.len(), 1, "the plugin can't set the timestamp");
+        assert_eq!(entry.fields["region"], "eu");
+
+        // Ordinary output and malformed protocol lines are left alone
+        assert_eq!(parse_log_line("Deploying...", 0), None);
+        assert_eq!(parse_log_line("::mis::log::not json", 0), None);
+        assert_eq!(
+            parse_log_line("::mis::log::{\"message\":\"no level\"}", 0),
+            None
+        );
+        assert_eq!(
+            parse_log_line("  ::mis::log::{\"level\":\"info\",\"message\":\"x\"}", 0),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_progress_line() {
+        assert_eq!(
+            parse_progress_line(
+                "::mis::progress::{\"progress\": 42, \"message\": \"uploading image\"}\n"
+            ),
+            Some(ProgressUpdate {
+                progress: Some(42.0),
+                message: Some("uploading image".to_string()),
+            })
+        );
+        assert_eq!(
+            parse_progress_line("::mis::progress::{\"message\": \"waiting\"}")
+                .unwrap()
+                .progress,
+            None
+        );
+        assert_eq!(
+            parse_progress_line("::mis::progress::{\"progress\": 250}")
+                .unwrap()
+                .progress,
+            Some(100.0)
+        );
+        assert_eq!(parse_progress_line("::mis::progress::42"), None);
+        assert_eq!(parse_progress_line("::mis::log::{\"progress\": 42}"), None);
+    }
+
+    #[test]
+    fn test_format_log_line() {
+        let entry = parse_log_line(
+            "::mis::log::{\"level\":\"error\",\"message\":\"Upload failed\",\"attempt\":3,\"bucket\":\"assets\"}",
+            1_792_152_000,
+        )
+        .unwrap();
+        assert_eq!(
+            format_log_line(&entry, false),
+            "12:00:00 ERROR Upload failed attempt=3 bucket=assets"
+        );
+        assert_eq!(
+            format_log_line(&entry, true),
+            "12:00:00 \x1b[31mERROR\x1b[0m Upload failed attempt=3 bucket=assets"
+        );
+    }
+
+    #[test]
+    fn test_log_line_round_trips_through_run_log() {
+        let entry = parse_log_line(
+            "::mis::log::{\"level\":\"info\",\"message\":\"Built 3 images\"}",
+            0,
+        )
+        .unwrap();
+        let json = serde_json::to_string(&entry).unwrap();
+        assert_eq!(
+            json,
+            r#"{"timestamp":"1970-01-01T00:00:00Z","level":"info","message":"Built 3 images"}"#
+        );
+        assert_eq!(serde_json::from_str::<PluginLogLine>(&json).unwrap(), entry);
+    }
+}
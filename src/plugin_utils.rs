@@ -1,8 +1,10 @@
+use crate::commands::migrate::LEGACY_MANIFEST_FILE;
+use crate::config::load_mis_config_from;
 use crate::constants::PLUGIN_MANIFEST_FILE;
-use crate::utils::find_project_root;
+use crate::utils::require_project_root;
 use anyhow::Result;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 
 /// Check if a plugin exists in the current project
 pub fn plugin_exists_in_project(plugin_name: &str) -> bool {
@@ -13,7 +15,7 @@ pub fn plugin_exists_in_project(plugin_name: &str) -> bool {
 
 /// Get the path to a plugin directory, ensuring it exists
 pub fn get_plugin_path(plugin_name: &str) -> Result<PathBuf> {
-    let root = find_project_root().ok_or_else(|| anyhow::anyhow!("Failed to find project root"))?;
+    let root = require_project_root()?;
 
     if !root.exists() {
         anyhow::bail!(
@@ -38,6 +40,7 @@ pub fn get_plugin_path(plugin_name: &str) -> Result<PathBuf> {
     }
 
     let manifest_path = plugin_path.join(PLUGIN_MANIFEST_FILE);
+    ensure_current_manifest_name(plugin_name, &plugin_path)?;
     if !manifest_path.exists() {
         anyhow::bail!(
             "🛑 manifest.toml not found for plugin '{}'.\n\
@@ -51,9 +54,71 @@ pub fn get_plugin_path(plugin_name: &str) -> Result<PathBuf> {
     Ok(plugin_path)
 }
 
+/// Fail with a pointer to `mis migrate` when a plugin still has its manifest under the old name
+pub fn ensure_current_manifest_name(plugin_name: &str, plugin_path: &Path) -> Result<()> {
+    if !plugin_path.join(PLUGIN_MANIFEST_FILE).exists()
+        && plugin_path.join(LEGACY_MANIFEST_FILE).exists()
+    {
+        anyhow::bail!(
+            "🛑 Plugin '{}' still uses the old plugin.toml manifest name.\n\
+             → Run `mis migrate` to upgrade the project's plugins to manifest.toml.",
+            plugin_name
+        );
+    }
+    Ok(())
+}
+
+/// Directory a plugin keeps data in between runs, passed to it as `ctx.state_dir`
+pub fn plugin_state_dir(project_root: &Path, plugin_name: &str) -> Result<PathBuf> {
+    ensure_path_component("plugin name", plugin_name)?;
+    Ok(project_root
+        .join(".makeitso")
+        .join("state")
+        .join(plugin_name))
+}
+
+/// Fail unless a manifest value used as a directory name under .makeitso is one plain path
+/// component, so it can't point anywhere else (`..`, separators, absolute paths)
+pub fn ensure_path_component(what: &str, name: &str) -> Result<()> {
+    let mut components = Path::new(name).components();
+    let single = matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    );
+    if !single || name.contains(['/', '\\']) {
+        anyhow::bail!(
+            "🛑 Invalid {} '{}': it names a directory under .makeitso.\n\
+             → Use a plain name without `/`, `\\` or `..` in manifest.toml.",
+            what,
+            name
+        );
+    }
+    Ok(())
+}
+
+/// Plugins turned off with `mis disable`; none when mis.toml can't be read
+pub fn disabled_plugins(project_root: &Path) -> Vec<String> {
+    load_mis_config_from(project_root)
+        .map(|(config, _, _)| config.disabled_plugins)
+        .unwrap_or_default()
+}
+
+/// Fail with a pointer to `mis enable` when the plugin has been disabled
+pub fn ensure_plugin_enabled(project_root: &Path, plugin_name: &str) -> Result<()> {
+    if disabled_plugins(project_root).iter().any(|p| p == plugin_name) {
+        anyhow::bail!(
+            "🛑 Plugin '{}' is disabled in this project.\n\
+             → Run `mis enable {}` to use it again.",
+            plugin_name,
+            plugin_name
+        );
+    }
+    Ok(())
+}
+
 /// Get the plugins directory path, creating it if needed for write operations
 pub fn get_plugins_dir(create_if_missing: bool) -> Result<PathBuf> {
-    let root = find_project_root().ok_or_else(|| anyhow::anyhow!("Failed to find project root"))?;
+    let root = require_project_root()?;
 
     if !root.exists() {
         anyhow::bail!(
@@ -172,6 +237,19 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_ensure_path_component_rejects_traversal() {
+        assert!(ensure_path_component("plugin name", "deploy").is_ok());
+        assert!(ensure_path_component("plugin name", "my.plugin-2").is_ok());
+        for name in ["", ".", "..", "../../..", "a/b", "a\\b", "/etc", "deploy/"] {
+            assert!(
+                ensure_path_component("plugin name", name).is_err(),
+                "{:?} should be rejected",
+                name
+            );
+        }
+    }
+
     #[test]
     fn test_plugin_exists_in_project_returns_true_when_plugin_exists() {
         run_test_in_temp_dir(|| {
@@ -0,0 +1,104 @@
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use indicatif::{MultiProgress, ProgressBar, ProgressDrawTarget, ProgressStyle};
+
+use crate::config::settings;
+use crate::logging::{Verbosity, enabled};
+use crate::models::Theme;
+use crate::plugin_log::ProgressUpdate;
+
+// Every plugin's bar lives in one MultiProgress, so concurrent workflow steps stack
+// their bars instead of drawing over each other. indicatif draws nothing when stderr
+// isn't a terminal, so CI logs only get the plugin's ordinary output.
+fn bars() -> &'static MultiProgress {
+    static BARS: OnceLock<MultiProgress> = OnceLock::new();
+    BARS.get_or_init(|| MultiProgress::with_draw_target(ProgressDrawTarget::stderr()))
+}
+
+/// Print plugin output with any progress bars cleared, then redraw them below it
+pub fn suspend<R>(print: impl FnOnce() -> R) -> R {
+    bars().suspend(print)
+}
+
+/// The bar or spinner for one running plugin, created on its first progress update
+/// and cleared when the plugin's output ends
+pub struct PluginProgress {
+    prefix: Option<String>,
+    bar: Option<ProgressBar>,
+    determinate: bool,
+}
+
+impl PluginProgress {
+    pub fn new(prefix: Option<&str>) -> Self {
+        Self {
+            prefix: prefix.map(|prefix| format!("[{}] ", prefix)),
+            bar: None,
+            determinate: false,
+        }
+    }
+
+    pub fn update(&mut self, update: &ProgressUpdate) {
+        // -q hides progress along with other non-essential output
+        if !enabled(Verbosity::Normal) {
+            return;
+        }
+
+        let bar = self.bar.get_or_insert_with(|| {
+            let bar = bars().add(ProgressBar::new(100));
+            if let Some(prefix) = &self.prefix {
+                bar.set_prefix(prefix.clone());
+            }
+            bar.enable_steady_tick(Duration::from_millis(100));
+            bar.set_style(spinner_style());
+            bar
+        });
+
+        match update.progress {
+            Some(percent) => {
+                if !self.determinate {
+                    bar.set_style(bar_style());
+                    self.determinate = true;
+                }
+                bar.set_position(percent.round() as u64);
+            }
+            None if self.determinate => {
+                bar.set_style(spinner_style());
+                self.determinate = false;
+            }
+            None => {}
+        }
+        if let Some(message) = &update.message {
+            bar.set_message(message.clone());
+        }
+    }
+}
+
+impl Drop for PluginProgress {
+    fn drop(&mut self) {
+        if let Some(bar) = self.bar.take() {
+            bar.finish_and_clear();
+            bars().remove(&bar);
+        }
+    }
+}
+
+fn fancy() -> bool {
+    settings().theme.unwrap_or_default() == Theme::Fancy
+}
+
+fn spinner_style() -> ProgressStyle {
+    let style = ProgressStyle::with_template("{prefix}{spinner} {msg}")
+        .unwrap_or_else(|_| ProgressStyle::default_spinner());
+    if fancy() {
+        style
+    } else {
+        style.tick_chars("|/-\\ ")
+    }
+}
+
+fn bar_style() -> ProgressStyle {
+    ProgressStyle::with_template("{prefix}[{bar:30}] {pos:>3}% {msg}")
+        .unwrap_or_else(|_| ProgressStyle::default_bar())
+        .progress_chars("=> ")
+}
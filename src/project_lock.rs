@@ -0,0 +1,247 @@
+use std::fs::{self, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::run_log::format_utc;
+
+/// How often a waiting run checks whether the lock was released
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Runs in this process currently holding the project lock. Workflow steps and hooks
+/// run in the same process as the command that took it, so they share it instead of
+/// waiting on themselves.
+static HELD: Mutex<usize> = Mutex::new(0);
+
+pub fn lock_path(project_root: &Path) -> PathBuf {
+    project_root.join(".makeitso").join(".lock")
+}
+
+/// Who holds the lock, as written to `.makeitso/.lock`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LockHolder {
+    pub pid: u32,
+    /// The "plugin:command" being run
+    pub target: String,
+    /// When the lock was taken, as UTC `YYYY-MM-DDTHH:MM:SSZ`
+    pub started: String,
+}
+
+/// Releases the project lock when dropped
+#[derive(Debug)]
+pub struct ProjectLock {
+    path: PathBuf,
+}
+
+impl Drop for ProjectLock {
+    fn drop(&mut self) {
+        let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+        *held = held.saturating_sub(1);
+        if *held == 0 {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+/// Take the advisory project lock for `target`, waiting up to `wait` for another run
+/// to release it. A lock left behind by a process that no longer exists is taken over.
+pub fn acquire_project_lock(
+    project_root: &Path,
+    target: &str,
+    wait: Option<Duration>,
+) -> Result<ProjectLock> {
+    let path = lock_path(project_root);
+    let mut held = HELD.lock().unwrap_or_else(|e| e.into_inner());
+    if *held > 0 {
+        *held += 1;
+        return Ok(ProjectLock { path });
+    }
+
+    let deadline = wait.map(|wait| Instant::now() + wait);
+    let mut announced = false;
+    loop {
+        match try_create(&path, target) {
+            Ok(()) => break,
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("🛑 Failed to create lock file: {}", path.display()));
+            }
+        }
+
+        let holder = read_holder(&path);
+        if let Some(holder) = &holder
+            && !process_alive(holder.pid)
+        {
+            debugln!(
+                "Removing stale lock from {} (pid {} is gone)",
+                holder.target,
+                holder.pid
+            );
+            remove_stale_lock(&path, holder).with_context(|| {
+                format!("🛑 Failed to remove stale lock file: {}", path.display())
+            })?;
+            continue;
+        }
+
+        let described = match &holder {
+            Some(holder) => format!(
+                "{} (pid {}, started {})",
+                holder.target, holder.pid, holder.started
+            ),
+            None => "another mis run".to_string(),
+        };
+        match deadline {
+            Some(deadline) if Instant::now() < deadline => {
+                if !announced {
+                    outln!("⏳ Waiting for {} to finish...", described);
+                    announced = true;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+            _ => anyhow::bail!(
+                "🛑 The project is locked by {}.\n\
+                 → Wait for it to finish, pass --lock-timeout <SECS> to wait for it, or --no-lock to run anyway.\n\
+                 → If no run is active, delete {}.",
+                described,
+                path.display()
+            ),
+        }
+    }
+
+    *held += 1;
+    Ok(ProjectLock { path })
+}
+
+/// Create the lock file only if it doesn't exist, so two runs can't both take it
+fn try_create(path: &Path, target: &str) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    let holder = LockHolder {
+        pid: std::process::id(),
+        target: target.to_string(),
+        started: format_utc(now),
+    };
+    writeln!(file, "{}", serde_json::to_string(&holder)?)
+}
+
+/// Move a dead run's lock out of the way. Another waiter may have taken it over since it was
+/// read, so the moved file is checked and put back unless it's still the stale one.
+fn remove_stale_lock(path: &Path, stale: &LockHolder) -> std::io::Result<()> {
+    let moved = path.with_file_name(format!(".lock.stale-{}", std::process::id()));
+    match fs::rename(path, &moved) {
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(()),
+        result => result?,
+    }
+    if read_holder(&moved).as_ref() != Some(stale) {
+        // A hard link doesn't replace a lock someone created in the meantime
+        let _ = fs::hard_link(&moved, path);
+    }
+    fs::remove_file(&moved)
+}
+
+fn read_holder(path: &Path) -> Option<LockHolder> {
+    serde_json::from_str(&fs::read_to_string(path).ok()?).ok()
+}
+
+/// `ps` rather than `kill -0`, which also fails for live processes owned by other users
+#[cfg(unix)]
+fn process_alive(pid: u32) -> bool {
+    std::process::Command::new("ps")
+        .args(["-p", &pid.to_string()])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(true)
+}
+
+#[cfg(not(unix))]
+fn process_alive(_pid: u32) -> bool {
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    // One test covers the whole lifecycle, since HELD is shared by every test thread
+    #[test]
+    fn test_project_lock_lifecycle() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        let path = lock_path(root);
+
+        // Another live process holds the lock: fail without waiting, or after the timeout
+        let other = LockHolder {
+            pid: std::process::id(),
+            target: "deploy:prod".to_string(),
+            started: "2026-10-16T12:00:00Z".to_string(),
+        };
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serde_json::to_string(&other).unwrap()).unwrap();
+        let err = acquire_project_lock(root, "deploy:prod", None).unwrap_err();
+        assert!(err.to_string().contains("deploy:prod (pid"), "{}", err);
+        assert!(err.to_string().contains("--no-lock"));
+        let started = Instant::now();
+        assert!(
+            acquire_project_lock(root, "deploy:prod", Some(Duration::from_millis(300))).is_err()
+        );
+        assert!(started.elapsed() >= Duration::from_millis(300));
+
+        // A lock left by a process that's gone is taken over
+        let stale = LockHolder {
+            pid: u32::MAX - 1,
+            ..other
+        };
+        fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+        let lock = acquire_project_lock(root, "deploy:prod", None).unwrap();
+        let holder = read_holder(&path).unwrap();
+        assert_eq!(holder.pid, std::process::id());
+        assert_eq!(holder.target, "deploy:prod");
+
+        // Nested runs in this process share it; the file goes with the last one
+        let nested = acquire_project_lock(root, "build:compile", None).unwrap();
+        drop(lock);
+        assert!(path.exists());
+        drop(nested);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_remove_stale_lock_keeps_a_lock_taken_in_the_meantime() {
+        let temp_dir = tempdir().unwrap();
+        let path = lock_path(temp_dir.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let stale = LockHolder {
+            pid: u32::MAX - 1,
+            target: "deploy:prod".to_string(),
+            started: "2026-10-16T12:00:00Z".to_string(),
+        };
+
+        // A faster waiter already replaced the stale lock with its own
+        let fresh = LockHolder {
+            pid: std::process::id(),
+            ..stale.clone()
+        };
+        fs::write(&path, serde_json::to_string(&fresh).unwrap()).unwrap();
+        remove_stale_lock(&path, &stale).unwrap();
+        assert_eq!(read_holder(&path), Some(fresh));
+
+        fs::write(&path, serde_json::to_string(&stale).unwrap()).unwrap();
+        remove_stale_lock(&path, &stale).unwrap();
+        assert!(!path.exists());
+        assert_eq!(fs::read_dir(path.parent().unwrap()).unwrap().count(), 0);
+    }
+}
@@ -0,0 +1,126 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anyhow::Result;
+use serde::Serialize;
+
+/// Set by `--output json`; messages then go to stderr so stdout holds only the report
+static JSON_OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Whether a command already printed its JSON report, so a failure doesn't print a second one
+static REPORTED: AtomicBool = AtomicBool::new(false);
+
+pub fn set_json_output(enabled: bool) {
+    JSON_OUTPUT.store(enabled, Ordering::Relaxed);
+}
+
+pub fn json_output() -> bool {
+    JSON_OUTPUT.load(Ordering::Relaxed)
+}
+
+/// Print a command's JSON report to stdout
+pub fn print_json<T: Serialize>(report: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(report)?);
+    REPORTED.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Report a command that failed before printing its own JSON
+pub fn print_error(error: &anyhow::Error) {
+    if REPORTED.load(Ordering::Relaxed) {
+        errln!("Error: {:#}", error);
+        return;
+    }
+    let report = serde_json::json!({
+        "status": "error",
+        "error": format!("{:#}", error),
+    });
+    // Serializing a json! value can't fail
+    let _ = print_json(&report);
+}
+
+/// What happened to one plugin in `mis add` and `mis update`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutcomeStatus {
+    Installed,
+    WouldInstall,
+    Updated,
+    WouldUpdate,
+    Skipped,
+    NotFound,
+    Failed,
+}
+
+/// One plugin's entry in an add or update report
+#[derive(Debug, Serialize)]
+pub struct PluginOutcome {
+    pub name: String,
+    pub status: OutcomeStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl PluginOutcome {
+    pub fn new(name: &str, status: OutcomeStatus) -> Self {
+        Self {
+            name: name.to_string(),
+            status,
+            version: None,
+            registry: None,
+            error: None,
+        }
+    }
+}
+
+/// The `--output json` report of `mis add` and `mis update`
+#[derive(Debug, Serialize)]
+pub struct PluginsReport {
+    pub status: &'static str,
+    pub dry_run: bool,
+    pub plugins: Vec<PluginOutcome>,
+}
+
+impl PluginsReport {
+    /// "error" when any plugin failed or wasn't found
+    pub fn new(dry_run: bool, plugins: Vec<PluginOutcome>) -> Self {
+        let failed = plugins
+            .iter()
+            .any(|p| matches!(p.status, OutcomeStatus::Failed | OutcomeStatus::NotFound));
+        Self {
+            status: if failed { "error" } else { "ok" },
+            dry_run,
+            plugins,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plugins_report_status_and_shape() {
+        let mut installed = PluginOutcome::new("deploy", OutcomeStatus::Installed);
+        installed.version = Some("1.2.0".to_string());
+        let report = PluginsReport::new(false, vec![installed]);
+        assert_eq!(report.status, "ok");
+        assert_eq!(
+            serde_json::to_value(&report).unwrap(),
+            serde_json::json!({
+                "status": "ok",
+                "dry_run": false,
+                "plugins": [{"name": "deploy", "status": "installed", "version": "1.2.0"}],
+            })
+        );
+
+        let report = PluginsReport::new(
+            true,
+            vec![PluginOutcome::new("missing", OutcomeStatus::NotFound)],
+        );
+        assert_eq!(report.status, "error");
+    }
+}
@@ -0,0 +1,240 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::plugin_log::PluginLogLine;
+use crate::security::PermissionViolation;
+
+/// Argument names whose values are never written to the run log
+const SECRET_ARG_MARKERS: &[&str] = &[
+    "token",
+    "secret",
+    "password",
+    "passwd",
+    "key",
+    "credential",
+    "auth",
+];
+
+const REDACTED: &str = "***";
+
+/// How a logged run ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunStatus {
+    Success,
+    Failed,
+}
+
+/// One `mis run` of a plugin command, as recorded in `.makeitso/logs/runs.jsonl`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RunLogEntry {
+    /// When the run started, as UTC `YYYY-MM-DDTHH:MM:SSZ`
+    pub timestamp: String,
+    pub plugin: String,
+    pub command: String,
+    pub args: serde_json::Map<String, serde_json::Value>,
+    pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<String>,
+    /// Deno permission flags the plugin ran with, after any approvals
+    pub permissions: Vec<String>,
+    /// Requested permissions that stayed blocked
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub violations: Vec<PermissionViolation>,
+    pub duration_ms: u64,
+    pub status: RunStatus,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_code: Option<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Approval token given with --approval for a protected environment
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub approval: Option<String>,
+    /// Structured log lines the plugin wrote to stderr
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub logs: Vec<PluginLogLine>,
+}
+
+impl RunLogEntry {
+    /// Start an entry for a run beginning now; secret-looking arguments are redacted
+    pub fn start(
+        plugin: &str,
+        command: &str,
+        args: &serde_json::Map<String, serde_json::Value>,
+        dry_run: bool,
+        environment: Option<&str>,
+    ) -> Self {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+
+        Self {
+            timestamp: format_utc(now),
+            plugin: plugin.to_string(),
+            command: command.to_string(),
+            args: redact_args(args),
+            dry_run,
+            environment: environment.map(str::to_string),
+            permissions: Vec::new(),
+            violations: Vec::new(),
+            duration_ms: 0,
+            status: RunStatus::Success,
+            exit_code: None,
+            error: None,
+            approval: None,
+            logs: Vec::new(),
+        }
+    }
+
+    /// Record how the run ended
+    pub fn finish<T>(&mut self, elapsed: Duration, outcome: &Result<T>) {
+        self.duration_ms = elapsed.as_millis() as u64;
+        if let Err(e) = outcome {
+            self.status = RunStatus::Failed;
+            self.error = Some(format!("{:#}", e));
+        }
+    }
+}
+
+pub fn run_log_path(project_root: &Path) -> PathBuf {
+    project_root
+        .join(".makeitso")
+        .join("logs")
+        .join("runs.jsonl")
+}
+
+/// Append one entry to the project's run log
+pub fn append_run_log(project_root: &Path, entry: &RunLogEntry) -> Result<()> {
+    let path = run_log_path(project_root);
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("Failed to open run log: {}", path.display()))?;
+    writeln!(file, "{}", serde_json::to_string(entry)?)
+        .with_context(|| format!("Failed to write run log: {}", path.display()))?;
+
+    Ok(())
+}
+
+/// Read every entry in the run log, oldest first. Lines that don't parse are skipped.
+pub fn read_run_log(project_root: &Path) -> Result<Vec<RunLogEntry>> {
+    let path = run_log_path(project_root);
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read run log: {}", path.display()))?;
+    Ok(content
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+fn redact_args(
+    args: &serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Map<String, serde_json::Value> {
+    args.iter()
+        .map(|(name, value)| {
+            let lower = name.to_lowercase();
+            if SECRET_ARG_MARKERS
+                .iter()
+                .any(|marker| lower.contains(marker))
+            {
+                (
+                    name.clone(),
+                    serde_json::Value::String(REDACTED.to_string()),
+                )
+            } else {
+                (name.clone(), value.clone())
+            }
+        })
+        .collect()
+}
+
+/// Format seconds since the Unix epoch as a UTC timestamp
+pub fn format_utc(secs: u64) -> String {
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        rem / 3_600,
+        rem % 3_600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_format_utc() {
+        assert_eq!(format_utc(0), "1970-01-01T00:00:00Z");
+        assert_eq!(format_utc(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(format_utc(1_792_152_000), "2026-10-16T12:00:00Z");
+    }
+
+    #[test]
+    fn test_run_log_redacts_secrets_and_round_trips() {
+        let temp_dir = tempdir().unwrap();
+        let args = serde_json::json!({"env": "prod", "api_key": "abc123", "GitHubToken": "ghp_x"});
+
+        let mut entry = RunLogEntry::start(
+            "deploy",
+            "prod",
+            args.as_object().unwrap(),
+            false,
+            Some("prod"),
+        );
+        assert_eq!(entry.args["env"], "prod");
+        assert_eq!(entry.args["api_key"], REDACTED);
+        assert_eq!(entry.args["GitHubToken"], REDACTED);
+
+        entry.exit_code = Some(1);
+        entry.finish::<()>(Duration::from_millis(1500), &Err(anyhow::anyhow!("boom")));
+        append_run_log(temp_dir.path(), &entry).unwrap();
+        append_run_log(temp_dir.path(), &entry).unwrap();
+
+        // A corrupt line doesn't hide the rest of the history
+        let path = run_log_path(temp_dir.path());
+        let mut content = fs::read_to_string(&path).unwrap();
+        content.push_str("not json\n");
+        fs::write(&path, content).unwrap();
+
+        let entries = read_run_log(temp_dir.path()).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].status, RunStatus::Failed);
+        assert_eq!(entries[0].duration_ms, 1500);
+        assert_eq!(entries[0].error.as_deref(), Some("boom"));
+        assert!(!fs::read_to_string(&path).unwrap().contains("abc123"));
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+/// Progress of one workflow run, saved to `.makeitso/runs/<run-id>.json` so it can be resumed
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct WorkflowRunState {
+    pub run_id: String,
+    pub workflow: String,
+
+    /// Step id → how the step ended
+    #[serde(default)]
+    pub steps: BTreeMap<String, StepStatus>,
+
+    /// Events published so far, so resumed steps still see them
+    #[serde(default)]
+    pub events: Map<String, JsonValue>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StepStatus {
+    Succeeded,
+    Failed,
+    /// Not run because its `on` event was never published
+    Skipped,
+}
+
+impl WorkflowRunState {
+    /// Start tracking a new run with a fresh run id
+    pub fn new(workflow: &str) -> Self {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Self {
+            run_id: format!("{}-{}-{}", workflow, started, std::process::id()),
+            workflow: workflow.to_string(),
+            ..Default::default()
+        }
+    }
+
+    pub fn path(project_root: &Path, run_id: &str) -> PathBuf {
+        project_root
+            .join(".makeitso")
+            .join("runs")
+            .join(format!("{}.json", run_id))
+    }
+
+    /// Load a previous run of `workflow` to resume it
+    pub fn load(project_root: &Path, run_id: &str, workflow: &str) -> Result<Self> {
+        if run_id.is_empty()
+            || !run_id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+        {
+            anyhow::bail!("🛑 Invalid run id '{}'.", run_id);
+        }
+
+        let path = Self::path(project_root, run_id);
+        let contents = fs::read_to_string(&path).map_err(|_| {
+            anyhow!(
+                "🛑 No saved run '{}' found.\n\
+                 → Runs that completed successfully aren't kept; check `.makeitso/runs/`.",
+                run_id
+            )
+        })?;
+        let state: Self = serde_json::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", path.display()))?;
+
+        if state.workflow != workflow {
+            anyhow::bail!(
+                "🛑 Run '{}' belongs to workflow '{}', not '{}'.\n\
+                 → Resume it with `mis run {} --resume {}`.",
+                run_id,
+                state.workflow,
+                workflow,
+                state.workflow,
+                run_id
+            );
+        }
+
+        Ok(state)
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root, &self.run_id);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write run state: {}", path.display()))
+    }
+
+    /// Forget a run that has nothing left to resume
+    pub fn remove(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root, &self.run_id);
+        if path.exists() {
+            fs::remove_file(&path)
+                .with_context(|| format!("Failed to remove {}", path.display()))?;
+        }
+        Ok(())
+    }
+
+    pub fn succeeded(&self, step_id: &str) -> bool {
+        self.steps.get(step_id) == Some(&StepStatus::Succeeded)
+    }
+
+    pub fn record(&mut self, step_id: &str, status: StepStatus) {
+        self.steps.insert(step_id.to_string(), status);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_run_state_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let mut state = WorkflowRunState::new("deploy");
+        assert!(state.run_id.starts_with("deploy-"));
+
+        state.record("build", StepStatus::Succeeded);
+        state.record("test", StepStatus::Failed);
+        state
+            .events
+            .insert("built".to_string(), serde_json::json!([{"tag": "v1"}]));
+        state.save(temp_dir.path()).unwrap();
+
+        let loaded = WorkflowRunState::load(temp_dir.path(), &state.run_id, "deploy").unwrap();
+        assert!(loaded.succeeded("build"));
+        assert!(!loaded.succeeded("test"));
+        assert!(!loaded.succeeded("ship"));
+        assert_eq!(loaded.events["built"], serde_json::json!([{"tag": "v1"}]));
+
+        loaded.remove(temp_dir.path()).unwrap();
+        assert!(WorkflowRunState::load(temp_dir.path(), &state.run_id, "deploy").is_err());
+    }
+
+    #[test]
+    fn test_run_state_load_rejects_bad_ids_and_other_workflows() {
+        let temp_dir = tempdir().unwrap();
+        let state = WorkflowRunState::new("deploy");
+        state.save(temp_dir.path()).unwrap();
+
+        let err = WorkflowRunState::load(temp_dir.path(), &state.run_id, "release")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("belongs to workflow 'deploy'"));
+
+        let err = WorkflowRunState::load(temp_dir.path(), "../secrets", "deploy")
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("Invalid run id"));
+    }
+}
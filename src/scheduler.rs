@@ -0,0 +1,338 @@
+use std::collections::{HashMap, VecDeque};
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::sync::mpsc;
+use std::thread;
+
+use anyhow::{Result, anyhow};
+
+/// A set of named tasks and the tasks each one needs to finish first
+#[derive(Debug)]
+pub struct TaskGraph {
+    ids: Vec<String>,
+    needs: Vec<Vec<usize>>,
+    always_run: Vec<bool>,
+}
+
+/// What happened to each task after a scheduled run
+#[derive(Debug, Default)]
+pub struct ScheduleOutcome {
+    pub completed: Vec<usize>,
+    pub failed: Vec<(usize, anyhow::Error)>,
+    /// Tasks that never started because something failed first
+    pub skipped: Vec<usize>,
+}
+
+impl ScheduleOutcome {
+    pub fn is_success(&self) -> bool {
+        self.failed.is_empty() && self.skipped.is_empty()
+    }
+}
+
+impl TaskGraph {
+    /// Build a graph from `(id, needs)` pairs, rejecting duplicate ids, unknown needs and cycles
+    pub fn new(tasks: Vec<(String, Vec<String>)>) -> Result<Self> {
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        for (i, (id, _)) in tasks.iter().enumerate() {
+            if index.insert(id.as_str(), i).is_some() {
+                return Err(anyhow!(
+                    "🛑 Duplicate step id '{}'.\n\
+                     → Give each step a unique `id`.",
+                    id
+                ));
+            }
+        }
+
+        let mut needs = Vec::with_capacity(tasks.len());
+        for (id, task_needs) in &tasks {
+            let mut resolved = Vec::with_capacity(task_needs.len());
+            for need in task_needs {
+                if need == id {
+                    return Err(anyhow!("🛑 Step '{}' cannot need itself.", id));
+                }
+                let dep = *index.get(need.as_str()).ok_or_else(|| {
+                    anyhow!(
+                        "🛑 Step '{}' needs unknown step '{}'.\n\
+                         → `needs` must refer to another step's `id` (or its `run` target).",
+                        id,
+                        need
+                    )
+                })?;
+                if !resolved.contains(&dep) {
+                    resolved.push(dep);
+                }
+            }
+            needs.push(resolved);
+        }
+
+        let graph = Self {
+            always_run: vec![false; tasks.len()],
+            ids: tasks.into_iter().map(|(id, _)| id).collect(),
+            needs,
+        };
+        graph.check_acyclic()?;
+        Ok(graph)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ids.len()
+    }
+
+    pub fn id(&self, task: usize) -> &str {
+        &self.ids[task]
+    }
+
+    /// Still run `task` after a failure, once everything it needs has finished either way
+    pub fn set_always_run(&mut self, task: usize) {
+        self.always_run[task] = true;
+    }
+
+    fn dependents(&self) -> Vec<Vec<usize>> {
+        let mut dependents = vec![Vec::new(); self.len()];
+        for (task, needs) in self.needs.iter().enumerate() {
+            for &dep in needs {
+                dependents[dep].push(task);
+            }
+        }
+        dependents
+    }
+
+    fn check_acyclic(&self) -> Result<()> {
+        let dependents = self.dependents();
+        let mut waiting: Vec<usize> = self.needs.iter().map(Vec::len).collect();
+        let mut ready: Vec<usize> = (0..self.len()).filter(|&i| waiting[i] == 0).collect();
+        let mut visited = 0;
+
+        while let Some(task) = ready.pop() {
+            visited += 1;
+            for &dependent in &dependents[task] {
+                waiting[dependent] -= 1;
+                if waiting[dependent] == 0 {
+                    ready.push(dependent);
+                }
+            }
+        }
+
+        if visited < self.len() {
+            let cycle: Vec<&str> = (0..self.len())
+                .filter(|&i| waiting[i] > 0)
+                .map(|i| self.id(i))
+                .collect();
+            return Err(anyhow!(
+                "🛑 Steps depend on each other in a cycle: {}\n\
+                 → Remove one of the `needs` entries to break the cycle.",
+                cycle.join(", ")
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Run every task in the graph, starting each one as soon as its needs have completed.
+///
+/// At most `max_workers` tasks run at once. After the first failure only always-run tasks
+/// are started; tasks already running are allowed to finish.
+pub fn run_graph<F>(graph: &TaskGraph, max_workers: usize, run_task: F) -> ScheduleOutcome
+where
+    F: Fn(usize) -> Result<()> + Sync,
+{
+    let max_workers = max_workers.max(1);
+    let dependents = graph.dependents();
+    let mut waiting: Vec<usize> = graph.needs.iter().map(Vec::len).collect();
+    let mut ready: VecDeque<usize> = (0..graph.len()).filter(|&i| waiting[i] == 0).collect();
+    let mut outcome = ScheduleOutcome::default();
+
+    // A task is ready once every task it needs has finished, however it finished
+    let mut finish = |task: usize, ready: &mut VecDeque<usize>| {
+        for &dependent in &dependents[task] {
+            waiting[dependent] -= 1;
+            if waiting[dependent] == 0 {
+                ready.push_back(dependent);
+            }
+        }
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let run_task = &run_task;
+
+    thread::scope(|scope| {
+        let mut running = 0;
+
+        loop {
+            // After a failure, tasks that aren't always-run are skipped as they become ready
+            while !outcome.failed.is_empty() {
+                let Some(pos) = ready.iter().position(|&t| !graph.always_run[t]) else {
+                    break;
+                };
+                let task = ready.remove(pos).expect("position is in range");
+                outcome.skipped.push(task);
+                finish(task, &mut ready);
+            }
+
+            while running < max_workers {
+                let Some(task) = ready.pop_front() else {
+                    break;
+                };
+                let tx = tx.clone();
+                scope.spawn(move || {
+                    let result = catch_unwind(AssertUnwindSafe(|| run_task(task)))
+                        .unwrap_or_else(|_| Err(anyhow!("🛑 Step panicked while running")));
+                    // The receiver outlives every worker, so this can't fail
+                    let _ = tx.send((task, result));
+                });
+                running += 1;
+            }
+
+            if running == 0 {
+                break;
+            }
+
+            let (task, result) = rx.recv().expect("scheduler workers hold a sender");
+            running -= 1;
+
+            match result {
+                Ok(()) => outcome.completed.push(task),
+                Err(e) => outcome.failed.push((task, e)),
+            }
+            finish(task, &mut ready);
+        }
+    });
+
+    outcome
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    fn graph(tasks: &[(&str, &[&str])]) -> Result<TaskGraph> {
+        TaskGraph::new(
+            tasks
+                .iter()
+                .map(|(id, needs)| {
+                    (
+                        id.to_string(),
+                        needs.iter().map(|n| n.to_string()).collect(),
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_task_graph_rejects_invalid_graphs() {
+        let err = graph(&[("a", &[]), ("a", &[])]).unwrap_err().to_string();
+        assert!(err.contains("Duplicate step id 'a'"));
+
+        let err = graph(&[("a", &["missing"])]).unwrap_err().to_string();
+        assert!(err.contains("unknown step 'missing'"));
+
+        let err = graph(&[("a", &["a"])]).unwrap_err().to_string();
+        assert!(err.contains("cannot need itself"));
+
+        let err = graph(&[("a", &["c"]), ("b", &["a"]), ("c", &["b"]), ("d", &[])])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("cycle: a, b, c"));
+    }
+
+    #[test]
+    fn test_run_graph_respects_needs() {
+        let graph = graph(&[
+            ("build", &[]),
+            ("lint", &[]),
+            ("test", &["build"]),
+            ("deploy", &["test", "lint"]),
+        ])
+        .unwrap();
+
+        let order = Mutex::new(Vec::new());
+        let outcome = run_graph(&graph, 4, |task| {
+            order.lock().unwrap().push(graph.id(task).to_string());
+            Ok(())
+        });
+
+        assert!(outcome.is_success());
+        let order = order.into_inner().unwrap();
+        let pos = |id: &str| order.iter().position(|o| o == id).unwrap();
+        assert!(pos("build") < pos("test"));
+        assert!(pos("test") < pos("deploy"));
+        assert!(pos("lint") < pos("deploy"));
+    }
+
+    #[test]
+    fn test_run_graph_limits_concurrency() {
+        let graph = graph(&[("a", &[]), ("b", &[]), ("c", &[]), ("d", &[])]).unwrap();
+        let active = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+
+        let outcome = run_graph(&graph, 2, |_| {
+            let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+            peak.fetch_max(now, Ordering::SeqCst);
+            thread::sleep(Duration::from_millis(20));
+            active.fetch_sub(1, Ordering::SeqCst);
+            Ok(())
+        });
+
+        assert!(outcome.is_success());
+        assert_eq!(outcome.completed.len(), 4);
+        assert!(peak.load(Ordering::SeqCst) <= 2);
+    }
+
+    #[test]
+    fn test_run_graph_skips_dependents_after_failure() {
+        let graph = graph(&[("build", &[]), ("test", &["build"]), ("deploy", &["test"])]).unwrap();
+
+        let outcome = run_graph(&graph, 2, |task| {
+            if graph.id(task) == "build" {
+                Err(anyhow!("compile error"))
+            } else {
+                Ok(())
+            }
+        });
+
+        assert!(!outcome.is_success());
+        assert_eq!(outcome.failed.len(), 1);
+        assert_eq!(outcome.failed[0].0, 0);
+        assert_eq!(outcome.skipped, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_run_graph_runs_always_run_tasks_after_failure() {
+        let mut graph = graph(&[
+            ("build", &[]),
+            ("test", &["build"]),
+            ("cleanup", &["test"]),
+            ("report", &[]),
+        ])
+        .unwrap();
+        graph.set_always_run(2);
+
+        let ran = Mutex::new(Vec::new());
+        let outcome = run_graph(&graph, 1, |task| {
+            ran.lock().unwrap().push(graph.id(task).to_string());
+            if graph.id(task) == "build" {
+                Err(anyhow!("compile error"))
+            } else {
+                Ok(())
+            }
+        });
+
+        assert_eq!(ran.into_inner().unwrap(), vec!["build", "cleanup"]);
+        assert_eq!(outcome.completed, vec![2]);
+        assert_eq!(outcome.failed.len(), 1);
+        let mut skipped = outcome.skipped.clone();
+        skipped.sort();
+        assert_eq!(skipped, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_run_graph_reports_panics_as_failures() {
+        let graph = graph(&[("a", &[])]).unwrap();
+        let outcome = run_graph(&graph, 1, |_| panic!("boom"));
+        assert_eq!(outcome.failed.len(), 1);
+    }
+}
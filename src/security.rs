@@ -1,60 +1,308 @@
 use anyhow::Result;
-use std::path::Path;
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path, PathBuf};
 use url;
 
+use crate::models::SecurityPolicy;
+
 /// Represents the security permissions required for plugin execution
 #[derive(Debug, Clone)]
 pub struct PluginPermissions {
+    /// Relative paths in a manifest are resolved against this
+    pub project_root: PathBuf,
     pub file_read: Vec<String>,
     pub file_write: Vec<String>,
     pub env_access: bool,
+
+    /// When set, only these variables are readable (`--allow-env=A,B`), whatever `env_access` says
+    pub env_vars: Vec<String>,
     pub network: Vec<String>,
     pub run_commands: Vec<String>,
+
+    /// Requested permissions that failed validation and weren't granted
+    pub violations: Vec<PermissionViolation>,
+
+    /// Risky permissions that were granted anyway, e.g. under `allow_insecure_network`
+    pub notices: Vec<String>,
+
+    /// Project rules applied on top of the built-in validation
+    pub policy: SecurityPolicy,
+}
+
+/// Which kind of manifest permission a violation refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PermissionKind {
+    Read,
+    Write,
+    Network,
+    Run,
+}
+
+impl PermissionKind {
+    /// Phrase used in prompts, e.g. "network access to internal.corp.example"
+    pub fn describe(self, value: &str) -> String {
+        match self {
+            PermissionKind::Read => format!("read access to {}", value),
+            PermissionKind::Write => format!("write access to {}", value),
+            PermissionKind::Network => format!("network access to {}", value),
+            PermissionKind::Run => format!("to run `{}`", value),
+        }
+    }
+}
+
+/// A permission requested in manifest.toml that security validation blocked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionViolation {
+    pub kind: PermissionKind,
+    pub value: String,
+    pub reason: String,
+
+    /// Whether the user may grant it anyway (malformed requests never can be)
+    pub overridable: bool,
+}
+
+/// Why a validator refused a requested permission
+#[derive(Debug)]
+struct Rejection {
+    reason: String,
+    overridable: bool,
+}
+
+impl Rejection {
+    /// Well-formed but risky; the user may still approve it
+    fn risky(reason: String) -> Self {
+        Self {
+            reason,
+            overridable: true,
+        }
+    }
+
+    /// Malformed or never safe to grant
+    fn invalid(reason: String) -> Self {
+        Self {
+            reason,
+            overridable: false,
+        }
+    }
+}
+
+/// Unix and macOS system locations plugins aren't granted without approval
+const DANGEROUS_UNIX_DIRS: &[&str] = &[
+    "/etc",
+    "/root",
+    "/sys",
+    "/proc",
+    "/dev",
+    "/tmp",
+    "/boot",
+    "/usr/bin",
+    "/usr/sbin",
+    "/bin",
+    "/sbin",
+    "/System",
+    "/Library",
+    "/Applications",
+    // Where macOS's /etc and /tmp symlinks lead
+    "/private/etc",
+    "/private/tmp",
+];
+
+/// Windows system locations, on any drive
+const DANGEROUS_WINDOWS_DIRS: &[&str] = &[
+    "/Windows",
+    "/Program Files",
+    "/Program Files (x86)",
+    "/ProgramData",
+    "/Users/Administrator",
+    "/Users/Default",
+    "/Users/Public",
+    "/Temp",
+    "/Recovery",
+    "/$Recycle.Bin",
+    "/System Volume Information",
+];
+
+/// A path in its plain form, without the `\\?\` prefix Windows canonicalization adds
+/// (`\\?\C:\dir` is `C:\dir`, `\\?\UNC\server\share` is `\\server\share`)
+fn simplified_path(path: &str) -> String {
+    if let Some(share) = path.strip_prefix(r"\\?\UNC\") {
+        return format!(r"\\{}", share);
+    }
+    match path.strip_prefix(r"\\?\") {
+        Some(rest) if rest.as_bytes().get(1) == Some(&b':') => rest.to_string(),
+        _ => path.to_string(),
+    }
+}
+
+/// Whether a path is in Windows form: a drive letter, a UNC or device path, backslash
+/// separators, or any path when running on Windows
+fn is_windows_path(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    cfg!(windows)
+        || (bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':')
+        || path.contains('\\')
+}
+
+/// Where `path` really points: symlinks followed as far as the path exists, and `.` and `..`
+/// applied to whatever doesn't exist yet
+fn resolve_path(path: &Path) -> PathBuf {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        if let Ok(canonical) = existing.canonicalize() {
+            return missing.iter().rev().fold(canonical, |mut resolved, part| {
+                match part {
+                    Component::ParentDir => {
+                        resolved.pop();
+                    }
+                    Component::Normal(name) => resolved.push(name),
+                    _ => {}
+                }
+                resolved
+            });
+        }
+        match (existing.parent(), existing.components().next_back()) {
+            (Some(parent), Some(last)) => {
+                missing.push(last);
+                existing = parent;
+            }
+            // Nothing on the path exists; resolve it lexically
+            _ => return lexically_normalized(path),
+        }
+    }
+}
+
+fn lexically_normalized(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Whether `path` is `dir` or somewhere inside it, comparing whole components
+fn is_within(path: &str, dir: &str, case_insensitive: bool) -> bool {
+    let (path, dir) = if case_insensitive {
+        (path.to_lowercase(), dir.to_lowercase())
+    } else {
+        (path.to_string(), dir.to_string())
+    };
+    Path::new(&path).starts_with(Path::new(&dir))
 }
 
 /// Security validation functions
 impl PluginPermissions {
     /// Validate and sanitize a file path to prevent dangerous access
-    fn validate_file_path(path: &str) -> Result<String, String> {
+    fn validate_file_path(path: &str) -> Result<String, Rejection> {
         // Block empty paths
         if path.trim().is_empty() {
-            return Err("Empty path not allowed".to_string());
-        }
-
-        // Block path traversal attempts
-        if path.contains("..") {
-            return Err(format!("Path traversal not allowed: {}", path));
-        }
-
-        // Block access to sensitive system directories
-        let dangerous_paths = [
-            "/etc/",
-            "/root/",
-            "/sys/",
-            "/proc/",
-            "/dev/",
-            "/tmp/",
-            "/boot/",
-            "/usr/bin/",
-            "/usr/sbin/",
-            "/bin/",
-            "/sbin/",
-            "C:\\Windows\\",
-            "C:\\Program Files\\",
-            "C:\\Users\\",
-            "C:\\temp\\",
-            "/System/",
-            "/Library/",
-            "/Applications/",
-        ];
+            return Err(Rejection::invalid("Empty path not allowed".to_string()));
+        }
 
-        for dangerous in &dangerous_paths {
-            if path.starts_with(dangerous) {
-                return Err(format!("Access to system directory not allowed: {}", path));
+        let path = simplified_path(path);
+        if is_windows_path(&path) {
+            Self::check_windows_path(&path)?;
+        } else {
+            // APFS is case-insensitive by default, so /system/ is /System/ there
+            let case_insensitive = cfg!(target_os = "macos");
+            if DANGEROUS_UNIX_DIRS
+                .iter()
+                .any(|dir| is_within(&path, dir, case_insensitive))
+            {
+                return Err(Rejection::risky(format!(
+                    "Access to system directory not allowed: {}",
+                    path
+                )));
             }
         }
 
-        Ok(path.to_string())
+        Ok(path)
+    }
+
+    /// Check where a path really leads: relative paths are resolved against the project root,
+    /// `..` and symlinks are followed, and the result must stay inside the project unless the
+    /// manifest named an absolute location outside it
+    fn check_resolved_path(&self, path: &str) -> Result<(), Rejection> {
+        // Windows paths can only be resolved on Windows
+        if is_windows_path(path) && !cfg!(windows) {
+            return Ok(());
+        }
+
+        let project_root = resolve_path(&self.project_root);
+        let resolved = resolve_path(&self.project_root.join(path));
+        if resolved.starts_with(&project_root) {
+            return Ok(());
+        }
+
+        let requested = Path::new(path);
+        if requested
+            .components()
+            .any(|c| c == std::path::Component::ParentDir)
+        {
+            return Err(Rejection::invalid(format!(
+                "Path traversal outside the project not allowed: {}",
+                path
+            )));
+        }
+
+        let resolved = simplified_path(&resolved.to_string_lossy());
+        if requested.is_relative() {
+            return Err(Rejection::risky(format!(
+                "Path leads outside the project through a symlink: {} → {}",
+                path, resolved
+            )));
+        }
+        Self::validate_file_path(&resolved).map_err(|rejection| {
+            Rejection::risky(format!("{} (resolved from {})", rejection.reason, path))
+        })?;
+        Ok(())
+    }
+
+    /// Windows paths: drive letters, UNC shares and device paths, compared case-insensitively
+    fn check_windows_path(path: &str) -> Result<(), Rejection> {
+        let normalized = path.replace('\\', "/");
+        if normalized.starts_with("//./") || normalized.starts_with("//?/") {
+            return Err(Rejection::invalid(format!("Device path not allowed: {}", path)));
+        }
+        if normalized.starts_with("//") {
+            return Err(Rejection::risky(format!(
+                "Access to network share not allowed: {}",
+                path
+            )));
+        }
+
+        // The part after the drive letter; a path starting with a separator is on the current drive
+        let rooted = match normalized.as_bytes() {
+            [drive, b':', rest @ ..] if drive.is_ascii_alphabetic() => {
+                if rest.first() != Some(&b'/') {
+                    return Err(Rejection::invalid(format!(
+                        "Drive-relative path not allowed: {}",
+                        path
+                    )));
+                }
+                &normalized[2..]
+            }
+            [b'/', ..] => normalized.as_str(),
+            _ => return Ok(()),
+        };
+
+        if DANGEROUS_WINDOWS_DIRS
+            .iter()
+            .any(|dir| is_within(rooted, dir, true))
+        {
+            return Err(Rejection::risky(format!(
+                "Access to system directory not allowed: {}",
+                path
+            )));
+        }
+        Ok(())
     }
 
     /// Expand $VAR and ${VAR} environment variables in a path string.
@@ -117,25 +365,28 @@ impl PluginPermissions {
     }
 
     /// Validate network domain/IP to prevent wildcards and dangerous access
-    fn validate_network_domain(domain: &str) -> Result<String, String> {
+    fn validate_network_domain(domain: &str) -> Result<String, Rejection> {
         // Normalize input: trim whitespace and convert to lowercase
         let normalized_domain = domain.trim().to_lowercase();
 
         // Block empty domains (after normalization)
         if normalized_domain.is_empty() {
-            return Err("Empty domain not allowed".to_string());
+            return Err(Rejection::invalid("Empty domain not allowed".to_string()));
         }
 
         // Block wildcard patterns
         if normalized_domain.contains('*') {
-            return Err(format!("Wildcard domains not allowed: {}", domain));
+            return Err(Rejection::invalid(format!("Wildcard domains not allowed: {}", domain)));
         }
 
         // Block dangerous IPs that could grant broad access (except localhost if allowed)
         let dangerous_ips = ["0.0.0.0", "::"];
         for dangerous in &dangerous_ips {
             if normalized_domain == *dangerous {
-                return Err(format!("Broad network access not allowed: {}", domain));
+                return Err(Rejection::invalid(format!(
+                    "Broad network access not allowed: {}",
+                    domain
+                )));
             }
         }
 
@@ -165,30 +416,31 @@ impl PluginPermissions {
 
         for metadata_host in &metadata_hosts {
             if normalized_domain == *metadata_host {
-                return Err(format!(
+                return Err(Rejection::invalid(format!(
                     "Cloud metadata service access not allowed: {}",
                     domain
-                ));
+                )));
             }
         }
 
         // Block private network ranges (could be used for internal attacks)
-        if normalized_domain.starts_with("192.168.")
-            || normalized_domain.starts_with("10.")
-            || normalized_domain.starts_with("172.")
-        {
-            return Err(format!("Private network access not allowed: {}", domain));
+        if Self::is_private_network(&normalized_domain) {
+            return Err(Rejection::risky(format!("Private network access not allowed: {}", domain)));
         }
 
         // Return the normalized domain
         Ok(normalized_domain)
     }
 
+    fn is_private_network(domain: &str) -> bool {
+        domain.starts_with("192.168.") || domain.starts_with("10.") || domain.starts_with("172.")
+    }
+
     /// Validate command to prevent injection and dangerous operations
-    fn validate_command(command: &str) -> Result<String, String> {
+    fn validate_command(command: &str) -> Result<String, Rejection> {
         // Block empty commands
         if command.trim().is_empty() {
-            return Err("Empty command not allowed".to_string());
+            return Err(Rejection::invalid("Empty command not allowed".to_string()));
         }
 
         // Always allow "mis" command (needed for runPlugin API)
@@ -198,17 +450,20 @@ impl PluginPermissions {
 
         // Block commands with arguments (potential injection)
         if command.contains(' ') || command.contains('\t') {
-            return Err(format!("Commands with arguments not allowed: {}", command));
+            return Err(Rejection::invalid(format!(
+                "Commands with arguments not allowed: {}",
+                command
+            )));
         }
 
         // Block shell operators that could enable chaining/injection
         let dangerous_chars = ['&', '|', ';', '>', '<', '`', '$', '(', ')', '{', '}'];
         for dangerous in &dangerous_chars {
             if command.contains(*dangerous) {
-                return Err(format!(
+                return Err(Rejection::invalid(format!(
                     "Command contains dangerous characters: {}",
                     command
-                ));
+                )));
             }
         }
 
@@ -220,7 +475,7 @@ impl PluginPermissions {
 
         for dangerous in &dangerous_commands {
             if command == *dangerous {
-                return Err(format!("Dangerous command not allowed: {}", command));
+                return Err(Rejection::risky(format!("Dangerous command not allowed: {}", command)));
             }
         }
 
@@ -232,6 +487,7 @@ impl PluginPermissions {
     /// Create safe conservative defaults that work for most plugins
     pub fn safe_defaults(project_root: &Path) -> Self {
         Self {
+            project_root: project_root.to_path_buf(),
             // Allow reading project files and .makeitso directory
             file_read: vec![
                 project_root.to_string_lossy().to_string(),
@@ -241,10 +497,14 @@ impl PluginPermissions {
             file_write: vec![project_root.to_string_lossy().to_string()],
             // Allow environment access (needed for many plugins)
             env_access: true,
+            env_vars: vec![],
             // No network access by default (including localhost - must be explicit)
             network: vec![],
             // Allow "mis" command by default (needed for runPlugin API)
             run_commands: vec!["mis".to_string()],
+            violations: vec![],
+            notices: vec![],
+            policy: SecurityPolicy::default(),
         }
     }
 
@@ -262,8 +522,10 @@ impl PluginPermissions {
             args.push(format!("--allow-write={}", self.file_write.join(",")));
         }
 
-        // Environment access
-        if self.env_access {
+        // Environment access, narrowed to the declared variables if there are any
+        if !self.env_vars.is_empty() {
+            args.push(format!("--allow-env={}", self.env_vars.join(",")));
+        } else if self.env_access {
             args.push("--allow-env".to_string());
         }
 
@@ -283,76 +545,159 @@ impl PluginPermissions {
     /// Add additional file read permissions with security validation
     pub fn allow_read<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
         let path_str = Self::expand_env_vars(&path.as_ref().to_string_lossy());
-        match Self::validate_file_path(&path_str) {
-            Ok(validated_path) => {
-                // Avoid duplicates
-                if !self.file_read.contains(&validated_path) {
-                    self.file_read.push(validated_path);
-                }
-            }
-            Err(err) => {
-                eprintln!("⚠️  Security warning: Blocked dangerous read path: {}", err);
-                // For v1.0, we silently block dangerous paths rather than panicking
-                // This prevents plugins from accidentally breaking the system
-            }
-        }
-        self
+        let result = Self::validate_file_path(&path_str)
+            .and_then(|validated| self.check_resolved_path(&validated).map(|_| validated));
+        self.record(PermissionKind::Read, path_str, result)
     }
 
     /// Add additional file write permissions with security validation
     pub fn allow_write<P: AsRef<Path>>(&mut self, path: P) -> &mut Self {
         let path_str = Self::expand_env_vars(&path.as_ref().to_string_lossy());
-        match Self::validate_file_path(&path_str) {
-            Ok(validated_path) => {
-                // Avoid duplicates
-                if !self.file_write.contains(&validated_path) {
-                    self.file_write.push(validated_path);
-                }
-            }
-            Err(err) => {
-                eprintln!(
-                    "⚠️  Security warning: Blocked dangerous write path: {}",
-                    err
-                );
-            }
+        let result = Self::validate_file_path(&path_str)
+            .and_then(|validated| self.check_resolved_path(&validated).map(|_| validated));
+        self.record(PermissionKind::Write, path_str, result)
+    }
+
+    /// Grant access to a file the CLI created for this run (context and result files) or
+    /// to the plugin's state directory.
+    /// These paths never come from a manifest, so they skip user-facing path validation.
+    pub fn allow_cli_managed_file<P: AsRef<Path>>(&mut self, path: P, writable: bool) -> &mut Self {
+        let path_str = path.as_ref().to_string_lossy().to_string();
+        if !self.file_read.contains(&path_str) {
+            self.file_read.push(path_str.clone());
+        }
+        if writable && !self.file_write.contains(&path_str) {
+            self.file_write.push(path_str);
         }
         self
     }
 
     /// Add network permissions for specific domains with security validation
     pub fn allow_network<S: AsRef<str>>(&mut self, domain: S) -> &mut Self {
-        let domain_str = domain.as_ref();
-        match Self::validate_network_domain(domain_str) {
-            Ok(validated_domain) => {
-                // Avoid duplicates
-                if !self.network.contains(&validated_domain) {
-                    self.network.push(validated_domain);
-                }
-            }
-            Err(err) => {
-                eprintln!(
-                    "⚠️  Security warning: Blocked dangerous network access: {}",
-                    err
-                );
-            }
-        }
-        self
+        let normalized = domain.as_ref().trim().to_lowercase();
+        let result = if self.network_allowed_by_policy(&normalized) {
+            Ok(normalized.clone())
+        } else if self.policy.allow_insecure_network == Some(true)
+            && Self::is_private_network(&normalized)
+        {
+            self.notices.push(format!(
+                "Granting private network access to {} (insecure network access is enabled)",
+                normalized
+            ));
+            Ok(normalized.clone())
+        } else {
+            Self::validate_network_domain(&normalized)
+        };
+        self.record(PermissionKind::Network, normalized, result)
     }
 
     /// Add permission to run specific commands with security validation
     pub fn allow_run<S: AsRef<str>>(&mut self, command: S) -> &mut Self {
         let command_str = command.as_ref();
-        match Self::validate_command(command_str) {
-            Ok(validated_command) => {
-                // Avoid duplicates
-                if !self.run_commands.contains(&validated_command) {
-                    self.run_commands.push(validated_command);
-                }
+        let result = Self::validate_command(command_str).and_then(|validated| {
+            if self.policy.blocked_commands.contains(&validated) {
+                Err(Rejection::invalid(format!(
+                    "Command blocked by project security policy: {}",
+                    validated
+                )))
+            } else {
+                Ok(validated)
             }
-            Err(err) => {
-                eprintln!("⚠️  Security warning: Blocked dangerous command: {}", err);
+        });
+        self.record(PermissionKind::Run, command_str.to_string(), result)
+    }
+
+    /// Hosts the project's [security] policy explicitly allows
+    fn network_allowed_by_policy(&self, domain: &str) -> bool {
+        if domain.is_empty() || domain.contains('*') {
+            return false;
+        }
+        self.policy
+            .allowed_network
+            .iter()
+            .any(|allowed| allowed.trim().eq_ignore_ascii_case(domain))
+            || (self.policy.allow_private_network == Some(true)
+                && Self::is_private_network(domain))
+    }
+
+    /// Grant a blocked permission the user explicitly approved
+    pub fn grant(&mut self, violation: &PermissionViolation) -> &mut Self {
+        self.violations.retain(|v| v != violation);
+        self.push_unique(violation.kind, violation.value.clone())
+    }
+
+    /// Print every risky grant, and every violation that was not granted (under a strict
+    /// policy those fail the run in `check_strict` instead)
+    pub fn warn_violations(&self) {
+        for notice in &self.notices {
+            errln!("⚠️  Security warning: {}", notice);
+        }
+        if self.policy.strict == Some(true) {
+            return;
+        }
+        for violation in &self.violations {
+            errln!(
+                "⚠️  Security warning: Blocked {}: {}",
+                violation.kind.describe(&violation.value),
+                violation.reason
+            );
+        }
+    }
+
+    /// Under a strict security policy, fail if anything requested is still blocked
+    pub fn check_strict(&self, plugin_name: &str) -> Result<()> {
+        if self.policy.strict != Some(true) || self.violations.is_empty() {
+            return Ok(());
+        }
+        let blocked: Vec<String> = self
+            .violations
+            .iter()
+            .map(|v| format!("   {}: {}", v.kind.describe(&v.value), v.reason))
+            .collect();
+        anyhow::bail!(
+            "🛑 Plugin '{}' requests permissions that security validation blocks:\n{}\n\
+             → Strict security is on (--strict-security or [security] strict), so blocked requests fail the run.\n\
+             → Remove them from the manifest, allow them in [security] in mis.toml, or trust them in .makeitso/trust.toml.",
+            plugin_name,
+            blocked.join("\n")
+        )
+    }
+
+    /// Keep a validated permission, or remember why it was blocked
+    fn record(
+        &mut self,
+        kind: PermissionKind,
+        value: String,
+        result: Result<String, Rejection>,
+    ) -> &mut Self {
+        match result {
+            Ok(validated) => self.push_unique(kind, validated),
+            Err(rejection) => {
+                let violation = PermissionViolation {
+                    kind,
+                    value,
+                    reason: rejection.reason,
+                    overridable: rejection.overridable,
+                };
+                if !self.violations.contains(&violation) {
+                    self.violations.push(violation);
+                }
+                self
             }
         }
+    }
+
+    fn push_unique(&mut self, kind: PermissionKind, value: String) -> &mut Self {
+        let list = match kind {
+            PermissionKind::Read => &mut self.file_read,
+            PermissionKind::Write => &mut self.file_write,
+            PermissionKind::Network => &mut self.network,
+            PermissionKind::Run => &mut self.run_commands,
+        };
+        // Avoid duplicates
+        if !list.contains(&value) {
+            list.push(value);
+        }
         self
     }
 }
@@ -368,14 +713,14 @@ impl PluginPermissions {
 /// - File paths are validated for path traversal and system directory access
 /// - Network domains are validated against localhost, private IPs, and wildcards
 /// - Commands are validated against injection and dangerous operations
-/// - Invalid permissions are blocked with warning messages but don't fail the build
+/// - Invalid permissions are blocked and recorded in `violations` rather than failing the build
 pub fn build_plugin_permissions(
     project_root: &Path,
     plugin_manifest: &crate::models::PluginManifest,
     command_name: &str,
 ) -> Result<PluginPermissions> {
-    // 1. Start with safe defaults
-    let mut permissions = PluginPermissions::safe_defaults(project_root);
+    // 1. Start with safe defaults, adjusted by the user's permissions and security policy
+    let mut permissions = default_plugin_permissions(project_root);
 
     // 2. Apply plugin-level permissions
     if let Some(plugin_perms) = &plugin_manifest.permissions {
@@ -396,12 +741,28 @@ pub fn build_plugin_permissions(
     Ok(permissions)
 }
 
+/// Safe defaults adjusted by the user's `[permissions]` and the project's `[security]` policy,
+/// before anything a manifest requests
+pub fn default_plugin_permissions(project_root: &Path) -> PluginPermissions {
+    let settings = crate::config::settings();
+    let mut permissions = PluginPermissions::safe_defaults(project_root);
+    permissions.policy = settings.security.clone().unwrap_or_default();
+    if let Some(env_access) = permissions.policy.env_access.or(settings
+        .permissions
+        .as_ref()
+        .and_then(|policy| policy.env_access))
+    {
+        permissions.env_access = env_access;
+    }
+    permissions
+}
+
 /// Apply security permissions from manifest configuration to PluginPermissions
 ///
 /// Each permission type is automatically validated through the allow_* methods:
 ///
-/// Dangerous permissions are blocked with warning messages but don't cause failure.
-fn apply_security_permissions(
+/// Dangerous permissions are recorded as violations but don't cause failure.
+pub fn apply_security_permissions(
     permissions: &mut PluginPermissions,
     config_perms: &crate::models::SecurityPermissions,
     context: &str,
@@ -421,6 +782,21 @@ fn apply_security_permissions(
         permissions.env_access = env_access;
     }
 
+    // Apply declared environment variables
+    for name in &config_perms.env_vars {
+        if !is_env_var_name(name) {
+            anyhow::bail!(
+                "🛑 Invalid environment variable '{}' in {} env_vars.\n\
+                 → Use names made of letters, digits and underscores, e.g. AWS_PROFILE.",
+                name,
+                context
+            );
+        }
+        if !permissions.env_vars.contains(name) {
+            permissions.env_vars.push(name.clone());
+        }
+    }
+
     // Apply network permissions
     for domain in &config_perms.network {
         permissions.allow_network(domain);
@@ -434,6 +810,35 @@ fn apply_security_permissions(
     Ok(())
 }
 
+fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Environment variables the plugin and command declare in `env_vars`
+pub fn declared_env_vars(
+    plugin_manifest: &crate::models::PluginManifest,
+    command_name: &str,
+) -> Vec<String> {
+    let command_perms = plugin_manifest
+        .commands
+        .get(command_name)
+        .and_then(|command| command.permissions.as_ref());
+
+    let mut names: Vec<String> = Vec::new();
+    for perms in plugin_manifest.permissions.iter().chain(command_perms) {
+        for name in &perms.env_vars {
+            if !names.contains(name) {
+                names.push(name.clone());
+            }
+        }
+    }
+    names
+}
+
 /// Legacy function for backward compatibility - uses safe defaults only
 pub fn build_plugin_permissions_legacy(project_root: &Path) -> Result<PluginPermissions> {
     // For plugins without manifest-declared permissions, use safe defaults
@@ -445,8 +850,90 @@ pub fn validate_registry_url(url: &str) -> Result<String, String> {
     validate_url_for_git_operations(url, "registry")
 }
 
-/// Core URL validation for git operations (registries)
+/// A registry source that points at a directory on this machine instead of a remote:
+/// `path:../plugins`, or a plain path starting with `/`, `./`, `../` or `~/`
+pub fn local_registry_path(source: &str) -> Option<&str> {
+    let source = source.trim();
+    if let Some(path) = source.strip_prefix("path:") {
+        return Some(path.trim());
+    }
+
+    let bytes = source.as_bytes();
+    let is_drive_path = bytes.len() > 2
+        && bytes[0].is_ascii_alphabetic()
+        && bytes[1] == b':'
+        && matches!(bytes[2], b'\\' | b'/');
+    let is_local = is_drive_path
+        || ["/", "./", "../", "~/", ".\\", "..\\"]
+            .iter()
+            .any(|prefix| source.starts_with(prefix));
+    is_local.then_some(source)
+}
+
+/// Where a local registry lives; relative paths are resolved against the project root
+pub fn resolve_local_registry(path: &str) -> std::path::PathBuf {
+    if let Some(rest) = path.strip_prefix("~/")
+        && let Some(home) = std::env::var_os("HOME")
+    {
+        return Path::new(&home).join(rest);
+    }
+
+    let path = Path::new(path);
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    crate::utils::find_project_root()
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default()
+        .join(path)
+}
+
+/// Validate a registry source. Remote URLs get the usual checks; local directories are
+/// allowed deliberately, but must exist and can't be system directories.
+pub fn validate_registry_source(source: &str) -> Result<String, String> {
+    let Some(path) = local_registry_path(source) else {
+        return validate_registry_url(source);
+    };
+    if path.is_empty() {
+        return Err("Empty registry path not allowed".to_string());
+    }
+
+    let resolved = resolve_local_registry(path);
+    let canonical = resolved
+        .canonicalize()
+        .map_err(|_| format!("Local registry not found: {}", resolved.display()))?;
+    if !canonical.is_dir() {
+        return Err(format!(
+            "Local registry is not a directory: {}",
+            canonical.display()
+        ));
+    }
+
+    let system_dirs = [
+        "/etc", "/proc", "/sys", "/dev", "/boot", "/bin", "/sbin", "/usr/bin", "/usr/sbin",
+    ];
+    if canonical.parent().is_none() || system_dirs.iter().any(|dir| canonical.starts_with(dir)) {
+        return Err(format!(
+            "System directory not allowed as a registry: {}",
+            canonical.display()
+        ));
+    }
+
+    Ok(canonical.to_string_lossy().to_string())
+}
+
+/// Core URL validation for git operations (registries), honouring the configured
+/// `[security] trusted_domains`
 pub fn validate_url_for_git_operations(url: &str, context: &str) -> Result<String, String> {
+    validate_git_url_with_policy(url, context, crate::config::settings().security.as_ref())
+}
+
+/// `validate_url_for_git_operations` against an explicit security policy
+pub fn validate_git_url_with_policy(
+    url: &str,
+    context: &str,
+    policy: Option<&SecurityPolicy>,
+) -> Result<String, String> {
     // Check for empty or whitespace-only URLs
     if url.trim().is_empty() {
         return Err(format!("Empty {} URL not allowed", context));
@@ -486,15 +973,21 @@ pub fn validate_url_for_git_operations(url: &str, context: &str) -> Result<Strin
     }
 
     // Validate the host (this gives more specific error messages for network issues)
-    validate_host_for_external_access(host, context)?;
+    let trusted = policy.and_then(|policy| trusted_domain_origin(policy, host));
+    validate_trusted_host(host, context, trusted.as_deref())?;
 
     // Then validate remaining schemes
     match scheme {
         "http" => {
-            // Allow HTTP only for certain trusted domains
-            if !is_trusted_git_domain(host) {
-                return Err("HTTPS required for remote repositories (HTTP is insecure)".to_string());
-            }
+            // Allow HTTP only for domains the organization trusts
+            let Some(origin) = &trusted else {
+                return Err(format!(
+                    "HTTPS required for remote repositories (HTTP is insecure); \
+                     add {} to [security] trusted_domains to allow it",
+                    host
+                ));
+            };
+            debugln!("Allowing HTTP for {}: {}", host, origin);
         }
         "https" | "ssh" | "git" => {
             // These schemes are generally safe
@@ -567,12 +1060,43 @@ pub fn is_private_ip(host: &str) -> bool {
     false
 }
 
-/// Check if a domain is trusted for git operations (allows HTTP)
-pub fn is_trusted_git_domain(_host: &str) -> bool {
-    // For now, we don't have any domains that we trust with HTTP
-    // All git operations should use HTTPS for security
-    // This function exists for future extensibility
-    false
+/// Where `host` was trusted, if it's in the policy's `trusted_domains`: the matching entry and
+/// the config file it came from, for messages
+pub fn trusted_domain_origin(policy: &SecurityPolicy, host: &str) -> Option<String> {
+    let entry = policy
+        .trusted_domains
+        .iter()
+        .find(|entry| host_matches(entry, host))?;
+    Some(match policy.trusted_domain_origins.get(entry) {
+        Some(path) => format!("trusted_domains entry '{}' in {}", entry, path.display()),
+        None => format!("trusted_domains entry '{}'", entry),
+    })
+}
+
+/// Whether `host` is `entry`, or a subdomain of it when the entry is `*.parent`
+pub fn host_matches(entry: &str, host: &str) -> bool {
+    let entry = entry.trim().to_lowercase();
+    let host = host.trim().to_lowercase();
+    match entry.strip_prefix("*.") {
+        Some(parent) => host
+            .strip_suffix(parent)
+            .is_some_and(|sub| sub.len() > 1 && sub.ends_with('.')),
+        None => !entry.is_empty() && host == entry,
+    }
+}
+
+/// `validate_host_for_external_access`, except that trusted hosts may be on a private network.
+/// Loopback and metadata addresses stay blocked, saying which entry didn't lift the rule.
+fn validate_trusted_host(host: &str, context: &str, trusted: Option<&str>) -> Result<(), String> {
+    let Some(origin) = trusted else {
+        return validate_host_for_external_access(host, context);
+    };
+    if is_private_ip(host) {
+        debugln!("Allowing private address {}: {}", host, origin);
+        return Ok(());
+    }
+    validate_host_for_external_access(host, context)
+        .map_err(|e| format!("{} (despite {}, which doesn't lift this rule)", e, origin))
 }
 
 /// Validate a Deno dependency URL for security
@@ -580,8 +1104,34 @@ pub fn validate_deno_dependency_url(url: &str) -> Result<String, String> {
     validate_url_for_dependencies(url)
 }
 
-/// Core URL validation for dependencies
+/// Check every `[deno_dependencies]` URL of a plugin before it's installed or run
+pub fn validate_plugin_dependencies(plugin_manifest: &crate::models::PluginManifest) -> Result<()> {
+    for (dep_name, dep_url) in &plugin_manifest.deno_dependencies {
+        if let Err(security_error) = validate_deno_dependency_url(dep_url) {
+            anyhow::bail!(
+                "🛑 Security validation failed for dependency '{}' ({}) of plugin '{}': {}\n\
+                 → Deno dependencies must use secure HTTPS URLs from trusted sources, and hosts \
+                 allowed by [security] dependency_hosts if the project sets it.",
+                dep_name,
+                dep_url,
+                plugin_manifest.plugin.name,
+                security_error
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Core URL validation for dependencies, honouring the configured `[security] trusted_domains`
 pub fn validate_url_for_dependencies(url: &str) -> Result<String, String> {
+    validate_dependency_url_with_policy(url, crate::config::settings().security.as_ref())
+}
+
+/// `validate_url_for_dependencies` against an explicit security policy
+pub fn validate_dependency_url_with_policy(
+    url: &str,
+    policy: Option<&SecurityPolicy>,
+) -> Result<String, String> {
     // Check for empty or whitespace-only URLs
     if url.trim().is_empty() {
         return Err("Empty dependency URL not allowed".to_string());
@@ -619,13 +1169,36 @@ pub fn validate_url_for_dependencies(url: &str) -> Result<String, String> {
     }
 
     // Validate the host (this gives more specific error messages for network issues)
-    validate_host_for_external_access(host, "dependency")?;
+    let trusted = policy.and_then(|policy| trusted_domain_origin(policy, host));
+    validate_trusted_host(host, "dependency", trusted.as_deref())?;
+
+    // An organization may only allow a few hosts, e.g. deno.land and an internal CDN
+    if let Some(allowed) = policy.and_then(|policy| policy.dependency_hosts.as_ref())
+        && !allowed.iter().any(|entry| host_matches(entry, host))
+    {
+        return Err(format!(
+            "Dependency host '{}' is not in [security] dependency_hosts (allowed: {})",
+            host,
+            if allowed.is_empty() {
+                "none".to_string()
+            } else {
+                allowed.join(", ")
+            }
+        ));
+    }
 
     // Then validate remaining schemes
     match scheme {
         "http" => {
-            // Require HTTPS for all remote dependencies (stricter than git)
-            return Err("HTTPS required for remote dependencies (HTTP is insecure)".to_string());
+            // Require HTTPS for remote dependencies, unless the organization trusts the host
+            let Some(origin) = &trusted else {
+                return Err(format!(
+                    "HTTPS required for remote dependencies (HTTP is insecure); \
+                     add {} to [security] trusted_domains to allow it",
+                    host
+                ));
+            };
+            debugln!("Allowing HTTP for {}: {}", host, origin);
         }
         "https" => {
             // HTTPS is safe for dependencies
@@ -644,7 +1217,10 @@ pub fn validate_url_for_dependencies(url: &str) -> Result<String, String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indexmap::IndexMap;
+    use std::collections::HashMap;
     use std::path::PathBuf;
+    use tempfile::tempdir;
 
     #[test]
     fn test_safe_defaults() {
@@ -774,6 +1350,23 @@ mod tests {
         assert!(args.contains(&"--allow-run=mis,docker".to_string()));
     }
 
+    #[test]
+    fn test_allow_cli_managed_file_bypasses_path_validation() {
+        let mut perms = PluginPermissions::safe_defaults(Path::new("/project"));
+        let context_file = std::env::temp_dir().join("mis-context-1.json");
+        let result_file = std::env::temp_dir().join("mis-result-1.json");
+
+        perms.allow_cli_managed_file(&context_file, false);
+        perms.allow_cli_managed_file(&result_file, true);
+
+        let context_str = context_file.to_string_lossy().to_string();
+        let result_str = result_file.to_string_lossy().to_string();
+        assert!(perms.file_read.contains(&context_str));
+        assert!(!perms.file_write.contains(&context_str));
+        assert!(perms.file_read.contains(&result_str));
+        assert!(perms.file_write.contains(&result_str));
+    }
+
     #[test]
     fn test_build_plugin_permissions() {
         let project_root = PathBuf::from("/test/project");
@@ -793,7 +1386,6 @@ mod tests {
     #[test]
     fn test_plugin_level_permissions() {
         use crate::models::{PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
@@ -801,6 +1393,7 @@ mod tests {
             file_read: vec!["./config".to_string(), "./data".to_string()],
             file_write: vec!["./output".to_string()],
             env_access: Some(false), // Override default
+            env_vars: vec![],
             network: vec!["api.github.com".to_string()],
             run_commands: vec!["git".to_string()],
         };
@@ -811,10 +1404,18 @@ mod tests {
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: Some(plugin_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let result = build_plugin_permissions(&project_root, &manifest, "test-command");
@@ -839,10 +1440,51 @@ mod tests {
         assert!(permissions.run_commands.contains(&"git".to_string()));
     }
 
+    #[test]
+    fn test_env_vars_narrow_env_access() {
+        use crate::models::PluginManifest;
+
+        let project_root = PathBuf::from("/test/project");
+        let manifest: PluginManifest = toml::from_str(
+            r#"
+[plugin]
+name = "deploy"
+version = "1.0.0"
+
+[permissions]
+env_vars = ["AWS_PROFILE", "CI"]
+
+[commands.apply]
+script = "./apply.ts"
+
+[commands.apply.permissions]
+env_vars = ["CI", "TF_TOKEN"]
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            declared_env_vars(&manifest, "apply"),
+            vec!["AWS_PROFILE", "CI", "TF_TOKEN"]
+        );
+
+        let permissions = build_plugin_permissions(&project_root, &manifest, "apply").unwrap();
+        let args = permissions.to_deno_args();
+        assert!(args.contains(&"--allow-env=AWS_PROFILE,CI,TF_TOKEN".to_string()));
+        assert!(!args.contains(&"--allow-env".to_string()));
+
+        let invalid = crate::models::SecurityPermissions {
+            env_vars: vec!["AWS-PROFILE".to_string()],
+            ..Default::default()
+        };
+        let mut permissions = PluginPermissions::safe_defaults(&project_root);
+        let err = apply_security_permissions(&mut permissions, &invalid, "plugin-level").unwrap_err();
+        assert!(err.to_string().contains("AWS-PROFILE"), "{}", err);
+    }
+
     #[test]
     fn test_command_level_permissions_extend_plugin() {
         use crate::models::{PluginCommand, PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
@@ -860,7 +1502,7 @@ mod tests {
             ..Default::default()
         };
 
-        let mut commands = HashMap::new();
+        let mut commands = IndexMap::new();
         commands.insert(
             "deploy".to_string(),
             PluginCommand {
@@ -869,6 +1511,11 @@ mod tests {
                 instructions: None,
                 args: None,
                 permissions: Some(command_permissions),
+                hooks: None,
+                test: None,
+                exclusive: false,
+                confirm: None,
+                deprecated: None,
             },
         );
 
@@ -878,10 +1525,18 @@ mod tests {
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
             commands,
-            deno_dependencies: HashMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: Some(plugin_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let result = build_plugin_permissions(&project_root, &manifest, "deploy");
@@ -906,7 +1561,6 @@ mod tests {
     #[test]
     fn test_command_without_permissions_inherits_plugin() {
         use crate::models::{PluginCommand, PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
@@ -917,7 +1571,7 @@ mod tests {
             ..Default::default()
         };
 
-        let mut commands = HashMap::new();
+        let mut commands = IndexMap::new();
         commands.insert(
             "status".to_string(),
             PluginCommand {
@@ -926,6 +1580,11 @@ mod tests {
                 instructions: None,
                 args: None,
                 permissions: None, // No command-specific permissions
+                hooks: None,
+                test: None,
+                exclusive: false,
+                confirm: None,
+                deprecated: None,
             },
         );
 
@@ -935,10 +1594,18 @@ mod tests {
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
             commands,
-            deno_dependencies: HashMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: Some(plugin_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let result = build_plugin_permissions(&project_root, &manifest, "status");
@@ -958,11 +1625,10 @@ mod tests {
     #[test]
     fn test_no_permissions_declared_uses_safe_defaults() {
         use crate::models::{PluginCommand, PluginManifest, PluginMeta};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
-        let mut commands = HashMap::new();
+        let mut commands = IndexMap::new();
         commands.insert(
             "basic".to_string(),
             PluginCommand {
@@ -971,6 +1637,11 @@ mod tests {
                 instructions: None,
                 args: None,
                 permissions: None,
+                hooks: None,
+                test: None,
+                exclusive: false,
+                confirm: None,
+                deprecated: None,
             },
         );
 
@@ -980,10 +1651,18 @@ mod tests {
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
             commands,
-            deno_dependencies: HashMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: None, // No plugin-level permissions
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let result = build_plugin_permissions(&project_root, &manifest, "basic");
@@ -1001,7 +1680,6 @@ mod tests {
     #[test]
     fn test_security_validation_still_blocks_dangerous_permissions() {
         use crate::models::{PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
@@ -1018,10 +1696,18 @@ mod tests {
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: Some(dangerous_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let result = build_plugin_permissions(&project_root, &manifest, "test-command");
@@ -1164,6 +1850,99 @@ script = "./test.ts"
         }
     }
 
+    #[test]
+    fn test_windows_system_paths() {
+        let blocked = [
+            r"D:\Windows\System32\drivers\etc\hosts",
+            r"c:\WINDOWS\system32",
+            "C:/Program Files/Git/bin",
+            r"C:\Program Files (x86)\Steam",
+            r"C:\ProgramData\ssh\sshd_config",
+            r"\\?\C:\Windows\System32\config\SAM",
+            r"C:\Users\Public\Documents",
+            r"\Windows\System32",
+        ];
+        for path in blocked {
+            let rejection = PluginPermissions::validate_file_path(path).unwrap_err();
+            assert!(rejection.overridable, "{} should be approvable", path);
+            assert!(rejection.reason.contains("system directory"), "{}", path);
+        }
+
+        let rejection = PluginPermissions::validate_file_path(r"\\fileserver\c$\secrets").unwrap_err();
+        assert!(rejection.reason.contains("network share"));
+        let rejection = PluginPermissions::validate_file_path(r"\\?\UNC\fileserver\share").unwrap_err();
+        assert!(rejection.reason.contains("network share"));
+        let rejection = PluginPermissions::validate_file_path(r"\\.\PhysicalDrive0").unwrap_err();
+        assert!(!rejection.overridable);
+        let rejection = PluginPermissions::validate_file_path("C:Windows").unwrap_err();
+        assert!(!rejection.overridable);
+
+        // Only whole components match, and the user's own profile is fine
+        for path in [
+            r"C:\Users\dev\projects\app",
+            r"C:\WindowsApps-backup\data",
+            r"D:\work\Temp2",
+            r"src\generated",
+        ] {
+            assert!(PluginPermissions::validate_file_path(path).is_ok(), "{}", path);
+        }
+        assert_eq!(
+            PluginPermissions::validate_file_path(r"\\?\D:\work\app").unwrap(),
+            r"D:\work\app"
+        );
+    }
+
+    #[test]
+    fn test_paths_are_resolved_against_the_project() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project_root = temp_dir.path().join("project");
+        std::fs::create_dir_all(project_root.join("sub")).unwrap();
+        let mut permissions = PluginPermissions::safe_defaults(&project_root);
+
+        // Dots in names and `..` that stays inside the project are fine
+        permissions.allow_read("my..dir/data");
+        permissions.allow_read("./sub/../config");
+        assert!(permissions.file_read.contains(&"my..dir/data".to_string()));
+        assert!(permissions.file_read.contains(&"./sub/../config".to_string()));
+
+        permissions.allow_write("../sibling");
+        permissions.allow_write("sub/../../../elsewhere");
+        assert_eq!(permissions.violations.len(), 2);
+        assert!(permissions.violations.iter().all(|v| !v.overridable
+            && v.reason.starts_with("Path traversal outside the project")));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlinks_out_of_the_project_need_approval() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project_root = temp_dir.path().join("project");
+        let outside = temp_dir.path().join("outside");
+        std::fs::create_dir_all(&project_root).unwrap();
+        std::fs::create_dir_all(&outside).unwrap();
+        std::os::unix::fs::symlink(&outside, project_root.join("shared")).unwrap();
+        std::os::unix::fs::symlink("/etc", project_root.join("config")).unwrap();
+        let mut permissions = PluginPermissions::safe_defaults(&project_root);
+
+        permissions.allow_read("shared/data");
+        permissions.allow_read("config/passwd");
+        assert!(!permissions.file_read.contains(&"shared/data".to_string()));
+        assert!(!permissions.file_read.contains(&"config/passwd".to_string()));
+
+        let reasons: Vec<_> = permissions.violations.iter().map(|v| &v.reason).collect();
+        assert!(reasons[0].starts_with("Path leads outside the project through a symlink"));
+        assert!(reasons[1].ends_with("config/passwd → /etc/passwd"));
+        assert!(permissions.violations.iter().all(|v| v.overridable));
+    }
+
+    #[test]
+    fn test_unix_system_paths_match_whole_components() {
+        assert!(PluginPermissions::validate_file_path("/etc").is_err());
+        assert!(PluginPermissions::validate_file_path("/tmp/backdoor").is_err());
+        assert!(PluginPermissions::validate_file_path("/etcetera/config").is_ok());
+        assert!(PluginPermissions::validate_file_path("/tmpdata/cache").is_ok());
+    }
+
     #[test]
     fn test_path_traversal_attempts() {
         let project_root = PathBuf::from("/test/project");
@@ -1422,7 +2201,6 @@ script = "./test.ts"
     #[test]
     fn test_command_specific_permissions_extend_plugin_permissions() {
         use crate::models::{PluginCommand, PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
@@ -1438,7 +2216,7 @@ script = "./test.ts"
             ..Default::default()
         };
 
-        let mut commands = HashMap::new();
+        let mut commands = IndexMap::new();
         commands.insert(
             "deploy".to_string(),
             PluginCommand {
@@ -1447,6 +2225,11 @@ script = "./test.ts"
                 instructions: None,
                 args: None,
                 permissions: Some(command_permissions),
+                hooks: None,
+                test: None,
+                exclusive: false,
+                confirm: None,
+                deprecated: None,
             },
         );
 
@@ -1456,10 +2239,18 @@ script = "./test.ts"
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
             commands,
-            deno_dependencies: HashMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: Some(plugin_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let result = build_plugin_permissions(&project_root, &manifest, "deploy");
@@ -1536,7 +2327,6 @@ script = "./test.ts"
     #[test]
     fn test_nonexistent_command_permissions() {
         use crate::models::{PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
@@ -1551,10 +2341,18 @@ script = "./test.ts"
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(), // No commands defined
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(), // No commands defined
+            deno_dependencies: IndexMap::new(),
             permissions: Some(plugin_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         // Try to build permissions for nonexistent command
@@ -1566,6 +2364,41 @@ script = "./test.ts"
         assert_eq!(permissions.run_commands, vec!["mis", "git"]);
     }
 
+    #[test]
+    fn test_local_registry_path_detection() {
+        assert_eq!(local_registry_path("path:../plugins"), Some("../plugins"));
+        assert_eq!(local_registry_path("./plugins"), Some("./plugins"));
+        assert_eq!(local_registry_path("/opt/mis-plugins"), Some("/opt/mis-plugins"));
+        assert_eq!(local_registry_path("~/src/plugins"), Some("~/src/plugins"));
+        assert_eq!(local_registry_path("C:\\src\\plugins"), Some("C:\\src\\plugins"));
+        assert_eq!(local_registry_path("https://github.com/user/repo.git"), None);
+        assert_eq!(local_registry_path("git@github.com:user/repo.git"), None);
+        assert_eq!(local_registry_path("file:///etc/passwd"), None);
+    }
+
+    #[test]
+    fn test_validate_registry_source_allows_local_directories() {
+        let temp_dir = tempdir().unwrap();
+        let registry = temp_dir.path().join("plugins");
+        std::fs::create_dir_all(&registry).unwrap();
+
+        let source = format!("path:{}", registry.display());
+        let validated = validate_registry_source(&source).unwrap();
+        assert_eq!(Path::new(&validated), registry.canonicalize().unwrap());
+        assert!(validate_registry_source(&registry.display().to_string()).is_ok());
+
+        let missing = format!("path:{}", temp_dir.path().join("missing").display());
+        assert!(validate_registry_source(&missing).unwrap_err().contains("not found"));
+        assert!(validate_registry_source("path:").is_err());
+        assert!(validate_registry_source("path:/etc").unwrap_err().contains("System directory"));
+        assert!(validate_registry_source("path:/").is_err());
+
+        // Remote sources still go through URL validation, and file:// stays blocked
+        assert!(validate_registry_source("https://github.com/user/repo.git").is_ok());
+        assert!(validate_registry_source("file:///tmp").is_err());
+        assert!(validate_registry_source("http://localhost/repo").is_err());
+    }
+
     #[test]
     fn test_url_validation_comprehensive() {
         // Test registry URL validation
@@ -1640,6 +2473,69 @@ script = "./test.ts"
         }
     }
 
+    #[test]
+    fn test_dependency_hosts_allowlist() {
+        let policy = SecurityPolicy {
+            dependency_hosts: Some(vec!["deno.land".to_string(), "*.cdn.corp.example".to_string()]),
+            ..Default::default()
+        };
+        let policy = Some(&policy);
+
+        assert!(validate_dependency_url_with_policy("https://deno.land/std@0.200.0/path/mod.ts", policy).is_ok());
+        assert!(validate_dependency_url_with_policy("https://eu.cdn.corp.example/lib.ts", policy).is_ok());
+        let error = validate_dependency_url_with_policy("https://esm.sh/react@18.2.0", policy).unwrap_err();
+        assert!(error.contains("'esm.sh' is not in [security] dependency_hosts"));
+        assert!(error.contains("allowed: deno.land, *.cdn.corp.example"));
+
+        // Without a list, any host passing the usual checks is fine
+        assert!(validate_dependency_url_with_policy("https://esm.sh/react@18.2.0", None).is_ok());
+        let empty = SecurityPolicy {
+            dependency_hosts: Some(Vec::new()),
+            ..Default::default()
+        };
+        assert!(validate_dependency_url_with_policy("https://deno.land/x/a.ts", Some(&empty)).is_err());
+    }
+
+    #[test]
+    fn test_trusted_domains_allow_http_and_private_hosts() {
+        let trusted = SecurityPolicy {
+            trusted_domains: vec![
+                "gitlab.build.internal".to_string(),
+                "*.corp.example".to_string(),
+                "10.0.0.5".to_string(),
+                "169.254.169.254".to_string(),
+            ],
+            trusted_domain_origins: HashMap::from([(
+                "gitlab.build.internal".to_string(),
+                PathBuf::from("/etc/makeitso/config.toml"),
+            )]),
+            ..Default::default()
+        };
+        let policy = Some(&trusted);
+
+        let http = "http://gitlab.build.internal/tools/plugins.git";
+        assert!(validate_git_url_with_policy(http, "registry", policy).is_ok());
+        let error = validate_git_url_with_policy(http, "registry", None).unwrap_err();
+        assert!(error.contains("add gitlab.build.internal to [security] trusted_domains"));
+
+        assert!(validate_git_url_with_policy("http://git.corp.example/p.git", "registry", policy).is_ok());
+        assert!(validate_git_url_with_policy("http://corp.example/p.git", "registry", policy).is_err());
+        assert!(validate_git_url_with_policy("http://evilcorp.example/p.git", "registry", policy).is_err());
+        assert!(validate_dependency_url_with_policy("http://10.0.0.5:8080/lib.ts", policy).is_ok());
+        assert!(validate_dependency_url_with_policy("http://10.0.0.6:8080/lib.ts", policy).is_err());
+
+        // Metadata services stay blocked, and the error says which entry didn't help
+        let error = validate_dependency_url_with_policy("http://169.254.169.254/lib.ts", policy)
+            .unwrap_err();
+        assert!(error.contains("Cloud metadata"));
+        assert!(error.contains("trusted_domains entry '169.254.169.254'"));
+
+        assert_eq!(
+            trusted_domain_origin(&trusted, "GitLab.Build.Internal").as_deref(),
+            Some("trusted_domains entry 'gitlab.build.internal' in /etc/makeitso/config.toml")
+        );
+    }
+
     #[test]
     fn test_domain_normalization() {
         let project_root = PathBuf::from("/test/project");
@@ -1737,6 +2633,98 @@ script = "./test.ts"
         assert_eq!(permissions.network.len(), initial_count + 3);
     }
 
+    #[test]
+    fn test_project_security_policy_extends_rules() {
+        let project_root = PathBuf::from("/test/project");
+        let mut permissions = PluginPermissions::safe_defaults(&project_root);
+        permissions.policy = SecurityPolicy {
+            blocked_commands: vec!["terraform".to_string()],
+            allowed_network: vec!["Metadata.Internal.Example".to_string()],
+            allow_private_network: Some(true),
+            allow_insecure_network: None,
+            env_access: None,
+            strict: None,
+            ..Default::default()
+        };
+
+        permissions.allow_run("terraform");
+        permissions.allow_run("git");
+        permissions.allow_network("10.0.0.5:5432");
+        permissions.allow_network("metadata.internal.example");
+        permissions.allow_network("169.254.169.254");
+        permissions.allow_network("*.example.com");
+
+        assert_eq!(permissions.run_commands, vec!["mis", "git"]);
+        assert_eq!(
+            permissions.network,
+            vec!["10.0.0.5:5432", "metadata.internal.example"]
+        );
+
+        let blocked: Vec<&str> = permissions
+            .violations
+            .iter()
+            .map(|v| v.value.as_str())
+            .collect();
+        assert_eq!(blocked, vec!["terraform", "169.254.169.254", "*.example.com"]);
+        assert!(!permissions.violations[0].overridable);
+        assert!(permissions.violations[0].reason.contains("project security policy"));
+    }
+
+    #[test]
+    fn test_insecure_network_downgrades_private_blocks() {
+        let mut permissions = PluginPermissions::safe_defaults(&PathBuf::from("/test/project"));
+        permissions.policy.allow_insecure_network = Some(true);
+
+        permissions.allow_network("192.168.1.10:2375");
+        permissions.allow_network("169.254.169.254");
+
+        assert_eq!(permissions.network, vec!["192.168.1.10:2375"]);
+        assert_eq!(permissions.violations.len(), 1);
+        assert_eq!(permissions.violations[0].value, "169.254.169.254");
+        assert_eq!(permissions.notices.len(), 1);
+        assert!(permissions.notices[0].contains("192.168.1.10:2375"));
+    }
+
+    #[test]
+    fn test_check_strict_fails_only_under_strict_policy() {
+        let mut permissions = PluginPermissions::safe_defaults(&PathBuf::from("/test/project"));
+        permissions.allow_run("ssh");
+        permissions.allow_network("api.example.com");
+        assert!(permissions.check_strict("deploy").is_ok());
+
+        permissions.policy.strict = Some(true);
+        let message = permissions.check_strict("deploy").unwrap_err().to_string();
+        assert!(message.contains("Plugin 'deploy'"));
+        assert!(message.contains("to run `ssh`"));
+        assert!(!message.contains("api.example.com"));
+
+        let ssh = permissions.violations[0].clone();
+        permissions.grant(&ssh);
+        assert!(permissions.check_strict("deploy").is_ok());
+    }
+
+    #[test]
+    fn test_violation_serializes_for_json_output() {
+        let mut permissions = PluginPermissions::safe_defaults(&PathBuf::from("/test/project"));
+        permissions.allow_network("*.example.com");
+
+        let json = serde_json::to_value(&permissions.violations).unwrap();
+        assert_eq!(json[0]["kind"], "network");
+        assert_eq!(json[0]["value"], "*.example.com");
+        assert_eq!(json[0]["overridable"], false);
+        assert!(json[0]["reason"].as_str().unwrap().contains("Wildcard"));
+    }
+
+    #[test]
+    fn test_private_network_blocked_without_policy() {
+        let mut permissions = PluginPermissions::safe_defaults(&PathBuf::from("/test/project"));
+        permissions.allow_network("192.168.1.10");
+
+        assert!(permissions.network.is_empty());
+        assert_eq!(permissions.violations.len(), 1);
+        assert!(permissions.violations[0].overridable);
+    }
+
     #[test]
     fn test_localhost_access_via_explicit_network_permissions() {
         let project_root = PathBuf::from("/test/project");
@@ -1761,7 +2749,6 @@ script = "./test.ts"
     #[test]
     fn test_localhost_via_manifest_permissions() {
         use crate::models::{PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         let project_root = PathBuf::from("/test/project");
 
@@ -1776,10 +2763,18 @@ script = "./test.ts"
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: Some(plugin_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let result = build_plugin_permissions(&project_root, &manifest, "test-command");
@@ -1870,7 +2865,6 @@ script = "./test.ts"
     #[test]
     fn test_expansion_flows_to_deno_args() {
         use crate::models::{PluginManifest, PluginMeta, SecurityPermissions};
-        use std::collections::HashMap;
 
         unsafe { std::env::set_var("MIS_TEST_E2E", "/test/e2e-expanded"); }
 
@@ -1887,10 +2881,18 @@ script = "./test.ts"
                 description: None,
                 version: "1.0.0".to_string(),
                 registry: None,
+                pin: None,
+                execution: None,
             },
-            commands: HashMap::new(),
-            deno_dependencies: HashMap::new(),
+            commands: IndexMap::new(),
+            deno_dependencies: IndexMap::new(),
             permissions: Some(plugin_permissions),
+            config_schema: IndexMap::new(),
+            mis_version_requirement: None,
+            requires: None,
+            container: None,
+            context: None,
+            context_transport: None,
         };
 
         let permissions = build_plugin_permissions(&project_root, &manifest, "any").unwrap();
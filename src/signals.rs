@@ -0,0 +1,171 @@
+//! Passing Ctrl-C (SIGINT) and SIGTERM on to the plugin being run, so mis outlives it long
+//! enough to clean up its temp files and write the run log
+
+use std::process::Child;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicI32, Ordering};
+use std::time::Duration;
+
+use crate::config::settings;
+
+/// How long a plugin gets to exit after a signal is passed on, unless `grace_period` is set
+pub const DEFAULT_GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+#[cfg(unix)]
+const FORWARDED: [libc::c_int; 2] = [libc::SIGINT, libc::SIGTERM];
+
+/// Signal received since the plugins running now were started (0 = none)
+static PENDING: AtomicI32 = AtomicI32::new(0);
+
+/// Last signal received by this process (0 = none), for its exit code
+static RECEIVED: AtomicI32 = AtomicI32::new(0);
+
+/// Plugins running with signals caught, and the handlers to put back after the last one
+static ACTIVE: Mutex<Active> = Mutex::new(Active {
+    runs: 0,
+    previous: Vec::new(),
+});
+
+struct Active {
+    runs: usize,
+    previous: Vec<usize>,
+}
+
+/// While alive, SIGINT and SIGTERM are recorded for `wait_with_timeout` to pass on to the
+/// plugin instead of killing mis. Guards nest, so concurrent workflow steps can each hold one.
+pub struct Forwarding(());
+
+impl Forwarding {
+    pub fn start() -> Self {
+        let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+        if active.runs == 0 {
+            PENDING.store(0, Ordering::SeqCst);
+            active.previous = catch_signals();
+        }
+        active.runs += 1;
+        Forwarding(())
+    }
+}
+
+impl Drop for Forwarding {
+    fn drop(&mut self) {
+        let mut active = ACTIVE.lock().unwrap_or_else(|e| e.into_inner());
+        active.runs -= 1;
+        if active.runs == 0 {
+            restore_signals(&active.previous);
+        }
+    }
+}
+
+#[cfg(unix)]
+extern "C" fn record(signal: libc::c_int) {
+    // Only atomics here: nothing else is safe to touch in a signal handler
+    PENDING.store(signal, Ordering::SeqCst);
+    RECEIVED.store(signal, Ordering::SeqCst);
+}
+
+#[cfg(unix)]
+fn catch_signals() -> Vec<usize> {
+    let handler = record as extern "C" fn(libc::c_int) as libc::sighandler_t;
+    FORWARDED
+        .iter()
+        // SAFETY: `record` only stores to atomics, which is async-signal-safe
+        .map(|&signal| unsafe { libc::signal(signal, handler) })
+        .collect()
+}
+
+#[cfg(unix)]
+fn restore_signals(previous: &[usize]) {
+    for (&signal, &handler) in FORWARDED.iter().zip(previous) {
+        // SAFETY: puts back the disposition `catch_signals` replaced
+        unsafe { libc::signal(signal, handler) };
+    }
+}
+
+// Windows delivers Ctrl-C to every process on the console, so the plugin already gets it
+#[cfg(not(unix))]
+fn catch_signals() -> Vec<usize> {
+    Vec::new()
+}
+
+#[cfg(not(unix))]
+fn restore_signals(_previous: &[usize]) {}
+
+/// Signal received since the running plugins were started, still to be passed on
+pub fn pending() -> Option<i32> {
+    Some(PENDING.load(Ordering::SeqCst)).filter(|&signal| signal != 0)
+}
+
+/// Signal that interrupted this run, if any; mis exits with 128 + its number
+pub fn received() -> Option<i32> {
+    Some(RECEIVED.load(Ordering::SeqCst)).filter(|&signal| signal != 0)
+}
+
+/// Send `signal` to the plugin so it can shut down cleanly
+pub fn forward(child: &Child, signal: i32) {
+    #[cfg(unix)]
+    // SAFETY: kill(2) on the child's own pid; it's not reaped yet, so the pid is still its
+    unsafe {
+        libc::kill(child.id() as libc::pid_t, signal);
+    }
+    #[cfg(not(unix))]
+    let _ = (child, signal);
+}
+
+/// How long a plugin gets to exit after a signal before it's killed
+pub fn grace_period() -> Duration {
+    settings()
+        .grace_period
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_GRACE_PERIOD)
+}
+
+/// "SIGINT" or "SIGTERM" for messages
+pub fn signal_name(signal: i32) -> String {
+    #[cfg(unix)]
+    match signal {
+        libc::SIGINT => return "SIGINT (Ctrl-C)".to_string(),
+        libc::SIGTERM => return "SIGTERM".to_string(),
+        _ => {}
+    }
+    format!("signal {}", signal)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::process::ExitStatusExt;
+
+    #[test]
+    fn test_signals_are_recorded_while_forwarding() {
+        let forwarding = Forwarding::start();
+        // SAFETY: the handler installed above records the signal instead of exiting
+        unsafe { libc::raise(libc::SIGTERM) };
+        assert_eq!(pending(), Some(libc::SIGTERM));
+        assert_eq!(received(), Some(libc::SIGTERM));
+        drop(forwarding);
+        PENDING.store(0, Ordering::SeqCst);
+        RECEIVED.store(0, Ordering::SeqCst);
+    }
+
+    #[test]
+    fn test_forward_signals_the_child() {
+        let mut child = std::process::Command::new("sleep")
+            .arg("5")
+            .spawn()
+            .unwrap();
+        forward(&child, libc::SIGTERM);
+        let status = child.wait().unwrap();
+        assert_eq!(status.signal(), Some(libc::SIGTERM));
+    }
+
+    #[test]
+    fn test_signal_name() {
+        assert_eq!(signal_name(libc::SIGINT), "SIGINT (Ctrl-C)");
+        assert_eq!(signal_name(libc::SIGTERM), "SIGTERM");
+        assert_eq!(
+            signal_name(libc::SIGHUP),
+            format!("signal {}", libc::SIGHUP)
+        );
+    }
+}
@@ -0,0 +1,320 @@
+//! Temporary files mis creates for plugin runs, and cleaning up after runs that crashed
+//! before they could remove their own
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Private directory holding one plugin run's context, result and audit files:
+/// `mis-run-<pid>-<random>`, inside `runs_dir()`
+pub const RUN_DIR_PREFIX: &str = "mis-run-";
+
+/// Files older versions of mis wrote straight into the temp dir: `<prefix><pid>-<n>.json`.
+/// Their run directories went there too.
+const LEGACY_FILE_PREFIXES: &[&str] = &["mis-context-", "mis-result-", "mis-perm-audit-"];
+
+/// Leftovers this old are removed whenever mis starts
+pub const STALE_AFTER: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Where run directories are created: `$XDG_RUNTIME_DIR/makeitso`, or a directory of the
+/// user's own in the temp dir. Create it with `ensure_private_dir` before use.
+pub fn runs_dir() -> PathBuf {
+    #[cfg(unix)]
+    {
+        if let Some(runtime_dir) = std::env::var_os("XDG_RUNTIME_DIR").filter(|d| !d.is_empty()) {
+            return PathBuf::from(runtime_dir).join("makeitso");
+        }
+        // SAFETY: geteuid can't fail and has no side effects
+        let uid = unsafe { libc::geteuid() };
+        std::env::temp_dir().join(format!("makeitso-{}", uid))
+    }
+    // The temp dir is already per-user on Windows
+    #[cfg(not(unix))]
+    std::env::temp_dir().join("makeitso")
+}
+
+/// Create `path` readable and writable by the current user only (0600 on Unix)
+pub fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+/// Whether an entry (from `fs::symlink_metadata`) belongs to the user mis runs as
+#[cfg(unix)]
+pub fn owned_by_current_user(meta: &fs::Metadata) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    // SAFETY: geteuid can't fail and has no side effects
+    meta.uid() == unsafe { libc::geteuid() }
+}
+
+/// Windows has no simple owner check; per-user directories are already private there
+#[cfg(not(unix))]
+pub fn owned_by_current_user(_meta: &fs::Metadata) -> bool {
+    true
+}
+
+/// Create `dir` if needed so only the current user can use it (0700 on Unix), refusing one
+/// that is a symlink or belongs to someone else
+pub fn ensure_private_dir(dir: &Path) -> std::io::Result<()> {
+    if let Some(parent) = dir.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let mut builder = fs::DirBuilder::new();
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    match builder.create(dir) {
+        Err(e) if e.kind() != std::io::ErrorKind::AlreadyExists => return Err(e),
+        _ => {}
+    }
+
+    let meta = fs::symlink_metadata(dir)?;
+    if !meta.is_dir() || !owned_by_current_user(&meta) {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} isn't a directory of your own", dir.display()),
+        ));
+    }
+    #[cfg(unix)]
+    fs::set_permissions(dir, std::os::unix::fs::PermissionsExt::from_mode(0o700))?;
+    Ok(())
+}
+
+/// Overwrite a file's contents with zeros so they don't linger on disk after it's removed.
+/// Refuses symlinks and anything but a regular file, so it can't be pointed at another file.
+pub fn overwrite_with_zeros(path: &Path) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true);
+    // O_NONBLOCK keeps a FIFO without a reader from hanging the open
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::custom_flags(
+        &mut options,
+        libc::O_NOFOLLOW | libc::O_NONBLOCK,
+    );
+    let mut file = options.open(path)?;
+    let meta = file.metadata()?;
+    if !meta.is_file() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::InvalidInput,
+            format!("{} isn't a regular file", path.display()),
+        ));
+    }
+    file.write_all(&vec![0; meta.len() as usize])?;
+    file.sync_all()
+}
+
+/// Remove leftovers of crashed runs older than `max_age`: run directories in `runs_dir()`,
+/// plus what older versions of mis left in the shared temp dir
+pub fn remove_abandoned_runs(max_age: Duration) -> Vec<PathBuf> {
+    let mut removed = Vec::new();
+    let runs_dir = runs_dir();
+    // Only a directory we made; never one planted under the name
+    if fs::symlink_metadata(&runs_dir)
+        .is_ok_and(|meta| meta.is_dir() && owned_by_current_user(&meta))
+    {
+        removed.extend(remove_stale_temp_files(&runs_dir, max_age));
+    }
+    removed.extend(remove_stale_temp_files(&std::env::temp_dir(), max_age));
+    removed
+}
+
+/// Run directories and context files in `temp_dir` left behind by runs that died, i.e.
+/// older than `max_age` and not owned by a mis process that's still running.
+///
+/// The temp dir is shared, so only the current user's own files and directories count;
+/// symlinks and anyone else's entries are skipped whatever they're called.
+pub fn stale_temp_files(temp_dir: &Path, max_age: Duration) -> Vec<(PathBuf, fs::Metadata)> {
+    let Ok(entries) = fs::read_dir(temp_dir) else {
+        return Vec::new();
+    };
+    let mut stale: Vec<(PathBuf, fs::Metadata)> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let pid = entry.file_name().to_str().and_then(owner_pid)?;
+            let path = entry.path();
+            let meta = fs::symlink_metadata(&path).ok()?;
+            if !(meta.is_file() || meta.is_dir()) || !owned_by_current_user(&meta) {
+                return None;
+            }
+            let old_enough = meta
+                .modified()
+                .ok()
+                .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+                .is_some_and(|age| age >= max_age);
+            (old_enough && !process_running(pid)).then_some((path, meta))
+        })
+        .collect();
+    stale.sort_by(|(a, _), (b, _)| a.cmp(b));
+    stale
+}
+
+/// Remove what `stale_temp_files` finds, overwriting context files first since they can hold
+/// secrets. Returns what was removed; entries that can't be removed (e.g. another user's)
+/// are left alone.
+pub fn remove_stale_temp_files(temp_dir: &Path, max_age: Duration) -> Vec<PathBuf> {
+    stale_temp_files(temp_dir, max_age)
+        .into_iter()
+        .filter_map(|(path, meta)| {
+            let removed = if meta.is_dir() {
+                let _ = overwrite_with_zeros(&path.join("context.json"));
+                fs::remove_dir_all(&path)
+            } else {
+                let _ = overwrite_with_zeros(&path);
+                fs::remove_file(&path)
+            };
+            match removed {
+                Ok(()) => {
+                    debugln!("Removed leftover temporary file {}", path.display());
+                    Some(path)
+                }
+                Err(e) => {
+                    debugln!("Couldn't remove {}: {}", path.display(), e);
+                    None
+                }
+            }
+        })
+        .collect()
+}
+
+/// The pid of the mis process that created a temp entry, from its name
+fn owner_pid(name: &str) -> Option<u32> {
+    let rest = std::iter::once(RUN_DIR_PREFIX)
+        .chain(LEGACY_FILE_PREFIXES.iter().copied())
+        .find_map(|prefix| name.strip_prefix(prefix))?;
+    let (pid, _) = rest.split_once('-')?;
+    pid.parse().ok()
+}
+
+/// Whether a process with this pid exists (a recycled pid errs on the side of keeping files)
+#[cfg(unix)]
+fn process_running(pid: u32) -> bool {
+    // SAFETY: signal 0 only checks that the process exists and may be signalled
+    let result = unsafe { libc::kill(pid as libc::pid_t, 0) };
+    result == 0 || std::io::Error::last_os_error().raw_os_error() == Some(libc::EPERM)
+}
+
+/// Without a cheap check, only the age threshold decides
+#[cfg(not(unix))]
+fn process_running(pid: u32) -> bool {
+    pid == std::process::id()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[cfg(unix)]
+    #[test]
+    fn test_write_private_file_is_owner_only() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let context_file = temp_dir.path().join("context.json");
+        write_private_file(&context_file, br#"{"token": "secret"}"#).unwrap();
+
+        let mode = fs::metadata(&context_file).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        // Never reuses a file someone else put there
+        assert!(write_private_file(&context_file, b"{}").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_ensure_private_dir_is_owner_only_and_refuses_symlinks() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempdir().unwrap();
+        let dir = temp_dir.path().join("cache/downloads");
+        ensure_private_dir(&dir).unwrap();
+        ensure_private_dir(&dir).unwrap();
+        let mode = fs::metadata(&dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+
+        let link = temp_dir.path().join("link");
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        assert!(ensure_private_dir(&link).is_err());
+    }
+
+    #[test]
+    fn test_overwrite_with_zeros_keeps_length() {
+        let temp_dir = tempdir().unwrap();
+        let context_file = temp_dir.path().join("context.json");
+        fs::write(&context_file, r#"{"token": "secret"}"#).unwrap();
+
+        overwrite_with_zeros(&context_file).unwrap();
+        let contents = fs::read(&context_file).unwrap();
+        assert_eq!(contents.len(), 19);
+        assert!(contents.iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_owner_pid() {
+        assert_eq!(owner_pid("mis-run-4242-Xy7a1Q"), Some(4242));
+        assert_eq!(owner_pid("mis-context-4242-0.json"), Some(4242));
+        assert_eq!(owner_pid("mis-perm-audit-17-3.json"), Some(17));
+        assert_eq!(owner_pid("mis-run-Xy7a1Q"), None);
+        assert_eq!(owner_pid("makeitso-downloads"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_remove_stale_temp_files_skips_live_runs() {
+        let temp_dir = tempdir().unwrap();
+        // Spawned and reaped, so its pid belongs to nobody for the rest of the test
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = exited.id();
+        exited.wait().unwrap();
+
+        let crashed = temp_dir.path().join(format!("mis-run-{}-abc123", dead_pid));
+        fs::create_dir(&crashed).unwrap();
+        fs::write(crashed.join("context.json"), "{}").unwrap();
+        let legacy = temp_dir.path().join(format!("mis-context-{}-0.json", dead_pid));
+        fs::write(&legacy, "{}").unwrap();
+        let live = temp_dir
+            .path()
+            .join(format!("mis-run-{}-def456", std::process::id()));
+        fs::create_dir(&live).unwrap();
+        let unrelated = temp_dir.path().join("other-tool-1-0.json");
+        fs::write(&unrelated, "{}").unwrap();
+
+        // Nothing is old enough yet
+        assert!(remove_stale_temp_files(temp_dir.path(), STALE_AFTER).is_empty());
+
+        let removed = remove_stale_temp_files(temp_dir.path(), Duration::ZERO);
+        assert_eq!(removed, vec![legacy.clone(), crashed.clone()]);
+        assert!(!crashed.exists());
+        assert!(!legacy.exists());
+        assert!(live.exists());
+        assert!(unrelated.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_remove_stale_temp_files_leaves_symlinks_alone() {
+        let temp_dir = tempdir().unwrap();
+        let mut exited = std::process::Command::new("true").spawn().unwrap();
+        let dead_pid = exited.id();
+        exited.wait().unwrap();
+
+        // Someone sharing the temp dir points leftover-looking names at a victim's files
+        let victim_dir = temp_dir.path().join("victim");
+        fs::create_dir(&victim_dir).unwrap();
+        let victim = victim_dir.join("context.json");
+        fs::write(&victim, "precious").unwrap();
+        let file_link = temp_dir.path().join(format!("mis-context-{}-0.json", dead_pid));
+        std::os::unix::fs::symlink(&victim, &file_link).unwrap();
+        let dir_link = temp_dir.path().join(format!("mis-run-{}-abc123", dead_pid));
+        std::os::unix::fs::symlink(&victim_dir, &dir_link).unwrap();
+
+        assert!(remove_stale_temp_files(temp_dir.path(), Duration::ZERO).is_empty());
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "precious");
+        assert!(file_link.is_symlink() && dir_link.is_symlink());
+
+        assert!(overwrite_with_zeros(&file_link).is_err());
+        assert_eq!(fs::read_to_string(&victim).unwrap(), "precious");
+    }
+}
@@ -0,0 +1,242 @@
+use std::borrow::Cow;
+use std::io::IsTerminal;
+
+use crate::config::settings;
+use crate::models::{ColorChoice, Theme};
+
+/// Glyph, ASCII replacement, minimal replacement
+const GLYPHS: &[(&str, &str, &str)] = &[
+    ("🛑", "[error]", "error:"),
+    ("❌", "[fail]", "error:"),
+    ("⚠️", "[warn]", ""),
+    ("✅", "[ok]", ""),
+    ("💡", "[hint]", ""),
+    ("→", "->", "->"),
+    ("←", "<-", "<-"),
+    ("─", "-", "-"),
+    ("├", "|-", "|-"),
+    ("└", "`-", "`-"),
+    ("│", "|", "|"),
+    ("•", "*", "*"),
+    ("…", "...", "..."),
+];
+
+/// Line marker and the color it gives the line: errors red, warnings yellow, successes
+/// green, hints cyan
+const LINE_COLORS: &[(&str, &str)] = &[
+    ("🛑", "31"),
+    ("❌", "31"),
+    ("Error:", "31"),
+    ("⚠️", "33"),
+    ("✅", "32"),
+    ("🎉", "32"),
+    ("→", "36"),
+    ("💡", "36"),
+];
+
+// Messages go through these instead of println!/eprintln! so emoji and box-drawing
+// characters can be swapped out on terminals and log systems that mangle them, and so
+// errors, warnings and successes are colored the same way everywhere.
+// With `--output json` stdout is kept for the JSON report, so outln!/out! print to stderr.
+// `-q` silences outln!; errors, warnings (errln!) and prompts (out!) still show.
+
+/// Print a line to stdout in the configured theme
+macro_rules! outln {
+    () => {
+        if !$crate::logging::enabled($crate::logging::Verbosity::Normal) {
+        } else if $crate::report::json_output() {
+            eprintln!()
+        } else {
+            println!()
+        }
+    };
+    ($($arg:tt)*) => {
+        if !$crate::logging::enabled($crate::logging::Verbosity::Normal) {
+        } else if $crate::report::json_output() {
+            eprintln!("{}", $crate::theme::style($crate::theme::Stream::Stderr, &format!($($arg)*)))
+        } else {
+            println!("{}", $crate::theme::style($crate::theme::Stream::Stdout, &format!($($arg)*)))
+        }
+    };
+}
+
+/// Print a line to stderr in the configured theme
+macro_rules! errln {
+    () => {
+        eprintln!()
+    };
+    ($($arg:tt)*) => {
+        eprintln!("{}", $crate::theme::style($crate::theme::Stream::Stderr, &format!($($arg)*)))
+    };
+}
+
+/// Print to stdout without a newline (prompts) in the configured theme
+macro_rules! out {
+    ($($arg:tt)*) => {
+        if $crate::report::json_output() {
+            eprint!("{}", $crate::theme::style($crate::theme::Stream::Stderr, &format!($($arg)*)))
+        } else {
+            print!("{}", $crate::theme::style($crate::theme::Stream::Stdout, &format!($($arg)*)))
+        }
+    };
+}
+
+/// Where a message is printed; only terminals get color in `auto` mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// Whether output on `stream` is colored: `--color`/`--no-color`, `MIS_COLOR`, `NO_COLOR`
+/// and the config files decide, and `auto` colors terminals other than TERM=dumb
+pub fn use_color(stream: Stream) -> bool {
+    match settings().color {
+        Some(ColorChoice::Always) => true,
+        Some(ColorChoice::Never) => false,
+        Some(ColorChoice::Auto) | None => {
+            let terminal = match stream {
+                Stream::Stdout => std::io::stdout().is_terminal(),
+                Stream::Stderr => std::io::stderr().is_terminal(),
+            };
+            terminal && std::env::var_os("TERM").is_none_or(|term| term != "dumb")
+        }
+    }
+}
+
+/// Wrap `text` in an ANSI color, e.g. `paint("31", "failed")` for red
+pub fn paint(code: &str, text: &str) -> String {
+    format!("\x1b[{}m{}\x1b[0m", code, text)
+}
+
+/// Theme a message and, if `stream` is colored, color each line by its marker
+pub fn style(stream: Stream, text: &str) -> Cow<'_, str> {
+    if !use_color(stream) {
+        return render(text);
+    }
+    Cow::Owned(render(&colorize(text)).into_owned())
+}
+
+/// Color each line that starts with a marker from `LINE_COLORS`
+fn colorize(text: &str) -> String {
+    text.split('\n')
+        .map(|line| {
+            let marked = line.trim_start();
+            match LINE_COLORS
+                .iter()
+                .find(|(marker, _)| marked.starts_with(marker))
+            {
+                Some((_, code)) => paint(code, line),
+                None => line.to_string(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Apply the configured theme (`theme` setting, `MIS_THEME` or `--theme`) to a message
+pub fn render(text: &str) -> Cow<'_, str> {
+    render_with(settings().theme.unwrap_or_default(), text)
+}
+
+pub fn render_with(theme: Theme, text: &str) -> Cow<'_, str> {
+    if theme == Theme::Fancy || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+
+    let mut rendered = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let known = GLYPHS.iter().find(|(glyph, _, _)| rest.starts_with(glyph));
+        let (glyph_len, replacement) = match known {
+            Some((glyph, ascii, minimal)) => (
+                glyph.len(),
+                if theme == Theme::Ascii {
+                    *ascii
+                } else {
+                    *minimal
+                },
+            ),
+            None if is_emoji(c) => (c.len_utf8(), if theme == Theme::Ascii { "*" } else { "" }),
+            None => {
+                rendered.push(c);
+                rest = &rest[c.len_utf8()..];
+                continue;
+            }
+        };
+
+        rest = &rest[glyph_len..];
+        // Emoji presentation selector left over from the glyph
+        rest = rest.strip_prefix('\u{FE0F}').unwrap_or(rest);
+        if replacement.is_empty() {
+            rest = rest.trim_start_matches(' ');
+        } else {
+            rendered.push_str(replacement);
+        }
+    }
+
+    Cow::Owned(rendered)
+}
+
+fn is_emoji(c: char) -> bool {
+    matches!(c as u32, 0x1F300..=0x1FAFF | 0x2600..=0x27BF | 0x2B00..=0x2BFF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_colorize_by_line_marker() {
+        assert_eq!(
+            colorize("🛑 Plugin failed.\n   details\n→ Try again."),
+            "\x1b[31m🛑 Plugin failed.\x1b[0m\n   details\n\x1b[36m→ Try again.\x1b[0m"
+        );
+        assert_eq!(
+            colorize("⚠️  Cache is cold"),
+            "\x1b[33m⚠️  Cache is cold\x1b[0m"
+        );
+        assert_eq!(
+            colorize("   ✅ Installed deploy"),
+            "\x1b[32m   ✅ Installed deploy\x1b[0m"
+        );
+        assert_eq!(colorize("📦 Uploaded"), "📦 Uploaded");
+    }
+
+    #[test]
+    fn test_render_with_fancy_is_unchanged() {
+        let text = "🛑 Plugin failed.\n→ Try again.";
+        assert_eq!(render_with(Theme::Fancy, text), text);
+    }
+
+    #[test]
+    fn test_render_with_ascii_replaces_glyphs() {
+        assert_eq!(
+            render_with(Theme::Ascii, "🛑 Plugin failed.\n→ Try again."),
+            "[error] Plugin failed.\n-> Try again."
+        );
+        assert_eq!(
+            render_with(Theme::Ascii, "⚠️  Warning: cache is cold"),
+            "[warn]  Warning: cache is cold"
+        );
+        assert_eq!(render_with(Theme::Ascii, "📦 Uploaded"), "* Uploaded");
+        assert!(render_with(Theme::Ascii, "└── step ✅ done").is_ascii());
+    }
+
+    #[test]
+    fn test_render_with_minimal_strips_decoration() {
+        assert_eq!(
+            render_with(Theme::Minimal, "🛑 Plugin failed.\n→ Try again."),
+            "error: Plugin failed.\n-> Try again."
+        );
+        assert_eq!(
+            render_with(Theme::Minimal, "⚠️  Warning: cache is cold"),
+            "Warning: cache is cold"
+        );
+        assert_eq!(render_with(Theme::Minimal, "📦 Uploaded"), "Uploaded");
+        assert_eq!(
+            render_with(Theme::Minimal, "Step 'build' ✅"),
+            "Step 'build' "
+        );
+    }
+}
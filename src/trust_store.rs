@@ -0,0 +1,247 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use serde::{Deserialize, Serialize};
+
+use crate::models::SecurityPermissions;
+use crate::security::{PermissionKind, PermissionViolation, PluginPermissions};
+
+/// Blocked permissions the user chose to always allow, per plugin (.makeitso/trust.toml)
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct TrustStore {
+    #[serde(default)]
+    pub plugins: BTreeMap<String, SecurityPermissions>,
+}
+
+/// The user's answer to a permission prompt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PermissionDecision {
+    Deny,
+    AllowOnce,
+    AllowAlways,
+}
+
+impl TrustStore {
+    pub fn path(project_root: &Path) -> PathBuf {
+        project_root.join(".makeitso").join("trust.toml")
+    }
+
+    /// Load the project's trust store; a missing file means nothing is trusted yet
+    pub fn load(project_root: &Path) -> Result<Self> {
+        let path = Self::path(project_root);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}", path.display()))?;
+        toml::from_str(&contents).map_err(|e| {
+            anyhow!(
+                "🛑 Failed to parse {}: {}\n\
+                 → Fix or delete the file to be asked again.",
+                path.display(),
+                e
+            )
+        })
+    }
+
+    pub fn save(&self, project_root: &Path) -> Result<()> {
+        let path = Self::path(project_root);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        fs::write(&path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write {}", path.display()))
+    }
+
+    pub fn is_trusted(&self, plugin_name: &str, violation: &PermissionViolation) -> bool {
+        self.plugins
+            .get(plugin_name)
+            .is_some_and(|trusted| entries(trusted, violation.kind).contains(&violation.value))
+    }
+
+    /// Grant every blocked permission this plugin has been trusted with
+    pub fn apply(&self, plugin_name: &str, permissions: &mut PluginPermissions) {
+        let trusted: Vec<PermissionViolation> = permissions
+            .violations
+            .iter()
+            .filter(|v| v.overridable && self.is_trusted(plugin_name, v))
+            .cloned()
+            .collect();
+        for violation in &trusted {
+            permissions.grant(violation);
+        }
+    }
+
+    pub fn trust(&mut self, plugin_name: &str, violation: &PermissionViolation) {
+        let trusted = self.plugins.entry(plugin_name.to_string()).or_default();
+        let list = entries_mut(trusted, violation.kind);
+        if !list.contains(&violation.value) {
+            list.push(violation.value.clone());
+        }
+    }
+}
+
+fn entries(permissions: &SecurityPermissions, kind: PermissionKind) -> &Vec<String> {
+    match kind {
+        PermissionKind::Read => &permissions.file_read,
+        PermissionKind::Write => &permissions.file_write,
+        PermissionKind::Network => &permissions.network,
+        PermissionKind::Run => &permissions.run_commands,
+    }
+}
+
+fn entries_mut(permissions: &mut SecurityPermissions, kind: PermissionKind) -> &mut Vec<String> {
+    match kind {
+        PermissionKind::Read => &mut permissions.file_read,
+        PermissionKind::Write => &mut permissions.file_write,
+        PermissionKind::Network => &mut permissions.network,
+        PermissionKind::Run => &mut permissions.run_commands,
+    }
+}
+
+/// Decide what to do with each blocked permission before the plugin starts.
+///
+/// Permissions trusted in .makeitso/trust.toml are granted, the rest are prompted for when
+/// `interactive`, and anything still blocked is reported as a warning. Under a strict
+/// security policy nothing is prompted for; the caller fails on what's left.
+pub fn resolve_violations(
+    permissions: &mut PluginPermissions,
+    plugin_name: &str,
+    project_root: &Path,
+    interactive: bool,
+) -> Result<()> {
+    let strict = permissions.policy.strict == Some(true);
+    let interactive = interactive && !strict;
+    if !permissions.violations.iter().any(|v| v.overridable) {
+        permissions.warn_violations();
+        return Ok(());
+    }
+
+    let mut store = TrustStore::load(project_root)?;
+    store.apply(plugin_name, permissions);
+
+    let undecided: Vec<PermissionViolation> = permissions
+        .violations
+        .iter()
+        .filter(|v| v.overridable && interactive)
+        .cloned()
+        .collect();
+    let mut store_changed = false;
+
+    for violation in &undecided {
+        let question = format!(
+            "⚠️  Plugin '{}' requests {} ({}). Allow?",
+            plugin_name,
+            violation.kind.describe(&violation.value),
+            violation.reason
+        );
+        match prompt_decision(&question)? {
+            PermissionDecision::Deny => {}
+            PermissionDecision::AllowOnce => {
+                permissions.grant(violation);
+            }
+            PermissionDecision::AllowAlways => {
+                permissions.grant(violation);
+                store.trust(plugin_name, violation);
+                store_changed = true;
+            }
+        }
+    }
+
+    if store_changed {
+        store.save(project_root)?;
+    }
+
+    permissions.warn_violations();
+    if !interactive && !strict && permissions.violations.iter().any(|v| v.overridable) {
+        errln!("→ Run the command in a terminal to approve blocked permissions.");
+    }
+
+    Ok(())
+}
+
+fn prompt_decision(question: &str) -> Result<PermissionDecision> {
+    out!("{} [y/N/always]: ", question);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    Ok(parse_decision(&input))
+}
+
+/// Anything that isn't a clear yes or "always" denies
+pub fn parse_decision(input: &str) -> PermissionDecision {
+    match input.trim().to_lowercase().as_str() {
+        "y" | "yes" => PermissionDecision::AllowOnce,
+        "a" | "always" => PermissionDecision::AllowAlways,
+        _ => PermissionDecision::Deny,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    fn blocked_network(permissions: &mut PluginPermissions, domain: &str) -> PermissionViolation {
+        permissions.allow_network(domain);
+        permissions.violations.last().cloned().unwrap()
+    }
+
+    #[test]
+    fn test_parse_decision() {
+        assert_eq!(parse_decision("y\n"), PermissionDecision::AllowOnce);
+        assert_eq!(parse_decision("YES"), PermissionDecision::AllowOnce);
+        assert_eq!(parse_decision("always"), PermissionDecision::AllowAlways);
+        assert_eq!(parse_decision("a"), PermissionDecision::AllowAlways);
+        assert_eq!(parse_decision(""), PermissionDecision::Deny);
+        assert_eq!(parse_decision("nope"), PermissionDecision::Deny);
+    }
+
+    #[test]
+    fn test_trust_store_round_trip() {
+        let temp_dir = tempdir().unwrap();
+        let mut permissions = PluginPermissions::safe_defaults(temp_dir.path());
+        let violation = blocked_network(&mut permissions, "192.168.1.10");
+
+        let mut store = TrustStore::load(temp_dir.path()).unwrap();
+        assert!(!store.is_trusted("deploy", &violation));
+
+        store.trust("deploy", &violation);
+        store.trust("deploy", &violation);
+        store.save(temp_dir.path()).unwrap();
+
+        let store = TrustStore::load(temp_dir.path()).unwrap();
+        assert!(store.is_trusted("deploy", &violation));
+        assert!(!store.is_trusted("other", &violation));
+        assert_eq!(store.plugins["deploy"].network, vec!["192.168.1.10"]);
+    }
+
+    #[test]
+    fn test_resolve_violations_grants_trusted_permissions() {
+        let temp_dir = tempdir().unwrap();
+        let mut permissions = PluginPermissions::safe_defaults(temp_dir.path());
+        let trusted = blocked_network(&mut permissions, "192.168.1.10");
+        blocked_network(&mut permissions, "10.0.0.5");
+        blocked_network(&mut permissions, "*.example.com");
+
+        let mut store = TrustStore::default();
+        store.trust("deploy", &trusted);
+        store.save(temp_dir.path()).unwrap();
+
+        resolve_violations(&mut permissions, "deploy", temp_dir.path(), false).unwrap();
+
+        assert_eq!(permissions.network, vec!["192.168.1.10"]);
+        let still_blocked: Vec<&str> = permissions
+            .violations
+            .iter()
+            .map(|v| v.value.as_str())
+            .collect();
+        assert_eq!(still_blocked, vec!["10.0.0.5", "*.example.com"]);
+    }
+}
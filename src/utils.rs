@@ -1,20 +1,131 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+use crate::config::{find_workspace_root, is_workspace_root};
+use crate::onboarding::not_in_project_hint;
+
+/// Which project `find_project_root_from` settles on when projects are nested
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProjectResolution {
+    /// The nearest enclosing project
+    Nearest,
+    /// The root of the workspace the nearest project belongs to, or that project
+    /// itself when it isn't part of a workspace
+    Workspace,
+}
 
 pub fn find_project_root() -> Option<PathBuf> {
-    let mut current = std::env::current_dir().ok()?;
+    let current = std::env::current_dir().ok()?;
+    find_project_root_from(&current, ProjectResolution::Nearest)
+}
 
-    loop {
+pub fn find_project_root_from(start: &Path, resolution: ProjectResolution) -> Option<PathBuf> {
+    let mut current = start.to_path_buf();
+
+    let project = loop {
         let candidate = current.join(".makeitso");
         if candidate.exists() && candidate.is_dir() {
-            return Some(current);
+            break current;
         }
 
         if !current.pop() {
-            break;
+            // If we reach here, we didn't find the project root
+            // This might be totally expected depending on the context
+            return None;
+        }
+    };
+
+    match resolution {
+        ProjectResolution::Nearest => Some(project),
+        ProjectResolution::Workspace if is_workspace_root(&project).unwrap_or(false) => {
+            Some(project)
         }
+        ProjectResolution::Workspace => find_workspace_root(&project)
+            .ok()
+            .flatten()
+            .or(Some(project)),
+    }
+}
+
+/// Find the project root, or fail with onboarding hints tailored to the current directory
+pub fn require_project_root() -> anyhow::Result<PathBuf> {
+    find_project_root().ok_or_else(|| {
+        let cwd = std::env::current_dir().unwrap_or_default();
+        anyhow::anyhow!("Failed to find project root\n{}", not_in_project_hint(&cwd))
+    })
+}
+
+/// Find the directory `--project <dir>` names: a project relative to `cwd`, or else
+/// relative to the root of the workspace `cwd` is in
+pub fn resolve_project_dir(cwd: &Path, project: &Path) -> anyhow::Result<PathBuf> {
+    let workspace_root = find_project_root_from(cwd, ProjectResolution::Workspace);
+    std::iter::once(cwd.join(project))
+        .chain(workspace_root.map(|root| root.join(project)))
+        .find(|dir| dir.join(".makeitso").is_dir())
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "🛑 No Make It So project found at {}.\n\
+                 → Pass a directory containing .makeitso/, relative to here or to the workspace root.\n\
+                 → Run `mis list --workspace` to see the workspace's projects.",
+                project.display()
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    fn write_mis_toml(dir: &Path, contents: &str) {
+        fs::create_dir_all(dir.join(".makeitso")).unwrap();
+        fs::write(dir.join(".makeitso/mis.toml"), contents).unwrap();
+    }
+
+    #[test]
+    fn test_find_project_root_from_in_workspace() {
+        let temp_dir = tempdir().unwrap();
+        let root = temp_dir.path();
+        write_mis_toml(root, "[workspace]\n");
+        let api = root.join("services/api");
+        write_mis_toml(&api, "");
+        let src = api.join("src/handlers");
+        fs::create_dir_all(&src).unwrap();
+
+        assert_eq!(
+            find_project_root_from(&src, ProjectResolution::Nearest),
+            Some(api.clone())
+        );
+        assert_eq!(
+            find_project_root_from(&src, ProjectResolution::Workspace),
+            Some(root.to_path_buf())
+        );
+        assert_eq!(
+            find_project_root_from(root, ProjectResolution::Workspace),
+            Some(root.to_path_buf())
+        );
+
+        // --project is tried relative to the current directory, then the workspace root
+        assert_eq!(
+            resolve_project_dir(root, Path::new("services/api")).unwrap(),
+            api
+        );
+        assert_eq!(
+            resolve_project_dir(&src, Path::new("services/api")).unwrap(),
+            api
+        );
+        assert!(resolve_project_dir(&src, Path::new("services/missing")).is_err());
     }
 
-    // If we reach here, we didn't find the project root
-    // This might be totally expected depending on the context
-    None
-}
\ No newline at end of file
+    #[test]
+    fn test_project_outside_workspace_resolves_to_itself() {
+        let temp_dir = tempdir().unwrap();
+        let project = temp_dir.path().join("app");
+        write_mis_toml(&project, "");
+
+        assert_eq!(
+            find_project_root_from(&project, ProjectResolution::Workspace),
+            Some(project)
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 use std::collections::{HashMap, HashSet};
-use crate::models::{ArgType, CommandArgs};
+use crate::models::{ArgDefinition, ArgType, CommandArgs, flag_name};
 
 #[derive(Debug)]
 pub struct ValidationError {
@@ -21,25 +21,30 @@ impl std::fmt::Display for ValidationError {
 impl std::error::Error for ValidationError {}
 
 pub fn validate_plugin_args(
-    provided_args: &HashMap<String, String>,
+    provided_args: &HashMap<String, Vec<String>>,
     command_args: Option<&CommandArgs>,
     plugin_name: &str,
     command_name: &str,
-) -> Result<HashMap<String, String>> {
+) -> Result<HashMap<String, Vec<String>>> {
     let Some(args_def) = command_args else {
         // No argument definition means no validation - accept all args (backward compatibility)
         return Ok(provided_args.clone());
     };
 
-    let mut validated_args = HashMap::new();
     let mut errors = Vec::new();
+    let provided_args = &resolve_aliases(provided_args, args_def, &mut errors);
+    let mut validated_args = HashMap::new();
 
     // Check for required arguments
     for (arg_name, arg_def) in &args_def.required {
-        if let Some(value) = provided_args.get(arg_name) {
-            match validate_arg_type(value, &arg_def.arg_type) {
-                Ok(validated_value) => {
-                    validated_args.insert(arg_name.clone(), validated_value);
+        if let Some(values) = provided_args.get(arg_name) {
+            if let Some(error) = multiplicity_error(arg_name, values, arg_def) {
+                errors.push(error);
+                continue;
+            }
+            match validate_arg_values(values, arg_def) {
+                Ok(validated_values) => {
+                    validated_args.insert(arg_name.clone(), validated_values);
                 }
                 Err(e) => {
                     errors.push(format!("Invalid value for required argument '--{}': {}", arg_name, e));
@@ -52,17 +57,21 @@ pub fn validate_plugin_args(
 
     // Check optional arguments and apply defaults
     for (arg_name, arg_def) in &args_def.optional {
-        if let Some(value) = provided_args.get(arg_name) {
-            match validate_arg_type(value, &arg_def.arg_type) {
-                Ok(validated_value) => {
-                    validated_args.insert(arg_name.clone(), validated_value);
+        if let Some(values) = provided_args.get(arg_name) {
+            if let Some(error) = multiplicity_error(arg_name, values, arg_def) {
+                errors.push(error);
+                continue;
+            }
+            match validate_arg_values(values, arg_def) {
+                Ok(validated_values) => {
+                    validated_args.insert(arg_name.clone(), validated_values);
                 }
                 Err(e) => {
                     errors.push(format!("Invalid value for optional argument '--{}': {}", arg_name, e));
                 }
             }
         } else if let Some(default) = &arg_def.default_value {
-            validated_args.insert(arg_name.clone(), default.clone());
+            validated_args.insert(arg_name.clone(), vec![default.clone()]);
         }
     }
 
@@ -74,8 +83,8 @@ pub fn validate_plugin_args(
     for provided_arg in provided_args.keys() {
         if !known_args.contains(provided_arg) {
             let suggestion = suggest_similar_arg(provided_arg, &known_args);
-            let mut error_msg = format!("Unknown argument '--{}' for command '{}:{}'", 
-                                      provided_arg, plugin_name, command_name);
+            let mut error_msg = format!("Unknown argument '{}' for command '{}:{}'", 
+                                      flag_name(provided_arg), plugin_name, command_name);
             if let Some(suggestion) = suggestion {
                 error_msg.push_str(&format!("\n💡 Did you mean '--{}'?", suggestion));
             }
@@ -100,9 +109,81 @@ pub fn validate_plugin_args(
     Ok(validated_args)
 }
 
+/// Rename arguments given by an alias (`-e`) to the name they're declared under
+fn resolve_aliases(
+    provided_args: &HashMap<String, Vec<String>>,
+    args_def: &CommandArgs,
+    errors: &mut Vec<String>,
+) -> HashMap<String, Vec<String>> {
+    let declared: HashMap<&str, &str> = args_def
+        .required
+        .iter()
+        .chain(&args_def.optional)
+        .flat_map(|(name, def)| def.aliases.iter().map(move |alias| (alias.as_str(), name.as_str())))
+        .collect();
+
+    let mut resolved: HashMap<String, Vec<String>> = HashMap::new();
+    let mut given_as: HashMap<&str, &str> = HashMap::new();
+    // Sorted so the values of a `multiple` argument given under several names merge in a stable order
+    let mut keys: Vec<&String> = provided_args.keys().collect();
+    keys.sort();
+    for key in keys {
+        let is_declared_name = args_def.required.contains_key(key) || args_def.optional.contains_key(key);
+        let name = match declared.get(key.as_str()) {
+            Some(name) if !is_declared_name => *name,
+            _ => key.as_str(),
+        };
+        let multiple = args_def.required.get(name).or_else(|| args_def.optional.get(name))
+            .is_some_and(|def| def.multiple);
+
+        if let Some(previous) = given_as.insert(name, key)
+            && !multiple
+        {
+            let mut both = [flag_name(previous), flag_name(key)];
+            both.sort();
+            errors.push(format!("Argument '--{}' was given twice (as {} and {})", name, both[0], both[1]));
+            continue;
+        }
+        resolved.entry(name.to_string()).or_default().extend(provided_args[key].iter().cloned());
+    }
+
+    resolved
+}
+
+/// An argument not declared `multiple` must be given once
+fn multiplicity_error(arg_name: &str, values: &[String], arg_def: &ArgDefinition) -> Option<String> {
+    (values.len() > 1 && !arg_def.multiple).then(|| {
+        format!("Argument '--{}' was given {} times, but takes a single value", arg_name, values.len())
+    })
+}
+
+fn validate_arg_values(values: &[String], arg_def: &ArgDefinition) -> Result<Vec<String>> {
+    values.iter().map(|value| validate_arg_value(value, arg_def)).collect()
+}
+
+/// Check the value's type, then that it's one of the argument's `allowed_values` if it lists any
+pub fn validate_arg_value(value: &str, arg_def: &ArgDefinition) -> Result<String> {
+    let allowed = &arg_def.allowed_values;
+    if matches!(arg_def.arg_type, ArgType::Enum) && allowed.is_empty() {
+        return Err(anyhow!("the plugin declares it as an enum but lists no allowed_values"));
+    }
+
+    let validated = validate_arg_type(value, &arg_def.arg_type)?;
+    if allowed.is_empty() || allowed.contains(&validated) {
+        return Ok(validated);
+    }
+
+    let hint = allowed
+        .iter()
+        .find(|a| a.eq_ignore_ascii_case(value))
+        .map(|a| format!(" (did you mean '{}'?)", a))
+        .unwrap_or_default();
+    Err(anyhow!("expected one of {}, got '{}'{}", allowed.join(", "), value, hint))
+}
+
 fn validate_arg_type(value: &str, arg_type: &ArgType) -> Result<String> {
     match arg_type {
-        ArgType::String => Ok(value.to_string()),
+        ArgType::String | ArgType::Enum => Ok(value.to_string()),
         ArgType::Boolean => {
             match value.to_lowercase().as_str() {
                 "true" | "1" | "yes" | "on" => Ok("true".to_string()),
@@ -124,37 +205,84 @@ fn validate_arg_type(value: &str, arg_type: &ArgType) -> Result<String> {
 }
 
 fn suggest_similar_arg(provided: &str, known_args: &HashSet<&String>) -> Option<String> {
-    let provided_lower = provided.to_lowercase();
-    
-    // Look for exact substring matches first
-    for &known in known_args {
-        let known_lower = known.to_lowercase();
-        if known_lower.contains(&provided_lower) || provided_lower.contains(&known_lower) {
-            return Some(known.clone());
-        }
+    suggest_similar(provided, known_args.iter().map(|known| known.as_str()))
+}
+
+/// The candidate `input` was most likely a typo of, if any is close enough.
+///
+/// Picks the smallest edit distance (ignoring case, counting a swapped pair of letters
+/// as one edit) within a third of the input's length; failing that, the shortest
+/// candidate that `input` abbreviates.
+pub fn suggest_similar<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<String> {
+    let input_lower = input.to_lowercase();
+    let max_distance = (input_lower.chars().count() / 3).max(1);
+
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&input_lower, &candidate.to_lowercase()), candidate))
+        .collect();
+    scored.sort();
+
+    scored
+        .iter()
+        .find(|(distance, _)| *distance <= max_distance)
+        .or_else(|| {
+            scored
+                .iter()
+                .filter(|(_, candidate)| candidate.to_lowercase().starts_with(&input_lower))
+                .min_by_key(|(_, candidate)| candidate.len())
+        })
+        .map(|(_, candidate)| candidate.to_string())
+}
+
+/// "\n💡 Did you mean '<suggestion>'?" for an unknown name, or "" when nothing is close
+pub fn did_you_mean<'a>(input: &str, candidates: impl IntoIterator<Item = &'a str>) -> String {
+    suggest_similar(input, candidates)
+        .map(|suggestion| format!("\n💡 Did you mean '{}'?", suggestion))
+        .unwrap_or_default()
+}
+
+/// Optimal string alignment distance: insertions, deletions, substitutions and
+/// transpositions of adjacent characters each cost one
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut rows = vec![vec![0; b.len() + 1]; a.len() + 1];
+
+    for (i, row) in rows.iter_mut().enumerate() {
+        row[0] = i;
     }
-    
-    // Look for args that start with the same letter
-    for &known in known_args {
-        if known.chars().next() == provided.chars().next() {
-            return Some(known.clone());
+    for (j, cell) in rows[0].iter_mut().enumerate() {
+        *cell = j;
+    }
+
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = (rows[i - 1][j] + 1)
+                .min(rows[i][j - 1] + 1)
+                .min(rows[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(rows[i - 2][j - 2] + 1);
+            }
+            rows[i][j] = distance;
         }
     }
-    
-    None
+
+    rows[a.len()][b.len()]
 }
 
 fn generate_usage_info(args_def: &CommandArgs, plugin_name: &str, command_name: &str) -> String {
     let mut usage = format!("📖 Usage: mis run {}:{}", plugin_name, command_name);
     
     // Add required args to usage
-    for arg_name in args_def.required.keys() {
-        usage.push_str(&format!(" --{} <value>", arg_name));
+    for (arg_name, def) in &args_def.required {
+        usage.push_str(&format!(" --{} {}", arg_name, def.value_placeholder()));
     }
     
     // Add optional args to usage
-    for arg_name in args_def.optional.keys() {
-        usage.push_str(&format!(" [--{} <value>]", arg_name));
+    for (arg_name, def) in &args_def.optional {
+        usage.push_str(&format!(" [--{} {}]", arg_name, def.value_placeholder()));
     }
     
     usage.push_str("\n\n📋 Arguments:");
@@ -164,7 +292,7 @@ fn generate_usage_info(args_def: &CommandArgs, plugin_name: &str, command_name:
         usage.push_str("\n\n  Required:");
         for (name, def) in &args_def.required {
             usage.push_str(&format!("\n    --{:15} {} ({})", 
-                                   name, def.description, format_arg_type(&def.arg_type)));
+                                   format!("{}{}", name, def.alias_flags()), def.description, format_arg_type(&def.arg_type)));
         }
     }
     
@@ -176,7 +304,7 @@ fn generate_usage_info(args_def: &CommandArgs, plugin_name: &str, command_name:
                 .map(|d| format!(" [default: {}]", d))
                 .unwrap_or_default();
             usage.push_str(&format!("\n    --{:15} {} ({}){}", 
-                                   name, def.description, format_arg_type(&def.arg_type), default_info));
+                                   format!("{}{}", name, def.alias_flags()), def.description, format_arg_type(&def.arg_type), default_info));
         }
     }
     
@@ -189,33 +317,44 @@ fn format_arg_type(arg_type: &ArgType) -> &'static str {
         ArgType::Boolean => "boolean",
         ArgType::Integer => "integer",
         ArgType::Float => "float",
+        ArgType::Enum => "enum",
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use indexmap::IndexMap;
     use crate::models::ArgDefinition;
     use std::collections::HashMap;
 
     fn create_test_command_args() -> CommandArgs {
-        let mut required = HashMap::new();
+        let mut required = IndexMap::new();
         required.insert("name".to_string(), ArgDefinition {
             description: "Name of the item".to_string(),
             arg_type: ArgType::String,
             default_value: None,
+            allowed_values: Vec::new(),
+            aliases: Vec::new(),
+            multiple: false,
         });
         required.insert("count".to_string(), ArgDefinition {
             description: "Number of items".to_string(),
             arg_type: ArgType::Integer,
             default_value: None,
+            allowed_values: Vec::new(),
+            aliases: Vec::new(),
+            multiple: false,
         });
 
-        let mut optional = HashMap::new();
+        let mut optional = IndexMap::new();
         optional.insert("verbose".to_string(), ArgDefinition {
             description: "Enable verbose output".to_string(),
             arg_type: ArgType::Boolean,
             default_value: Some("false".to_string()),
+            allowed_values: Vec::new(),
+            aliases: Vec::new(),
+            multiple: false,
         });
 
         CommandArgs { required, optional }
@@ -224,23 +363,23 @@ mod tests {
     #[test]
     fn test_validate_plugin_args_success() {
         let mut provided = HashMap::new();
-        provided.insert("name".to_string(), "test".to_string());
-        provided.insert("count".to_string(), "5".to_string());
+        provided.insert("name".to_string(), vec!["test".to_string()]);
+        provided.insert("count".to_string(), vec!["5".to_string()]);
         
         let args_def = create_test_command_args();
         let result = validate_plugin_args(&provided, Some(&args_def), "test-plugin", "test-command");
         
         assert!(result.is_ok());
         let validated = result.unwrap();
-        assert_eq!(validated.get("name"), Some(&"test".to_string()));
-        assert_eq!(validated.get("count"), Some(&"5".to_string()));
-        assert_eq!(validated.get("verbose"), Some(&"false".to_string())); // default applied
+        assert_eq!(validated.get("name"), Some(&vec!["test".to_string()]));
+        assert_eq!(validated.get("count"), Some(&vec!["5".to_string()]));
+        assert_eq!(validated.get("verbose"), Some(&vec!["false".to_string()])); // default applied
     }
 
     #[test]
     fn test_validate_plugin_args_missing_required() {
         let mut provided = HashMap::new();
-        provided.insert("name".to_string(), "test".to_string());
+        provided.insert("name".to_string(), vec!["test".to_string()]);
         // Missing 'count' required argument
         
         let args_def = create_test_command_args();
@@ -254,9 +393,9 @@ mod tests {
     #[test]
     fn test_validate_plugin_args_unknown_argument() {
         let mut provided = HashMap::new();
-        provided.insert("name".to_string(), "test".to_string());
-        provided.insert("count".to_string(), "5".to_string());
-        provided.insert("unknown".to_string(), "value".to_string());
+        provided.insert("name".to_string(), vec!["test".to_string()]);
+        provided.insert("count".to_string(), vec!["5".to_string()]);
+        provided.insert("unknown".to_string(), vec!["value".to_string()]);
         
         let args_def = create_test_command_args();
         let result = validate_plugin_args(&provided, Some(&args_def), "test-plugin", "test-command");
@@ -269,8 +408,8 @@ mod tests {
     #[test]
     fn test_validate_plugin_args_invalid_type() {
         let mut provided = HashMap::new();
-        provided.insert("name".to_string(), "test".to_string());
-        provided.insert("count".to_string(), "not-a-number".to_string());
+        provided.insert("name".to_string(), vec!["test".to_string()]);
+        provided.insert("count".to_string(), vec!["not-a-number".to_string()]);
         
         let args_def = create_test_command_args();
         let result = validate_plugin_args(&provided, Some(&args_def), "test-plugin", "test-command");
@@ -283,13 +422,13 @@ mod tests {
     #[test]
     fn test_validate_plugin_args_no_definition_backward_compatibility() {
         let mut provided = HashMap::new();
-        provided.insert("any-arg".to_string(), "any-value".to_string());
+        provided.insert("any-arg".to_string(), vec!["any-value".to_string()]);
         
         let result = validate_plugin_args(&provided, None, "test-plugin", "test-command");
         
         assert!(result.is_ok());
         let validated = result.unwrap();
-        assert_eq!(validated.get("any-arg"), Some(&"any-value".to_string()));
+        assert_eq!(validated.get("any-arg"), Some(&vec!["any-value".to_string()]));
     }
 
     #[test]
@@ -304,6 +443,110 @@ mod tests {
         assert!(validate_arg_type("invalid", &ArgType::Boolean).is_err());
     }
 
+    #[test]
+    fn test_validate_enum_argument() {
+        let mut required = IndexMap::new();
+        required.insert("env".to_string(), ArgDefinition {
+            description: "Target environment".to_string(),
+            arg_type: ArgType::Enum,
+            default_value: None,
+            allowed_values: vec!["staging".to_string(), "prod".to_string()],
+            aliases: Vec::new(),
+            multiple: false,
+        });
+        let args_def = CommandArgs { required, optional: IndexMap::new() };
+
+        let provided = HashMap::from([("env".to_string(), vec!["prod".to_string()])]);
+        let validated = validate_plugin_args(&provided, Some(&args_def), "deploy", "run").unwrap();
+        assert_eq!(validated.get("env"), Some(&vec!["prod".to_string()]));
+
+        let provided = HashMap::from([("env".to_string(), vec!["Prod".to_string()])]);
+        let error = validate_plugin_args(&provided, Some(&args_def), "deploy", "run")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("expected one of staging, prod, got 'Prod' (did you mean 'prod'?)"));
+        assert!(error.contains("--env <staging|prod>"));
+    }
+
+    #[test]
+    fn test_validate_plugin_args_resolves_aliases() {
+        let mut required = IndexMap::new();
+        required.insert("environment".to_string(), ArgDefinition {
+            description: "Target environment".to_string(),
+            arg_type: ArgType::String,
+            default_value: None,
+            allowed_values: Vec::new(),
+            aliases: vec!["e".to_string(), "env".to_string()],
+            multiple: false,
+        });
+        let args_def = CommandArgs { required, optional: IndexMap::new() };
+
+        let provided = HashMap::from([("e".to_string(), vec!["prod".to_string()])]);
+        let validated = validate_plugin_args(&provided, Some(&args_def), "deploy", "run").unwrap();
+        assert_eq!(validated, HashMap::from([("environment".to_string(), vec!["prod".to_string()])]));
+
+        let provided = HashMap::from([
+            ("env".to_string(), vec!["prod".to_string()]),
+            ("environment".to_string(), vec!["staging".to_string()]),
+        ]);
+        let error = validate_plugin_args(&provided, Some(&args_def), "deploy", "run")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("'--environment' was given twice (as --env and --environment)"));
+        assert!(error.contains("--environment, -e, --env"));
+
+        let provided = HashMap::from([("x".to_string(), vec!["1".to_string()])]);
+        let error = validate_plugin_args(&provided, Some(&args_def), "deploy", "run")
+            .unwrap_err()
+            .to_string();
+        assert!(error.contains("Unknown argument '-x'"));
+    }
+
+    #[test]
+    fn test_validate_plugin_args_merges_aliases_of_multiple_args() {
+        let mut optional = IndexMap::new();
+        optional.insert("tag".to_string(), ArgDefinition {
+            description: "Image tag".to_string(),
+            arg_type: ArgType::String,
+            default_value: None,
+            allowed_values: Vec::new(),
+            aliases: vec!["t".to_string()],
+            multiple: true,
+        });
+        let args_def = CommandArgs { required: IndexMap::new(), optional };
+
+        let provided = HashMap::from([
+            ("tag".to_string(), vec!["v1".to_string(), "v2".to_string()]),
+            ("t".to_string(), vec!["latest".to_string()]),
+        ]);
+        let validated = validate_plugin_args(&provided, Some(&args_def), "docker", "push").unwrap();
+        assert_eq!(validated["tag"], vec!["latest", "v1", "v2"]);
+        assert!(generate_usage_info(&args_def, "docker", "push").contains("[--tag <value>...]"));
+    }
+
+    #[test]
+    fn test_validate_enum_without_allowed_values() {
+        let def = ArgDefinition {
+            description: "Region".to_string(),
+            arg_type: ArgType::Enum,
+            default_value: None,
+            allowed_values: Vec::new(),
+            aliases: Vec::new(),
+            multiple: false,
+        };
+        let error = validate_arg_value("eu", &def).unwrap_err().to_string();
+        assert!(error.contains("no allowed_values"));
+
+        // allowed_values also restrict other types
+        let def = ArgDefinition {
+            arg_type: ArgType::Integer,
+            allowed_values: vec!["1".to_string(), "3".to_string()],
+            ..def
+        };
+        assert_eq!(validate_arg_value("3", &def).unwrap(), "3");
+        assert!(validate_arg_value("2", &def).is_err());
+    }
+
     #[test]
     fn test_suggest_similar_arg() {
         let verbose = "verbose".to_string();
@@ -318,5 +561,27 @@ mod tests {
         assert_eq!(suggest_similar_arg("verbos", &known_args), Some("verbose".to_string()));
         assert_eq!(suggest_similar_arg("v", &known_args), Some("verbose".to_string()));
         assert_eq!(suggest_similar_arg("xyz", &known_args), None);
+        assert_eq!(suggest_similar_arg("cuont", &known_args), Some("count".to_string()));
+        // Sharing a first letter is no longer enough
+        assert_eq!(suggest_similar_arg("ohm", &known_args), None);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("deploy", "deploy"), 0);
+        assert_eq!(edit_distance("deplyo", "deploy"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_suggest_similar() {
+        let commands = ["deploy", "destroy", "status"];
+        assert_eq!(suggest_similar("deplyo", commands), Some("deploy".to_string()));
+        assert_eq!(suggest_similar("Stauts", commands), Some("status".to_string()));
+        assert_eq!(suggest_similar("de", commands), Some("deploy".to_string()));
+        assert_eq!(suggest_similar("build", commands), None);
+        assert_eq!(did_you_mean("destory", commands), "\n💡 Did you mean 'destroy'?");
+        assert_eq!(did_you_mean("build", commands), "");
     }
 } 
\ No newline at end of file
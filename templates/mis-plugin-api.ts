@@ -10,13 +10,31 @@
  * - ctx.manifest: Plugin metadata from manifest.toml (name, version, commands, dependencies, registry)
  * - ctx.config: User-editable configuration from config.toml (your custom settings)
  * - ctx.plugin_args: CLI arguments passed by the user (--arg=value)
+ * - ctx.raw_args: Arguments after `--`, unparsed and in order
  * - ctx.project_variables: Project-level variables from mis.toml
  * - ctx.meta: Quick access to plugin metadata (same as ctx.manifest.plugin)
  * - ctx.project_root: Absolute path to the project root
  * - ctx.dry_run: Whether this is a dry-run execution
+ * - ctx.environment: Profile selected with `mis run --env <name>` (null if none)
+ * - ctx.events: Payloads of events published by earlier workflow steps, by event name
+ * - ctx.artifacts_dir: Directory for build outputs, emptied before each run
+ * - ctx.state_dir: The plugin's own directory for caches and state, kept between runs
+ *
+ * Refresh this file and mis-types.d.ts with `mis api update`.
  */
 
-import type { PluginContext, PluginResult } from "./mis-types.d.ts";
+import type {
+  LogLevel,
+  PluginContext,
+  PluginResult,
+  PluginRunResult,
+} from "./mis-types.d.ts";
+
+/**
+ * Version of this helper library. mis passes the version it ships as
+ * ctx.capabilities.api_version, and loadContext refuses to continue on a mismatch.
+ */
+export const MIS_API_VERSION = 1;
 
 async function loadContext<TConfig = Record<string, unknown>>(): Promise<
   PluginContext<TConfig>
@@ -28,25 +46,33 @@ async function loadContext<TConfig = Record<string, unknown>>(): Promise<
 
   if (contextFileIndex !== -1 && Deno.args[contextFileIndex + 1]) {
     const contextFilePath = Deno.args[contextFileIndex + 1];
+    let ctx: PluginContext<TConfig>;
     try {
       const contextData = await Deno.readTextFile(contextFilePath);
-      return JSON.parse(contextData) as PluginContext<TConfig>;
+      ctx = JSON.parse(contextData) as PluginContext<TConfig>;
     } catch (error) {
       throw new Error(
         `Failed to read context file '${contextFilePath}': ${error}`,
       );
     }
+    checkApiVersion(ctx);
+    return ctx;
   }
 
-  // Fallback to stdin (composition mode)
+  // Fallback to stdin (context_transport = "stdin", or composition mode)
+  let stdinContext: PluginContext<TConfig> | undefined;
   try {
     const stdinContent = await readStdinContent();
     if (stdinContent.trim()) {
-      return JSON.parse(stdinContent) as PluginContext<TConfig>;
+      stdinContext = JSON.parse(stdinContent) as PluginContext<TConfig>;
     }
   } catch (error) {
     // stdin parsing failed, continue to error
   }
+  if (stdinContext) {
+    checkApiVersion(stdinContext);
+    return stdinContext;
+  }
 
   throw new Error(
     "🛑 No context provided. Expected either --context-file <path> argument or context via stdin.\n" +
@@ -54,6 +80,26 @@ async function loadContext<TConfig = Record<string, unknown>>(): Promise<
   );
 }
 
+/**
+ * Fail fast when the CLI expects a different version of this library.
+ * CLIs from before the handshake don't send a version and are accepted.
+ */
+function checkApiVersion<TConfig>(ctx: PluginContext<TConfig>): void {
+  const cliVersion = ctx.capabilities?.api_version;
+  if (cliVersion === undefined || cliVersion === MIS_API_VERSION) return;
+
+  if (cliVersion > MIS_API_VERSION) {
+    throw new Error(
+      `🛑 .makeitso/mis-plugin-api.ts is API v${MIS_API_VERSION}, but mis ${ctx.capabilities?.mis_version} expects v${cliVersion}.\n` +
+        "→ Run `mis api update` to refresh it.",
+    );
+  }
+  throw new Error(
+    `🛑 .makeitso/mis-plugin-api.ts is API v${MIS_API_VERSION}, newer than mis ${ctx.capabilities?.mis_version} supports (v${cliVersion}).\n` +
+      "→ Upgrade mis, or run `mis api update` to install the version it ships.",
+  );
+}
+
 async function readStdinContent(): Promise<string> {
   const chunks: Uint8Array[] = [];
   for await (const chunk of Deno.stdin.readable) {
@@ -456,8 +502,77 @@ function outputError<TConfig = Record<string, unknown>>(
   Deno.exit(1);
 }
 
+/**
+ * Write a structured result for the CLI to pick up after the plugin exits.
+ * Does nothing when the plugin wasn't launched with --result-file
+ * (e.g. when run directly with Deno).
+ *
+ * @example
+ * await mis.writeResult({
+ *   message: "Deployed to staging",
+ *   warnings: ["Cache was cold"],
+ *   outputs: { url: "https://staging.example.com" },
+ *   events: [{ name: "deployed", payload: { env: "staging" } }],
+ * });
+ *
+ * // On a dry run, report the files you would write so `mis` can show a diff
+ * if (ctx.dry_run) {
+ *   await mis.writeResult({
+ *     changes: [{ path: "k8s/deployment.yaml", content: renderedYaml }],
+ *   });
+ * }
+ */
+async function writeResult(result: PluginRunResult): Promise<void> {
+  const resultFileIndex = Deno.args.findIndex((arg) =>
+    arg === "--result-file"
+  );
+
+  if (resultFileIndex === -1 || !Deno.args[resultFileIndex + 1]) {
+    return;
+  }
+
+  await Deno.writeTextFile(
+    Deno.args[resultFileIndex + 1],
+    JSON.stringify(result, null, 2),
+  );
+}
+
+/**
+ * Write a structured log line for the CLI to timestamp, color and save in the
+ * run log. Extra fields are shown as key=value after the message.
+ * Plain console output still works and is passed through untouched.
+ *
+ * @example
+ * mis.log("info", "Uploading assets", { bucket: "static" });
+ * mis.log("warn", "Cache is cold");
+ */
+function log(
+  level: LogLevel,
+  message: string,
+  fields: Record<string, unknown> = {},
+): void {
+  console.error(
+    `::mis::log::${JSON.stringify({ ...fields, level, message })}`,
+  );
+}
+
+/**
+ * Report progress for the CLI to draw as a progress bar, or as a spinner when
+ * `percent` is null. Updates are ignored when stderr isn't a terminal.
+ *
+ * @example
+ * mis.progress(42, "uploading image");
+ * mis.progress(null, "waiting for rollout");
+ */
+function progress(percent: number | null, message?: string): void {
+  console.error(
+    `::mis::progress::${JSON.stringify({ progress: percent, message })}`,
+  );
+}
+
 // export a mis object with all of the above api functions
 export const mis = {
+  apiVersion: MIS_API_VERSION,
   loadContext,
   runPlugin,
   runPluginSafe,
@@ -468,6 +583,9 @@ export const mis = {
   extractFinalJson,
   outputSuccess,
   outputError,
+  writeResult,
+  log,
+  progress,
   getConfig,
   getArg,
   getProjectVar,
@@ -7,7 +7,8 @@ import { mis } from "../../mis-plugin-api.ts";
 import * as cow from "https://deno.land/x/cowsay@1.1/mod.ts";
 
 // 👇 This is the entrypoint of your plugin script.
-// The Make It So CLI passes context via a temporary file when it runs your plugin.
+// The Make It So CLI passes context via a temporary file when it runs your plugin,
+// or on stdin if manifest.toml sets context_transport = "stdin".
 try {
   // Load context using the shared utility
   const ctx: PluginContext = await mis.loadContext();
@@ -31,7 +32,7 @@ try {
   // NEW: Use helper functions to access data safely with defaults
 
   // Access user configuration from config.toml with default fallback
-  const message = mis.getConfig(ctx, "message", "Hello from examples 🪄");
+  const message = mis.getConfig(ctx, "message", "Hello from {{name}} 🪄");
   const theme = mis.getConfig(ctx, "theme", "default");
 
   // Access CLI arguments passed by user
@@ -58,7 +59,7 @@ try {
   }
 
   // Do your thing — in this case, print a talking cow 🐮
-  console.log("Hello from examples!");
+  console.log("Hello from {{name}}!");
   console.log(cow.say({ text: finalMessage }));
 
   // Output success result using shared utility
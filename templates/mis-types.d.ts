@@ -24,13 +24,24 @@
  * // ctx.config.database.host is now fully typed!
  */
 export type PluginContext<TConfig = Record<string, unknown>> = {
+  schema_version?: number; // Layout of this context; same as capabilities.context_schema
   plugin_args: Record<string, unknown>;
+  raw_args?: string[]; // Arguments after `--` in `mis run`, unparsed and in order
   manifest: PluginManifest; // Plugin metadata (from manifest.toml)
   config: TConfig; // User configuration (from config.toml)
   project_variables: Record<string, unknown>; // Project-level variables
   project_root: string;
   meta: PluginMeta;
   dry_run: boolean;
+  environment?: string | null; // Profile selected with `mis run --env <name>`
+  events?: Record<string, unknown[]>; // Payloads of events published earlier in the workflow, by name
+  artifacts_dir?: string; // Files written here are uploaded with `mis run --upload-artifacts`
+  state_dir?: string; // The plugin's own directory (.makeitso/state/<plugin>), kept between runs and always writable
+  capabilities?: MisCapabilities; // What the running CLI supports
+  env?: Record<string, string>; // Values of the variables declared in `env_vars` that are set
+  git?: GitInfo; // Only with `[context] git = true` in manifest.toml, and only inside a git repository
+  planning?: boolean; // `mis run --plan`: return a plan via writeResult() instead of acting (dry_run is set too)
+  plan?: PluginPlan; // `mis run --apply-plan`: the reviewed plan to carry out exactly
   results?: Array<{
     plugin: string;
     success: boolean;
@@ -40,6 +51,20 @@ export type PluginContext<TConfig = Record<string, unknown>> = {
   }>;
 };
 
+export type GitInfo = {
+  branch: string | null; // null on a detached HEAD
+  commit: string | null; // full SHA; null before the first commit
+  tag: string | null; // tag pointing at HEAD
+  dirty: boolean; // uncommitted changes, including untracked files
+};
+
+export type MisCapabilities = {
+  mis_version: string;
+  context_schema: number; // Bumped when the context layout changes incompatibly
+  api_version?: number; // mis-plugin-api.ts version this CLI ships (see MIS_API_VERSION)
+  features: string[]; // e.g. "result-file", "events", "planned-changes", "artifacts", "environments", "git", "log-lines", "progress", "plans", "state-dir"
+};
+
 export type PluginManifest = {
   plugin: PluginMeta;
   commands: string[]; // Available command names
@@ -66,6 +91,52 @@ export type PluginResult<TConfig = Record<string, unknown>> =
     context?: PluginContext<TConfig>; // passthrough context even on failure
   };
 
+/**
+ * Structured result written to the `--result-file` path via `mis.writeResult()`.
+ * The CLI reads it after the plugin exits and surfaces it to the user
+ * (or as JSON with `mis run --output json`).
+ */
+export type PluginRunResult = {
+  success?: boolean; // explicit success flag (defaults to the exit code)
+  message?: string; // human-readable summary
+  warnings?: string[]; // non-fatal issues to show the user
+  outputs?: Record<string, unknown>; // machine-readable outputs
+  events?: PluginEvent[]; // events for later steps in the same workflow run
+  changes?: PlannedChange[]; // files a dry run would write, shown as diffs
+  plan?: PluginPlan; // required when ctx.planning is set
+};
+
+/**
+ * A reviewable plan returned from `mis run --plan`. `actions` are shown to the
+ * user; `data` is handed back unchanged as `ctx.plan.data` when the plan is applied.
+ */
+export type PluginPlan = {
+  summary?: string;
+  actions: PlanAction[];
+  data?: unknown;
+};
+
+export type PlanAction = {
+  description: string; // e.g. "Scale web to 3 replicas"
+  kind?: "create" | "update" | "delete"; // shown as +, ~ or -
+};
+
+export type PlannedChange = {
+  path: string; // relative to the project root
+  content?: string | null; // new file contents; omit or null to delete the file
+};
+
+/**
+ * Level of a structured log line written with `mis.log()`.
+ * `debug` only shows with `mis -v`; `mis -q` hides everything below `warn`.
+ */
+export type LogLevel = "debug" | "info" | "warn" | "error";
+
+export type PluginEvent = {
+  name: string; // e.g. "image_published"
+  payload?: unknown; // delivered to subscribers in ctx.events[name]
+};
+
 // Helper type for common sectioned config pattern
 export type SectionedConfig<T> = {
   [K in keyof T]: T[K];
@@ -0,0 +1,51 @@
+import type { PluginContext } from "../../mis-types.d.ts";
+import { mis } from "../../mis-plugin-api.ts";
+
+type Config = { environments?: Record<string, { url?: string }> };
+
+async function git(...args: string[]): Promise<string> {
+  const { success, stdout, stderr } = await new Deno.Command("git", { args })
+    .output();
+  if (!success) throw new Error(new TextDecoder().decode(stderr).trim());
+  return new TextDecoder().decode(stdout).trim();
+}
+
+try {
+  const ctx: PluginContext<Config> = await mis.loadContext<Config>();
+  const environment = mis.getArg<Config, string>(ctx, "environment");
+  const force = String(mis.getArg(ctx, "force", "false")) === "true";
+
+  const target = ctx.config.environments?.[environment];
+  if (!target) {
+    throw new Error(
+      `No [environments.${environment}] table in config.toml for this plugin`,
+    );
+  }
+
+  if (!force && await git("status", "--porcelain")) {
+    throw new Error(
+      "Working tree has uncommitted changes (pass --force to deploy anyway)",
+    );
+  }
+  const version = mis.getArg(ctx, "version") ??
+    await git("rev-parse", "--short", "HEAD");
+
+  if (ctx.dry_run) {
+    console.log(`🚫 Dry run: would deploy ${version} to ${environment}.`);
+  } else {
+    console.log(`🚀 Deploying ${version} to ${environment} (${target.url})...`);
+    // TODO: replace with your real deploy step, e.g. an API call or CLI.
+    // Declare the hosts or commands it needs under [permissions] in manifest.toml.
+  }
+
+  await mis.writeResult({
+    success: true,
+    message: `Deployed ${version} to ${environment}`,
+    outputs: { environment, version, url: target.url },
+    events: ctx.dry_run
+      ? []
+      : [{ name: "deployed", payload: { environment, version } }],
+  });
+} catch (error) {
+  mis.outputError(error instanceof Error ? error.message : String(error));
+}
@@ -0,0 +1,70 @@
+// Written against Node's APIs. mis runs it with Deno's Node compatibility,
+// so the permissions in manifest.toml still apply.
+import { execFileSync } from "node:child_process";
+import { readFile, writeFile } from "node:fs/promises";
+import process from "node:process";
+import type { PluginContext, PluginRunResult } from "../../mis-types.d.ts";
+
+type Config = { environments?: Record<string, { url?: string }> };
+
+function flag(name: string): string | undefined {
+  const index = process.argv.indexOf(name);
+  return index === -1 ? undefined : process.argv[index + 1];
+}
+
+async function writeResult(result: PluginRunResult): Promise<void> {
+  const path = flag("--result-file");
+  if (path) await writeFile(path, JSON.stringify(result));
+}
+
+function git(...args: string[]): string {
+  return execFileSync("git", args, { encoding: "utf8" }).trim();
+}
+
+try {
+  const contextFile = flag("--context-file");
+  if (!contextFile) throw new Error("Run this plugin with `mis run`.");
+  const ctx: PluginContext<Config> = JSON.parse(
+    await readFile(contextFile, "utf8"),
+  );
+  const environment = String(ctx.plugin_args.environment);
+  const force = String(ctx.plugin_args.force ?? "false") === "true";
+
+  const target = ctx.config.environments?.[environment];
+  if (!target) {
+    throw new Error(
+      `No [environments.${environment}] table in config.toml for this plugin`,
+    );
+  }
+
+  if (!force && git("status", "--porcelain")) {
+    throw new Error(
+      "Working tree has uncommitted changes (pass --force to deploy anyway)",
+    );
+  }
+  const version = ctx.plugin_args.version
+    ? String(ctx.plugin_args.version)
+    : git("rev-parse", "--short", "HEAD");
+
+  if (ctx.dry_run) {
+    console.log(`🚫 Dry run: would deploy ${version} to ${environment}.`);
+  } else {
+    console.log(`🚀 Deploying ${version} to ${environment} (${target.url})...`);
+    // TODO: replace with your real deploy step, e.g. an API call or CLI.
+    // Declare the hosts or commands it needs under [permissions] in manifest.toml.
+  }
+
+  await writeResult({
+    success: true,
+    message: `Deployed ${version} to ${environment}`,
+    outputs: { environment, version, url: target.url },
+    events: ctx.dry_run
+      ? []
+      : [{ name: "deployed", payload: { environment, version } }],
+  });
+} catch (error) {
+  const message = error instanceof Error ? error.message : String(error);
+  console.error(`❌ ${message}`);
+  await writeResult({ success: false, message });
+  process.exit(1);
+}
@@ -0,0 +1,46 @@
+import type { PluginContext } from "../../mis-types.d.ts";
+import { mis } from "../../mis-plugin-api.ts";
+
+type Config = {
+  image: string;
+  registry?: string;
+  dockerfile?: string;
+  context?: string;
+};
+
+try {
+  const ctx: PluginContext<Config> = await mis.loadContext<Config>();
+  const registry = ctx.config.registry;
+  const image = registry ? `${registry}/${ctx.config.image}` : ctx.config.image;
+  const ref = `${image}:${mis.getArg(ctx, "tag", "latest")}`;
+
+  const args = [
+    "build",
+    "--tag",
+    ref,
+    "--file",
+    ctx.config.dockerfile ?? "Dockerfile",
+  ];
+  if (String(mis.getArg(ctx, "no_cache", "false")) === "true") {
+    args.push("--no-cache");
+  }
+  args.push(ctx.config.context ?? ".");
+
+  if (ctx.dry_run) {
+    console.log(`🚫 Dry run: would run docker ${args.join(" ")}`);
+  } else {
+    const { success } = await new Deno.Command("docker", {
+      args,
+      cwd: ctx.project_root,
+    }).spawn().status;
+    if (!success) throw new Error(`docker build failed for ${ref}`);
+  }
+
+  await mis.writeResult({
+    success: true,
+    message: `Built ${ref}`,
+    outputs: { image: ref },
+  });
+} catch (error) {
+  mis.outputError(error instanceof Error ? error.message : String(error));
+}
@@ -0,0 +1,29 @@
+import type { PluginContext } from "../../mis-types.d.ts";
+import { mis } from "../../mis-plugin-api.ts";
+
+type Config = { image: string; registry?: string };
+
+try {
+  const ctx: PluginContext<Config> = await mis.loadContext<Config>();
+  const registry = ctx.config.registry;
+  const image = registry ? `${registry}/${ctx.config.image}` : ctx.config.image;
+  const ref = `${image}:${mis.getArg(ctx, "tag")}`;
+
+  if (ctx.dry_run) {
+    console.log(`🚫 Dry run: would run docker push ${ref}`);
+  } else {
+    const { success } = await new Deno.Command("docker", {
+      args: ["push", ref],
+    }).spawn().status;
+    if (!success) throw new Error(`docker push failed for ${ref}`);
+  }
+
+  await mis.writeResult({
+    success: true,
+    message: `Pushed ${ref}`,
+    outputs: { image: ref },
+    events: ctx.dry_run ? [] : [{ name: "image_published", payload: { image: ref } }],
+  });
+} catch (error) {
+  mis.outputError(error instanceof Error ? error.message : String(error));
+}
@@ -0,0 +1,48 @@
+// Written against Node's APIs. mis runs it with Deno's Node compatibility,
+// so the permissions in manifest.toml still apply.
+import { spawnSync } from "node:child_process";
+import { readFile, writeFile } from "node:fs/promises";
+import process from "node:process";
+import type { PluginContext, PluginRunResult } from "../../mis-types.d.ts";
+
+type Config = { image: string; registry?: string };
+
+function flag(name: string): string | undefined {
+  const index = process.argv.indexOf(name);
+  return index === -1 ? undefined : process.argv[index + 1];
+}
+
+async function writeResult(result: PluginRunResult): Promise<void> {
+  const path = flag("--result-file");
+  if (path) await writeFile(path, JSON.stringify(result));
+}
+
+try {
+  const contextFile = flag("--context-file");
+  if (!contextFile) throw new Error("Run this plugin with `mis run`.");
+  const ctx: PluginContext<Config> = JSON.parse(
+    await readFile(contextFile, "utf8"),
+  );
+  const registry = ctx.config.registry;
+  const image = registry ? `${registry}/${ctx.config.image}` : ctx.config.image;
+  const ref = `${image}:${ctx.plugin_args.tag}`;
+
+  if (ctx.dry_run) {
+    console.log(`🚫 Dry run: would run docker push ${ref}`);
+  } else {
+    const { status } = spawnSync("docker", ["push", ref], { stdio: "inherit" });
+    if (status !== 0) throw new Error(`docker push failed for ${ref}`);
+  }
+
+  await writeResult({
+    success: true,
+    message: `Pushed ${ref}`,
+    outputs: { image: ref },
+    events: ctx.dry_run ? [] : [{ name: "image_published", payload: { image: ref } }],
+  });
+} catch (error) {
+  const message = error instanceof Error ? error.message : String(error);
+  console.error(`❌ ${message}`);
+  await writeResult({ success: false, message });
+  process.exit(1);
+}
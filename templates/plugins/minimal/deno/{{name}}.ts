@@ -0,0 +1,17 @@
+import type { PluginContext } from "../../mis-types.d.ts";
+import { mis } from "../../mis-plugin-api.ts";
+
+try {
+  const ctx: PluginContext = await mis.loadContext();
+  const name = mis.getArg(ctx, "name", "world");
+
+  if (ctx.dry_run) {
+    console.log(`🚫 Dry run: would greet ${name}.`);
+  } else {
+    console.log(`👋 Hello, ${name}!`);
+  }
+
+  await mis.writeResult({ success: true, message: `Greeted ${name}` });
+} catch (error) {
+  mis.outputError(error instanceof Error ? error.message : String(error));
+}
@@ -0,0 +1,35 @@
+// Written against Node's APIs. mis runs it with Deno's Node compatibility,
+// so the permissions in manifest.toml still apply.
+import { readFile, writeFile } from "node:fs/promises";
+import process from "node:process";
+import type { PluginContext, PluginRunResult } from "../../mis-types.d.ts";
+
+function flag(name: string): string | undefined {
+  const index = process.argv.indexOf(name);
+  return index === -1 ? undefined : process.argv[index + 1];
+}
+
+async function writeResult(result: PluginRunResult): Promise<void> {
+  const path = flag("--result-file");
+  if (path) await writeFile(path, JSON.stringify(result));
+}
+
+try {
+  const contextFile = flag("--context-file");
+  if (!contextFile) throw new Error("Run this plugin with `mis run`.");
+  const ctx: PluginContext = JSON.parse(await readFile(contextFile, "utf8"));
+  const name = String(ctx.plugin_args.name ?? "world");
+
+  if (ctx.dry_run) {
+    console.log(`🚫 Dry run: would greet ${name}.`);
+  } else {
+    console.log(`👋 Hello, ${name}!`);
+  }
+
+  await writeResult({ success: true, message: `Greeted ${name}` });
+} catch (error) {
+  const message = error instanceof Error ? error.message : String(error);
+  console.error(`❌ ${message}`);
+  await writeResult({ success: false, message });
+  process.exit(1);
+}
@@ -0,0 +1,28 @@
+import type { PluginContext } from "../../mis-types.d.ts";
+import { mis } from "../../mis-plugin-api.ts";
+
+type Config = { working_dir?: string };
+
+try {
+  const ctx: PluginContext<Config> = await mis.loadContext<Config>();
+  const dir = `${ctx.project_root}/${ctx.config.working_dir ?? "."}`;
+  const varFile = mis.getArg<Config, string | undefined>(ctx, "var_file");
+
+  // A dry run shows the plan without changing any infrastructure
+  const args = [`-chdir=${dir}`, ctx.dry_run ? "plan" : "apply", "-input=false"];
+  if (varFile) args.push(`-var-file=${varFile}`);
+  if (!ctx.dry_run && String(mis.getArg(ctx, "auto_approve", "false")) === "true") {
+    args.push("-auto-approve");
+  }
+
+  const { success } = await new Deno.Command("terraform", { args }).spawn()
+    .status;
+  if (!success) throw new Error(`terraform ${args[1]} failed`);
+
+  await mis.writeResult({
+    success: true,
+    message: ctx.dry_run ? "Dry run: plan complete, nothing applied" : "Apply complete",
+  });
+} catch (error) {
+  mis.outputError(error instanceof Error ? error.message : String(error));
+}
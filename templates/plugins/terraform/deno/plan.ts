@@ -0,0 +1,21 @@
+import type { PluginContext } from "../../mis-types.d.ts";
+import { mis } from "../../mis-plugin-api.ts";
+
+type Config = { working_dir?: string };
+
+try {
+  const ctx: PluginContext<Config> = await mis.loadContext<Config>();
+  const dir = `${ctx.project_root}/${ctx.config.working_dir ?? "."}`;
+  const varFile = mis.getArg<Config, string | undefined>(ctx, "var_file");
+
+  const args = [`-chdir=${dir}`, "plan", "-input=false"];
+  if (varFile) args.push(`-var-file=${varFile}`);
+
+  const { success } = await new Deno.Command("terraform", { args }).spawn()
+    .status;
+  if (!success) throw new Error("terraform plan failed");
+
+  await mis.writeResult({ success: true, message: "Plan complete" });
+} catch (error) {
+  mis.outputError(error instanceof Error ? error.message : String(error));
+}
@@ -0,0 +1,49 @@
+// Written against Node's APIs. mis runs it with Deno's Node compatibility,
+// so the permissions in manifest.toml still apply.
+import { spawnSync } from "node:child_process";
+import { readFile, writeFile } from "node:fs/promises";
+import { join } from "node:path";
+import process from "node:process";
+import type { PluginContext, PluginRunResult } from "../../mis-types.d.ts";
+
+type Config = { working_dir?: string };
+
+function flag(name: string): string | undefined {
+  const index = process.argv.indexOf(name);
+  return index === -1 ? undefined : process.argv[index + 1];
+}
+
+async function writeResult(result: PluginRunResult): Promise<void> {
+  const path = flag("--result-file");
+  if (path) await writeFile(path, JSON.stringify(result));
+}
+
+try {
+  const contextFile = flag("--context-file");
+  if (!contextFile) throw new Error("Run this plugin with `mis run`.");
+  const ctx: PluginContext<Config> = JSON.parse(
+    await readFile(contextFile, "utf8"),
+  );
+  const dir = join(ctx.project_root, ctx.config.working_dir ?? ".");
+  const varFile = ctx.plugin_args.var_file;
+
+  // A dry run shows the plan without changing any infrastructure
+  const args = [`-chdir=${dir}`, ctx.dry_run ? "plan" : "apply", "-input=false"];
+  if (varFile) args.push(`-var-file=${varFile}`);
+  if (!ctx.dry_run && String(ctx.plugin_args.auto_approve ?? "false") === "true") {
+    args.push("-auto-approve");
+  }
+
+  const { status } = spawnSync("terraform", args, { stdio: "inherit" });
+  if (status !== 0) throw new Error(`terraform ${args[1]} failed`);
+
+  await writeResult({
+    success: true,
+    message: ctx.dry_run ? "Dry run: plan complete, nothing applied" : "Apply complete",
+  });
+} catch (error) {
+  const message = error instanceof Error ? error.message : String(error);
+  console.error(`❌ ${message}`);
+  await writeResult({ success: false, message });
+  process.exit(1);
+}
@@ -6,6 +6,7 @@ import type {
   PluginManifest,
   PluginMeta,
   PluginResult,
+  PluginRunResult,
 } from "../mis-types.d.ts";
 
 Deno.test("PluginContext - has required fields", () => {
@@ -120,3 +121,17 @@ Deno.test("PluginManifest - complete structure", () => {
   assertEquals(Object.keys(manifest.deno_dependencies).length, 2);
   assertEquals(manifest.registry, "https://github.com/company/plugins.git");
 });
+
+Deno.test("PluginRunResult - all fields optional", () => {
+  const empty: PluginRunResult = {};
+  assertEquals(empty.success, undefined);
+
+  const full: PluginRunResult = {
+    success: true,
+    message: "Deployed",
+    warnings: ["Cache was cold"],
+    outputs: { url: "https://staging.example.com" },
+  };
+  assertEquals(full.warnings?.length, 1);
+  assertExists(full.outputs?.url);
+});